@@ -1,15 +1,38 @@
-use crate::models::analytics::{GlobalAnalytics, SnippetAnalytics};
+use crate::commands::clipboard_commands::{copy_to_clipboard, set_clipboard_image};
+use crate::models::analytics::{
+    AnalyticsConsentState, AnalyticsCsvColumn, GlobalAnalytics, ProductivitySummary,
+    SnippetAnalytics, SourceUsage, TagUsage, TrendBucket, UsageContext, UsageSource, UsageTrends,
+};
+use crate::models::{Snippet, SnippetKind};
 use crate::services::analytics;
+use crate::services::attachments;
 use crate::services::database::get_pool;
-use sqlx::Row;
+use crate::services::dynamic_snippets;
+use crate::services::focus;
+use crate::services::library_stats::{self, LibraryStatistics};
+use crate::services::markdown_format;
+use crate::services::snippet_order;
+use crate::services::snippet_parameters::{self, UnfilledParameters};
+use crate::services::snippet_slots;
+use crate::services::token_estimate;
+use crate::utils::error::AppError;
+use crate::utils::time::current_timestamp;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
 use tauri::AppHandle;
 
 /// Record a snippet usage event (M1)
 ///
+/// Nothing is recorded while analytics or usage tracking is turned off in
+/// the privacy settings. The event is queued and written with the next
+/// batch, within a few seconds.
+///
 /// # Arguments
 ///
 /// * `app` - Application handle for accessing database pool
 /// * `snippet_id` - ID of the snippet being used
+/// * `source` - Optional entry point the snippet was used from
+/// * `tag_filter` - Optional tag filter active in the search at the time
 ///
 /// # Returns
 ///
@@ -18,16 +41,83 @@ use tauri::AppHandle;
 /// # Examples
 ///
 /// ```typescript
-/// await invoke('record_snippet_usage', { snippetId: 42 });
+/// await invoke('record_snippet_usage', { snippetId: 42, source: 'management' });
 /// ```
 #[tauri::command]
-pub async fn record_snippet_usage(app: AppHandle, snippet_id: i64) -> Result<(), String> {
+pub async fn record_snippet_usage(
+    app: AppHandle,
+    snippet_id: i64,
+    source: Option<UsageSource>,
+    tag_filter: Option<String>,
+) -> Result<(), String> {
     let pool = get_pool(&app).map_err(|e| e.to_string())?;
-    analytics::record_usage(&pool, snippet_id)
+    if !analytics::recording_allowed(&pool)
+        .await
+        .map_err(|e| e.to_string())?
+    {
+        return Ok(());
+    }
+    let context = UsageContext {
+        source,
+        tag_filter,
+        active_app: focus::active_app(&app),
+    };
+    analytics::queue_usage(&app, &pool, snippet_id, &context)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Records usage of copied snippets if the privacy settings allow it
+///
+/// The copy already happened, so failures are logged rather than returned.
+async fn record_copied_usage(
+    app: &AppHandle,
+    pool: &SqlitePool,
+    snippet_ids: impl IntoIterator<Item = i64>,
+    context: &UsageContext,
+) {
+    match analytics::recording_allowed(pool).await {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            eprintln!("Warning: Skipping usage recording: {}", e);
+            return;
+        }
+    }
+
+    for snippet_id in snippet_ids {
+        // Continue recording even if one fails
+        if let Err(e) = analytics::queue_usage(app, pool, snippet_id, context).await {
+            eprintln!(
+                "Warning: Failed to record usage for snippet {}: {}",
+                snippet_id, e
+            );
+        }
+    }
+}
+
+/// Loads the snippets being copied in one query, warning about missing ones
+async fn load_copied_snippets(
+    pool: &SqlitePool,
+    snippet_ids: &[i64],
+) -> Result<Vec<Snippet>, AppError> {
+    let snippets = snippet_order::load_snippets(pool, snippet_ids).await?;
+    for snippet_id in snippet_ids {
+        if !snippets.iter().any(|snippet| snippet.id.0 == *snippet_id) {
+            eprintln!("Warning: Snippet {} not found", snippet_id);
+        }
+    }
+    Ok(snippets)
+}
+
+/// Gets the database pool after writing queued usage, so reads see every copy
+async fn flushed_pool(app: &AppHandle) -> Result<SqlitePool, String> {
+    if let Err(e) = analytics::flush_queued_usage(app).await {
+        eprintln!("Warning: Failed to write queued usage: {}", e);
+    }
+    get_pool(app).map_err(|e| e.to_string())
+}
+
 /// Get analytics data for a specific snippet (M2)
 ///
 /// # Arguments
@@ -50,7 +140,7 @@ pub async fn get_snippet_analytics(
     app: AppHandle,
     snippet_id: i64,
 ) -> Result<SnippetAnalytics, String> {
-    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    let pool = flushed_pool(&app).await?;
     analytics::get_snippet_analytics(&pool, snippet_id)
         .await
         .map_err(|e| e.to_string())
@@ -83,7 +173,7 @@ pub async fn get_global_analytics(
     most_used_limit: Option<i64>,
     recent_limit: Option<i64>,
 ) -> Result<GlobalAnalytics, String> {
-    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    let pool = flushed_pool(&app).await?;
     let most_used = most_used_limit.unwrap_or(10);
     let recent = recent_limit.unwrap_or(20);
 
@@ -92,74 +182,499 @@ pub async fn get_global_analytics(
         .map_err(|e| e.to_string())
 }
 
+/// Get usage counts over time for charts
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `bucket` - `day`, `week` or `month` (UTC, weeks start on Monday)
+/// * `start` - Optional start of the range as a Unix timestamp (default: 30 days before `end`)
+/// * `end` - Optional end of the range as a Unix timestamp (default: now)
+/// * `snippet_limit` - Optional maximum number of per-snippet series (default: 10)
+///
+/// # Returns
+///
+/// UsageTrends with a global series and one series per most used snippet,
+/// each with a point for every bucket in the range
+///
+/// # Examples
+///
+/// ```typescript
+/// const trends = await invoke('get_usage_trends', { bucket: 'week', snippetLimit: 5 });
+/// const counts = trends.global.map((point) => point.count);
+/// ```
+#[tauri::command]
+pub async fn get_usage_trends(
+    app: AppHandle,
+    bucket: TrendBucket,
+    start: Option<i64>,
+    end: Option<i64>,
+    snippet_limit: Option<usize>,
+) -> Result<UsageTrends, String> {
+    let pool = flushed_pool(&app).await?;
+    // The end is exclusive, so include usages recorded this second
+    let end = end.unwrap_or_else(|| current_timestamp() + 1);
+    let start = start.unwrap_or(end - 30 * 24 * 3600);
+
+    analytics::get_usage_trends(&pool, bucket, start, end, snippet_limit.unwrap_or(10))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get usage streaks, estimated keystrokes saved and the busiest hours, for
+/// the productivity dashboard
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `utc_offset_minutes` - Optional offset of the user's time zone from UTC,
+///   in minutes east of UTC (default: 0)
+///
+/// # Returns
+///
+/// ProductivitySummary with daily and weekly streaks, keystrokes saved
+/// (content length times uses) and usage per hour of the day
+///
+/// # Examples
+///
+/// ```typescript
+/// const summary = await invoke('get_productivity_summary', {
+///   utcOffsetMinutes: -new Date().getTimezoneOffset(),
+/// });
+/// ```
+#[tauri::command]
+pub async fn get_productivity_summary(
+    app: AppHandle,
+    utc_offset_minutes: Option<i32>,
+) -> Result<ProductivitySummary, String> {
+    let pool = flushed_pool(&app).await?;
+    analytics::get_productivity_summary(&pool, current_timestamp(), utc_offset_minutes.unwrap_or(0))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get usage grouped by the tags of the used snippets
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `since` - Optional Unix timestamp; only usages from then on are counted
+///
+/// # Returns
+///
+/// One entry per tag with its usage count and how often the search was
+/// filtered to it, most used first
+///
+/// # Examples
+///
+/// ```typescript
+/// const tags = await invoke('get_analytics_by_tag', { since: null });
+/// ```
+#[tauri::command]
+pub async fn get_analytics_by_tag(
+    app: AppHandle,
+    since: Option<i64>,
+) -> Result<Vec<TagUsage>, String> {
+    let pool = flushed_pool(&app).await?;
+    analytics::get_analytics_by_tag(&pool, since)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get usage grouped by entry point (search overlay, shortcuts, API, ...)
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `since` - Optional Unix timestamp; only usages from then on are counted
+///
+/// # Returns
+///
+/// One entry per entry point, most used first; `source` is null for usages
+/// recorded before entry points were tracked
+///
+/// # Examples
+///
+/// ```typescript
+/// const sources = await invoke('get_analytics_by_source', { since: null });
+/// ```
+#[tauri::command]
+pub async fn get_analytics_by_source(
+    app: AppHandle,
+    since: Option<i64>,
+) -> Result<Vec<SourceUsage>, String> {
+    let pool = flushed_pool(&app).await?;
+    analytics::get_analytics_by_source(&pool, since)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Copy snippets to clipboard and record usage analytics (M4)
 ///
 /// This command combines clipboard operations with usage tracking.
 /// It fetches the snippet content by IDs, concatenates them, copies to clipboard,
-/// and records analytics for each snippet being copied.
+/// and records analytics for each snippet being copied, unless the privacy
+/// settings turn recording off. A single snippet with an image attachment
+/// copies the image instead.
+///
+/// Each static snippet's `{{name}}` placeholders are filled from its own
+/// values, falling back to the declared defaults. Placeholders of required
+/// parameters left without a value are copied as written and reported.
 ///
 /// # Arguments
 ///
 /// * `app` - Application handle for accessing database pool
-/// * `snippet_ids` - Vector of snippet IDs being copied (in desired order)
+/// * `snippet_ids` - Vector of snippet IDs being copied, in the order they
+///   are joined; an ID may repeat
+/// * `confirm_dynamic` - Must be `true` when any snippet is dynamic; the
+///   frontend gets the commands from `get_dynamic_snippet_commands` and asks
+///   the user first
+/// * `source` - Optional entry point, recorded with the usage
+/// * `tag_filter` - Optional tag filter active in the search, recorded with the usage
+/// * `separator` - Optional text put between snippets (default: a blank line)
+/// * `values` - Optional parameter values by snippet ID, then parameter name
 ///
 /// # Returns
 ///
-/// Result indicating success or error message
+/// The snippets whose required parameters were left unfilled
+///
+/// # Errors
+///
+/// Fails without copying anything if a dynamic snippet is included without
+/// confirmation, if one of the commands fails or times out, or if a
+/// parameter value is invalid
 ///
 /// # Examples
 ///
 /// ```typescript
-/// await invoke('copy_snippets_with_analytics', {
-///   snippetIds: [1, 2, 3]
+/// const unfilled = await invoke('copy_snippets_with_analytics', {
+///   snippetIds: [1, 2, 3],
+///   source: 'search_overlay',
+///   separator: '\n---\n',
+///   values: { 2: { env: 'prod' } },
 /// });
 /// ```
 #[tauri::command]
 pub async fn copy_snippets_with_analytics(
     app: AppHandle,
     snippet_ids: Vec<i64>,
-) -> Result<(), String> {
+    confirm_dynamic: Option<bool>,
+    source: Option<UsageSource>,
+    tag_filter: Option<String>,
+    separator: Option<String>,
+    values: Option<HashMap<i64, HashMap<String, String>>>,
+) -> Result<Vec<UnfilledParameters>, String> {
     let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    let context = UsageContext {
+        source,
+        tag_filter,
+        active_app: focus::active_app(&app),
+    };
 
-    // Fetch snippet content for each ID in order
-    let mut contents = Vec::new();
-    for snippet_id in &snippet_ids {
-        let result = sqlx::query("SELECT content FROM snippets WHERE id = ?")
-            .bind(snippet_id)
-            .fetch_optional(&pool)
-            .await
-            .map_err(|e| format!("Failed to fetch snippet {}: {}", snippet_id, e))?;
-
-        match result {
-            Some(row) => {
-                let content: String = row.get(0);
-                contents.push(content);
-            }
-            None => {
-                eprintln!("Warning: Snippet {} not found", snippet_id);
-            }
+    // A snippet holding an image copies the image rather than its text
+    if let [snippet_id] = snippet_ids[..] {
+        if let Some((attachment, data)) = attachments::first_image(&pool, snippet_id).await? {
+            set_clipboard_image(&attachment.mime_type, &data).await?;
+            record_copied_usage(&app, &pool, snippet_ids, &context).await;
+            return Ok(Vec::new());
         }
     }
 
-    // Concatenate content with double newline separator (empty line between)
-    let text = contents.join("\n\n");
+    // Fetch the snippets in the order given
+    let snippets = load_copied_snippets(&pool, &snippet_ids).await?;
+
+    // Concatenate content, by default with an empty line between
+    let separator = separator
+        .as_deref()
+        .unwrap_or(token_estimate::COMPOSED_SEPARATOR);
+    let (text, unfilled) = compose_text(
+        &pool,
+        snippets,
+        separator,
+        &values.unwrap_or_default(),
+        confirm_dynamic,
+    )
+    .await?;
 
     // Copy to clipboard
-    use crate::commands::clipboard_commands::copy_to_clipboard;
     copy_to_clipboard(app.clone(), text).await?;
 
     // Record analytics for each snippet
-    for snippet_id in snippet_ids {
-        // Continue recording even if one fails
-        if let Err(e) = analytics::record_usage(&pool, snippet_id).await {
-            eprintln!(
-                "Warning: Failed to record usage for snippet {}: {}",
-                snippet_id, e
-            );
+    record_copied_usage(&app, &pool, snippet_ids, &context).await;
+
+    Ok(unfilled)
+}
+
+/// Copy snippets to the clipboard as one text, joined by a chosen separator
+///
+/// For pieces that are always sent together, such as an email intro, a
+/// signature and a legal footer. Unlike `copy_snippets_with_analytics`, a
+/// missing snippet fails the copy instead of being left out, and a snippet
+/// can be repeated. Usage is recorded for every snippet copied.
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `snippet_ids` - Snippets in the order they are joined
+/// * `separator` - Optional text put between snippets (default: a blank line)
+/// * `confirm_dynamic` - Must be `true` when any snippet is dynamic
+/// * `source` - Optional entry point, recorded with the usage
+///
+/// # Returns
+///
+/// The text that was copied
+///
+/// # Examples
+///
+/// ```typescript
+/// const text = await invoke('compose_snippets', { snippetIds: [4, 9, 2], separator: '\n' });
+/// ```
+#[tauri::command]
+pub async fn compose_snippets(
+    app: AppHandle,
+    snippet_ids: Vec<i64>,
+    separator: Option<String>,
+    confirm_dynamic: Option<bool>,
+    source: Option<UsageSource>,
+) -> Result<String, String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    let text =
+        compose_selection(&pool, &snippet_ids, separator.as_deref(), confirm_dynamic).await?;
+    copy_to_clipboard(app.clone(), text.clone()).await?;
+
+    let context = UsageContext {
+        source,
+        tag_filter: None,
+        active_app: focus::active_app(&app),
+    };
+    record_copied_usage(&app, &pool, snippet_ids, &context).await;
+
+    Ok(text)
+}
+
+/// Builds the text `compose_snippets` copies
+///
+/// # Errors
+///
+/// Fails if no snippet is chosen, one does not exist, or `compose_text` fails
+async fn compose_selection(
+    pool: &SqlitePool,
+    snippet_ids: &[i64],
+    separator: Option<&str>,
+    confirm_dynamic: Option<bool>,
+) -> Result<String, String> {
+    if snippet_ids.is_empty() {
+        return Err(
+            AppError::InvalidInput("Choose at least one snippet to compose".to_string()).into(),
+        );
+    }
+
+    let snippets = snippet_order::load_snippets(pool, snippet_ids).await?;
+    if let Some(missing) = snippet_ids
+        .iter()
+        .find(|id| !snippets.iter().any(|snippet| snippet.id.0 == **id))
+    {
+        return Err(AppError::NotFound(format!("Snippet with id {} not found", missing)).into());
+    }
+
+    let separator = separator.unwrap_or(token_estimate::COMPOSED_SEPARATOR);
+    let (text, _) =
+        compose_text(pool, snippets, separator, &HashMap::new(), confirm_dynamic).await?;
+    Ok(text)
+}
+
+/// Joins the snippets' text, filling the placeholders of static ones and
+/// running the commands of dynamic ones
+///
+/// # Returns
+///
+/// The text and the snippets whose required parameters were left unfilled,
+/// each listed once
+///
+/// # Errors
+///
+/// Fails if a dynamic snippet is included without `confirm_dynamic`, if one
+/// of the commands fails or times out, or if a parameter value is invalid
+async fn compose_text(
+    pool: &SqlitePool,
+    snippets: Vec<Snippet>,
+    separator: &str,
+    values: &HashMap<i64, HashMap<String, String>>,
+    confirm_dynamic: Option<bool>,
+) -> Result<(String, Vec<UnfilledParameters>), String> {
+    // Never run a command the user has not seen
+    let has_dynamic = snippets
+        .iter()
+        .any(|snippet| snippet.kind == SnippetKind::Dynamic);
+    if has_dynamic && confirm_dynamic != Some(true) {
+        return Err(AppError::Forbidden(
+            "Dynamic snippets must be confirmed before their commands run".to_string(),
+        )
+        .into());
+    }
+
+    let no_values = HashMap::new();
+    let mut contents = Vec::with_capacity(snippets.len());
+    let mut unfilled: Vec<UnfilledParameters> = Vec::new();
+    for snippet in snippets {
+        match snippet.kind {
+            SnippetKind::Static => {
+                let parameters = snippet_parameters::get_parameters(pool, snippet.id.0).await?;
+                let (text, missing) = snippet_parameters::fill_parameters(
+                    &snippet.content,
+                    &parameters,
+                    values.get(&snippet.id.0).unwrap_or(&no_values),
+                )?;
+                if !missing.is_empty() && !unfilled.iter().any(|u| u.snippet_id == snippet.id.0) {
+                    unfilled.push(UnfilledParameters {
+                        snippet_id: snippet.id.0,
+                        snippet_name: snippet.name,
+                        parameters: missing,
+                    });
+                }
+                contents.push(text);
+            }
+            SnippetKind::Dynamic => contents.push(
+                dynamic_snippets::run_command(&snippet.content, dynamic_snippets::COMMAND_TIMEOUT)
+                    .await?,
+            ),
         }
     }
 
-    Ok(())
+    Ok((contents.join(separator), unfilled))
+}
+
+/// Copy the most recently used snippet to the clipboard
+///
+/// Backs the quick paste shortcut, which repeats the last copy without
+/// opening any window. Usage is recorded like any other copy. Dynamic
+/// snippets are refused because there is no window to confirm their command.
+///
+/// # Returns
+///
+/// The ID of the copied snippet, or `None` if no snippet has been used yet
+///
+/// # Examples
+///
+/// ```typescript
+/// const copiedId = await invoke('copy_last_used_snippet');
+/// ```
+#[tauri::command]
+pub async fn copy_last_used_snippet(app: AppHandle) -> Result<Option<i64>, String> {
+    let pool = flushed_pool(&app).await?;
+
+    let Some(snippet_id) = analytics::most_recently_used_snippet(&pool).await? else {
+        return Ok(None);
+    };
+    copy_snippets_with_analytics(
+        app,
+        vec![snippet_id],
+        None,
+        Some(UsageSource::QuickPaste),
+        None,
+        None,
+        None,
+    )
+    .await?;
+
+    Ok(Some(snippet_id))
+}
+
+/// Copy the snippet assigned to a numbered slot to the clipboard
+///
+/// Backs the `Cmd/Ctrl+Shift+1..9` shortcuts. Like quick paste, usage is
+/// recorded and dynamic snippets are refused.
+///
+/// # Returns
+///
+/// The ID of the copied snippet, or `None` if the slot is empty
+///
+/// # Examples
+///
+/// ```typescript
+/// const copiedId = await invoke('copy_slot_snippet', { slot: 1 });
+/// ```
+#[tauri::command]
+pub async fn copy_slot_snippet(app: AppHandle, slot: u8) -> Result<Option<i64>, String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+
+    let Some(snippet_id) = snippet_slots::snippet_for_slot(&pool, slot).await? else {
+        return Ok(None);
+    };
+    copy_snippets_with_analytics(
+        app,
+        vec![snippet_id],
+        None,
+        Some(UsageSource::SlotShortcut),
+        None,
+        None,
+        None,
+    )
+    .await?;
+
+    Ok(Some(snippet_id))
+}
+
+/// Copy snippets to the clipboard as one markdown document
+///
+/// Each snippet becomes a section headed by its name, with its content in a
+/// fenced code block labelled with the detected language and its tags listed
+/// below. Usage is recorded for every snippet copied. Dynamic snippets are
+/// rendered as their command and never executed.
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `snippet_ids` - Snippets to include, in document order
+/// * `source` - Optional entry point, recorded with the usage
+/// * `tag_filter` - Optional tag filter active in the search, recorded with the usage
+///
+/// # Returns
+///
+/// The markdown that was copied
+///
+/// # Errors
+///
+/// Returns an error if none of the snippets exist or the clipboard write fails
+///
+/// # Examples
+///
+/// ```typescript
+/// const markdown = await invoke('copy_snippets_as_markdown', {
+///   snippetIds: [1, 2, 3]
+/// });
+/// ```
+#[tauri::command]
+pub async fn copy_snippets_as_markdown(
+    app: AppHandle,
+    snippet_ids: Vec<i64>,
+    source: Option<UsageSource>,
+    tag_filter: Option<String>,
+) -> Result<String, String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+
+    let snippets = load_copied_snippets(&pool, &snippet_ids).await?;
+    if snippets.is_empty() {
+        return Err(AppError::InvalidInput("No snippets to copy".to_string()).into());
+    }
+
+    let markdown = markdown_format::snippets_to_markdown_document(&snippets);
+    copy_to_clipboard(app.clone(), markdown.clone()).await?;
+
+    let context = UsageContext {
+        source,
+        tag_filter,
+        active_app: focus::active_app(&app),
+    };
+    record_copied_usage(
+        &app,
+        &pool,
+        snippets.iter().map(|snippet| snippet.id.0),
+        &context,
+    )
+    .await;
+
+    Ok(markdown)
 }
 
 /// Clear all analytics data (Z8)
@@ -180,6 +695,7 @@ pub async fn copy_snippets_with_analytics(
 #[tauri::command]
 pub async fn clear_all_analytics(app: AppHandle) -> Result<(), String> {
     let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    analytics::discard_queued_usage(&app);
     analytics::clear_all_analytics(&pool)
         .await
         .map_err(|e| e.to_string())
@@ -206,12 +722,39 @@ pub async fn clear_all_analytics(app: AppHandle) -> Result<(), String> {
 /// ```
 #[tauri::command]
 pub async fn clear_analytics_before(app: AppHandle, before_timestamp: i64) -> Result<u64, String> {
-    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    let pool = flushed_pool(&app).await?;
     analytics::clear_analytics_before(&pool, before_timestamp)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Get whether usage is being recorded and how much has been stored
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+///
+/// # Returns
+///
+/// The privacy switches, whether usage is recorded right now, and the
+/// number and age of stored usage events
+///
+/// # Examples
+///
+/// ```typescript
+/// const consent = await invoke('get_analytics_consent_state');
+/// if (!consent.recording && consent.stored_events > 0) {
+///   // offer to clear the remaining data
+/// }
+/// ```
+#[tauri::command]
+pub async fn get_analytics_consent_state(app: AppHandle) -> Result<AnalyticsConsentState, String> {
+    let pool = flushed_pool(&app).await?;
+    analytics::get_consent_state(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Export analytics data to JSON format (Z7)
 ///
 /// # Arguments
@@ -229,7 +772,7 @@ pub async fn clear_analytics_before(app: AppHandle, before_timestamp: i64) -> Re
 /// ```
 #[tauri::command]
 pub async fn export_analytics_to_json(app: AppHandle) -> Result<String, String> {
-    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    let pool = flushed_pool(&app).await?;
 
     // Fetch all analytics records
     let records = sqlx::query_as::<_, (i64, i64, i64)>(
@@ -254,6 +797,68 @@ pub async fn export_analytics_to_json(app: AppHandle) -> Result<String, String>
     serde_json::to_string_pretty(&json_records).map_err(|e| format!("Failed to serialize: {}", e))
 }
 
+/// Export analytics data to CSV format
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `columns` - Optional columns to include, in order; defaults to
+///   snippet name, timestamp, ISO date and tags
+/// * `start` - Optional Unix timestamp; only usages from then on are exported
+/// * `end` - Optional Unix timestamp; only usages before then are exported
+///
+/// # Returns
+///
+/// RFC 4180 CSV text with a header row, most recent usage first
+///
+/// # Examples
+///
+/// ```typescript
+/// const csv = await invoke('export_analytics_to_csv', {
+///   columns: ['iso_date', 'snippet_name', 'tags'],
+///   start: Math.floor(new Date('2024-01-01').getTime() / 1000),
+/// });
+/// ```
+#[tauri::command]
+pub async fn export_analytics_to_csv(
+    app: AppHandle,
+    columns: Option<Vec<AnalyticsCsvColumn>>,
+    start: Option<i64>,
+    end: Option<i64>,
+) -> Result<String, String> {
+    let pool = flushed_pool(&app).await?;
+    analytics::export_analytics_csv(&pool, &columns.unwrap_or_default(), start, end)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get aggregate content statistics for the library insights page
+///
+/// Statistics are served from an in-memory cache that is updated per snippet
+/// on create/update/delete and rebuilt lazily after imports and restores.
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+///
+/// # Returns
+///
+/// LibraryStatistics with word/character totals, language breakdown, tag
+/// co-occurrence and monthly growth
+///
+/// # Examples
+///
+/// ```typescript
+/// const stats = await invoke('get_library_statistics');
+/// console.log(`${stats.total_words} words across ${stats.total_snippets} snippets`);
+/// ```
+#[tauri::command]
+pub async fn get_library_statistics(app: AppHandle) -> Result<LibraryStatistics, String> {
+    library_stats::get_library_statistics(&app)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -266,8 +871,65 @@ mod tests {
     fn test_command_exports() {
         // Verify command functions are properly exported
         // This is a compile-time check
-        let _f1: fn(AppHandle, i64) -> _ = record_snippet_usage;
+        let _f1: fn(AppHandle, i64, Option<UsageSource>, Option<String>) -> _ =
+            record_snippet_usage;
         let _f2: fn(AppHandle, i64) -> _ = get_snippet_analytics;
         let _f3: fn(AppHandle, Option<i64>, Option<i64>) -> _ = get_global_analytics;
     }
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::services::database::run_migrations(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO snippets (id, name, content, kind, created_at, updated_at) VALUES
+                (1, 'intro', 'Hi there,', 'static', 0, 0),
+                (2, 'signature', '-- Me', 'static', 0, 0),
+                (3, 'branch', 'git branch --show-current', 'dynamic', 0, 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_compose_keeps_order_and_repeats() {
+        let pool = setup_test_db().await;
+        let text = compose_selection(&pool, &[2, 1, 2], None, None)
+            .await
+            .unwrap();
+        assert_eq!(text, "-- Me\n\nHi there,\n\n-- Me");
+    }
+
+    #[tokio::test]
+    async fn test_compose_uses_separator() {
+        let pool = setup_test_db().await;
+        let text = compose_selection(&pool, &[1, 2], Some(" | "), None)
+            .await
+            .unwrap();
+        assert_eq!(text, "Hi there, | -- Me");
+    }
+
+    #[tokio::test]
+    async fn test_compose_rejects_unconfirmed_dynamic_and_missing() {
+        let pool = setup_test_db().await;
+        for confirm_dynamic in [None, Some(false)] {
+            let error = compose_selection(&pool, &[1, 3], None, confirm_dynamic)
+                .await
+                .unwrap_err();
+            assert!(error.contains("must be confirmed"), "{}", error);
+        }
+
+        let error = compose_selection(&pool, &[1, 99], None, None)
+            .await
+            .unwrap_err();
+        assert!(error.contains("99 not found"), "{}", error);
+        assert!(compose_selection(&pool, &[], None, None).await.is_err());
+    }
 }
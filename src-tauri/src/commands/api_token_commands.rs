@@ -0,0 +1,124 @@
+use crate::services::api_tokens::{self, ApiScope, ApiToken, ApiTokenAuditEntry, CreatedApiToken};
+use crate::services::database::get_pool;
+use tauri::AppHandle;
+
+/// Default and maximum number of audit entries returned at once
+const DEFAULT_AUDIT_LIMIT: i64 = 100;
+const MAX_AUDIT_LIMIT: i64 = 1000;
+
+/// Mint a scoped token for the local REST API
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `name` - Label shown in the token list
+/// * `scopes` - Scopes to grant (`search`, `read`, `write`)
+///
+/// # Returns
+///
+/// The token metadata and its plaintext secret. The secret is only returned
+/// here; it cannot be retrieved again.
+///
+/// # Errors
+///
+/// Returns an error string if the name is empty, no scope is given, or the
+/// database write fails
+///
+/// # Examples
+///
+/// ```typescript
+/// const { token, secret } = await invoke('create_api_token', {
+///   name: 'Raycast',
+///   scopes: ['search'],
+/// });
+/// ```
+#[tauri::command]
+pub async fn create_api_token(
+    app: AppHandle,
+    name: String,
+    scopes: Vec<ApiScope>,
+) -> Result<CreatedApiToken, String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    api_tokens::create_token(&pool, &name, &scopes)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// List all API tokens, including revoked ones
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+///
+/// # Returns
+///
+/// Token metadata, newest first. Secrets are never included.
+///
+/// # Examples
+///
+/// ```typescript
+/// const tokens = await invoke('list_api_tokens');
+/// ```
+#[tauri::command]
+pub async fn list_api_tokens(app: AppHandle) -> Result<Vec<ApiToken>, String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    api_tokens::list_tokens(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Revoke an API token so further requests with it are rejected
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `id` - ID of the token to revoke
+///
+/// # Errors
+///
+/// Returns an error string if the token does not exist
+///
+/// # Examples
+///
+/// ```typescript
+/// await invoke('revoke_api_token', { id: 3 });
+/// ```
+#[tauri::command]
+pub async fn revoke_api_token(app: AppHandle, id: i64) -> Result<(), String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    api_tokens::revoke_token(&pool, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get the audit trail of REST API requests
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `token_id` - Only return entries for this token (optional)
+/// * `limit` - Maximum number of entries (default: 100, max: 1000)
+///
+/// # Returns
+///
+/// Audit entries, newest first, including rejected requests
+///
+/// # Examples
+///
+/// ```typescript
+/// const entries = await invoke('get_api_token_audit', { tokenId: 3, limit: 50 });
+/// ```
+#[tauri::command]
+pub async fn get_api_token_audit(
+    app: AppHandle,
+    token_id: Option<i64>,
+    limit: Option<i64>,
+) -> Result<Vec<ApiTokenAuditEntry>, String> {
+    let limit = limit
+        .unwrap_or(DEFAULT_AUDIT_LIMIT)
+        .clamp(1, MAX_AUDIT_LIMIT);
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    api_tokens::get_audit_log(&pool, token_id, limit)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,122 @@
+use crate::commands::clipboard_commands::get_clipboard_image;
+use crate::commands::snippet_commands::{create_snippet, delete_snippet};
+use crate::models::{CreateSnippetInput, Snippet, SnippetId, SnippetKind};
+use crate::services::attachments::{self, Attachment};
+use crate::services::database::get_pool;
+use crate::utils::error::AppError;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+/// Create a snippet holding the image on the clipboard
+///
+/// The image is stored in the database. The snippet's content is a short
+/// placeholder naming the image format and size, so the snippet can still
+/// be searched; copying the snippet puts the image back on the clipboard.
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `name` - Unique snippet name
+/// * `description` - Optional description
+/// * `tags` - Optional tags
+///
+/// # Errors
+///
+/// Returns an error if the clipboard holds no image, the image is too large,
+/// or the name is taken
+///
+/// # Examples
+///
+/// ```typescript
+/// const snippet = await invoke('create_image_snippet', {
+///   name: 'Architecture diagram',
+///   tags: ['docs'],
+/// });
+/// ```
+#[tauri::command]
+pub async fn create_image_snippet(
+    app: AppHandle,
+    name: String,
+    description: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<Snippet, String> {
+    let data = get_clipboard_image().await?.ok_or_else(|| {
+        AppError::InvalidInput("The clipboard does not contain an image".to_string())
+    })?;
+    let mime_type = attachments::check_image(&data)?;
+
+    let snippet = create_snippet(
+        app.clone(),
+        CreateSnippetInput {
+            name,
+            content: attachments::image_placeholder(mime_type, data.len()),
+            description,
+            tags: tags.unwrap_or_default(),
+            kind: SnippetKind::Static,
+        },
+    )
+    .await?;
+
+    let pool = get_pool(&app)?;
+    if let Err(e) = attachments::attach_image_data(&pool, snippet.id.0, &data).await {
+        // Don't leave a placeholder behind without its image
+        let _ = delete_snippet(app, snippet.id).await;
+        return Err(e.into());
+    }
+
+    Ok(snippet)
+}
+
+/// Attach an image file to a snippet by reference
+///
+/// Only the path is stored, and the file is read whenever the snippet is
+/// copied, so it should stay where it is.
+///
+/// # Examples
+///
+/// ```typescript
+/// const attachment = await invoke('attach_image_file', {
+///   snippetId: 42,
+///   path: '/home/me/diagrams/flow.png',
+/// });
+/// ```
+#[tauri::command]
+pub async fn attach_image_file(
+    app: AppHandle,
+    snippet_id: SnippetId,
+    path: PathBuf,
+) -> Result<Attachment, String> {
+    let pool = get_pool(&app)?;
+    Ok(attachments::attach_image_file(&pool, snippet_id.0, &path).await?)
+}
+
+/// Get a snippet's attachments, without their data
+///
+/// # Examples
+///
+/// ```typescript
+/// const attachments = await invoke('get_snippet_attachments', { snippetId: 42 });
+/// ```
+#[tauri::command]
+pub async fn get_snippet_attachments(
+    app: AppHandle,
+    snippet_id: SnippetId,
+) -> Result<Vec<Attachment>, String> {
+    let pool = get_pool(&app)?;
+    Ok(attachments::get_attachments(&pool, snippet_id.0).await?)
+}
+
+/// Remove an attachment from its snippet
+///
+/// Files attached by reference are not deleted.
+///
+/// # Examples
+///
+/// ```typescript
+/// await invoke('delete_snippet_attachment', { id: 7 });
+/// ```
+#[tauri::command]
+pub async fn delete_snippet_attachment(app: AppHandle, id: i64) -> Result<(), String> {
+    let pool = get_pool(&app)?;
+    Ok(attachments::delete_attachment(&pool, id).await?)
+}
@@ -1,8 +1,40 @@
 use crate::utils::error::AppError;
+use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 
+/// Mechanism used to read and write the system clipboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardBackend {
+    /// `pbcopy`/`pbpaste` on macOS
+    MacOs,
+    /// `wl-copy`/`wl-paste` (data-control) on Wayland
+    Wayland,
+    /// `xclip` on X11
+    X11,
+    /// No known mechanism; copying fails
+    Unsupported,
+}
+
+/// What the clipboard can do in this session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClipboardSupport {
+    pub backend: ClipboardBackend,
+    /// External program that writes the clipboard
+    pub tool: Option<String>,
+    /// Whether `tool` was found on `PATH`
+    pub tool_available: bool,
+    /// Copied text stays available after Snips exits
+    pub persists_after_exit: bool,
+    /// Highlighted text can be captured without simulating a copy
+    pub primary_selection: bool,
+    /// What to do when something is missing
+    pub message: Option<String>,
+}
+
 /// Get the currently selected text from the active application.
 /// On macOS, this uses AppleScript to simulate Cmd+C and read the clipboard.
+/// On Linux, this reads the primary selection, so nothing is simulated.
 ///
 /// # Returns
 ///
@@ -60,12 +92,25 @@ pub async fn get_selected_text(_app: AppHandle) -> Result<String, String> {
         Ok(selected_text)
     }
 
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(target_os = "linux")]
     {
-        Err(
-            AppError::Unsupported("Text selection capture is only supported on macOS".to_string())
-                .into(),
+        use crate::services::linux_clipboard::{self, Selection};
+
+        let backend = linux_clipboard::current_backend()?;
+        let selected_text = linux_clipboard::read(backend, Selection::Primary)?;
+        if selected_text.trim().is_empty() {
+            return Err(AppError::NotFound("No text selected".to_string()).into());
+        }
+
+        Ok(selected_text)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        Err(AppError::Unsupported(
+            "Text selection capture is only supported on macOS and Linux".to_string(),
         )
+        .into())
     }
 }
 
@@ -86,12 +131,68 @@ pub async fn get_selected_text(_app: AppHandle) -> Result<String, String> {
 /// - Clipboard write operation fails
 /// - Platform is not supported
 #[tauri::command]
-pub async fn copy_to_clipboard(_app: AppHandle, text: String) -> Result<(), String> {
-    // Validate input
+pub async fn copy_to_clipboard(app: AppHandle, text: String) -> Result<(), String> {
+    validate_copy_text(&text)?;
+
+    set_clipboard_content(&text).await?;
+
+    #[cfg(target_os = "linux")]
+    copy_to_primary_if_enabled(&app, &text).await;
+    #[cfg(not(target_os = "linux"))]
+    let _ = app;
+
+    Ok(())
+}
+
+/// Copy formatted text to the system clipboard
+///
+/// Applications that understand formatting (email clients, documents) paste
+/// the HTML; everything else gets the plain text. On macOS the HTML is also
+/// offered as RTF for apps that only read that. On Linux the clipboard tools
+/// offer one format per copy, so only the HTML is offered there.
+///
+/// # Arguments
+///
+/// * `text` - Plain text version of the content
+/// * `html` - HTML version of the content
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Either version is empty or too large
+/// - Clipboard write operation fails
+/// - Platform is not supported
+///
+/// # Examples
+///
+/// ```typescript
+/// await invoke('copy_to_clipboard_rich', { text: 'Hello', html: '<b>Hello</b>' });
+/// ```
+#[tauri::command]
+pub async fn copy_to_clipboard_rich(
+    app: AppHandle,
+    text: String,
+    html: String,
+) -> Result<(), String> {
+    validate_copy_text(&text)?;
+    validate_copy_text(&html)?;
+
+    set_rich_clipboard_content(&text, &html).await?;
+
+    #[cfg(target_os = "linux")]
+    copy_to_primary_if_enabled(&app, &text).await;
+    #[cfg(not(target_os = "linux"))]
+    let _ = app;
+
+    Ok(())
+}
+
+/// Rejects text that is empty or too large to copy
+fn validate_copy_text(text: &str) -> Result<(), AppError> {
     if text.is_empty() {
-        return Err(
-            AppError::InvalidInput("Cannot copy empty text to clipboard".to_string()).into(),
-        );
+        return Err(AppError::InvalidInput(
+            "Cannot copy empty text to clipboard".to_string(),
+        ));
     }
 
     // Limit text size to prevent issues (10MB)
@@ -100,14 +201,131 @@ pub async fn copy_to_clipboard(_app: AppHandle, text: String) -> Result<(), Stri
         return Err(AppError::InvalidInput(format!(
             "Text too large to copy (max {} MB)",
             MAX_SIZE / 1024 / 1024
-        ))
-        .into());
+        )));
+    }
+
+    Ok(())
+}
+
+/// Also puts copied text in the PRIMARY selection when the Linux clipboard
+/// setting asks for it, so middle-click pastes it too
+///
+/// The clipboard copy already succeeded, so failures are only logged.
+#[cfg(target_os = "linux")]
+async fn copy_to_primary_if_enabled(app: &AppHandle, text: &str) {
+    use crate::services::database::get_pool;
+    use crate::services::linux_clipboard;
+
+    let target = match get_pool(app) {
+        Ok(pool) => extra_copy_target(&pool).await,
+        Err(e) => Err(e),
+    };
+    let result = match target {
+        Ok(None) => return,
+        Ok(Some(selection)) => linux_clipboard::current_backend()
+            .and_then(|backend| linux_clipboard::write(backend, selection, text)),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = result {
+        eprintln!("Failed to copy to the primary selection: {}", e);
+    }
+}
+
+/// Selection a copy also goes to besides the clipboard, per the settings
+#[cfg(target_os = "linux")]
+async fn extra_copy_target(
+    pool: &sqlx::SqlitePool,
+) -> Result<Option<crate::services::linux_clipboard::Selection>, AppError> {
+    use crate::services::linux_clipboard::Selection;
+    use crate::services::settings::SettingsService;
+
+    let settings = SettingsService::new(pool.clone()).get_settings().await?;
+    Ok(settings
+        .linux_clipboard
+        .copy_to_primary
+        .then_some(Selection::Primary))
+}
+
+/// Report which clipboard mechanism is in use and whether copies persist
+///
+/// On Linux the process that copies owns the selection, and the text is
+/// lost when it exits. Snips hands ownership to `wl-copy` or `xclip`, which
+/// keep serving the text in the background, so copies survive Snips exiting
+/// as long as the tool is installed.
+///
+/// # Examples
+///
+/// ```typescript
+/// const support = await invoke('probe_clipboard_support');
+/// if (!support.tool_available) console.warn(support.message);
+/// ```
+#[tauri::command]
+pub fn probe_clipboard_support() -> ClipboardSupport {
+    #[cfg(target_os = "macos")]
+    {
+        ClipboardSupport {
+            backend: ClipboardBackend::MacOs,
+            tool: Some("pbcopy".to_string()),
+            tool_available: true,
+            // The pasteboard server keeps the data
+            persists_after_exit: true,
+            primary_selection: false,
+            message: None,
+        }
     }
 
-    set_clipboard_content(&text).await
+    #[cfg(target_os = "linux")]
+    {
+        use crate::services::linux_clipboard::{self, LinuxClipboardBackend};
+
+        let Some(backend) = LinuxClipboardBackend::detect() else {
+            return ClipboardSupport {
+                backend: ClipboardBackend::Unsupported,
+                tool: None,
+                tool_available: false,
+                persists_after_exit: false,
+                primary_selection: false,
+                message: Some("No Wayland or X11 session was found".to_string()),
+            };
+        };
+
+        let tool = backend.write_program();
+        let tool_available = linux_clipboard::is_installed(tool);
+        let kind = match backend {
+            LinuxClipboardBackend::Wayland => ClipboardBackend::Wayland,
+            LinuxClipboardBackend::X11 => ClipboardBackend::X11,
+        };
+        ClipboardSupport {
+            backend: kind,
+            tool: Some(tool.to_string()),
+            tool_available,
+            persists_after_exit: tool_available,
+            primary_selection: tool_available,
+            message: (!tool_available).then(|| {
+                format!(
+                    "{} was not found. Install {} to copy snippets to the clipboard",
+                    tool,
+                    backend.package()
+                )
+            }),
+        }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        ClipboardSupport {
+            backend: ClipboardBackend::Unsupported,
+            tool: None,
+            tool_available: false,
+            persists_after_exit: false,
+            primary_selection: false,
+            message: Some("Clipboard operations are not supported on this platform".to_string()),
+        }
+    }
 }
 
 /// Helper function to get clipboard content
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
 async fn get_clipboard_content() -> Result<String, String> {
     #[cfg(target_os = "macos")]
     {
@@ -124,7 +342,15 @@ async fn get_clipboard_content() -> Result<String, String> {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(target_os = "linux")]
+    {
+        use crate::services::linux_clipboard::{self, Selection};
+
+        let backend = linux_clipboard::current_backend()?;
+        Ok(linux_clipboard::read(backend, Selection::Clipboard)?)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     {
         Err(
             AppError::Unsupported("Clipboard operations only supported on macOS".to_string())
@@ -162,8 +388,235 @@ async fn set_clipboard_content(text: &str) -> Result<(), String> {
         Ok(())
     }
 
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(target_os = "linux")]
+    {
+        use crate::services::linux_clipboard::{self, Selection};
+
+        let backend = linux_clipboard::current_backend()?;
+        Ok(linux_clipboard::write(backend, Selection::Clipboard, text)?)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        let _ = text;
+        Err(
+            AppError::Unsupported("Clipboard operations only supported on macOS".to_string())
+                .into(),
+        )
+    }
+}
+
+/// Helper function to set clipboard content in several formats
+async fn set_rich_clipboard_content(text: &str, html: &str) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        use std::process::Command;
+
+        // NSPasteboard through the JavaScript for Automation bridge; AppKit
+        // converts the HTML to RTF
+        let script = r#"
+            ObjC.import('AppKit');
+            function run(argv) {
+                const [text, html] = argv;
+                const pasteboard = $.NSPasteboard.generalPasteboard;
+                pasteboard.clearContents;
+                pasteboard.setStringForType($(text), $.NSPasteboardTypeString);
+                pasteboard.setStringForType($(html), $.NSPasteboardTypeHTML);
+                const data = $('<meta charset="utf-8">' + html)
+                    .dataUsingEncoding($.NSUTF8StringEncoding);
+                const attributed = $.NSAttributedString.alloc
+                    .initWithHTMLDocumentAttributes(data, null);
+                if (attributed && !attributed.isNil()) {
+                    const rtf = attributed.RTFFromRangeDocumentAttributes(
+                        $.NSMakeRange(0, attributed.length),
+                        $.NSDictionary.dictionary
+                    );
+                    pasteboard.setDataForType(rtf, $.NSPasteboardTypeRTF);
+                }
+            }
+        "#;
+
+        let output = Command::new("osascript")
+            .args(["-l", "JavaScript", "-e", script, "--", text, html])
+            .output()
+            .map_err(|e| AppError::External(format!("Failed to write to clipboard: {}", e)))?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::External(format!(
+                "Failed to write to clipboard: {}",
+                error.trim()
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use crate::services::linux_clipboard::{self, ContentType, Selection};
+
+        let _ = text;
+        let backend = linux_clipboard::current_backend()?;
+        Ok(linux_clipboard::write_as(
+            backend,
+            Selection::Clipboard,
+            ContentType::Html,
+            html,
+        )?)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        let _ = (text, html);
+        Err(
+            AppError::Unsupported("Clipboard operations only supported on macOS".to_string())
+                .into(),
+        )
+    }
+}
+
+/// Reads the image on the clipboard, `None` when it holds no image
+pub(crate) async fn get_clipboard_image() -> Result<Option<Vec<u8>>, String> {
+    #[cfg(target_os = "macos")]
+    {
+        use std::process::Command;
+
+        // osascript only prints text, so the image goes through a file
+        let script = r#"
+            ObjC.import('AppKit');
+            function run(argv) {
+                const pasteboard = $.NSPasteboard.generalPasteboard;
+                for (const type of ['public.png', 'public.jpeg', 'public.tiff']) {
+                    const data = pasteboard.dataForType(type);
+                    if (data && !data.isNil() && data.writeToFileAtomically(argv[0], true)) {
+                        return 'found';
+                    }
+                }
+                return '';
+            }
+        "#;
+
+        let path = image_transfer_path();
+        let output = Command::new("osascript")
+            .args(["-l", "JavaScript", "-e", script, "--"])
+            .arg(&path)
+            .output()
+            .map_err(|e| AppError::External(format!("Failed to read clipboard: {}", e)))?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(
+                AppError::External(format!("Failed to read clipboard: {}", error.trim())).into(),
+            );
+        }
+        if String::from_utf8_lossy(&output.stdout).trim() != "found" {
+            return Ok(None);
+        }
+
+        let data = std::fs::read(&path)
+            .map_err(|e| AppError::External(format!("Failed to read clipboard: {}", e)));
+        let _ = std::fs::remove_file(&path);
+        Ok(Some(data?))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use crate::services::attachments;
+        use crate::services::linux_clipboard::{self, Selection};
+
+        let backend = linux_clipboard::current_backend()?;
+        let offered = linux_clipboard::list_types(backend, Selection::Clipboard)?;
+        let Some(mime_type) = attachments::preferred_image_type(&offered) else {
+            return Ok(None);
+        };
+        let data = linux_clipboard::read_bytes(backend, Selection::Clipboard, mime_type)?;
+        Ok((!data.is_empty()).then_some(data))
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        Err(
+            AppError::Unsupported("Clipboard operations only supported on macOS".to_string())
+                .into(),
+        )
+    }
+}
+
+/// Puts an image on the clipboard under its MIME type
+pub(crate) async fn set_clipboard_image(mime_type: &str, data: &[u8]) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        use std::process::Command;
+
+        let pasteboard_type = match mime_type {
+            "image/png" => "public.png",
+            "image/jpeg" => "public.jpeg",
+            "image/gif" => "com.compuserve.gif",
+            "image/tiff" => "public.tiff",
+            "image/webp" => "org.webmproject.webp",
+            _ => {
+                return Err(AppError::Unsupported(format!(
+                    "Cannot copy {} to the clipboard",
+                    mime_type
+                ))
+                .into())
+            }
+        };
+        let script = r#"
+            ObjC.import('AppKit');
+            function run(argv) {
+                const [path, type] = argv;
+                const data = $.NSData.dataWithContentsOfFile(path);
+                const pasteboard = $.NSPasteboard.generalPasteboard;
+                pasteboard.clearContents;
+                if (data.isNil() || !pasteboard.setDataForType(data, type)) {
+                    throw new Error('The pasteboard rejected the image');
+                }
+            }
+        "#;
+
+        let path = image_transfer_path();
+        std::fs::write(&path, data)
+            .map_err(|e| AppError::External(format!("Failed to write to clipboard: {}", e)))?;
+        let output = Command::new("osascript")
+            .args(["-l", "JavaScript", "-e", script, "--"])
+            .arg(&path)
+            .arg(pasteboard_type)
+            .output();
+        let _ = std::fs::remove_file(&path);
+        let output = output
+            .map_err(|e| AppError::External(format!("Failed to write to clipboard: {}", e)))?;
+
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::External(format!(
+                "Failed to write to clipboard: {}",
+                error.trim()
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use crate::services::linux_clipboard::{self, Selection};
+
+        let backend = linux_clipboard::current_backend()?;
+        Ok(linux_clipboard::write_bytes(
+            backend,
+            Selection::Clipboard,
+            mime_type,
+            data,
+        )?)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     {
+        let _ = (mime_type, data);
         Err(
             AppError::Unsupported("Clipboard operations only supported on macOS".to_string())
                 .into(),
@@ -171,10 +624,57 @@ async fn set_clipboard_content(text: &str) -> Result<(), String> {
     }
 }
 
+/// File images are passed through on their way to and from the pasteboard
+#[cfg(target_os = "macos")]
+fn image_transfer_path() -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("snips-clipboard-{}.img", std::process::id()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_validate_copy_text() {
+        assert!(validate_copy_text("<b>Hello</b>").is_ok());
+        assert!(matches!(
+            validate_copy_text(""),
+            Err(AppError::InvalidInput(_))
+        ));
+        let too_large = "x".repeat(10 * 1024 * 1024 + 1);
+        assert!(validate_copy_text(&too_large).is_err());
+    }
+
+    #[tokio::test]
+    #[cfg(target_os = "linux")]
+    async fn test_extra_copy_target_follows_setting() {
+        use crate::services::linux_clipboard::Selection;
+        use crate::services::settings::SettingsService;
+
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::services::database::run_migrations(&pool)
+            .await
+            .unwrap();
+        assert_eq!(extra_copy_target(&pool).await.unwrap(), None);
+
+        let service = SettingsService::new(pool.clone());
+        let mut settings = service.get_settings().await.unwrap();
+        settings.linux_clipboard.copy_to_primary = true;
+        service.update_settings(settings.clone()).await.unwrap();
+        assert_eq!(
+            extra_copy_target(&pool).await.unwrap(),
+            Some(Selection::Primary)
+        );
+
+        settings.linux_clipboard.copy_to_primary = false;
+        service.update_settings(settings).await.unwrap();
+        assert_eq!(extra_copy_target(&pool).await.unwrap(), None);
+    }
+
     #[tokio::test]
     #[cfg(target_os = "macos")]
     async fn test_clipboard_operations() {
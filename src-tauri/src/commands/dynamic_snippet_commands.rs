@@ -0,0 +1,48 @@
+use crate::services::database::get_pool;
+use crate::services::dynamic_snippets::{self, DynamicCommand, ExecutionEnvironment};
+use tauri::AppHandle;
+
+/// Get the commands that copying these snippets would run
+///
+/// The frontend shows these in a confirmation prompt before calling
+/// `copy_snippets_with_analytics` with `confirmDynamic: true`.
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `snippet_ids` - Snippets about to be copied
+///
+/// # Returns
+///
+/// The dynamic snippets among `snippet_ids`, in order; empty if all are static
+///
+/// # Examples
+///
+/// ```typescript
+/// const commands = await invoke('get_dynamic_snippet_commands', {
+///   snippetIds: [1, 2, 3]
+/// });
+/// ```
+#[tauri::command]
+pub async fn get_dynamic_snippet_commands(
+    app: AppHandle,
+    snippet_ids: Vec<i64>,
+) -> Result<Vec<DynamicCommand>, String> {
+    let pool = get_pool(&app)?;
+    Ok(dynamic_snippets::dynamic_commands(&pool, &snippet_ids).await?)
+}
+
+/// Get the sandbox dynamic snippet commands run in
+///
+/// Lets the editor warn that commands run confined under Snap or the macOS
+/// App Sandbox, where user tools may be missing.
+///
+/// # Examples
+///
+/// ```typescript
+/// const environment = await invoke('get_dynamic_snippet_environment');
+/// ```
+#[tauri::command]
+pub async fn get_dynamic_snippet_environment() -> Result<ExecutionEnvironment, String> {
+    Ok(ExecutionEnvironment::detect())
+}
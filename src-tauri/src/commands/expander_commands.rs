@@ -0,0 +1,83 @@
+use crate::commands::settings_commands::{get_settings, update_settings, SettingsServiceState};
+use crate::models::SnippetId;
+use crate::services::database::get_pool;
+use crate::services::expander::{self, ExpanderStatus, ExpansionTrigger};
+use tauri::{AppHandle, State};
+
+/// Turn text expansion on or off
+///
+/// Saves `expansion_settings.enabled` like any other settings change, which
+/// starts or stops watching typing.
+///
+/// # Returns
+///
+/// Whether expansion is running, or why it could not start (e.g. the
+/// session is Wayland or the permission to watch typing is missing)
+///
+/// # Examples
+///
+/// ```typescript
+/// const status = await invoke('set_text_expansion_enabled', { enabled: true });
+/// if (status.error) console.warn(status.error);
+/// ```
+#[tauri::command]
+pub async fn set_text_expansion_enabled(
+    app: AppHandle,
+    enabled: bool,
+    settings_state: State<'_, SettingsServiceState>,
+) -> Result<ExpanderStatus, String> {
+    let mut settings = get_settings(app.clone(), settings_state.clone()).await?;
+    settings.expansion_settings.enabled = enabled;
+    update_settings(app.clone(), settings, settings_state).await?;
+    Ok(expander::status(&app).await)
+}
+
+/// Get whether text expansion is running
+#[tauri::command]
+pub async fn get_text_expansion_status(app: AppHandle) -> Result<ExpanderStatus, String> {
+    Ok(expander::status(&app).await)
+}
+
+/// Set or remove the trigger that expands into a snippet
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `id` - Snippet to set the trigger of
+/// * `trigger` - Text such as `;sig`, or `null` to remove the trigger
+///
+/// # Returns
+///
+/// The trigger as stored
+///
+/// # Errors
+///
+/// Returns an error if the trigger is malformed, belongs to another snippet
+/// or overlaps one, or the snippet is dynamic or does not exist
+///
+/// # Examples
+///
+/// ```typescript
+/// await invoke('set_snippet_expansion_trigger', { id: 42, trigger: ';sig' });
+/// ```
+#[tauri::command]
+pub async fn set_snippet_expansion_trigger(
+    app: AppHandle,
+    id: SnippetId,
+    trigger: Option<String>,
+) -> Result<Option<String>, String> {
+    let pool = get_pool(&app)?;
+    let trigger = expander::set_trigger(&pool, id.into(), trigger.as_deref()).await?;
+    expander::reload_triggers(&app).await?;
+    Ok(trigger)
+}
+
+/// List the snippets that have a trigger, by trigger
+///
+/// Archived and dynamic snippets are left out; they keep their trigger but do
+/// not expand.
+#[tauri::command]
+pub async fn get_expansion_triggers(app: AppHandle) -> Result<Vec<ExpansionTrigger>, String> {
+    let pool = get_pool(&app)?;
+    Ok(expander::list_triggers(&pool).await?)
+}
@@ -0,0 +1,144 @@
+use crate::commands::snippet_commands::get_snippet;
+use crate::commands::storage_commands::import_snippet_exports;
+use crate::models::SnippetId;
+use crate::services::database::get_pool;
+use crate::services::gist::{self, GistExportResult};
+use crate::services::keyring;
+use crate::services::library_stats;
+use crate::services::menubar;
+use crate::services::name_index;
+use crate::services::settings::SettingsService;
+use crate::utils::error::AppError;
+use crate::utils::time::current_timestamp;
+use tauri::AppHandle;
+
+/// Import every file of a GitHub gist as a snippet
+///
+/// Each file becomes a snippet named after the file, tagged with the file's
+/// language, with the gist description as its description. Existing snippets
+/// with the same name are updated. The stored access token is used when set,
+/// which is required for secret gists.
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `gist` - Gist ID or URL
+///
+/// # Returns
+///
+/// The number of snippets imported
+///
+/// # Errors
+///
+/// Returns an error string if the gist ID is invalid, the gist does not
+/// exist, or the request fails
+///
+/// # Examples
+///
+/// ```typescript
+/// const count = await invoke('import_from_gist', {
+///   gist: 'https://gist.github.com/octocat/aa5a315d61ae9438b18d',
+/// });
+/// ```
+#[tauri::command]
+pub async fn import_from_gist(app: AppHandle, gist: String) -> Result<usize, String> {
+    let gist_id = gist::parse_gist_id(&gist)
+        .ok_or_else(|| AppError::InvalidInput(format!("Not a gist ID or URL: {}", gist)))?;
+
+    let pool = get_pool(&app)?;
+    let token = gist_access_token(&app).await?;
+    let document = gist::fetch_gist(&gist_id, token.as_deref()).await?;
+
+    let snippets = gist::gist_to_snippets(&document, current_timestamp());
+    let imported = import_snippet_exports(&pool, snippets).await?;
+    library_stats::invalidate(&app).await;
+    name_index::invalidate(&app).await;
+    menubar::notify_snippets_changed(&app);
+    Ok(imported)
+}
+
+/// Publish a snippet as a new GitHub gist
+///
+/// Requires a personal access token with the `gist` scope, stored with
+/// `set_gist_access_token`.
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `id` - The snippet to publish
+/// * `public` - Whether the gist is public (default: false, a secret gist)
+///
+/// # Returns
+///
+/// The new gist's ID and URL
+///
+/// # Errors
+///
+/// Returns an error string if no token is configured, the snippet does not
+/// exist, or GitHub rejects the request
+///
+/// # Examples
+///
+/// ```typescript
+/// const { url } = await invoke('export_snippet_to_gist', { id: 42, public: false });
+/// ```
+#[tauri::command]
+pub async fn export_snippet_to_gist(
+    app: AppHandle,
+    id: SnippetId,
+    public: Option<bool>,
+) -> Result<GistExportResult, String> {
+    let token = gist_access_token(&app).await?.ok_or_else(|| {
+        AppError::InvalidInput("GitHub access token is not configured".to_string())
+    })?;
+    let snippet = get_snippet(app, id).await?;
+
+    let payload = gist::snippet_to_gist_payload(&snippet, public.unwrap_or(false));
+    Ok(gist::create_gist(&payload, &token).await?)
+}
+
+/// Store the GitHub access token used for gists in the OS keychain
+///
+/// # Arguments
+///
+/// * `token` - Personal access token with the `gist` scope; `null` or an
+///   empty string removes the stored token
+///
+/// # Examples
+///
+/// ```typescript
+/// await invoke('set_gist_access_token', { token: 'ghp_...' });
+/// ```
+#[tauri::command]
+pub async fn set_gist_access_token(token: Option<String>) -> Result<(), String> {
+    Ok(keyring::set_gist_access_token(token.as_deref()).await?)
+}
+
+/// Get whether a GitHub access token is configured, without revealing it
+#[tauri::command]
+pub async fn has_gist_access_token(app: AppHandle) -> Result<bool, String> {
+    Ok(gist_access_token(&app).await?.is_some())
+}
+
+/// The gist token from the keychain, or from settings where an earlier
+/// version saved it and the keychain could not take it
+async fn gist_access_token(app: &AppHandle) -> Result<Option<String>, AppError> {
+    let stored = match keyring::get_credential(keyring::GIST_ACCESS_TOKEN).await {
+        Ok(token) => token,
+        Err(e) => {
+            eprintln!("Falling back to the gist token in settings: {}", e);
+            None
+        }
+    };
+    let token = match stored {
+        Some(token) => Some(token),
+        None => {
+            let pool = get_pool(app)?;
+            let settings = SettingsService::new(pool).get_settings().await?;
+            settings.gist_settings.access_token
+        }
+    };
+    Ok(token
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty()))
+}
@@ -1,51 +1,134 @@
 pub mod analytics_commands;
+pub mod api_token_commands;
+pub mod attachment_commands;
 pub mod clipboard_commands;
+pub mod dynamic_snippet_commands;
+pub mod expander_commands;
+pub mod gist_commands;
+pub mod preview_commands;
 pub mod search_commands;
 pub mod settings_commands;
+pub mod share_commands;
 pub mod shortcut_commands;
 pub mod snippet_commands;
+pub mod source_commands;
 pub mod storage_commands;
 pub mod tag_commands;
+pub mod team_feed_commands;
+pub mod theme_commands;
+pub mod token_commands;
 pub mod window_commands;
 
 // Re-export analytics commands
 pub use analytics_commands::{
-    copy_snippets_with_analytics, get_global_analytics, get_snippet_analytics, record_snippet_usage,
+    compose_snippets, copy_last_used_snippet, copy_slot_snippet, copy_snippets_as_markdown,
+    copy_snippets_with_analytics, get_analytics_by_source, get_analytics_by_tag,
+    get_global_analytics, get_library_statistics, get_productivity_summary, get_snippet_analytics,
+    get_usage_trends, record_snippet_usage,
+};
+
+// Re-export API token commands
+pub use api_token_commands::{
+    create_api_token, get_api_token_audit, list_api_tokens, revoke_api_token,
+};
+
+// Re-export attachment commands
+pub use attachment_commands::{
+    attach_image_file, create_image_snippet, delete_snippet_attachment, get_snippet_attachments,
 };
 
 // Re-export clipboard commands
-pub use clipboard_commands::{copy_to_clipboard, get_selected_text};
+pub use clipboard_commands::{
+    copy_to_clipboard, copy_to_clipboard_rich, get_selected_text, probe_clipboard_support,
+};
+
+// Re-export dynamic snippet commands
+pub use dynamic_snippet_commands::{get_dynamic_snippet_commands, get_dynamic_snippet_environment};
+
+// Re-export expander commands
+pub use expander_commands::{
+    get_expansion_triggers, get_text_expansion_status, set_snippet_expansion_trigger,
+    set_text_expansion_enabled,
+};
+
+// Re-export gist commands
+pub use gist_commands::{
+    export_snippet_to_gist, has_gist_access_token, import_from_gist, set_gist_access_token,
+};
+
+// Re-export preview commands
+pub use preview_commands::render_snippet_preview;
 
 // Re-export search commands
-pub use search_commands::search_snippets;
+pub use search_commands::{
+    batch_search, explain_search_ranking, get_frequent_snippets, get_pinned_snippets,
+    get_recent_snippets, search_snippets,
+};
 
 // Re-export settings commands
 pub use settings_commands::{
-    get_settings, get_storage_type, set_storage_type, update_settings, SettingsServiceState,
+    export_settings, get_api_server_status, get_settings, get_storage_type, import_settings,
+    set_storage_type, update_settings, SettingsServiceState,
 };
 
+// Re-export share commands
+pub use share_commands::{import_shared_snippet, share_snippet};
+
 // Re-export shortcut commands
 pub use shortcut_commands::{
-    get_default_shortcuts, is_shortcut_valid, register_custom_shortcut,
-    reregister_default_shortcuts, unregister_shortcut,
+    assign_snippet_slot, check_shortcut, get_default_shortcuts, get_slot_assignments,
+    is_shortcut_valid, register_custom_shortcut, reregister_default_shortcuts, unregister_shortcut,
 };
 
 // Re-export snippet commands
 pub use snippet_commands::{
-    create_snippet, delete_snippet, get_all_snippets, get_snippet, update_snippet,
+    add_snippet_alias, append_to_snippet, archive_snippet, archive_snippets, create_snippet,
+    create_snippet_with_resolution, delete_snippet, detect_snippet_language, get_all_snippets,
+    get_expiring_snippets, get_snippet, get_snippet_aliases, get_snippet_parameters,
+    get_stale_snippets, remove_snippet_alias, render_snippet_with_parameters, reorder_snippets,
+    set_snippet_aliases, set_snippet_expiration, set_snippet_parameters, toggle_pin_snippet,
+    unarchive_snippet, update_snippet,
+};
+
+// Re-export source commands
+pub use source_commands::{
+    get_snippet_source, get_snippet_versions, refresh_snippet_source, set_snippet_source,
 };
 
 // Re-export storage commands
 pub use storage_commands::{
-    backup_database, export_to_json, get_backup_config, get_database_stats, import_from_json,
-    list_backups, restore_database, update_backup_config,
+    backup_database, check_search_index_consistency, discard_import_job, export_diagnostics_bundle,
+    export_to_json, export_to_markdown, export_to_vscode_snippets, factory_reset,
+    get_backup_config, get_database_diagnostics, get_database_stats, get_resumable_import,
+    get_startup_report, import_from_json, import_from_markdown, import_from_snippet_app,
+    import_from_vscode_snippets, list_backups, optimize_database, preview_backup,
+    prune_backups_now, rebuild_search_index, repair_database, restore_database,
+    restore_snippets_from_backup, update_backup_config, verify_backup,
 };
 
 // Re-export tag commands
-pub use tag_commands::{get_tags, update_tag_color_cmd};
+pub use tag_commands::{
+    cleanup_orphan_tags, delete_tag, get_tag_stats, get_tag_tree, get_tags, merge_tags, rename_tag,
+    suggest_tags_for_content, update_tag_color_cmd,
+};
+
+// Re-export team feed commands
+pub use team_feed_commands::{
+    get_snippet_provenance, list_feeds, subscribe_feed, sync_feed_now, unsubscribe_feed,
+};
+
+// Re-export theme commands
+pub use theme_commands::{
+    apply_custom_theme, delete_custom_theme, get_active_custom_theme, get_resolved_theme,
+    import_pywal_theme, list_custom_themes, save_custom_theme,
+};
+
+// Re-export token estimate commands
+pub use token_commands::{estimate_snippet_tokens, estimate_text_tokens};
 
 // Re-export window commands
 pub use window_commands::{
-    hide_search_window, show_management_window, show_quick_add_window, show_search_window,
+    get_resource_usage, get_window_diagnostics, handle_overlay_escape, hide_search_window,
+    reset_window_layout, show_management_window, show_quick_add_window, show_search_window,
     toggle_search_window, update_badge_count,
 };
@@ -0,0 +1,50 @@
+use crate::commands::snippet_commands::get_snippet;
+use crate::models::SnippetId;
+use crate::services::preview::{self, PreviewFormat, RenderedPreview};
+use tauri::AppHandle;
+
+/// Render a snippet as sanitized HTML for display in any window
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `id` - The snippet to render
+/// * `format` - Optional rendering mode (default: `auto`, which picks markdown,
+///   code or plain text from the tags and detected language)
+///
+/// # Returns
+///
+/// `RenderedPreview` containing the HTML and the format/language that was used.
+/// All snippet text is escaped, so the HTML is safe to inject directly.
+///
+/// # Errors
+///
+/// Returns an error string if the snippet does not exist or the database
+/// query fails
+///
+/// # Examples
+///
+/// ```javascript
+/// // From frontend
+/// const preview = await invoke('render_snippet_preview', {
+///   id: 42,
+///   format: 'markdown'
+/// });
+/// element.innerHTML = preview.html;
+/// ```
+#[tauri::command]
+pub async fn render_snippet_preview(
+    app: AppHandle,
+    id: SnippetId,
+    format: Option<PreviewFormat>,
+) -> Result<RenderedPreview, String> {
+    let snippet = get_snippet(app, id).await?;
+    let tags = snippet.tags.unwrap_or_default();
+
+    Ok(preview::render_preview(
+        &snippet.content,
+        format.unwrap_or_default(),
+        &tags,
+        snippet.language.as_deref(),
+    ))
+}
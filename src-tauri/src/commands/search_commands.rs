@@ -1,5 +1,5 @@
-use crate::models::SearchResult;
-use crate::services::search;
+use crate::models::{SearchPage, SearchResult};
+use crate::services::search::{self, BatchSearchRequest, BatchSearchResponse, RankingExplanation};
 use crate::utils::error::AppError;
 use tauri::AppHandle;
 
@@ -9,22 +9,35 @@ use tauri::AppHandle;
 ///
 /// * `app` - Tauri application handle
 /// * `query` - The search query string
-/// * `limit` - Optional maximum number of results (default: 50, max: 1000)
+/// * `limit` - Optional maximum number of results per page (default: 50, max: 1000)
+/// * `offset` - Optional number of matches to skip (default: 0)
+/// * `cursor` - Optional `next_cursor` from the previous page; overrides `offset`
+/// * `include_content` - Optional; `false` leaves each result's `content`
+///   empty so only its `preview` is sent (default: `true`)
 ///
 /// # Returns
 ///
-/// Vector of `SearchResult` sorted by relevance score combining:
+/// `SearchPage` whose `results` are sorted by relevance score combining:
 /// - Text relevance (FTS5 BM25 ranking)
 /// - Usage frequency (how often used)
 /// - Recency (when last used)
 ///
+/// plus `total_matches` across all pages and a `next_cursor` that is `null`
+/// on the last page
+///
 /// # Examples
 ///
 /// ```javascript
 /// // From frontend
-/// const results = await invoke('search_snippets', {
+/// const page = await invoke('search_snippets', {
+///   query: 'react hooks',
+///   limit: 20,
+///   includeContent: false
+/// });
+/// const next = await invoke('search_snippets', {
 ///   query: 'react hooks',
-///   limit: 20
+///   limit: 20,
+///   cursor: page.next_cursor
 /// });
 /// ```
 #[tauri::command]
@@ -32,7 +45,10 @@ pub async fn search_snippets(
     app: AppHandle,
     query: String,
     limit: Option<i64>,
-) -> Result<Vec<SearchResult>, String> {
+    offset: Option<i64>,
+    cursor: Option<String>,
+    include_content: Option<bool>,
+) -> Result<SearchPage, String> {
     // Validate input
     if query.len() > 1000 {
         return Err(AppError::InvalidInput(
@@ -41,9 +57,224 @@ pub async fn search_snippets(
         .into());
     }
 
-    search::search_snippets(&app, &query, limit)
+    let mut page = search::search_snippets(&app, &query, limit, offset, cursor.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    if include_content == Some(false) {
+        search::omit_content(&mut page.results);
+    }
+    Ok(page)
+}
+
+/// Get pinned snippets for the search overlay's empty state
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `limit` - Optional maximum number of results (default: 50, max: 1000)
+/// * `include_content` - Optional; `false` sends previews without the full
+///   content (default: `true`)
+///
+/// # Returns
+///
+/// Pinned snippets with usage statistics, sorted by relevance score
+///
+/// # Examples
+///
+/// ```javascript
+/// const pinned = await invoke('get_pinned_snippets', { limit: 10 });
+/// ```
+#[tauri::command]
+pub async fn get_pinned_snippets(
+    app: AppHandle,
+    limit: Option<i64>,
+    include_content: Option<bool>,
+) -> Result<Vec<SearchResult>, String> {
+    let mut results = search::get_pinned_snippets(&app, limit)
+        .await
+        .map_err(|e| e.to_string())?;
+    if include_content == Some(false) {
+        search::omit_content(&mut results);
+    }
+    Ok(results)
+}
+
+/// Get the most recently used snippets for the search overlay's empty state
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `limit` - Optional maximum number of results (default: 50, max: 1000)
+/// * `include_content` - Optional; `false` sends previews without the full
+///   content (default: `true`)
+///
+/// # Returns
+///
+/// Used snippets with usage statistics, most recently used first
+///
+/// # Examples
+///
+/// ```javascript
+/// const recent = await invoke('get_recent_snippets', { limit: 10 });
+/// ```
+#[tauri::command]
+pub async fn get_recent_snippets(
+    app: AppHandle,
+    limit: Option<i64>,
+    include_content: Option<bool>,
+) -> Result<Vec<SearchResult>, String> {
+    let mut results = search::get_recent_snippets(&app, limit)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    if include_content == Some(false) {
+        search::omit_content(&mut results);
+    }
+    Ok(results)
+}
+
+/// Get the most often used snippets for the search overlay's empty state
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `limit` - Optional maximum number of results (default: 50, max: 1000)
+/// * `include_content` - Optional; `false` sends previews without the full
+///   content (default: `true`)
+///
+/// # Returns
+///
+/// Used snippets with usage statistics, most often used first
+///
+/// # Examples
+///
+/// ```javascript
+/// const frequent = await invoke('get_frequent_snippets', { limit: 10 });
+/// ```
+#[tauri::command]
+pub async fn get_frequent_snippets(
+    app: AppHandle,
+    limit: Option<i64>,
+    include_content: Option<bool>,
+) -> Result<Vec<SearchResult>, String> {
+    let mut results = search::get_frequent_snippets(&app, limit)
+        .await
+        .map_err(|e| e.to_string())?;
+    if include_content == Some(false) {
+        search::omit_content(&mut results);
+    }
+    Ok(results)
+}
+
+/// Explain how a snippet's relevance score for a query is made up
+///
+/// A debugging aid for tuning the search weights: shows where the snippet
+/// ranks and what each part of its score (text relevance, usage, recency,
+/// app context, pinning) contributes.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `query` - The search query string
+/// * `snippet_id` - A snippet matching the query
+///
+/// # Returns
+///
+/// The snippet's position, its usage statistics and a `ScoreBreakdown`
+/// with each score, its weight and the weighted amount
+///
+/// # Examples
+///
+/// ```javascript
+/// const explanation = await invoke('explain_search_ranking', {
+///   query: 'react hooks',
+///   snippetId: 42
+/// });
+/// ```
+#[tauri::command]
+pub async fn explain_search_ranking(
+    app: AppHandle,
+    query: String,
+    snippet_id: i64,
+) -> Result<RankingExplanation, String> {
+    if query.len() > 1000 {
+        return Err(AppError::InvalidInput(
+            "Search query too long (max 1000 characters)".to_string(),
+        )
+        .into());
+    }
+
+    Ok(search::explain_search_ranking(&app, &query, snippet_id).await?)
+}
+
+/// Maximum number of queries accepted in one `batch_search` call
+const MAX_BATCH_SIZE: usize = 20;
+
+/// Run several search queries in a single invoke
+///
+/// Useful when a window needs suggestions, search results and tag counts at
+/// once (e.g. when the search overlay opens). All queries share one database
+/// connection and one settings load.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `requests` - Up to 20 queries, each tagged with a `kind`
+/// * `include_content` - Optional; `false` sends previews without the full
+///   content (default: `true`)
+///
+/// # Returns
+///
+/// One `BatchSearchResponse` per request, in request order. Individual query
+/// failures are reported as `{ kind: 'error', message }` entries.
+///
+/// # Examples
+///
+/// ```javascript
+/// const [suggestions, tagCounts] = await invoke('batch_search', {
+///   requests: [
+///     { kind: 'suggestions', limit: 10 },
+///     { kind: 'tag_counts' }
+///   ]
+/// });
+/// ```
+#[tauri::command]
+pub async fn batch_search(
+    app: AppHandle,
+    requests: Vec<BatchSearchRequest>,
+    include_content: Option<bool>,
+) -> Result<Vec<BatchSearchResponse>, String> {
+    if requests.len() > MAX_BATCH_SIZE {
+        return Err(AppError::InvalidInput(format!(
+            "Too many queries in batch (max {})",
+            MAX_BATCH_SIZE
+        ))
+        .into());
+    }
+
+    if requests.iter().any(
+        |request| matches!(request, BatchSearchRequest::Search { query, .. } if query.len() > 1000),
+    ) {
+        return Err(AppError::InvalidInput(
+            "Search query too long (max 1000 characters)".to_string(),
+        )
+        .into());
+    }
+
+    let mut responses = search::batch_search(&app, requests)
+        .await
+        .map_err(|e| e.to_string())?;
+    if include_content == Some(false) {
+        for response in &mut responses {
+            if let BatchSearchResponse::Search { results }
+            | BatchSearchResponse::Suggestions { results }
+            | BatchSearchResponse::Recent { results }
+            | BatchSearchResponse::Frequent { results } = response
+            {
+                search::omit_content(results);
+            }
+        }
+    }
+    Ok(responses)
 }
 
 #[cfg(test)]
@@ -1,7 +1,15 @@
+use crate::commands::shortcut_commands::sync_slot_shortcuts;
 use crate::models::settings::{AppSettings, StorageType};
+use crate::services::analytics;
+use crate::services::api_server::{self, ApiServerStatus};
+use crate::services::appearance::{self, ResolvedTheme};
 use crate::services::database::get_pool;
-use crate::services::settings::SettingsService;
+use crate::services::settings::{
+    self, SettingsFileFormat, SettingsService, SETTINGS_CHANGED_EVENT,
+};
+use crate::services::{expander, keyring, shortcuts, window};
 use crate::utils::error::AppError;
+use std::path::Path;
 use tauri::{AppHandle, Emitter, State};
 use tokio::sync::Mutex;
 
@@ -42,7 +50,7 @@ pub async fn get_settings(
 #[tauri::command]
 pub async fn update_settings(
     app: AppHandle,
-    settings: AppSettings,
+    mut settings: AppSettings,
     settings_state: State<'_, SettingsServiceState>,
 ) -> Result<(), String> {
     // Get or initialize settings service
@@ -57,15 +65,51 @@ pub async fn update_settings(
 
     let service = service_guard.as_ref().unwrap();
 
+    let previous = service
+        .get_settings()
+        .await
+        .map_err(|e| format!("Failed to get settings: {}", e))?;
+
+    // Credentials go to the OS keychain, never the settings table
+    if let Some(token) = settings.gist_settings.access_token.take() {
+        keyring::set_gist_access_token(Some(&token))
+            .await
+            .map_err(|e| format!("Failed to store the gist access token: {}", e))?;
+    }
+
     service
         .update_settings(settings.clone())
         .await
         .map_err(|e| format!("Failed to update settings: {}", e))?;
 
+    // Start or stop the HTTP API server to match
+    api_server::apply_settings(&app, &settings.api_server).await;
+
+    // Start or stop watching typing for expansion triggers
+    expander::apply_settings(&app, &settings.expansion_settings).await;
+
+    // Delete recorded usage if tracking was just turned off and the user asked for that
+    if analytics::should_purge(&previous.privacy_settings, &settings.privacy_settings) {
+        let pool = get_pool(&app).map_err(|e| e.to_string())?;
+        analytics::discard_queued_usage(&app);
+        analytics::clear_all_analytics(&pool)
+            .await
+            .map_err(|e| format!("Failed to purge analytics: {}", e))?;
+    }
+
     // Emit settings change event for live updates
-    app.emit("settings-changed", &settings)
+    app.emit(SETTINGS_CHANGED_EVENT, &settings)
         .map_err(|e| format!("Failed to emit settings change event: {}", e))?;
 
+    // Follow a theme switch in the window chrome, unless a custom theme sets it
+    if previous.theme != settings.theme {
+        if settings.custom_theme.is_none() {
+            window::apply_window_theme(&app, settings.theme);
+        }
+        let system = appearance::system_appearance(&app);
+        appearance::notify(&app, &ResolvedTheme::new(settings.theme, system));
+    }
+
     Ok(())
 }
 
@@ -96,6 +140,112 @@ pub async fn set_storage_type(
     update_settings(app, settings, settings_state).await
 }
 
+/// Export settings to a file
+///
+/// Writes TOML when the path ends in `.toml` and JSON otherwise. Credentials
+/// such as the Gist access token are left out.
+///
+/// # Arguments
+///
+/// * `path` - Destination file
+///
+/// # Examples
+///
+/// ```typescript
+/// await invoke('export_settings', { path: '/home/me/snips-settings.toml' });
+/// ```
+#[tauri::command]
+pub async fn export_settings(
+    app: AppHandle,
+    path: String,
+    settings_state: State<'_, SettingsServiceState>,
+) -> Result<(), String> {
+    let current = get_settings(app, settings_state).await?;
+    let path = Path::new(&path);
+
+    let text = settings::serialize_settings(&current, SettingsFileFormat::from_path(path))
+        .map_err(|e| e.to_string())?;
+    std::fs::write(path, text).map_err(|e| format!("Failed to write settings file: {}", e))
+}
+
+/// Import settings from a file written by `export_settings`
+///
+/// The file is validated like any settings update before anything is
+/// applied. Credentials are not part of the file, so the current ones are
+/// kept. Global shortcuts are re-registered and the window theme applied
+/// so the imported settings take effect without a restart.
+///
+/// # Arguments
+///
+/// * `path` - TOML (`.toml`) or JSON settings file
+///
+/// # Returns
+///
+/// The settings now in effect
+///
+/// # Examples
+///
+/// ```typescript
+/// const settings = await invoke('import_settings', { path });
+/// ```
+#[tauri::command]
+pub async fn import_settings(
+    app: AppHandle,
+    path: String,
+    settings_state: State<'_, SettingsServiceState>,
+) -> Result<AppSettings, String> {
+    let path = Path::new(&path);
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read settings file: {}", e))?;
+    let mut imported = settings::parse_settings(&text, SettingsFileFormat::from_path(path))
+        .map_err(|e| e.to_string())?;
+
+    let current = get_settings(app.clone(), settings_state.clone()).await?;
+    imported.keep_credentials_from(&current);
+
+    update_settings(app.clone(), imported.clone(), settings_state).await?;
+
+    window::apply_window_theme(&app, imported.theme);
+    shortcuts::register_shortcuts_from_settings(&app, &imported.global_shortcuts)
+        .map_err(|e| format!("Settings imported, but shortcuts failed to register: {}", e))?;
+    // Registering from settings released the slot shortcuts too
+    sync_slot_shortcuts(&app).await?;
+
+    Ok(imported)
+}
+
+/// Get the settings sections that were reset to their defaults on load
+///
+/// A section is reset when its stored value does not match the expected
+/// schema, e.g. after a downgrade or a hand-edited settings file.
+///
+/// # Returns
+///
+/// Top-level setting keys such as `search_settings`, empty if all loaded
+#[tauri::command]
+pub async fn get_invalid_settings_sections(
+    app: AppHandle,
+    settings_state: State<'_, SettingsServiceState>,
+) -> Result<Vec<String>, String> {
+    // Loading the settings is what finds invalid sections
+    get_settings(app, settings_state.clone()).await?;
+    let service_guard = settings_state.0.lock().await;
+    Ok(match service_guard.as_ref() {
+        Some(service) => service.invalid_sections().await,
+        None => Vec::new(),
+    })
+}
+
+/// Get whether the local HTTP API server is running
+///
+/// # Returns
+///
+/// The listening port, or the error that kept the server from starting
+#[tauri::command]
+pub async fn get_api_server_status(app: AppHandle) -> Result<ApiServerStatus, String> {
+    Ok(api_server::status(&app).await)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
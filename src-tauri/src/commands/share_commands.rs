@@ -0,0 +1,168 @@
+use crate::commands::settings_commands::{get_settings, SettingsServiceState};
+use crate::commands::snippet_commands::get_snippet;
+use crate::commands::storage_commands::{
+    import_snippet_exports_with_mode, ImportMode, ImportReport, SnippetExport,
+};
+use crate::models::SnippetId;
+use crate::services::database::get_pool;
+use crate::services::library_stats;
+use crate::services::menubar;
+use crate::services::name_index;
+use crate::services::share;
+use crate::services::snippet_aliases;
+use crate::services::snippet_parameters;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, State};
+
+/// Share a snippet through an encrypted one-time link
+///
+/// The snippet is encrypted on this machine and only the ciphertext is
+/// uploaded to the paste endpoint from `share_settings`. The key is in the
+/// returned link's fragment, so whoever has the link can open it, once.
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `id` - The snippet to share
+///
+/// # Returns
+///
+/// The link to send to a teammate
+///
+/// # Errors
+///
+/// Returns an error string if the snippet does not exist or the upload fails
+///
+/// # Examples
+///
+/// ```typescript
+/// const url = await invoke('share_snippet', { id: 42 });
+/// ```
+#[tauri::command]
+pub async fn share_snippet(
+    app: AppHandle,
+    id: SnippetId,
+    settings_state: State<'_, SettingsServiceState>,
+) -> Result<String, String> {
+    let settings = get_settings(app.clone(), settings_state).await?;
+    let pool = get_pool(&app)?;
+    let aliases = snippet_aliases::get_aliases(&pool, id.0).await?;
+    let parameters = snippet_parameters::get_parameters(&pool, id.0).await?;
+    let snippet = get_snippet(app, id).await?;
+
+    let export = SnippetExport {
+        name: snippet.name,
+        content: snippet.content,
+        description: snippet.description,
+        tags: snippet.tags.unwrap_or_default(),
+        created_at: snippet.created_at,
+        updated_at: snippet.updated_at,
+        kind: snippet.kind,
+        aliases,
+        parameters,
+        archived: snippet.archived,
+        expires_at: snippet.expires_at,
+    };
+    Ok(share::share(&settings.share_settings.endpoint, &export).await?)
+}
+
+/// Import a snippet from a share link
+///
+/// The paste is deleted after it has been downloaded, so the link cannot be
+/// opened again. A local snippet with the same name is never overwritten
+/// unless `mode` asks for it: by default the share is imported under a free
+/// name such as `deploy (2)`.
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `url` - The share link
+/// * `mode` - What to do if the name is taken (default: `rename`)
+///
+/// # Returns
+///
+/// What was imported. `conflicted` lists a taken name and how it was
+/// resolved, so the UI can offer to replace the local snippet.
+///
+/// # Errors
+///
+/// Returns an error string if the link is malformed, the share was already
+/// opened, or the key does not decrypt it
+///
+/// # Examples
+///
+/// ```typescript
+/// const report = await invoke('import_shared_snippet', { url });
+/// if (report.conflicted.length) askWhetherToReplace(report.conflicted[0]);
+/// ```
+#[tauri::command]
+pub async fn import_shared_snippet(
+    app: AppHandle,
+    url: String,
+    mode: Option<ImportMode>,
+) -> Result<ImportReport, String> {
+    let snippet = share::receive(&url).await?;
+
+    let pool = get_pool(&app)?;
+    let report = import_share(&pool, snippet, mode).await?;
+    library_stats::invalidate(&app).await;
+    name_index::invalidate(&app).await;
+    menubar::notify_snippets_changed(&app);
+    Ok(report)
+}
+
+/// Imports a received share, renaming it if its name is taken unless `mode`
+/// says otherwise
+async fn import_share(
+    pool: &SqlitePool,
+    snippet: SnippetExport,
+    mode: Option<ImportMode>,
+) -> Result<ImportReport, String> {
+    import_snippet_exports_with_mode(pool, vec![snippet], mode.unwrap_or(ImportMode::Rename)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::storage_commands::ConflictResolution;
+    use crate::services::database::run_migrations;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_share_with_taken_name_leaves_local_snippet() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+        sqlx::query(
+            "INSERT INTO snippets (name, content, created_at, updated_at)
+             VALUES ('deploy', 'make deploy', 0, 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let shared = SnippetExport {
+            name: "deploy".to_string(),
+            content: "rm -rf build".to_string(),
+            ..SnippetExport::default()
+        };
+        let report = import_share(&pool, shared.clone(), None).await.unwrap();
+        assert_eq!(report.created, ["deploy (2)"]);
+        assert_eq!(report.conflicted[0].resolution, ConflictResolution::Renamed);
+
+        let report = import_share(&pool, shared, Some(ImportMode::Skip))
+            .await
+            .unwrap();
+        assert_eq!(report.skipped, ["deploy"]);
+
+        let local: String =
+            sqlx::query_scalar("SELECT content FROM snippets WHERE name = 'deploy'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(local, "make deploy");
+    }
+}
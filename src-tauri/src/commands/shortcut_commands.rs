@@ -5,9 +5,16 @@
 /// - Register custom shortcuts
 /// - Unregister shortcuts
 /// - Handle shortcut conflicts
-use crate::services::shortcuts;
+/// - Assign snippets to numbered slots
+use crate::models::SnippetId;
+use crate::services::database::get_pool;
+use crate::services::notifications::{self, NotificationKind};
+use crate::services::portal_shortcuts::{self, PortalShortcutState};
+use crate::services::settings::SettingsService;
+use crate::services::shortcuts::{self, ShortcutAvailability};
+use crate::services::snippet_slots::{self, SlotAssignment};
 use serde::{Deserialize, Serialize};
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
 
 /// Information about a registered shortcut.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +53,11 @@ pub fn get_default_shortcuts() -> Vec<ShortcutInfo> {
             description: "Open quick add dialog".to_string(),
             is_default: true,
         },
+        ShortcutInfo {
+            shortcut: shortcuts::SHORTCUT_QUICK_PASTE.to_string(),
+            description: "Copy the last used snippet".to_string(),
+            is_default: true,
+        },
     ]
 }
 
@@ -55,7 +67,7 @@ pub fn get_default_shortcuts() -> Vec<ShortcutInfo> {
 ///
 /// * `app` - The Tauri application handle (automatically injected)
 /// * `shortcut` - The shortcut string to register (e.g., "Cmd+Shift+K")
-/// * `action` - The action to perform (currently only "search" or "quick-add" supported)
+/// * `action` - The action to perform ("search", "quick-add" or "quick-paste")
 ///
 /// # Returns
 ///
@@ -99,6 +111,10 @@ pub fn register_custom_shortcut(
             }
         })
         .map_err(|e| e.to_string()),
+        "quick-paste" => {
+            shortcuts::register_custom_shortcut(&app, &shortcut, shortcuts::copy_last_used_snippet)
+                .map_err(|e| e.to_string())
+        }
         _ => Err(format!("Unknown action: {}", action)),
     }
 }
@@ -151,6 +167,88 @@ pub fn is_shortcut_valid(shortcut: String) -> bool {
     shortcut.parse::<Shortcut>().is_ok()
 }
 
+/// Result of checking a shortcut before assigning it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutCheck {
+    /// The shortcut string that was checked
+    pub shortcut: String,
+    /// Whether the shortcut parses
+    pub valid: bool,
+    /// Canonical modifier names ("CommandOrControl", "Super", "Control", "Alt", "Shift")
+    pub modifiers: Vec<String>,
+    /// The non-modifier key (e.g. "S")
+    pub key: Option<String>,
+    /// Display string for this platform (e.g. "⇧⌘S" or "Ctrl+Shift+S")
+    pub display: Option<String>,
+    /// Whether the shortcut can be registered (or is already registered by Snips)
+    pub available: bool,
+    /// Whether Snips itself currently holds the shortcut
+    pub registered_by_snips: bool,
+    /// Why the shortcut is unavailable, if it is
+    pub conflict: Option<String>,
+}
+
+/// Checks a shortcut for validity and conflicts with other applications.
+///
+/// Unlike `is_shortcut_valid`, this attempts a trial registration (released
+/// immediately) to find out whether another application already holds the
+/// accelerator, and returns the parts the settings UI needs for a
+/// "press keys to record" flow.
+///
+/// # Arguments
+///
+/// * `app` - The Tauri application handle (automatically injected)
+/// * `shortcut` - The shortcut string to check
+///
+/// # Examples
+///
+/// ```typescript
+/// const check = await invoke('check_shortcut', { shortcut: 'CmdOrCtrl+Shift+K' });
+/// if (!check.available) console.warn(check.conflict);
+/// ```
+#[tauri::command]
+pub fn check_shortcut(app: AppHandle, shortcut: String) -> ShortcutCheck {
+    let Some(parts) = shortcuts::describe_shortcut(&shortcut, cfg!(target_os = "macos")) else {
+        return ShortcutCheck {
+            shortcut,
+            valid: false,
+            modifiers: Vec::new(),
+            key: None,
+            display: None,
+            available: false,
+            registered_by_snips: false,
+            conflict: Some("Invalid shortcut format".to_string()),
+        };
+    };
+
+    let (available, registered_by_snips, conflict) =
+        match shortcuts::probe_availability(&app, &shortcut) {
+            Ok(ShortcutAvailability::Available) => (true, false, None),
+            Ok(ShortcutAvailability::RegisteredBySnips) => (true, true, None),
+            Ok(ShortcutAvailability::Taken(reason)) => (
+                false,
+                false,
+                Some(format!(
+                    "Already in use by another application ({})",
+                    reason
+                )),
+            ),
+            Err(e) => (false, false, Some(e.to_string())),
+        };
+
+    ShortcutCheck {
+        shortcut,
+        valid: true,
+        modifiers: parts.modifiers.into_iter().map(str::to_string).collect(),
+        key: Some(parts.key),
+        display: Some(parts.display),
+        available,
+        registered_by_snips,
+        conflict,
+    }
+}
+
 /// Re-registers all default shortcuts.
 ///
 /// This is useful if shortcuts were unregistered or if there was a conflict
@@ -172,12 +270,10 @@ pub fn is_shortcut_valid(shortcut: String) -> bool {
 #[tauri::command]
 pub async fn reregister_default_shortcuts(app: AppHandle) -> Result<(), String> {
     use crate::services::database::DbPool;
-    use crate::services::settings::SettingsService;
-    use tauri::Manager;
 
     // Get database pool from app state
     let db_pool = app.state::<DbPool>();
-    let settings_service = SettingsService::new(db_pool.0.clone());
+    let settings_service = SettingsService::new(db_pool.get());
 
     // Load current settings
     let settings = settings_service
@@ -187,7 +283,94 @@ pub async fn reregister_default_shortcuts(app: AppHandle) -> Result<(), String>
 
     // Register shortcuts from settings
     shortcuts::register_shortcuts_from_settings(&app, &settings.global_shortcuts)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    // Settings registration released everything, including slot shortcuts
+    sync_slot_shortcuts(&app).await
+}
+
+/// Registers shortcuts for the currently assigned slots
+///
+/// Called at startup and whenever slot assignments or shortcuts change.
+pub async fn sync_slot_shortcuts(app: &AppHandle) -> Result<(), String> {
+    let pool = get_pool(app)?;
+    let slots: Vec<u8> = snippet_slots::get_assignments(&pool)
+        .await?
+        .into_iter()
+        .map(|assignment| assignment.slot)
+        .collect();
+    let registered = shortcuts::register_slot_shortcuts(app, &slots).map_err(|e| e.to_string());
+
+    // Key grabs do not work on Wayland; bind every shortcut through the desktop portal too
+    if portal_shortcuts::is_wayland_session() {
+        if let Some(state) = app.try_state::<PortalShortcutState>() {
+            let settings = SettingsService::new(pool).get_settings().await?;
+            if let Err(e) =
+                portal_shortcuts::rebind(app, &state, &settings.global_shortcuts, &slots).await
+            {
+                eprintln!("Failed to bind shortcuts through the desktop portal: {}", e);
+                notifications::notify(
+                    app,
+                    NotificationKind::ShortcutRegistrationFailed,
+                    format!("Global shortcuts could not be bound: {}", e),
+                );
+            }
+        }
+    }
+
+    registered
+}
+
+/// Assigns a snippet to a numbered slot, or clears the slot.
+///
+/// Slot `n` is pasted with Cmd/Ctrl+Shift+`n`. A snippet occupies at most one
+/// slot, so assigning it elsewhere moves it.
+///
+/// # Arguments
+///
+/// * `app` - The Tauri application handle (automatically injected)
+/// * `slot` - Slot number, 1 through 9
+/// * `snippet_id` - Snippet to assign, or `null` to clear the slot
+///
+/// # Returns
+///
+/// The slot assignments after the change.
+///
+/// # Errors
+///
+/// Returns an error if the slot is out of range, the snippet does not exist,
+/// or the slot shortcut cannot be registered (e.g. another application owns it).
+///
+/// # Examples
+///
+/// ```typescript
+/// await invoke('assign_snippet_slot', { slot: 1, snippetId: 42 });
+/// await invoke('assign_snippet_slot', { slot: 1, snippetId: null });
+/// ```
+#[tauri::command]
+pub async fn assign_snippet_slot(
+    app: AppHandle,
+    slot: u8,
+    snippet_id: Option<SnippetId>,
+) -> Result<Vec<SlotAssignment>, String> {
+    let pool = get_pool(&app)?;
+    snippet_slots::assign_slot(&pool, slot, snippet_id.map(i64::from)).await?;
+    sync_slot_shortcuts(&app).await?;
+    Ok(snippet_slots::get_assignments(&pool).await?)
+}
+
+/// Returns the snippets assigned to numbered slots, in slot order.
+///
+/// # Examples
+///
+/// ```typescript
+/// const slots = await invoke('get_slot_assignments');
+/// console.log(slots); // [{ slot: 1, snippet_id: 42, snippet_name: "..." }]
+/// ```
+#[tauri::command]
+pub async fn get_slot_assignments(app: AppHandle) -> Result<Vec<SlotAssignment>, String> {
+    let pool = get_pool(&app)?;
+    Ok(snippet_slots::get_assignments(&pool).await?)
 }
 
 #[cfg(test)]
@@ -197,10 +380,11 @@ mod tests {
     #[test]
     fn test_get_default_shortcuts() {
         let shortcuts = get_default_shortcuts();
-        assert_eq!(shortcuts.len(), 2);
+        assert_eq!(shortcuts.len(), 3);
 
         assert!(shortcuts.iter().any(|s| s.shortcut == "CmdOrCtrl+Shift+S"));
         assert!(shortcuts.iter().any(|s| s.shortcut == "CmdOrCtrl+Shift+A"));
+        assert!(shortcuts.iter().any(|s| s.shortcut == "CmdOrCtrl+Shift+L"));
 
         for shortcut in shortcuts {
             assert!(shortcut.is_default);
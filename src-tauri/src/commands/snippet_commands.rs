@@ -1,9 +1,26 @@
-use crate::models::{CreateSnippetInput, Snippet, SnippetId, UpdateSnippetInput};
+use crate::commands::storage_commands;
+use crate::models::settings::SortPreference;
+use crate::models::{
+    CreateSnippetInput, CreateSnippetOutcome, NameConflictMode, Snippet, SnippetId, SnippetKind,
+    UpdateSnippetInput,
+};
+use crate::services::archive::{self, StaleSnippet};
 use crate::services::database::get_pool;
+use crate::services::expiration::{self, ExpiringSnippet};
+use crate::services::language;
+use crate::services::library_stats;
+use crate::services::menubar;
+use crate::services::name_index;
+use crate::services::settings::SettingsService;
+use crate::services::snippet_aliases;
+use crate::services::snippet_order;
+use crate::services::snippet_parameters::{self, SnippetParameter};
 use crate::services::tags;
+use crate::services::team_feeds;
 use crate::utils::error::AppError;
 use crate::utils::time::current_timestamp;
-use sqlx::Row;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
 use tauri::AppHandle;
 
 /// Create a new snippet with optional tags
@@ -19,17 +36,21 @@ pub async fn create_snippet(app: AppHandle, input: CreateSnippetInput) -> Result
 
     let pool = get_pool(&app)?;
     let now = current_timestamp();
+    let language = language::detect_language(input.name.trim(), input.content.trim(), input.kind);
 
     // Insert snippet
     let result = sqlx::query(
-        "INSERT INTO snippets (name, content, description, created_at, updated_at)
-         VALUES (?, ?, ?, ?, ?)",
+        "INSERT INTO snippets
+            (name, content, description, created_at, updated_at, kind, language, position)
+         VALUES (?, ?, ?, ?, ?, ?, ?, (SELECT COALESCE(MIN(position), 0) - 1 FROM snippets))",
     )
     .bind(input.name.trim())
     .bind(input.content.trim())
     .bind(input.description.as_deref().map(|s| s.trim()))
     .bind(now)
     .bind(now)
+    .bind(input.kind.as_str())
+    .bind(language)
     .execute(&pool)
     .await
     .map_err(|e| {
@@ -44,79 +65,238 @@ pub async fn create_snippet(app: AppHandle, input: CreateSnippetInput) -> Result
 
     // Associate tags
     if !input.tags.is_empty() {
-        tags::associate_tags(&app, snippet_id, &input.tags).await?;
+        tags::associate_tags(&pool, snippet_id, &input.tags).await?;
     }
+    library_stats::refresh_snippet(&app, snippet_id).await;
+    name_index::refresh_snippet(&app, snippet_id).await;
+    menubar::notify_snippets_changed(&app);
 
     // Fetch and return the created snippet with tags
     get_snippet(app, SnippetId(snippet_id)).await
 }
 
-/// Get a single snippet by ID
+/// Create a new snippet, deciding what to do if its name is already taken
+///
+/// Unlike `create_snippet`, a taken name is not an error: the snippet is
+/// created under a free name (`rename`), its content is appended to the
+/// existing snippet (`append`), or nothing is written and the existing
+/// snippet is returned with a suggested free name (`ask`, the default).
+///
+/// # Examples
+///
+/// ```javascript
+/// const outcome = await invoke('create_snippet_with_resolution', { input, onConflict: 'ask' });
+/// if (outcome.status === 'name_taken') showChoices(outcome.existing, outcome.suggested_name);
+/// ```
 #[tauri::command]
-pub async fn get_snippet(app: AppHandle, id: SnippetId) -> Result<Snippet, String> {
+pub async fn create_snippet_with_resolution(
+    app: AppHandle,
+    input: CreateSnippetInput,
+    on_conflict: Option<NameConflictMode>,
+) -> Result<CreateSnippetOutcome, String> {
     let pool = get_pool(&app)?;
 
-    let result = sqlx::query(
-        "SELECT id, name, content, description, created_at, updated_at
-         FROM snippets WHERE id = ?",
-    )
-    .bind(id.0)
-    .fetch_optional(&pool)
-    .await
-    .map_err(|e| AppError::from(e).to_string())?;
-
-    match result {
-        Some(row) => {
-            let snippet_id: i64 = row.get(0);
-            let tags = tags::get_snippet_tags(&app, snippet_id).await?;
-
-            Ok(Snippet {
-                id: SnippetId(snippet_id),
-                name: row.get(1),
-                content: row.get(2),
-                description: row.get(3),
-                created_at: row.get(4),
-                updated_at: row.get(5),
-                tags: Some(tags),
+    let existing_id: Option<i64> = sqlx::query_scalar("SELECT id FROM snippets WHERE name = ?")
+        .bind(input.name.trim())
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to check existing snippet: {}", e)))?;
+    let Some(existing_id) = existing_id else {
+        let snippet = create_snippet(app, input).await?;
+        return Ok(CreateSnippetOutcome::Created { snippet });
+    };
+
+    match on_conflict.unwrap_or_default() {
+        NameConflictMode::Ask => {
+            let suggested_name = suggest_free_name(&pool, input.name.trim()).await?;
+            let existing = get_snippet(app, SnippetId(existing_id)).await?;
+            Ok(CreateSnippetOutcome::NameTaken {
+                existing,
+                suggested_name,
             })
         }
-        None => Err(AppError::NotFound(format!("Snippet with id {} not found", id.0)).into()),
+        NameConflictMode::Rename => {
+            let name = suggest_free_name(&pool, input.name.trim()).await?;
+            let snippet = create_snippet(app, CreateSnippetInput { name, ..input }).await?;
+            Ok(CreateSnippetOutcome::Renamed { snippet })
+        }
+        NameConflictMode::Append => {
+            let snippet = append_content(
+                &app,
+                SnippetId(existing_id),
+                &input.content,
+                APPEND_SEPARATOR,
+                &input.tags,
+            )
+            .await?;
+            Ok(CreateSnippetOutcome::Appended { snippet })
+        }
     }
 }
 
-/// Get all snippets with their tags
+/// Add text to the end of an existing snippet
+///
+/// For building up running notes from captured selections. The text is
+/// trimmed and put after `separator`, which defaults to a blank line.
+///
+/// # Examples
+///
+/// ```javascript
+/// const notes = await invoke('append_to_snippet', { id: 7, text: selection, separator: '\n- ' });
+/// ```
 #[tauri::command]
-pub async fn get_all_snippets(app: AppHandle) -> Result<Vec<Snippet>, String> {
-    let pool = get_pool(&app)?;
+pub async fn append_to_snippet(
+    app: AppHandle,
+    id: SnippetId,
+    text: String,
+    separator: Option<String>,
+) -> Result<Snippet, String> {
+    let separator = separator.as_deref().unwrap_or(APPEND_SEPARATOR);
+    append_content(&app, id, &text, separator, &[]).await
+}
 
-    let results = sqlx::query(
-        "SELECT id, name, content, description, created_at, updated_at
-         FROM snippets ORDER BY created_at DESC",
-    )
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| AppError::from(e).to_string())?;
+/// Separator put between a snippet's content and appended text by default
+const APPEND_SEPARATOR: &str = "\n\n";
 
-    let mut snippets = Vec::new();
-    for row in results {
-        let snippet_id: i64 = row.get(0);
-        let tags = tags::get_snippet_tags(&app, snippet_id).await?;
+/// Finds a free name for a snippet whose name is taken
+async fn suggest_free_name(pool: &SqlitePool, name: &str) -> Result<String, String> {
+    let mut conn = pool
+        .acquire()
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to acquire connection: {}", e)))?;
+    storage_commands::free_snippet_name(&mut conn, name).await
+}
+
+/// Adds `text` to the end of a snippet's content, after `separator`
+///
+/// `tags` are added to the snippet's own, and its language is detected again
+/// from the longer content. Team feed snippets are read-only.
+async fn append_content(
+    app: &AppHandle,
+    id: SnippetId,
+    text: &str,
+    separator: &str,
+    tags: &[String],
+) -> Result<Snippet, String> {
+    let pool = get_pool(app)?;
+    append_to_stored_content(&pool, id, text, separator, tags).await?;
+
+    library_stats::refresh_snippet(app, id.0).await;
+    name_index::refresh_snippet(app, id.0).await;
+    menubar::notify_snippets_changed(app);
+
+    get_snippet(app.clone(), id).await
+}
 
-        snippets.push(Snippet {
-            id: SnippetId(snippet_id),
-            name: row.get(1),
-            content: row.get(2),
-            description: row.get(3),
-            created_at: row.get(4),
-            updated_at: row.get(5),
-            tags: Some(tags),
-        });
+/// Database half of `append_content`
+async fn append_to_stored_content(
+    pool: &SqlitePool,
+    id: SnippetId,
+    text: &str,
+    separator: &str,
+    tags: &[String],
+) -> Result<(), AppError> {
+    if text.trim().is_empty() {
+        return Err(AppError::InvalidInput(
+            "Appended text cannot be empty".to_string(),
+        ));
+    }
+
+    if team_feeds::get_provenance(pool, id.0).await?.is_read_only() {
+        return Err(AppError::Forbidden(
+            "Team feed snippets are read-only; create a new snippet instead".to_string(),
+        ));
     }
 
-    Ok(snippets)
+    let snippet = snippet_order::load_snippets(pool, &[id.0])
+        .await?
+        .pop()
+        .ok_or_else(|| AppError::NotFound(format!("Snippet with id {} not found", id.0)))?;
+    let content = format!("{}{}{}", snippet.content, separator, text.trim());
+    let language = language::detect_language(&snippet.name, &content, snippet.kind);
+
+    sqlx::query("UPDATE snippets SET content = ?, updated_at = ?, language = ? WHERE id = ?")
+        .bind(&content)
+        .bind(current_timestamp())
+        .bind(language)
+        .bind(id.0)
+        .execute(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to append to snippet: {}", e)))?;
+
+    if !tags.is_empty() {
+        tags::associate_tags(pool, id.0, tags).await?;
+    }
+    Ok(())
+}
+
+/// Get a single snippet by ID
+#[tauri::command]
+pub async fn get_snippet(app: AppHandle, id: SnippetId) -> Result<Snippet, String> {
+    let pool = get_pool(&app)?;
+
+    snippet_order::load_snippets(&pool, &[id.0])
+        .await?
+        .pop()
+        .ok_or_else(|| AppError::NotFound(format!("Snippet with id {} not found", id.0)).into())
+}
+
+/// Get snippets with their tags, one page at a time
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `limit` - Optional maximum number of snippets; all of them when omitted
+/// * `offset` - Optional number of snippets to skip (default: 0)
+/// * `sort_by` - Optional order (`name`, `created`, `updated`, `usage` or
+///   `manual`); defaults to the `sort_preference` setting
+/// * `include_archived` - Optional; also list archived snippets (default: false)
+///
+/// # Examples
+///
+/// ```javascript
+/// const firstPage = await invoke('get_all_snippets', { limit: 100, sortBy: 'usage' });
+/// ```
+#[tauri::command]
+pub async fn get_all_snippets(
+    app: AppHandle,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    sort_by: Option<SortPreference>,
+    include_archived: Option<bool>,
+) -> Result<Vec<Snippet>, String> {
+    if limit.is_some_and(|limit| limit < 0) || offset.is_some_and(|offset| offset < 0) {
+        return Err(
+            AppError::InvalidInput("limit and offset must not be negative".to_string()).into(),
+        );
+    }
+    let pool = get_pool(&app)?;
+
+    let sort_preference = match sort_by {
+        Some(sort_by) => sort_by,
+        // A broken settings row should not hide the library; fall back to newest first
+        None => SettingsService::new(pool.clone())
+            .get_settings()
+            .await
+            .map(|settings| settings.sort_preference)
+            .unwrap_or_default(),
+    };
+
+    snippet_order::list_snippets(
+        &pool,
+        sort_preference,
+        include_archived.unwrap_or(false),
+        limit,
+        offset.unwrap_or(0),
+    )
+    .await
+    .map_err(|e| e.to_string())
 }
 
 /// Update an existing snippet
+///
+/// Editing a snippet synced from a team feed leaves it untouched and returns
+/// a new local fork instead.
 #[tauri::command]
 pub async fn update_snippet(
     app: AppHandle,
@@ -133,28 +313,27 @@ pub async fn update_snippet(
 
     let pool = get_pool(&app)?;
 
-    // Check if snippet exists
-    let exists = sqlx::query("SELECT id FROM snippets WHERE id = ?")
-        .bind(id.0)
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| AppError::from(e).to_string())?;
-
-    if exists.is_none() {
-        return Err(AppError::NotFound(format!("Snippet with id {} not found", id.0)).into());
+    // Snippets synced from a team feed are read-only; editing one forks it
+    let provenance = team_feeds::get_provenance(&pool, id.0).await?;
+    if provenance.is_read_only() {
+        return fork_feed_snippet(app, id, input, provenance).await;
     }
 
     let now = current_timestamp();
+    let language = language::detect_language(input.name.trim(), input.content.trim(), input.kind);
 
     // Update snippet
     sqlx::query(
-        "UPDATE snippets SET name = ?, content = ?, description = ?, updated_at = ?
+        "UPDATE snippets
+         SET name = ?, content = ?, description = ?, updated_at = ?, kind = ?, language = ?
          WHERE id = ?",
     )
     .bind(input.name.trim())
     .bind(input.content.trim())
     .bind(input.description.as_deref().map(|s| s.trim()))
     .bind(now)
+    .bind(input.kind.as_str())
+    .bind(language)
     .bind(id.0)
     .execute(&pool)
     .await
@@ -169,39 +348,488 @@ pub async fn update_snippet(
     // Update tags: remove old associations and create new ones
     tags::remove_snippet_tags(&app, id.0).await?;
     if !input.tags.is_empty() {
-        tags::associate_tags(&app, id.0, &input.tags).await?;
+        tags::associate_tags(&pool, id.0, &input.tags).await?;
     }
+    library_stats::refresh_snippet(&app, id.0).await;
+    name_index::refresh_snippet(&app, id.0).await;
+    menubar::notify_snippets_changed(&app);
 
     // Fetch and return the updated snippet
     get_snippet(app, id).await
 }
 
+/// Save an edit of a team feed snippet as a new local snippet
+///
+/// The fork keeps a link to the feed snippet. Unless it was renamed it takes
+/// the snippet's name within the feed, without the feed prefix, and it drops
+/// the feed's tag so it is not mistaken for a synced snippet.
+async fn fork_feed_snippet(
+    app: AppHandle,
+    id: SnippetId,
+    input: UpdateSnippetInput,
+    provenance: team_feeds::SnippetProvenance,
+) -> Result<Snippet, String> {
+    let pool = get_pool(&app)?;
+    let feed_name = provenance.feed_name.unwrap_or_default();
+    let feed_key = provenance.feed_key.unwrap_or_default();
+
+    let name = match input.name.trim() {
+        name if name == team_feeds::feed_snippet_name(&feed_name, &feed_key) => feed_key.as_str(),
+        name => name,
+    };
+    let feed_tag = team_feeds::feed_tag(&feed_name);
+    let fork_tags: Vec<String> = input
+        .tags
+        .iter()
+        .filter(|tag| tags::normalize_tag_path(tag) != feed_tag)
+        .cloned()
+        .collect();
+
+    let now = current_timestamp();
+    let language = language::detect_language(name, input.content.trim(), input.kind);
+    let result = sqlx::query(
+        "INSERT INTO snippets
+            (name, content, description, created_at, updated_at, kind, language, position,
+             forked_from)
+         VALUES (?, ?, ?, ?, ?, ?, ?, (SELECT COALESCE(MIN(position), 0) - 1 FROM snippets), ?)",
+    )
+    .bind(name)
+    .bind(input.content.trim())
+    .bind(input.description.as_deref().map(|s| s.trim()))
+    .bind(now)
+    .bind(now)
+    .bind(input.kind.as_str())
+    .bind(language)
+    .bind(id.0)
+    .execute(&pool)
+    .await
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint failed") {
+            AppError::Duplicate(format!(
+                "Snippet with name '{}' already exists; rename the copy of the team snippet",
+                name
+            ))
+        } else {
+            AppError::Database(format!("Failed to fork snippet: {}", e))
+        }
+    })?;
+
+    let fork_id = result.last_insert_rowid();
+    if !fork_tags.is_empty() {
+        tags::associate_tags(&pool, fork_id, &fork_tags).await?;
+    }
+    library_stats::refresh_snippet(&app, fork_id).await;
+    name_index::refresh_snippet(&app, fork_id).await;
+    menubar::notify_snippets_changed(&app);
+
+    get_snippet(app, SnippetId(fork_id)).await
+}
+
 /// Delete a snippet by ID
+///
+/// Snippets synced from a team feed cannot be deleted; they go away when the
+/// feed drops them or is unsubscribed.
 #[tauri::command]
 pub async fn delete_snippet(app: AppHandle, id: SnippetId) -> Result<(), String> {
     let pool = get_pool(&app)?;
 
-    // Check if snippet exists
-    let exists = sqlx::query("SELECT id FROM snippets WHERE id = ?")
+    if team_feeds::get_provenance(&pool, id.0)
+        .await?
+        .is_read_only()
+    {
+        return Err(AppError::Forbidden(
+            "Team feed snippets are read-only; unsubscribe from the feed instead".to_string(),
+        )
+        .into());
+    }
+
+    // Delete snippet (cascades to snippet_tags and analytics due to foreign keys)
+    sqlx::query("DELETE FROM snippets WHERE id = ?")
         .bind(id.0)
-        .fetch_optional(&pool)
+        .execute(&pool)
         .await
         .map_err(|e| AppError::from(e).to_string())?;
+    library_stats::refresh_snippet(&app, id.0).await;
+    name_index::refresh_snippet(&app, id.0).await;
+    menubar::notify_snippets_changed(&app);
 
-    if exists.is_none() {
-        return Err(AppError::NotFound(format!("Snippet with id {} not found", id.0)).into());
-    }
+    Ok(())
+}
 
-    // Delete snippet (cascades to snippet_tags and analytics due to foreign keys)
-    sqlx::query("DELETE FROM snippets WHERE id = ?")
+/// Pin or unpin a snippet
+///
+/// Pinning does not change `updated_at`; it is a display preference rather
+/// than an edit.
+///
+/// # Returns
+///
+/// The snippet with its new `pinned` state
+#[tauri::command]
+pub async fn toggle_pin_snippet(app: AppHandle, id: SnippetId) -> Result<Snippet, String> {
+    let pool = get_pool(&app)?;
+
+    let result = sqlx::query("UPDATE snippets SET pinned = NOT pinned WHERE id = ?")
         .bind(id.0)
         .execute(&pool)
         .await
         .map_err(|e| AppError::from(e).to_string())?;
 
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Snippet with id {} not found", id.0)).into());
+    }
+
+    get_snippet(app, id).await
+}
+
+/// List snippets nobody has used for a while, as candidates for archiving
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `threshold_days` - Optional number of days without a usage (default: 90)
+///
+/// # Returns
+///
+/// Unused snippets, longest unused first. Pinned and archived snippets are
+/// not listed.
+///
+/// # Examples
+///
+/// ```typescript
+/// const stale = await invoke('get_stale_snippets', { thresholdDays: 180 });
+/// ```
+#[tauri::command]
+pub async fn get_stale_snippets(
+    app: AppHandle,
+    threshold_days: Option<u32>,
+) -> Result<Vec<StaleSnippet>, String> {
+    let pool = get_pool(&app)?;
+    let threshold_days = threshold_days.unwrap_or(archive::DEFAULT_STALE_THRESHOLD_DAYS);
+    Ok(archive::get_stale_snippets(&pool, current_timestamp(), threshold_days).await?)
+}
+
+/// Archive snippets, leaving them out of search
+///
+/// # Returns
+///
+/// Number of snippets archived
+///
+/// # Examples
+///
+/// ```typescript
+/// const stale = await invoke('get_stale_snippets', {});
+/// await invoke('archive_snippets', { ids: stale.map((s) => s.snippet_id) });
+/// ```
+#[tauri::command]
+pub async fn archive_snippets(app: AppHandle, ids: Vec<SnippetId>) -> Result<u64, String> {
+    let pool = get_pool(&app)?;
+    let ids: Vec<i64> = ids.into_iter().map(i64::from).collect();
+    let archived = archive::archive_snippets(&pool, &ids).await?;
+    menubar::notify_snippets_changed(&app);
+    Ok(archived)
+}
+
+/// Archive a snippet, leaving it out of search and the snippet list
+///
+/// Archiving does not change `updated_at`. Archived snippets are found with
+/// an `is:archived` search and listed with `include_archived`.
+///
+/// # Returns
+///
+/// The archived snippet
+#[tauri::command]
+pub async fn archive_snippet(app: AppHandle, id: SnippetId) -> Result<Snippet, String> {
+    let pool = get_pool(&app)?;
+    archive::set_archived(&pool, id.0, true).await?;
+    menubar::notify_snippets_changed(&app);
+    get_snippet(app, id).await
+}
+
+/// Restore an archived snippet to search and the snippet list
+///
+/// # Returns
+///
+/// The restored snippet
+#[tauri::command]
+pub async fn unarchive_snippet(app: AppHandle, id: SnippetId) -> Result<Snippet, String> {
+    let pool = get_pool(&app)?;
+    archive::set_archived(&pool, id.0, false).await?;
+    menubar::notify_snippets_changed(&app);
+    get_snippet(app, id).await
+}
+
+/// Set or clear a snippet's expiration date
+///
+/// Expired snippets are flagged in search results and, with `auto_archive`
+/// on in the expiration settings, archived by a background task.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `id` - Snippet ID
+/// * `expires_at` - Unix timestamp, or `None` to never expire
+///
+/// # Returns
+///
+/// The snippet with its new `expires_at`
+///
+/// # Examples
+///
+/// ```typescript
+/// const inOneDay = Math.floor(Date.now() / 1000) + 24 * 3600;
+/// await invoke('set_snippet_expiration', { id: 42, expiresAt: inOneDay });
+/// ```
+#[tauri::command]
+pub async fn set_snippet_expiration(
+    app: AppHandle,
+    id: SnippetId,
+    expires_at: Option<i64>,
+) -> Result<Snippet, String> {
+    let pool = get_pool(&app)?;
+    expiration::set_expiration(&pool, id.0, expires_at).await?;
+    get_snippet(app, id).await
+}
+
+/// List snippets that have expired or expire soon, for a reminder
+/// notification
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `within_hours` - Optional number of hours to look ahead (default: 24)
+///
+/// # Returns
+///
+/// Expired and soon expiring snippets that are not archived, soonest first
+///
+/// # Examples
+///
+/// ```typescript
+/// const expiring = await invoke('get_expiring_snippets', { withinHours: 48 });
+/// ```
+#[tauri::command]
+pub async fn get_expiring_snippets(
+    app: AppHandle,
+    within_hours: Option<u32>,
+) -> Result<Vec<ExpiringSnippet>, String> {
+    let pool = get_pool(&app)?;
+    let now = current_timestamp();
+    let within_hours = within_hours.unwrap_or(expiration::DEFAULT_REMINDER_HOURS);
+    let until = now + i64::from(within_hours) * 3600;
+    Ok(expiration::get_expiring_snippets(&pool, now, until).await?)
+}
+
+/// Set the manual snippet order
+///
+/// Used when `sort_preference` is `manual`. Snippets not listed keep their
+/// relative order after the listed ones.
+///
+/// # Examples
+///
+/// ```typescript
+/// await invoke('reorder_snippets', { ids: [3, 1, 2] });
+/// ```
+#[tauri::command]
+pub async fn reorder_snippets(app: AppHandle, ids: Vec<SnippetId>) -> Result<(), String> {
+    let pool = get_pool(&app)?;
+    let ids: Vec<i64> = ids.into_iter().map(i64::from).collect();
+    snippet_order::reorder(&pool, &ids).await?;
     Ok(())
 }
 
+/// Guess the language of a snippet without saving it
+///
+/// Uses the same heuristics as create and update (name extension, shebang,
+/// keywords), so editors can show the language while the user types.
+///
+/// # Returns
+///
+/// A language identifier such as `rust` or `shellscript`, or `None` for plain text
+///
+/// # Examples
+///
+/// ```typescript
+/// const language = await invoke('detect_snippet_language', {
+///   name: 'deploy.sh',
+///   content: 'make deploy',
+/// });
+/// ```
+#[tauri::command]
+pub async fn detect_snippet_language(
+    name: String,
+    content: String,
+    kind: Option<SnippetKind>,
+) -> Result<Option<String>, String> {
+    Ok(
+        language::detect_language(name.trim(), content.trim(), kind.unwrap_or_default())
+            .map(str::to_string),
+    )
+}
+
+/// Get the parameters a snippet declares for its `{{name}}` placeholders
+///
+/// The UI renders a form from these (dropdowns for choices, date pickers,
+/// number inputs) instead of free-text placeholder filling.
+///
+/// # Returns
+///
+/// Declared parameters in form order; empty when the snippet has none
+///
+/// # Examples
+///
+/// ```typescript
+/// const parameters = await invoke('get_snippet_parameters', { id: 42 });
+/// ```
+#[tauri::command]
+pub async fn get_snippet_parameters(
+    app: AppHandle,
+    id: SnippetId,
+) -> Result<Vec<SnippetParameter>, String> {
+    let pool = get_pool(&app)?;
+    Ok(snippet_parameters::get_parameters(&pool, id.0).await?)
+}
+
+/// Declare the parameters of a snippet
+///
+/// Pass an empty list to remove the form.
+///
+/// # Examples
+///
+/// ```typescript
+/// await invoke('set_snippet_parameters', {
+///   id: 42,
+///   parameters: [{ name: 'env', type: 'choice', choices: ['staging', 'prod'] }],
+/// });
+/// ```
+#[tauri::command]
+pub async fn set_snippet_parameters(
+    app: AppHandle,
+    id: SnippetId,
+    parameters: Vec<SnippetParameter>,
+) -> Result<(), String> {
+    let pool = get_pool(&app)?;
+    snippet_parameters::set_parameters(&pool, id.0, &parameters).await?;
+    Ok(())
+}
+
+/// Get the search aliases of a snippet
+///
+/// # Examples
+///
+/// ```typescript
+/// const aliases = await invoke('get_snippet_aliases', { id: 42 });
+/// ```
+#[tauri::command]
+pub async fn get_snippet_aliases(app: AppHandle, id: SnippetId) -> Result<Vec<String>, String> {
+    let pool = get_pool(&app)?;
+    Ok(snippet_aliases::get_aliases(&pool, id.0).await?)
+}
+
+/// Replace the search aliases of a snippet
+///
+/// Aliases are short keywords such as `k8s` that bring the snippet up in
+/// search ahead of name and content matches. Blank and duplicate aliases
+/// are dropped; pass an empty list to remove them all.
+///
+/// # Returns
+///
+/// The aliases as stored
+///
+/// # Examples
+///
+/// ```typescript
+/// await invoke('set_snippet_aliases', { id: 42, aliases: ['k8s', 'kro'] });
+/// ```
+#[tauri::command]
+pub async fn set_snippet_aliases(
+    app: AppHandle,
+    id: SnippetId,
+    aliases: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let pool = get_pool(&app)?;
+    Ok(snippet_aliases::set_aliases(&pool, id.0, &aliases).await?)
+}
+
+/// Add a search alias to a snippet
+///
+/// # Returns
+///
+/// All aliases of the snippet
+///
+/// # Examples
+///
+/// ```typescript
+/// const aliases = await invoke('add_snippet_alias', { id: 42, alias: 'k8s' });
+/// ```
+#[tauri::command]
+pub async fn add_snippet_alias(
+    app: AppHandle,
+    id: SnippetId,
+    alias: String,
+) -> Result<Vec<String>, String> {
+    let pool = get_pool(&app)?;
+    Ok(snippet_aliases::add_alias(&pool, id.0, &alias).await?)
+}
+
+/// Remove a search alias from a snippet, ignoring case
+///
+/// # Returns
+///
+/// The remaining aliases of the snippet
+///
+/// # Examples
+///
+/// ```typescript
+/// const aliases = await invoke('remove_snippet_alias', { id: 42, alias: 'k8s' });
+/// ```
+#[tauri::command]
+pub async fn remove_snippet_alias(
+    app: AppHandle,
+    id: SnippetId,
+    alias: String,
+) -> Result<Vec<String>, String> {
+    let pool = get_pool(&app)?;
+    Ok(snippet_aliases::remove_alias(&pool, id.0, &alias).await?)
+}
+
+/// Fill a snippet's placeholders with values submitted from its form
+///
+/// Values are validated against the declared parameter types; missing
+/// values fall back to defaults.
+///
+/// # Errors
+///
+/// Returns an error naming the parameter when a value is missing or invalid
+///
+/// # Examples
+///
+/// ```typescript
+/// const text = await invoke('render_snippet_with_parameters', {
+///   id: 42,
+///   values: { env: 'prod', date: '2024-05-01' },
+/// });
+/// ```
+#[tauri::command]
+pub async fn render_snippet_with_parameters(
+    app: AppHandle,
+    id: SnippetId,
+    values: HashMap<String, String>,
+) -> Result<String, String> {
+    let pool = get_pool(&app)?;
+    let parameters = snippet_parameters::get_parameters(&pool, id.0).await?;
+    let content: String = sqlx::query_scalar("SELECT content FROM snippets WHERE id = ?")
+        .bind(id.0)
+        .fetch_one(&pool)
+        .await
+        .map_err(|e| AppError::from(e).to_string())?;
+
+    Ok(snippet_parameters::render_with_parameters(
+        &content,
+        &parameters,
+        &values,
+    )?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -214,6 +842,7 @@ mod tests {
             content: "content".to_string(),
             description: None,
             tags: vec![],
+            kind: SnippetKind::Static,
         };
         assert!(input.name.trim().is_empty());
 
@@ -223,6 +852,7 @@ mod tests {
             content: "".to_string(),
             description: None,
             tags: vec![],
+            kind: SnippetKind::Static,
         };
         assert!(input.content.trim().is_empty());
     }
@@ -234,10 +864,101 @@ mod tests {
             content: "  content  ".to_string(),
             description: Some("  desc  ".to_string()),
             tags: vec!["  tag1  ".to_string()],
+            kind: SnippetKind::Static,
         };
 
         assert_eq!(input.name.trim(), "test");
         assert_eq!(input.content.trim(), "content");
         assert_eq!(input.description.as_deref().map(|s| s.trim()), Some("desc"));
     }
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::services::database::run_migrations(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO snippets (id, name, content, created_at, updated_at)
+             VALUES (1, 'notes', 'first', 0, 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    async fn stored_content(pool: &SqlitePool, id: i64) -> String {
+        sqlx::query_scalar("SELECT content FROM snippets WHERE id = ?")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_append_keeps_existing_content() {
+        let pool = setup_test_db().await;
+        append_to_stored_content(&pool, SnippetId(1), "  second \n", APPEND_SEPARATOR, &[])
+            .await
+            .unwrap();
+        assert_eq!(stored_content(&pool, 1).await, "first\n\nsecond");
+
+        append_to_stored_content(&pool, SnippetId(1), "third", "\n- ", &[])
+            .await
+            .unwrap();
+        assert_eq!(stored_content(&pool, 1).await, "first\n\nsecond\n- third");
+
+        let empty = append_to_stored_content(&pool, SnippetId(1), "  ", "\n", &[]).await;
+        assert!(matches!(empty, Err(AppError::InvalidInput(_))));
+        assert_eq!(stored_content(&pool, 1).await, "first\n\nsecond\n- third");
+    }
+
+    #[tokio::test]
+    async fn test_append_adds_tags() {
+        let pool = setup_test_db().await;
+        tags::associate_tags(&pool, 1, &["work".to_string()])
+            .await
+            .unwrap();
+
+        let added = ["work".to_string(), " ideas/later ".to_string()];
+        append_to_stored_content(&pool, SnippetId(1), "more", "\n", &added)
+            .await
+            .unwrap();
+
+        let snippet = snippet_order::load_snippets(&pool, &[1])
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(
+            snippet.tags,
+            Some(vec!["ideas/later".to_string(), "work".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_append_to_missing_or_feed_snippet() {
+        let pool = setup_test_db().await;
+        let missing = append_to_stored_content(&pool, SnippetId(99), "text", "\n", &[]).await;
+        assert!(matches!(missing, Err(AppError::NotFound(_))));
+
+        sqlx::query(
+            "INSERT INTO snippet_feeds (id, name, url, kind, created_at)
+             VALUES (1, 'team', 'https://example.com/feed.json', 'json', 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("UPDATE snippets SET feed_id = 1, feed_key = 'notes' WHERE id = 1")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let read_only = append_to_stored_content(&pool, SnippetId(1), "text", "\n", &[]).await;
+        assert!(matches!(read_only, Err(AppError::Forbidden(_))));
+        assert_eq!(stored_content(&pool, 1).await, "first");
+    }
 }
@@ -0,0 +1,94 @@
+use crate::models::SnippetId;
+use crate::services::database::get_pool;
+use crate::services::source_refresh::{self, SnippetSource, SnippetVersion, SourceChange};
+use tauri::AppHandle;
+
+/// Get the remote file a snippet mirrors
+///
+/// # Returns
+///
+/// The source URL and when it was last fetched, or `None` for a snippet that
+/// is not a mirror
+///
+/// # Examples
+///
+/// ```typescript
+/// const source = await invoke('get_snippet_source', { id: 42 });
+/// ```
+#[tauri::command]
+pub async fn get_snippet_source(
+    app: AppHandle,
+    id: SnippetId,
+) -> Result<Option<SnippetSource>, String> {
+    let pool = get_pool(&app)?;
+    Ok(source_refresh::get_source(&pool, id.0).await?)
+}
+
+/// Make a snippet mirror a remote file, or stop mirroring
+///
+/// Accepts raw file URLs, GitHub file pages and gist URLs. The snippet is
+/// re-fetched by the scheduled refresh when it is enabled in settings.
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `id` - The snippet
+/// * `url` - http(s) URL of the source, or `null` to clear it
+///
+/// # Examples
+///
+/// ```typescript
+/// await invoke('set_snippet_source', {
+///   id: 42,
+///   url: 'https://github.com/me/dotfiles/blob/main/.ssh/config',
+/// });
+/// ```
+#[tauri::command]
+pub async fn set_snippet_source(
+    app: AppHandle,
+    id: SnippetId,
+    url: Option<String>,
+) -> Result<(), String> {
+    let pool = get_pool(&app)?;
+    source_refresh::set_source(&pool, id.0, url.as_deref()).await?;
+    Ok(())
+}
+
+/// Fetch a snippet's source now, regardless of the schedule
+///
+/// # Returns
+///
+/// A summary of the change, or `None` if the content was unchanged
+///
+/// # Errors
+///
+/// Returns an error string if the snippet has no source or the download fails
+///
+/// # Examples
+///
+/// ```typescript
+/// const change = await invoke('refresh_snippet_source', { id: 42 });
+/// ```
+#[tauri::command]
+pub async fn refresh_snippet_source(
+    app: AppHandle,
+    id: SnippetId,
+) -> Result<Option<SourceChange>, String> {
+    Ok(source_refresh::refresh_snippet(&app, id.0).await?)
+}
+
+/// Get the earlier contents of a snippet, newest first
+///
+/// # Examples
+///
+/// ```typescript
+/// const versions = await invoke('get_snippet_versions', { id: 42 });
+/// ```
+#[tauri::command]
+pub async fn get_snippet_versions(
+    app: AppHandle,
+    id: SnippetId,
+) -> Result<Vec<SnippetVersion>, String> {
+    let pool = get_pool(&app)?;
+    Ok(source_refresh::get_versions(&pool, id.0).await?)
+}
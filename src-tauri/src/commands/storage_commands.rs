@@ -1,8 +1,42 @@
-use crate::services::backup_scheduler::{BackupConfig, BackupSchedulerState};
-use crate::services::database::get_pool;
+use crate::commands::snippet_commands::create_snippet;
+use crate::models::SnippetKind;
+use crate::services::backup_files::{
+    self, extract_backup, is_backup_file, verify_backup_file, BackupCompression, BackupReader,
+    BackupVerification,
+};
+use crate::services::backup_scheduler::{
+    rotate_backups, BackupConfig, BackupSchedulerState, PruneReport,
+};
+use crate::services::confirmation::{Confirmation, ConfirmationState, DestructiveAction};
+use crate::services::database::{
+    self, get_pool, DatabaseDiagnostics, OptimizeReport, RepairReport, SearchIndexConsistency,
+};
+use crate::services::db_watcher;
+use crate::services::diagnostics_bundle::{self, DiagnosticsBundleReport};
+use crate::services::export_format::{self, EXPORT_FORMAT_VERSION};
+use crate::services::factory_reset::{self, FactoryResetOutcome};
+use crate::services::import_jobs::{self, ImportJob};
+use crate::services::importers::{self, ImportSource, ImportSummary};
+use crate::services::language::{self, language_id_for_tag};
+use crate::services::library_stats;
+use crate::services::markdown_format::{
+    markdown_file_name, markdown_to_snippet, snippet_to_markdown,
+};
+use crate::services::menubar;
+use crate::services::name_index;
+use crate::services::search;
+use crate::services::snippet_aliases;
+use crate::services::snippet_parameters::{self, SnippetParameter};
+use crate::services::startup::{StartupReport, StartupReportState};
+use crate::services::tags::normalize_tag_path;
+use crate::services::vscode_snippets::{snippets_to_vscode, vscode_to_snippets};
+use crate::utils::time::current_timestamp;
 use serde::{Deserialize, Serialize};
-use sqlx::Row;
-use std::path::PathBuf;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Manager};
 
 /// Database statistics
@@ -13,6 +47,8 @@ pub struct DatabaseStats {
     pub total_analytics_records: i64,
     pub database_size_bytes: u64,
     pub last_backup: Option<i64>,
+    /// Sizes before and after the last `optimize_database`, if it ever ran
+    pub last_optimization: Option<OptimizeReport>,
 }
 
 /// Backup metadata
@@ -21,6 +57,98 @@ pub struct BackupInfo {
     pub path: String,
     pub created_at: i64,
     pub size_bytes: u64,
+    /// Whether the backup file is gzip-compressed
+    #[serde(default)]
+    pub compressed: bool,
+}
+
+/// Summary of a snippet stored in a backup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupSnippetSummary {
+    /// Snippet ID inside the backup (used to select snippets for restore)
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub updated_at: i64,
+    /// Whether a snippet with the same name exists in the current database
+    pub exists_in_current: bool,
+}
+
+/// Contents of a backup, shown before it is restored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupPreview {
+    pub path: String,
+    pub snippet_count: usize,
+    pub tag_count: i64,
+    pub snippets: Vec<BackupSnippetSummary>,
+}
+
+/// What restoring a backup changes, by snippet name
+///
+/// Restoring replaces the whole database, so current snippets missing from
+/// the backup are lost.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RestoreReport {
+    /// Whether this only shows what the restore would do; nothing was written
+    pub dry_run: bool,
+    /// Snippets in the backup that are not in the current database
+    pub added: Vec<String>,
+    /// Current snippets the backup does not have
+    pub removed: Vec<String>,
+    /// Snippets in both whose content or description differ
+    pub changed: Vec<String>,
+    pub unchanged: usize,
+    /// Last migration applied to the backup, 0 if it predates versioning
+    pub backup_schema_version: i64,
+    /// Last migration this version of the app knows
+    pub latest_schema_version: i64,
+}
+
+impl RestoreReport {
+    /// Whether the backup was made by a newer version of the app, whose
+    /// schema this one cannot migrate
+    pub fn schema_mismatch(&self) -> bool {
+        self.backup_schema_version > self.latest_schema_version
+    }
+}
+
+/// Which snippets an export includes; every condition given must match
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportFilter {
+    /// Snippets with any of these tags; a tag path also matches the tags
+    /// nested below it, so `k8s` includes `k8s/prod`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Snippets synced from the team feed with this name
+    pub collection: Option<String>,
+    /// Snippets matching a search, with the same syntax as the search bar
+    pub query: Option<String>,
+    /// Snippets last updated at or after this Unix timestamp
+    pub updated_since: Option<i64>,
+    /// Snippets last updated at or before this Unix timestamp
+    pub updated_until: Option<i64>,
+}
+
+impl ExportFilter {
+    /// Whether a snippet passes the tag and date conditions
+    fn matches(&self, snippet: &SnippetExport) -> bool {
+        let has_tag = self.tags.is_empty()
+            || self.tags.iter().any(|wanted| {
+                let wanted = normalize_tag_path(wanted).to_lowercase();
+                snippet.tags.iter().any(|tag| {
+                    let tag = tag.to_lowercase();
+                    tag == wanted || tag.starts_with(&format!("{}/", wanted))
+                })
+            });
+        has_tag
+            && self
+                .updated_since
+                .is_none_or(|since| snippet.updated_at >= since)
+            && self
+                .updated_until
+                .is_none_or(|until| snippet.updated_at <= until)
+    }
 }
 
 /// Export data structure for JSON format
@@ -32,7 +160,10 @@ pub struct ExportData {
 }
 
 /// Snippet with tags for export
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Fields added after format 1.0.0 default when missing, so older exports,
+/// gists and plain files still read as static, unarchived snippets.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SnippetExport {
     pub name: String,
     pub content: String,
@@ -40,9 +171,79 @@ pub struct SnippetExport {
     pub tags: Vec<String>,
     pub created_at: i64,
     pub updated_at: i64,
+    #[serde(default)]
+    pub kind: SnippetKind,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub parameters: Vec<SnippetParameter>,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+}
+
+/// How an import treats a snippet whose name is already taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Keep the existing snippet and leave the imported one out
+    Skip,
+    /// Replace the existing snippet's content, description and tags
+    #[default]
+    Overwrite,
+    /// Import the snippet under a free name such as `deploy (2)`
+    Rename,
+    /// Replace the existing snippet only if the imported one was updated later
+    MergeNewer,
+}
+
+/// What an import did about a name that was already taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    Skipped,
+    Overwritten,
+    Renamed,
+}
+
+/// An imported snippet whose name was already taken
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportConflict {
+    pub name: String,
+    pub resolution: ConflictResolution,
+    /// The free name the snippet was imported under when renamed
+    pub renamed_to: Option<String>,
+}
+
+/// What an import wrote, by snippet name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// New snippets, including renamed ones under their new name
+    pub created: Vec<String>,
+    /// Existing snippets that were overwritten
+    pub updated: Vec<String>,
+    /// Snippets left out for an empty name or content, or because of a conflict
+    pub skipped: Vec<String>,
+    /// Snippets whose name was already taken, however they were resolved
+    pub conflicted: Vec<ImportConflict>,
+    /// Whether this only shows what the import would do; nothing was written
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+impl ImportReport {
+    /// Number of snippets written
+    pub fn imported(&self) -> usize {
+        self.created.len() + self.updated.len()
+    }
 }
 
 /// Create a backup of the database
+///
+/// The backup is compressed according to the current `BackupConfig` and a
+/// SHA-256 checksum is written alongside it. After the backup is written, old
+/// backups are rotated according to the retention policy.
 #[tauri::command]
 pub async fn backup_database(app: AppHandle) -> Result<BackupInfo, String> {
     let app_dir = app
@@ -67,33 +268,100 @@ pub async fn backup_database(app: AppHandle) -> Result<BackupInfo, String> {
         .map_err(|e| format!("Failed to get timestamp: {}", e))?
         .as_secs();
 
-    let backup_filename = format!("snips_backup_{}.db", timestamp);
+    let config = get_backup_config(app.clone()).await?;
+    let backup_filename = format!(
+        "snips_backup_{}{}",
+        timestamp,
+        config.compression.file_suffix()
+    );
     let backup_path = backup_dir.join(&backup_filename);
 
-    // Copy database file to backup location
-    std::fs::copy(&db_path, &backup_path).map_err(|e| format!("Failed to copy database: {}", e))?;
+    // Recent commits sit in the WAL until a checkpoint; fold them into the file first
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    database::checkpoint(&pool).await?;
+
+    // Write the (optionally compressed) backup and its checksum
+    backup_files::write_backup(&db_path, &backup_path, config.compression)?;
 
     // Get backup file size
     let size_bytes = std::fs::metadata(&backup_path)
         .map_err(|e| format!("Failed to get backup file size: {}", e))?
         .len();
 
+    // Apply the retention policy; a failed rotation must not fail the backup itself
+    if let Err(e) = rotate_backups(&app, &config).await {
+        eprintln!("Failed to rotate old backups: {}", e);
+    }
+
     Ok(BackupInfo {
         path: backup_path.to_string_lossy().to_string(),
         created_at: timestamp as i64,
         size_bytes,
+        compressed: config.compression == BackupCompression::Gzip,
     })
 }
 
 /// Restore database from a backup file
+///
+/// The backup is verified with `verify_backup` first; corrupt backups or
+/// backups whose checksum does not match are rejected.
+///
+/// # Arguments
+///
+/// * `backup_path` - Path of the backup file
+/// * `dry_run` - Only compare the backup with the current database (default: false)
+///
+/// # Returns
+///
+/// The snippets the restore adds, removes and changes, and the backup's
+/// schema version. A backup from an older version is migrated after it is
+/// restored; one from a newer version is refused.
+///
+/// # Examples
+///
+/// ```javascript
+/// const report = await invoke('restore_database', { backupPath, dryRun: true });
+/// if (confirm(`${report.removed.length} snippets will be lost`)) {
+///   await invoke('restore_database', { backupPath });
+/// }
+/// ```
 #[tauri::command]
-pub async fn restore_database(app: AppHandle, backup_path: String) -> Result<(), String> {
+pub async fn restore_database(
+    app: AppHandle,
+    backup_path: String,
+    dry_run: Option<bool>,
+) -> Result<RestoreReport, String> {
     let backup_file = PathBuf::from(&backup_path);
 
     if !backup_file.exists() {
         return Err("Backup file not found".to_string());
     }
 
+    let verification = verify_backup_file(&backup_file).await?;
+    if !verification.valid {
+        return Err(format!(
+            "Backup failed verification and was not restored: {}",
+            if verification.checksum_matches == Some(false) {
+                "checksum mismatch".to_string()
+            } else {
+                verification.integrity_message
+            }
+        ));
+    }
+
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    let mut report = compare_backup(&pool, &backup_file).await?;
+    if dry_run.unwrap_or(false) {
+        report.dry_run = true;
+        return Ok(report);
+    }
+    if report.schema_mismatch() {
+        return Err(format!(
+            "Backup is from a newer version of Snips (schema {}, this version knows {})",
+            report.backup_schema_version, report.latest_schema_version
+        ));
+    }
+
     let app_dir = app
         .path()
         .app_data_dir()
@@ -101,6 +369,9 @@ pub async fn restore_database(app: AppHandle, backup_path: String) -> Result<(),
 
     let db_path = app_dir.join("snips.db");
 
+    // Move recent commits out of the WAL so the pre-restore copy has them
+    database::checkpoint(&pool).await?;
+
     // Create a backup of current database before restoring
     if db_path.exists() {
         let pre_restore_backup = app_dir.join("snips_pre_restore.db");
@@ -108,11 +379,327 @@ pub async fn restore_database(app: AppHandle, backup_path: String) -> Result<(),
             .map_err(|e| format!("Failed to create pre-restore backup: {}", e))?;
     }
 
-    // Copy backup file to database location
-    std::fs::copy(&backup_file, &db_path)
-        .map_err(|e| format!("Failed to restore database: {}", e))?;
+    // Write the (decompressed) backup next to the database, never over the
+    // file the pool still has open
+    let restored = app_dir.join("snips.db.restore.tmp");
+    if let Err(e) = extract_backup(&backup_file, &restored) {
+        let _ = std::fs::remove_file(&restored);
+        return Err(format!("Failed to restore database: {}", e));
+    }
+
+    // Swaps it in with the pool closed, brings a backup from an older version
+    // up to the current schema and refreshes the caches and windows
+    if let Err(e) = db_watcher::replace_database_file(&app, &restored, &db_path).await {
+        let _ = std::fs::remove_file(&restored);
+        return Err(format!("Failed to restore database: {}", e));
+    }
+
+    Ok(report)
+}
+
+/// Compares a backup's snippets and schema with the current database
+async fn compare_backup(pool: &SqlitePool, backup_file: &Path) -> Result<RestoreReport, String> {
+    let current: Vec<(String, String, Option<String>)> =
+        sqlx::query_as("SELECT name, content, description FROM snippets")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to fetch current snippets: {}", e))?;
+
+    let mut reader = BackupReader::open(backup_file).await?;
+    let snippets = read_backup_snippets(&mut reader).await;
+    let backup_schema_version = backup_schema_version(reader.connection()).await;
+    reader.close().await;
+
+    let mut report = RestoreReport {
+        backup_schema_version: backup_schema_version?,
+        latest_schema_version: database::get_migrations().last().map_or(0, |m| m.version),
+        ..RestoreReport::default()
+    };
+    let mut current: HashMap<String, (String, Option<String>)> = current
+        .into_iter()
+        .map(|(name, content, description)| (name, (content, description)))
+        .collect();
+    for (_, snippet) in snippets? {
+        match current.remove(&snippet.name) {
+            None => report.added.push(snippet.name),
+            Some((content, description)) => {
+                if content == snippet.content && description == snippet.description {
+                    report.unchanged += 1;
+                } else {
+                    report.changed.push(snippet.name);
+                }
+            }
+        }
+    }
+    report.removed = current.into_keys().collect();
+    report.added.sort();
+    report.changed.sort();
+    report.removed.sort();
+    Ok(report)
+}
+
+/// Last migration applied to a backup
+///
+/// Backups made before `schema_version` existed carry the SQL plugin's
+/// `_sqlx_migrations` history instead; a backup with neither counts as 0.
+async fn backup_schema_version(conn: &mut SqliteConnection) -> Result<i64, String> {
+    let tables: Vec<String> = sqlx::query_scalar(
+        "SELECT name FROM sqlite_master
+         WHERE type = 'table' AND name IN ('schema_version', '_sqlx_migrations')",
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to read backup schema: {}", e))?;
 
-    Ok(())
+    let query = if tables.iter().any(|table| table == "schema_version") {
+        "SELECT COALESCE(MAX(version), 0) FROM schema_version"
+    } else if tables.iter().any(|table| table == "_sqlx_migrations") {
+        "SELECT COALESCE(MAX(version), 0) FROM _sqlx_migrations WHERE success = 1"
+    } else {
+        return Ok(0);
+    };
+    sqlx::query_scalar(query)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to read backup schema version: {}", e))
+}
+
+/// Delete all local data and restart as a fresh install
+///
+/// Guarded by the two-stage confirmation used for destructive actions: the
+/// first call deletes nothing and returns a challenge, and only a second
+/// call with its token, within a minute and with the same `delete_backups`,
+/// performs the reset. The database (snippets, settings, analytics, API
+/// tokens) and the logs are always deleted; backups only when asked. The app restarts
+/// shortly after the reset and recreates the database from the migrations.
+///
+/// # Arguments
+///
+/// * `delete_backups` - Also delete the backups directory
+/// * `confirmation_token` - Token from the challenge returned by the first call
+///
+/// # Returns
+///
+/// `FactoryResetOutcome` with either the challenge or the deleted paths
+///
+/// # Errors
+///
+/// Returns an error string if the token is invalid or expired, or if a file
+/// could not be deleted
+///
+/// # Examples
+///
+/// ```javascript
+/// const first = await invoke('factory_reset', { deleteBackups: false });
+/// if (first.status === 'confirmation_required' && confirm(first.challenge.description)) {
+///   await invoke('factory_reset', {
+///     deleteBackups: false,
+///     confirmationToken: first.challenge.token,
+///   });
+/// }
+/// ```
+#[tauri::command]
+pub async fn factory_reset(
+    app: AppHandle,
+    delete_backups: bool,
+    confirmation_token: Option<String>,
+) -> Result<FactoryResetOutcome, String> {
+    let confirmation = app
+        .state::<ConfirmationState>()
+        .check(
+            &DestructiveAction::FactoryReset { delete_backups },
+            confirmation_token.as_deref(),
+            false,
+        )
+        .map_err(|e| e.to_string())?;
+
+    match confirmation {
+        Confirmation::Required(challenge) => {
+            Ok(FactoryResetOutcome::ConfirmationRequired { challenge })
+        }
+        Confirmation::Confirmed => {
+            let report = factory_reset::factory_reset(&app, delete_backups)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(FactoryResetOutcome::Reset { report })
+        }
+    }
+}
+
+/// Verify a backup's integrity without restoring it
+///
+/// # Arguments
+///
+/// * `backup_path` - Path of the backup file to check
+///
+/// # Returns
+///
+/// `BackupVerification` with the checksum result and the outcome of
+/// `PRAGMA integrity_check` on the backup contents
+///
+/// # Errors
+///
+/// Returns an error string if the backup does not exist or cannot be read
+///
+/// # Examples
+///
+/// ```javascript
+/// const result = await invoke('verify_backup', { backupPath: backup.path });
+/// if (!result.valid) {
+///   console.warn(result.integrity_message);
+/// }
+/// ```
+#[tauri::command]
+pub async fn verify_backup(backup_path: String) -> Result<BackupVerification, String> {
+    verify_backup_file(&PathBuf::from(backup_path))
+        .await
+        .map_err(String::from)
+}
+
+/// Get the effective SQLite settings and connection pool state
+///
+/// # Returns
+///
+/// `DatabaseDiagnostics` with the journal mode, `synchronous` level, busy
+/// timeout and page counts of a pooled connection, plus pool usage
+///
+/// # Examples
+///
+/// ```javascript
+/// const diagnostics = await invoke('get_database_diagnostics');
+/// console.log(diagnostics.journal_mode, diagnostics.busy_timeout_ms);
+/// ```
+#[tauri::command]
+pub async fn get_database_diagnostics(app: AppHandle) -> Result<DatabaseDiagnostics, String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    database::get_diagnostics(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Export a diagnostics bundle to attach to bug reports
+///
+/// Zips window, database, resource watchdog, startup and clipboard
+/// diagnostics with the settings (secrets redacted) and the tail of recent
+/// log files. Sections that cannot be collected are listed in the report
+/// and in the bundle's `manifest.json` rather than failing the export.
+///
+/// # Arguments
+///
+/// * `export_path` - Destination of the zip file
+///
+/// # Returns
+///
+/// `DiagnosticsBundleReport` with the files written and any missing sections
+///
+/// # Examples
+///
+/// ```javascript
+/// const report = await invoke('export_diagnostics_bundle', { exportPath });
+/// if (report.errors.length > 0) console.warn(report.errors);
+/// ```
+#[tauri::command]
+pub async fn export_diagnostics_bundle(
+    app: AppHandle,
+    export_path: String,
+) -> Result<DiagnosticsBundleReport, String> {
+    diagnostics_bundle::export_diagnostics_bundle(&app, &PathBuf::from(export_path))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Compact the database and refresh its query planner statistics
+///
+/// Runs `VACUUM`, `ANALYZE` and `PRAGMA optimize`. Useful after deleting many
+/// snippets or clearing analytics, which leave free pages behind.
+///
+/// # Returns
+///
+/// `OptimizeReport` with the database size before and after and the space
+/// reclaimed; the same report appears in `get_database_stats` afterwards
+///
+/// # Examples
+///
+/// ```javascript
+/// const report = await invoke('optimize_database');
+/// console.log(`Reclaimed ${report.reclaimed_bytes} bytes`);
+/// ```
+#[tauri::command]
+pub async fn optimize_database(app: AppHandle) -> Result<OptimizeReport, String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    database::optimize(&pool).await.map_err(|e| e.to_string())
+}
+
+/// Apply pending migrations, recreate missing schema objects and rebuild
+/// the search index
+///
+/// Fixes what `get_database_diagnostics` reports in `missing_objects`, for
+/// example a search index or trigger lost to a crash. A recreated table
+/// starts empty; restore a backup to get its rows back.
+///
+/// # Returns
+///
+/// `RepairReport` with the migrations applied, the objects recreated and the
+/// number of snippets in the rebuilt search index
+///
+/// # Examples
+///
+/// ```javascript
+/// const report = await invoke('repair_database');
+/// console.log(`Recreated ${report.recreated.length} objects`);
+/// ```
+#[tauri::command]
+pub async fn repair_database(app: AppHandle) -> Result<RepairReport, String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    let report = database::repair(&pool).await.map_err(|e| e.to_string())?;
+    library_stats::invalidate(&app).await;
+    name_index::invalidate(&app).await;
+    menubar::notify_snippets_changed(&app);
+    Ok(report)
+}
+
+/// Compare the search index with the snippets table
+///
+/// # Returns
+///
+/// `SearchIndexConsistency` with the row counts on both sides and the number
+/// of snippets missing from the index, index rows without a snippet and
+/// index rows whose text is out of date
+///
+/// # Examples
+///
+/// ```javascript
+/// const check = await invoke('check_search_index_consistency');
+/// if (!check.consistent) await invoke('rebuild_search_index');
+/// ```
+#[tauri::command]
+pub async fn check_search_index_consistency(
+    app: AppHandle,
+) -> Result<SearchIndexConsistency, String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    database::check_search_index(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Drop and repopulate the full-text search index from the snippets table
+///
+/// # Returns
+///
+/// `SearchIndexConsistency` of the rebuilt index
+///
+/// # Examples
+///
+/// ```javascript
+/// const check = await invoke('rebuild_search_index');
+/// console.log(`Indexed ${check.index_rows} snippets`);
+/// ```
+#[tauri::command]
+pub async fn rebuild_search_index(app: AppHandle) -> Result<SearchIndexConsistency, String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    database::rebuild_search_index(&pool)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 /// Get database statistics
@@ -159,13 +746,7 @@ pub async fn get_database_stats(app: AppHandle) -> Result<DatabaseStats, String>
         std::fs::read_dir(&backup_dir).ok().and_then(|entries| {
             entries
                 .filter_map(|e| e.ok())
-                .filter(|e| {
-                    e.path()
-                        .extension()
-                        .and_then(|s| s.to_str())
-                        .map(|s| s == "db")
-                        .unwrap_or(false)
-                })
+                .filter(|e| is_backup_file(&e.path()))
                 .filter_map(|e| e.metadata().ok())
                 .filter_map(|m| m.modified().ok())
                 .filter_map(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
@@ -176,67 +757,209 @@ pub async fn get_database_stats(app: AppHandle) -> Result<DatabaseStats, String>
         None
     };
 
+    let last_optimization = database::last_optimization(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
     Ok(DatabaseStats {
         total_snippets: snippet_count,
         total_tags: tag_count,
         total_analytics_records: analytics_count,
         database_size_bytes,
         last_backup,
+        last_optimization,
     })
 }
 
-/// Export database to JSON format
-#[tauri::command]
-pub async fn export_to_json(app: AppHandle, export_path: String) -> Result<(), String> {
-    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+/// Query selecting every snippet with its tags joined into a comma-separated list
+const SNIPPETS_WITH_TAGS_QUERY: &str = r#"
+    SELECT
+        s.id,
+        s.name,
+        s.content,
+        s.description,
+        s.created_at,
+        s.updated_at,
+        s.kind,
+        s.aliases,
+        s.parameters,
+        s.archived,
+        s.expires_at,
+        GROUP_CONCAT(t.name, ',') as tags
+    FROM snippets s
+    LEFT JOIN snippet_tags st ON s.id = st.snippet_id
+    LEFT JOIN tags t ON st.tag_id = t.id
+    GROUP BY s.id
+    ORDER BY s.created_at
+"#;
+
+/// Like `SNIPPETS_WITH_TAGS_QUERY`, but only the columns every backup has;
+/// backups can predate kinds, aliases, parameters, archiving and expiry
+const BACKUP_SNIPPETS_QUERY: &str = r#"
+    SELECT
+        s.id,
+        s.name,
+        s.content,
+        s.description,
+        s.created_at,
+        s.updated_at,
+        GROUP_CONCAT(t.name, ',') as tags
+    FROM snippets s
+    LEFT JOIN snippet_tags st ON s.id = st.snippet_id
+    LEFT JOIN tags t ON st.tag_id = t.id
+    GROUP BY s.id
+    ORDER BY s.created_at
+"#;
+
+/// Converts a row of `SNIPPETS_WITH_TAGS_QUERY` or `BACKUP_SNIPPETS_QUERY`
+/// into a `SnippetExport`; columns the row lacks keep their defaults
+fn snippet_export_from_row(row: &SqliteRow) -> Result<SnippetExport, String> {
+    let tags_str: Option<String> = row.try_get("tags").ok();
+    let tags = tags_str
+        .map(|t| {
+            t.split(',')
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(SnippetExport {
+        name: row.try_get("name").map_err(|e| e.to_string())?,
+        content: row.try_get("content").map_err(|e| e.to_string())?,
+        description: row.try_get("description").ok(),
+        tags,
+        created_at: row.try_get("created_at").map_err(|e| e.to_string())?,
+        updated_at: row.try_get("updated_at").map_err(|e| e.to_string())?,
+        kind: row
+            .try_get("kind")
+            .map(SnippetKind::from_db)
+            .unwrap_or_default(),
+        aliases: json_column(row, "aliases")?,
+        parameters: json_column(row, "parameters")?,
+        archived: row.try_get("archived").unwrap_or(false),
+        expires_at: row.try_get("expires_at").ok().flatten(),
+    })
+}
 
-    // Fetch all snippets with their tags
-    let snippets = sqlx::query(
-        r#"
-        SELECT
-            s.id,
-            s.name,
-            s.content,
-            s.description,
-            s.created_at,
-            s.updated_at,
-            GROUP_CONCAT(t.name, ',') as tags
-        FROM snippets s
-        LEFT JOIN snippet_tags st ON s.id = st.snippet_id
-        LEFT JOIN tags t ON st.tag_id = t.id
-        GROUP BY s.id
-        ORDER BY s.created_at
-        "#,
-    )
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| format!("Failed to fetch snippets: {}", e))?;
-
-    let mut snippet_exports = Vec::new();
-
-    for row in snippets {
-        let tags_str: Option<String> = row.try_get("tags").ok();
-        let tags = tags_str
-            .map(|t| {
-                t.split(',')
-                    .filter(|s| !s.is_empty())
-                    .map(|s| s.to_string())
-                    .collect()
-            })
-            .unwrap_or_default();
+/// Reads a column holding a JSON array, or an empty list when it is `NULL`
+/// or missing
+fn json_column<T: serde::de::DeserializeOwned>(
+    row: &SqliteRow,
+    column: &str,
+) -> Result<Vec<T>, String> {
+    match row.try_get::<Option<String>, _>(column).ok().flatten() {
+        Some(json) => {
+            serde_json::from_str(&json).map_err(|e| format!("Invalid {} of snippet: {}", column, e))
+        }
+        None => Ok(Vec::new()),
+    }
+}
 
-        snippet_exports.push(SnippetExport {
-            name: row.try_get("name").map_err(|e| e.to_string())?,
-            content: row.try_get("content").map_err(|e| e.to_string())?,
-            description: row.try_get("description").ok(),
-            tags,
-            created_at: row.try_get("created_at").map_err(|e| e.to_string())?,
-            updated_at: row.try_get("updated_at").map_err(|e| e.to_string())?,
+/// Fetches all snippets with their tags in export form
+async fn fetch_snippet_exports(pool: &SqlitePool) -> Result<Vec<SnippetExport>, String> {
+    let rows = sqlx::query(SNIPPETS_WITH_TAGS_QUERY)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch snippets: {}", e))?;
+
+    rows.iter().map(snippet_export_from_row).collect()
+}
+
+/// Fetches the snippets an `ExportFilter` selects, in export form
+async fn fetch_filtered_snippet_exports(
+    app: &AppHandle,
+    pool: &SqlitePool,
+    filter: &ExportFilter,
+) -> Result<Vec<SnippetExport>, String> {
+    let mut allowed_ids: Option<HashSet<i64>> = None;
+
+    if let Some(collection) = filter.collection.as_deref() {
+        let ids: Vec<i64> = sqlx::query_scalar(
+            "SELECT s.id FROM snippets s JOIN snippet_feeds f ON f.id = s.feed_id
+             WHERE f.name = ?",
+        )
+        .bind(collection.trim())
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch collection: {}", e))?;
+        allowed_ids = Some(ids.into_iter().collect());
+    }
+
+    if let Some(query) = filter.query.as_deref().filter(|q| !q.trim().is_empty()) {
+        let mut matches = HashSet::new();
+        let mut cursor = None;
+        loop {
+            let page =
+                search::search_snippets(app, query, Some(1000), None, cursor.as_deref()).await?;
+            matches.extend(page.results.iter().map(|result| result.snippet.id.0));
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        allowed_ids = Some(match allowed_ids {
+            Some(ids) => ids.intersection(&matches).copied().collect(),
+            None => matches,
         });
     }
 
+    let rows = sqlx::query(SNIPPETS_WITH_TAGS_QUERY)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch snippets: {}", e))?;
+    let mut snippets = Vec::new();
+    for row in &rows {
+        let id: i64 = row.try_get("id").map_err(|e| e.to_string())?;
+        if allowed_ids.as_ref().is_some_and(|ids| !ids.contains(&id)) {
+            continue;
+        }
+        let snippet = snippet_export_from_row(row)?;
+        if filter.matches(&snippet) {
+            snippets.push(snippet);
+        }
+    }
+    Ok(snippets)
+}
+
+/// Export database to JSON format
+///
+/// The file records `EXPORT_FORMAT_VERSION`, which `import_from_json` uses to
+/// migrate it when the format changes.
+///
+/// # Arguments
+///
+/// * `export_path` - Path of the file to write
+/// * `filter` - Which snippets to export; all of them when omitted
+///
+/// # Returns
+///
+/// The number of snippets exported
+///
+/// # Examples
+///
+/// ```javascript
+/// const count = await invoke('export_to_json', {
+///   exportPath: '/tmp/k8s.json',
+///   filter: { tags: ['k8s'], updated_since: 1704067200 },
+/// });
+/// ```
+#[tauri::command]
+pub async fn export_to_json(
+    app: AppHandle,
+    export_path: String,
+    filter: Option<ExportFilter>,
+) -> Result<usize, String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+
+    let snippet_exports = match filter {
+        Some(filter) => fetch_filtered_snippet_exports(&app, &pool, &filter).await?,
+        None => fetch_snippet_exports(&pool).await?,
+    };
+    let count = snippet_exports.len();
+
     let export_data = ExportData {
-        version: "1.0.0".to_string(),
+        version: EXPORT_FORMAT_VERSION.to_string(),
         exported_at: std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .map_err(|e| format!("Failed to get timestamp: {}", e))?
@@ -251,127 +974,788 @@ pub async fn export_to_json(app: AppHandle, export_path: String) -> Result<(), S
     std::fs::write(&export_path, json)
         .map_err(|e| format!("Failed to write export file: {}", e))?;
 
-    Ok(())
+    Ok(count)
+}
+
+/// Writes every snippet to a JSON export file, unless the file already holds
+/// exactly these snippets
+///
+/// Keeping an unchanged file untouched (including its `exported_at`) lets a
+/// scheduled export live in a git repository or synced folder without a new
+/// revision on every run.
+///
+/// # Returns
+///
+/// The number of snippets in the export
+pub(crate) async fn write_json_mirror(app: &AppHandle, path: &Path) -> Result<usize, String> {
+    let pool = get_pool(app).map_err(|e| e.to_string())?;
+    let snippets = fetch_snippet_exports(&pool).await?;
+    let count = snippets.len();
+
+    let unchanged = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| export_format::parse_export(&text).ok())
+        .is_some_and(|existing| {
+            serde_json::to_value(&existing.snippets).ok() == serde_json::to_value(&snippets).ok()
+        });
+    if unchanged {
+        return Ok(count);
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create export directory: {}", e))?;
+    }
+    let export_data = ExportData {
+        version: EXPORT_FORMAT_VERSION.to_string(),
+        exported_at: current_timestamp(),
+        snippets,
+    };
+    let json = serde_json::to_string_pretty(&export_data)
+        .map_err(|e| format!("Failed to serialize data: {}", e))?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write export file: {}", e))?;
+
+    Ok(count)
 }
 
+/// Number of snippets between `import-progress` events
+const IMPORT_PROGRESS_INTERVAL: usize = 200;
+
 /// Import snippets from JSON format
+///
+/// The import runs in a single transaction: if any snippet fails, nothing is
+/// written. `import-progress` events report how far it has got. The import
+/// is recorded as a job until it commits, so one cut short by a crash is
+/// still offered again by `list_unfinished_imports`. Jobs left by earlier
+/// versions, which committed in chunks, continue after the last committed
+/// chunk unless `resume` is `false`, in which case the file starts over.
+///
+/// # Arguments
+///
+/// * `import_path` - Path of the JSON export to import
+/// * `resume` - Whether to continue an unfinished import of this file (default: true)
+/// * `mode` - What to do with snippets whose name is taken (default: `overwrite`)
+/// * `dry_run` - Only report what the import would do (default: false)
+///
+/// # Returns
+///
+/// The snippets created, updated, skipped and in conflict. When an
+/// interrupted import is resumed, only snippets written by this run are listed.
+/// A dry run reports the same without writing anything or recording a job.
+///
+/// # Errors
+///
+/// Returns an error string if the file cannot be read or parsed, or if
+/// writing any snippet fails; the database is then left as it was
+///
+/// # Examples
+///
+/// ```javascript
+/// const job = await invoke('get_resumable_import', { importPath });
+/// const report = await invoke('import_from_json', {
+///   importPath,
+///   resume: job !== null,
+///   mode: 'merge_newer',
+/// });
+/// ```
 #[tauri::command]
-pub async fn import_from_json(app: AppHandle, import_path: String) -> Result<usize, String> {
+pub async fn import_from_json(
+    app: AppHandle,
+    import_path: String,
+    resume: Option<bool>,
+    mode: Option<ImportMode>,
+    dry_run: Option<bool>,
+) -> Result<ImportReport, String> {
     let pool = get_pool(&app).map_err(|e| e.to_string())?;
 
     // Read the import file
     let json = std::fs::read_to_string(&import_path)
         .map_err(|e| format!("Failed to read import file: {}", e))?;
 
-    let import_data: ExportData =
-        serde_json::from_str(&json).map_err(|e| format!("Failed to parse import file: {}", e))?;
+    // Older export formats are migrated; newer ones are refused
+    let import_data = export_format::parse_export(&json)
+        .map_err(|e| format!("Failed to parse import file: {}", e))?;
+
+    let file_hash = import_jobs::content_hash(json.as_bytes());
+    let unfinished = import_jobs::find_unfinished(&pool, &file_hash, JSON_IMPORT_FORMAT)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if dry_run.unwrap_or(false) {
+        let already_imported = match &unfinished {
+            Some(job) if resume.unwrap_or(true) => job.processed_items.max(0) as usize,
+            _ => 0,
+        };
+        let snippets = import_data.snippets.into_iter().skip(already_imported);
+        return preview_snippet_exports(&pool, snippets.collect(), mode.unwrap_or_default()).await;
+    }
+
+    let job = match unfinished {
+        Some(job) if resume.unwrap_or(true) => job,
+        _ => import_jobs::start(
+            &pool,
+            &import_path,
+            &file_hash,
+            JSON_IMPORT_FORMAT,
+            import_data.snippets.len() as i64,
+        )
+        .await
+        .map_err(|e| e.to_string())?,
+    };
+
+    let report = import_snippet_exports_with_job(
+        &app,
+        &pool,
+        &job,
+        import_data.snippets,
+        mode.unwrap_or_default(),
+    )
+    .await?;
+    library_stats::invalidate(&app).await;
+    name_index::invalidate(&app).await;
+    menubar::notify_snippets_changed(&app);
+    Ok(report)
+}
+
+/// Format name recorded on import jobs created by `import_from_json`
+const JSON_IMPORT_FORMAT: &str = "json";
+
+/// Check whether an earlier import of a file was interrupted
+///
+/// # Arguments
+///
+/// * `import_path` - Path of the JSON export about to be imported
+///
+/// # Returns
+///
+/// The unfinished `ImportJob` for this file's contents, or `null` if there is
+/// nothing to resume
+///
+/// # Examples
+///
+/// ```javascript
+/// const job = await invoke('get_resumable_import', { importPath });
+/// if (job) console.log(`${job.processed_items} of ${job.total_items} already imported`);
+/// ```
+#[tauri::command]
+pub async fn get_resumable_import(
+    app: AppHandle,
+    import_path: String,
+) -> Result<Option<ImportJob>, String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    let contents =
+        std::fs::read(&import_path).map_err(|e| format!("Failed to read import file: {}", e))?;
+    let file_hash = import_jobs::content_hash(&contents);
+
+    import_jobs::find_unfinished(&pool, &file_hash, JSON_IMPORT_FORMAT)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// List all imports that were interrupted before finishing
+///
+/// Intended for startup, so the app can offer to resume them.
+#[tauri::command]
+pub async fn list_unfinished_imports(app: AppHandle) -> Result<Vec<ImportJob>, String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    import_jobs::list_unfinished(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Forget an unfinished import so it is no longer offered for resumption
+///
+/// Snippets already imported by the job are kept.
+#[tauri::command]
+pub async fn discard_import_job(app: AppHandle, id: i64) -> Result<(), String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    import_jobs::remove(&pool, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Export snippets as markdown files, one `.md` file per snippet
+///
+/// Each file has YAML front matter with the name, description, tags and
+/// timestamps, followed by the snippet content as the body. The format is
+/// readable by Obsidian and other markdown note tools.
+///
+/// # Arguments
+///
+/// * `export_dir` - Directory to write the files into (created if missing)
+///
+/// # Returns
+///
+/// The number of files written
+///
+/// # Examples
+///
+/// ```javascript
+/// const count = await invoke('export_to_markdown', { exportDir: '/Users/me/Notes/snips' });
+/// ```
+#[tauri::command]
+pub async fn export_to_markdown(app: AppHandle, export_dir: String) -> Result<usize, String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    let snippets = fetch_snippet_exports(&pool).await?;
 
-    let mut imported_count = 0;
+    let export_dir = PathBuf::from(export_dir);
+    std::fs::create_dir_all(&export_dir)
+        .map_err(|e| format!("Failed to create export directory: {}", e))?;
+
+    let mut used_names = HashSet::new();
+    for snippet in &snippets {
+        let file_name = markdown_file_name(&snippet.name, &mut used_names);
+        std::fs::write(export_dir.join(&file_name), snippet_to_markdown(snippet))
+            .map_err(|e| format!("Failed to write {}: {}", file_name, e))?;
+    }
+
+    Ok(snippets.len())
+}
+
+/// Import snippets from a directory of markdown files
+///
+/// Every `.md` file in the directory (not recursive) becomes a snippet. The
+/// name comes from the `name`/`title` front matter, falling back to the file
+/// name. Existing snippets with the same name are updated.
+///
+/// # Arguments
+///
+/// * `import_dir` - Directory containing the markdown files
+///
+/// # Returns
+///
+/// The number of snippets imported
+#[tauri::command]
+pub async fn import_from_markdown(app: AppHandle, import_dir: String) -> Result<usize, String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(&import_dir)
+        .map_err(|e| format!("Failed to read import directory: {}", e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+        })
+        .collect();
+    paths.sort();
+
+    let now = current_timestamp();
+    let mut snippets = Vec::with_capacity(paths.len());
+    for path in paths {
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let fallback_name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if let Some(snippet) = markdown_to_snippet(&text, &fallback_name, now) {
+            snippets.push(snippet);
+        }
+    }
+
+    let imported = import_snippet_exports(&pool, snippets).await?;
+    library_stats::invalidate(&app).await;
+    name_index::invalidate(&app).await;
+    menubar::notify_snippets_changed(&app);
+    Ok(imported)
+}
+
+/// Export all snippets to a VS Code `.code-snippets` file
+///
+/// Snippet names become entry keys, the content becomes the `body` line array
+/// and language tags (e.g. `rust`, `bash`) become the `scope`. The file can be
+/// dropped into `.vscode/` or the user snippets folder.
+///
+/// # Arguments
+///
+/// * `export_path` - Path of the file to write
+///
+/// # Returns
+///
+/// The number of snippets exported
+///
+/// # Examples
+///
+/// ```javascript
+/// const count = await invoke('export_to_vscode_snippets', {
+///   exportPath: '/Users/me/project/.vscode/snips.code-snippets'
+/// });
+/// ```
+#[tauri::command]
+pub async fn export_to_vscode_snippets(
+    app: AppHandle,
+    export_path: String,
+) -> Result<usize, String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    let snippets = fetch_snippet_exports(&pool).await?;
+
+    let document = snippets_to_vscode(&snippets);
+    let json = serde_json::to_string_pretty(&document)
+        .map_err(|e| format!("Failed to serialize data: {}", e))?;
+
+    std::fs::write(&export_path, json)
+        .map_err(|e| format!("Failed to write export file: {}", e))?;
+
+    Ok(snippets.len())
+}
+
+/// Import snippets from VS Code snippet files
+///
+/// Accepts a single `.code-snippets`/`.json` file or a directory of them (not
+/// recursive), such as the VS Code user snippets folder. Each entry's `scope`
+/// becomes its tags; entries in a per-language file like `rust.json` are
+/// tagged with that language. Existing snippets with the same name are
+/// updated.
+///
+/// # Arguments
+///
+/// * `import_path` - Snippets file or directory
+///
+/// # Returns
+///
+/// The number of snippets imported
+///
+/// # Examples
+///
+/// ```javascript
+/// const count = await invoke('import_from_vscode_snippets', {
+///   importPath: '/Users/me/Library/Application Support/Code/User/snippets'
+/// });
+/// ```
+#[tauri::command]
+pub async fn import_from_vscode_snippets(
+    app: AppHandle,
+    import_path: String,
+) -> Result<usize, String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+
+    let import_path = PathBuf::from(import_path);
+    let paths = if import_path.is_dir() {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(&import_path)
+            .map_err(|e| format!("Failed to read import directory: {}", e))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .is_some_and(|ext| ext == "code-snippets" || ext == "json")
+            })
+            .collect();
+        paths.sort();
+        paths
+    } else {
+        vec![import_path]
+    };
+
+    let now = current_timestamp();
+    let mut snippets = Vec::new();
+    for path in paths {
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let file_language = path
+            .extension()
+            .filter(|ext| *ext == "json")
+            .and_then(|_| path.file_stem())
+            .and_then(|stem| stem.to_str())
+            .and_then(language_id_for_tag);
+        let parsed = vscode_to_snippets(&text, file_language, now)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        snippets.extend(parsed);
+    }
+
+    let imported = import_snippet_exports(&pool, snippets).await?;
+    library_stats::invalidate(&app).await;
+    name_index::invalidate(&app).await;
+    menubar::notify_snippets_changed(&app);
+    Ok(imported)
+}
+
+/// Import a snippet library from Alfred or Raycast
+///
+/// Reads an Alfred `.alfredsnippets` bundle or a Raycast snippet export and
+/// creates a snippet for each entry. Snippets whose name already exists are
+/// left untouched and reported in `skipped`, so running the import twice is
+/// harmless.
+///
+/// # Arguments
+///
+/// * `import_path` - The exported file
+/// * `source` - `alfred` or `raycast`; detected from the extension when omitted
+///
+/// # Returns
+///
+/// `ImportSummary` with the number of snippets created and the skipped names
+///
+/// # Examples
+///
+/// ```javascript
+/// const summary = await invoke('import_from_snippet_app', {
+///   importPath: '/Users/me/Downloads/Work.alfredsnippets'
+/// });
+/// ```
+#[tauri::command]
+pub async fn import_from_snippet_app(
+    app: AppHandle,
+    import_path: String,
+    source: Option<ImportSource>,
+) -> Result<ImportSummary, String> {
+    let (source, inputs) = importers::read_library(std::path::Path::new(&import_path), source)
+        .map_err(|e| e.to_string())?;
+
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    let mut taken: HashSet<String> = sqlx::query_scalar("SELECT name FROM snippets")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| format!("Failed to load existing snippets: {}", e))?
+        .into_iter()
+        .collect();
+
+    let mut summary = ImportSummary {
+        source,
+        imported: 0,
+        skipped: Vec::new(),
+    };
+    for input in inputs {
+        if !taken.insert(input.name.clone()) {
+            summary.skipped.push(input.name);
+            continue;
+        }
+        create_snippet(app.clone(), input).await?;
+        summary.imported += 1;
+    }
+
+    Ok(summary)
+}
+
+/// Writes exported snippets into the database, merging by name
+///
+/// Snippets whose name already exists are updated in place (content,
+/// description, kind, tags, aliases, parameters, archived state and expiry);
+/// others are inserted. Snippets with an empty name or
+/// content are skipped. Everything is written in one transaction, so a
+/// failure leaves the database as it was.
+///
+/// # Returns
+///
+/// The number of snippets written
+pub(crate) async fn import_snippet_exports(
+    pool: &SqlitePool,
+    snippets: Vec<SnippetExport>,
+) -> Result<usize, String> {
+    let report = import_snippet_exports_with_mode(pool, snippets, ImportMode::Overwrite).await?;
+    Ok(report.imported())
+}
+
+/// Imports snippets in one transaction, resolving taken names according to
+/// `mode`
+///
+/// # Returns
+///
+/// The snippets created, updated, skipped and in conflict
+pub(crate) async fn import_snippet_exports_with_mode(
+    pool: &SqlitePool,
+    snippets: Vec<SnippetExport>,
+    mode: ImportMode,
+) -> Result<ImportReport, String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let mut report = ImportReport::default();
 
     // Import each snippet
-    for snippet in import_data.snippets {
-        // Validate snippet data
-        if snippet.name.is_empty() {
-            continue; // Skip invalid snippets
+    for snippet in snippets {
+        import_snippet_export(&mut tx, snippet, mode, &mut report).await?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit import: {}", e))?;
+
+    Ok(report)
+}
+
+/// Works out what importing snippets would do, without writing anything
+///
+/// The import runs as usual inside a transaction that is rolled back, so the
+/// report matches a real import exactly. Importers can offer a dry run by
+/// calling this instead of writing.
+pub(crate) async fn preview_snippet_exports(
+    pool: &SqlitePool,
+    snippets: Vec<SnippetExport>,
+    mode: ImportMode,
+) -> Result<ImportReport, String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let mut report = ImportReport {
+        dry_run: true,
+        ..ImportReport::default()
+    };
+    for snippet in snippets {
+        import_snippet_export(&mut tx, snippet, mode, &mut report).await?;
+    }
+    tx.rollback()
+        .await
+        .map_err(|e| format!("Failed to roll back import preview: {}", e))?;
+
+    Ok(report)
+}
+
+/// Imports snippets for an import job in one transaction
+///
+/// Snippets an earlier run of the job already committed are skipped. The job
+/// is removed in the same transaction, so it disappears exactly when its
+/// snippets are written.
+///
+/// # Returns
+///
+/// What this run wrote; snippets written by earlier runs of the job are only
+/// counted in the job's progress
+async fn import_snippet_exports_with_job(
+    app: &AppHandle,
+    pool: &SqlitePool,
+    job: &ImportJob,
+    snippets: Vec<SnippetExport>,
+    mode: ImportMode,
+) -> Result<ImportReport, String> {
+    let total = snippets.len();
+    let mut processed = job.processed_items.max(0) as usize;
+    let mut report = ImportReport::default();
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    for snippet in snippets.into_iter().skip(processed) {
+        import_snippet_export(&mut tx, snippet, mode, &mut report).await?;
+        processed += 1;
+        if processed % IMPORT_PROGRESS_INTERVAL == 0 || processed == total {
+            import_jobs::emit_progress(app, job.id, processed, total);
         }
+    }
+    import_jobs::finish(&mut tx, job.id)
+        .await
+        .map_err(|e| e.to_string())?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit import: {}", e))?;
+
+    Ok(report)
+}
 
-        if snippet.content.is_empty() {
-            continue; // Skip snippets without content
+/// Finds a name not yet taken by appending ` (2)`, ` (3)`, ... to `name`
+pub(crate) async fn free_snippet_name(
+    conn: &mut SqliteConnection,
+    name: &str,
+) -> Result<String, String> {
+    let mut n = 2;
+    loop {
+        let candidate = format!("{} ({})", name, n);
+        let taken: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM snippets WHERE name = ?)")
+                .bind(&candidate)
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to check existing snippet: {}", e))?;
+        if !taken {
+            return Ok(candidate);
         }
+        n += 1;
+    }
+}
 
-        // Check if snippet with same name already exists
-        let existing: Option<i64> = sqlx::query_scalar("SELECT id FROM snippets WHERE name = ?")
+/// Writes one exported snippet, resolving a taken name according to `mode`
+///
+/// Snippets synced from a team feed are never overwritten; in `overwrite`
+/// and `merge_newer` mode a conflict with one is skipped.
+///
+/// # Returns
+///
+/// Whether the snippet was written. The outcome is added to `report`.
+async fn import_snippet_export(
+    conn: &mut SqliteConnection,
+    mut snippet: SnippetExport,
+    mode: ImportMode,
+    report: &mut ImportReport,
+) -> Result<bool, String> {
+    // Validate snippet data
+    let aliases = snippet_aliases::normalize_aliases(&snippet.aliases);
+    if snippet.name.is_empty()
+        || snippet.content.is_empty()
+        || aliases.is_err()
+        || snippet_parameters::validate_parameters(&snippet.parameters).is_err()
+    {
+        report.skipped.push(snippet.name); // Skip invalid snippets
+        return Ok(false);
+    }
+    // Stored as NULL when empty, like `set_aliases` and `set_parameters` do
+    let aliases = aliases.unwrap_or_default();
+    let aliases = (!aliases.is_empty())
+        .then(|| serde_json::to_string(&aliases))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    let parameters = (!snippet.parameters.is_empty())
+        .then(|| serde_json::to_string(&snippet.parameters))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+
+    // Check if snippet with same name already exists
+    let existing: Option<(i64, i64, Option<i64>)> =
+        sqlx::query_as("SELECT id, updated_at, feed_id FROM snippets WHERE name = ?")
             .bind(&snippet.name)
-            .fetch_optional(&pool)
+            .fetch_optional(&mut *conn)
             .await
             .map_err(|e| format!("Failed to check existing snippet: {}", e))?;
 
-        let snippet_id = if let Some(id) = existing {
-            // Update existing snippet
-            sqlx::query(
-                r#"
-                UPDATE snippets
-                SET content = ?, description = ?, updated_at = ?
-                WHERE id = ?
-                "#,
-            )
-            .bind(&snippet.content)
-            .bind(&snippet.description)
-            .bind(snippet.updated_at)
-            .bind(id)
-            .execute(&pool)
-            .await
-            .map_err(|e| format!("Failed to update snippet: {}", e))?;
-
-            id
-        } else {
-            // Insert new snippet
-            let result = sqlx::query(
-                r#"
-                INSERT INTO snippets (name, content, description, created_at, updated_at)
-                VALUES (?, ?, ?, ?, ?)
-                "#,
-            )
-            .bind(&snippet.name)
-            .bind(&snippet.content)
-            .bind(&snippet.description)
-            .bind(snippet.created_at)
-            .bind(snippet.updated_at)
-            .execute(&pool)
+    let existing = match existing {
+        None => None,
+        Some((id, updated_at, feed_id)) => {
+            let resolution = match mode {
+                ImportMode::Rename => ConflictResolution::Renamed,
+                _ if feed_id.is_some() => ConflictResolution::Skipped,
+                ImportMode::Overwrite => ConflictResolution::Overwritten,
+                ImportMode::MergeNewer if snippet.updated_at > updated_at => {
+                    ConflictResolution::Overwritten
+                }
+                ImportMode::Skip | ImportMode::MergeNewer => ConflictResolution::Skipped,
+            };
+            let renamed_to = if resolution == ConflictResolution::Renamed {
+                Some(free_snippet_name(conn, &snippet.name).await?)
+            } else {
+                None
+            };
+            report.conflicted.push(ImportConflict {
+                name: snippet.name.clone(),
+                resolution,
+                renamed_to: renamed_to.clone(),
+            });
+
+            match resolution {
+                ConflictResolution::Skipped => {
+                    report.skipped.push(snippet.name);
+                    return Ok(false);
+                }
+                ConflictResolution::Renamed => {
+                    snippet.name = renamed_to.unwrap_or_default();
+                    None
+                }
+                ConflictResolution::Overwritten => Some(id),
+            }
+        }
+    };
+
+    let language = language::detect_language(&snippet.name, &snippet.content, snippet.kind);
+    if existing.is_some() {
+        report.updated.push(snippet.name.clone());
+    } else {
+        report.created.push(snippet.name.clone());
+    }
+
+    let snippet_id = if let Some(id) = existing {
+        // Update existing snippet; like tags, aliases and parameters are only
+        // replaced when the import has some
+        sqlx::query(
+            r#"
+            UPDATE snippets
+            SET content = ?, description = ?, updated_at = ?, language = ?, kind = ?,
+                aliases = COALESCE(?, aliases), parameters = COALESCE(?, parameters),
+                archived = ?, expires_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&snippet.content)
+        .bind(&snippet.description)
+        .bind(snippet.updated_at)
+        .bind(language)
+        .bind(snippet.kind.as_str())
+        .bind(&aliases)
+        .bind(&parameters)
+        .bind(snippet.archived)
+        .bind(snippet.expires_at)
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to update snippet: {}", e))?;
+
+        id
+    } else {
+        // Insert new snippet
+        let result = sqlx::query(
+            r#"
+            INSERT INTO snippets
+                (name, content, description, created_at, updated_at, language, kind,
+                 aliases, parameters, archived, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&snippet.name)
+        .bind(&snippet.content)
+        .bind(&snippet.description)
+        .bind(snippet.created_at)
+        .bind(snippet.updated_at)
+        .bind(language)
+        .bind(snippet.kind.as_str())
+        .bind(&aliases)
+        .bind(&parameters)
+        .bind(snippet.archived)
+        .bind(snippet.expires_at)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to insert snippet: {}", e))?;
+
+        result.last_insert_rowid()
+    };
+
+    // Handle tags
+    if !snippet.tags.is_empty() {
+        // Delete existing tags for this snippet
+        sqlx::query("DELETE FROM snippet_tags WHERE snippet_id = ?")
+            .bind(snippet_id)
+            .execute(&mut *conn)
             .await
-            .map_err(|e| format!("Failed to insert snippet: {}", e))?;
+            .map_err(|e| format!("Failed to delete existing tags: {}", e))?;
 
-            result.last_insert_rowid()
-        };
+        // Insert tags
+        for tag_name in snippet.tags {
+            if tag_name.is_empty() {
+                continue;
+            }
 
-        // Handle tags
-        if !snippet.tags.is_empty() {
-            // Delete existing tags for this snippet
-            sqlx::query("DELETE FROM snippet_tags WHERE snippet_id = ?")
-                .bind(snippet_id)
-                .execute(&pool)
+            // Get or create tag
+            let tag_id: Option<i64> = sqlx::query_scalar("SELECT id FROM tags WHERE name = ?")
+                .bind(&tag_name)
+                .fetch_optional(&mut *conn)
                 .await
-                .map_err(|e| format!("Failed to delete existing tags: {}", e))?;
-
-            // Insert tags
-            for tag_name in snippet.tags {
-                if tag_name.is_empty() {
-                    continue;
-                }
+                .map_err(|e| format!("Failed to get tag: {}", e))?;
 
-                // Get or create tag
-                let tag_id: Option<i64> = sqlx::query_scalar("SELECT id FROM tags WHERE name = ?")
+            let tag_id = if let Some(id) = tag_id {
+                id
+            } else {
+                let result = sqlx::query("INSERT INTO tags (name) VALUES (?)")
                     .bind(&tag_name)
-                    .fetch_optional(&pool)
+                    .execute(&mut *conn)
                     .await
-                    .map_err(|e| format!("Failed to get tag: {}", e))?;
+                    .map_err(|e| format!("Failed to insert tag: {}", e))?;
 
-                let tag_id = if let Some(id) = tag_id {
-                    id
-                } else {
-                    let result = sqlx::query("INSERT INTO tags (name) VALUES (?)")
-                        .bind(&tag_name)
-                        .execute(&pool)
-                        .await
-                        .map_err(|e| format!("Failed to insert tag: {}", e))?;
-
-                    result.last_insert_rowid()
-                };
-
-                // Link tag to snippet
-                sqlx::query("INSERT INTO snippet_tags (snippet_id, tag_id) VALUES (?, ?)")
-                    .bind(snippet_id)
-                    .bind(tag_id)
-                    .execute(&pool)
-                    .await
-                    .map_err(|e| format!("Failed to link tag: {}", e))?;
-            }
-        }
+                result.last_insert_rowid()
+            };
 
-        imported_count += 1;
+            // Link tag to snippet
+            sqlx::query("INSERT INTO snippet_tags (snippet_id, tag_id) VALUES (?, ?)")
+                .bind(snippet_id)
+                .bind(tag_id)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to link tag: {}", e))?;
+        }
     }
 
-    Ok(imported_count)
+    Ok(true)
 }
 
 /// List all available backups
@@ -396,7 +1780,7 @@ pub async fn list_backups(app: AppHandle) -> Result<Vec<BackupInfo>, String> {
         let entry = entry.map_err(|e| format!("Failed to read directory entry: {}", e))?;
         let path = entry.path();
 
-        if path.extension().and_then(|s| s.to_str()) != Some("db") {
+        if !is_backup_file(&path) {
             continue;
         }
 
@@ -412,6 +1796,7 @@ pub async fn list_backups(app: AppHandle) -> Result<Vec<BackupInfo>, String> {
             .as_secs() as i64;
 
         backups.push(BackupInfo {
+            compressed: BackupCompression::from_path(&path) == Some(BackupCompression::Gzip),
             path: path.to_string_lossy().to_string(),
             created_at,
             size_bytes: metadata.len(),
@@ -419,7 +1804,7 @@ pub async fn list_backups(app: AppHandle) -> Result<Vec<BackupInfo>, String> {
     }
 
     // Sort by creation time, newest first
-    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    backups.sort_by_key(|backup| Reverse(backup.created_at));
 
     Ok(backups)
 }
@@ -440,6 +1825,9 @@ pub async fn get_backup_config(app: AppHandle) -> Result<BackupConfig, String> {
 /// Update backup scheduler configuration
 #[tauri::command]
 pub async fn update_backup_config(app: AppHandle, config: BackupConfig) -> Result<(), String> {
+    if let Some(export) = &config.scheduled_export {
+        export.validate()?;
+    }
     let state = app.state::<BackupSchedulerState>();
     let scheduler_lock = state.0.read().await;
 
@@ -451,6 +1839,167 @@ pub async fn update_backup_config(app: AppHandle, config: BackupConfig) -> Resul
     }
 }
 
+/// Prune old backups immediately using the current retention policy
+///
+/// # Returns
+///
+/// A `PruneReport` with the number of deleted backups and the space reclaimed
+#[tauri::command]
+pub async fn prune_backups_now(app: AppHandle) -> Result<PruneReport, String> {
+    let config = get_backup_config(app.clone()).await?;
+    rotate_backups(&app, &config).await
+}
+
+/// Reads all snippets (with tags) from an open backup, keyed by their backup ID
+async fn read_backup_snippets(
+    reader: &mut BackupReader,
+) -> Result<Vec<(i64, SnippetExport)>, String> {
+    let rows = sqlx::query(BACKUP_SNIPPETS_QUERY)
+        .fetch_all(reader.connection())
+        .await
+        .map_err(|e| format!("Failed to read snippets from backup: {}", e))?;
+
+    rows.iter()
+        .map(|row| {
+            let id: i64 = row.try_get("id").map_err(|e| e.to_string())?;
+            Ok((id, snippet_export_from_row(row)?))
+        })
+        .collect()
+}
+
+/// Preview the contents of a backup without restoring it
+///
+/// The backup is opened read-only; the live database is not modified.
+///
+/// # Arguments
+///
+/// * `backup_path` - Path of the backup file to inspect
+///
+/// # Returns
+///
+/// `BackupPreview` with snippet counts and a summary of each snippet, flagging
+/// those whose name already exists in the current database
+///
+/// # Errors
+///
+/// Returns an error string if the backup cannot be opened or read
+///
+/// # Examples
+///
+/// ```javascript
+/// const preview = await invoke('preview_backup', { backupPath: backup.path });
+/// console.log(`${preview.snippet_count} snippets in backup`);
+/// ```
+#[tauri::command]
+pub async fn preview_backup(app: AppHandle, backup_path: String) -> Result<BackupPreview, String> {
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    let current_names: HashSet<String> = sqlx::query_scalar("SELECT name FROM snippets")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| format!("Failed to fetch current snippets: {}", e))?
+        .into_iter()
+        .collect();
+
+    let mut reader = BackupReader::open(&PathBuf::from(&backup_path)).await?;
+    let snippets = read_backup_snippets(&mut reader).await;
+    let tag_count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM tags")
+        .fetch_one(reader.connection())
+        .await
+        .map_err(|e| format!("Failed to count tags in backup: {}", e));
+    reader.close().await;
+
+    let mut snippets: Vec<BackupSnippetSummary> = snippets?
+        .into_iter()
+        .map(|(id, snippet)| BackupSnippetSummary {
+            id,
+            exists_in_current: current_names.contains(&snippet.name),
+            name: snippet.name,
+            description: snippet.description,
+            tags: snippet.tags,
+            updated_at: snippet.updated_at,
+        })
+        .collect();
+    snippets.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(BackupPreview {
+        path: backup_path,
+        snippet_count: snippets.len(),
+        tag_count: tag_count?,
+        snippets,
+    })
+}
+
+/// Merge selected snippets from a backup into the current database
+///
+/// Unlike `restore_database`, this keeps the current database and only
+/// restores the chosen snippets. A snippet whose name already exists is
+/// overwritten with the backup version; the rest are added.
+///
+/// # Arguments
+///
+/// * `backup_path` - Path of the backup file
+/// * `snippet_ids` - IDs (as reported by `preview_backup`) of the snippets to restore
+///
+/// # Returns
+///
+/// The number of snippets restored
+///
+/// # Errors
+///
+/// Returns an error string if the backup fails verification or cannot be read
+///
+/// # Examples
+///
+/// ```javascript
+/// const restored = await invoke('restore_snippets_from_backup', {
+///   backupPath: preview.path,
+///   snippetIds: [3, 7]
+/// });
+/// ```
+#[tauri::command]
+pub async fn restore_snippets_from_backup(
+    app: AppHandle,
+    backup_path: String,
+    snippet_ids: Vec<i64>,
+) -> Result<usize, String> {
+    let backup_file = PathBuf::from(&backup_path);
+    let verification = verify_backup_file(&backup_file).await?;
+    if !verification.valid {
+        return Err("Backup failed verification and was not restored".to_string());
+    }
+
+    let selected: HashSet<i64> = snippet_ids.into_iter().collect();
+    let mut reader = BackupReader::open(&backup_file).await?;
+    let snippets = read_backup_snippets(&mut reader).await;
+    reader.close().await;
+
+    let chosen: Vec<SnippetExport> = snippets?
+        .into_iter()
+        .filter(|(id, _)| selected.contains(id))
+        .map(|(_, snippet)| snippet)
+        .collect();
+
+    let pool = get_pool(&app).map_err(|e| e.to_string())?;
+    let restored = import_snippet_exports(&pool, chosen).await?;
+    library_stats::invalidate(&app).await;
+    name_index::invalidate(&app).await;
+    menubar::notify_snippets_changed(&app);
+    Ok(restored)
+}
+
+/// Get the integrity report and phase timings collected when the app started
+///
+/// # Returns
+///
+/// `StartupReport` including the temporary files removed by the startup janitor
+/// and how long each startup phase took, deferred phases included once they ran
+#[tauri::command]
+pub async fn get_startup_report(app: AppHandle) -> Result<StartupReport, String> {
+    let state = app.state::<StartupReportState>();
+    let report = state.0.read().await;
+    Ok(report.clone())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -467,6 +2016,7 @@ mod tests {
                 tags: vec!["tag1".to_string()],
                 created_at: 1000,
                 updated_at: 2000,
+                ..SnippetExport::default()
             }],
         };
 
@@ -477,6 +2027,244 @@ mod tests {
         assert_eq!(export.snippets.len(), deserialized.snippets.len());
     }
 
+    fn snippet_export(name: &str, content: &str, updated_at: i64) -> SnippetExport {
+        SnippetExport {
+            name: name.to_string(),
+            content: content.to_string(),
+            description: None,
+            tags: Vec::new(),
+            created_at: 0,
+            updated_at,
+            ..SnippetExport::default()
+        }
+    }
+
+    async fn import_with_mode(mode: ImportMode) -> (ImportReport, Vec<(String, String)>) {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        database::run_migrations(&pool).await.unwrap();
+        sqlx::query(
+            "INSERT INTO snippets (name, content, created_at, updated_at)
+             VALUES ('deploy', 'local', 0, 100), ('logs', 'local', 0, 100)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        let mut report = ImportReport::default();
+        for snippet in [
+            snippet_export("deploy", "older", 50),
+            snippet_export("logs", "newer", 200),
+            snippet_export("fresh", "new", 10),
+            snippet_export("", "no name", 10),
+        ] {
+            import_snippet_export(&mut conn, snippet, mode, &mut report)
+                .await
+                .unwrap();
+        }
+        let rows = sqlx::query_as("SELECT name, content FROM snippets ORDER BY name")
+            .fetch_all(&mut *conn)
+            .await
+            .unwrap();
+        (report, rows)
+    }
+
+    fn pairs(rows: &[(&str, &str)]) -> Vec<(String, String)> {
+        rows.iter()
+            .map(|(name, content)| (name.to_string(), content.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_import_mode_skip_and_overwrite() {
+        let (report, rows) = import_with_mode(ImportMode::Skip).await;
+        assert_eq!(report.created, ["fresh"]);
+        assert_eq!(report.skipped, ["deploy", "logs", ""]);
+        assert_eq!(report.conflicted.len(), 2);
+        assert_eq!(
+            rows,
+            pairs(&[("deploy", "local"), ("fresh", "new"), ("logs", "local")])
+        );
+
+        let (report, rows) = import_with_mode(ImportMode::Overwrite).await;
+        assert_eq!(report.updated, ["deploy", "logs"]);
+        assert_eq!(report.imported(), 3);
+        assert_eq!(
+            rows,
+            pairs(&[("deploy", "older"), ("fresh", "new"), ("logs", "newer")])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_mode_rename_and_merge_newer() {
+        let (report, rows) = import_with_mode(ImportMode::Rename).await;
+        assert_eq!(report.created, ["deploy (2)", "logs (2)", "fresh"]);
+        assert_eq!(report.conflicted[0].resolution, ConflictResolution::Renamed);
+        assert_eq!(
+            report.conflicted[0].renamed_to.as_deref(),
+            Some("deploy (2)")
+        );
+        assert_eq!(rows.len(), 5);
+
+        let (report, rows) = import_with_mode(ImportMode::MergeNewer).await;
+        assert_eq!(report.updated, ["logs"]);
+        assert_eq!(report.skipped, ["deploy", ""]);
+        assert_eq!(
+            rows,
+            pairs(&[("deploy", "local"), ("fresh", "new"), ("logs", "newer")])
+        );
+    }
+
+    async fn memory_pool() -> SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        database::run_migrations(&pool).await.unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_export_round_trip_keeps_dynamic_snippet_state() {
+        let source = memory_pool().await;
+        sqlx::query(
+            r#"INSERT INTO snippets
+                (name, content, created_at, updated_at, kind, aliases, parameters, archived,
+                 expires_at)
+               VALUES ('branches', 'git branch --list {{pattern}}', 0, 10, 'dynamic',
+                 '["br"]', '[{"name": "pattern", "type": "text", "default": "*"}]', 1, 500)"#,
+        )
+        .execute(&source)
+        .await
+        .unwrap();
+
+        let export = ExportData {
+            version: EXPORT_FORMAT_VERSION.to_string(),
+            exported_at: 20,
+            snippets: fetch_snippet_exports(&source).await.unwrap(),
+        };
+        let json = serde_json::to_string(&export).unwrap();
+        let parsed = export_format::parse_export(&json).unwrap();
+
+        let target = memory_pool().await;
+        assert_eq!(
+            import_snippet_exports(&target, parsed.snippets)
+                .await
+                .unwrap(),
+            1
+        );
+
+        let imported = fetch_snippet_exports(&target).await.unwrap();
+        let snippet = &imported[0];
+        assert_eq!(snippet.kind, SnippetKind::Dynamic);
+        assert_eq!(snippet.aliases, ["br"]);
+        assert_eq!(snippet.parameters.len(), 1);
+        assert_eq!(snippet.parameters[0].name, "pattern");
+        assert_eq!(snippet.parameters[0].default.as_deref(), Some("*"));
+        assert!(snippet.archived);
+        assert_eq!(snippet.expires_at, Some(500));
+    }
+
+    #[tokio::test]
+    async fn test_import_rolls_back_on_failure() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        database::run_migrations(&pool).await.unwrap();
+
+        let mut broken = snippet_export("broken", "x", 0);
+        // Linking the same tag twice violates the snippet_tags primary key
+        broken.tags = vec!["dup".to_string(), "dup".to_string()];
+        let snippets = vec![snippet_export("fine", "x", 0), broken];
+        assert!(import_snippet_exports(&pool, snippets).await.is_err());
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM snippets")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_preview_snippet_exports_writes_nothing() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        database::run_migrations(&pool).await.unwrap();
+        sqlx::query(
+            "INSERT INTO snippets (name, content, created_at, updated_at)
+             VALUES ('deploy', 'local', 0, 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let snippets = vec![
+            snippet_export("deploy", "x", 1),
+            snippet_export("new", "x", 1),
+        ];
+        let report = preview_snippet_exports(&pool, snippets, ImportMode::Rename)
+            .await
+            .unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.created, ["deploy (2)", "new"]);
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM snippets")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_restore_report_schema_mismatch() {
+        let report = RestoreReport {
+            backup_schema_version: 19,
+            latest_schema_version: 18,
+            ..RestoreReport::default()
+        };
+        assert!(report.schema_mismatch());
+    }
+
+    #[test]
+    fn test_export_filter_matches_tags_and_dates() {
+        let mut snippet = snippet_export("restart", "kubectl", 500);
+        snippet.tags = vec!["K8s/prod".to_string(), "ops".to_string()];
+
+        assert!(ExportFilter::default().matches(&snippet));
+        let by_tag = ExportFilter {
+            tags: vec!["k8s".to_string()],
+            ..ExportFilter::default()
+        };
+        assert!(by_tag.matches(&snippet));
+        let by_prefix = ExportFilter {
+            tags: vec!["k8".to_string()],
+            ..ExportFilter::default()
+        };
+        assert!(!by_prefix.matches(&snippet));
+
+        let in_range = ExportFilter {
+            updated_since: Some(400),
+            updated_until: Some(500),
+            ..by_tag.clone()
+        };
+        assert!(in_range.matches(&snippet));
+        let too_old = ExportFilter {
+            updated_since: Some(501),
+            ..ExportFilter::default()
+        };
+        assert!(!too_old.matches(&snippet));
+    }
+
     #[test]
     fn test_database_stats_structure() {
         let stats = DatabaseStats {
@@ -485,6 +2273,7 @@ mod tests {
             total_analytics_records: 100,
             database_size_bytes: 1024,
             last_backup: Some(1234567890),
+            last_optimization: None,
         };
 
         assert_eq!(stats.total_snippets, 10);
@@ -1,5 +1,9 @@
-use crate::models::tag::Tag;
-use crate::services::tags::{get_all_tags, update_tag_color};
+use crate::models::tag::{Tag, TagReassignment, TagStats, TagTreeNode};
+use crate::models::SnippetKind;
+use crate::services::database::get_pool;
+use crate::services::library_stats;
+use crate::services::tag_suggestions::{self, TagSuggestion, DEFAULT_SUGGESTION_LIMIT};
+use crate::services::tags::{self, get_all_tags, update_tag_color};
 use tauri::AppHandle;
 
 /// Get all tags with their colors
@@ -45,3 +49,197 @@ pub async fn update_tag_color_cmd(
         .await
         .map_err(|e| e.to_string())
 }
+
+/// Rename a tag
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `old_name` - Current name of the tag
+/// * `new_name` - New name for the tag
+///
+/// # Returns
+///
+/// The renamed tag with its unchanged id and color
+///
+/// # Errors
+///
+/// Returns error string if the tag doesn't exist, the new name is empty or
+/// already taken by another tag
+#[tauri::command]
+pub async fn rename_tag(app: AppHandle, old_name: String, new_name: String) -> Result<Tag, String> {
+    let pool = get_pool(&app)?;
+    let tag = tags::rename_tag(&pool, &old_name, &new_name)
+        .await
+        .map_err(|e| e.to_string())?;
+    library_stats::invalidate(&app).await;
+    Ok(tag)
+}
+
+/// Merge one tag into another
+///
+/// Every snippet tagged `source` is tagged `target` instead, and `source`
+/// is deleted, all in one transaction.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `source` - Tag to merge away
+/// * `target` - Tag that takes over the snippets
+///
+/// # Errors
+///
+/// Returns error string if either tag doesn't exist or they are the same tag
+#[tauri::command]
+pub async fn merge_tags(
+    app: AppHandle,
+    source: String,
+    target: String,
+) -> Result<TagReassignment, String> {
+    let pool = get_pool(&app)?;
+    let report = tags::merge_tags(&pool, &source, &target)
+        .await
+        .map_err(|e| e.to_string())?;
+    library_stats::invalidate(&app).await;
+    Ok(report)
+}
+
+/// Delete a tag, optionally moving its snippets to another tag
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `name` - Tag to delete
+/// * `reassign_to` - Optional tag that takes over the snippets; without it
+///   the snippets just lose the tag
+///
+/// # Errors
+///
+/// Returns error string if either tag doesn't exist
+#[tauri::command]
+pub async fn delete_tag(
+    app: AppHandle,
+    name: String,
+    reassign_to: Option<String>,
+) -> Result<TagReassignment, String> {
+    let pool = get_pool(&app)?;
+    let report = tags::delete_tag(&pool, &name, reassign_to.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+    library_stats::invalidate(&app).await;
+    Ok(report)
+}
+
+/// Get every tag with its snippet count and last-used timestamp
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+///
+/// # Returns
+///
+/// Usage statistics per tag, sorted by name; unused tags have a count of 0
+///
+/// # Errors
+///
+/// Returns error string if database operations fail
+#[tauri::command]
+pub async fn get_tag_stats(app: AppHandle) -> Result<Vec<TagStats>, String> {
+    let pool = get_pool(&app)?;
+    tags::get_tag_stats(&pool).await.map_err(|e| e.to_string())
+}
+
+/// Delete tags that are not associated with any snippet
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+///
+/// # Returns
+///
+/// Names of the deleted tags
+///
+/// # Errors
+///
+/// Returns error string if database operations fail
+#[tauri::command]
+pub async fn cleanup_orphan_tags(app: AppHandle) -> Result<Vec<String>, String> {
+    let pool = get_pool(&app)?;
+    tags::cleanup_orphan_tags(&pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Get all tags arranged as a hierarchy
+///
+/// Tags are paths such as `lang/rust/async`; each `/` nests a level.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+///
+/// # Returns
+///
+/// The top-level nodes with their nested levels and snippet counts
+///
+/// # Errors
+///
+/// Returns error string if database operations fail
+#[tauri::command]
+pub async fn get_tag_tree(app: AppHandle) -> Result<Vec<TagTreeNode>, String> {
+    let pool = get_pool(&app)?;
+    tags::get_tag_tree(&pool).await.map_err(|e| e.to_string())
+}
+
+/// Suggest tags for a snippet from its name and content
+///
+/// Combines the detected language, existing tags that appear as words in
+/// the text, and the tags of similar snippets in the library.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `name` - Optional snippet name
+/// * `content` - Snippet content
+/// * `kind` - Optional snippet kind (default: static)
+/// * `current_tags` - Optional tags already on the snippet; never suggested
+/// * `limit` - Optional maximum number of suggestions (default: 5)
+///
+/// # Returns
+///
+/// Ranked suggestions, best first
+///
+/// # Errors
+///
+/// Returns error string if database operations fail
+///
+/// # Examples
+///
+/// ```typescript
+/// const suggestions = await invoke('suggest_tags_for_content', {
+///   name: 'deploy.sh',
+///   content: 'git push origin main',
+///   currentTags: ['deploy'],
+/// });
+/// ```
+#[tauri::command]
+pub async fn suggest_tags_for_content(
+    app: AppHandle,
+    name: Option<String>,
+    content: String,
+    kind: Option<SnippetKind>,
+    current_tags: Option<Vec<String>>,
+    limit: Option<usize>,
+) -> Result<Vec<TagSuggestion>, String> {
+    let pool = get_pool(&app)?;
+    tag_suggestions::suggest_tags_for_content(
+        &pool,
+        name.as_deref().unwrap_or("").trim(),
+        content.trim(),
+        kind.unwrap_or_default(),
+        &current_tags.unwrap_or_default(),
+        limit.unwrap_or(DEFAULT_SUGGESTION_LIMIT),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,89 @@
+use crate::models::SnippetId;
+use crate::services::database::get_pool;
+use crate::services::team_feeds::{self, FeedKind, FeedSyncReport, SnippetFeed, SnippetProvenance};
+use tauri::AppHandle;
+
+/// Subscribe to a team's snippet feed
+///
+/// The feed is synced right away and then every `interval_hours`. Its
+/// snippets are read-only, named `<name>/<snippet>` and tagged `team/<name>`.
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `name` - Name of the team collection
+/// * `url` - URL of a Snips JSON export, or of a Git repository
+/// * `kind` - `json` or `git`; guessed from the URL when omitted
+/// * `interval_hours` - Hours between syncs (default: 24)
+///
+/// # Returns
+///
+/// The feed. A failed first sync is reported in its `last_error` rather than
+/// failing the subscription.
+///
+/// # Examples
+///
+/// ```typescript
+/// const feed = await invoke('subscribe_feed', {
+///   name: 'platform',
+///   url: 'https://github.com/acme/platform-snippets.git',
+/// });
+/// ```
+#[tauri::command]
+pub async fn subscribe_feed(
+    app: AppHandle,
+    name: String,
+    url: String,
+    kind: Option<FeedKind>,
+    interval_hours: Option<u32>,
+) -> Result<SnippetFeed, String> {
+    let pool = get_pool(&app)?;
+    let feed = team_feeds::subscribe(&pool, &name, &url, kind, interval_hours).await?;
+    if let Err(e) = team_feeds::sync_feed(&app, feed.id).await {
+        eprintln!("First sync of feed {} failed: {}", feed.name, e);
+    }
+    Ok(team_feeds::get_feed(&pool, feed.id).await?)
+}
+
+/// Unsubscribe from a feed and remove its snippets
+///
+/// Local forks of its snippets are kept.
+#[tauri::command]
+pub async fn unsubscribe_feed(app: AppHandle, id: i64) -> Result<(), String> {
+    Ok(team_feeds::remove_feed(&app, id).await?)
+}
+
+/// List the subscribed feeds with their sync state
+#[tauri::command]
+pub async fn list_feeds(app: AppHandle) -> Result<Vec<SnippetFeed>, String> {
+    let pool = get_pool(&app)?;
+    Ok(team_feeds::list_feeds(&pool).await?)
+}
+
+/// Sync a feed now, regardless of its schedule
+///
+/// # Errors
+///
+/// Returns an error string if the feed cannot be downloaded or parsed; the
+/// error is also kept as the feed's `last_error`
+///
+/// # Examples
+///
+/// ```typescript
+/// const report = await invoke('sync_feed_now', { id: 1 });
+/// ```
+#[tauri::command]
+pub async fn sync_feed_now(app: AppHandle, id: i64) -> Result<FeedSyncReport, String> {
+    Ok(team_feeds::sync_feed(&app, id).await?)
+}
+
+/// Get where a snippet came from: the feed it is synced from, or the feed
+/// snippet it was forked from
+#[tauri::command]
+pub async fn get_snippet_provenance(
+    app: AppHandle,
+    id: SnippetId,
+) -> Result<SnippetProvenance, String> {
+    let pool = get_pool(&app)?;
+    Ok(team_feeds::get_provenance(&pool, id.0).await?)
+}
@@ -0,0 +1,198 @@
+use crate::commands::settings_commands::{get_settings, update_settings, SettingsServiceState};
+use crate::models::settings::Theme;
+use crate::services::appearance::{self, ResolvedTheme};
+use crate::services::custom_themes::{self, CustomTheme, CUSTOM_THEME_CHANGED_EVENT};
+use crate::services::{pywal, window};
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, State};
+
+/// List the custom themes in the config dir's `themes` folder
+///
+/// Files that are not valid themes are skipped.
+///
+/// # Examples
+///
+/// ```typescript
+/// const themes = await invoke('list_custom_themes');
+/// ```
+#[tauri::command]
+pub async fn list_custom_themes(app: AppHandle) -> Result<Vec<CustomTheme>, String> {
+    let dir = custom_themes::themes_dir(&app)?;
+    Ok(custom_themes::list_themes(&dir)?)
+}
+
+/// Save a custom theme, replacing any theme with the same name
+///
+/// If the saved theme is the active one, open windows pick up the change.
+///
+/// # Returns
+///
+/// The theme as saved
+///
+/// # Examples
+///
+/// ```typescript
+/// await invoke('save_custom_theme', {
+///   theme: { name: 'Nord', dark: true, variables: { background: '220 16% 22%' } }
+/// });
+/// ```
+#[tauri::command]
+pub async fn save_custom_theme(
+    app: AppHandle,
+    theme: CustomTheme,
+    settings_state: State<'_, SettingsServiceState>,
+) -> Result<CustomTheme, String> {
+    let dir = custom_themes::themes_dir(&app)?;
+    let saved = custom_themes::save_theme(&dir, theme)?;
+
+    let settings = get_settings(app.clone(), settings_state).await?;
+    if settings.custom_theme.as_deref() == Some(saved.name.as_str()) {
+        emit_custom_theme(&app, Some(&saved));
+    }
+    Ok(saved)
+}
+
+/// Import a pywal or wallust color scheme as a custom theme
+///
+/// # Arguments
+///
+/// * `path` - Scheme to import; defaults to `~/.cache/wal/colors.json`
+/// * `name` - Theme name; defaults to `Pywal`, replacing the last import
+///
+/// # Returns
+///
+/// The theme as saved; apply it with `apply_custom_theme`
+///
+/// # Examples
+///
+/// ```typescript
+/// const theme = await invoke('import_pywal_theme', { path: null, name: null });
+/// await invoke('apply_custom_theme', { name: theme.name });
+/// ```
+#[tauri::command]
+pub async fn import_pywal_theme(
+    app: AppHandle,
+    path: Option<String>,
+    name: Option<String>,
+    settings_state: State<'_, SettingsServiceState>,
+) -> Result<CustomTheme, String> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => pywal::default_colors_path()
+            .ok_or("Could not find the pywal cache directory; pass the colors.json path")?,
+    };
+    let json = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let name = name.unwrap_or_else(|| pywal::DEFAULT_THEME_NAME.to_string());
+    let theme = pywal::theme_from_pywal(&name, &json)?;
+
+    save_custom_theme(app, theme, settings_state).await
+}
+
+/// Delete a custom theme
+///
+/// Deleting the active theme switches back to the built-in palette.
+#[tauri::command]
+pub async fn delete_custom_theme(
+    app: AppHandle,
+    name: String,
+    settings_state: State<'_, SettingsServiceState>,
+) -> Result<(), String> {
+    let dir = custom_themes::themes_dir(&app)?;
+    custom_themes::delete_theme(&dir, &name)?;
+
+    let settings = get_settings(app.clone(), settings_state.clone()).await?;
+    if settings.custom_theme.as_deref() == Some(name.trim()) {
+        apply_custom_theme(app, None, settings_state).await?;
+    }
+    Ok(())
+}
+
+/// Make a custom theme the active one, or pass `null` to go back to the
+/// built-in light and dark palettes
+///
+/// The choice is saved in settings, the native window chrome follows the
+/// theme's `dark` flag, and every window is sent `custom-theme-changed`.
+///
+/// # Returns
+///
+/// The theme now active, if any
+///
+/// # Examples
+///
+/// ```typescript
+/// await invoke('apply_custom_theme', { name: 'Nord' });
+/// await invoke('apply_custom_theme', { name: null });
+/// ```
+#[tauri::command]
+pub async fn apply_custom_theme(
+    app: AppHandle,
+    name: Option<String>,
+    settings_state: State<'_, SettingsServiceState>,
+) -> Result<Option<CustomTheme>, String> {
+    let theme = match name {
+        Some(name) => {
+            let dir = custom_themes::themes_dir(&app)?;
+            Some(custom_themes::load_theme(&dir, &name)?)
+        }
+        None => None,
+    };
+
+    let mut settings = get_settings(app.clone(), settings_state.clone()).await?;
+    settings.custom_theme = theme.as_ref().map(|t| t.name.clone());
+    let window_theme = match &theme {
+        Some(theme) if theme.dark => Theme::Dark,
+        Some(_) => Theme::Light,
+        None => settings.theme,
+    };
+    update_settings(app.clone(), settings, settings_state).await?;
+
+    window::apply_window_theme(&app, window_theme);
+    emit_custom_theme(&app, theme.as_ref());
+    Ok(theme)
+}
+
+/// Get the active custom theme, if any
+///
+/// Returns `null` when the saved theme has since been deleted or broken, so
+/// the UI falls back to the built-in palette.
+#[tauri::command]
+pub async fn get_active_custom_theme(
+    app: AppHandle,
+    settings_state: State<'_, SettingsServiceState>,
+) -> Result<Option<CustomTheme>, String> {
+    let settings = get_settings(app.clone(), settings_state).await?;
+    let Some(name) = settings.custom_theme else {
+        return Ok(None);
+    };
+    let dir = custom_themes::themes_dir(&app)?;
+    match custom_themes::load_theme(&dir, &name) {
+        Ok(theme) => Ok(Some(theme)),
+        Err(e) => {
+            eprintln!("Active theme '{}' is unavailable: {}", name, e);
+            Ok(None)
+        }
+    }
+}
+
+/// Get whether the UI should be light or dark, with the built-in palette
+///
+/// Resolves the `system` theme against the OS appearance; listen for
+/// `appearance-updated` to follow later changes.
+///
+/// # Examples
+///
+/// ```typescript
+/// const { appearance, palette } = await invoke('get_resolved_theme');
+/// ```
+#[tauri::command]
+pub async fn get_resolved_theme(app: AppHandle) -> Result<ResolvedTheme, String> {
+    let system = appearance::system_appearance(&app);
+    Ok(appearance::resolved_theme(&app, system).await?)
+}
+
+fn emit_custom_theme(app: &AppHandle, theme: Option<&CustomTheme>) {
+    if let Err(e) = app.emit(CUSTOM_THEME_CHANGED_EVENT, theme) {
+        eprintln!("Failed to emit {}: {}", CUSTOM_THEME_CHANGED_EVENT, e);
+    }
+}
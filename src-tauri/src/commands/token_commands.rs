@@ -0,0 +1,47 @@
+use crate::services::database::get_pool;
+use crate::services::token_estimate::{self, ComposedTokenEstimate};
+use tauri::AppHandle;
+
+/// Estimate the LLM token count of snippets, alone and copied together
+///
+/// Counts approximate OpenAI's `cl100k_base` tokenizer; expect the real
+/// count to be within about 15%.
+///
+/// # Arguments
+///
+/// * `app` - Application handle for accessing database pool
+/// * `snippet_ids` - Snippets in copy order; pass one ID for a single snippet
+///
+/// # Returns
+///
+/// Per-snippet counts plus the total for the composed text as
+/// `copy_snippets_with_analytics` would build it
+///
+/// # Examples
+///
+/// ```typescript
+/// const estimate = await invoke('estimate_snippet_tokens', {
+///   snippetIds: [1, 2, 3]
+/// });
+/// const fits = estimate.total_tokens <= 128000;
+/// ```
+#[tauri::command]
+pub async fn estimate_snippet_tokens(
+    app: AppHandle,
+    snippet_ids: Vec<i64>,
+) -> Result<ComposedTokenEstimate, String> {
+    let pool = get_pool(&app)?;
+    Ok(token_estimate::estimate_snippets(&pool, &snippet_ids).await?)
+}
+
+/// Estimate the LLM token count of unsaved text, e.g. while editing
+///
+/// # Examples
+///
+/// ```typescript
+/// const tokens = await invoke('estimate_text_tokens', { text: draft });
+/// ```
+#[tauri::command]
+pub async fn estimate_text_tokens(text: String) -> Result<usize, String> {
+    Ok(token_estimate::estimate_tokens(&text))
+}
@@ -1,6 +1,8 @@
-use tauri::AppHandle;
+use tauri::{AppHandle, WebviewWindow};
 
-use crate::services::window;
+use crate::services::quick_windows;
+use crate::services::resource_monitor::{self, ResourceUsage};
+use crate::services::window::{self, WindowDiagnostics};
 
 /// Shows the search window
 #[tauri::command]
@@ -14,6 +16,13 @@ pub async fn hide_search_window(app: AppHandle) -> Result<(), String> {
     window::hide_search_window(&app).map_err(|e| e.to_string())
 }
 
+/// Hides the calling overlay on Escape if the auto-hide preferences allow;
+/// returns whether it was hidden
+#[tauri::command]
+pub async fn handle_overlay_escape(window: WebviewWindow) -> Result<bool, String> {
+    quick_windows::handle_escape(&window).map_err(|e| e.to_string())
+}
+
 /// Toggles the search window visibility
 #[tauri::command]
 pub async fn toggle_search_window(app: AppHandle) -> Result<(), String> {
@@ -38,8 +47,66 @@ pub async fn show_quick_add_window(app: AppHandle) -> Result<(), String> {
     window::show_quick_add_window(&app).map_err(|e| e.to_string())
 }
 
+/// Forgets remembered window sizes and positions
+///
+/// Open windows go back to their default size, centered; closed windows
+/// open that way next time.
+///
+/// # Examples
+///
+/// ```typescript
+/// await invoke('reset_window_layout');
+/// ```
+#[tauri::command]
+pub async fn reset_window_layout(app: AppHandle) -> Result<(), String> {
+    window::reset_window_layout(&app)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Updates the menubar badge count
 #[tauri::command]
 pub async fn update_badge_count(app: AppHandle, count: u32) -> Result<(), String> {
     crate::services::menubar::update_badge_count(&app, count).map_err(|e| e.to_string())
 }
+
+/// Gets window diagnostics, including whether focus was handed back to the
+/// previously active app after the search window last closed
+///
+/// # Returns
+///
+/// Visibility and focus of the search window plus the last focus restore
+///
+/// # Examples
+///
+/// ```typescript
+/// const diagnostics = await invoke('get_window_diagnostics');
+/// if (diagnostics.last_focus_restore?.success === false) {
+///   console.warn(diagnostics.last_focus_restore.error);
+/// }
+/// ```
+#[tauri::command]
+pub async fn get_window_diagnostics(app: AppHandle) -> Result<WindowDiagnostics, String> {
+    Ok(window::window_diagnostics(&app))
+}
+
+/// Gets the app's own resource usage and any suspected leaks
+///
+/// # Returns
+///
+/// The current sample (RSS, open file descriptors, threads, tokio tasks,
+/// open windows), up to an hour of per-minute history, and the resources
+/// that have been growing steadily over that history
+///
+/// # Examples
+///
+/// ```typescript
+/// const usage = await invoke('get_resource_usage');
+/// for (const warning of usage.warnings) {
+///   console.warn(`${warning.resource}: ${warning.first_value} -> ${warning.last_value}`);
+/// }
+/// ```
+#[tauri::command]
+pub async fn get_resource_usage(app: AppHandle) -> Result<ResourceUsage, String> {
+    Ok(resource_monitor::get_resource_usage(&app).await)
+}
@@ -3,10 +3,27 @@ pub mod models;
 pub mod services;
 pub mod utils;
 
+use services::analytics::{self, UsageQueue};
+use services::api_server::ApiServerState;
+use services::appearance::AppearanceState;
 use services::backup_scheduler::{BackupScheduler, BackupSchedulerState};
+use services::confirmation::ConfirmationState;
 use services::database::{self, DbPool};
+use services::expander::ExpanderState;
+use services::focus::FocusState;
+use services::library_stats::LibraryStatsState;
+use services::name_index::NameIndexState;
+use services::overlay_placement::OverlayPlacementState;
+use services::portal_shortcuts::PortalShortcutState;
+use services::quick_windows::QuickWindowState;
+use services::resource_monitor::ResourceMonitorState;
+use services::settings::SettingsService;
+use services::startup::{DeferredStartup, StartupReport, StartupReportState, StartupTimer};
+use services::tray::TrayState;
+use services::window_layout::WindowLayoutState;
 use std::sync::Arc;
-use tauri::Manager;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
 use tokio::sync::{Mutex, RwLock};
 
 // Re-export commands for use in tests and external crates
@@ -16,40 +33,18 @@ pub use commands::*;
 fn init_system_tray(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     use tauri::{
         image::Image,
-        menu::{Menu, MenuItem},
         tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     };
 
-    // Create menu items
-    let search_item = MenuItem::with_id(
-        app,
-        "search",
-        "Search Snippets",
-        true,
-        Some("CmdOrCtrl+Shift+S"),
-    )?;
-    let quick_add_item = MenuItem::with_id(
-        app,
-        "quick-add",
-        "Quick Add",
-        true,
-        Some("CmdOrCtrl+Shift+A"),
-    )?;
-    let settings_item = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
-    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, Some("CmdOrCtrl+Q"))?;
-
-    // Build the menu
-    let menu = Menu::with_items(
-        app,
-        &[&search_item, &quick_add_item, &settings_item, &quit_item],
-    )?;
+    // The "Recent" submenu is filled in once the tray exists
+    let menu = services::menubar::build_tray_menu(app.handle(), &[])?;
 
     // Load the tray icon (menubar icon for macOS)
     let icon_bytes = include_bytes!("../icons/icon-menubar.png");
     let icon = Image::from_bytes(icon_bytes)?;
 
     // Build and configure the tray icon
-    let _tray = TrayIconBuilder::with_id("main-tray")
+    let _tray = TrayIconBuilder::with_id(services::tray::TRAY_ID)
         .icon(icon)
         .menu(&menu)
         .tooltip("Snips - Snippet Manager")
@@ -68,57 +63,228 @@ fn init_system_tray(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Erro
         })
         .build(app)?;
 
+    // Keep the "Recent" submenu and today's usage count in step with snippet usage and edits
+    services::menubar::start(app.handle());
+    services::tray::start(app.handle());
+
     Ok(())
 }
 
+/// Starts the services that can wait until a window is on screen
+///
+/// Runs once, on the first page load or after `startup::DEFERRED_FALLBACK`,
+/// and adds each phase's timing to the startup report.
+fn start_deferred_services(app: &AppHandle) {
+    // Setup has not finished, or the database failed to open
+    if app.try_state::<DbPool>().is_none() {
+        return;
+    }
+    let Some(mut timer) = app.state::<DeferredStartup>().claim() else {
+        return;
+    };
+    let started_at_ms = timer.elapsed_ms();
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let pool = handle.state::<DbPool>().get();
+
+        // Detect languages for snippets saved before detection existed
+        let began = Instant::now();
+        if let Err(e) = services::language::backfill_languages(&pool).await {
+            eprintln!("Failed to backfill snippet languages: {}", e);
+        }
+        timer.record("language_backfill", began);
+
+        // The first run backs up the database right away, so it waits for the window
+        let began = Instant::now();
+        let backups = handle.state::<BackupSchedulerState>();
+        if let Some(scheduler) = backups.0.read().await.as_ref() {
+            scheduler.start().await;
+        }
+        timer.record("backup_scheduler", began);
+
+        // Start the HTTP API server if the user enabled it
+        let began = Instant::now();
+        match SettingsService::new(pool.clone()).get_settings().await {
+            Ok(settings) => {
+                services::api_server::apply_settings(&handle, &settings.api_server).await
+            }
+            Err(e) => eprintln!("Skipping API server startup: {}", e),
+        }
+        timer.record("api_server", began);
+
+        // Watch typing for expansion triggers if the user enabled it
+        let began = Instant::now();
+        match SettingsService::new(pool).get_settings().await {
+            Ok(settings) => {
+                services::expander::apply_settings(&handle, &settings.expansion_settings).await
+            }
+            Err(e) => eprintln!("Skipping text expansion startup: {}", e),
+        }
+        timer.record("expander", began);
+
+        // Move credentials saved in settings by earlier versions to the keychain
+        let began = Instant::now();
+        if let Err(e) = services::keyring::migrate_settings_credentials(&handle).await {
+            eprintln!("Failed to move credentials to the OS keychain: {}", e);
+        }
+        timer.record("credential_migration", began);
+
+        // Keep snippets that mirror a remote file current (opt-in in settings)
+        let began = Instant::now();
+        services::source_refresh::start(handle.clone());
+        timer.record("source_refresh", began);
+
+        // Sync subscribed team snippet feeds on their schedules
+        let began = Instant::now();
+        services::team_feeds::start(handle.clone());
+        timer.record("team_feeds", began);
+
+        // Archive expired snippets (opt-in in settings)
+        let began = Instant::now();
+        services::expiration::start(handle.clone());
+        timer.record("expiration", began);
+
+        // Sample our own memory, descriptors and tasks to catch slow leaks
+        let began = Instant::now();
+        services::resource_monitor::start(handle.clone());
+        timer.record("resource_monitor", began);
+
+        // Reopen the database when a sync tool replaces the file
+        let began = Instant::now();
+        services::db_watcher::start(handle.clone());
+        timer.record("db_watcher", began);
+
+        services::startup::record_deferred(&handle, timer, started_at_ms).await;
+    });
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let launched = Instant::now();
+
     tauri::Builder::default()
+        // Registered first so a second launch hands over to the running instance and exits
+        // before it opens the database, registers shortcuts or adds a tray icon
+        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            if let Err(e) = services::window::show_search_window(app) {
+                eprintln!("Failed to show search window for second launch: {}", e);
+            }
+        }))
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(database::init_database().build())
-        .setup(|app| {
-            // Initialize SQLx database pool for backend queries
-            let handle = app.handle().clone();
-            let result = tauri::async_runtime::block_on(async move {
-                match database::init_db_pool(&handle).await {
-                    Ok(pool) => {
-                        handle.manage(DbPool(pool));
-                        // Initialize settings service state (lazy initialization)
-                        handle.manage(commands::settings_commands::SettingsServiceState(
-                            Mutex::new(None),
-                        ));
-                        // Initialize backup scheduler state
-                        handle.manage(BackupSchedulerState(Arc::new(RwLock::new(None))));
-
-                        // Initialize and start backup scheduler
-                        let scheduler = BackupScheduler::new(handle.clone());
-                        tauri::async_runtime::spawn(async move {
-                            scheduler.start().await;
-                        });
+        .setup(move |app| {
+            let mut timer = StartupTimer::new(launched);
 
-                        // Store scheduler in state (already in async context, no block_on needed)
-                        let state = handle.state::<BackupSchedulerState>();
-                        let mut scheduler_lock = state.0.write().await;
-                        *scheduler_lock = Some(BackupScheduler::new(handle.clone()));
+            // Clean up temp files left behind by a previous crash before touching the database
+            let began = Instant::now();
+            let janitor = match app.path().app_data_dir() {
+                Ok(app_dir) => {
+                    services::janitor::clean_app_dir(&app_dir, std::time::SystemTime::now())
+                }
+                Err(e) => {
+                    eprintln!("Skipping startup cleanup, no app data dir: {}", e);
+                    Default::default()
+                }
+            };
+            for removed in &janitor.removed {
+                println!("Removed stale {:?}: {}", removed.kind, removed.path);
+            }
+            timer.record("janitor", began);
 
-                        Ok(())
-                    }
+            app.manage(DeferredStartup::new(launched));
+            app.manage(LibraryStatsState::default());
+            app.manage(UsageQueue::default());
+            app.manage(NameIndexState::default());
+            app.manage(FocusState::default());
+            app.manage(ConfirmationState::default());
+            app.manage(ApiServerState::default());
+            app.manage(ExpanderState::default());
+            app.manage(ResourceMonitorState::default());
+            app.manage(TrayState::default());
+            app.manage(WindowLayoutState::default());
+            app.manage(OverlayPlacementState::default());
+            app.manage(QuickWindowState::default());
+            app.manage(PortalShortcutState::default());
+            app.manage(AppearanceState::default());
+
+            // Compositor rules must be in place before the overlays are first shown
+            let began = Instant::now();
+            services::window::install_compositor_rules();
+            // The search window comes from tauri.conf.json, not a builder
+            if let Ok(search) = services::window::get_search_window(app.handle()) {
+                services::quick_windows::attach(&search);
+            }
+            timer.record("windows", began);
+
+            // Initialize SQLx database pool for backend queries
+            let handle = app.handle().clone();
+            let result = tauri::async_runtime::block_on(async {
+                let began = Instant::now();
+                let pool = match database::init_db_pool(&handle).await {
+                    Ok(pool) => pool,
                     Err(e) => {
                         eprintln!("Failed to initialize database pool: {}", e);
-                        Err(Box::new(e) as Box<dyn std::error::Error>)
+                        return Err(Box::new(e) as Box<dyn std::error::Error>);
                     }
+                };
+                handle.manage(DbPool::new(pool));
+                timer.record("database", began);
+
+                let began = Instant::now();
+                // Remembered window sizes and positions, restored as windows open
+                if let Err(e) = services::window_layout::init(&handle).await {
+                    eprintln!("Failed to load window layout: {}", e);
+                }
+                // Monitor the search and Quick Add overlays open on
+                if let Err(e) = services::overlay_placement::start(&handle).await {
+                    eprintln!("Failed to load overlay placement: {}", e);
                 }
+                // Whether the overlays hide on focus loss and Escape
+                if let Err(e) = services::quick_windows::start(&handle).await {
+                    eprintln!("Failed to load overlay auto-hide preferences: {}", e);
+                }
+                timer.record("window_preferences", began);
+
+                // Initialize settings service state (lazy initialization)
+                handle.manage(commands::settings_commands::SettingsServiceState(
+                    Mutex::new(None),
+                ));
+                // The scheduler is stored now so its config can be read and updated, and
+                // started with the deferred services. The running instance is the one
+                // stored in state so config updates reach the background loop.
+                handle.manage(BackupSchedulerState(Arc::new(RwLock::new(Some(
+                    BackupScheduler::new(handle.clone()),
+                )))));
+
+                // Write usage analytics in batches instead of once per copy
+                analytics::start(handle.clone());
+
+                Ok(())
             });
 
             // Initialize the system tray/menubar
+            let began = Instant::now();
             init_system_tray(app)?;
+            timer.record("tray", began);
 
             // Register global shortcuts
+            let began = Instant::now();
             if let Err(e) = services::shortcuts::register_all_shortcuts(app.handle()) {
                 eprintln!("Warning: Failed to register global shortcuts: {}", e);
                 // Don't fail app startup if shortcuts fail to register
             }
+            timer.record("shortcuts", began);
+
+            // Register numbered slot shortcuts for assigned favorites
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = commands::shortcut_commands::sync_slot_shortcuts(&handle).await {
+                    eprintln!("Warning: Failed to register slot shortcuts: {}", e);
+                }
+            });
 
             // Set up menu event handlers
             app.on_menu_event(move |app, event| match event.id().as_ref() {
@@ -140,54 +306,228 @@ pub fn run() {
                 "quit" => {
                     app.exit(0);
                 }
-                _ => {}
+                id => {
+                    if let Some(snippet_id) = services::menubar::parse_recent_item_id(id) {
+                        let app = app.clone();
+                        tauri::async_runtime::spawn(async move {
+                            if let Err(e) =
+                                commands::analytics_commands::copy_snippets_with_analytics(
+                                    app,
+                                    vec![snippet_id],
+                                    None,
+                                    Some(models::analytics::UsageSource::Tray),
+                                    None,
+                                    None,
+                                    None,
+                                )
+                                .await
+                            {
+                                eprintln!("Failed to copy recent snippet: {}", e);
+                            }
+                        });
+                    }
+                }
             });
 
+            // Start the deferred services even if no window ever loads
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(services::startup::DEFERRED_FALLBACK).await;
+                start_deferred_services(&handle);
+            });
+
+            let setup_ms = timer.elapsed_ms();
+            app.manage(StartupReportState(RwLock::new(StartupReport {
+                generated_at: utils::time::current_timestamp(),
+                janitor,
+                phases: timer.into_phases(),
+                setup_ms,
+                deferred_at_ms: None,
+            })));
+
             result
         })
+        .on_window_event(|window, event| {
+            // Only sent while the window follows the system appearance
+            if let tauri::WindowEvent::ThemeChanged(theme) = event {
+                services::appearance::handle_system_change(window.app_handle(), *theme);
+            }
+        })
+        .on_page_load(|webview, payload| {
+            if payload.event() == tauri::webview::PageLoadEvent::Finished {
+                start_deferred_services(webview.app_handle());
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             commands::snippet_commands::create_snippet,
+            commands::snippet_commands::create_snippet_with_resolution,
+            commands::snippet_commands::append_to_snippet,
             commands::snippet_commands::get_snippet,
             commands::snippet_commands::get_all_snippets,
             commands::snippet_commands::update_snippet,
             commands::snippet_commands::delete_snippet,
+            commands::snippet_commands::toggle_pin_snippet,
+            commands::snippet_commands::get_stale_snippets,
+            commands::snippet_commands::archive_snippets,
+            commands::snippet_commands::archive_snippet,
+            commands::snippet_commands::unarchive_snippet,
+            commands::snippet_commands::set_snippet_expiration,
+            commands::snippet_commands::get_expiring_snippets,
+            commands::snippet_commands::reorder_snippets,
+            commands::snippet_commands::detect_snippet_language,
+            commands::snippet_commands::get_snippet_parameters,
+            commands::snippet_commands::set_snippet_parameters,
+            commands::snippet_commands::get_snippet_aliases,
+            commands::snippet_commands::set_snippet_aliases,
+            commands::snippet_commands::add_snippet_alias,
+            commands::snippet_commands::remove_snippet_alias,
+            commands::snippet_commands::render_snippet_with_parameters,
+            commands::attachment_commands::create_image_snippet,
+            commands::attachment_commands::attach_image_file,
+            commands::attachment_commands::get_snippet_attachments,
+            commands::attachment_commands::delete_snippet_attachment,
+            commands::source_commands::get_snippet_source,
+            commands::source_commands::set_snippet_source,
+            commands::source_commands::refresh_snippet_source,
+            commands::source_commands::get_snippet_versions,
             commands::search_commands::search_snippets,
+            commands::search_commands::batch_search,
+            commands::search_commands::get_pinned_snippets,
+            commands::search_commands::get_recent_snippets,
+            commands::search_commands::get_frequent_snippets,
+            commands::search_commands::explain_search_ranking,
+            commands::preview_commands::render_snippet_preview,
             commands::analytics_commands::record_snippet_usage,
             commands::analytics_commands::get_snippet_analytics,
             commands::analytics_commands::get_global_analytics,
+            commands::analytics_commands::get_usage_trends,
+            commands::analytics_commands::get_productivity_summary,
+            commands::analytics_commands::get_analytics_by_tag,
+            commands::analytics_commands::get_analytics_by_source,
             commands::analytics_commands::copy_snippets_with_analytics,
+            commands::analytics_commands::compose_snippets,
+            commands::analytics_commands::copy_snippets_as_markdown,
+            commands::analytics_commands::copy_last_used_snippet,
+            commands::analytics_commands::copy_slot_snippet,
             commands::analytics_commands::clear_all_analytics,
             commands::analytics_commands::clear_analytics_before,
+            commands::analytics_commands::get_analytics_consent_state,
             commands::analytics_commands::export_analytics_to_json,
+            commands::analytics_commands::export_analytics_to_csv,
+            commands::analytics_commands::get_library_statistics,
+            commands::api_token_commands::create_api_token,
+            commands::api_token_commands::list_api_tokens,
+            commands::api_token_commands::revoke_api_token,
+            commands::api_token_commands::get_api_token_audit,
             commands::settings_commands::get_settings,
             commands::settings_commands::update_settings,
             commands::settings_commands::get_storage_type,
             commands::settings_commands::set_storage_type,
+            commands::settings_commands::get_api_server_status,
+            commands::settings_commands::export_settings,
+            commands::settings_commands::import_settings,
+            commands::settings_commands::get_invalid_settings_sections,
             commands::window_commands::show_search_window,
             commands::window_commands::hide_search_window,
+            commands::window_commands::handle_overlay_escape,
             commands::window_commands::toggle_search_window,
             commands::window_commands::show_management_window,
             commands::window_commands::show_settings_window,
             commands::window_commands::show_quick_add_window,
             commands::window_commands::update_badge_count,
+            commands::window_commands::get_window_diagnostics,
+            commands::window_commands::get_resource_usage,
+            commands::window_commands::reset_window_layout,
             commands::shortcut_commands::get_default_shortcuts,
             commands::shortcut_commands::register_custom_shortcut,
             commands::shortcut_commands::unregister_shortcut,
             commands::shortcut_commands::is_shortcut_valid,
+            commands::shortcut_commands::check_shortcut,
             commands::shortcut_commands::reregister_default_shortcuts,
+            commands::shortcut_commands::assign_snippet_slot,
+            commands::shortcut_commands::get_slot_assignments,
             commands::clipboard_commands::get_selected_text,
             commands::clipboard_commands::copy_to_clipboard,
+            commands::clipboard_commands::copy_to_clipboard_rich,
+            commands::clipboard_commands::probe_clipboard_support,
+            commands::dynamic_snippet_commands::get_dynamic_snippet_commands,
+            commands::dynamic_snippet_commands::get_dynamic_snippet_environment,
+            commands::expander_commands::set_text_expansion_enabled,
+            commands::expander_commands::get_text_expansion_status,
+            commands::expander_commands::set_snippet_expansion_trigger,
+            commands::expander_commands::get_expansion_triggers,
             commands::storage_commands::backup_database,
             commands::storage_commands::restore_database,
+            commands::storage_commands::factory_reset,
             commands::storage_commands::get_database_stats,
+            commands::storage_commands::get_database_diagnostics,
+            commands::storage_commands::export_diagnostics_bundle,
+            commands::storage_commands::optimize_database,
+            commands::storage_commands::repair_database,
+            commands::storage_commands::check_search_index_consistency,
+            commands::storage_commands::rebuild_search_index,
             commands::storage_commands::export_to_json,
             commands::storage_commands::import_from_json,
+            commands::storage_commands::get_resumable_import,
+            commands::storage_commands::list_unfinished_imports,
+            commands::storage_commands::discard_import_job,
+            commands::storage_commands::export_to_markdown,
+            commands::storage_commands::import_from_markdown,
+            commands::storage_commands::export_to_vscode_snippets,
+            commands::storage_commands::import_from_vscode_snippets,
+            commands::storage_commands::import_from_snippet_app,
+            commands::gist_commands::import_from_gist,
+            commands::gist_commands::export_snippet_to_gist,
+            commands::gist_commands::set_gist_access_token,
+            commands::gist_commands::has_gist_access_token,
+            commands::share_commands::share_snippet,
+            commands::share_commands::import_shared_snippet,
+            commands::team_feed_commands::subscribe_feed,
+            commands::team_feed_commands::unsubscribe_feed,
+            commands::team_feed_commands::list_feeds,
+            commands::team_feed_commands::sync_feed_now,
+            commands::team_feed_commands::get_snippet_provenance,
             commands::storage_commands::list_backups,
             commands::storage_commands::get_backup_config,
             commands::storage_commands::update_backup_config,
+            commands::storage_commands::prune_backups_now,
+            commands::storage_commands::verify_backup,
+            commands::storage_commands::preview_backup,
+            commands::storage_commands::restore_snippets_from_backup,
+            commands::storage_commands::get_startup_report,
+            commands::theme_commands::list_custom_themes,
+            commands::theme_commands::save_custom_theme,
+            commands::theme_commands::delete_custom_theme,
+            commands::theme_commands::import_pywal_theme,
+            commands::theme_commands::apply_custom_theme,
+            commands::theme_commands::get_active_custom_theme,
+            commands::theme_commands::get_resolved_theme,
+            commands::token_commands::estimate_snippet_tokens,
+            commands::token_commands::estimate_text_tokens,
             commands::tag_commands::get_tags,
-            commands::tag_commands::update_tag_color_cmd
+            commands::tag_commands::update_tag_color_cmd,
+            commands::tag_commands::rename_tag,
+            commands::tag_commands::merge_tags,
+            commands::tag_commands::delete_tag,
+            commands::tag_commands::get_tag_stats,
+            commands::tag_commands::cleanup_orphan_tags,
+            commands::tag_commands::get_tag_tree,
+            commands::tag_commands::suggest_tags_for_content
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Write the usage still waiting for the next batch
+            if let tauri::RunEvent::Exit = event {
+                if let (Some(queue), Some(pool)) =
+                    (app.try_state::<UsageQueue>(), app.try_state::<DbPool>())
+                {
+                    let pool = pool.get();
+                    let flush = analytics::flush_usage(&pool, &queue);
+                    if let Err(e) = tauri::async_runtime::block_on(flush) {
+                        eprintln!("Failed to write queued usage on exit: {}", e);
+                    }
+                }
+            }
+        });
 }
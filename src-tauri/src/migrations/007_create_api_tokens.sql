@@ -0,0 +1,28 @@
+-- Create API tokens table for the local REST API
+-- Only a SHA-256 hash of each token is stored; the plaintext is shown once at creation
+CREATE TABLE api_tokens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    token_prefix TEXT NOT NULL,
+    scopes TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    last_used_at INTEGER,
+    revoked_at INTEGER
+);
+
+-- Create audit trail of token usage, including rejected requests
+CREATE TABLE api_token_audit (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token_id INTEGER,
+    method TEXT NOT NULL,
+    route TEXT NOT NULL,
+    required_scope TEXT,
+    allowed INTEGER NOT NULL,
+    reason TEXT,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (token_id) REFERENCES api_tokens(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_api_token_audit_created_at ON api_token_audit(created_at);
+CREATE INDEX idx_api_token_audit_token_id ON api_token_audit(token_id);
@@ -0,0 +1,14 @@
+-- Create import jobs table so interrupted imports can be resumed
+-- A row exists only while an import is unfinished; it is deleted on completion
+CREATE TABLE import_jobs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    file_path TEXT NOT NULL,
+    file_hash TEXT NOT NULL,
+    format TEXT NOT NULL,
+    total_items INTEGER NOT NULL,
+    processed_items INTEGER NOT NULL DEFAULT 0,
+    imported_items INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    UNIQUE (file_hash, format)
+);
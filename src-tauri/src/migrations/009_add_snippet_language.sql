@@ -0,0 +1,5 @@
+-- Add detected language to snippets
+-- NULL means the content looks like plain text; filled in by the app on save
+ALTER TABLE snippets ADD COLUMN language TEXT;
+
+CREATE INDEX idx_snippets_language ON snippets(language) WHERE language IS NOT NULL;
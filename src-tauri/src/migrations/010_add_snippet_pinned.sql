@@ -0,0 +1,6 @@
+-- Add pinned flag to snippets
+-- Pinned snippets get a relevance bonus and fill the search overlay's empty state
+ALTER TABLE snippets ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+
+-- Partial index: only the few pinned rows are indexed
+CREATE INDEX idx_snippets_pinned ON snippets(updated_at DESC) WHERE pinned = 1;
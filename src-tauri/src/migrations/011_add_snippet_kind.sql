@@ -0,0 +1,4 @@
+-- Add kind column to snippets
+-- 'static' snippets copy their content; 'dynamic' snippets run it as a shell command
+ALTER TABLE snippets ADD COLUMN kind TEXT NOT NULL DEFAULT 'static'
+    CHECK (kind IN ('static', 'dynamic'));
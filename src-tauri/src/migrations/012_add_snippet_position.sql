@@ -0,0 +1,11 @@
+-- Add position column for manual snippet ordering
+-- Lower positions come first; backfilled from the previous newest-first order
+ALTER TABLE snippets ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+
+UPDATE snippets SET position = (
+    SELECT COUNT(*) FROM snippets AS newer
+    WHERE newer.created_at > snippets.created_at
+       OR (newer.created_at = snippets.created_at AND newer.id > snippets.id)
+);
+
+CREATE INDEX idx_snippets_position ON snippets(position);
@@ -0,0 +1,3 @@
+-- Add declared template parameters to snippets
+-- JSON array of parameter definitions; NULL means the snippet has no form
+ALTER TABLE snippets ADD COLUMN parameters TEXT;
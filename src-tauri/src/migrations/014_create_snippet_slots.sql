@@ -0,0 +1,8 @@
+-- Create numbered slots for favorite snippets, pasted with Ctrl/Cmd+Shift+1..9
+-- A snippet occupies at most one slot
+CREATE TABLE snippet_slots (
+    slot INTEGER PRIMARY KEY CHECK (slot BETWEEN 1 AND 9),
+    snippet_id INTEGER NOT NULL UNIQUE,
+    assigned_at INTEGER NOT NULL,
+    FOREIGN KEY (snippet_id) REFERENCES snippets(id) ON DELETE CASCADE
+);
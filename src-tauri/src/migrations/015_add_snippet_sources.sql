@@ -0,0 +1,17 @@
+-- Remote files mirrored by snippets
+-- source_url is NULL for snippets that are not mirrors; source_checked_at is
+-- when the source was last fetched, whether or not it had changed
+ALTER TABLE snippets ADD COLUMN source_url TEXT;
+ALTER TABLE snippets ADD COLUMN source_checked_at INTEGER;
+
+-- Earlier contents of snippets, recorded before they are overwritten
+CREATE TABLE snippet_versions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    snippet_id INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (snippet_id) REFERENCES snippets(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_snippet_versions_snippet ON snippet_versions(snippet_id, created_at);
@@ -0,0 +1,6 @@
+-- Where a usage came from and the tag filter active at the time
+-- Both are NULL for usages recorded before context was tracked
+ALTER TABLE analytics ADD COLUMN source TEXT;
+ALTER TABLE analytics ADD COLUMN tag_filter TEXT;
+
+CREATE INDEX idx_analytics_source ON analytics(source);
@@ -0,0 +1,15 @@
+-- Binary data attached to snippets, such as screenshots and diagrams
+-- Each attachment is stored inline in data or refers to a file by file_path
+CREATE TABLE snippet_attachments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    snippet_id INTEGER NOT NULL,
+    mime_type TEXT NOT NULL,
+    data BLOB,
+    file_path TEXT,
+    byte_size INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    CHECK ((data IS NULL) <> (file_path IS NULL)),
+    FOREIGN KEY (snippet_id) REFERENCES snippets(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_snippet_attachments_snippet ON snippet_attachments(snippet_id, id);
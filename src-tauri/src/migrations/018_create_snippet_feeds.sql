@@ -0,0 +1,20 @@
+-- Snippet feeds a team publishes, synced into read-only snippets
+-- kind is 'json' (a URL serving an export) or 'git' (a repository)
+CREATE TABLE snippet_feeds (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    url TEXT NOT NULL UNIQUE,
+    kind TEXT NOT NULL,
+    interval_hours INTEGER NOT NULL DEFAULT 24,
+    synced_at INTEGER,
+    last_error TEXT,
+    created_at INTEGER NOT NULL
+);
+
+-- feed_id and feed_key are the feed a read-only snippet comes from and its
+-- name there; forked_from is the feed snippet a local copy was made from
+ALTER TABLE snippets ADD COLUMN feed_id INTEGER REFERENCES snippet_feeds(id) ON DELETE CASCADE;
+ALTER TABLE snippets ADD COLUMN feed_key TEXT;
+ALTER TABLE snippets ADD COLUMN forked_from INTEGER REFERENCES snippets(id) ON DELETE SET NULL;
+
+CREATE UNIQUE INDEX idx_snippets_feed_key ON snippets(feed_id, feed_key) WHERE feed_id IS NOT NULL;
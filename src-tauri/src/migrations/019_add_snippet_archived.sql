@@ -0,0 +1,3 @@
+-- Add archived flag to snippets
+-- Archived snippets stay in the library but are left out of search
+ALTER TABLE snippets ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
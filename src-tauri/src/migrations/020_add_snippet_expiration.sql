@@ -0,0 +1,6 @@
+-- Add expiration dates to snippets
+-- Temporary snippets (tokens, meeting links) are flagged once expires_at passes
+ALTER TABLE snippets ADD COLUMN expires_at INTEGER;
+
+-- Partial index: only the few snippets with an expiration date are indexed
+CREATE INDEX idx_snippets_expires_at ON snippets(expires_at) WHERE expires_at IS NOT NULL;
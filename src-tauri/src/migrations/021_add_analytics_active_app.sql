@@ -0,0 +1,6 @@
+-- Record the application a snippet was used in
+-- Search ranks snippets higher when they are often used with the focused app
+ALTER TABLE analytics ADD COLUMN active_app TEXT;
+
+CREATE INDEX idx_analytics_active_app ON analytics(active_app, snippet_id)
+    WHERE active_app IS NOT NULL;
@@ -0,0 +1,6 @@
+-- Index usage by snippet and time
+-- Recent and frequent snippet lists read each snippet's use count and last
+-- use from the index alone; it also covers lookups by snippet_id
+DROP INDEX IF EXISTS idx_analytics_snippet_id;
+
+CREATE INDEX idx_analytics_snippet_used_at ON analytics(snippet_id, used_at);
@@ -0,0 +1,94 @@
+-- Make tag names searchable
+-- snippets_fts gets a tags column with the names of a snippet's tags, kept
+-- in sync when tags are added, removed or renamed. Search only matches the
+-- column while search_in_tags is on.
+
+DROP TRIGGER IF EXISTS snippets_au;
+DROP TRIGGER IF EXISTS snippets_ad;
+DROP TRIGGER IF EXISTS snippets_ai;
+DROP TABLE IF EXISTS snippets_fts;
+
+CREATE VIRTUAL TABLE snippets_fts USING fts5(
+    name,
+    content,
+    tags,
+    tokenize='porter unicode61'
+);
+
+-- Trigger to insert into FTS5 when snippet is created
+CREATE TRIGGER snippets_ai AFTER INSERT ON snippets BEGIN
+    INSERT INTO snippets_fts(rowid, name, content, tags)
+    VALUES (new.id, new.name, new.content, (
+        SELECT group_concat(name, ' ') FROM (
+            SELECT t.name FROM snippet_tags st JOIN tags t ON t.id = st.tag_id
+            WHERE st.snippet_id = new.id ORDER BY t.name
+        )
+    ));
+END;
+
+-- Trigger to delete from FTS5 when snippet is deleted
+CREATE TRIGGER snippets_ad AFTER DELETE ON snippets BEGIN
+    DELETE FROM snippets_fts WHERE rowid = old.id;
+END;
+
+-- Trigger to update FTS5 when snippet is updated
+CREATE TRIGGER snippets_au AFTER UPDATE ON snippets BEGIN
+    DELETE FROM snippets_fts WHERE rowid = old.id;
+    INSERT INTO snippets_fts(rowid, name, content, tags)
+    VALUES (new.id, new.name, new.content, (
+        SELECT group_concat(name, ' ') FROM (
+            SELECT t.name FROM snippet_tags st JOIN tags t ON t.id = st.tag_id
+            WHERE st.snippet_id = new.id ORDER BY t.name
+        )
+    ));
+END;
+
+-- Triggers to reindex a snippet when a tag is added to or removed from it
+CREATE TRIGGER snippet_tags_ai AFTER INSERT ON snippet_tags BEGIN
+    DELETE FROM snippets_fts WHERE rowid = new.snippet_id;
+    INSERT INTO snippets_fts(rowid, name, content, tags)
+    SELECT s.id, s.name, s.content, (
+        SELECT group_concat(name, ' ') FROM (
+            SELECT t.name FROM snippet_tags st JOIN tags t ON t.id = st.tag_id
+            WHERE st.snippet_id = s.id ORDER BY t.name
+        )
+    )
+    FROM snippets s WHERE s.id = new.snippet_id;
+END;
+
+CREATE TRIGGER snippet_tags_ad AFTER DELETE ON snippet_tags BEGIN
+    DELETE FROM snippets_fts WHERE rowid = old.snippet_id;
+    INSERT INTO snippets_fts(rowid, name, content, tags)
+    SELECT s.id, s.name, s.content, (
+        SELECT group_concat(name, ' ') FROM (
+            SELECT t.name FROM snippet_tags st JOIN tags t ON t.id = st.tag_id
+            WHERE st.snippet_id = s.id ORDER BY t.name
+        )
+    )
+    FROM snippets s WHERE s.id = old.snippet_id;
+END;
+
+-- Trigger to reindex a tag's snippets when the tag is renamed
+CREATE TRIGGER tags_au AFTER UPDATE OF name ON tags BEGIN
+    DELETE FROM snippets_fts
+    WHERE rowid IN (SELECT snippet_id FROM snippet_tags WHERE tag_id = new.id);
+    INSERT INTO snippets_fts(rowid, name, content, tags)
+    SELECT s.id, s.name, s.content, (
+        SELECT group_concat(name, ' ') FROM (
+            SELECT t.name FROM snippet_tags st JOIN tags t ON t.id = st.tag_id
+            WHERE st.snippet_id = s.id ORDER BY t.name
+        )
+    )
+    FROM snippets s
+    WHERE s.id IN (SELECT snippet_id FROM snippet_tags WHERE tag_id = new.id);
+END;
+
+-- Populate FTS5 table with existing snippets and their tags
+INSERT INTO snippets_fts(rowid, name, content, tags)
+SELECT s.id, s.name, s.content, (
+    SELECT group_concat(name, ' ') FROM (
+        SELECT t.name FROM snippet_tags st JOIN tags t ON t.id = st.tag_id
+        WHERE st.snippet_id = s.id ORDER BY t.name
+    )
+)
+FROM snippets s;
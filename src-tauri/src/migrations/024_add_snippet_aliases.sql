@@ -0,0 +1,99 @@
+-- Add search aliases to snippets
+-- JSON array of short keywords such as "k8s"; NULL means the snippet has
+-- none. The search index gets an aliases column, which search weighs above
+-- the others.
+ALTER TABLE snippets ADD COLUMN aliases TEXT;
+
+DROP TRIGGER IF EXISTS tags_au;
+DROP TRIGGER IF EXISTS snippet_tags_ad;
+DROP TRIGGER IF EXISTS snippet_tags_ai;
+DROP TRIGGER IF EXISTS snippets_au;
+DROP TRIGGER IF EXISTS snippets_ad;
+DROP TRIGGER IF EXISTS snippets_ai;
+DROP TABLE IF EXISTS snippets_fts;
+
+CREATE VIRTUAL TABLE snippets_fts USING fts5(
+    name,
+    content,
+    tags,
+    aliases,
+    tokenize='porter unicode61'
+);
+
+-- Trigger to insert into FTS5 when snippet is created
+CREATE TRIGGER snippets_ai AFTER INSERT ON snippets BEGIN
+    INSERT INTO snippets_fts(rowid, name, content, tags, aliases)
+    VALUES (new.id, new.name, new.content, (
+        SELECT group_concat(name, ' ') FROM (
+            SELECT t.name FROM snippet_tags st JOIN tags t ON t.id = st.tag_id
+            WHERE st.snippet_id = new.id ORDER BY t.name
+        )
+    ), new.aliases);
+END;
+
+-- Trigger to delete from FTS5 when snippet is deleted
+CREATE TRIGGER snippets_ad AFTER DELETE ON snippets BEGIN
+    DELETE FROM snippets_fts WHERE rowid = old.id;
+END;
+
+-- Trigger to update FTS5 when snippet is updated
+CREATE TRIGGER snippets_au AFTER UPDATE ON snippets BEGIN
+    DELETE FROM snippets_fts WHERE rowid = old.id;
+    INSERT INTO snippets_fts(rowid, name, content, tags, aliases)
+    VALUES (new.id, new.name, new.content, (
+        SELECT group_concat(name, ' ') FROM (
+            SELECT t.name FROM snippet_tags st JOIN tags t ON t.id = st.tag_id
+            WHERE st.snippet_id = new.id ORDER BY t.name
+        )
+    ), new.aliases);
+END;
+
+-- Triggers to reindex a snippet when a tag is added to or removed from it
+CREATE TRIGGER snippet_tags_ai AFTER INSERT ON snippet_tags BEGIN
+    DELETE FROM snippets_fts WHERE rowid = new.snippet_id;
+    INSERT INTO snippets_fts(rowid, name, content, tags, aliases)
+    SELECT s.id, s.name, s.content, (
+        SELECT group_concat(name, ' ') FROM (
+            SELECT t.name FROM snippet_tags st JOIN tags t ON t.id = st.tag_id
+            WHERE st.snippet_id = s.id ORDER BY t.name
+        )
+    ), s.aliases
+    FROM snippets s WHERE s.id = new.snippet_id;
+END;
+
+CREATE TRIGGER snippet_tags_ad AFTER DELETE ON snippet_tags BEGIN
+    DELETE FROM snippets_fts WHERE rowid = old.snippet_id;
+    INSERT INTO snippets_fts(rowid, name, content, tags, aliases)
+    SELECT s.id, s.name, s.content, (
+        SELECT group_concat(name, ' ') FROM (
+            SELECT t.name FROM snippet_tags st JOIN tags t ON t.id = st.tag_id
+            WHERE st.snippet_id = s.id ORDER BY t.name
+        )
+    ), s.aliases
+    FROM snippets s WHERE s.id = old.snippet_id;
+END;
+
+-- Trigger to reindex a tag's snippets when the tag is renamed
+CREATE TRIGGER tags_au AFTER UPDATE OF name ON tags BEGIN
+    DELETE FROM snippets_fts
+    WHERE rowid IN (SELECT snippet_id FROM snippet_tags WHERE tag_id = new.id);
+    INSERT INTO snippets_fts(rowid, name, content, tags, aliases)
+    SELECT s.id, s.name, s.content, (
+        SELECT group_concat(name, ' ') FROM (
+            SELECT t.name FROM snippet_tags st JOIN tags t ON t.id = st.tag_id
+            WHERE st.snippet_id = s.id ORDER BY t.name
+        )
+    ), s.aliases
+    FROM snippets s
+    WHERE s.id IN (SELECT snippet_id FROM snippet_tags WHERE tag_id = new.id);
+END;
+
+-- Populate FTS5 table with existing snippets and their tags
+INSERT INTO snippets_fts(rowid, name, content, tags, aliases)
+SELECT s.id, s.name, s.content, (
+    SELECT group_concat(name, ' ') FROM (
+        SELECT t.name FROM snippet_tags st JOIN tags t ON t.id = st.tag_id
+        WHERE st.snippet_id = s.id ORDER BY t.name
+    )
+), s.aliases
+FROM snippets s;
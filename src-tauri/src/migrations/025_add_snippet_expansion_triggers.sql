@@ -0,0 +1,9 @@
+-- Add text expansion triggers to snippets
+-- Typing a trigger such as ";sig" replaces it with the snippet's content
+-- while text expansion is on; NULL means the snippet has none. A trigger
+-- belongs to at most one snippet.
+ALTER TABLE snippets ADD COLUMN expansion_trigger TEXT;
+
+CREATE UNIQUE INDEX idx_snippets_expansion_trigger
+    ON snippets(expansion_trigger)
+    WHERE expansion_trigger IS NOT NULL;
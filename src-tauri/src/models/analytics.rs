@@ -25,6 +25,65 @@ pub struct AnalyticsRecord {
     pub used_at: i64,
 }
 
+/// Entry point a snippet was used from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageSource {
+    /// Copied from the search overlay
+    SearchOverlay,
+    /// Copied from the management window
+    Management,
+    /// The quick paste shortcut
+    QuickPaste,
+    /// A numbered slot shortcut
+    SlotShortcut,
+    /// The local HTTP API
+    Api,
+    /// The tray menu's "Recent" submenu
+    Tray,
+    /// A trigger typed while text expansion is on
+    Expansion,
+}
+
+impl UsageSource {
+    /// Value stored in the `source` column
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UsageSource::SearchOverlay => "search_overlay",
+            UsageSource::Management => "management",
+            UsageSource::QuickPaste => "quick_paste",
+            UsageSource::SlotShortcut => "slot_shortcut",
+            UsageSource::Api => "api",
+            UsageSource::Tray => "tray",
+            UsageSource::Expansion => "expansion",
+        }
+    }
+
+    /// Parses a `source` column value; unknown values yield `None`
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "search_overlay" => Some(UsageSource::SearchOverlay),
+            "management" => Some(UsageSource::Management),
+            "quick_paste" => Some(UsageSource::QuickPaste),
+            "slot_shortcut" => Some(UsageSource::SlotShortcut),
+            "api" => Some(UsageSource::Api),
+            "tray" => Some(UsageSource::Tray),
+            "expansion" => Some(UsageSource::Expansion),
+            _ => None,
+        }
+    }
+}
+
+/// Optional context recorded with a usage
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageContext {
+    pub source: Option<UsageSource>,
+    /// Tag filter active in the search when the snippet was used
+    pub tag_filter: Option<String>,
+    /// Application the snippet was used in, where it is known
+    pub active_app: Option<String>,
+}
+
 /// Analytics data for a specific snippet
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)] // Will be used in Task Group M (Analytics backend)
@@ -64,6 +123,146 @@ pub struct RecentActivity {
     pub used_at: i64,
 }
 
+/// Usage of the snippets carrying a tag
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagUsage {
+    pub tag_name: String,
+    /// Usages of snippets with this tag
+    pub usage_count: i64,
+    /// Usages made while the search was filtered to this tag
+    pub filtered_count: i64,
+    pub last_used: Option<i64>,
+}
+
+/// Usage through one entry point
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceUsage {
+    /// `None` for usages recorded before sources were tracked
+    pub source: Option<UsageSource>,
+    pub usage_count: i64,
+    pub last_used: Option<i64>,
+}
+
+/// Width of the time buckets in usage trends
+///
+/// Buckets start at midnight UTC; weeks start on Monday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendBucket {
+    Day,
+    Week,
+    Month,
+}
+
+/// Number of usages in one time bucket
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrendPoint {
+    /// Unix timestamp of the start of the bucket
+    pub bucket_start: i64,
+    pub count: i64,
+}
+
+/// Usage over time of one snippet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnippetTrend {
+    pub snippet_id: i64,
+    pub snippet_name: String,
+    /// Usages in the whole range
+    pub total: i64,
+    /// One point per bucket in the range, including empty buckets
+    pub points: Vec<TrendPoint>,
+}
+
+/// Time series of usage counts, globally and per snippet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageTrends {
+    pub bucket: TrendBucket,
+    /// Start of the range (inclusive)
+    pub start: i64,
+    /// End of the range (exclusive)
+    pub end: i64,
+    /// One point per bucket in the range, including empty buckets
+    pub global: Vec<TrendPoint>,
+    /// Most used snippets in the range, most used first
+    pub snippets: Vec<SnippetTrend>,
+}
+
+/// Usages in one hour of the day
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HourUsage {
+    /// Hour of the day, 0 to 23, in the requested time zone
+    pub hour: u8,
+    pub count: i64,
+}
+
+/// Usage streaks and estimated time saved, for the productivity dashboard
+///
+/// Days and weeks are counted in the requested time zone; weeks start on
+/// Monday. A streak is still current until a whole day (or week) has passed
+/// without a usage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProductivitySummary {
+    pub total_usages: i64,
+    /// Days with at least one usage
+    pub active_days: i64,
+    pub current_daily_streak: i64,
+    pub longest_daily_streak: i64,
+    pub current_weekly_streak: i64,
+    pub longest_weekly_streak: i64,
+    /// Characters inserted by snippets instead of being typed: the content
+    /// length of each used snippet times its uses
+    pub keystrokes_saved: i64,
+    /// Hours with at least one usage, busiest first
+    pub busiest_hours: Vec<HourUsage>,
+}
+
+/// Whether usage is being recorded and what has been recorded so far
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnalyticsConsentState {
+    pub enable_analytics: bool,
+    pub track_usage: bool,
+    /// Whether usages are recorded right now, i.e. both switches are on
+    pub recording: bool,
+    pub purge_on_disable: bool,
+    /// Usage events currently stored
+    pub stored_events: i64,
+    /// Unix timestamp of the oldest stored event
+    pub oldest_event: Option<i64>,
+}
+
+/// Column of an analytics CSV export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalyticsCsvColumn {
+    SnippetName,
+    /// Unix timestamp in seconds
+    Timestamp,
+    /// UTC date and time in ISO 8601, e.g. `2024-03-01T14:05:00Z`
+    IsoDate,
+    /// Tags of the snippet, comma separated
+    Tags,
+}
+
+impl AnalyticsCsvColumn {
+    /// Columns exported when none are selected
+    pub const ALL: [AnalyticsCsvColumn; 4] = [
+        AnalyticsCsvColumn::SnippetName,
+        AnalyticsCsvColumn::Timestamp,
+        AnalyticsCsvColumn::IsoDate,
+        AnalyticsCsvColumn::Tags,
+    ];
+
+    /// Header row label
+    pub fn header(self) -> &'static str {
+        match self {
+            AnalyticsCsvColumn::SnippetName => "snippet_name",
+            AnalyticsCsvColumn::Timestamp => "timestamp",
+            AnalyticsCsvColumn::IsoDate => "iso_date",
+            AnalyticsCsvColumn::Tags => "tags",
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,4 +316,34 @@ mod tests {
         assert_eq!(global.total_snippets, 50);
         assert_eq!(global.total_usages, 500);
     }
+
+    #[test]
+    fn test_usage_source_db_round_trip() {
+        for source in [
+            UsageSource::SearchOverlay,
+            UsageSource::Management,
+            UsageSource::QuickPaste,
+            UsageSource::SlotShortcut,
+            UsageSource::Api,
+            UsageSource::Tray,
+            UsageSource::Expansion,
+        ] {
+            assert_eq!(UsageSource::from_db(source.as_str()), Some(source));
+            assert_eq!(
+                serde_json::to_string(&source).unwrap(),
+                format!("\"{}\"", source.as_str())
+            );
+        }
+        assert_eq!(UsageSource::from_db("dbus"), None);
+    }
+
+    #[test]
+    fn test_trend_bucket_serialization() {
+        assert_eq!(
+            serde_json::to_string(&TrendBucket::Week).unwrap(),
+            "\"week\""
+        );
+        let bucket: TrendBucket = serde_json::from_str("\"month\"").unwrap();
+        assert_eq!(bucket, TrendBucket::Month);
+    }
 }
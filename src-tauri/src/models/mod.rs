@@ -13,10 +13,15 @@ pub use analytics::{
 #[allow(unused_imports)]
 pub use settings::{
     AppSettings, AuthToken, CloudAccountInfo, CloudSyncResult, CloudSyncSettings, CloudSyncStatus,
-    ConflictInfo, ConflictResolutionStrategy, GitStatus, GitSyncResult, GlobalShortcuts,
-    PrivacySettings, SearchSettings, StorageType, SyncStatus, Theme,
+    ConflictInfo, ConflictResolutionStrategy, GistSettings, GitStatus, GitSyncResult,
+    GlobalShortcuts, LinuxClipboardSettings, OverlayMonitor, PrivacySettings, QuickAddFallback,
+    QuickWindowPreferences, SearchPlacement, SearchSettings, ShareSettings, SortPreference,
+    StorageType, SyncStatus, Theme,
 };
 #[allow(unused_imports)]
-pub use snippet::{CreateSnippetInput, SearchResult, Snippet, SnippetId, UpdateSnippetInput};
+pub use snippet::{
+    CreateSnippetInput, CreateSnippetOutcome, NameConflictMode, SearchPage, SearchResult, Snippet,
+    SnippetId, SnippetKind, UpdateSnippetInput,
+};
 #[allow(unused_imports)]
-pub use tag::{SnippetTag, Tag, TagId};
+pub use tag::{SnippetTag, Tag, TagId, TagReassignment, TagStats, TagTreeNode};
@@ -1,48 +1,87 @@
 use serde::{Deserialize, Serialize};
 
 /// Storage type for snippets
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum StorageType {
+    #[default]
     Local,
     Git,
     Cloud,
 }
 
-impl Default for StorageType {
-    fn default() -> Self {
-        Self::Local
-    }
-}
-
 /// Application theme
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Theme {
     Light,
     Dark,
+    #[default]
     System,
 }
 
-impl Default for Theme {
-    fn default() -> Self {
-        Self::System
-    }
-}
-
 /// Conflict resolution strategy for sync operations
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum ConflictResolutionStrategy {
     LastWriteWins,
     KeepBoth,
+    #[default]
     AskUser,
 }
 
-impl Default for ConflictResolutionStrategy {
-    fn default() -> Self {
-        Self::AskUser
-    }
+/// Order of the snippet list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SortPreference {
+    /// Alphabetical, case-insensitive
+    Name,
+    /// Newest first
+    #[default]
+    Created,
+    /// Most recently edited first
+    Updated,
+    /// Most used first
+    Usage,
+    /// User-defined order set with `reorder_snippets`
+    Manual,
+}
+
+/// What Quick Add opens with when no text is selected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum QuickAddFallback {
+    /// Show the capture error instead of the form
+    Error,
+    /// Open the form with empty content
+    #[default]
+    EmptyForm,
+    /// Prefill the content from the clipboard
+    Clipboard,
+}
+
+/// Monitor the search and Quick Add overlays open on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlayMonitor {
+    /// The primary monitor
+    Primary,
+    /// The monitor under the mouse cursor
+    #[default]
+    Cursor,
+    /// The monitor showing the focused window
+    ActiveWindow,
+}
+
+/// Where the search overlay opens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchPlacement {
+    /// Centered on the monitor chosen by `overlay_monitor`
+    #[default]
+    Center,
+    /// Next to the mouse cursor
+    NearCursor,
 }
 
 /// Global keyboard shortcuts configuration
@@ -50,6 +89,14 @@ impl Default for ConflictResolutionStrategy {
 pub struct GlobalShortcuts {
     pub quick_add: String,
     pub search_select: String,
+    /// Copies the most recently used snippet without opening a window;
+    /// empty disables it
+    #[serde(default = "default_quick_paste")]
+    pub quick_paste: String,
+}
+
+fn default_quick_paste() -> String {
+    "CommandOrControl+Shift+L".to_string()
 }
 
 impl Default for GlobalShortcuts {
@@ -57,6 +104,7 @@ impl Default for GlobalShortcuts {
         Self {
             quick_add: "CommandOrControl+Shift+A".to_string(),
             search_select: "CommandOrControl+Shift+S".to_string(),
+            quick_paste: default_quick_paste(),
         }
     }
 }
@@ -73,6 +121,20 @@ pub struct SearchSettings {
     pub weight_usage_frequency: f64,
     /// Weight for recency in search ranking (default: 1.0)
     pub weight_recency: f64,
+    /// Flat relevance bonus for pinned snippets (default: 20.0)
+    #[serde(default = "default_pinned_bonus")]
+    pub pinned_bonus: f64,
+    /// Weight for usage with the focused application (default: 3.0)
+    #[serde(default = "default_weight_app_context")]
+    pub weight_app_context: f64,
+}
+
+fn default_pinned_bonus() -> f64 {
+    20.0
+}
+
+fn default_weight_app_context() -> f64 {
+    3.0
 }
 
 impl Default for SearchSettings {
@@ -84,6 +146,8 @@ impl Default for SearchSettings {
             weight_text_relevance: 10.0,
             weight_usage_frequency: 2.0,
             weight_recency: 1.0,
+            pinned_bonus: default_pinned_bonus(),
+            weight_app_context: default_weight_app_context(),
         }
     }
 }
@@ -93,6 +157,9 @@ impl Default for SearchSettings {
 pub struct PrivacySettings {
     pub enable_analytics: bool,
     pub track_usage: bool,
+    /// Delete recorded usage when tracking is turned off
+    #[serde(default)]
+    pub purge_on_disable: bool,
 }
 
 impl Default for PrivacySettings {
@@ -100,10 +167,18 @@ impl Default for PrivacySettings {
         Self {
             enable_analytics: true,
             track_usage: true,
+            purge_on_disable: false,
         }
     }
 }
 
+impl PrivacySettings {
+    /// Whether snippet usage may be recorded; both switches must be on
+    pub fn records_usage(&self) -> bool {
+        self.enable_analytics && self.track_usage
+    }
+}
+
 /// Cloud sync settings configuration
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CloudSyncSettings {
@@ -122,6 +197,168 @@ impl Default for CloudSyncSettings {
     }
 }
 
+/// GitHub Gist integration settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct GistSettings {
+    /// Personal access token with the `gist` scope; public gists can be
+    /// imported without one
+    ///
+    /// Saving settings moves it to the OS keychain; it is only kept here when
+    /// the keychain is unavailable to the migration from earlier versions.
+    #[serde(default)]
+    pub access_token: Option<String>,
+}
+
+/// Where encrypted snippet shares are uploaded
+///
+/// The endpoint takes the ciphertext as a POST body and answers with the URL
+/// of the new paste, as `paste.rs` and compatible services do. Only
+/// ciphertext is uploaded; the key stays in the link's fragment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareSettings {
+    pub endpoint: String,
+}
+
+impl Default for ShareSettings {
+    fn default() -> Self {
+        Self {
+            endpoint: "https://paste.rs/".to_string(),
+        }
+    }
+}
+
+/// Local HTTP API server settings
+///
+/// The server only listens on the loopback interface and every request must
+/// carry an API token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiServerSettings {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for ApiServerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 38274,
+        }
+    }
+}
+
+/// Scheduled refresh of snippets that mirror a remote file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceRefreshSettings {
+    pub enabled: bool,
+    /// Hours between fetches of each snippet's source
+    pub interval_hours: u32,
+}
+
+impl Default for SourceRefreshSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: 24,
+        }
+    }
+}
+
+/// Which events show a desktop notification
+///
+/// `enabled` silences every notification at once; the other switches pick
+/// events while it is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    /// A scheduled backup finished
+    pub backup_completed: bool,
+    /// A scheduled backup failed
+    pub backup_failed: bool,
+    /// A snippet was copied by a shortcut, with no window to show it
+    pub snippet_copied: bool,
+    /// Global shortcuts could not be bound through the Wayland portal
+    pub shortcut_failures: bool,
+    /// A scheduled refresh changed snippets that mirror a source URL
+    #[serde(default = "default_source_updated")]
+    pub source_updated: bool,
+}
+
+fn default_source_updated() -> bool {
+    true
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            backup_completed: false,
+            backup_failed: true,
+            snippet_copied: false,
+            shortcut_failures: true,
+            source_updated: default_source_updated(),
+        }
+    }
+}
+
+/// What happens to snippets whose expiration date has passed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpirationSettings {
+    /// Archive expired snippets automatically instead of only flagging them
+    pub auto_archive: bool,
+}
+
+/// Typing a snippet's trigger in any application replaces it with the snippet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpansionSettings {
+    /// Watch typing for triggers; off by default
+    pub enabled: bool,
+    /// Applications expansions are never pasted into, by name or bundle
+    /// identifier (case-insensitive); the snippet is only copied there
+    #[serde(default = "default_paste_blocklist")]
+    pub paste_blocklist: Vec<String>,
+}
+
+fn default_paste_blocklist() -> Vec<String> {
+    ["1Password", "Bitwarden", "KeePassXC", "Keychain Access"]
+        .map(String::from)
+        .to_vec()
+}
+
+impl Default for ExpansionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            paste_blocklist: default_paste_blocklist(),
+        }
+    }
+}
+
+/// When the search and Quick Add overlays hide themselves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuickWindowPreferences {
+    /// Hide when another window takes focus
+    pub hide_on_blur: bool,
+    /// Hide when Escape is pressed
+    pub hide_on_escape: bool,
+}
+
+impl Default for QuickWindowPreferences {
+    fn default() -> Self {
+        Self {
+            hide_on_blur: true,
+            hide_on_escape: true,
+        }
+    }
+}
+
+/// Clipboard behavior that only applies on Linux
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct LinuxClipboardSettings {
+    /// Also put copied snippets in the PRIMARY selection, for middle-click paste
+    #[serde(default)]
+    pub copy_to_primary: bool,
+}
+
 /// Application settings
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct AppSettings {
@@ -137,6 +374,51 @@ pub struct AppSettings {
     pub privacy_settings: PrivacySettings,
     #[serde(default)]
     pub cloud_sync_settings: Option<CloudSyncSettings>,
+    #[serde(default)]
+    pub gist_settings: GistSettings,
+    #[serde(default)]
+    pub sort_preference: SortPreference,
+    #[serde(default)]
+    pub api_server: ApiServerSettings,
+    #[serde(default)]
+    pub source_refresh_settings: SourceRefreshSettings,
+    #[serde(default)]
+    pub expiration_settings: ExpirationSettings,
+    #[serde(default)]
+    pub quick_add_fallback: QuickAddFallback,
+    #[serde(default)]
+    pub overlay_monitor: OverlayMonitor,
+    #[serde(default)]
+    pub search_placement: SearchPlacement,
+    #[serde(default)]
+    pub quick_window_preferences: QuickWindowPreferences,
+    #[serde(default)]
+    pub linux_clipboard: LinuxClipboardSettings,
+    /// Name of the custom theme laid over the light or dark palette, if any
+    #[serde(default)]
+    pub custom_theme: Option<String>,
+    #[serde(default)]
+    pub share_settings: ShareSettings,
+    #[serde(default)]
+    pub notification_settings: NotificationSettings,
+    #[serde(default)]
+    pub expansion_settings: ExpansionSettings,
+}
+
+impl AppSettings {
+    /// Copy of the settings without credentials, for writing to a file
+    pub fn without_credentials(&self) -> Self {
+        let mut settings = self.clone();
+        settings.gist_settings.access_token = None;
+        settings
+    }
+
+    /// Takes the credentials from `current`, keeping them across an import
+    pub fn keep_credentials_from(&mut self, current: &AppSettings) {
+        self.gist_settings
+            .access_token
+            .clone_from(&current.gist_settings.access_token);
+    }
 }
 
 /// Sync status information
@@ -229,10 +511,50 @@ mod tests {
             settings.global_shortcuts.search_select,
             "CommandOrControl+Shift+S"
         );
+        assert_eq!(
+            settings.global_shortcuts.quick_paste,
+            "CommandOrControl+Shift+L"
+        );
         assert_eq!(settings.search_settings.max_results, 50);
         assert!(settings.search_settings.enable_fuzzy_search);
         assert!(settings.privacy_settings.enable_analytics);
         assert!(settings.cloud_sync_settings.is_none());
+        assert!(settings.gist_settings.access_token.is_none());
+        assert_eq!(settings.sort_preference, SortPreference::Created);
+        assert!(!settings.api_server.enabled);
+        assert!(!settings.source_refresh_settings.enabled);
+        assert!(!settings.expiration_settings.auto_archive);
+        assert!(settings.notification_settings.enabled);
+        assert!(!settings.notification_settings.snippet_copied);
+        assert!(!settings.expansion_settings.enabled);
+        assert!(settings
+            .expansion_settings
+            .paste_blocklist
+            .contains(&"KeePassXC".to_string()));
+        assert_eq!(settings.quick_add_fallback, QuickAddFallback::EmptyForm);
+        assert_eq!(settings.overlay_monitor, OverlayMonitor::Cursor);
+        assert_eq!(settings.search_placement, SearchPlacement::Center);
+        assert!(settings.quick_window_preferences.hide_on_blur);
+        assert!(settings.quick_window_preferences.hide_on_escape);
+        assert!(!settings.linux_clipboard.copy_to_primary);
+    }
+
+    #[test]
+    fn test_linux_clipboard_round_trip() {
+        let mut settings = AppSettings::default();
+        settings.linux_clipboard.copy_to_primary = true;
+        let json = serde_json::to_value(&settings).unwrap();
+        assert_eq!(json["linux_clipboard"]["copy_to_primary"], true);
+        let loaded: AppSettings = serde_json::from_value(json).unwrap();
+        assert!(loaded.linux_clipboard.copy_to_primary);
+
+        // Settings saved before the option existed leave it off
+        let mut json = serde_json::to_value(AppSettings::default()).unwrap();
+        json.as_object_mut().unwrap().remove("linux_clipboard");
+        let loaded: AppSettings = serde_json::from_value(json).unwrap();
+        assert!(!loaded.linux_clipboard.copy_to_primary);
+        let empty: LinuxClipboardSettings = serde_json::from_str("{}").unwrap();
+        assert!(!empty.copy_to_primary);
     }
 
     #[test]
@@ -252,6 +574,15 @@ mod tests {
         assert_eq!(json, r#""system""#);
     }
 
+    #[test]
+    fn test_search_settings_without_pinned_bonus() {
+        // Settings saved before pinning existed still load with the default bonus
+        let json = r#"{"max_results":50,"enable_fuzzy_search":true,"search_in_tags":true,
+            "weight_text_relevance":10.0,"weight_usage_frequency":2.0,"weight_recency":1.0}"#;
+        let settings: SearchSettings = serde_json::from_str(json).unwrap();
+        assert_eq!(settings.pinned_bonus, 20.0);
+    }
+
     #[test]
     fn test_cloud_sync_settings_default() {
         let settings = CloudSyncSettings::default();
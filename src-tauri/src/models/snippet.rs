@@ -16,6 +16,35 @@ impl From<SnippetId> for i64 {
     }
 }
 
+/// How a snippet's content is turned into copied text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnippetKind {
+    /// Content is copied as-is
+    #[default]
+    Static,
+    /// Content is a shell command whose output is copied
+    Dynamic,
+}
+
+impl SnippetKind {
+    /// Value stored in the `kind` column
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SnippetKind::Static => "static",
+            SnippetKind::Dynamic => "dynamic",
+        }
+    }
+
+    /// Parses a `kind` column value, treating unknown values as static
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "dynamic" => SnippetKind::Dynamic,
+            _ => SnippetKind::Static,
+        }
+    }
+}
+
 /// Represents a text snippet stored in the database
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)] // Will be used in Task Group D (CRUD commands)
@@ -28,6 +57,20 @@ pub struct Snippet {
     pub updated_at: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<String>>,
+    /// Pinned snippets rank higher in search and show when the query is empty
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub kind: SnippetKind,
+    /// Language detected from the name and content; `None` for plain text
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Archived snippets are left out of search and the snippet list
+    #[serde(default)]
+    pub archived: bool,
+    /// Unix timestamp after which the snippet is flagged as expired
+    #[serde(default)]
+    pub expires_at: Option<i64>,
 }
 
 /// Input data for creating a new snippet
@@ -39,6 +82,39 @@ pub struct CreateSnippetInput {
     pub description: Option<String>,
     #[serde(default)]
     pub tags: Vec<String>,
+    #[serde(default)]
+    pub kind: SnippetKind,
+}
+
+/// How `create_snippet_with_resolution` treats a name that is already taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NameConflictMode {
+    /// Create nothing and report the conflict so the user can choose
+    #[default]
+    Ask,
+    /// Create the snippet under a free name such as `deploy (2)`
+    Rename,
+    /// Add the content to the end of the existing snippet
+    Append,
+}
+
+/// Result of a `create_snippet_with_resolution` call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum CreateSnippetOutcome {
+    /// The snippet was created under the requested name
+    Created { snippet: Snippet },
+    /// The name was taken; the snippet was created under a free one
+    Renamed { snippet: Snippet },
+    /// The name was taken; the content was appended to that snippet
+    Appended { snippet: Snippet },
+    /// The name was taken and nothing was written
+    NameTaken {
+        existing: Snippet,
+        /// A free name the snippet could be created under instead
+        suggested_name: String,
+    },
 }
 
 /// Input data for updating an existing snippet
@@ -50,6 +126,8 @@ pub struct UpdateSnippetInput {
     pub description: Option<String>,
     #[serde(default)]
     pub tags: Vec<String>,
+    #[serde(default)]
+    pub kind: SnippetKind,
 }
 
 /// Search result with relevance scoring and usage statistics
@@ -61,6 +139,23 @@ pub struct SearchResult {
     pub usage_count: i64,
     pub last_used: Option<i64>,
     pub relevance_score: f64,
+    /// Excerpt of the content around the matched terms, or its beginning
+    pub preview: String,
+    /// Whether the snippet's `expires_at` has passed
+    #[serde(default)]
+    pub expired: bool,
+}
+
+/// One page of search results
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchPage {
+    pub results: Vec<SearchResult>,
+    /// Matches across all pages
+    pub total_matches: i64,
+    /// Position of the first result among all matches
+    pub offset: i64,
+    /// Pass back as `cursor` to get the next page; `None` on the last page
+    pub next_cursor: Option<String>,
 }
 
 #[cfg(test)]
@@ -86,6 +181,11 @@ mod tests {
             created_at: 1000,
             updated_at: 2000,
             tags: Some(vec!["tag1".to_string()]),
+            pinned: true,
+            kind: SnippetKind::Dynamic,
+            language: Some("shellscript".to_string()),
+            archived: false,
+            expires_at: Some(3000),
         };
 
         let json = serde_json::to_string(&snippet).unwrap();
@@ -93,6 +193,21 @@ mod tests {
 
         assert_eq!(snippet.id, deserialized.id);
         assert_eq!(snippet.name, deserialized.name);
+        assert!(deserialized.pinned);
+        assert_eq!(deserialized.kind, SnippetKind::Dynamic);
+        assert_eq!(deserialized.language.as_deref(), Some("shellscript"));
+    }
+
+    #[test]
+    fn test_snippet_kind_defaults_to_static() {
+        let input: CreateSnippetInput =
+            serde_json::from_str(r#"{"name":"n","content":"c","description":null}"#).unwrap();
+        assert_eq!(input.kind, SnippetKind::Static);
+        assert_eq!(SnippetKind::from_db("dynamic"), SnippetKind::Dynamic);
+        assert_eq!(
+            SnippetKind::from_db(SnippetKind::Static.as_str()),
+            SnippetKind::Static
+        );
     }
 
     #[test]
@@ -102,8 +217,16 @@ mod tests {
             content: "content".to_string(),
             description: None,
             tags: vec!["tag1".to_string(), "tag2".to_string()],
+            kind: SnippetKind::Static,
         };
 
         assert_eq!(input.tags.len(), 2);
     }
+
+    #[test]
+    fn test_name_conflict_mode() {
+        let mode: NameConflictMode = serde_json::from_str(r#""append""#).unwrap();
+        assert_eq!(mode, NameConflictMode::Append);
+        assert_eq!(NameConflictMode::default(), NameConflictMode::Ask);
+    }
 }
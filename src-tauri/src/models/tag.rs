@@ -25,6 +25,50 @@ pub struct Tag {
     pub color: String,
 }
 
+/// How much a tag is used
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagStats {
+    pub id: TagId,
+    pub name: String,
+    pub color: String,
+    /// Snippets carrying the tag; 0 for an orphan
+    pub snippet_count: i64,
+    /// Most recent use of any snippet carrying the tag
+    pub last_used: Option<i64>,
+}
+
+/// Outcome of merging a tag into another or deleting it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagReassignment {
+    /// Name of the tag that no longer exists
+    pub removed: String,
+    /// Tag that took over its snippets, if any
+    pub reassigned_to: Option<String>,
+    /// Snippets that carried the removed tag
+    pub snippets_affected: u64,
+}
+
+/// A level of the tag hierarchy
+///
+/// Tags nest by path: `lang/rust/async` sits below `lang/rust`, which sits
+/// below `lang`. Levels that are only implied by deeper tags appear in the
+/// tree without being tags themselves.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagTreeNode {
+    /// Last path segment, e.g. `async`
+    pub name: String,
+    /// Full path, e.g. `lang/rust/async`
+    pub path: String,
+    /// Color of the tag at this path; `None` when no tag has exactly this path
+    pub color: Option<String>,
+    /// Snippets carrying exactly this tag
+    pub snippet_count: i64,
+    /// Distinct snippets carrying this tag or any tag below it
+    pub total_count: i64,
+    /// Nested levels, sorted by name
+    pub children: Vec<TagTreeNode>,
+}
+
 /// Association between a snippet and a tag
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)] // Will be used in Task Group D (CRUD commands)
@@ -1,9 +1,105 @@
 use crate::models::analytics::{
-    GlobalAnalytics, MostUsedSnippet, RecentActivity, SnippetAnalytics,
+    AnalyticsConsentState, AnalyticsCsvColumn, GlobalAnalytics, HourUsage, MostUsedSnippet,
+    ProductivitySummary, RecentActivity, SnippetAnalytics, SnippetTrend, SourceUsage, TagUsage,
+    TrendBucket, TrendPoint, UsageContext, UsageSource, UsageTrends,
 };
+use crate::models::settings::PrivacySettings;
+use crate::services::database::get_pool;
+use crate::services::menubar;
+use crate::services::settings::SettingsService;
 use crate::utils::error::AppError;
 use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use time::{Date, Duration, Month, OffsetDateTime};
+
+/// Most buckets a usage trends query may span, e.g. about 2.7 years of days
+const MAX_TREND_BUCKETS: usize = 1000;
+
+/// How often queued usage events are written to the database
+const FLUSH_INTERVAL_SECS: u64 = 3;
+
+/// A usage event waiting to be written
+#[derive(Debug, Clone, PartialEq)]
+struct PendingUsage {
+    snippet_id: i64,
+    used_at: i64,
+    source: Option<UsageSource>,
+    tag_filter: Option<String>,
+    active_app: Option<String>,
+}
+
+impl PendingUsage {
+    /// A usage of `snippet_id` happening now
+    fn now(snippet_id: i64, context: &UsageContext) -> Result<Self, AppError> {
+        let used_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| AppError::Database(format!("Failed to get current time: {}", e)))?
+            .as_secs() as i64;
+        let tag_filter = context
+            .tag_filter
+            .as_deref()
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_string);
+
+        Ok(Self {
+            snippet_id,
+            used_at,
+            source: context.source,
+            tag_filter,
+            active_app: context.active_app.clone(),
+        })
+    }
+}
+
+/// Usage events waiting for the next flush
+///
+/// Copies queue their usage here instead of writing it right away. The queue
+/// is written in one transaction every few seconds and on exit, so a burst of
+/// copies takes the SQLite write lock once rather than once per snippet.
+#[derive(Debug, Default)]
+pub struct UsageQueue(Mutex<Vec<PendingUsage>>);
+
+impl UsageQueue {
+    /// Queues a usage of `snippet_id`, timestamped now
+    pub fn push(&self, snippet_id: i64, context: &UsageContext) -> Result<(), AppError> {
+        let usage = PendingUsage::now(snippet_id, context)?;
+        if let Ok(mut pending) = self.0.lock() {
+            pending.push(usage);
+        }
+        Ok(())
+    }
+
+    /// Drops every queued event without writing it
+    pub fn discard(&self) {
+        if let Ok(mut pending) = self.0.lock() {
+            pending.clear();
+        }
+    }
+
+    /// Number of queued events
+    pub fn pending(&self) -> usize {
+        self.0.lock().map(|pending| pending.len()).unwrap_or(0)
+    }
+
+    fn take(&self) -> Vec<PendingUsage> {
+        self.0
+            .lock()
+            .map(|mut pending| std::mem::take(&mut *pending))
+            .unwrap_or_default()
+    }
+
+    /// Puts events back ahead of anything queued since they were taken
+    fn restore(&self, mut events: Vec<PendingUsage>) {
+        if let Ok(mut pending) = self.0.lock() {
+            events.append(&mut pending);
+            *pending = events;
+        }
+    }
+}
 
 /// Record a snippet usage event
 ///
@@ -27,21 +123,152 @@ use std::time::{SystemTime, UNIX_EPOCH};
 /// # }
 /// ```
 pub async fn record_usage(pool: &SqlitePool, snippet_id: i64) -> Result<(), AppError> {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map_err(|e| AppError::Database(format!("Failed to get current time: {}", e)))?
-        .as_secs() as i64;
-
-    sqlx::query("INSERT INTO analytics (snippet_id, used_at) VALUES (?, ?)")
-        .bind(snippet_id)
-        .bind(now)
-        .execute(pool)
+    record_usage_with_context(pool, snippet_id, &UsageContext::default()).await
+}
+
+/// Record a snippet usage event along with where it came from
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `snippet_id` - ID of the snippet being used
+/// * `context` - Entry point and active tag filter, each optional
+pub async fn record_usage_with_context(
+    pool: &SqlitePool,
+    snippet_id: i64,
+    context: &UsageContext,
+) -> Result<(), AppError> {
+    let usage = PendingUsage::now(snippet_id, context)?;
+
+    sqlx::query(
+        "INSERT INTO analytics (snippet_id, used_at, source, tag_filter, active_app)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(usage.snippet_id)
+    .bind(usage.used_at)
+    .bind(usage.source.map(UsageSource::as_str))
+    .bind(usage.tag_filter)
+    .bind(usage.active_app)
+    .execute(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to record usage: {}", e)))?;
+
+    Ok(())
+}
+
+/// Write the queued usage events in a single transaction
+///
+/// Events for snippets deleted since they were queued are dropped. If the
+/// write fails, the events go back on the queue for the next flush.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `queue` - Queue to drain
+///
+/// # Returns
+///
+/// Number of usage events written
+pub async fn flush_usage(pool: &SqlitePool, queue: &UsageQueue) -> Result<u64, AppError> {
+    let pending = queue.take();
+    if pending.is_empty() {
+        return Ok(0);
+    }
+
+    match write_usage(pool, &pending).await {
+        Ok(written) => Ok(written),
+        Err(e) => {
+            queue.restore(pending);
+            Err(e)
+        }
+    }
+}
+
+async fn write_usage(pool: &SqlitePool, pending: &[PendingUsage]) -> Result<u64, AppError> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to start transaction: {}", e)))?;
+
+    let mut written = 0;
+    for usage in pending {
+        // A deleted snippet must not fail the whole batch on its foreign key
+        let result = sqlx::query(
+            "INSERT INTO analytics (snippet_id, used_at, source, tag_filter, active_app)
+             SELECT ?, ?, ?, ?, ? WHERE EXISTS (SELECT 1 FROM snippets WHERE id = ?)",
+        )
+        .bind(usage.snippet_id)
+        .bind(usage.used_at)
+        .bind(usage.source.map(UsageSource::as_str))
+        .bind(usage.tag_filter.as_deref())
+        .bind(usage.active_app.as_deref())
+        .bind(usage.snippet_id)
+        .execute(&mut *tx)
         .await
         .map_err(|e| AppError::Database(format!("Failed to record usage: {}", e)))?;
+        written += result.rows_affected();
+    }
 
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to commit usage: {}", e)))?;
+    Ok(written)
+}
+
+/// Queue a snippet usage event for the next flush
+///
+/// Writes the event right away if the app has no usage queue.
+pub async fn queue_usage(
+    app: &AppHandle,
+    pool: &SqlitePool,
+    snippet_id: i64,
+    context: &UsageContext,
+) -> Result<(), AppError> {
+    match app.try_state::<UsageQueue>() {
+        Some(queue) => queue.push(snippet_id, context),
+        None => record_usage_with_context(pool, snippet_id, context).await,
+    }
+}
+
+/// Write the app's queued usage events now
+///
+/// Analytics reads call this first so they include the latest copies. When
+/// anything was written, listeners are told snippets changed so the tray
+/// menu picks up the new recently used order.
+pub async fn flush_queued_usage(app: &AppHandle) -> Result<(), AppError> {
+    let Some(queue) = app.try_state::<UsageQueue>() else {
+        return Ok(());
+    };
+    if queue.pending() == 0 {
+        return Ok(());
+    }
+
+    let pool = get_pool(app)?;
+    if flush_usage(&pool, &queue).await? > 0 {
+        menubar::notify_snippets_changed(app);
+    }
     Ok(())
 }
 
+/// Drop the app's queued usage events, e.g. when analytics are cleared
+pub fn discard_queued_usage(app: &AppHandle) {
+    if let Some(queue) = app.try_state::<UsageQueue>() {
+        queue.discard();
+    }
+}
+
+/// Starts the background loop that writes queued usage events
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(FLUSH_INTERVAL_SECS)).await;
+            if let Err(e) = flush_queued_usage(&app).await {
+                eprintln!("Failed to write queued usage: {}", e);
+            }
+        }
+    });
+}
+
 /// Get analytics data for a specific snippet
 ///
 /// # Arguments
@@ -203,6 +430,169 @@ pub async fn get_global_analytics(
     })
 }
 
+/// Get usage grouped by the tags of the used snippets
+///
+/// A usage counts towards every tag of its snippet. Usages made while the
+/// search was filtered to a tag are also counted per tag, case-insensitively.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `since` - Optional Unix timestamp; only usages from then on are counted
+///
+/// # Returns
+///
+/// Tags with at least one usage, most used first
+pub async fn get_analytics_by_tag(
+    pool: &SqlitePool,
+    since: Option<i64>,
+) -> Result<Vec<TagUsage>, AppError> {
+    let since = since.unwrap_or(i64::MIN);
+
+    let by_snippet_tag = sqlx::query_as::<_, (String, i64, Option<i64>)>(
+        r#"
+        SELECT
+            t.name,
+            COUNT(a.id) as usage_count,
+            MAX(a.used_at) as last_used
+        FROM analytics a
+        JOIN snippet_tags st ON a.snippet_id = st.snippet_id
+        JOIN tags t ON st.tag_id = t.id
+        WHERE a.used_at >= ?
+        GROUP BY t.id
+        "#,
+    )
+    .bind(since)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to fetch analytics by tag: {}", e)))?;
+
+    let by_filter = sqlx::query_as::<_, (String, i64, Option<i64>)>(
+        r#"
+        SELECT
+            LOWER(tag_filter),
+            COUNT(*) as usage_count,
+            MAX(used_at) as last_used
+        FROM analytics
+        WHERE tag_filter IS NOT NULL AND used_at >= ?
+        GROUP BY LOWER(tag_filter)
+        "#,
+    )
+    .bind(since)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to fetch analytics by tag filter: {}", e)))?;
+
+    let mut usages: HashMap<String, TagUsage> = HashMap::new();
+    for (tag_name, usage_count, last_used) in by_snippet_tag {
+        usages.insert(
+            tag_name.to_lowercase(),
+            TagUsage {
+                tag_name,
+                usage_count,
+                filtered_count: 0,
+                last_used,
+            },
+        );
+    }
+    for (tag_filter, filtered_count, last_used) in by_filter {
+        let usage = usages
+            .entry(tag_filter.clone())
+            .or_insert_with(|| TagUsage {
+                tag_name: tag_filter,
+                usage_count: 0,
+                filtered_count: 0,
+                last_used: None,
+            });
+        usage.filtered_count = filtered_count;
+        usage.last_used = usage.last_used.max(last_used);
+    }
+
+    let mut usages: Vec<TagUsage> = usages.into_values().collect();
+    usages.sort_by(|a, b| {
+        b.usage_count
+            .cmp(&a.usage_count)
+            .then(b.filtered_count.cmp(&a.filtered_count))
+            .then_with(|| a.tag_name.cmp(&b.tag_name))
+    });
+    Ok(usages)
+}
+
+/// Get usage grouped by the entry point it came from
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `since` - Optional Unix timestamp; only usages from then on are counted
+///
+/// # Returns
+///
+/// Entry points with at least one usage, most used first. Usages recorded
+/// before sources were tracked are grouped under `source: None`.
+pub async fn get_analytics_by_source(
+    pool: &SqlitePool,
+    since: Option<i64>,
+) -> Result<Vec<SourceUsage>, AppError> {
+    let rows = sqlx::query_as::<_, (Option<String>, i64, Option<i64>)>(
+        r#"
+        SELECT
+            source,
+            COUNT(*) as usage_count,
+            MAX(used_at) as last_used
+        FROM analytics
+        WHERE used_at >= ?
+        GROUP BY source
+        "#,
+    )
+    .bind(since.unwrap_or(i64::MIN))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to fetch analytics by source: {}", e)))?;
+
+    // Sources written by a newer version are unknown here; fold them into None
+    let mut usages: Vec<SourceUsage> = Vec::with_capacity(rows.len());
+    for (source, usage_count, last_used) in rows {
+        let source = source.as_deref().and_then(UsageSource::from_db);
+        match usages.iter_mut().find(|usage| usage.source == source) {
+            Some(usage) => {
+                usage.usage_count += usage_count;
+                usage.last_used = usage.last_used.max(last_used);
+            }
+            None => usages.push(SourceUsage {
+                source,
+                usage_count,
+                last_used,
+            }),
+        }
+    }
+
+    usages.sort_by(|a, b| b.usage_count.cmp(&a.usage_count));
+    Ok(usages)
+}
+
+/// Get the most recently used snippet
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+///
+/// # Returns
+///
+/// The ID of the snippet with the latest usage, or `None` if nothing has been used yet
+pub async fn most_recently_used_snippet(pool: &SqlitePool) -> Result<Option<i64>, AppError> {
+    let snippet_id = sqlx::query_scalar(
+        "SELECT a.snippet_id FROM analytics a
+         INNER JOIN snippets s ON s.id = a.snippet_id
+         ORDER BY a.used_at DESC, a.id DESC
+         LIMIT 1",
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to get last used snippet: {}", e)))?;
+
+    Ok(snippet_id)
+}
+
 /// Clear all analytics data
 ///
 /// # Arguments
@@ -271,6 +661,450 @@ pub async fn clear_analytics_before(
     Ok(result.rows_affected())
 }
 
+/// Whether the user's privacy settings allow recording snippet usage
+///
+/// # Errors
+///
+/// Returns `AppError` if the settings cannot be read
+pub async fn recording_allowed(pool: &SqlitePool) -> Result<bool, AppError> {
+    let settings = SettingsService::new(pool.clone()).get_settings().await?;
+    Ok(settings.privacy_settings.records_usage())
+}
+
+/// Whether a privacy settings change should delete the recorded usage
+///
+/// True when recording is off, purging is on, and that was not already the
+/// case: either recording was just turned off or purging was just enabled.
+pub fn should_purge(previous: &PrivacySettings, current: &PrivacySettings) -> bool {
+    let purging = |privacy: &PrivacySettings| privacy.purge_on_disable && !privacy.records_usage();
+    purging(current) && !purging(previous)
+}
+
+/// Get the analytics consent state with a summary of the stored data
+///
+/// # Errors
+///
+/// Returns `AppError` if the settings or the analytics table cannot be read
+pub async fn get_consent_state(pool: &SqlitePool) -> Result<AnalyticsConsentState, AppError> {
+    let privacy = SettingsService::new(pool.clone())
+        .get_settings()
+        .await?
+        .privacy_settings;
+
+    let (stored_events, oldest_event) =
+        sqlx::query_as::<_, (i64, Option<i64>)>("SELECT COUNT(*), MIN(used_at) FROM analytics")
+            .fetch_one(pool)
+            .await
+            .map_err(|e| AppError::Database(format!("Failed to summarize analytics: {}", e)))?;
+
+    Ok(AnalyticsConsentState {
+        enable_analytics: privacy.enable_analytics,
+        track_usage: privacy.track_usage,
+        recording: privacy.records_usage(),
+        purge_on_disable: privacy.purge_on_disable,
+        stored_events,
+        oldest_event,
+    })
+}
+
+/// Returns the start of the bucket containing a timestamp
+fn bucket_start(timestamp: i64, bucket: TrendBucket) -> Result<i64, AppError> {
+    let date = OffsetDateTime::from_unix_timestamp(timestamp)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid timestamp {}: {}", timestamp, e)))?
+        .date();
+    let start = match bucket {
+        TrendBucket::Day => date,
+        TrendBucket::Week => {
+            date - Duration::days(i64::from(date.weekday().number_days_from_monday()))
+        }
+        TrendBucket::Month => first_of_month(date.year(), date.month())?,
+    };
+    Ok(start.midnight().assume_utc().unix_timestamp())
+}
+
+/// Returns the start of the bucket after the one starting at `start`
+fn next_bucket_start(start: i64, bucket: TrendBucket) -> Result<i64, AppError> {
+    match bucket {
+        TrendBucket::Day => Ok(start + 24 * 3600),
+        TrendBucket::Week => Ok(start + 7 * 24 * 3600),
+        TrendBucket::Month => {
+            let date = OffsetDateTime::from_unix_timestamp(start)
+                .map_err(|e| AppError::InvalidInput(format!("Invalid timestamp: {}", e)))?
+                .date();
+            let next = match date.month() {
+                Month::December => first_of_month(date.year() + 1, Month::January)?,
+                month => first_of_month(date.year(), month.next())?,
+            };
+            Ok(next.midnight().assume_utc().unix_timestamp())
+        }
+    }
+}
+
+fn first_of_month(year: i32, month: Month) -> Result<Date, AppError> {
+    Date::from_calendar_date(year, month, 1)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid date: {}", e)))
+}
+
+/// SQLite expression for the bucket start of `a.used_at`, matching `bucket_start`
+fn bucket_sql(bucket: TrendBucket) -> &'static str {
+    match bucket {
+        TrendBucket::Day => "CAST(strftime('%s', date(a.used_at, 'unixepoch')) AS INTEGER)",
+        // 'weekday 0' moves forward to Sunday (staying on a Sunday), six days back is Monday
+        TrendBucket::Week => {
+            "CAST(strftime('%s', date(a.used_at, 'unixepoch', 'weekday 0', '-6 days')) AS INTEGER)"
+        }
+        TrendBucket::Month => {
+            "CAST(strftime('%s', date(a.used_at, 'unixepoch', 'start of month')) AS INTEGER)"
+        }
+    }
+}
+
+/// Get usage counts over time, globally and for the most used snippets
+///
+/// Every series has one point per bucket from the bucket containing `start`
+/// up to `end`, with zero counts for empty buckets, so charts can plot them
+/// directly.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `bucket` - Width of each bucket
+/// * `start` - Unix timestamp of the start of the range (inclusive)
+/// * `end` - Unix timestamp of the end of the range (exclusive)
+/// * `snippet_limit` - Maximum number of per-snippet series to return
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidInput` if the range is empty or spans more than
+/// 1000 buckets, and `AppError::Database` if the query fails
+pub async fn get_usage_trends(
+    pool: &SqlitePool,
+    bucket: TrendBucket,
+    start: i64,
+    end: i64,
+    snippet_limit: usize,
+) -> Result<UsageTrends, AppError> {
+    if start >= end {
+        return Err(AppError::InvalidInput(
+            "Usage trends range must end after it starts".to_string(),
+        ));
+    }
+
+    let mut buckets = Vec::new();
+    let mut current = bucket_start(start, bucket)?;
+    while current < end {
+        if buckets.len() == MAX_TREND_BUCKETS {
+            return Err(AppError::InvalidInput(format!(
+                "Usage trends range spans more than {} buckets; use a wider bucket",
+                MAX_TREND_BUCKETS
+            )));
+        }
+        buckets.push(current);
+        current = next_bucket_start(current, bucket)?;
+    }
+    let positions: HashMap<i64, usize> = buckets.iter().enumerate().map(|(i, b)| (*b, i)).collect();
+    let empty_series = || -> Vec<TrendPoint> {
+        buckets
+            .iter()
+            .map(|bucket_start| TrendPoint {
+                bucket_start: *bucket_start,
+                count: 0,
+            })
+            .collect()
+    };
+
+    let sql = format!(
+        r#"
+        SELECT
+            a.snippet_id,
+            s.name,
+            {} as bucket_start,
+            COUNT(*) as usage_count
+        FROM analytics a
+        JOIN snippets s ON a.snippet_id = s.id
+        WHERE a.used_at >= ? AND a.used_at < ?
+        GROUP BY a.snippet_id, bucket_start
+        "#,
+        bucket_sql(bucket)
+    );
+    let rows = sqlx::query_as::<_, (i64, String, i64, i64)>(&sql)
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to fetch usage trends: {}", e)))?;
+
+    let mut global = empty_series();
+    let mut by_snippet: HashMap<i64, SnippetTrend> = HashMap::new();
+    for (snippet_id, snippet_name, bucket_start, count) in rows {
+        let Some(&position) = positions.get(&bucket_start) else {
+            continue;
+        };
+        global[position].count += count;
+
+        let trend = by_snippet
+            .entry(snippet_id)
+            .or_insert_with(|| SnippetTrend {
+                snippet_id,
+                snippet_name,
+                total: 0,
+                points: empty_series(),
+            });
+        trend.total += count;
+        trend.points[position].count += count;
+    }
+
+    let mut snippets: Vec<SnippetTrend> = by_snippet.into_values().collect();
+    snippets.sort_by(|a, b| b.total.cmp(&a.total).then(a.snippet_id.cmp(&b.snippet_id)));
+    snippets.truncate(snippet_limit);
+
+    Ok(UsageTrends {
+        bucket,
+        start,
+        end,
+        global,
+        snippets,
+    })
+}
+
+/// Largest UTC offset in use, in minutes (UTC+14:00)
+const MAX_UTC_OFFSET_MINUTES: i32 = 14 * 60;
+
+/// Returns the current and longest runs of consecutive periods
+///
+/// `periods` must be sorted and free of duplicates. The current run is the
+/// one ending at `current` or the period before it, so a streak is not lost
+/// before the current period is over.
+fn streaks(periods: &[i64], current: i64) -> (i64, i64) {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous = None;
+    for &period in periods {
+        run = if previous == Some(period - 1) {
+            run + 1
+        } else {
+            1
+        };
+        longest = longest.max(run);
+        previous = Some(period);
+    }
+    let current_run = match previous {
+        Some(last) if last == current || last == current - 1 => run,
+        _ => 0,
+    };
+    (current_run, longest)
+}
+
+/// Week number of a day number counted from the Unix epoch, with weeks
+/// starting on Monday (1970-01-01 was a Thursday)
+fn week_of_day(day: i64) -> i64 {
+    (day + 3).div_euclid(7)
+}
+
+/// Get usage streaks, estimated keystrokes saved and the busiest hours
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `now` - Unix timestamp the current streaks are measured up to
+/// * `utc_offset_minutes` - Offset of the user's time zone from UTC, used to
+///   split usages into days, weeks and hours
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidInput` if the offset is beyond ±14 hours and
+/// `AppError::Database` if a query fails
+pub async fn get_productivity_summary(
+    pool: &SqlitePool,
+    now: i64,
+    utc_offset_minutes: i32,
+) -> Result<ProductivitySummary, AppError> {
+    if utc_offset_minutes.abs() > MAX_UTC_OFFSET_MINUTES {
+        return Err(AppError::InvalidInput(format!(
+            "UTC offset must be within ±{} minutes, got {}",
+            MAX_UTC_OFFSET_MINUTES, utc_offset_minutes
+        )));
+    }
+    let offset = i64::from(utc_offset_minutes) * 60;
+
+    let days: Vec<i64> = sqlx::query_scalar(
+        "SELECT DISTINCT (used_at + ?) / 86400 AS day FROM analytics ORDER BY day",
+    )
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to fetch usage days: {}", e)))?;
+    let mut weeks: Vec<i64> = days.iter().map(|day| week_of_day(*day)).collect();
+    weeks.dedup();
+
+    let today = (now + offset).div_euclid(86400);
+    let (current_daily_streak, longest_daily_streak) = streaks(&days, today);
+    let (current_weekly_streak, longest_weekly_streak) = streaks(&weeks, week_of_day(today));
+
+    // Usages of deleted snippets are gone with them, so every usage has content
+    let (total_usages, keystrokes_saved): (i64, i64) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*), COALESCE(SUM(LENGTH(s.content)), 0)
+        FROM analytics a
+        JOIN snippets s ON a.snippet_id = s.id
+        "#,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to estimate keystrokes saved: {}", e)))?;
+
+    let busiest_hours = sqlx::query_as::<_, (i64, i64)>(
+        r#"
+        SELECT ((used_at + ?) % 86400) / 3600 AS hour, COUNT(*) AS usage_count
+        FROM analytics
+        GROUP BY hour
+        ORDER BY usage_count DESC, hour
+        "#,
+    )
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to fetch busiest hours: {}", e)))?
+    .into_iter()
+    .map(|(hour, count)| HourUsage {
+        hour: hour as u8,
+        count,
+    })
+    .collect();
+
+    Ok(ProductivitySummary {
+        total_usages,
+        active_days: days.len() as i64,
+        current_daily_streak,
+        longest_daily_streak,
+        current_weekly_streak,
+        longest_weekly_streak,
+        keystrokes_saved,
+        busiest_hours,
+    })
+}
+
+/// Quotes a CSV field if needed, as described in RFC 4180
+///
+/// Fields containing a comma, double quote, CR or LF are wrapped in double
+/// quotes, with embedded double quotes doubled.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Formats a Unix timestamp as a UTC ISO 8601 date and time
+fn iso_date(timestamp: i64) -> Result<String, AppError> {
+    let datetime = OffsetDateTime::from_unix_timestamp(timestamp)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid timestamp {}: {}", timestamp, e)))?;
+    Ok(format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        datetime.year(),
+        u8::from(datetime.month()),
+        datetime.day(),
+        datetime.hour(),
+        datetime.minute(),
+        datetime.second()
+    ))
+}
+
+/// Export usage events as CSV for spreadsheet analysis
+///
+/// The output follows RFC 4180: a header row, CRLF line endings, and
+/// fields quoted when they contain commas, quotes or line breaks. Events
+/// are ordered most recent first.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `columns` - Columns to write, in order; all columns when empty
+/// * `start` - Optional Unix timestamp; only usages from then on are exported
+/// * `end` - Optional Unix timestamp; only usages before then are exported
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidInput` if the range is empty and
+/// `AppError::Database` if a query fails
+pub async fn export_analytics_csv(
+    pool: &SqlitePool,
+    columns: &[AnalyticsCsvColumn],
+    start: Option<i64>,
+    end: Option<i64>,
+) -> Result<String, AppError> {
+    let columns = if columns.is_empty() {
+        &AnalyticsCsvColumn::ALL[..]
+    } else {
+        columns
+    };
+    let start = start.unwrap_or(i64::MIN);
+    let end = end.unwrap_or(i64::MAX);
+    if start >= end {
+        return Err(AppError::InvalidInput(
+            "Analytics export range must end after it starts".to_string(),
+        ));
+    }
+
+    let rows = sqlx::query_as::<_, (i64, String, i64)>(
+        r#"
+        SELECT a.snippet_id, s.name, a.used_at
+        FROM analytics a
+        JOIN snippets s ON a.snippet_id = s.id
+        WHERE a.used_at >= ? AND a.used_at < ?
+        ORDER BY a.used_at DESC, a.id DESC
+        "#,
+    )
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to fetch analytics: {}", e)))?;
+
+    let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+    if columns.contains(&AnalyticsCsvColumn::Tags) {
+        let tag_rows = sqlx::query_as::<_, (i64, String)>(
+            r#"
+            SELECT st.snippet_id, t.name
+            FROM snippet_tags st
+            JOIN tags t ON st.tag_id = t.id
+            ORDER BY t.name COLLATE NOCASE
+            "#,
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to fetch snippet tags: {}", e)))?;
+        for (snippet_id, tag_name) in tag_rows {
+            tags.entry(snippet_id).or_default().push(tag_name);
+        }
+    }
+
+    let header: Vec<&str> = columns.iter().map(|column| column.header()).collect();
+    let mut csv = header.join(",");
+    csv.push_str("\r\n");
+
+    for (snippet_id, snippet_name, used_at) in rows {
+        let mut fields = Vec::with_capacity(columns.len());
+        for column in columns {
+            let value = match column {
+                AnalyticsCsvColumn::SnippetName => snippet_name.clone(),
+                AnalyticsCsvColumn::Timestamp => used_at.to_string(),
+                AnalyticsCsvColumn::IsoDate => iso_date(used_at)?,
+                AnalyticsCsvColumn::Tags => tags
+                    .get(&snippet_id)
+                    .map(|names| names.join(", "))
+                    .unwrap_or_default(),
+            };
+            fields.push(csv_field(&value));
+        }
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+
+    Ok(csv)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -306,6 +1140,9 @@ mod tests {
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 snippet_id INTEGER NOT NULL,
                 used_at INTEGER NOT NULL,
+                source TEXT,
+                tag_filter TEXT,
+                active_app TEXT,
                 FOREIGN KEY (snippet_id) REFERENCES snippets(id) ON DELETE CASCADE
             )
             "#,
@@ -355,6 +1192,68 @@ mod tests {
         assert_eq!(count.0, 1);
     }
 
+    #[tokio::test]
+    async fn test_flush_usage() {
+        let pool = setup_test_db().await;
+        let queue = UsageQueue::default();
+        let overlay = UsageContext {
+            source: Some(UsageSource::SearchOverlay),
+            tag_filter: Some(" git ".to_string()),
+            active_app: Some("kitty".to_string()),
+        };
+        queue.push(1, &overlay).unwrap();
+        queue.push(2, &UsageContext::default()).unwrap();
+        // Deleted before the flush
+        queue.push(99, &UsageContext::default()).unwrap();
+        assert_eq!(queue.pending(), 3);
+
+        // Nothing is written until the queue is flushed
+        assert_eq!(
+            get_global_analytics(&pool, 10, 20)
+                .await
+                .unwrap()
+                .total_usages,
+            0
+        );
+
+        assert_eq!(flush_usage(&pool, &queue).await.unwrap(), 2);
+        assert_eq!(queue.pending(), 0);
+        let row: (i64, Option<String>, Option<String>, Option<String>) = sqlx::query_as(
+            "SELECT snippet_id, source, tag_filter, active_app FROM analytics ORDER BY id",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            row,
+            (
+                1,
+                Some("search_overlay".to_string()),
+                Some("git".to_string()),
+                Some("kitty".to_string())
+            )
+        );
+        assert_eq!(flush_usage(&pool, &queue).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_flush_usage_keeps_events_on_failure() {
+        let pool = setup_test_db().await;
+        let queue = UsageQueue::default();
+        queue.push(1, &UsageContext::default()).unwrap();
+        queue.push(2, &UsageContext::default()).unwrap();
+
+        sqlx::query("DROP TABLE analytics")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(flush_usage(&pool, &queue).await.is_err());
+        assert_eq!(queue.pending(), 2);
+
+        queue.discard();
+        assert_eq!(queue.pending(), 0);
+    }
+
     #[tokio::test]
     async fn test_get_snippet_analytics_no_usage() {
         let pool = setup_test_db().await;
@@ -425,6 +1324,21 @@ mod tests {
         assert_eq!(analytics.recent_activity.len(), 3);
     }
 
+    #[tokio::test]
+    async fn test_most_recently_used_snippet() {
+        let pool = setup_test_db().await;
+        assert_eq!(most_recently_used_snippet(&pool).await.unwrap(), None);
+
+        sqlx::query(
+            "INSERT INTO analytics (snippet_id, used_at) VALUES (2, 50), (1, 100), (2, 100)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        // Ties on used_at go to the latest recorded usage
+        assert_eq!(most_recently_used_snippet(&pool).await.unwrap(), Some(2));
+    }
+
     #[tokio::test]
     async fn test_get_global_analytics_empty() {
         let pool = setup_test_db().await;
@@ -436,4 +1350,305 @@ mod tests {
         assert_eq!(analytics.most_used_snippets.len(), 0);
         assert_eq!(analytics.recent_activity.len(), 0);
     }
+
+    #[tokio::test]
+    async fn test_get_analytics_by_source() {
+        let pool = setup_test_db().await;
+        let overlay = UsageContext {
+            source: Some(UsageSource::SearchOverlay),
+            ..Default::default()
+        };
+        record_usage_with_context(&pool, 1, &overlay).await.unwrap();
+        record_usage_with_context(&pool, 2, &overlay).await.unwrap();
+        record_usage(&pool, 1).await.unwrap();
+
+        let usages = get_analytics_by_source(&pool, None).await.unwrap();
+        assert_eq!(usages.len(), 2);
+        assert_eq!(usages[0].source, Some(UsageSource::SearchOverlay));
+        assert_eq!(usages[0].usage_count, 2);
+        assert_eq!(usages[1].source, None);
+
+        assert!(get_analytics_by_source(&pool, Some(i64::MAX))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_analytics_by_tag() {
+        let pool = setup_test_db().await;
+        sqlx::raw_sql(
+            "CREATE TABLE tags (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+             CREATE TABLE snippet_tags (snippet_id INTEGER NOT NULL, tag_id INTEGER NOT NULL);
+             INSERT INTO tags (id, name) VALUES (1, 'Git'), (2, 'docker');
+             INSERT INTO snippet_tags (snippet_id, tag_id) VALUES (1, 1), (1, 2), (2, 1);",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let filtered = UsageContext {
+            source: Some(UsageSource::SearchOverlay),
+            tag_filter: Some("git".to_string()),
+            ..Default::default()
+        };
+        record_usage_with_context(&pool, 1, &filtered)
+            .await
+            .unwrap();
+        record_usage(&pool, 2).await.unwrap();
+        record_usage(&pool, 2).await.unwrap();
+
+        let usages = get_analytics_by_tag(&pool, None).await.unwrap();
+        let summary: Vec<(&str, i64, i64)> = usages
+            .iter()
+            .map(|u| (u.tag_name.as_str(), u.usage_count, u.filtered_count))
+            .collect();
+        assert_eq!(summary, vec![("Git", 3, 1), ("docker", 1, 0)]);
+    }
+
+    // 2024-01-01 00:00:00 UTC, a Monday
+    const JAN_1: i64 = 1_704_067_200;
+    const DAY: i64 = 24 * 3600;
+
+    #[test]
+    fn test_bucket_start() {
+        let wednesday_noon = JAN_1 + 2 * DAY + 12 * 3600;
+        assert_eq!(
+            bucket_start(wednesday_noon, TrendBucket::Day).unwrap(),
+            JAN_1 + 2 * DAY
+        );
+        assert_eq!(
+            bucket_start(wednesday_noon, TrendBucket::Week).unwrap(),
+            JAN_1
+        );
+        // Sunday belongs to the week that started the Monday before
+        assert_eq!(
+            bucket_start(JAN_1 + 6 * DAY + 3600, TrendBucket::Week).unwrap(),
+            JAN_1
+        );
+        assert_eq!(
+            bucket_start(JAN_1 + 40 * DAY, TrendBucket::Month).unwrap(),
+            JAN_1 + 31 * DAY
+        );
+        assert_eq!(
+            next_bucket_start(JAN_1 + 335 * DAY, TrendBucket::Month).unwrap(),
+            JAN_1 + 366 * DAY
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_usage_trends() {
+        let pool = setup_test_db().await;
+        // Snippet 1: twice on Jan 1, once on Jan 8 (Monday); snippet 2: Sunday Jan 7
+        sqlx::query(
+            "INSERT INTO analytics (snippet_id, used_at) VALUES (?, ?), (?, ?), (?, ?), (?, ?)",
+        )
+        .bind(1)
+        .bind(JAN_1 + 60)
+        .bind(1)
+        .bind(JAN_1 + 3600)
+        .bind(1)
+        .bind(JAN_1 + 7 * DAY + 60)
+        .bind(2)
+        .bind(JAN_1 + 6 * DAY + 60)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let trends = get_usage_trends(&pool, TrendBucket::Day, JAN_1, JAN_1 + 14 * DAY, 10)
+            .await
+            .unwrap();
+        assert_eq!(trends.global.len(), 14);
+        assert_eq!(trends.global[0].count, 2);
+        assert_eq!(trends.global[6].count, 1);
+        assert_eq!(trends.global[7].count, 1);
+        assert_eq!(trends.snippets[0].snippet_id, 1);
+        assert_eq!(trends.snippets[0].total, 3);
+        assert_eq!(trends.snippets[0].points.len(), 14);
+
+        let trends = get_usage_trends(&pool, TrendBucket::Week, JAN_1, JAN_1 + 14 * DAY, 1)
+            .await
+            .unwrap();
+        let counts: Vec<i64> = trends.global.iter().map(|p| p.count).collect();
+        assert_eq!(counts, vec![3, 1]);
+        assert_eq!(trends.snippets.len(), 1);
+
+        let trends = get_usage_trends(&pool, TrendBucket::Month, JAN_1, JAN_1 + DAY, 10)
+            .await
+            .unwrap();
+        assert_eq!(trends.global.len(), 1);
+        assert_eq!(trends.global[0].count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_usage_trends_rejects_bad_ranges() {
+        let pool = setup_test_db().await;
+        assert!(get_usage_trends(&pool, TrendBucket::Day, 100, 100, 10)
+            .await
+            .is_err());
+        assert!(
+            get_usage_trends(&pool, TrendBucket::Day, JAN_1, JAN_1 + 2000 * DAY, 10)
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_streaks() {
+        assert_eq!(streaks(&[], 10), (0, 0));
+        assert_eq!(streaks(&[1, 2, 3, 7, 8], 8), (2, 3));
+        // Still current until a whole period passes without a usage
+        assert_eq!(streaks(&[1, 2, 3, 7, 8], 9), (2, 3));
+        assert_eq!(streaks(&[1, 2, 3, 7, 8], 10), (0, 3));
+        assert_eq!(week_of_day(0), 0);
+        // 1970-01-05 was the first Monday
+        assert_eq!(week_of_day(4), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_productivity_summary() {
+        let pool = setup_test_db().await;
+        // Jan 1 (Monday) to Jan 3 daily, then Jan 8 and Jan 9 in the next week
+        for (snippet_id, used_at) in [
+            (1, JAN_1 + 9 * 3600),
+            (1, JAN_1 + DAY + 9 * 3600),
+            (2, JAN_1 + 2 * DAY + 23 * 3600 + 1800),
+            (1, JAN_1 + 7 * DAY + 9 * 3600),
+            (2, JAN_1 + 8 * DAY + 14 * 3600),
+        ] {
+            sqlx::query("INSERT INTO analytics (snippet_id, used_at) VALUES (?, ?)")
+                .bind(snippet_id)
+                .bind(used_at)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let summary = get_productivity_summary(&pool, JAN_1 + 9 * DAY + 3600, 0)
+            .await
+            .unwrap();
+        assert_eq!(summary.total_usages, 5);
+        assert_eq!(summary.active_days, 5);
+        assert_eq!(
+            (summary.current_daily_streak, summary.longest_daily_streak),
+            (2, 3)
+        );
+        assert_eq!(
+            (summary.current_weekly_streak, summary.longest_weekly_streak),
+            (2, 2)
+        );
+        // "Content 1" and "Content 2" are 9 characters each
+        assert_eq!(summary.keystrokes_saved, 45);
+        assert_eq!(summary.busiest_hours[0], HourUsage { hour: 9, count: 3 });
+        assert_eq!(summary.busiest_hours.len(), 3);
+
+        // An hour ahead, the late usage on Jan 3 moves to Jan 4 and breaks the streak
+        let summary = get_productivity_summary(&pool, JAN_1 + 30 * DAY, 60)
+            .await
+            .unwrap();
+        assert_eq!(summary.longest_daily_streak, 2);
+        assert_eq!(summary.current_daily_streak, 0);
+        assert_eq!(summary.busiest_hours[0], HourUsage { hour: 10, count: 3 });
+        assert!(summary.busiest_hours.iter().any(|h| h.hour == 0));
+
+        assert!(get_productivity_summary(&pool, JAN_1, 15 * 60)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+        assert_eq!(
+            iso_date(JAN_1 + 14 * 3600 + 5 * 60).unwrap(),
+            "2024-01-01T14:05:00Z"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_analytics_csv() {
+        let pool = setup_test_db().await;
+        sqlx::raw_sql(
+            "CREATE TABLE tags (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+             CREATE TABLE snippet_tags (snippet_id INTEGER NOT NULL, tag_id INTEGER NOT NULL);
+             INSERT INTO tags (id, name) VALUES (1, 'shell'), (2, 'Git');
+             INSERT INTO snippet_tags (snippet_id, tag_id) VALUES (1, 1), (1, 2);
+             UPDATE snippets SET name = 'Undo, \"softly\"' WHERE id = 1;",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        for (snippet_id, used_at) in [(1, JAN_1), (2, JAN_1 + DAY), (1, JAN_1 + 2 * DAY)] {
+            sqlx::query("INSERT INTO analytics (snippet_id, used_at) VALUES (?, ?)")
+                .bind(snippet_id)
+                .bind(used_at)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let csv = export_analytics_csv(&pool, &[], None, None).await.unwrap();
+        assert_eq!(
+            csv,
+            "snippet_name,timestamp,iso_date,tags\r\n\
+             \"Undo, \"\"softly\"\"\",1704240000,2024-01-03T00:00:00Z,\"Git, shell\"\r\n\
+             Test Snippet 2,1704153600,2024-01-02T00:00:00Z,\r\n\
+             \"Undo, \"\"softly\"\"\",1704067200,2024-01-01T00:00:00Z,\"Git, shell\"\r\n"
+        );
+
+        let ranged = export_analytics_csv(
+            &pool,
+            &[
+                AnalyticsCsvColumn::Timestamp,
+                AnalyticsCsvColumn::SnippetName,
+            ],
+            Some(JAN_1 + DAY),
+            Some(JAN_1 + 2 * DAY),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            ranged,
+            "timestamp,snippet_name\r\n1704153600,Test Snippet 2\r\n"
+        );
+
+        assert!(matches!(
+            export_analytics_csv(&pool, &[], Some(JAN_1), Some(JAN_1)).await,
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_should_purge() {
+        let privacy = |(enable_analytics, track_usage, purge_on_disable)| PrivacySettings {
+            enable_analytics,
+            track_usage,
+            purge_on_disable,
+        };
+        let cases = [
+            // Tracking turned off with purging on
+            ((true, true, true), (true, false, true), true),
+            ((true, true, true), (false, true, true), true),
+            // Purging enabled while tracking was already off
+            ((false, false, false), (false, false, true), true),
+            // Tracking off without purging
+            ((true, true, false), (false, true, false), false),
+            // Unrelated save while already purged
+            ((false, true, true), (false, true, true), false),
+            // Tracking turned back on
+            ((false, true, true), (true, true, true), false),
+        ];
+        for (previous, current, expected) in cases {
+            assert_eq!(
+                should_purge(&privacy(previous), &privacy(current)),
+                expected,
+                "{:?} -> {:?}",
+                previous,
+                current
+            );
+        }
+    }
 }
@@ -0,0 +1,506 @@
+use crate::commands::{analytics_commands, snippet_commands};
+use crate::models::analytics::UsageSource;
+use crate::models::settings::ApiServerSettings;
+use crate::models::SnippetId;
+use crate::services::api_tokens::{self, bearer_token};
+use crate::services::confirmation::{Confirmation, ConfirmationState, DestructiveAction};
+use crate::services::database::get_pool;
+use crate::services::search;
+use crate::utils::error::AppError;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{oneshot, Mutex};
+
+/// Maximum size of the request line plus headers
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+/// Maximum request body size (1 MiB)
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Time a client gets to send its request before the connection is dropped
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Whether the HTTP API server is running
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiServerStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    /// Why the server could not start (e.g. the port is in use)
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct ServerHandle {
+    running: Option<(u16, oneshot::Sender<()>)>,
+    last_error: Option<String>,
+}
+
+/// State holding the running HTTP API server, if any
+#[derive(Debug, Default)]
+pub struct ApiServerState(Mutex<ServerHandle>);
+
+/// Starts, stops or restarts the server to match the settings
+///
+/// The server only ever binds to the loopback interface. Failures to bind are
+/// reported through `status` rather than returned, so saving settings never
+/// fails because of a busy port.
+pub async fn apply_settings(app: &AppHandle, settings: &ApiServerSettings) {
+    let Some(state) = app.try_state::<ApiServerState>() else {
+        return;
+    };
+    let mut handle = state.0.lock().await;
+
+    let wanted = settings.enabled.then_some(settings.port);
+    if handle.running.as_ref().map(|(port, _)| *port) == wanted {
+        return;
+    }
+
+    if let Some((_, shutdown)) = handle.running.take() {
+        let _ = shutdown.send(());
+    }
+    handle.last_error = None;
+
+    let Some(port) = wanted else {
+        return;
+    };
+    match TcpListener::bind(("127.0.0.1", port)).await {
+        Ok(listener) => {
+            let (shutdown, stopped) = oneshot::channel();
+            tauri::async_runtime::spawn(serve(app.clone(), listener, stopped));
+            handle.running = Some((port, shutdown));
+        }
+        Err(e) => {
+            let message = format!("Failed to listen on 127.0.0.1:{}: {}", port, e);
+            eprintln!("{}", message);
+            handle.last_error = Some(message);
+        }
+    }
+}
+
+/// Reports whether the server is running
+pub async fn status(app: &AppHandle) -> ApiServerStatus {
+    let Some(state) = app.try_state::<ApiServerState>() else {
+        return ApiServerStatus {
+            running: false,
+            port: None,
+            error: None,
+        };
+    };
+    let handle = state.0.lock().await;
+    ApiServerStatus {
+        running: handle.running.is_some(),
+        port: handle.running.as_ref().map(|(port, _)| *port),
+        error: handle.last_error.clone(),
+    }
+}
+
+async fn serve(app: AppHandle, listener: TcpListener, mut stopped: oneshot::Receiver<()>) {
+    loop {
+        tokio::select! {
+            _ = &mut stopped => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    tauri::async_runtime::spawn(handle_connection(app.clone(), stream));
+                }
+                Err(e) => eprintln!("API server accept failed: {}", e),
+            },
+        }
+    }
+}
+
+async fn handle_connection(app: AppHandle, mut stream: TcpStream) {
+    let response = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(request)) => handle_request(&app, request).await,
+        Ok(Err(response)) => response,
+        Err(_) => Response::error(408, "Request timed out"),
+    };
+
+    let body = response.body.to_string();
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n",
+        response.status,
+        reason_phrase(response.status),
+        body.len()
+    );
+    let _ = stream.write_all(head.as_bytes()).await;
+    let _ = stream.write_all(body.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// A parsed HTTP request
+#[derive(Debug, Clone, PartialEq)]
+struct Request {
+    method: String,
+    /// Path without the query string
+    path: String,
+    query: HashMap<String, String>,
+    /// Header names are lowercased
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Response {
+    status: u16,
+    body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message }),
+        }
+    }
+
+    fn from_error(message: &str) -> Self {
+        Self::error(status_for_error(message), message)
+    }
+}
+
+async fn read_request<R: AsyncRead + Unpin>(stream: R) -> Result<Request, Response> {
+    let mut reader = BufReader::new(stream);
+    let mut head = String::new();
+    loop {
+        let read = reader
+            .read_line(&mut head)
+            .await
+            .map_err(|_| Response::error(400, "Malformed request"))?;
+        if head.len() > MAX_HEAD_BYTES {
+            return Err(Response::error(431, "Request headers too large"));
+        }
+        if read == 0 || head.ends_with("\r\n\r\n") || head.ends_with("\n\n") {
+            break;
+        }
+    }
+
+    let mut request = parse_head(&head).ok_or_else(|| Response::error(400, "Malformed request"))?;
+    let length = match request.header("content-length") {
+        Some(value) => value
+            .trim()
+            .parse::<usize>()
+            .map_err(|_| Response::error(400, "Invalid Content-Length"))?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(Response::error(413, "Request body too large"));
+    }
+    request.body = vec![0; length];
+    reader
+        .read_exact(&mut request.body)
+        .await
+        .map_err(|_| Response::error(400, "Incomplete request body"))?;
+
+    Ok(request)
+}
+
+/// Parses the request line and headers
+fn parse_head(head: &str) -> Option<Request> {
+    let mut lines = head.lines();
+    let mut parts = lines.next()?.split_whitespace();
+    let method = parts.next()?.to_ascii_uppercase();
+    let target = parts.next()?;
+    if !parts.next()?.starts_with("HTTP/1.") {
+        return None;
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let headers = lines
+        .take_while(|line| !line.trim().is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+
+    Some(Request {
+        method,
+        path: percent_decode(path),
+        query: parse_query(query),
+        headers,
+        body: Vec::new(),
+    })
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (
+                percent_decode(&key.replace('+', " ")),
+                percent_decode(&value.replace('+', " ")),
+            )
+        })
+        .collect()
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or_default();
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Rejects requests whose `Host` is not a loopback name
+///
+/// Browsers always send the page's host, so this stops DNS-rebinding pages
+/// from reaching the API through the user's browser.
+fn is_loopback_host(host: Option<&str>) -> bool {
+    let Some(host) = host else {
+        // HTTP/1.0 clients may omit Host; browsers never do
+        return true;
+    };
+    let name = if let Some(rest) = host.strip_prefix('[') {
+        rest.split(']').next().unwrap_or_default()
+    } else {
+        host.split(':').next().unwrap_or_default()
+    };
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "127.0.0.1" | "localhost" | "::1"
+    )
+}
+
+/// Maps an error message in `AppError` display form to an HTTP status
+fn status_for_error(message: &str) -> u16 {
+    let prefixes = [
+        ("Not found:", 404),
+        ("Invalid input:", 400),
+        ("Validation error:", 400),
+        ("Duplicate entry:", 409),
+        ("Unauthorized:", 401),
+        ("Forbidden:", 403),
+    ];
+    prefixes
+        .iter()
+        .find(|(prefix, _)| message.starts_with(prefix))
+        .map_or(500, |(_, status)| *status)
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchBody {
+    query: String,
+    limit: Option<i64>,
+}
+
+async fn handle_request(app: &AppHandle, request: Request) -> Response {
+    if !is_loopback_host(request.header("host")) {
+        return Response::error(403, "Requests must be addressed to localhost");
+    }
+
+    let pool = match get_pool(app) {
+        Ok(pool) => pool,
+        Err(e) => return Response::from_error(&e.to_string()),
+    };
+    let presented = request.header("authorization").and_then(bearer_token);
+    if let Err(e) =
+        api_tokens::authorize_request(&pool, presented, &request.method, &request.path).await
+    {
+        return Response::from_error(&e.to_string());
+    }
+
+    route(app, &request)
+        .await
+        .unwrap_or_else(|message| Response::from_error(&message))
+}
+
+async fn route(app: &AppHandle, request: &Request) -> Result<Response, String> {
+    let segments: Vec<&str> = request
+        .path
+        .trim_matches('/')
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["snippets"]) => {
+            let snippets =
+                snippet_commands::get_all_snippets(app.clone(), None, None, None, None).await?;
+            Ok(Response::ok(json!(snippets)))
+        }
+        ("GET", ["snippets", id]) => {
+            let snippet = snippet_commands::get_snippet(app.clone(), parse_id(id)?).await?;
+            Ok(Response::ok(json!(snippet)))
+        }
+        ("DELETE", ["snippets", id]) => {
+            let id = parse_id(id)?;
+            let force = request.query.get("force").is_some_and(|v| v == "true");
+            let state = app
+                .try_state::<ConfirmationState>()
+                .ok_or_else(|| AppError::Unknown("Confirmation is not initialized".to_string()))?;
+            let confirmation = state.check(
+                &DestructiveAction::DeleteSnippet { id: id.0 },
+                request.header("x-confirmation-token"),
+                force,
+            )?;
+            match confirmation {
+                Confirmation::Required(challenge) => Ok(Response {
+                    status: 409,
+                    body: json!({ "confirmation_required": challenge }),
+                }),
+                Confirmation::Confirmed => {
+                    snippet_commands::delete_snippet(app.clone(), id).await?;
+                    Ok(Response::ok(json!({ "deleted": id.0 })))
+                }
+            }
+        }
+        ("GET", ["search"]) => {
+            let query = request.query.get("q").cloned().unwrap_or_default();
+            let limit = match request.query.get("limit") {
+                Some(limit) => Some(
+                    limit
+                        .parse()
+                        .map_err(|_| AppError::InvalidInput("limit must be a number".into()))?,
+                ),
+                None => None,
+            };
+            let results = search::search_snippets(app, &query, limit, None, None)
+                .await?
+                .results;
+            Ok(Response::ok(json!(results)))
+        }
+        ("POST", ["search"]) => {
+            let body: SearchBody = serde_json::from_slice(&request.body)
+                .map_err(|e| AppError::InvalidInput(format!("Invalid search body: {}", e)))?;
+            let results = search::search_snippets(app, &body.query, body.limit, None, None)
+                .await?
+                .results;
+            Ok(Response::ok(json!(results)))
+        }
+        ("POST", ["copy", id]) => {
+            let id = parse_id(id)?;
+            // Fails for missing snippets before anything touches the clipboard
+            snippet_commands::get_snippet(app.clone(), id).await?;
+            // Dynamic snippets are refused: confirmation can only be given in the app
+            analytics_commands::copy_snippets_with_analytics(
+                app.clone(),
+                vec![id.0],
+                None,
+                Some(UsageSource::Api),
+                None,
+                None,
+                None,
+            )
+            .await?;
+            Ok(Response::ok(json!({ "copied": id.0 })))
+        }
+        _ => Ok(Response::error(405, "Unsupported method or route")),
+    }
+}
+
+fn parse_id(segment: &str) -> Result<SnippetId, AppError> {
+    segment
+        .parse()
+        .map(SnippetId)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid snippet id '{}'", segment)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_request() {
+        let raw = b"POST /search?x=1 HTTP/1.1\r\nHost: localhost:38274\r\n\
+            Authorization: Bearer snips_abc\r\nContent-Length: 17\r\n\r\n{\"query\":\"hook\"}\n";
+        let request = read_request(&raw[..]).await.unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/search");
+        assert_eq!(request.header("authorization"), Some("Bearer snips_abc"));
+        assert_eq!(request.body, b"{\"query\":\"hook\"}\n");
+
+        let body: SearchBody = serde_json::from_slice(&request.body).unwrap();
+        assert_eq!(body.query, "hook");
+    }
+
+    #[tokio::test]
+    async fn test_read_request_rejects_large_body() {
+        let raw = format!(
+            "POST /search HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY_BYTES + 1
+        );
+        let response = read_request(raw.as_bytes()).await.unwrap_err();
+        assert_eq!(response.status, 413);
+    }
+
+    #[test]
+    fn test_parse_head_query() {
+        let request = parse_head("GET /search?q=react+hooks%21&limit=5 HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(request.query["q"], "react hooks!");
+        assert_eq!(request.query["limit"], "5");
+
+        assert!(parse_head("GET /search\r\n").is_none());
+        assert!(parse_head("").is_none());
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("a%20b"), "a b");
+        assert_eq!(percent_decode("%E2%9C%93"), "✓");
+        // Malformed escapes are kept as-is
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+        assert_eq!(percent_decode("%é"), "%é");
+    }
+
+    #[test]
+    fn test_is_loopback_host() {
+        assert!(is_loopback_host(Some("localhost:38274")));
+        assert!(is_loopback_host(Some("127.0.0.1")));
+        assert!(is_loopback_host(Some("[::1]:38274")));
+        assert!(is_loopback_host(None));
+        assert!(!is_loopback_host(Some("attacker.example:38274")));
+    }
+
+    #[test]
+    fn test_status_for_error() {
+        assert_eq!(status_for_error("Not found: Snippet with id 3"), 404);
+        assert_eq!(status_for_error("Unauthorized: Missing token"), 401);
+        assert_eq!(status_for_error("Forbidden: Token lacks scope"), 403);
+        assert_eq!(status_for_error("SQL error: disk I/O"), 500);
+    }
+}
@@ -0,0 +1,571 @@
+use crate::utils::error::AppError;
+use crate::utils::time::current_timestamp;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqlitePool};
+
+/// Prefix of every generated token, so leaked tokens are easy to recognise
+const TOKEN_PREFIX: &str = "snips_";
+/// Number of random bytes in a token (hex encoded after the prefix)
+const TOKEN_BYTES: usize = 32;
+/// Number of leading token characters kept in plaintext for display
+const DISPLAY_PREFIX_LEN: usize = TOKEN_PREFIX.len() + 6;
+const MAX_TOKEN_NAME_LENGTH: usize = 100;
+/// Audit rows beyond this count are trimmed, oldest first
+const MAX_AUDIT_ENTRIES: i64 = 10_000;
+
+/// Permission a token can be granted for the local REST API
+///
+/// Scopes are hierarchical: `write` implies `read`, and `read` implies
+/// `search`. A search-only token can run queries but never fetch or change
+/// snippets directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiScope {
+    Search,
+    Read,
+    Write,
+}
+
+impl ApiScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Search => "search",
+            Self::Read => "read",
+            Self::Write => "write",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "search" => Some(Self::Search),
+            "read" => Some(Self::Read),
+            "write" => Some(Self::Write),
+            _ => None,
+        }
+    }
+
+    /// Whether holding this scope satisfies a route requiring `required`
+    pub fn grants(self, required: ApiScope) -> bool {
+        match self {
+            Self::Write => true,
+            Self::Read => matches!(required, Self::Read | Self::Search),
+            Self::Search => required == Self::Search,
+        }
+    }
+}
+
+/// Stored API token metadata (never includes the secret)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: i64,
+    pub name: String,
+    /// First characters of the token, for telling tokens apart in the UI
+    pub token_prefix: String,
+    pub scopes: Vec<ApiScope>,
+    pub created_at: i64,
+    pub last_used_at: Option<i64>,
+    pub revoked_at: Option<i64>,
+}
+
+/// A newly minted token; `secret` is only ever returned here
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedApiToken {
+    pub token: ApiToken,
+    pub secret: String,
+}
+
+/// One request recorded in the token audit trail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTokenAuditEntry {
+    pub id: i64,
+    /// `None` when the request presented no token or an unknown one
+    pub token_id: Option<i64>,
+    pub token_name: Option<String>,
+    pub method: String,
+    pub route: String,
+    pub required_scope: Option<ApiScope>,
+    pub allowed: bool,
+    /// Why the request was rejected; `None` for allowed requests
+    pub reason: Option<String>,
+    pub created_at: i64,
+}
+
+/// Scope required to call a REST route
+///
+/// # Arguments
+///
+/// * `method` - HTTP method (case-insensitive)
+/// * `path` - Request path; any query string is ignored
+///
+/// # Returns
+///
+/// The required scope, or `None` for routes the API does not expose. Unknown
+/// routes must be rejected rather than treated as unprotected.
+pub fn required_scope(method: &str, path: &str) -> Option<ApiScope> {
+    let path = path.split('?').next().unwrap_or_default();
+    let resource = path.trim_matches('/').split('/').next().unwrap_or_default();
+    let method = method.to_ascii_uppercase();
+
+    match (resource, method.as_str()) {
+        ("search", "GET" | "POST") => Some(ApiScope::Search),
+        ("copy", "POST") => Some(ApiScope::Read),
+        ("snippets" | "tags", "GET" | "HEAD") => Some(ApiScope::Read),
+        ("snippets" | "tags", "POST" | "PUT" | "PATCH" | "DELETE") => Some(ApiScope::Write),
+        _ => None,
+    }
+}
+
+/// Extracts the token from an `Authorization: Bearer <token>` header value
+pub fn bearer_token(header: &str) -> Option<&str> {
+    let (scheme, token) = header.trim().split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+}
+
+/// Mints a new token with the given scopes
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `name` - Human-readable label (e.g. "Raycast extension")
+/// * `scopes` - Scopes to grant; duplicates are ignored
+///
+/// # Returns
+///
+/// The stored token metadata together with the plaintext secret, which is
+/// not recoverable afterwards
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidInput` for an empty name or scope list, or
+/// `AppError::Database` if the random source or database fails
+pub async fn create_token(
+    pool: &SqlitePool,
+    name: &str,
+    scopes: &[ApiScope],
+) -> Result<CreatedApiToken, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::InvalidInput(
+            "Token name cannot be empty".to_string(),
+        ));
+    }
+    if name.len() > MAX_TOKEN_NAME_LENGTH {
+        return Err(AppError::InvalidInput(format!(
+            "Token name too long (max {} characters)",
+            MAX_TOKEN_NAME_LENGTH
+        )));
+    }
+
+    let mut unique_scopes: Vec<ApiScope> = Vec::new();
+    for scope in scopes {
+        if !unique_scopes.contains(scope) {
+            unique_scopes.push(*scope);
+        }
+    }
+    if unique_scopes.is_empty() {
+        return Err(AppError::InvalidInput(
+            "A token needs at least one scope".to_string(),
+        ));
+    }
+
+    let secret = generate_secret()?;
+    let token_prefix = secret[..DISPLAY_PREFIX_LEN].to_string();
+    let now = current_timestamp();
+
+    let result = sqlx::query(
+        "INSERT INTO api_tokens (name, token_hash, token_prefix, scopes, created_at)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(name)
+    .bind(hash_token(&secret))
+    .bind(&token_prefix)
+    .bind(encode_scopes(&unique_scopes))
+    .bind(now)
+    .execute(pool)
+    .await?;
+
+    Ok(CreatedApiToken {
+        token: ApiToken {
+            id: result.last_insert_rowid(),
+            name: name.to_string(),
+            token_prefix,
+            scopes: unique_scopes,
+            created_at: now,
+            last_used_at: None,
+            revoked_at: None,
+        },
+        secret,
+    })
+}
+
+/// Lists all tokens, newest first, including revoked ones
+///
+/// # Errors
+///
+/// Returns `AppError` if the database query fails
+pub async fn list_tokens(pool: &SqlitePool) -> Result<Vec<ApiToken>, AppError> {
+    let rows = sqlx::query(
+        "SELECT id, name, token_prefix, scopes, created_at, last_used_at, revoked_at
+         FROM api_tokens
+         ORDER BY created_at DESC, id DESC",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(token_from_row).collect())
+}
+
+/// Revokes a token so it can no longer authenticate
+///
+/// Revoking an already revoked token is a no-op. The token row is kept so
+/// the audit trail still resolves its name.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if no token has the given ID
+pub async fn revoke_token(pool: &SqlitePool, id: i64) -> Result<(), AppError> {
+    let exists = sqlx::query("SELECT 1 FROM api_tokens WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    if exists.is_none() {
+        return Err(AppError::NotFound(format!("API token {} not found", id)));
+    }
+
+    sqlx::query("UPDATE api_tokens SET revoked_at = ? WHERE id = ? AND revoked_at IS NULL")
+        .bind(current_timestamp())
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Checks a request against the token store and records it in the audit trail
+///
+/// This is the single entry point the HTTP layer calls for every request.
+/// Every outcome—allowed, unauthenticated or forbidden—is audited.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `presented` - Token taken from the request, if any (see `bearer_token`)
+/// * `method` - HTTP method
+/// * `path` - Request path
+///
+/// # Returns
+///
+/// The authenticated token when it holds the scope the route requires
+///
+/// # Errors
+///
+/// * `AppError::Unauthorized` - No token, unknown token or revoked token
+/// * `AppError::Forbidden` - Valid token without the required scope, or unknown route
+pub async fn authorize_request(
+    pool: &SqlitePool,
+    presented: Option<&str>,
+    method: &str,
+    path: &str,
+) -> Result<ApiToken, AppError> {
+    let route = path.split('?').next().unwrap_or_default();
+    let required = required_scope(method, route);
+
+    let token = match presented {
+        Some(secret) => {
+            let row = sqlx::query(
+                "SELECT id, name, token_prefix, scopes, created_at, last_used_at, revoked_at
+                 FROM api_tokens WHERE token_hash = ?",
+            )
+            .bind(hash_token(secret))
+            .fetch_optional(pool)
+            .await?;
+            row.as_ref().map(token_from_row)
+        }
+        None => None,
+    };
+
+    let token_id = token.as_ref().map(|t| t.id);
+    let outcome = match (token, required) {
+        (None, _) if presented.is_none() => {
+            Err(AppError::Unauthorized("Missing API token".to_string()))
+        }
+        (None, _) => Err(AppError::Unauthorized("Unknown API token".to_string())),
+        (Some(token), _) if token.revoked_at.is_some() => Err(AppError::Unauthorized(
+            "API token has been revoked".to_string(),
+        )),
+        (Some(_), None) => Err(AppError::Forbidden(format!(
+            "{} {} is not an API route",
+            method, route
+        ))),
+        (Some(token), Some(scope)) if !token.scopes.iter().any(|s| s.grants(scope)) => Err(
+            AppError::Forbidden(format!("API token lacks the '{}' scope", scope.as_str())),
+        ),
+        (Some(token), Some(_)) => Ok(token),
+    };
+
+    let now = current_timestamp();
+    let reason = outcome.as_ref().err().map(|e| e.to_string());
+    sqlx::query(
+        "INSERT INTO api_token_audit
+         (token_id, method, route, required_scope, allowed, reason, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(token_id)
+    .bind(method.to_ascii_uppercase())
+    .bind(route)
+    .bind(required.map(ApiScope::as_str))
+    .bind(outcome.is_ok())
+    .bind(reason)
+    .bind(now)
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        "DELETE FROM api_token_audit
+         WHERE id <= (SELECT MAX(id) FROM api_token_audit) - ?",
+    )
+    .bind(MAX_AUDIT_ENTRIES)
+    .execute(pool)
+    .await?;
+
+    let mut token = outcome?;
+    sqlx::query("UPDATE api_tokens SET last_used_at = ? WHERE id = ?")
+        .bind(now)
+        .bind(token.id)
+        .execute(pool)
+        .await?;
+    token.last_used_at = Some(now);
+
+    Ok(token)
+}
+
+/// Reads the audit trail, newest first
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `token_id` - Restrict to one token; `None` returns every entry
+/// * `limit` - Maximum number of entries
+///
+/// # Errors
+///
+/// Returns `AppError` if the database query fails
+pub async fn get_audit_log(
+    pool: &SqlitePool,
+    token_id: Option<i64>,
+    limit: i64,
+) -> Result<Vec<ApiTokenAuditEntry>, AppError> {
+    let rows = sqlx::query(
+        "SELECT a.id, a.token_id, t.name AS token_name, a.method, a.route,
+                a.required_scope, a.allowed, a.reason, a.created_at
+         FROM api_token_audit a
+         LEFT JOIN api_tokens t ON t.id = a.token_id
+         WHERE ? IS NULL OR a.token_id = ?
+         ORDER BY a.id DESC
+         LIMIT ?",
+    )
+    .bind(token_id)
+    .bind(token_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ApiTokenAuditEntry {
+            id: row.get("id"),
+            token_id: row.get("token_id"),
+            token_name: row.get("token_name"),
+            method: row.get("method"),
+            route: row.get("route"),
+            required_scope: row
+                .get::<Option<String>, _>("required_scope")
+                .and_then(|scope| ApiScope::parse(&scope)),
+            allowed: row.get("allowed"),
+            reason: row.get("reason"),
+            created_at: row.get("created_at"),
+        })
+        .collect())
+}
+
+fn generate_secret() -> Result<String, AppError> {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| AppError::Database(format!("Failed to generate token: {}", e)))?;
+    Ok(format!("{}{}", TOKEN_PREFIX, hex::encode(bytes)))
+}
+
+fn hash_token(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+fn encode_scopes(scopes: &[ApiScope]) -> String {
+    scopes
+        .iter()
+        .map(|scope| scope.as_str())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn token_from_row(row: &SqliteRow) -> ApiToken {
+    let scopes: String = row.get("scopes");
+    ApiToken {
+        id: row.get("id"),
+        name: row.get("name"),
+        token_prefix: row.get("token_prefix"),
+        scopes: scopes.split(',').filter_map(ApiScope::parse).collect(),
+        created_at: row.get("created_at"),
+        last_used_at: row.get("last_used_at"),
+        revoked_at: row.get("revoked_at"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        sqlx::raw_sql(include_str!("../migrations/007_create_api_tokens.sql"))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        pool
+    }
+
+    #[test]
+    fn test_scope_hierarchy() {
+        assert!(ApiScope::Write.grants(ApiScope::Read));
+        assert!(ApiScope::Write.grants(ApiScope::Search));
+        assert!(ApiScope::Read.grants(ApiScope::Search));
+        assert!(!ApiScope::Read.grants(ApiScope::Write));
+        assert!(!ApiScope::Search.grants(ApiScope::Read));
+    }
+
+    #[test]
+    fn test_required_scope_per_route() {
+        assert_eq!(
+            required_scope("get", "/search?q=git"),
+            Some(ApiScope::Search)
+        );
+        assert_eq!(required_scope("GET", "/snippets/4"), Some(ApiScope::Read));
+        assert_eq!(required_scope("GET", "/tags"), Some(ApiScope::Read));
+        assert_eq!(required_scope("POST", "/snippets"), Some(ApiScope::Write));
+        assert_eq!(
+            required_scope("DELETE", "/snippets/4"),
+            Some(ApiScope::Write)
+        );
+        assert_eq!(required_scope("POST", "/copy/4"), Some(ApiScope::Read));
+        assert_eq!(required_scope("GET", "/copy/4"), None);
+        assert_eq!(required_scope("GET", "/settings"), None);
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        assert_eq!(bearer_token("Bearer snips_abc"), Some("snips_abc"));
+        assert_eq!(bearer_token("bearer  snips_abc "), Some("snips_abc"));
+        assert_eq!(bearer_token("Basic dXNlcg=="), None);
+        assert_eq!(bearer_token("Bearer"), None);
+    }
+
+    #[tokio::test]
+    async fn test_create_and_list_tokens() {
+        let pool = setup_test_db().await;
+
+        let created = create_token(&pool, " CLI ", &[ApiScope::Read, ApiScope::Read])
+            .await
+            .unwrap();
+        assert!(created.secret.starts_with(TOKEN_PREFIX));
+        assert_eq!(created.secret.len(), TOKEN_PREFIX.len() + TOKEN_BYTES * 2);
+        assert_eq!(created.token.name, "CLI");
+        assert_eq!(created.token.scopes, vec![ApiScope::Read]);
+
+        let tokens = list_tokens(&pool).await.unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(
+            tokens[0].token_prefix,
+            &created.secret[..DISPLAY_PREFIX_LEN]
+        );
+
+        // The plaintext secret is never stored
+        let stored: String = sqlx::query_scalar("SELECT token_hash FROM api_tokens")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_ne!(stored, created.secret);
+
+        assert!(create_token(&pool, "", &[ApiScope::Read]).await.is_err());
+        assert!(create_token(&pool, "none", &[]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_authorize_enforces_scopes_and_audits() {
+        let pool = setup_test_db().await;
+        let search = create_token(&pool, "search", &[ApiScope::Search])
+            .await
+            .unwrap();
+
+        let token = authorize_request(&pool, Some(&search.secret), "GET", "/search?q=x")
+            .await
+            .unwrap();
+        assert_eq!(token.id, search.token.id);
+        assert!(token.last_used_at.is_some());
+
+        let denied = authorize_request(&pool, Some(&search.secret), "GET", "/snippets").await;
+        assert!(matches!(denied, Err(AppError::Forbidden(_))));
+
+        let missing = authorize_request(&pool, None, "GET", "/search").await;
+        assert!(matches!(missing, Err(AppError::Unauthorized(_))));
+
+        let unknown = authorize_request(&pool, Some("snips_nope"), "GET", "/search").await;
+        assert!(matches!(unknown, Err(AppError::Unauthorized(_))));
+
+        let audit = get_audit_log(&pool, None, 10).await.unwrap();
+        assert_eq!(audit.len(), 4);
+        assert!(audit[3].allowed);
+        assert_eq!(audit[3].route, "/search");
+        assert_eq!(audit[3].token_name.as_deref(), Some("search"));
+        assert!(!audit[2].allowed);
+        assert_eq!(audit[2].required_scope, Some(ApiScope::Read));
+        assert!(audit[0].token_id.is_none());
+
+        let for_token = get_audit_log(&pool, Some(search.token.id), 10)
+            .await
+            .unwrap();
+        assert_eq!(for_token.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_revoked_token_is_rejected() {
+        let pool = setup_test_db().await;
+        let write = create_token(&pool, "write", &[ApiScope::Write])
+            .await
+            .unwrap();
+
+        authorize_request(&pool, Some(&write.secret), "POST", "/snippets")
+            .await
+            .unwrap();
+
+        revoke_token(&pool, write.token.id).await.unwrap();
+        // Revoking twice is fine
+        revoke_token(&pool, write.token.id).await.unwrap();
+        assert!(matches!(
+            revoke_token(&pool, 999).await,
+            Err(AppError::NotFound(_))
+        ));
+
+        let result = authorize_request(&pool, Some(&write.secret), "GET", "/snippets").await;
+        assert!(matches!(result, Err(AppError::Unauthorized(_))));
+        assert!(list_tokens(&pool).await.unwrap()[0].revoked_at.is_some());
+    }
+}
@@ -0,0 +1,196 @@
+/// Resolves the theme setting to the light or dark appearance in effect.
+///
+/// `Theme::System` follows the OS. On macOS and Windows the native windows
+/// report the system appearance and send `WindowEvent::ThemeChanged` when the
+/// user switches it, which `handle_system_change` turns into an
+/// `appearance-updated` event so the frontend never has to guess.
+use crate::models::settings::Theme;
+use crate::services::custom_themes::CustomTheme;
+use crate::services::database::get_pool;
+use crate::services::settings::SettingsService;
+use crate::utils::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event emitted with a `ResolvedTheme` when the appearance changes
+pub const APPEARANCE_UPDATED_EVENT: &str = "appearance-updated";
+
+/// Built-in light palette, matching `:root` in `globals.css`
+const LIGHT_PALETTE: [(&str, &str); 14] = [
+    ("background", "0 0% 100%"),
+    ("foreground", "222.2 84% 4.9%"),
+    ("primary", "221.2 83.2% 53.3%"),
+    ("primary-foreground", "210 40% 98%"),
+    ("secondary", "210 40% 96.1%"),
+    ("secondary-foreground", "222.2 47.4% 11.2%"),
+    ("accent", "210 40% 96.1%"),
+    ("accent-foreground", "222.2 47.4% 11.2%"),
+    ("muted", "210 40% 96.1%"),
+    ("muted-foreground", "215.4 16.3% 46.9%"),
+    ("border", "214.3 31.8% 91.4%"),
+    ("input", "214.3 31.8% 91.4%"),
+    ("ring", "221.2 83.2% 53.3%"),
+    ("radius", "0.5rem"),
+];
+
+/// Built-in dark palette, matching `.dark` in `globals.css`
+const DARK_PALETTE: [(&str, &str); 14] = [
+    ("background", "222.2 84% 4.9%"),
+    ("foreground", "210 40% 98%"),
+    ("primary", "217.2 91.2% 59.8%"),
+    ("primary-foreground", "222.2 47.4% 11.2%"),
+    ("secondary", "217.2 32.6% 17.5%"),
+    ("secondary-foreground", "210 40% 98%"),
+    ("accent", "217.2 32.6% 17.5%"),
+    ("accent-foreground", "210 40% 98%"),
+    ("muted", "217.2 32.6% 17.5%"),
+    ("muted-foreground", "215 20.2% 65.1%"),
+    ("border", "217.2 32.6% 17.5%"),
+    ("input", "217.2 32.6% 17.5%"),
+    ("ring", "224.3 76.3% 48%"),
+    ("radius", "0.5rem"),
+];
+
+/// Light or dark, after `Theme::System` has been resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Appearance {
+    #[default]
+    Light,
+    Dark,
+}
+
+impl From<tauri::Theme> for Appearance {
+    fn from(theme: tauri::Theme) -> Self {
+        match theme {
+            tauri::Theme::Dark => Appearance::Dark,
+            _ => Appearance::Light,
+        }
+    }
+}
+
+/// The theme setting together with the appearance it resolves to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResolvedTheme {
+    /// The `theme` setting
+    pub theme: Theme,
+    /// Whether the UI should be light or dark
+    pub appearance: Appearance,
+    /// Built-in palette for the appearance
+    pub palette: CustomTheme,
+}
+
+impl ResolvedTheme {
+    /// Resolves the theme setting against the system appearance
+    pub fn new(theme: Theme, system: Appearance) -> Self {
+        let appearance = resolve(theme, system);
+        Self {
+            theme,
+            appearance,
+            palette: builtin_palette(appearance),
+        }
+    }
+}
+
+/// Last appearance sent to the frontend, so each window reporting the same
+/// system change only produces one event
+#[derive(Default)]
+pub struct AppearanceState(pub Mutex<Option<Appearance>>);
+
+/// Resolves the theme setting against the system appearance
+pub fn resolve(theme: Theme, system: Appearance) -> Appearance {
+    match theme {
+        Theme::Light => Appearance::Light,
+        Theme::Dark => Appearance::Dark,
+        Theme::System => system,
+    }
+}
+
+/// The built-in palette for an appearance
+pub fn builtin_palette(appearance: Appearance) -> CustomTheme {
+    let (name, variables) = match appearance {
+        Appearance::Light => ("Light", LIGHT_PALETTE),
+        Appearance::Dark => ("Dark", DARK_PALETTE),
+    };
+    CustomTheme {
+        name: name.to_string(),
+        dark: appearance == Appearance::Dark,
+        variables: variables
+            .iter()
+            .map(|(variable, value)| (variable.to_string(), value.to_string()))
+            .collect(),
+    }
+}
+
+/// The appearance the OS is using, as reported by the native windows
+///
+/// Falls back to light when no window is open or the platform cannot tell.
+pub fn system_appearance(app: &AppHandle) -> Appearance {
+    app.webview_windows()
+        .values()
+        .find_map(|window| window.theme().ok())
+        .map(Appearance::from)
+        .unwrap_or_default()
+}
+
+/// Resolves the saved theme setting against `system`
+pub async fn resolved_theme(
+    app: &AppHandle,
+    system: Appearance,
+) -> Result<ResolvedTheme, AppError> {
+    let settings = SettingsService::new(get_pool(app)?).get_settings().await?;
+    Ok(ResolvedTheme::new(settings.theme, system))
+}
+
+/// Emits `appearance-updated` if the resolved appearance differs from the
+/// last one sent
+pub fn notify(app: &AppHandle, resolved: &ResolvedTheme) {
+    if let Some(state) = app.try_state::<AppearanceState>() {
+        let Ok(mut last) = state.0.lock() else {
+            return;
+        };
+        if *last == Some(resolved.appearance) {
+            return;
+        }
+        *last = Some(resolved.appearance);
+    }
+    if let Err(e) = app.emit(APPEARANCE_UPDATED_EVENT, resolved) {
+        eprintln!("Failed to emit {}: {}", APPEARANCE_UPDATED_EVENT, e);
+    }
+}
+
+/// Re-resolves the appearance after the OS switched between light and dark
+pub fn handle_system_change(app: &AppHandle, theme: tauri::Theme) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match resolved_theme(&app, Appearance::from(theme)).await {
+            Ok(resolved) => notify(&app, &resolved),
+            Err(e) => eprintln!("Failed to resolve theme after a system change: {}", e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::custom_themes::validate_theme;
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(resolve(Theme::Light, Appearance::Dark), Appearance::Light);
+        assert_eq!(resolve(Theme::Dark, Appearance::Light), Appearance::Dark);
+        assert_eq!(resolve(Theme::System, Appearance::Dark), Appearance::Dark);
+        assert_eq!(resolve(Theme::System, Appearance::Light), Appearance::Light);
+    }
+
+    #[test]
+    fn test_builtin_palettes_are_valid_themes() {
+        for appearance in [Appearance::Light, Appearance::Dark] {
+            let palette = builtin_palette(appearance);
+            assert!(validate_theme(&palette).is_ok());
+            assert_eq!(palette.dark, appearance == Appearance::Dark);
+            assert_eq!(palette.variables.len(), 14);
+        }
+    }
+}
@@ -0,0 +1,236 @@
+/// Archiving snippets that are no longer used.
+///
+/// An archived snippet stays in the library, with its tags and usage
+/// history, but search and the snippet list leave it out. The stale snippet
+/// report suggests candidates: snippets nobody has used for a while.
+use crate::utils::error::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+
+/// Days without a usage after which a snippet counts as stale, by default
+pub const DEFAULT_STALE_THRESHOLD_DAYS: u32 = 90;
+
+/// A snippet that has not been used for a while
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaleSnippet {
+    pub snippet_id: i64,
+    pub snippet_name: String,
+    pub created_at: i64,
+    pub usage_count: i64,
+    /// `None` if the snippet was never used
+    pub last_used: Option<i64>,
+}
+
+/// Lists snippets unused for at least `threshold_days`
+///
+/// Never used snippets are listed once they are older than the threshold,
+/// so new snippets get the same grace period. Pinned and already archived
+/// snippets are left out. Usage is only known while analytics are recorded.
+///
+/// # Returns
+///
+/// Stale snippets, longest unused first
+pub async fn get_stale_snippets(
+    pool: &SqlitePool,
+    now: i64,
+    threshold_days: u32,
+) -> Result<Vec<StaleSnippet>, AppError> {
+    let cutoff = now - i64::from(threshold_days) * 24 * 3600;
+
+    let rows = sqlx::query_as::<_, (i64, String, i64, i64, Option<i64>)>(
+        r#"
+        SELECT
+            s.id,
+            s.name,
+            s.created_at,
+            COUNT(a.id) as usage_count,
+            MAX(a.used_at) as last_used
+        FROM snippets s
+        LEFT JOIN analytics a ON s.id = a.snippet_id
+        WHERE s.archived = 0 AND s.pinned = 0
+        GROUP BY s.id
+        HAVING COALESCE(MAX(a.used_at), s.created_at) < ?
+        ORDER BY COALESCE(MAX(a.used_at), s.created_at), s.id
+        "#,
+    )
+    .bind(cutoff)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to fetch stale snippets: {}", e)))?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(snippet_id, snippet_name, created_at, usage_count, last_used)| StaleSnippet {
+                snippet_id,
+                snippet_name,
+                created_at,
+                usage_count,
+                last_used,
+            },
+        )
+        .collect())
+}
+
+/// Archives snippets, leaving them out of search
+///
+/// Either every snippet is archived or, if one of them does not exist, none
+/// is. Archiving does not change `updated_at`.
+///
+/// # Returns
+///
+/// Number of snippets archived
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if an ID does not exist
+pub async fn archive_snippets(pool: &SqlitePool, ids: &[i64]) -> Result<u64, AppError> {
+    let ids: Vec<i64> = ids
+        .iter()
+        .copied()
+        .collect::<HashSet<i64>>()
+        .into_iter()
+        .collect();
+    if ids.is_empty() {
+        return Ok(0);
+    }
+
+    let mut tx = pool.begin().await?;
+    let placeholders = vec!["?"; ids.len()].join(", ");
+    let sql = format!(
+        "UPDATE snippets SET archived = 1 WHERE id IN ({})",
+        placeholders
+    );
+    let mut query = sqlx::query(&sql);
+    for id in &ids {
+        query = query.bind(id);
+    }
+    let archived = query.execute(&mut *tx).await?.rows_affected();
+    if archived < ids.len() as u64 {
+        tx.rollback().await?;
+        return Err(AppError::NotFound(format!(
+            "{} of the snippets to archive do not exist",
+            ids.len() as u64 - archived
+        )));
+    }
+    tx.commit().await?;
+
+    Ok(archived)
+}
+
+/// Archives or restores one snippet
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the snippet does not exist
+pub async fn set_archived(pool: &SqlitePool, id: i64, archived: bool) -> Result<(), AppError> {
+    let result = sqlx::query("UPDATE snippets SET archived = ? WHERE id = ?")
+        .bind(archived)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!(
+            "Snippet with id {} not found",
+            id
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::run_migrations;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    const DAY: i64 = 24 * 3600;
+    const NOW: i64 = 1000 * DAY;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+        sqlx::raw_sql(&format!(
+            "INSERT INTO snippets (id, name, content, created_at, updated_at, pinned) VALUES
+                (1, 'used', 'x', 0, 0, 0), (2, 'old', 'x', 0, 0, 0),
+                (3, 'never', 'x', 0, 0, 0), (4, 'new', 'x', {now}, {now}, 0),
+                (5, 'pinned', 'x', 0, 0, 1);
+             INSERT INTO analytics (snippet_id, used_at) VALUES
+                (1, {now} - 5 * {day}), (2, {now} - 200 * {day}), (2, {now} - 100 * {day});",
+            now = NOW,
+            day = DAY
+        ))
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    async fn stale_names(pool: &SqlitePool, threshold_days: u32) -> Vec<String> {
+        get_stale_snippets(pool, NOW, threshold_days)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.snippet_name)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_get_stale_snippets() {
+        let pool = setup_test_db().await;
+
+        let stale = get_stale_snippets(&pool, NOW, 90).await.unwrap();
+        assert_eq!(stale.len(), 2);
+        assert_eq!(stale[0].snippet_name, "never");
+        assert_eq!(stale[0].last_used, None);
+        assert_eq!(
+            (stale[1].snippet_name.as_str(), stale[1].usage_count),
+            ("old", 2)
+        );
+        assert_eq!(stale[1].last_used, Some(NOW - 100 * DAY));
+
+        assert_eq!(stale_names(&pool, 150).await, vec!["never"]);
+        assert_eq!(stale_names(&pool, 1).await, vec!["never", "old", "used"]);
+    }
+
+    #[tokio::test]
+    async fn test_archive_snippets() {
+        let pool = setup_test_db().await;
+
+        assert_eq!(archive_snippets(&pool, &[2, 3, 3]).await.unwrap(), 2);
+        assert!(stale_names(&pool, 90).await.is_empty());
+        assert_eq!(archive_snippets(&pool, &[]).await.unwrap(), 0);
+
+        // Nothing is archived when an ID is missing
+        assert!(matches!(
+            archive_snippets(&pool, &[1, 99]).await,
+            Err(AppError::NotFound(_))
+        ));
+        let archived: i64 = sqlx::query_scalar("SELECT archived FROM snippets WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(archived, 0);
+    }
+
+    #[tokio::test]
+    async fn test_set_archived() {
+        let pool = setup_test_db().await;
+
+        set_archived(&pool, 3, true).await.unwrap();
+        assert_eq!(stale_names(&pool, 90).await, vec!["old"]);
+        set_archived(&pool, 3, false).await.unwrap();
+        assert_eq!(stale_names(&pool, 90).await, vec!["never", "old"]);
+
+        assert!(matches!(
+            set_archived(&pool, 99, true).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+}
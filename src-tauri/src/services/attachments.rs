@@ -0,0 +1,414 @@
+/// Images and other binary data attached to snippets.
+///
+/// An attachment is stored in the database or refers to a file on disk.
+/// Snippets made from a clipboard image keep the image inline; file
+/// references suit large assets that already live elsewhere and are read
+/// each time the snippet is copied. A snippet with an image attachment
+/// copies the image instead of its text.
+use crate::utils::error::AppError;
+use crate::utils::time::current_timestamp;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqlitePool};
+use std::path::Path;
+
+/// Largest attachment accepted (20 MB)
+pub const MAX_ATTACHMENT_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Image formats that can be attached, in order of preference when the
+/// clipboard offers several
+pub const IMAGE_MIME_TYPES: &[&str] = &[
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "image/tiff",
+];
+
+/// An attachment, without its data
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: i64,
+    pub snippet_id: i64,
+    pub mime_type: String,
+    pub byte_size: i64,
+    /// File the data is read from; `None` when it is stored in the database
+    pub file_path: Option<String>,
+    pub created_at: i64,
+}
+
+/// Detects an image format from its leading bytes
+pub fn sniff_image_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if data.starts_with(&[0xff, 0xd8, 0xff]) {
+        Some("image/jpeg")
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if data.starts_with(b"II*\0") || data.starts_with(b"MM\0*") {
+        Some("image/tiff")
+    } else {
+        None
+    }
+}
+
+/// Picks the preferred image format among those a clipboard offers
+pub fn preferred_image_type(offered: &[String]) -> Option<&'static str> {
+    IMAGE_MIME_TYPES
+        .iter()
+        .copied()
+        .find(|mime_type| offered.iter().any(|offer| offer == mime_type))
+}
+
+/// Searchable text stored as the content of an image snippet
+pub fn image_placeholder(mime_type: &str, byte_size: usize) -> String {
+    format!("[Image: {}, {} KB]", mime_type, byte_size.div_ceil(1024))
+}
+
+fn check_size(byte_size: u64) -> Result<(), AppError> {
+    if byte_size == 0 {
+        return Err(AppError::InvalidInput("Attachment is empty".to_string()));
+    }
+    if byte_size > MAX_ATTACHMENT_BYTES {
+        return Err(AppError::InvalidInput(format!(
+            "Attachment too large (max {} MB)",
+            MAX_ATTACHMENT_BYTES / 1024 / 1024
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that data is an image that can be attached, returning its format
+///
+/// # Errors
+///
+/// * `AppError::InvalidInput` - The data is empty, too large, or not an image
+pub fn check_image(data: &[u8]) -> Result<&'static str, AppError> {
+    check_size(data.len() as u64)?;
+    sniff_image_type(data)
+        .ok_or_else(|| AppError::InvalidInput("Data is not a supported image".to_string()))
+}
+
+fn attachment_from_row(row: &SqliteRow) -> Attachment {
+    Attachment {
+        id: row.get("id"),
+        snippet_id: row.get("snippet_id"),
+        mime_type: row.get("mime_type"),
+        byte_size: row.get("byte_size"),
+        file_path: row.get("file_path"),
+        created_at: row.get("created_at"),
+    }
+}
+
+async fn insert(
+    pool: &SqlitePool,
+    snippet_id: i64,
+    mime_type: &str,
+    data: Option<&[u8]>,
+    file_path: Option<&str>,
+    byte_size: u64,
+) -> Result<Attachment, AppError> {
+    let exists = sqlx::query("SELECT 1 FROM snippets WHERE id = ?")
+        .bind(snippet_id)
+        .fetch_optional(pool)
+        .await?;
+    if exists.is_none() {
+        return Err(AppError::NotFound(format!(
+            "Snippet with id {} not found",
+            snippet_id
+        )));
+    }
+
+    let id = sqlx::query(
+        "INSERT INTO snippet_attachments
+            (snippet_id, mime_type, data, file_path, byte_size, created_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(snippet_id)
+    .bind(mime_type)
+    .bind(data)
+    .bind(file_path)
+    .bind(byte_size as i64)
+    .bind(current_timestamp())
+    .execute(pool)
+    .await?
+    .last_insert_rowid();
+
+    get_attachment(pool, id).await
+}
+
+/// Stores an image in the database and attaches it to a snippet
+///
+/// # Errors
+///
+/// * `AppError::InvalidInput` - The data is empty, too large, or not an image
+/// * `AppError::NotFound` - The snippet does not exist
+pub async fn attach_image_data(
+    pool: &SqlitePool,
+    snippet_id: i64,
+    data: &[u8],
+) -> Result<Attachment, AppError> {
+    let mime_type = check_image(data)?;
+    insert(
+        pool,
+        snippet_id,
+        mime_type,
+        Some(data),
+        None,
+        data.len() as u64,
+    )
+    .await
+}
+
+/// Attaches an image file to a snippet by reference
+///
+/// Only the path is stored; the file is read when the snippet is copied.
+///
+/// # Errors
+///
+/// * `AppError::InvalidInput` - The file is missing, empty, too large, or
+///   not an image
+/// * `AppError::NotFound` - The snippet does not exist
+pub async fn attach_image_file(
+    pool: &SqlitePool,
+    snippet_id: i64,
+    path: &Path,
+) -> Result<Attachment, AppError> {
+    let unreadable = |e: std::io::Error| {
+        AppError::InvalidInput(format!("Cannot read {}: {}", path.display(), e))
+    };
+    let metadata = tokio::fs::metadata(path).await.map_err(unreadable)?;
+    if !metadata.is_file() {
+        return Err(AppError::InvalidInput(format!(
+            "{} is not a file",
+            path.display()
+        )));
+    }
+    check_size(metadata.len())?;
+
+    let data = tokio::fs::read(path).await.map_err(unreadable)?;
+    let mime_type = sniff_image_type(&data).ok_or_else(|| {
+        AppError::InvalidInput(format!("{} is not a supported image", path.display()))
+    })?;
+    let path = path
+        .to_str()
+        .ok_or_else(|| AppError::InvalidInput("File path is not valid UTF-8".to_string()))?;
+    insert(
+        pool,
+        snippet_id,
+        mime_type,
+        None,
+        Some(path),
+        metadata.len(),
+    )
+    .await
+}
+
+/// Gets an attachment by ID
+pub async fn get_attachment(pool: &SqlitePool, id: i64) -> Result<Attachment, AppError> {
+    let row = sqlx::query(
+        "SELECT id, snippet_id, mime_type, byte_size, file_path, created_at
+         FROM snippet_attachments WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Attachment with id {} not found", id)))?;
+    Ok(attachment_from_row(&row))
+}
+
+/// Lists a snippet's attachments, oldest first
+pub async fn get_attachments(
+    pool: &SqlitePool,
+    snippet_id: i64,
+) -> Result<Vec<Attachment>, AppError> {
+    let rows = sqlx::query(
+        "SELECT id, snippet_id, mime_type, byte_size, file_path, created_at
+         FROM snippet_attachments WHERE snippet_id = ? ORDER BY id",
+    )
+    .bind(snippet_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.iter().map(attachment_from_row).collect())
+}
+
+/// Removes an attachment; referenced files are left on disk
+pub async fn delete_attachment(pool: &SqlitePool, id: i64) -> Result<(), AppError> {
+    let result = sqlx::query("DELETE FROM snippet_attachments WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!(
+            "Attachment with id {} not found",
+            id
+        )));
+    }
+    Ok(())
+}
+
+/// First image attached to a snippet, with its data
+///
+/// # Errors
+///
+/// * `AppError::External` - A referenced file can no longer be read
+pub async fn first_image(
+    pool: &SqlitePool,
+    snippet_id: i64,
+) -> Result<Option<(Attachment, Vec<u8>)>, AppError> {
+    let row = sqlx::query(
+        "SELECT id, snippet_id, mime_type, byte_size, file_path, created_at, data
+         FROM snippet_attachments
+         WHERE snippet_id = ? AND mime_type LIKE 'image/%'
+         ORDER BY id LIMIT 1",
+    )
+    .bind(snippet_id)
+    .fetch_optional(pool)
+    .await?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+
+    let attachment = attachment_from_row(&row);
+    let data = match &attachment.file_path {
+        Some(path) => tokio::fs::read(path).await.map_err(|e| {
+            AppError::External(format!("Failed to read attachment {}: {}", path, e))
+        })?,
+        None => row.get("data"),
+    };
+    Ok(Some((attachment, data)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::raw_sql(
+            "PRAGMA foreign_keys = ON;
+             CREATE TABLE snippets (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+             INSERT INTO snippets (id, name) VALUES (1, 'diagram');",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::raw_sql(include_str!(
+            "../migrations/017_create_snippet_attachments.sql"
+        ))
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    #[test]
+    fn test_sniff_image_type() {
+        assert_eq!(sniff_image_type(PNG), Some("image/png"));
+        assert_eq!(sniff_image_type(b"\xff\xd8\xff\xe0"), Some("image/jpeg"));
+        assert_eq!(sniff_image_type(b"GIF89a..."), Some("image/gif"));
+        assert_eq!(
+            sniff_image_type(b"RIFF\0\0\0\0WEBPVP8 "),
+            Some("image/webp")
+        );
+        assert_eq!(sniff_image_type(b"plain text"), None);
+        assert_eq!(sniff_image_type(b""), None);
+    }
+
+    #[test]
+    fn test_preferred_image_type() {
+        let offered = |types: &[&str]| types.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            preferred_image_type(&offered(&["TARGETS", "image/jpeg", "image/png"])),
+            Some("image/png")
+        );
+        assert_eq!(
+            preferred_image_type(&offered(&["text/plain", "UTF8_STRING"])),
+            None
+        );
+        assert_eq!(
+            image_placeholder("image/png", 1500),
+            "[Image: image/png, 2 KB]"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_attach_and_read_inline_image() {
+        let pool = setup_test_db().await;
+
+        let attachment = attach_image_data(&pool, 1, PNG).await.unwrap();
+        assert_eq!(attachment.mime_type, "image/png");
+        assert_eq!(attachment.byte_size, PNG.len() as i64);
+        assert_eq!(attachment.file_path, None);
+
+        let (first, data) = first_image(&pool, 1).await.unwrap().unwrap();
+        assert_eq!(first, attachment);
+        assert_eq!(data, PNG);
+        assert_eq!(get_attachments(&pool, 1).await.unwrap(), vec![attachment]);
+
+        // Deleting the snippet takes its attachments along
+        sqlx::query("DELETE FROM snippets WHERE id = 1")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(get_attachments(&pool, 1).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_attach_image_file_by_reference() {
+        let pool = setup_test_db().await;
+        let path =
+            std::env::temp_dir().join(format!("snips-attachment-{}.png", std::process::id()));
+        std::fs::write(&path, PNG).unwrap();
+
+        let attachment = attach_image_file(&pool, 1, &path).await.unwrap();
+        assert_eq!(attachment.file_path.as_deref(), path.to_str());
+        let (_, data) = first_image(&pool, 1).await.unwrap().unwrap();
+        assert_eq!(data, PNG);
+
+        // A file that went away is reported rather than copied as nothing
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            first_image(&pool, 1).await,
+            Err(AppError::External(_))
+        ));
+
+        delete_attachment(&pool, attachment.id).await.unwrap();
+        assert_eq!(first_image(&pool, 1).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_attach_rejects_bad_input() {
+        let pool = setup_test_db().await;
+
+        assert!(matches!(
+            attach_image_data(&pool, 1, b"").await,
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            attach_image_data(&pool, 1, b"not an image").await,
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            attach_image_data(&pool, 99, PNG).await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            attach_image_file(&pool, 1, Path::new("/nonexistent/image.png")).await,
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            delete_attachment(&pool, 42).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,486 @@
+use crate::utils::error::AppError;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{ConnectOptions, SqliteConnection};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Extension of checksum files written next to each backup
+pub const CHECKSUM_EXTENSION: &str = "sha256";
+
+/// Compression applied to new backup files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupCompression {
+    /// Plain copy of the SQLite file (`.db`)
+    None,
+    /// Gzip-compressed SQLite file (`.db.gz`)
+    #[default]
+    Gzip,
+}
+
+impl BackupCompression {
+    /// File name suffix used for backups with this compression
+    pub fn file_suffix(self) -> &'static str {
+        match self {
+            Self::None => ".db",
+            Self::Gzip => ".db.gz",
+        }
+    }
+
+    /// Detects the compression of an existing backup from its file name
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        if name.ends_with(Self::Gzip.file_suffix()) {
+            Some(Self::Gzip)
+        } else if name.ends_with(Self::None.file_suffix()) {
+            Some(Self::None)
+        } else {
+            None
+        }
+    }
+}
+
+/// Result of verifying a backup file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupVerification {
+    pub path: String,
+    pub compressed: bool,
+    /// SHA-256 of the backup file as stored on disk
+    pub checksum: String,
+    /// Whether the stored checksum matches (`None` if the backup has no checksum file)
+    pub checksum_matches: Option<bool>,
+    /// Whether the SQLite file opens and passes `PRAGMA integrity_check`
+    pub integrity_ok: bool,
+    /// Output of `PRAGMA integrity_check`, or the reason the file could not be opened
+    pub integrity_message: String,
+    /// True when the backup is safe to restore
+    pub valid: bool,
+}
+
+/// Returns true if `path` looks like a backup file (compressed or not)
+pub fn is_backup_file(path: &Path) -> bool {
+    BackupCompression::from_path(path).is_some()
+}
+
+/// Path of the checksum file stored alongside a backup
+pub fn checksum_path(backup_path: &Path) -> PathBuf {
+    let mut name = backup_path.as_os_str().to_owned();
+    name.push(".");
+    name.push(CHECKSUM_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// Computes the hex-encoded SHA-256 of a file
+///
+/// # Errors
+///
+/// Returns `AppError::External` if the file cannot be read
+pub fn sha256_file(path: &Path) -> Result<String, AppError> {
+    let mut reader = BufReader::new(open(path)?);
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .map_err(|e| AppError::External(format!("Failed to read {}: {}", path.display(), e)))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Writes a backup of `source` to `destination` and records its checksum
+///
+/// The checksum file uses the `sha256sum` format (`<hex>  <file name>`), so
+/// backups can also be checked by hand with `sha256sum -c`.
+///
+/// # Arguments
+///
+/// * `source` - The live database file
+/// * `destination` - Backup file to create
+/// * `compression` - Compression to apply
+///
+/// # Returns
+///
+/// The SHA-256 checksum of the written backup file
+///
+/// # Errors
+///
+/// Returns `AppError::External` if any file operation fails
+pub fn write_backup(
+    source: &Path,
+    destination: &Path,
+    compression: BackupCompression,
+) -> Result<String, AppError> {
+    let io_error = |e: std::io::Error| {
+        AppError::External(format!(
+            "Failed to write backup {}: {}",
+            destination.display(),
+            e
+        ))
+    };
+
+    match compression {
+        BackupCompression::None => {
+            std::fs::copy(source, destination).map_err(io_error)?;
+        }
+        BackupCompression::Gzip => {
+            let mut reader = BufReader::new(open(source)?);
+            let writer = BufWriter::new(File::create(destination).map_err(io_error)?);
+            let mut encoder = GzEncoder::new(writer, Compression::default());
+            std::io::copy(&mut reader, &mut encoder).map_err(io_error)?;
+            encoder
+                .finish()
+                .map_err(io_error)?
+                .flush()
+                .map_err(io_error)?;
+        }
+    }
+
+    let checksum = sha256_file(destination)?;
+    let file_name = destination
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    std::fs::write(
+        checksum_path(destination),
+        format!("{}  {}\n", checksum, file_name),
+    )
+    .map_err(io_error)?;
+
+    Ok(checksum)
+}
+
+/// Reads the checksum recorded for a backup, if any
+pub fn read_checksum(backup_path: &Path) -> Option<String> {
+    let contents = std::fs::read_to_string(checksum_path(backup_path)).ok()?;
+    contents
+        .split_whitespace()
+        .next()
+        .map(|checksum| checksum.to_lowercase())
+}
+
+/// Writes the plain SQLite contents of a backup to `destination`
+///
+/// Gzip backups are decompressed; uncompressed backups are copied.
+///
+/// # Errors
+///
+/// Returns `AppError::External` if the backup cannot be read or decompressed
+pub fn extract_backup(backup_path: &Path, destination: &Path) -> Result<(), AppError> {
+    let io_error = |e: std::io::Error| {
+        AppError::External(format!(
+            "Failed to extract backup {}: {}",
+            backup_path.display(),
+            e
+        ))
+    };
+
+    match BackupCompression::from_path(backup_path) {
+        Some(BackupCompression::Gzip) => {
+            let mut decoder = GzDecoder::new(BufReader::new(open(backup_path)?));
+            let mut writer = BufWriter::new(File::create(destination).map_err(io_error)?);
+            std::io::copy(&mut decoder, &mut writer).map_err(io_error)?;
+            writer.flush().map_err(io_error)?;
+        }
+        _ => {
+            std::fs::copy(backup_path, destination).map_err(io_error)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Path of the scratch file used while verifying a backup
+///
+/// Lives next to the backup with a `.tmp` suffix so interrupted verifications
+/// are easy to recognise and clean up.
+pub fn verification_scratch_path(backup_path: &Path) -> PathBuf {
+    let file_name = backup_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    backup_path.with_file_name(format!(".verify-{}.tmp", file_name))
+}
+
+/// Verifies a backup's checksum and SQLite integrity
+///
+/// The backup is extracted to a scratch file, opened read-only and checked
+/// with `PRAGMA integrity_check`. The scratch file is always removed.
+///
+/// # Arguments
+///
+/// * `backup_path` - The backup file to verify
+///
+/// # Returns
+///
+/// A `BackupVerification` describing the outcome. A corrupt backup is reported
+/// through the returned value rather than as an error.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the backup does not exist, or
+/// `AppError::External` if it cannot be read
+pub async fn verify_backup_file(backup_path: &Path) -> Result<BackupVerification, AppError> {
+    if !backup_path.exists() {
+        return Err(AppError::NotFound(format!(
+            "Backup file not found: {}",
+            backup_path.display()
+        )));
+    }
+
+    let compressed = BackupCompression::from_path(backup_path) == Some(BackupCompression::Gzip);
+    let checksum = sha256_file(backup_path)?;
+    let checksum_matches = read_checksum(backup_path).map(|expected| expected == checksum);
+
+    let scratch = verification_scratch_path(backup_path);
+    let (integrity_ok, integrity_message) = match extract_backup(backup_path, &scratch) {
+        Ok(()) => check_sqlite_integrity(&scratch).await,
+        Err(e) => (false, e.to_string()),
+    };
+    let _ = std::fs::remove_file(&scratch);
+
+    Ok(BackupVerification {
+        path: backup_path.to_string_lossy().to_string(),
+        compressed,
+        checksum,
+        checksum_matches,
+        integrity_ok,
+        integrity_message,
+        valid: integrity_ok && checksum_matches != Some(false),
+    })
+}
+
+/// Opens a SQLite file read-only and runs `PRAGMA integrity_check`
+async fn check_sqlite_integrity(db_path: &Path) -> (bool, String) {
+    let connection = SqliteConnectOptions::new()
+        .filename(db_path)
+        .read_only(true)
+        .connect()
+        .await;
+
+    let mut connection = match connection {
+        Ok(connection) => connection,
+        Err(e) => return (false, format!("Failed to open backup: {}", e)),
+    };
+
+    let result = sqlx::query_scalar::<_, String>("PRAGMA integrity_check")
+        .fetch_all(&mut connection)
+        .await;
+    let _ = sqlx::Connection::close(connection).await;
+
+    match result {
+        Ok(lines) if lines.len() == 1 && lines[0] == "ok" => (true, "ok".to_string()),
+        Ok(lines) => (false, lines.join("\n")),
+        Err(e) => (false, format!("Integrity check failed: {}", e)),
+    }
+}
+
+/// Read-only connection to the contents of a backup
+///
+/// Compressed backups are extracted to a scratch file first; the scratch file
+/// is removed by `close`.
+pub struct BackupReader {
+    connection: SqliteConnection,
+    scratch: PathBuf,
+}
+
+impl BackupReader {
+    /// Opens a backup for reading without touching the live database
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::NotFound` if the backup does not exist, or
+    /// `AppError::External` if it cannot be extracted or opened
+    pub async fn open(backup_path: &Path) -> Result<Self, AppError> {
+        if !backup_path.exists() {
+            return Err(AppError::NotFound(format!(
+                "Backup file not found: {}",
+                backup_path.display()
+            )));
+        }
+
+        let scratch = verification_scratch_path(backup_path);
+        extract_backup(backup_path, &scratch)?;
+
+        let connection = SqliteConnectOptions::new()
+            .filename(&scratch)
+            .read_only(true)
+            .connect()
+            .await;
+
+        match connection {
+            Ok(connection) => Ok(Self {
+                connection,
+                scratch,
+            }),
+            Err(e) => {
+                let _ = std::fs::remove_file(&scratch);
+                Err(AppError::External(format!("Failed to open backup: {}", e)))
+            }
+        }
+    }
+
+    /// The read-only connection to the backup contents
+    pub fn connection(&mut self) -> &mut SqliteConnection {
+        &mut self.connection
+    }
+
+    /// Closes the connection and removes the scratch file
+    pub async fn close(self) {
+        let _ = sqlx::Connection::close(self.connection).await;
+        let _ = std::fs::remove_file(&self.scratch);
+    }
+}
+
+fn open(path: &Path) -> Result<File, AppError> {
+    File::open(path)
+        .map_err(|e| AppError::External(format!("Failed to open {}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an empty scratch directory unique to the calling test
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("snips_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    async fn create_sqlite_file(path: &Path) {
+        let mut connection = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .connect()
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE snippets (id INTEGER PRIMARY KEY, name TEXT)")
+            .execute(&mut connection)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO snippets (name) VALUES ('one'), ('two')")
+            .execute(&mut connection)
+            .await
+            .unwrap();
+        sqlx::Connection::close(connection).await.unwrap();
+    }
+
+    #[test]
+    fn test_compression_from_path() {
+        assert_eq!(
+            BackupCompression::from_path(Path::new("snips_backup_1.db.gz")),
+            Some(BackupCompression::Gzip)
+        );
+        assert_eq!(
+            BackupCompression::from_path(Path::new("snips_backup_1.db")),
+            Some(BackupCompression::None)
+        );
+        assert_eq!(
+            BackupCompression::from_path(Path::new("snips_backup_1.db.gz.sha256")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_checksum_path() {
+        assert_eq!(
+            checksum_path(Path::new("/b/snips_backup_1.db.gz")),
+            PathBuf::from("/b/snips_backup_1.db.gz.sha256")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gzip_backup_round_trip_verifies() {
+        let dir = test_dir("round_trip");
+        let source = dir.join("snips.db");
+        create_sqlite_file(&source).await;
+
+        let backup = dir.join("snips_backup_1.db.gz");
+        let checksum = write_backup(&source, &backup, BackupCompression::Gzip).unwrap();
+        assert_eq!(read_checksum(&backup), Some(checksum));
+
+        let verification = verify_backup_file(&backup).await.unwrap();
+        assert!(verification.compressed);
+        assert_eq!(verification.checksum_matches, Some(true));
+        assert!(verification.integrity_ok);
+        assert!(verification.valid);
+        assert!(!verification_scratch_path(&backup).exists());
+
+        let restored = dir.join("restored.db");
+        extract_backup(&backup, &restored).unwrap();
+        assert_eq!(
+            std::fs::read(&restored).unwrap(),
+            std::fs::read(&source).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tampered_backup_fails_checksum() {
+        let dir = test_dir("tampered");
+        let source = dir.join("snips.db");
+        create_sqlite_file(&source).await;
+
+        let backup = dir.join("snips_backup_1.db");
+        write_backup(&source, &backup, BackupCompression::None).unwrap();
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&backup)
+            .unwrap()
+            .write_all(b"tampered")
+            .unwrap();
+
+        let verification = verify_backup_file(&backup).await.unwrap();
+        assert_eq!(verification.checksum_matches, Some(false));
+        assert!(!verification.valid);
+    }
+
+    #[tokio::test]
+    async fn test_backup_reader_reads_compressed_backup() {
+        let dir = test_dir("reader");
+        let source = dir.join("snips.db");
+        create_sqlite_file(&source).await;
+        let backup = dir.join("snips_backup_1.db.gz");
+        write_backup(&source, &backup, BackupCompression::Gzip).unwrap();
+
+        let mut reader = BackupReader::open(&backup).await.unwrap();
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM snippets")
+            .fetch_one(reader.connection())
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+
+        // The backup is opened read-only
+        let write = sqlx::query("DELETE FROM snippets")
+            .execute(reader.connection())
+            .await;
+        assert!(write.is_err());
+
+        reader.close().await;
+        assert!(!verification_scratch_path(&backup).exists());
+    }
+
+    #[tokio::test]
+    async fn test_non_sqlite_backup_fails_integrity() {
+        let dir = test_dir("non_sqlite");
+        let backup = dir.join("snips_backup_1.db");
+        std::fs::write(&backup, vec![7u8; 8192]).unwrap();
+
+        let verification = verify_backup_file(&backup).await.unwrap();
+        assert_eq!(verification.checksum_matches, None);
+        assert!(!verification.integrity_ok);
+        assert!(!verification.valid);
+    }
+}
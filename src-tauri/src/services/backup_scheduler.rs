@@ -1,5 +1,15 @@
-use crate::commands::storage_commands::backup_database;
+use crate::commands::storage_commands::{
+    backup_database, export_to_markdown, list_backups, optimize_database, write_json_mirror,
+    BackupInfo,
+};
+use crate::commands::tag_commands::cleanup_orphan_tags;
+use crate::services::backup_files::{checksum_path, BackupCompression};
+use crate::services::notifications::{self, NotificationKind};
+use crate::services::tray;
+use crate::utils::time::current_timestamp;
 use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tauri::AppHandle;
@@ -14,6 +24,75 @@ pub struct BackupConfig {
     pub interval_hours: u64,
     /// Maximum number of backups to keep (0 = unlimited)
     pub max_backups: usize,
+    /// Maximum age of a backup in days before it is pruned (0 = unlimited)
+    #[serde(default)]
+    pub max_age_days: u64,
+    /// Compression applied to new backups
+    #[serde(default)]
+    pub compression: BackupCompression,
+    /// Run `optimize_database` after each automatic backup
+    #[serde(default)]
+    pub optimize_after_backup: bool,
+    /// Delete tags no snippet carries after each automatic backup
+    #[serde(default)]
+    pub cleanup_orphan_tags: bool,
+    /// Plaintext export written on each automatic backup; none when unset
+    #[serde(default)]
+    pub scheduled_export: Option<ScheduledExport>,
+}
+
+/// Format of the scheduled plaintext export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduledExportFormat {
+    /// One `snips.json` export, rewritten only when a snippet changed
+    Json,
+    /// One markdown file per snippet, as written by `export_to_markdown`
+    Markdown,
+}
+
+/// A plaintext mirror of the library, kept next to the binary backups
+///
+/// Pointing it at a synced folder or a git checkout gives a readable,
+/// diffable history of the library.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledExport {
+    /// Absolute path of the directory to write into (created if missing)
+    pub directory: String,
+    pub format: ScheduledExportFormat,
+}
+
+/// File the JSON scheduled export is written to
+pub const SCHEDULED_EXPORT_FILE: &str = "snips.json";
+
+impl ScheduledExport {
+    /// Checks that the directory is an absolute path
+    pub fn validate(&self) -> Result<(), String> {
+        if !Path::new(self.directory.trim()).is_absolute() {
+            return Err(format!(
+                "Scheduled export directory must be an absolute path: {}",
+                self.directory
+            ));
+        }
+        Ok(())
+    }
+
+    /// Writes the export
+    ///
+    /// # Returns
+    ///
+    /// The number of snippets in the export
+    pub async fn write(&self, app: &AppHandle) -> Result<usize, String> {
+        let directory = PathBuf::from(self.directory.trim());
+        match self.format {
+            ScheduledExportFormat::Json => {
+                write_json_mirror(app, &directory.join(SCHEDULED_EXPORT_FILE)).await
+            }
+            ScheduledExportFormat::Markdown => {
+                export_to_markdown(app.clone(), directory.to_string_lossy().to_string()).await
+            }
+        }
+    }
 }
 
 impl Default for BackupConfig {
@@ -22,10 +101,26 @@ impl Default for BackupConfig {
             enabled: false,
             interval_hours: 24, // Daily by default
             max_backups: 7,     // Keep 7 backups by default
+            max_age_days: 0,    // No age limit by default
+            compression: BackupCompression::Gzip,
+            optimize_after_backup: false,
+            cleanup_orphan_tags: false,
+            scheduled_export: None,
         }
     }
 }
 
+/// Result of applying the backup retention policy
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PruneReport {
+    /// Number of backup files deleted
+    pub deleted_count: usize,
+    /// Total size of the deleted backup files in bytes
+    pub reclaimed_bytes: u64,
+    /// Number of backups left after pruning
+    pub remaining_count: usize,
+}
+
 /// Backup scheduler service
 pub struct BackupScheduler {
     config: Arc<RwLock<BackupConfig>>,
@@ -61,26 +156,61 @@ impl BackupScheduler {
                     continue;
                 }
 
-                // Create backup
-                match backup_database(app_handle.clone()).await {
+                // Create backup (rotation of old backups happens as part of the backup)
+                let backed_up = match backup_database(app_handle.clone()).await {
                     Ok(backup_info) => {
                         println!(
                             "Automatic backup created: {} ({} bytes)",
                             backup_info.path, backup_info.size_bytes
                         );
-
-                        // Clean up old backups if needed
-                        if current_config.max_backups > 0 {
-                            if let Err(e) =
-                                Self::cleanup_old_backups(&app_handle, current_config.max_backups)
-                                    .await
-                            {
-                                eprintln!("Failed to cleanup old backups: {}", e);
-                            }
+                        notifications::notify(
+                            &app_handle,
+                            NotificationKind::BackupCompleted,
+                            format!("Saved to {}", backup_info.path),
+                        );
+                        if let Err(e) = tray::set_backup_failed(&app_handle, false) {
+                            eprintln!("Failed to update tray icon: {}", e);
                         }
+                        true
                     }
                     Err(e) => {
                         eprintln!("Failed to create automatic backup: {}", e);
+                        notifications::notify(&app_handle, NotificationKind::BackupFailed, e);
+                        if let Err(e) = tray::set_backup_failed(&app_handle, true) {
+                            eprintln!("Failed to update tray icon: {}", e);
+                        }
+                        false
+                    }
+                };
+
+                // Compact only with a fresh backup to fall back on
+                if backed_up && current_config.optimize_after_backup {
+                    match optimize_database(app_handle.clone()).await {
+                        Ok(report) => println!(
+                            "Database optimized: reclaimed {} bytes",
+                            report.reclaimed_bytes
+                        ),
+                        Err(e) => eprintln!("Failed to optimize database: {}", e),
+                    }
+                }
+
+                // Like compaction, cleanup only runs with a fresh backup to fall back on
+                if backed_up && current_config.cleanup_orphan_tags {
+                    match cleanup_orphan_tags(app_handle.clone()).await {
+                        Ok(removed) => println!("Removed {} orphan tags", removed.len()),
+                        Err(e) => eprintln!("Failed to clean up orphan tags: {}", e),
+                    }
+                }
+
+                // The plaintext export does not modify the database, so it runs
+                // even when the backup failed
+                if let Some(export) = &current_config.scheduled_export {
+                    match export.write(&app_handle).await {
+                        Ok(count) => println!(
+                            "Scheduled export of {} snippets written to {}",
+                            count, export.directory
+                        ),
+                        Err(e) => eprintln!("Failed to write scheduled export: {}", e),
                     }
                 }
 
@@ -101,31 +231,78 @@ impl BackupScheduler {
     pub async fn get_config(&self) -> BackupConfig {
         self.config.read().await.clone()
     }
+}
 
-    /// Clean up old backups, keeping only the most recent max_count
-    async fn cleanup_old_backups(app: &AppHandle, max_count: usize) -> Result<(), String> {
-        use crate::commands::storage_commands::list_backups;
-
-        let mut backups = list_backups(app.clone()).await?;
+/// Selects the backups that fall outside the retention policy
+///
+/// `backups` must be sorted newest first. The most recent backup is always kept,
+/// even when it is older than `max_age_days`, so a scheduler that was disabled for
+/// a long time never prunes the user down to zero backups.
+///
+/// # Arguments
+///
+/// * `backups` - All existing backups, newest first
+/// * `config` - Retention settings (`max_backups` and `max_age_days`)
+/// * `now` - Current Unix timestamp in seconds
+///
+/// # Returns
+///
+/// The backups that should be deleted
+pub fn select_backups_to_prune<'a>(
+    backups: &'a [BackupInfo],
+    config: &BackupConfig,
+    now: i64,
+) -> Vec<&'a BackupInfo> {
+    let max_age_secs = (config.max_age_days as i64).saturating_mul(24 * 3600);
 
-        if backups.len() <= max_count {
-            return Ok(()); // Nothing to cleanup
-        }
+    backups
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(index, backup)| {
+            let over_count = config.max_backups > 0 && *index >= config.max_backups;
+            let over_age = config.max_age_days > 0 && now - backup.created_at > max_age_secs;
+            over_count || over_age
+        })
+        .map(|(_, backup)| backup)
+        .collect()
+}
 
-        // Sort by creation time, newest first
-        backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+/// Applies the retention policy to the backups directory
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `config` - Retention settings to apply
+///
+/// # Returns
+///
+/// A `PruneReport` describing how many backups were removed and the space reclaimed
+///
+/// # Errors
+///
+/// Returns an error string if the backups directory cannot be read. Failures to
+/// delete individual files are logged and skipped.
+pub async fn rotate_backups(app: &AppHandle, config: &BackupConfig) -> Result<PruneReport, String> {
+    let mut backups = list_backups(app.clone()).await?;
+    backups.sort_by_key(|backup| Reverse(backup.created_at));
 
-        // Delete old backups
-        for backup in backups.iter().skip(max_count) {
-            if let Err(e) = std::fs::remove_file(&backup.path) {
-                eprintln!("Failed to delete old backup {}: {}", backup.path, e);
-            } else {
+    let mut report = PruneReport::default();
+    for backup in select_backups_to_prune(&backups, config, current_timestamp()) {
+        match std::fs::remove_file(&backup.path) {
+            Ok(()) => {
+                // Backups created before checksums were introduced have no checksum file
+                let _ = std::fs::remove_file(checksum_path(Path::new(&backup.path)));
                 println!("Deleted old backup: {}", backup.path);
+                report.deleted_count += 1;
+                report.reclaimed_bytes += backup.size_bytes;
             }
+            Err(e) => eprintln!("Failed to delete old backup {}: {}", backup.path, e),
         }
-
-        Ok(())
     }
+    report.remaining_count = backups.len() - report.deleted_count;
+
+    Ok(report)
 }
 
 /// State wrapper for backup scheduler
@@ -149,9 +326,112 @@ mod tests {
             enabled: true,
             interval_hours: 12,
             max_backups: 10,
+            max_age_days: 30,
+            compression: BackupCompression::None,
+            optimize_after_backup: true,
+            cleanup_orphan_tags: true,
+            scheduled_export: Some(ScheduledExport {
+                directory: "/home/me/Sync/snips".to_string(),
+                format: ScheduledExportFormat::Markdown,
+            }),
         };
         assert!(config.enabled);
         assert_eq!(config.interval_hours, 12);
         assert_eq!(config.max_backups, 10);
+        assert_eq!(config.max_age_days, 30);
+        assert_eq!(config.compression, BackupCompression::None);
+    }
+
+    #[test]
+    fn test_backup_config_deserialize_without_max_age() {
+        let json = r#"{"enabled":true,"interval_hours":6,"max_backups":3}"#;
+        let config: BackupConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.max_backups, 3);
+        assert_eq!(config.max_age_days, 0);
+        assert_eq!(config.compression, BackupCompression::Gzip);
+        assert!(!config.optimize_after_backup);
+        assert!(!config.cleanup_orphan_tags);
+        assert!(config.scheduled_export.is_none());
+    }
+
+    #[test]
+    fn test_scheduled_export_validate() {
+        let export = |directory: &str| ScheduledExport {
+            directory: directory.to_string(),
+            format: ScheduledExportFormat::Json,
+        };
+        assert!(export("/home/me/Sync/snips").validate().is_ok());
+        assert!(export("Sync/snips").validate().is_err());
+        assert!(export("").validate().is_err());
+    }
+
+    fn backup(path: &str, created_at: i64) -> BackupInfo {
+        BackupInfo {
+            path: path.to_string(),
+            created_at,
+            size_bytes: 100,
+            compressed: false,
+        }
+    }
+
+    #[test]
+    fn test_select_backups_to_prune_by_count() {
+        let backups = vec![backup("c", 300), backup("b", 200), backup("a", 100)];
+        let config = BackupConfig {
+            max_backups: 2,
+            ..BackupConfig::default()
+        };
+
+        let pruned = select_backups_to_prune(&backups, &config, 400);
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(pruned[0].path, "a");
+    }
+
+    #[test]
+    fn test_select_backups_to_prune_by_age() {
+        let day = 24 * 3600;
+        let now = 100 * day;
+        let backups = vec![
+            backup("new", now - day),
+            backup("old", now - 10 * day),
+            backup("older", now - 20 * day),
+        ];
+        let config = BackupConfig {
+            max_backups: 0,
+            max_age_days: 7,
+            ..BackupConfig::default()
+        };
+
+        let pruned: Vec<&str> = select_backups_to_prune(&backups, &config, now)
+            .iter()
+            .map(|b| b.path.as_str())
+            .collect();
+        assert_eq!(pruned, vec!["old", "older"]);
+    }
+
+    #[test]
+    fn test_select_backups_to_prune_keeps_newest() {
+        let day = 24 * 3600;
+        let now = 100 * day;
+        let backups = vec![backup("only", now - 50 * day)];
+        let config = BackupConfig {
+            max_backups: 0,
+            max_age_days: 7,
+            ..BackupConfig::default()
+        };
+
+        assert!(select_backups_to_prune(&backups, &config, now).is_empty());
+    }
+
+    #[test]
+    fn test_select_backups_to_prune_unlimited() {
+        let backups = vec![backup("b", 200), backup("a", 100)];
+        let config = BackupConfig {
+            max_backups: 0,
+            max_age_days: 0,
+            ..BackupConfig::default()
+        };
+
+        assert!(select_backups_to_prune(&backups, &config, 1_000_000).is_empty());
     }
 }
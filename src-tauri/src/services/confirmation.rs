@@ -0,0 +1,224 @@
+use crate::utils::error::AppError;
+use crate::utils::time::current_timestamp;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// How long a confirmation token stays valid, in seconds
+pub const CONFIRMATION_TTL_SECS: i64 = 60;
+
+/// Most outstanding tokens kept; the oldest are dropped beyond this
+const MAX_PENDING: usize = 100;
+
+/// A destructive operation requested through an external surface
+///
+/// Tokens are bound to one action, so a token obtained for deleting snippet 1
+/// cannot be replayed to delete snippet 2.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DestructiveAction {
+    DeleteSnippet { id: i64 },
+    ClearAnalytics,
+    RestoreDatabase,
+    FactoryReset { delete_backups: bool },
+}
+
+impl DestructiveAction {
+    /// Human-readable description for prompts and error messages
+    pub fn describe(&self) -> String {
+        match self {
+            DestructiveAction::DeleteSnippet { id } => format!("delete snippet {}", id),
+            DestructiveAction::ClearAnalytics => "clear all analytics".to_string(),
+            DestructiveAction::RestoreDatabase => "replace the database from a backup".to_string(),
+            DestructiveAction::FactoryReset { delete_backups } => if *delete_backups {
+                "delete all snippets, settings, analytics, logs and backups"
+            } else {
+                "delete all snippets, settings, analytics and logs"
+            }
+            .to_string(),
+        }
+    }
+}
+
+/// Token a caller must send back to go ahead with a destructive action
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfirmationChallenge {
+    pub token: String,
+    pub action: DestructiveAction,
+    pub description: String,
+    pub expires_at: i64,
+}
+
+/// Result of checking a destructive request against the confirmation policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Confirmation {
+    /// The caller confirmed (valid token or explicit force); go ahead
+    Confirmed,
+    /// First stage: nothing was done, return the challenge to the caller
+    Required(ConfirmationChallenge),
+}
+
+/// Two-stage confirmation policy shared by every external surface
+///
+/// A destructive call without a token is answered with a short-lived,
+/// single-use token for that exact action; repeating the call with the token
+/// performs it. Callers may skip the round trip with an explicit force flag,
+/// which scripts have to opt into deliberately.
+#[derive(Debug, Default)]
+pub struct ConfirmationState(Mutex<HashMap<String, (DestructiveAction, i64)>>);
+
+impl ConfirmationState {
+    /// Applies the confirmation policy to a destructive request
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The operation about to be performed
+    /// * `token` - Token from an earlier challenge, if the caller has one
+    /// * `force` - Explicit opt-out of the two-stage handshake
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::Forbidden` if a token was given but is unknown,
+    /// expired, already used or issued for a different action. The token is
+    /// spent either way.
+    pub fn check(
+        &self,
+        action: &DestructiveAction,
+        token: Option<&str>,
+        force: bool,
+    ) -> Result<Confirmation, AppError> {
+        self.check_at(action, token, force, current_timestamp())
+    }
+
+    fn check_at(
+        &self,
+        action: &DestructiveAction,
+        token: Option<&str>,
+        force: bool,
+        now: i64,
+    ) -> Result<Confirmation, AppError> {
+        if force {
+            return Ok(Confirmation::Confirmed);
+        }
+
+        let mut pending = self
+            .0
+            .lock()
+            .map_err(|_| AppError::Unknown("Confirmation state is poisoned".to_string()))?;
+        pending.retain(|_, (_, expires_at)| *expires_at > now);
+
+        match token {
+            Some(token) => match pending.remove(token.trim()) {
+                Some((expected, _)) if expected == *action => Ok(Confirmation::Confirmed),
+                _ => Err(AppError::Forbidden(format!(
+                    "Confirmation token is invalid or expired; request a new one to {}",
+                    action.describe()
+                ))),
+            },
+            None => {
+                if pending.len() >= MAX_PENDING {
+                    if let Some(oldest) = pending
+                        .iter()
+                        .min_by_key(|(_, (_, expires_at))| *expires_at)
+                        .map(|(token, _)| token.clone())
+                    {
+                        pending.remove(&oldest);
+                    }
+                }
+
+                let token = generate_token()?;
+                let expires_at = now + CONFIRMATION_TTL_SECS;
+                pending.insert(token.clone(), (action.clone(), expires_at));
+                Ok(Confirmation::Required(ConfirmationChallenge {
+                    token,
+                    action: action.clone(),
+                    description: action.describe(),
+                    expires_at,
+                }))
+            }
+        }
+    }
+}
+
+/// Short random token; 64 bits is plenty for a single-use, one-minute secret
+fn generate_token() -> Result<String, AppError> {
+    let mut bytes = [0u8; 8];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| AppError::Unknown(format!("Failed to generate token: {}", e)))?;
+    Ok(hex::encode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn challenge(state: &ConfirmationState, action: &DestructiveAction, now: i64) -> String {
+        match state.check_at(action, None, false, now).unwrap() {
+            Confirmation::Required(challenge) => challenge.token,
+            Confirmation::Confirmed => panic!("expected a challenge"),
+        }
+    }
+
+    #[test]
+    fn test_two_stage_confirmation() {
+        let state = ConfirmationState::default();
+        let delete = DestructiveAction::DeleteSnippet { id: 7 };
+
+        let token = challenge(&state, &delete, 1000);
+        assert_eq!(token.len(), 16);
+        assert_eq!(
+            state.check_at(&delete, Some(&token), false, 1010).unwrap(),
+            Confirmation::Confirmed
+        );
+
+        // Tokens are single-use
+        assert!(matches!(
+            state.check_at(&delete, Some(&token), false, 1011),
+            Err(AppError::Forbidden(_))
+        ));
+    }
+
+    #[test]
+    fn test_token_bound_to_action() {
+        let state = ConfirmationState::default();
+        let token = challenge(&state, &DestructiveAction::DeleteSnippet { id: 1 }, 0);
+
+        let other = DestructiveAction::DeleteSnippet { id: 2 };
+        assert!(state.check_at(&other, Some(&token), false, 1).is_err());
+    }
+
+    #[test]
+    fn test_token_expires() {
+        let state = ConfirmationState::default();
+        let action = DestructiveAction::ClearAnalytics;
+        let token = challenge(&state, &action, 0);
+
+        assert!(state
+            .check_at(&action, Some(&token), false, CONFIRMATION_TTL_SECS)
+            .is_err());
+    }
+
+    #[test]
+    fn test_force_skips_handshake() {
+        let state = ConfirmationState::default();
+        assert_eq!(
+            state
+                .check_at(&DestructiveAction::RestoreDatabase, None, true, 0)
+                .unwrap(),
+            Confirmation::Confirmed
+        );
+    }
+
+    #[test]
+    fn test_pending_tokens_are_capped() {
+        let state = ConfirmationState::default();
+        let action = DestructiveAction::ClearAnalytics;
+        let first = challenge(&state, &action, 0);
+        for _ in 0..MAX_PENDING {
+            challenge(&state, &action, 1);
+        }
+
+        assert_eq!(state.0.lock().unwrap().len(), MAX_PENDING);
+        assert!(state.check_at(&action, Some(&first), false, 1).is_err());
+    }
+}
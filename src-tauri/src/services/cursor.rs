@@ -0,0 +1,151 @@
+/// Mouse cursor position lookup.
+///
+/// Asks the windowing system directly: Quartz events on macOS and
+/// `XQueryPointer` on X11. Wayland does not let clients read the pointer
+/// outside their own surfaces, so there the compositor is asked over IPC
+/// where it offers a way (Hyprland); elsewhere the position is unknown.
+use crate::services::focus::{self, FocusBackend};
+use crate::services::overlay_placement::ScreenPoint;
+
+/// Parses `hyprctl cursorpos` output (`"1234, 567"`)
+pub fn parse_hyprctl_cursorpos(output: &str) -> Option<(f64, f64)> {
+    let (x, y) = output.trim().split_once(',')?;
+    Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
+
+/// Current cursor position
+///
+/// Points are physical pixels on X11 and logical pixels on macOS and
+/// Hyprland, matching how each reports window frames.
+pub fn cursor_position(backend: FocusBackend) -> Option<ScreenPoint> {
+    match backend {
+        FocusBackend::Hyprland => {
+            let output = match focus::run("hyprctl", &["cursorpos"]) {
+                Ok(output) => output,
+                Err(e) => {
+                    eprintln!("Failed to get cursor position: {}", e);
+                    return None;
+                }
+            };
+            let (x, y) = parse_hyprctl_cursorpos(&output)?;
+            Some(ScreenPoint::Logical(x, y))
+        }
+        FocusBackend::X11 => {
+            x11_pointer().map(|(x, y)| ScreenPoint::Physical(f64::from(x), f64::from(y)))
+        }
+        FocusBackend::MacOs => quartz_pointer().map(|(x, y)| ScreenPoint::Logical(x, y)),
+        // Sway has no IPC request for the pointer position
+        FocusBackend::Sway | FocusBackend::Unsupported => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn x11_pointer() -> Option<(i32, i32)> {
+    use std::os::raw::{c_char, c_int, c_uint, c_ulong, c_void};
+
+    #[link(name = "X11")]
+    extern "C" {
+        fn XOpenDisplay(name: *const c_char) -> *mut c_void;
+        fn XCloseDisplay(display: *mut c_void) -> c_int;
+        fn XDefaultRootWindow(display: *mut c_void) -> c_ulong;
+        fn XQueryPointer(
+            display: *mut c_void,
+            window: c_ulong,
+            root_return: *mut c_ulong,
+            child_return: *mut c_ulong,
+            root_x_return: *mut c_int,
+            root_y_return: *mut c_int,
+            win_x_return: *mut c_int,
+            win_y_return: *mut c_int,
+            mask_return: *mut c_uint,
+        ) -> c_int;
+    }
+
+    // SAFETY: the display is checked for null before use and closed once;
+    // every out-pointer refers to a live local
+    unsafe {
+        let display = XOpenDisplay(std::ptr::null());
+        if display.is_null() {
+            return None;
+        }
+        let (mut root, mut child) = (0, 0);
+        let (mut root_x, mut root_y, mut win_x, mut win_y) = (0, 0, 0, 0);
+        let mut mask = 0;
+        let same_screen = XQueryPointer(
+            display,
+            XDefaultRootWindow(display),
+            &mut root,
+            &mut child,
+            &mut root_x,
+            &mut root_y,
+            &mut win_x,
+            &mut win_y,
+            &mut mask,
+        );
+        XCloseDisplay(display);
+        (same_screen != 0).then_some((root_x, root_y))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn x11_pointer() -> Option<(i32, i32)> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+fn quartz_pointer() -> Option<(f64, f64)> {
+    use std::os::raw::c_void;
+
+    #[repr(C)]
+    struct CGPoint {
+        x: f64,
+        y: f64,
+    }
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventCreate(source: *const c_void) -> *mut c_void;
+        fn CGEventGetLocation(event: *mut c_void) -> CGPoint;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFRelease(object: *const c_void);
+    }
+
+    // SAFETY: a null source is allowed; the event is checked for null and
+    // released exactly once
+    unsafe {
+        let event = CGEventCreate(std::ptr::null());
+        if event.is_null() {
+            return None;
+        }
+        let location = CGEventGetLocation(event);
+        CFRelease(event);
+        Some((location.x, location.y))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn quartz_pointer() -> Option<(f64, f64)> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hyprctl_cursorpos() {
+        assert_eq!(
+            parse_hyprctl_cursorpos("1234, 567\n"),
+            Some((1234.0, 567.0))
+        );
+        assert_eq!(parse_hyprctl_cursorpos("-40, 12"), Some((-40.0, 12.0)));
+        assert_eq!(
+            parse_hyprctl_cursorpos("HYPRLAND_INSTANCE_SIGNATURE not set"),
+            None
+        );
+        assert_eq!(parse_hyprctl_cursorpos(""), None);
+    }
+}
@@ -0,0 +1,373 @@
+use crate::utils::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Event emitted with the active `CustomTheme`, or null, when it changes
+pub const CUSTOM_THEME_CHANGED_EVENT: &str = "custom-theme-changed";
+
+/// Subdirectory of the app config directory holding the theme files
+pub const THEMES_DIR: &str = "themes";
+
+/// CSS variables a theme may set, without the leading `--`
+///
+/// These are the variables defined in `globals.css`; anything a theme leaves
+/// out keeps its built-in light or dark value.
+pub const THEME_VARIABLES: [&str; 14] = [
+    "background",
+    "foreground",
+    "primary",
+    "primary-foreground",
+    "secondary",
+    "secondary-foreground",
+    "accent",
+    "accent-foreground",
+    "muted",
+    "muted-foreground",
+    "border",
+    "input",
+    "ring",
+    "radius",
+];
+
+/// Longest theme name, and longest variable value
+const MAX_NAME_LEN: usize = 64;
+
+/// A user-defined palette, stored as JSON in the config dir's `themes` folder
+///
+/// # Examples
+///
+/// ```json
+/// {
+///   "name": "Nord",
+///   "dark": true,
+///   "variables": { "background": "220 16% 22%", "primary": "193 43% 67%" }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomTheme {
+    /// Display name; the file name is derived from it
+    pub name: String,
+    /// Whether the palette is dark, so dark-only styles and the native window
+    /// chrome follow it
+    #[serde(default)]
+    pub dark: bool,
+    /// CSS variable values keyed by variable name, e.g. `"primary": "193 43% 67%"`
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+}
+
+/// A color as hue in degrees and saturation and lightness in percent, the
+/// form the CSS variables take
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    pub hue: f64,
+    pub saturation: f64,
+    pub lightness: f64,
+}
+
+impl Hsl {
+    /// Parses a `#rrggbb` or `#rgb` color
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let digits = hex.trim().strip_prefix('#')?;
+        if !digits.is_ascii() {
+            return None;
+        }
+        let channel = |range: std::ops::Range<usize>| u8::from_str_radix(&digits[range], 16).ok();
+        let (r, g, b) = match digits.len() {
+            6 => (channel(0..2)?, channel(2..4)?, channel(4..6)?),
+            3 => (
+                channel(0..1)? * 17,
+                channel(1..2)? * 17,
+                channel(2..3)? * 17,
+            ),
+            _ => return None,
+        };
+        Some(Self::from_rgb(r, g, b))
+    }
+
+    fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let lightness = (max + min) / 2.0;
+        let delta = max - min;
+        if delta == 0.0 {
+            return Self {
+                hue: 0.0,
+                saturation: 0.0,
+                lightness: lightness * 100.0,
+            };
+        }
+
+        let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+        let hue = if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        Self {
+            hue,
+            saturation: saturation * 100.0,
+            lightness: lightness * 100.0,
+        }
+    }
+
+    /// Moves the lightness `fraction` of the way toward `other`'s
+    pub fn toward(self, other: Hsl, fraction: f64) -> Self {
+        Self {
+            lightness: self.lightness + (other.lightness - self.lightness) * fraction,
+            ..self
+        }
+    }
+
+    /// Whether the color reads as dark
+    pub fn is_dark(&self) -> bool {
+        self.lightness < 50.0
+    }
+
+    /// The `H S% L%` triple used by the CSS variables, e.g. `220 16% 22%`
+    pub fn to_css(self) -> String {
+        fn number(value: f64) -> String {
+            let rounded = format!("{:.1}", value);
+            rounded.strip_suffix(".0").unwrap_or(&rounded).to_string()
+        }
+        format!(
+            "{} {}% {}%",
+            number(self.hue),
+            number(self.saturation),
+            number(self.lightness)
+        )
+    }
+}
+
+/// Directory custom themes are stored in, created if missing
+pub fn themes_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| AppError::TauriError(format!("Failed to get app config dir: {}", e)))?
+        .join(THEMES_DIR);
+    std::fs::create_dir_all(&dir).map_err(io_error("create", &dir))?;
+    Ok(dir)
+}
+
+/// Maps an I/O error on `path` to an `AppError`
+fn io_error<'a>(action: &'a str, path: &'a Path) -> impl FnOnce(std::io::Error) -> AppError + 'a {
+    move |e| AppError::External(format!("Failed to {} {}: {}", action, path.display(), e))
+}
+
+/// File name for a theme: its name lowercased, with runs of other characters
+/// turned into single dashes
+fn theme_file_name(name: &str) -> Result<String, AppError> {
+    let mut slug = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        return Err(AppError::InvalidInput(format!(
+            "Theme name '{}' needs at least one letter or digit",
+            name
+        )));
+    }
+    Ok(format!("{}.json", slug))
+}
+
+/// Whether a value is safe to put in a CSS custom property
+///
+/// Colors (`210 40% 98%`, `#2e3440`, `hsl(...)`) and lengths pass; anything
+/// that could end the declaration or rule does not.
+fn is_safe_css_value(value: &str) -> bool {
+    !value.trim().is_empty()
+        && value.len() <= MAX_NAME_LEN
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || " .%#(),/-".contains(c))
+}
+
+/// Checks a theme's name and variables
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidInput` for an empty or overlong name, an unknown
+/// variable, or a value that is not a plain CSS color or length
+pub fn validate_theme(theme: &CustomTheme) -> Result<(), AppError> {
+    let name = theme.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(AppError::InvalidInput(format!(
+            "Theme name must be 1 to {} characters",
+            MAX_NAME_LEN
+        )));
+    }
+    theme_file_name(name)?;
+
+    for (variable, value) in &theme.variables {
+        if !THEME_VARIABLES.contains(&variable.as_str()) {
+            return Err(AppError::InvalidInput(format!(
+                "Unknown theme variable '{}'; expected one of: {}",
+                variable,
+                THEME_VARIABLES.join(", ")
+            )));
+        }
+        if !is_safe_css_value(value) {
+            return Err(AppError::InvalidInput(format!(
+                "Invalid value for theme variable '{}': {}",
+                variable, value
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Lists the valid themes in `dir`, sorted by name
+///
+/// Files that cannot be read or fail validation are skipped with a warning,
+/// so one bad file does not hide the others.
+pub fn list_themes(dir: &Path) -> Result<Vec<CustomTheme>, AppError> {
+    let mut themes = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(io_error("read", dir))? {
+        let path = entry.map_err(io_error("read", dir))?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        match read_theme(&path) {
+            Ok(theme) => themes.push(theme),
+            Err(e) => eprintln!("Skipping theme {}: {}", path.display(), e),
+        }
+    }
+    themes.sort_by_key(|theme| theme.name.to_lowercase());
+    Ok(themes)
+}
+
+fn read_theme(path: &Path) -> Result<CustomTheme, AppError> {
+    let json = std::fs::read_to_string(path).map_err(io_error("read", path))?;
+    let theme: CustomTheme = serde_json::from_str(&json)?;
+    validate_theme(&theme)?;
+    Ok(theme)
+}
+
+/// Loads the theme called `name`
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if there is no such theme
+pub fn load_theme(dir: &Path, name: &str) -> Result<CustomTheme, AppError> {
+    let path = dir.join(theme_file_name(name)?);
+    if !path.exists() {
+        return Err(AppError::NotFound(format!("Theme '{}' not found", name)));
+    }
+    read_theme(&path)
+}
+
+/// Validates and writes a theme, replacing any theme with the same file name
+///
+/// # Returns
+///
+/// The theme as saved, with its name trimmed
+pub fn save_theme(dir: &Path, theme: CustomTheme) -> Result<CustomTheme, AppError> {
+    validate_theme(&theme)?;
+    let theme = CustomTheme {
+        name: theme.name.trim().to_string(),
+        ..theme
+    };
+    let json = serde_json::to_string_pretty(&theme)?;
+    let path = dir.join(theme_file_name(&theme.name)?);
+    std::fs::write(&path, json).map_err(io_error("write", &path))?;
+    Ok(theme)
+}
+
+/// Deletes the theme called `name`
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if there is no such theme
+pub fn delete_theme(dir: &Path, name: &str) -> Result<(), AppError> {
+    let path = dir.join(theme_file_name(name)?);
+    if !path.exists() {
+        return Err(AppError::NotFound(format!("Theme '{}' not found", name)));
+    }
+    std::fs::remove_file(&path).map_err(io_error("delete", &path))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn theme(name: &str, variables: &[(&str, &str)]) -> CustomTheme {
+        CustomTheme {
+            name: name.to_string(),
+            dark: true,
+            variables: variables
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_hsl_from_hex() {
+        let css = |hex: &str| Hsl::from_hex(hex).map(Hsl::to_css);
+        assert_eq!(css("#ffffff").as_deref(), Some("0 0% 100%"));
+        assert_eq!(css("#000").as_deref(), Some("0 0% 0%"));
+        assert_eq!(css("#ff0000").as_deref(), Some("0 100% 50%"));
+        assert_eq!(css("#2e3440").as_deref(), Some("220 16.4% 21.6%"));
+        assert_eq!(css("2e3440"), None);
+        assert_eq!(css("#2e344"), None);
+        assert_eq!(css("#zzzzzz"), None);
+    }
+
+    #[test]
+    fn test_theme_file_name() {
+        assert_eq!(theme_file_name("Nord").unwrap(), "nord.json");
+        assert_eq!(
+            theme_file_name("  Solarized / Dark!! ").unwrap(),
+            "solarized-dark.json"
+        );
+        assert!(theme_file_name("../..").is_err());
+    }
+
+    #[test]
+    fn test_validate_theme() {
+        assert!(validate_theme(&theme("Nord", &[("background", "220 16% 22%")])).is_ok());
+        assert!(validate_theme(&theme("Hex", &[("primary", "#88c0d0")])).is_ok());
+        assert!(validate_theme(&theme("Round", &[("radius", "0.75rem")])).is_ok());
+        assert!(validate_theme(&theme(" ", &[])).is_err());
+        assert!(validate_theme(&theme("Nord", &[("sidebar", "0 0% 0%")])).is_err());
+        assert!(validate_theme(&theme("Evil", &[("primary", "red; } body { x")])).is_err());
+    }
+
+    #[test]
+    fn test_save_list_and_delete_themes() {
+        let dir = std::env::temp_dir().join(format!("snips_themes_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("broken.json"), "{").unwrap();
+
+        let saved = save_theme(&dir, theme(" Nord ", &[("primary", "193 43% 67%")])).unwrap();
+        assert_eq!(saved.name, "Nord");
+        save_theme(&dir, theme("dracula", &[])).unwrap();
+
+        let names: Vec<String> = list_themes(&dir)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, ["dracula", "Nord"]);
+        assert_eq!(load_theme(&dir, "nord").unwrap(), saved);
+
+        delete_theme(&dir, "Nord").unwrap();
+        assert!(matches!(
+            load_theme(&dir, "Nord"),
+            Err(AppError::NotFound(_))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,13 +1,148 @@
+use crate::services::settings::SettingsService;
 use crate::utils::error::AppError;
-use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
-use std::str::FromStr;
+use crate::utils::time::current_timestamp;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{
+    SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions, SqliteSynchronous,
+};
+use sqlx::{Row, SqliteConnection};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager};
-use tauri_plugin_sql::{Migration, MigrationKind};
+
+/// Connections in the backend pool
+const MAX_CONNECTIONS: u32 = 5;
+
+/// How long a connection waits for another writer's lock before failing
+/// with "database is locked"
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long a query waits for a free pool connection
+const ACQUIRE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Settings key under which the last `optimize` report is stored
+const LAST_OPTIMIZATION_KEY: &str = "last_optimization";
 
 /// Database connection pool state
-pub struct DbPool(pub SqlitePool);
+///
+/// The pool can be swapped for one on a new file, e.g. when a sync tool
+/// replaced `snips.db`; `get` always returns the current one.
+pub struct DbPool(RwLock<SqlitePool>);
+
+impl DbPool {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self(RwLock::new(pool))
+    }
+
+    /// The current pool
+    pub fn get(&self) -> SqlitePool {
+        match self.0.read() {
+            Ok(pool) => pool.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Swaps in a new pool and returns the previous one, to be closed
+    pub fn replace(&self, pool: SqlitePool) -> SqlitePool {
+        match self.0.write() {
+            Ok(mut current) => std::mem::replace(&mut *current, pool),
+            Err(poisoned) => std::mem::replace(&mut *poisoned.into_inner(), pool),
+        }
+    }
+}
 
-/// Initialize the database with migrations
+/// A versioned schema change
+///
+/// Migrations are forward-only: once released, a migration's SQL never
+/// changes, and fixes ship as a new version.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+/// A table, index or trigger defined by the migrations
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaObject {
+    /// `table`, `index` or `trigger`
+    pub kind: String,
+    pub name: String,
+}
+
+/// A schema object with the statement that creates it
+struct SchemaEntry {
+    object: SchemaObject,
+    sql: String,
+    virtual_table: bool,
+}
+
+/// What `repair` changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepairReport {
+    /// Migrations that had not been applied yet
+    pub applied_migrations: Vec<i64>,
+    /// Missing objects that were recreated, tables first
+    pub recreated: Vec<SchemaObject>,
+    /// Rows in the rebuilt search index
+    pub search_index_rows: i64,
+}
+
+/// How the search index compares with the snippets table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchIndexConsistency {
+    pub snippet_rows: i64,
+    pub index_rows: i64,
+    /// Snippets the index has no row for; searches never find them
+    pub missing_rows: i64,
+    /// Index rows whose snippet no longer exists
+    pub orphaned_rows: i64,
+    /// Index rows whose name or content differs from the snippet's
+    pub stale_rows: i64,
+    /// No missing, orphaned or stale rows
+    pub consistent: bool,
+}
+
+/// Outcome of compacting and re-analyzing the database
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OptimizeReport {
+    /// Unix timestamp of when the optimization finished
+    pub optimized_at: i64,
+    pub size_before_bytes: u64,
+    pub size_after_bytes: u64,
+    /// `size_before_bytes - size_after_bytes`, never negative
+    pub reclaimed_bytes: u64,
+    pub duration_ms: u64,
+}
+
+/// Effective SQLite settings of a pooled connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseDiagnostics {
+    /// `wal` once the pool has opened the database
+    pub journal_mode: String,
+    /// `off`, `normal`, `full` or `extra`
+    pub synchronous: String,
+    pub busy_timeout_ms: i64,
+    pub foreign_keys: bool,
+    /// WAL pages that trigger an automatic checkpoint
+    pub wal_autocheckpoint: i64,
+    pub page_size: i64,
+    pub page_count: i64,
+    /// Unused pages that `VACUUM` would reclaim
+    pub freelist_count: i64,
+    pub pool_size: u32,
+    pub pool_idle: usize,
+    pub pool_max_connections: u32,
+    /// Last migration applied to the database
+    pub schema_version: i64,
+    /// Last migration this build knows about
+    pub latest_schema_version: i64,
+    /// Tables, indexes and triggers the migrations define but the database lacks
+    pub missing_objects: Vec<SchemaObject>,
+}
+
+/// Schema migrations, oldest first
 pub fn get_migrations() -> Vec<Migration> {
     vec![
         // Migration 1: Create core tables
@@ -15,53 +150,527 @@ pub fn get_migrations() -> Vec<Migration> {
             version: 1,
             description: "create_initial_tables",
             sql: include_str!("../migrations/001_create_initial_tables.sql"),
-            kind: MigrationKind::Up,
         },
         // Migration 2: Create FTS5 table and triggers
         Migration {
             version: 2,
             description: "create_fts5_search",
             sql: include_str!("../migrations/002_create_fts5_search.sql"),
-            kind: MigrationKind::Up,
         },
         // Migration 3: Create indexes
         Migration {
             version: 3,
             description: "create_indexes",
             sql: include_str!("../migrations/003_create_indexes.sql"),
-            kind: MigrationKind::Up,
         },
         // Migration 4: Create settings table
         Migration {
             version: 4,
             description: "create_settings_table",
             sql: include_str!("../migrations/004_create_settings_table.sql"),
-            kind: MigrationKind::Up,
         },
         // Migration 5: Add tag colors
         Migration {
             version: 5,
             description: "add_tag_colors",
             sql: include_str!("../migrations/005_add_tag_colors.sql"),
-            kind: MigrationKind::Up,
         },
         // Migration 6: Fix FTS5 tags column issue
         Migration {
             version: 6,
             description: "fix_fts5_tags",
             sql: include_str!("../migrations/006_fix_fts5_tags.sql"),
-            kind: MigrationKind::Up,
+        },
+        // Migration 7: Create scoped API tokens and their audit trail
+        Migration {
+            version: 7,
+            description: "create_api_tokens",
+            sql: include_str!("../migrations/007_create_api_tokens.sql"),
+        },
+        // Migration 8: Track unfinished imports for resumption
+        Migration {
+            version: 8,
+            description: "create_import_jobs",
+            sql: include_str!("../migrations/008_create_import_jobs.sql"),
+        },
+        // Migration 9: Detected snippet language
+        Migration {
+            version: 9,
+            description: "add_snippet_language",
+            sql: include_str!("../migrations/009_add_snippet_language.sql"),
+        },
+        // Migration 10: Pinned snippets
+        Migration {
+            version: 10,
+            description: "add_snippet_pinned",
+            sql: include_str!("../migrations/010_add_snippet_pinned.sql"),
+        },
+        // Migration 11: Dynamic (shell command) snippets
+        Migration {
+            version: 11,
+            description: "add_snippet_kind",
+            sql: include_str!("../migrations/011_add_snippet_kind.sql"),
+        },
+        // Migration 12: Manual snippet ordering
+        Migration {
+            version: 12,
+            description: "add_snippet_position",
+            sql: include_str!("../migrations/012_add_snippet_position.sql"),
+        },
+        // Migration 13: Declared template parameters
+        Migration {
+            version: 13,
+            description: "add_snippet_parameters",
+            sql: include_str!("../migrations/013_add_snippet_parameters.sql"),
+        },
+        // Migration 14: Numbered shortcut slots
+        Migration {
+            version: 14,
+            description: "create_snippet_slots",
+            sql: include_str!("../migrations/014_create_snippet_slots.sql"),
+        },
+        // Migration 15: Snippets mirroring a remote file, with version history
+        Migration {
+            version: 15,
+            description: "add_snippet_sources",
+            sql: include_str!("../migrations/015_add_snippet_sources.sql"),
+        },
+        // Migration 16: Usage source and active tag filter in analytics
+        Migration {
+            version: 16,
+            description: "add_analytics_context",
+            sql: include_str!("../migrations/016_add_analytics_context.sql"),
+        },
+        // Migration 17: Image and binary attachments
+        Migration {
+            version: 17,
+            description: "create_snippet_attachments",
+            sql: include_str!("../migrations/017_create_snippet_attachments.sql"),
+        },
+        // Migration 18: Subscribed team feeds and forks of their snippets
+        Migration {
+            version: 18,
+            description: "create_snippet_feeds",
+            sql: include_str!("../migrations/018_create_snippet_feeds.sql"),
+        },
+        // Migration 19: Archived snippets, left out of search
+        Migration {
+            version: 19,
+            description: "add_snippet_archived",
+            sql: include_str!("../migrations/019_add_snippet_archived.sql"),
+        },
+        // Migration 20: Expiration dates of temporary snippets
+        Migration {
+            version: 20,
+            description: "add_snippet_expiration",
+            sql: include_str!("../migrations/020_add_snippet_expiration.sql"),
+        },
+        // Migration 21: Application a snippet was used in
+        Migration {
+            version: 21,
+            description: "add_analytics_active_app",
+            sql: include_str!("../migrations/021_add_analytics_active_app.sql"),
+        },
+        // Migration 22: Index usage by snippet and time
+        Migration {
+            version: 22,
+            description: "add_analytics_snippet_used_at_index",
+            sql: include_str!("../migrations/022_add_analytics_snippet_used_at_index.sql"),
+        },
+        // Migration 23: Tag names in the search index
+        Migration {
+            version: 23,
+            description: "add_fts5_tags",
+            sql: include_str!("../migrations/023_add_fts5_tags.sql"),
+        },
+        // Migration 24: Search aliases of snippets
+        Migration {
+            version: 24,
+            description: "add_snippet_aliases",
+            sql: include_str!("../migrations/024_add_snippet_aliases.sql"),
+        },
+        // Migration 25: Text expansion triggers of snippets
+        Migration {
+            version: 25,
+            description: "add_snippet_expansion_triggers",
+            sql: include_str!("../migrations/025_add_snippet_expansion_triggers.sql"),
         },
     ]
 }
 
-/// Initialize the database plugin with migrations
+/// Initialize the database plugin
+///
+/// The schema is owned by `run_migrations`; the plugin only preloads the
+/// database and no longer applies migrations of its own.
 pub fn init_database() -> tauri_plugin_sql::Builder {
-    tauri_plugin_sql::Builder::default().add_migrations("sqlite:snips.db", get_migrations())
+    tauri_plugin_sql::Builder::default()
 }
 
-/// Initialize SQLx connection pool for backend queries
-pub async fn init_db_pool(app: &AppHandle) -> Result<SqlitePool, AppError> {
+/// Whether a table exists in the database
+async fn table_exists(pool: &SqlitePool, name: &str) -> Result<bool, AppError> {
+    Ok(sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?)",
+    )
+    .bind(name)
+    .fetch_one(pool)
+    .await?)
+}
+
+/// Creates the `schema_version` table, adopting the SQL plugin's history
+///
+/// The SQL plugin only ever shipped the baseline migrations, versions 1
+/// through 6, and recorded them in `_sqlx_migrations`. Those rows are copied
+/// over so an existing database is not migrated twice; version 7
+/// (`create_api_tokens`) is the first one `run_migrations` owns.
+async fn ensure_schema_version_table(pool: &SqlitePool) -> Result<(), AppError> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at INTEGER NOT NULL
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    if table_exists(pool, "_sqlx_migrations").await? {
+        sqlx::query(
+            "INSERT OR IGNORE INTO schema_version (version, description, applied_at)
+             SELECT version, description,
+                    COALESCE(CAST(strftime('%s', installed_on) AS INTEGER), 0)
+             FROM _sqlx_migrations
+             WHERE success = 1",
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Last migration applied to the database, 0 if none
+pub async fn schema_version(pool: &SqlitePool) -> Result<i64, AppError> {
+    if !table_exists(pool, "schema_version").await? {
+        return Ok(0);
+    }
+    Ok(
+        sqlx::query_scalar("SELECT COALESCE(MAX(version), 0) FROM schema_version")
+            .fetch_one(pool)
+            .await?,
+    )
+}
+
+/// Applies every migration newer than the database's schema version
+///
+/// Each migration runs in one transaction together with its `schema_version`
+/// row, so a failure leaves the database at the last version that applied
+/// cleanly. A database written by a newer build is left alone.
+///
+/// # Returns
+///
+/// The versions that were applied, oldest first
+///
+/// # Errors
+///
+/// Returns `AppError::Database` naming the migration that failed
+pub async fn run_migrations(pool: &SqlitePool) -> Result<Vec<i64>, AppError> {
+    ensure_schema_version_table(pool).await?;
+    let current = schema_version(pool).await?;
+    let migrations = get_migrations();
+    let latest = migrations.last().map_or(0, |m| m.version);
+    if current > latest {
+        eprintln!(
+            "Warning: database schema version {} is newer than this build's {}; not migrating",
+            current, latest
+        );
+        return Ok(Vec::new());
+    }
+
+    let mut applied = Vec::new();
+    for migration in migrations.iter().filter(|m| m.version > current) {
+        let mut tx = pool.begin().await?;
+        sqlx::raw_sql(migration.sql)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                AppError::Database(format!(
+                    "Migration {} ({}) failed: {}",
+                    migration.version, migration.description, e
+                ))
+            })?;
+        sqlx::query(
+            "INSERT INTO schema_version (version, description, applied_at) VALUES (?, ?, ?)",
+        )
+        .bind(migration.version)
+        .bind(migration.description)
+        .bind(current_timestamp())
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        applied.push(migration.version);
+    }
+    Ok(applied)
+}
+
+/// Tables, indexes and triggers in a database, in creation order
+///
+/// FTS5 shadow tables (`snippets_fts_data` and friends) are left out; SQLite
+/// creates and drops them together with their virtual table.
+async fn schema_entries(pool: &SqlitePool) -> Result<Vec<SchemaEntry>, AppError> {
+    let rows = sqlx::query(
+        "SELECT type, name, sql FROM sqlite_master
+         WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite_%'
+         ORDER BY rowid",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let entries: Vec<SchemaEntry> = rows
+        .iter()
+        .map(|row| {
+            let sql: String = row.get(2);
+            SchemaEntry {
+                object: SchemaObject {
+                    kind: row.get(0),
+                    name: row.get(1),
+                },
+                virtual_table: sql.to_uppercase().starts_with("CREATE VIRTUAL TABLE"),
+                sql,
+            }
+        })
+        .collect();
+
+    let shadow_prefixes: Vec<String> = entries
+        .iter()
+        .filter(|e| e.virtual_table)
+        .map(|e| format!("{}_", e.object.name))
+        .collect();
+    Ok(entries
+        .into_iter()
+        .filter(|e| {
+            e.object.kind != "table"
+                || !shadow_prefixes.iter().any(|p| e.object.name.starts_with(p))
+        })
+        .collect())
+}
+
+/// Schema the migrations produce, read from a scratch in-memory database
+async fn expected_schema() -> Result<Vec<SchemaEntry>, AppError> {
+    let reference = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await?;
+    run_migrations(&reference).await?;
+    let entries = schema_entries(&reference).await;
+    reference.close().await;
+    entries
+}
+
+/// Objects the migrations define that the database lacks, tables first
+async fn missing_entries(pool: &SqlitePool) -> Result<Vec<SchemaEntry>, AppError> {
+    let present: Vec<SchemaObject> = schema_entries(pool)
+        .await?
+        .into_iter()
+        .map(|e| e.object)
+        .collect();
+    let mut missing: Vec<SchemaEntry> = expected_schema()
+        .await?
+        .into_iter()
+        .filter(|e| !present.contains(&e.object))
+        .collect();
+    // Indexes and triggers need their table
+    missing.sort_by_key(|e| match e.object.kind.as_str() {
+        "table" => 0,
+        "index" => 1,
+        _ => 2,
+    });
+    Ok(missing)
+}
+
+/// Tables, indexes and triggers the migrations define but the database lacks
+pub async fn missing_schema_objects(pool: &SqlitePool) -> Result<Vec<SchemaObject>, AppError> {
+    Ok(missing_entries(pool)
+        .await?
+        .into_iter()
+        .map(|e| e.object)
+        .collect())
+}
+
+/// Tag names of the snippet `s` as the search index stores them: sorted,
+/// space-separated, and NULL for an untagged snippet
+///
+/// Must match the expression the search index triggers use.
+const INDEXED_TAG_NAMES: &str = "(SELECT group_concat(name, ' ') FROM (
+    SELECT t.name FROM snippet_tags st JOIN tags t ON t.id = st.tag_id
+    WHERE st.snippet_id = s.id ORDER BY t.name))";
+
+/// Refills the search index from the snippets and tags tables
+///
+/// `snippets_fts` is a standalone FTS5 table kept in sync by triggers, so a
+/// missing trigger or an interrupted write leaves it out of date.
+///
+/// # Returns
+///
+/// The number of rows in the rebuilt index
+async fn refill_search_index(conn: &mut SqliteConnection) -> Result<i64, AppError> {
+    sqlx::query("DELETE FROM snippets_fts")
+        .execute(&mut *conn)
+        .await?;
+    let result = sqlx::query(&format!(
+        "INSERT INTO snippets_fts (rowid, name, content, tags, aliases)
+         SELECT s.id, s.name, s.content, {}, s.aliases FROM snippets s",
+        INDEXED_TAG_NAMES
+    ))
+    .execute(&mut *conn)
+    .await?;
+    Ok(result.rows_affected() as i64)
+}
+
+/// Compares the search index with the snippets table
+///
+/// The index stores its own copy of each name, content, set of tag names and
+/// aliases, so besides rows missing on either side it can detect rows whose
+/// text is out of date.
+pub async fn check_search_index(pool: &SqlitePool) -> Result<SearchIndexConsistency, AppError> {
+    let row = sqlx::query(&format!(
+        "SELECT
+            (SELECT COUNT(*) FROM snippets),
+            (SELECT COUNT(*) FROM snippets_fts),
+            (SELECT COUNT(*) FROM snippets s
+             WHERE NOT EXISTS (SELECT 1 FROM snippets_fts f WHERE f.rowid = s.id)),
+            (SELECT COUNT(*) FROM snippets_fts f
+             WHERE NOT EXISTS (SELECT 1 FROM snippets s WHERE s.id = f.rowid)),
+            (SELECT COUNT(*) FROM snippets s JOIN snippets_fts f ON f.rowid = s.id
+             WHERE f.name IS NOT s.name OR f.content IS NOT s.content
+                OR f.tags IS NOT {} OR f.aliases IS NOT s.aliases)",
+        INDEXED_TAG_NAMES
+    ))
+    .fetch_one(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to check search index: {}", e)))?;
+
+    let missing_rows: i64 = row.get(2);
+    let orphaned_rows: i64 = row.get(3);
+    let stale_rows: i64 = row.get(4);
+    Ok(SearchIndexConsistency {
+        snippet_rows: row.get(0),
+        index_rows: row.get(1),
+        missing_rows,
+        orphaned_rows,
+        stale_rows,
+        consistent: missing_rows == 0 && orphaned_rows == 0 && stale_rows == 0,
+    })
+}
+
+/// Drops the search index, recreates it and fills it from the snippets table
+///
+/// Recreating the table rather than only emptying it also discards damaged
+/// FTS5 shadow tables. Runs in one transaction, so searches see either the
+/// old index or the complete new one.
+///
+/// # Returns
+///
+/// The consistency of the rebuilt index, which is consistent unless a
+/// snippet changed while it was being checked
+pub async fn rebuild_search_index(pool: &SqlitePool) -> Result<SearchIndexConsistency, AppError> {
+    let create = expected_schema()
+        .await?
+        .into_iter()
+        .find(|e| e.object.kind == "table" && e.object.name == "snippets_fts")
+        .ok_or_else(|| AppError::Database("Migrations define no search index".to_string()))?;
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("DROP TABLE IF EXISTS snippets_fts")
+        .execute(&mut *tx)
+        .await?;
+    sqlx::raw_sql(&create.sql).execute(&mut *tx).await?;
+    refill_search_index(&mut tx).await?;
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to rebuild search index: {}", e)))?;
+
+    check_search_index(pool).await
+}
+
+/// Brings the schema back in line with the migrations
+///
+/// Applies pending migrations, recreates missing tables, indexes and
+/// triggers from the statements the migrations produce, and rebuilds the
+/// search index. A recreated table is empty; its rows cannot be recovered
+/// here, only from a backup.
+///
+/// # Errors
+///
+/// Returns `AppError` if a migration or statement fails; the recreation
+/// and rebuild run in one transaction and are rolled back together
+pub async fn repair(pool: &SqlitePool) -> Result<RepairReport, AppError> {
+    let applied_migrations = run_migrations(pool).await?;
+    let missing = missing_entries(pool).await?;
+    let existing_tables: Vec<String> =
+        sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type = 'table'")
+            .fetch_all(pool)
+            .await?;
+
+    let mut tx = pool.begin().await?;
+    for entry in &missing {
+        if entry.virtual_table {
+            // Shadow tables left behind would make CREATE VIRTUAL TABLE fail
+            let prefix = format!("{}_", entry.object.name);
+            for shadow in existing_tables.iter().filter(|t| t.starts_with(&prefix)) {
+                sqlx::query(&format!("DROP TABLE IF EXISTS \"{}\"", shadow))
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+        sqlx::raw_sql(&entry.sql)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                AppError::Database(format!(
+                    "Failed to recreate {} {}: {}",
+                    entry.object.kind, entry.object.name, e
+                ))
+            })?;
+    }
+    let search_index_rows = refill_search_index(&mut tx).await?;
+    tx.commit().await?;
+
+    Ok(RepairReport {
+        applied_migrations,
+        recreated: missing.into_iter().map(|e| e.object).collect(),
+        search_index_rows,
+    })
+}
+
+/// Connection options for the backend pool
+///
+/// WAL lets the search overlay read while the management window writes, and
+/// `synchronous = NORMAL` is safe with WAL (a power loss can drop the last
+/// commits but not corrupt the file). The busy timeout makes a writer wait
+/// for the lock instead of failing immediately with "database is locked".
+/// WAL is stored in the database file, so the SQL plugin's connection uses
+/// it too once the pool has opened the database.
+pub fn connect_options(db_path: &Path) -> SqliteConnectOptions {
+    SqliteConnectOptions::new()
+        .filename(db_path)
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .synchronous(SqliteSynchronous::Normal)
+        .busy_timeout(BUSY_TIMEOUT)
+        .foreign_keys(true)
+}
+
+/// Opens a pool on a database file with the backend's connection options
+pub async fn open_pool(db_path: &Path) -> Result<SqlitePool, AppError> {
+    SqlitePoolOptions::new()
+        .max_connections(MAX_CONNECTIONS)
+        .acquire_timeout(ACQUIRE_TIMEOUT)
+        .connect_with(connect_options(db_path))
+        .await
+        .map_err(|e| AppError::Database(format!("Failed to connect to database: {}", e)))
+}
+
+/// Path of the database file, creating its directory if needed
+pub fn database_path(app: &AppHandle) -> Result<PathBuf, AppError> {
     let app_dir = app
         .path()
         .app_data_dir()
@@ -71,35 +680,357 @@ pub async fn init_db_pool(app: &AppHandle) -> Result<SqlitePool, AppError> {
     std::fs::create_dir_all(&app_dir)
         .map_err(|e| AppError::Database(format!("Failed to create app data dir: {}", e)))?;
 
-    let db_path = app_dir.join("snips.db");
-    let db_url = format!("sqlite://{}", db_path.display());
+    Ok(app_dir.join("snips.db"))
+}
 
-    let options = SqliteConnectOptions::from_str(&db_url)
-        .map_err(|e| AppError::Database(format!("Invalid database URL: {}", e)))?
-        .create_if_missing(true);
+/// Initialize SQLx connection pool for backend queries and apply pending
+/// migrations
+pub async fn init_db_pool(app: &AppHandle) -> Result<SqlitePool, AppError> {
+    let pool = open_pool(&database_path(app)?).await?;
+    run_migrations(&pool).await?;
+    Ok(pool)
+}
 
-    let pool = SqlitePoolOptions::new()
-        .max_connections(5)
-        .connect_with(options)
+/// Moves everything in the WAL into the database file and empties the WAL
+///
+/// Must run before the database file is copied or replaced; with WAL, recent
+/// commits live in `snips.db-wal` until a checkpoint.
+pub async fn checkpoint(pool: &SqlitePool) -> Result<(), AppError> {
+    sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .execute(pool)
         .await
-        .map_err(|e| AppError::Database(format!("Failed to connect to database: {}", e)))?;
+        .map_err(|e| AppError::Database(format!("Failed to checkpoint database: {}", e)))?;
+    Ok(())
+}
 
-    Ok(pool)
+/// Size of the database in bytes, from its page count
+async fn database_size(pool: &SqlitePool) -> Result<u64, AppError> {
+    let mut conn = pool.acquire().await?;
+    let page_size = pragma(&mut conn, "page_size").await?;
+    let page_count = pragma(&mut conn, "page_count").await?;
+    Ok((page_size * page_count).max(0) as u64)
+}
+
+/// Compacts the database and refreshes the query planner statistics
+///
+/// Runs `VACUUM` to rebuild the file without free pages, `ANALYZE` to
+/// refresh index statistics and `PRAGMA optimize`, then checkpoints so the
+/// file on disk shrinks right away. `VACUUM` needs a moment of exclusive
+/// access; other connections wait up to the busy timeout. The report is
+/// stored so `get_database_stats` can show it later.
+pub async fn optimize(pool: &SqlitePool) -> Result<OptimizeReport, AppError> {
+    let started = Instant::now();
+    checkpoint(pool).await?;
+    let size_before_bytes = database_size(pool).await?;
+
+    for statement in ["VACUUM", "ANALYZE", "PRAGMA optimize"] {
+        sqlx::query(statement)
+            .execute(pool)
+            .await
+            .map_err(|e| AppError::Database(format!("{} failed: {}", statement, e)))?;
+    }
+    checkpoint(pool).await?;
+    let size_after_bytes = database_size(pool).await?;
+
+    let report = OptimizeReport {
+        optimized_at: current_timestamp(),
+        size_before_bytes,
+        size_after_bytes,
+        reclaimed_bytes: size_before_bytes.saturating_sub(size_after_bytes),
+        duration_ms: started.elapsed().as_millis() as u64,
+    };
+    SettingsService::new(pool.clone())
+        .update_setting(LAST_OPTIMIZATION_KEY, serde_json::to_string(&report)?)
+        .await?;
+    Ok(report)
+}
+
+/// Returns the report of the last `optimize`, if it ever ran
+pub async fn last_optimization(pool: &SqlitePool) -> Result<Option<OptimizeReport>, AppError> {
+    let stored = SettingsService::new(pool.clone())
+        .get_setting(LAST_OPTIMIZATION_KEY)
+        .await?;
+    // A report from an incompatible version is treated as missing
+    Ok(stored.and_then(|json| serde_json::from_str(&json).ok()))
+}
+
+/// Reads the effective pragmas of a pooled connection, the pool's state and
+/// how the schema compares to the migrations
+pub async fn get_diagnostics(pool: &SqlitePool) -> Result<DatabaseDiagnostics, AppError> {
+    let mut conn = pool.acquire().await?;
+    let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+        .fetch_one(&mut *conn)
+        .await?;
+    let synchronous = pragma(&mut conn, "synchronous").await?;
+    let busy_timeout_ms = pragma(&mut conn, "busy_timeout").await?;
+    let foreign_keys = pragma(&mut conn, "foreign_keys").await?;
+    let wal_autocheckpoint = pragma(&mut conn, "wal_autocheckpoint").await?;
+    let page_size = pragma(&mut conn, "page_size").await?;
+    let page_count = pragma(&mut conn, "page_count").await?;
+    let freelist_count = pragma(&mut conn, "freelist_count").await?;
+    drop(conn);
+
+    Ok(DatabaseDiagnostics {
+        journal_mode: journal_mode.to_lowercase(),
+        synchronous: synchronous_name(synchronous).to_string(),
+        busy_timeout_ms,
+        foreign_keys: foreign_keys != 0,
+        wal_autocheckpoint,
+        page_size,
+        page_count,
+        freelist_count,
+        pool_size: pool.size(),
+        pool_idle: pool.num_idle(),
+        pool_max_connections: MAX_CONNECTIONS,
+        schema_version: schema_version(pool).await?,
+        latest_schema_version: get_migrations().last().map_or(0, |m| m.version),
+        missing_objects: missing_schema_objects(pool).await?,
+    })
+}
+
+/// Reads an integer pragma
+async fn pragma(conn: &mut SqliteConnection, name: &str) -> Result<i64, AppError> {
+    Ok(sqlx::query_scalar(&format!("PRAGMA {}", name))
+        .fetch_one(conn)
+        .await?)
+}
+
+/// Name of a `PRAGMA synchronous` level
+fn synchronous_name(level: i64) -> &'static str {
+    match level {
+        0 => "off",
+        1 => "normal",
+        2 => "full",
+        3 => "extra",
+        _ => "unknown",
+    }
 }
 
 /// Get database pool from app state
 pub fn get_pool(app: &AppHandle) -> Result<SqlitePool, AppError> {
-    Ok(app.state::<DbPool>().0.clone())
+    Ok(app.state::<DbPool>().get())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_pool_uses_wal_and_busy_timeout() {
+        let dir = std::env::temp_dir().join(format!("snips_pragmas_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let pool = open_pool(&dir.join("snips.db")).await.unwrap();
+        let diagnostics = get_diagnostics(&pool).await.unwrap();
+        assert_eq!(diagnostics.journal_mode, "wal");
+        assert_eq!(diagnostics.synchronous, "normal");
+        assert_eq!(diagnostics.busy_timeout_ms, 5000);
+        assert!(diagnostics.foreign_keys);
+        assert_eq!(diagnostics.pool_max_connections, 5);
+
+        sqlx::query("CREATE TABLE t (id INTEGER PRIMARY KEY)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        checkpoint(&pool).await.unwrap();
+        let wal_size = std::fs::metadata(dir.join("snips.db-wal"))
+            .map(|m| m.len())
+            .unwrap_or(0);
+        assert_eq!(wal_size, 0);
+
+        pool.close().await;
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_optimize_reclaims_free_pages() {
+        let dir = std::env::temp_dir().join(format!("snips_optimize_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let pool = open_pool(&dir.join("snips.db")).await.unwrap();
+        sqlx::raw_sql(
+            "CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT, updated_at INTEGER);
+             CREATE TABLE t (body TEXT);
+             WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 500)
+             INSERT INTO t SELECT printf('%.1000c', 'x') FROM n;
+             DELETE FROM t;",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert_eq!(last_optimization(&pool).await.unwrap(), None);
+
+        let report = optimize(&pool).await.unwrap();
+        assert!(report.size_after_bytes < report.size_before_bytes);
+        assert_eq!(
+            report.reclaimed_bytes,
+            report.size_before_bytes - report.size_after_bytes
+        );
+        assert_eq!(get_diagnostics(&pool).await.unwrap().freelist_count, 0);
+        assert_eq!(last_optimization(&pool).await.unwrap(), Some(report));
+
+        pool.close().await;
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    async fn memory_pool() -> SqlitePool {
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_run_migrations_is_forward_only() {
+        let pool = memory_pool().await;
+        let applied = run_migrations(&pool).await.unwrap();
+        assert_eq!(applied, (1..=25).collect::<Vec<i64>>());
+        assert_eq!(schema_version(&pool).await.unwrap(), 25);
+
+        assert!(run_migrations(&pool).await.unwrap().is_empty());
+        assert!(missing_schema_objects(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_migrations_adopts_plugin_history() {
+        let pool = memory_pool().await;
+        for migration in get_migrations() {
+            sqlx::raw_sql(migration.sql).execute(&pool).await.unwrap();
+        }
+        sqlx::raw_sql(
+            "CREATE TABLE _sqlx_migrations (
+                version BIGINT PRIMARY KEY, description TEXT NOT NULL,
+                installed_on TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                success BOOLEAN NOT NULL, checksum BLOB NOT NULL, execution_time BIGINT NOT NULL
+            );
+            WITH RECURSIVE n(v) AS (SELECT 1 UNION ALL SELECT v + 1 FROM n WHERE v < 25)
+            INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+            SELECT v, 'migration', 1, x'00', 0 FROM n;",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert!(run_migrations(&pool).await.unwrap().is_empty());
+        assert_eq!(schema_version(&pool).await.unwrap(), 25);
+    }
+
+    #[tokio::test]
+    async fn test_repair_recreates_missing_objects() {
+        let pool = memory_pool().await;
+        run_migrations(&pool).await.unwrap();
+        sqlx::raw_sql(
+            "INSERT INTO snippets (name, content, created_at, updated_at)
+             VALUES ('greet', 'hello', 0, 0), ('part', 'goodbye', 0, 0);
+             DROP TRIGGER snippets_ai;
+             DROP TABLE snippets_fts;
+             DROP TABLE snippet_slots;",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let missing = missing_schema_objects(&pool).await.unwrap();
+        let names: Vec<&str> = missing.iter().map(|o| o.name.as_str()).collect();
+        assert_eq!(names, vec!["snippets_fts", "snippet_slots", "snippets_ai"]);
+
+        let report = repair(&pool).await.unwrap();
+        assert!(report.applied_migrations.is_empty());
+        assert_eq!(report.recreated, missing);
+        assert_eq!(report.search_index_rows, 2);
+        assert!(missing_schema_objects(&pool).await.unwrap().is_empty());
+
+        let found: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM snippets_fts WHERE snippets_fts MATCH 'hello'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(found, 1);
+    }
+
+    #[tokio::test]
+    async fn test_search_index_check_and_rebuild() {
+        let pool = memory_pool().await;
+        run_migrations(&pool).await.unwrap();
+        sqlx::raw_sql(
+            "INSERT INTO snippets (id, name, content, created_at, updated_at)
+             VALUES (1, 'greet', 'hello', 0, 0), (2, 'part', 'goodbye', 0, 0),
+                    (3, 'ping', 'pong', 0, 0);
+             DELETE FROM snippets_fts WHERE rowid = 1;
+             INSERT INTO snippets_fts (rowid, name, content) VALUES (9, 'gone', 'gone');
+             UPDATE snippets_fts SET content = 'outdated' WHERE rowid = 2;",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Equal counts alone do not mean the index is in sync
+        let check = check_search_index(&pool).await.unwrap();
+        assert_eq!((check.snippet_rows, check.index_rows), (3, 3));
+        assert_eq!(
+            (check.missing_rows, check.orphaned_rows, check.stale_rows),
+            (1, 1, 1)
+        );
+        assert!(!check.consistent);
+
+        let rebuilt = rebuild_search_index(&pool).await.unwrap();
+        assert!(rebuilt.consistent);
+        assert_eq!(rebuilt.index_rows, 3);
+    }
+
+    #[tokio::test]
+    async fn test_search_index_tracks_tags() {
+        let pool = memory_pool().await;
+        run_migrations(&pool).await.unwrap();
+        sqlx::raw_sql(
+            "INSERT INTO snippets (id, name, content, created_at, updated_at)
+             VALUES (1, 'deploy', 'kubectl apply', 0, 0), (2, 'greet', 'hello', 0, 0);
+             INSERT INTO tags (id, name) VALUES (1, 'docker'), (2, 'infra/k8s');
+             INSERT INTO snippet_tags (snippet_id, tag_id) VALUES (1, 2), (1, 1);
+             UPDATE tags SET name = 'containers' WHERE id = 1;",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let tags: Option<String> =
+            sqlx::query_scalar("SELECT tags FROM snippets_fts WHERE rowid = 1")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(tags.as_deref(), Some("containers infra/k8s"));
+        assert!(check_search_index(&pool).await.unwrap().consistent);
+
+        sqlx::query("DELETE FROM snippet_tags WHERE tag_id = 2")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE snippets_fts SET tags = 'outdated' WHERE rowid = 2")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let check = check_search_index(&pool).await.unwrap();
+        assert_eq!(check.stale_rows, 1);
+
+        let rebuilt = rebuild_search_index(&pool).await.unwrap();
+        assert!(rebuilt.consistent);
+        let found: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM snippets_fts WHERE snippets_fts MATCH 'tags : containers'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(found, 1);
+    }
+
     #[test]
     fn test_migrations_count() {
         let migrations = get_migrations();
-        assert_eq!(migrations.len(), 6);
+        assert_eq!(migrations.len(), 25);
     }
 
     #[test]
@@ -111,5 +1042,24 @@ mod tests {
         assert_eq!(migrations[3].version, 4);
         assert_eq!(migrations[4].version, 5);
         assert_eq!(migrations[5].version, 6);
+        assert_eq!(migrations[6].version, 7);
+        assert_eq!(migrations[7].version, 8);
+        assert_eq!(migrations[8].version, 9);
+        assert_eq!(migrations[9].version, 10);
+        assert_eq!(migrations[10].version, 11);
+        assert_eq!(migrations[11].version, 12);
+        assert_eq!(migrations[12].version, 13);
+        assert_eq!(migrations[13].version, 14);
+        assert_eq!(migrations[14].version, 15);
+        assert_eq!(migrations[15].version, 16);
+        assert_eq!(migrations[16].version, 17);
+        assert_eq!(migrations[17].version, 18);
+        assert_eq!(migrations[18].version, 19);
+        assert_eq!(migrations[19].version, 20);
+        assert_eq!(migrations[20].version, 21);
+        assert_eq!(migrations[21].version, 22);
+        assert_eq!(migrations[22].version, 23);
+        assert_eq!(migrations[23].version, 24);
+        assert_eq!(migrations[24].version, 25);
     }
 }
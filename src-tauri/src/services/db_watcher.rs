@@ -0,0 +1,238 @@
+/// Notices when `snips.db` is replaced behind the app's back.
+///
+/// Sync tools such as Syncthing and Dropbox deliver a changed file by writing
+/// a temporary copy and renaming it over the original. Open connections keep
+/// reading the old, now unlinked file, so the app would never see the synced
+/// snippets and its writes would be lost. The watcher polls the file's
+/// identity and, when it changes, reopens the database on the new file.
+use crate::commands::settings_commands::SettingsServiceState;
+use crate::services::database::{self, DbPool};
+use crate::services::settings::{SettingsService, SETTINGS_CHANGED_EVENT};
+use crate::services::{library_stats, menubar, name_index};
+use crate::utils::error::AppError;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// How often the database file is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Identity of the database file the pool was last opened on
+///
+/// Shared with `reload_database`, so a file the app swaps in itself (e.g. a
+/// restored backup) is not picked up again as an external replacement.
+static KNOWN_FILE: Mutex<Option<FileIdentity>> = Mutex::new(None);
+
+/// Records the file now at `path` as the one the pool is open on
+///
+/// # Returns
+///
+/// Whether the file differs from the one recorded before
+fn remember_file(path: &Path) -> bool {
+    let current = file_identity(path);
+    let mut known = match KNOWN_FILE.lock() {
+        Ok(known) => known,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    std::mem::replace(&mut *known, current) != current
+}
+
+/// Identifies the file at a path; replacing the file changes it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileIdentity {
+    /// Device and inode number; a renamed-over file always has a new inode
+    #[cfg(unix)]
+    inode: (u64, u64),
+    /// Creation time, which a replacement file gets anew
+    #[cfg(not(unix))]
+    created: Option<std::time::SystemTime>,
+}
+
+/// The identity of the file at `path`, or `None` if it does not exist
+fn file_identity(path: &Path) -> Option<FileIdentity> {
+    let metadata = std::fs::metadata(path).ok()?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some(FileIdentity {
+            inode: (metadata.dev(), metadata.ino()),
+        })
+    }
+    #[cfg(not(unix))]
+    {
+        Some(FileIdentity {
+            created: metadata.created().ok(),
+        })
+    }
+}
+
+/// Reopens the database on the file now at `path`
+///
+/// The file is migrated and any missing tables, indexes or triggers are
+/// recreated before the pool is swapped, so a file synced from an older
+/// version works like one opened at launch. Afterwards the caches are
+/// dropped and every window is told that settings and snippets changed.
+///
+/// # Errors
+///
+/// Returns `AppError` if the file cannot be opened or migrated; the app then
+/// keeps using the previous pool
+pub async fn reload_database(app: &AppHandle, path: &Path) -> Result<(), AppError> {
+    remember_file(path);
+    let pool = database::open_pool(path).await?;
+    database::run_migrations(&pool).await?;
+    if !database::missing_schema_objects(&pool).await?.is_empty() {
+        let report = database::repair(&pool).await?;
+        println!(
+            "Recreated {} schema objects in the replaced database",
+            report.recreated.len()
+        );
+    }
+
+    let previous = app.state::<DbPool>().replace(pool.clone());
+    previous.close().await;
+
+    library_stats::invalidate(app).await;
+    name_index::invalidate(app).await;
+    if let Some(state) = app.try_state::<SettingsServiceState>() {
+        *state.0.lock().await = None;
+    }
+
+    match SettingsService::new(pool).get_settings().await {
+        Ok(settings) => {
+            if let Err(e) = app.emit(SETTINGS_CHANGED_EVENT, &settings) {
+                eprintln!("Failed to emit {}: {}", SETTINGS_CHANGED_EVENT, e);
+            }
+        }
+        Err(e) => eprintln!("Failed to load settings from the replaced database: {}", e),
+    }
+    menubar::notify_snippets_changed(app);
+
+    Ok(())
+}
+
+/// Replaces the database file with `replacement` and reopens it
+///
+/// The current pool is closed first and the old `-wal` and `-shm` files are
+/// removed, so no open connection can serve pages cached from the old file
+/// or write them into the new one. The new file is then opened, migrated and
+/// swapped in by `reload_database`. If the rename fails the old file is
+/// reopened.
+///
+/// # Errors
+///
+/// Returns `AppError` if the file cannot be renamed, opened or migrated
+pub async fn replace_database_file(
+    app: &AppHandle,
+    replacement: &Path,
+    path: &Path,
+) -> Result<(), AppError> {
+    app.state::<DbPool>().get().close().await;
+
+    for suffix in ["-wal", "-shm"] {
+        let mut side_file = path.as_os_str().to_owned();
+        side_file.push(suffix);
+        match std::fs::remove_file(&side_file) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                reload_database(app, path).await?;
+                return Err(AppError::External(format!(
+                    "Failed to remove {}: {}",
+                    Path::new(&side_file).display(),
+                    e
+                )));
+            }
+        }
+    }
+
+    if let Err(e) = std::fs::rename(replacement, path) {
+        reload_database(app, path).await?;
+        return Err(AppError::External(format!(
+            "Failed to replace {}: {}",
+            path.display(),
+            e
+        )));
+    }
+    reload_database(app, path).await
+}
+
+/// Starts the background loop that watches the database file
+pub fn start(app: AppHandle) {
+    let path: PathBuf = match database::database_path(&app) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Not watching the database file: {}", e);
+            return;
+        }
+    };
+
+    remember_file(&path);
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            // Missing for a moment while a sync tool swaps the file in
+            if file_identity(&path).is_none() {
+                continue;
+            }
+            // Don't retry a file that failed to open until it changes again
+            if !remember_file(&path) {
+                continue;
+            }
+
+            match reload_database(&app, &path).await {
+                Ok(()) => println!("Reloaded {} after it was replaced", path.display()),
+                Err(e) => eprintln!("Failed to reload replaced database: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_identity() {
+        let dir = std::env::temp_dir().join(format!("snips-db-watcher-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("snips.db");
+        std::fs::write(&path, b"one").unwrap();
+        let original = file_identity(&path).unwrap();
+
+        // Writing in place keeps the file
+        std::fs::write(&path, b"two").unwrap();
+        assert_eq!(file_identity(&path), Some(original));
+
+        // Renaming another file over it, as sync tools do, does not
+        let synced = dir.join(".syncthing.snips.db.tmp");
+        std::fs::write(&synced, b"three").unwrap();
+        std::fs::rename(&synced, &path).unwrap();
+        assert_ne!(file_identity(&path), Some(original));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(file_identity(&path), None);
+    }
+
+    #[test]
+    fn test_remember_file() {
+        let dir = std::env::temp_dir().join(format!("snips-db-known-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("snips.db");
+        std::fs::write(&path, b"one").unwrap();
+        remember_file(&path);
+        assert!(!remember_file(&path));
+
+        // A file swapped in by the app is remembered, so the watcher skips it
+        let restored = dir.join("snips.db.restore.tmp");
+        std::fs::write(&restored, b"two").unwrap();
+        std::fs::rename(&restored, &path).unwrap();
+        assert!(remember_file(&path));
+        assert!(!remember_file(&path));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
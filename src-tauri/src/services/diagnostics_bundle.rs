@@ -0,0 +1,331 @@
+/// Diagnostics bundle for bug reports.
+///
+/// Collects everything the individual diagnostics commands report (window
+/// and focus state, database settings, the resource watchdog, the startup
+/// report, clipboard support) together with the settings and the tail of
+/// any log files into a single zip. Secrets are redacted from the settings
+/// before they are written, and a section that cannot be collected is noted
+/// in the manifest instead of failing the whole export.
+use crate::commands::clipboard_commands::probe_clipboard_support;
+use crate::services::database::{self, get_pool};
+use crate::services::resource_monitor;
+use crate::services::settings::SettingsService;
+use crate::services::startup::StartupReportState;
+use crate::services::window;
+use crate::utils::error::AppError;
+use crate::utils::time::current_timestamp;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use zip::result::ZipError;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Replacement for redacted setting values
+pub const REDACTED: &str = "[redacted]";
+
+/// Setting keys whose values are never included, matched as substrings
+const SECRET_KEY_MARKERS: [&str; 4] = ["token", "secret", "password", "api_key"];
+
+/// Log files included, most recently modified first
+const MAX_LOG_FILES: usize = 5;
+
+/// Bytes kept from the end of each log file
+const MAX_LOG_BYTES: u64 = 256 * 1024;
+
+/// A file in the bundle: archive path and contents
+type BundleFile = (String, Vec<u8>);
+
+/// Outcome of a diagnostics bundle export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticsBundleReport {
+    pub path: String,
+    /// Files in the bundle, in archive order
+    pub files: Vec<String>,
+    /// Sections that could not be collected, with the reason
+    pub errors: Vec<String>,
+    pub size_bytes: u64,
+}
+
+/// Describes the bundle; written as `manifest.json`
+#[derive(Debug, Clone, Serialize)]
+struct Manifest {
+    app_version: String,
+    os: &'static str,
+    arch: &'static str,
+    generated_at: i64,
+    files: Vec<String>,
+    errors: Vec<String>,
+}
+
+/// Replaces secret values in serialized settings with [`REDACTED`]
+///
+/// Any key containing `token`, `secret`, `password` or `api_key` is
+/// redacted at every depth, so secrets added to the settings later are
+/// covered without listing them here. Unset (`null`) values are kept to
+/// show whether the secret was configured.
+pub fn redact_secrets(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, entry) in map.iter_mut() {
+                let key = key.to_lowercase();
+                if SECRET_KEY_MARKERS.iter().any(|marker| key.contains(marker)) {
+                    if !entry.is_null() {
+                        *entry = Value::String(REDACTED.to_string());
+                    }
+                } else {
+                    redact_secrets(entry);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_secrets),
+        _ => {}
+    }
+}
+
+/// Builds a zip archive with every entry deflated
+pub fn write_zip(entries: &[BundleFile]) -> Result<Vec<u8>, AppError> {
+    let to_error = |e: ZipError| AppError::Unknown(format!("Failed to compress: {}", e));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, data) in entries {
+        writer
+            .start_file(name.as_str(), options)
+            .map_err(to_error)?;
+        writer
+            .write_all(data)
+            .map_err(|e| to_error(ZipError::Io(e)))?;
+    }
+    Ok(writer.finish().map_err(to_error)?.into_inner())
+}
+
+/// Reads up to `max_bytes` from the end of a file
+///
+/// When the file is cut, the partial first line is dropped.
+fn read_tail(path: &Path, max_bytes: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(max_bytes);
+    file.seek(SeekFrom::Start(start))?;
+
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail)?;
+    if start > 0 {
+        if let Some(newline) = tail.iter().position(|&byte| byte == b'\n') {
+            tail.drain(..=newline);
+        }
+    }
+    Ok(tail)
+}
+
+/// Tails of the most recently modified `.log` files in `dir`
+///
+/// A missing directory yields no logs.
+pub fn recent_logs(dir: &Path) -> Result<Vec<BundleFile>, AppError> {
+    let to_error = |e: std::io::Error| AppError::Unknown(format!("Failed to read logs: {}", e));
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(to_error(e)),
+    };
+
+    let mut logs: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "log"))
+        .filter_map(|path| {
+            let modified = path.metadata().and_then(|meta| meta.modified()).ok()?;
+            Some((modified, path))
+        })
+        .collect();
+    logs.sort_by(|a, b| b.0.cmp(&a.0));
+
+    logs.into_iter()
+        .take(MAX_LOG_FILES)
+        .map(|(_, path)| {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            Ok((
+                format!("logs/{}", name),
+                read_tail(&path, MAX_LOG_BYTES).map_err(to_error)?,
+            ))
+        })
+        .collect()
+}
+
+/// Serializes a section as a pretty JSON file
+fn json_section<T: Serialize>(name: &str, value: &T) -> Result<Vec<BundleFile>, AppError> {
+    Ok(vec![(name.to_string(), serde_json::to_vec_pretty(value)?)])
+}
+
+/// Settings as JSON with secrets redacted
+async fn redacted_settings(app: &AppHandle) -> Result<Value, AppError> {
+    let settings = SettingsService::new(get_pool(app)?).get_settings().await?;
+    let mut value = serde_json::to_value(settings)?;
+    redact_secrets(&mut value);
+    Ok(value)
+}
+
+/// Writes a diagnostics bundle to `path`
+///
+/// # Arguments
+///
+/// * `app` - Application handle
+/// * `path` - Destination of the zip file
+///
+/// # Returns
+///
+/// The files written and the sections that could not be collected
+///
+/// # Errors
+///
+/// Returns `AppError` if the bundle cannot be built or written
+pub async fn export_diagnostics_bundle(
+    app: &AppHandle,
+    path: &Path,
+) -> Result<DiagnosticsBundleReport, AppError> {
+    let mut files: Vec<BundleFile> = Vec::new();
+    let mut errors = Vec::new();
+    let mut collect = |section: &str, result: Result<Vec<BundleFile>, AppError>| match result {
+        Ok(entries) => files.extend(entries),
+        Err(e) => errors.push(format!("{}: {}", section, e)),
+    };
+
+    collect(
+        "window",
+        json_section("window.json", &window::window_diagnostics(app)),
+    );
+
+    let database = match get_pool(app) {
+        Ok(pool) => database::get_diagnostics(&pool).await,
+        Err(e) => Err(e),
+    };
+    collect(
+        "database",
+        database.and_then(|diagnostics| json_section("database.json", &diagnostics)),
+    );
+
+    let resources = resource_monitor::get_resource_usage(app).await;
+    collect("resources", json_section("resources.json", &resources));
+
+    if let Some(state) = app.try_state::<StartupReportState>() {
+        let report = state.0.read().await.clone();
+        collect("startup", json_section("startup.json", &report));
+    }
+
+    collect(
+        "clipboard",
+        json_section("clipboard.json", &probe_clipboard_support()),
+    );
+
+    let settings = redacted_settings(app).await;
+    collect(
+        "settings",
+        settings.and_then(|value| json_section("settings.json", &value)),
+    );
+
+    let logs = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| AppError::TauriError(e.to_string()))
+        .and_then(|dir| recent_logs(&dir));
+    collect("logs", logs);
+
+    let names: Vec<String> = std::iter::once("manifest.json".to_string())
+        .chain(files.iter().map(|(name, _)| name.clone()))
+        .collect();
+    let manifest = Manifest {
+        app_version: app.package_info().version.to_string(),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        generated_at: current_timestamp(),
+        files: names.clone(),
+        errors: errors.clone(),
+    };
+    let mut bundle = json_section("manifest.json", &manifest)?;
+    bundle.extend(files);
+
+    let bytes = write_zip(&bundle)?;
+    std::fs::write(path, &bytes)
+        .map_err(|e| AppError::Unknown(format!("Failed to write diagnostics bundle: {}", e)))?;
+
+    Ok(DiagnosticsBundleReport {
+        path: path.to_string_lossy().to_string(),
+        files: names,
+        errors,
+        size_bytes: bytes.len() as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::importers::read_zip_entries;
+    use serde_json::json;
+    use zip::ZipArchive;
+
+    #[test]
+    fn test_redact_secrets() {
+        let mut settings = json!({
+            "theme": "dark",
+            "gist_settings": { "access_token": "ghp_secret" },
+            "cloud": { "api_key": null, "accounts": [{ "password": "hunter2", "user": "me" }] }
+        });
+        redact_secrets(&mut settings);
+
+        assert_eq!(settings["theme"], "dark");
+        assert_eq!(settings["gist_settings"]["access_token"], REDACTED);
+        // Unset secrets stay visible as unset
+        assert!(settings["cloud"]["api_key"].is_null());
+        assert_eq!(settings["cloud"]["accounts"][0]["password"], REDACTED);
+        assert_eq!(settings["cloud"]["accounts"][0]["user"], "me");
+    }
+
+    #[test]
+    fn test_write_zip_round_trip() {
+        let entries = vec![
+            ("manifest.json".to_string(), b"{}".to_vec()),
+            (
+                "logs/snips.log".to_string(),
+                "line\n".repeat(1000).into_bytes(),
+            ),
+        ];
+        let archive = write_zip(&entries).unwrap();
+        assert_eq!(read_zip_entries(&archive).unwrap(), entries);
+
+        let mut archive = ZipArchive::new(Cursor::new(archive)).unwrap();
+        assert_eq!(archive.len(), 2);
+        let log = archive.by_name("logs/snips.log").unwrap();
+        assert_eq!(log.compression(), CompressionMethod::Deflated);
+        assert!(log.compressed_size() < log.size());
+    }
+
+    #[test]
+    fn test_recent_logs_keeps_tail() {
+        let dir = std::env::temp_dir().join(format!("snips_logs_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut log = String::new();
+        for i in 0..40_000 {
+            log.push_str(&format!("entry {}\n", i));
+        }
+        std::fs::write(dir.join("snips.log"), &log).unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let logs = recent_logs(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].0, "logs/snips.log");
+        let tail = String::from_utf8(logs[0].1.clone()).unwrap();
+        assert!(tail.len() as u64 <= MAX_LOG_BYTES);
+        assert!(tail.starts_with("entry "));
+        assert!(tail.ends_with("entry 39999\n"));
+
+        assert!(recent_logs(&dir).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,244 @@
+use crate::models::SnippetKind;
+use crate::utils::error::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::process::Command;
+
+/// How long a dynamic snippet's command may run before it is killed
+pub const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Maximum command output copied to the clipboard (1 MiB)
+const MAX_OUTPUT_BYTES: u64 = 1024 * 1024;
+
+/// Where dynamic snippet commands run
+///
+/// Inside a Flatpak the app's own sandbox has almost no tools, so commands are
+/// forwarded to the host with `flatpak-spawn --host`. Snap and the macOS App
+/// Sandbox offer no such escape, so commands run confined and may fail to find
+/// user tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionEnvironment {
+    Native,
+    Flatpak,
+    Snap,
+    MacSandbox,
+}
+
+impl ExecutionEnvironment {
+    /// Detects the sandbox the app is running in
+    pub fn detect() -> Self {
+        let set = |name: &str| std::env::var_os(name).is_some_and(|v| !v.is_empty());
+        if set("FLATPAK_ID") || Path::new("/.flatpak-info").exists() {
+            Self::Flatpak
+        } else if set("SNAP") {
+            Self::Snap
+        } else if set("APP_SANDBOX_CONTAINER_ID") {
+            Self::MacSandbox
+        } else {
+            Self::Native
+        }
+    }
+
+    /// Program and arguments that run `command` through a shell
+    pub fn shell_argv(self, command: &str) -> Vec<String> {
+        let mut argv = Vec::new();
+        if self == Self::Flatpak {
+            argv.extend(["flatpak-spawn".to_string(), "--host".to_string()]);
+        }
+        if cfg!(windows) {
+            argv.extend(["cmd".to_string(), "/C".to_string()]);
+        } else {
+            argv.extend(["sh".to_string(), "-c".to_string()]);
+        }
+        argv.push(command.to_string());
+        argv
+    }
+}
+
+/// A dynamic snippet's command, shown to the user for confirmation before it runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DynamicCommand {
+    pub snippet_id: i64,
+    pub name: String,
+    pub command: String,
+}
+
+/// Lists the dynamic snippets among `snippet_ids`, in the given order
+///
+/// # Errors
+///
+/// Returns `AppError` if the database query fails
+pub async fn dynamic_commands(
+    pool: &SqlitePool,
+    snippet_ids: &[i64],
+) -> Result<Vec<DynamicCommand>, AppError> {
+    let mut commands = Vec::new();
+    for &snippet_id in snippet_ids {
+        let row = sqlx::query("SELECT name, content FROM snippets WHERE id = ? AND kind = ?")
+            .bind(snippet_id)
+            .bind(SnippetKind::Dynamic.as_str())
+            .fetch_optional(pool)
+            .await?;
+
+        if let Some(row) = row {
+            commands.push(DynamicCommand {
+                snippet_id,
+                name: row.get(0),
+                command: row.get(1),
+            });
+        }
+    }
+    Ok(commands)
+}
+
+/// Runs a dynamic snippet's command and returns its output
+///
+/// The command runs through `sh -c` (`cmd /C` on Windows) in the user's home
+/// directory with no stdin. A single trailing newline is stripped so e.g.
+/// `date +%F` pastes inline.
+///
+/// # Errors
+///
+/// - `AppError::External` if the command cannot start, exits non-zero,
+///   exceeds `timeout` or prints non-UTF-8 output
+/// - `AppError::Validation` if the output exceeds 1 MiB
+pub async fn run_command(command: &str, timeout: Duration) -> Result<String, AppError> {
+    let argv = ExecutionEnvironment::detect().shell_argv(command);
+    let mut cmd = Command::new(&argv[0]);
+    cmd.args(&argv[1..])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(home) = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")) {
+        cmd.current_dir(home);
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| AppError::External(format!("Failed to run {}: {}", argv[0], e)))?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| AppError::Unknown("Command stdout was not captured".to_string()))?;
+    let stderr = child
+        .stderr
+        .take()
+        .ok_or_else(|| AppError::Unknown("Command stderr was not captured".to_string()))?;
+
+    let run = async {
+        let mut output = Vec::new();
+        let mut errors = Vec::new();
+        // Read one byte past the limit to detect oversized output
+        let mut stdout = stdout.take(MAX_OUTPUT_BYTES + 1);
+        let mut stderr = stderr.take(MAX_OUTPUT_BYTES);
+        let (read_out, read_err) = tokio::join!(
+            stdout.read_to_end(&mut output),
+            stderr.read_to_end(&mut errors),
+        );
+        read_out?;
+        read_err?;
+        let status = child.wait().await?;
+        Ok::<_, std::io::Error>((status, output, errors))
+    };
+
+    // Dropping the future on timeout kills the child (kill_on_drop)
+    let (status, output, errors) = tokio::time::timeout(timeout, run)
+        .await
+        .map_err(|_| AppError::External(format!("Command timed out after {:?}", timeout)))?
+        .map_err(|e| AppError::External(format!("Failed to read command output: {}", e)))?;
+
+    if output.len() as u64 > MAX_OUTPUT_BYTES {
+        return Err(AppError::Validation(
+            "Command output exceeds 1 MiB".to_string(),
+        ));
+    }
+    if !status.success() {
+        return Err(AppError::External(format!(
+            "Command exited with {}: {}",
+            status,
+            String::from_utf8_lossy(&errors).trim()
+        )));
+    }
+
+    let mut output = String::from_utf8(output)
+        .map_err(|_| AppError::External("Command output is not valid UTF-8".to_string()))?;
+    if output.ends_with('\n') {
+        output.pop();
+        if output.ends_with('\r') {
+            output.pop();
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[test]
+    fn test_shell_argv() {
+        let native = ExecutionEnvironment::Native.shell_argv("git branch --show-current");
+        assert_eq!(native.last().unwrap(), "git branch --show-current");
+        assert_ne!(native[0], "flatpak-spawn");
+
+        let flatpak = ExecutionEnvironment::Flatpak.shell_argv("date");
+        assert_eq!(&flatpak[..2], ["flatpak-spawn", "--host"]);
+        assert_eq!(flatpak.last().unwrap(), "date");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_command() {
+        let output = run_command("printf 'a\\nb\\n'", COMMAND_TIMEOUT)
+            .await
+            .unwrap();
+        assert_eq!(output, "a\nb");
+
+        let failed = run_command("echo oops >&2; exit 3", COMMAND_TIMEOUT).await;
+        assert!(matches!(failed, Err(AppError::External(msg)) if msg.contains("oops")));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_command_timeout() {
+        let started = std::time::Instant::now();
+        let result = run_command("sleep 5", Duration::from_millis(200)).await;
+        assert!(matches!(result, Err(AppError::External(msg)) if msg.contains("timed out")));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_dynamic_commands() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE snippets (id INTEGER PRIMARY KEY, name TEXT, content TEXT,
+                kind TEXT NOT NULL DEFAULT 'static')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO snippets VALUES (1, 'branch', 'git branch --show-current', 'dynamic'),
+                (2, 'greeting', 'Hello', 'static'), (3, 'date', 'date +%F', 'dynamic')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let commands = dynamic_commands(&pool, &[3, 2, 1, 99]).await.unwrap();
+        let names: Vec<&str> = commands.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["date", "branch"]);
+        assert_eq!(commands[0].command, "date +%F");
+    }
+}
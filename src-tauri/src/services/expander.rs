@@ -0,0 +1,1166 @@
+/// Abbreviation text expansion.
+///
+/// While expansion is on, typing a snippet's trigger such as `;sig` in any
+/// application replaces it with the snippet: the snippet is copied, the
+/// trigger is erased with backspaces and the clipboard is pasted over it.
+///
+/// Typing is watched through `xinput` on X11 and a listen-only Quartz event
+/// tap on macOS. Only the last few characters typed are kept, in memory, to
+/// match triggers against. Wayland compositors do not let applications see
+/// typing in other windows, so expansion is unavailable there.
+///
+/// Nothing is pasted while macOS secure input is on (a password field is
+/// focused) or into an application on the paste blocklist; the snippet is
+/// then only copied. Dynamic snippets never expand, since their command may
+/// only run once the user has confirmed it.
+use crate::commands::analytics_commands::copy_snippets_with_analytics;
+use crate::models::analytics::UsageSource;
+use crate::models::settings::ExpansionSettings;
+use crate::models::SnippetKind;
+use crate::services::database::get_pool;
+use crate::services::focus::{self, FocusBackend, PreviousWindow};
+use crate::services::notifications::{notify, NotificationKind};
+use crate::services::settings::SettingsService;
+use crate::utils::error::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::{mpsc, Mutex};
+
+/// Shortest trigger, in characters
+pub const MIN_TRIGGER_CHARS: usize = 2;
+
+/// Longest trigger, in characters
+pub const MAX_TRIGGER_CHARS: usize = 32;
+
+/// Time the trigger's last key gets to reach the application before it is erased
+const KEY_SETTLE: Duration = Duration::from_millis(50);
+
+/// A snippet's trigger
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpansionTrigger {
+    pub snippet_id: i64,
+    pub snippet_name: String,
+    pub trigger: String,
+}
+
+/// Whether text expansion is running
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpanderStatus {
+    pub running: bool,
+    /// Why expansion could not start (e.g. a Wayland session or a missing permission)
+    pub error: Option<String>,
+}
+
+/// A key seen by a watcher, reduced to what matters for matching triggers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypedKey {
+    /// A printable character
+    Char(char),
+    Backspace,
+    /// Anything that may move the caret: Enter, arrows, shortcuts, clicks
+    Reset,
+}
+
+/// A trigger that was just typed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TriggerMatch {
+    pub snippet_id: i64,
+    /// Characters to erase
+    pub chars: usize,
+}
+
+/// Matches typed keys against the triggers
+///
+/// Keeps no more characters than the longest trigger has.
+#[derive(Debug, Default)]
+pub struct TriggerMatcher {
+    triggers: HashMap<String, i64>,
+    longest: usize,
+    typed: Vec<char>,
+}
+
+impl TriggerMatcher {
+    /// Matcher for triggers mapped to their snippets
+    pub fn new(triggers: HashMap<String, i64>) -> Self {
+        let longest = triggers
+            .keys()
+            .map(|t| t.chars().count())
+            .max()
+            .unwrap_or(0);
+        Self {
+            triggers,
+            longest,
+            typed: Vec::new(),
+        }
+    }
+
+    /// Takes the next key
+    ///
+    /// # Returns
+    ///
+    /// The trigger the key completed, if any; the typed characters are
+    /// forgotten once a trigger matches
+    pub fn feed(&mut self, key: TypedKey) -> Option<TriggerMatch> {
+        match key {
+            TypedKey::Char(c) => self.typed.push(c),
+            TypedKey::Backspace => {
+                self.typed.pop();
+                return None;
+            }
+            TypedKey::Reset => {
+                self.typed.clear();
+                return None;
+            }
+        }
+        if self.typed.len() > self.longest {
+            self.typed.drain(..self.typed.len() - self.longest);
+        }
+
+        // Triggers cannot contain one another, so at most one ends here
+        let found = (1..=self.typed.len()).find_map(|chars| {
+            let suffix: String = self.typed[self.typed.len() - chars..].iter().collect();
+            self.triggers
+                .get(&suffix)
+                .map(|&snippet_id| TriggerMatch { snippet_id, chars })
+        })?;
+        self.typed.clear();
+        Some(found)
+    }
+}
+
+/// Checks that a trigger can be typed and recognized
+///
+/// # Errors
+///
+/// Returns `AppError::Validation` if the trigger is too short or too long,
+/// or contains whitespace or control characters
+pub fn validate_trigger(trigger: &str) -> Result<(), AppError> {
+    let chars = trigger.chars().count();
+    if !(MIN_TRIGGER_CHARS..=MAX_TRIGGER_CHARS).contains(&chars) {
+        return Err(AppError::Validation(format!(
+            "A trigger must be {} to {} characters long",
+            MIN_TRIGGER_CHARS, MAX_TRIGGER_CHARS
+        )));
+    }
+    if trigger.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(AppError::Validation(format!(
+            "Trigger \"{}\" cannot contain spaces or control characters",
+            trigger
+        )));
+    }
+    Ok(())
+}
+
+/// Lists the triggers of snippets that can expand, by trigger
+///
+/// Archived and dynamic snippets are left out; they keep their trigger but
+/// do not expand.
+pub async fn list_triggers(pool: &SqlitePool) -> Result<Vec<ExpansionTrigger>, AppError> {
+    let rows: Vec<(i64, String, String)> = sqlx::query_as(
+        "SELECT id, name, expansion_trigger FROM snippets
+         WHERE expansion_trigger IS NOT NULL AND archived = 0 AND kind = 'static'
+         ORDER BY expansion_trigger",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(snippet_id, snippet_name, trigger)| ExpansionTrigger {
+            snippet_id,
+            snippet_name,
+            trigger,
+        })
+        .collect())
+}
+
+/// Sets or removes the trigger of a snippet
+///
+/// A blank trigger removes it. Since a trigger fires as soon as it is typed,
+/// one that contains another could never fire and is refused. Dynamic
+/// snippets cannot have a trigger: typing it would run their command without
+/// the user confirming it.
+///
+/// # Returns
+///
+/// The trigger as stored
+///
+/// # Errors
+///
+/// * `AppError::Validation` - The trigger is malformed or overlaps another,
+///   or the snippet is dynamic
+/// * `AppError::Duplicate` - Another snippet has the trigger
+/// * `AppError::NotFound` - The snippet does not exist
+pub async fn set_trigger(
+    pool: &SqlitePool,
+    snippet_id: i64,
+    trigger: Option<&str>,
+) -> Result<Option<String>, AppError> {
+    let kind: Option<String> = sqlx::query_scalar("SELECT kind FROM snippets WHERE id = ?")
+        .bind(snippet_id)
+        .fetch_optional(pool)
+        .await?;
+    let Some(kind) = kind else {
+        return Err(AppError::NotFound(format!(
+            "Snippet with id {} not found",
+            snippet_id
+        )));
+    };
+
+    let trigger = trigger.map(str::trim).filter(|t| !t.is_empty());
+    if let Some(trigger) = trigger {
+        validate_trigger(trigger)?;
+        if SnippetKind::from_db(&kind) == SnippetKind::Dynamic {
+            return Err(AppError::Validation(
+                "Dynamic snippets run a command that must be confirmed, so they cannot have a \
+                 trigger"
+                    .to_string(),
+            ));
+        }
+
+        let others: Vec<(i64, String)> = sqlx::query_as(
+            "SELECT id, expansion_trigger FROM snippets
+             WHERE expansion_trigger IS NOT NULL AND id != ?",
+        )
+        .bind(snippet_id)
+        .fetch_all(pool)
+        .await?;
+        for (other_id, other) in others {
+            if other == trigger {
+                return Err(AppError::Duplicate(format!(
+                    "Trigger \"{}\" belongs to snippet {}",
+                    trigger, other_id
+                )));
+            }
+            if other.contains(trigger) || trigger.contains(other.as_str()) {
+                return Err(AppError::Validation(format!(
+                    "Trigger \"{}\" overlaps \"{}\" of snippet {}",
+                    trigger, other, other_id
+                )));
+            }
+        }
+    }
+
+    sqlx::query("UPDATE snippets SET expansion_trigger = ? WHERE id = ?")
+        .bind(trigger)
+        .bind(snippet_id)
+        .execute(pool)
+        .await?;
+    Ok(trigger.map(str::to_string))
+}
+
+async fn load_triggers(pool: &SqlitePool) -> Result<HashMap<String, i64>, AppError> {
+    Ok(list_triggers(pool)
+        .await?
+        .into_iter()
+        .map(|t| (t.trigger, t.snippet_id))
+        .collect())
+}
+
+enum WatchEvent {
+    Key(TypedKey),
+    Triggers(HashMap<String, i64>),
+}
+
+/// Stops a watcher
+type StopWatching = Box<dyn FnOnce() + Send>;
+
+struct Watcher {
+    events: mpsc::UnboundedSender<WatchEvent>,
+    stop: StopWatching,
+}
+
+#[derive(Default)]
+struct ExpanderHandle {
+    running: Option<Watcher>,
+    last_error: Option<String>,
+}
+
+/// State holding the running keyboard watcher, if any
+#[derive(Default)]
+pub struct ExpanderState(Mutex<ExpanderHandle>);
+
+/// Starts or stops expansion to match the settings
+///
+/// Like the HTTP API server, failures to start are reported through
+/// `status` rather than returned, so saving settings never fails because
+/// the session cannot watch typing.
+pub async fn apply_settings(app: &AppHandle, settings: &ExpansionSettings) {
+    let Some(state) = app.try_state::<ExpanderState>() else {
+        return;
+    };
+    let mut handle = state.0.lock().await;
+    if handle.running.is_some() == settings.enabled {
+        return;
+    }
+
+    if let Some(watcher) = handle.running.take() {
+        (watcher.stop)();
+    }
+    handle.last_error = None;
+
+    if !settings.enabled {
+        return;
+    }
+    match start(app).await {
+        Ok(watcher) => handle.running = Some(watcher),
+        Err(e) => {
+            let message = format!("Failed to start text expansion: {}", e);
+            eprintln!("{}", message);
+            handle.last_error = Some(message);
+        }
+    }
+}
+
+/// Reports whether expansion is running
+pub async fn status(app: &AppHandle) -> ExpanderStatus {
+    let Some(state) = app.try_state::<ExpanderState>() else {
+        return ExpanderStatus {
+            running: false,
+            error: None,
+        };
+    };
+    let handle = state.0.lock().await;
+    ExpanderStatus {
+        running: handle.running.is_some(),
+        error: handle.last_error.clone(),
+    }
+}
+
+/// Hands the current triggers to the running watcher, after they changed
+pub async fn reload_triggers(app: &AppHandle) -> Result<(), AppError> {
+    let Some(state) = app.try_state::<ExpanderState>() else {
+        return Ok(());
+    };
+    let handle = state.0.lock().await;
+    if let Some(watcher) = &handle.running {
+        let triggers = load_triggers(&get_pool(app)?).await?;
+        let _ = watcher.events.send(WatchEvent::Triggers(triggers));
+    }
+    Ok(())
+}
+
+/// Session that typing can be watched and replaced in
+///
+/// XWayland only sees typing in X11 windows, so Wayland sessions are
+/// unsupported even when `DISPLAY` is set.
+fn expansion_backend() -> Result<FocusBackend, AppError> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some_and(|v| !v.is_empty());
+    match FocusBackend::detect() {
+        FocusBackend::MacOs => Ok(FocusBackend::MacOs),
+        FocusBackend::X11 if !wayland => Ok(FocusBackend::X11),
+        _ => Err(AppError::Unsupported(
+            "Text expansion needs an X11 session or macOS".to_string(),
+        )),
+    }
+}
+
+async fn start(app: &AppHandle) -> Result<Watcher, AppError> {
+    let backend = expansion_backend()?;
+    let triggers = load_triggers(&get_pool(app)?).await?;
+
+    let (events, received) = mpsc::unbounded_channel();
+    let stop = match backend {
+        FocusBackend::MacOs => quartz::watch(events.clone())?,
+        _ => watch_x11(events.clone())?,
+    };
+    let _ = events.send(WatchEvent::Triggers(triggers));
+    tauri::async_runtime::spawn(expand_triggers(app.clone(), backend, received));
+    Ok(Watcher { events, stop })
+}
+
+/// Matches watched keys until the watcher stops
+async fn expand_triggers(
+    app: AppHandle,
+    backend: FocusBackend,
+    mut events: mpsc::UnboundedReceiver<WatchEvent>,
+) {
+    let mut matcher = TriggerMatcher::default();
+    while let Some(event) = events.recv().await {
+        match event {
+            WatchEvent::Triggers(triggers) => matcher = TriggerMatcher::new(triggers),
+            WatchEvent::Key(key) => {
+                if let Some(found) = matcher.feed(key) {
+                    if let Err(e) = expand(&app, backend, found).await {
+                        eprintln!("Failed to expand snippet {}: {}", found.snippet_id, e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Replaces a typed trigger with its snippet
+///
+/// The snippet is copied first, so a snippet that cannot be copied leaves
+/// the trigger as typed. The snippet stays on the clipboard afterwards, and
+/// is all that happens when pasting is refused.
+async fn expand(app: &AppHandle, backend: FocusBackend, found: TriggerMatch) -> Result<(), String> {
+    tokio::time::sleep(KEY_SETTLE).await;
+    // Dynamic snippets are never confirmed here, so copying one fails
+    // without running its command
+    copy_snippets_with_analytics(
+        app.clone(),
+        vec![found.snippet_id],
+        Some(false),
+        Some(UsageSource::Expansion),
+        None,
+        None,
+        None,
+    )
+    .await?;
+
+    let blocklist = SettingsService::new(get_pool(app)?)
+        .get_settings()
+        .await?
+        .expansion_settings
+        .paste_blocklist;
+    let refused =
+        tauri::async_runtime::spawn_blocking(move || match paste_refusal(backend, &blocklist) {
+            Some(reason) => Ok(Some(reason)),
+            None => erase_and_paste(backend, found.chars).map(|()| None),
+        })
+        .await
+        .map_err(|e| format!("Failed to paste: {}", e))??;
+
+    if let Some(reason) = refused {
+        notify(
+            app,
+            NotificationKind::SnippetCopied,
+            format!("Copied but not pasted: {}", reason),
+        );
+    }
+    Ok(())
+}
+
+/// Why the snippet must not be pasted into the focused application, if so
+///
+/// macOS turns on secure input while a password field has focus. Linux has
+/// no such signal, so there the blocklist is the only guard. When the
+/// focused application cannot be told, nothing is pasted.
+fn paste_refusal(backend: FocusBackend, blocklist: &[String]) -> Option<String> {
+    if quartz::secure_input_enabled() {
+        return Some("a password field has focus".to_string());
+    }
+    match focus::active_window(backend) {
+        Ok(Some(window)) => {
+            blocked_app(&window, blocklist).map(|app| format!("{} is on the paste blocklist", app))
+        }
+        Ok(None) | Err(_) => Some("the focused application is unknown".to_string()),
+    }
+}
+
+/// The blocklist entry matching the window's application name or, on macOS,
+/// its bundle identifier
+fn blocked_app<'a>(window: &PreviousWindow, blocklist: &'a [String]) -> Option<&'a str> {
+    let names = [window.app.as_deref(), Some(window.handle.as_str())];
+    blocklist
+        .iter()
+        .find(|entry| {
+            names
+                .iter()
+                .flatten()
+                .any(|name| name.eq_ignore_ascii_case(entry.trim()))
+        })
+        .map(String::as_str)
+}
+
+/// Sends `chars` backspaces followed by the paste shortcut
+///
+/// The paste shortcut is Ctrl+V on X11, which terminals bind to something
+/// else; expansion does not work in them.
+fn erase_and_paste(backend: FocusBackend, chars: usize) -> Result<(), AppError> {
+    match backend {
+        FocusBackend::MacOs => {
+            let script = format!(
+                "tell application \"System Events\"
+                    repeat {} times
+                        key code 51
+                    end repeat
+                    keystroke \"v\" using {{command down}}
+                end tell",
+                chars
+            );
+            focus::run("osascript", &["-e", &script])?;
+        }
+        _ => {
+            let mut args = vec!["key", "--clearmodifiers"];
+            for _ in 0..chars {
+                args.push("BackSpace");
+            }
+            args.push("ctrl+v");
+            focus::run("xdotool", &args)?;
+        }
+    }
+    Ok(())
+}
+
+/// Raw input event printed by `xinput test-xi2`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RawEvent {
+    KeyPress(u32),
+    KeyRelease(u32),
+    ButtonPress,
+}
+
+/// Reads raw events from `xinput test-xi2 --root` output, line by line
+///
+/// Raw events arrive for the physical device and again for the virtual
+/// master device it is attached to; only the physical ones are kept.
+#[derive(Debug, Default)]
+struct XinputParser {
+    event_type: Option<u32>,
+    from_physical: bool,
+}
+
+impl XinputParser {
+    fn feed(&mut self, line: &str) -> Option<RawEvent> {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("EVENT type ") {
+            self.event_type = rest.split_whitespace().next()?.parse().ok();
+            self.from_physical = false;
+        } else if let Some(rest) = line.strip_prefix("device: ") {
+            // "device: 11 (11)" is the device id, then the source device id
+            let (device, source) = rest.split_once(' ')?;
+            self.from_physical = source.trim_matches(|c| c == '(' || c == ')') == device;
+        } else if let Some(detail) = line.strip_prefix("detail: ") {
+            let event_type = self.event_type.take()?;
+            if !self.from_physical {
+                return None;
+            }
+            let detail = detail.parse().ok()?;
+            return match event_type {
+                13 => Some(RawEvent::KeyPress(detail)),
+                14 => Some(RawEvent::KeyRelease(detail)),
+                15 => Some(RawEvent::ButtonPress),
+                _ => None,
+            };
+        }
+        None
+    }
+}
+
+/// Parses `xmodmap -pke` into the unshifted and shifted keysym names of each keycode
+fn parse_xmodmap(output: &str) -> HashMap<u32, (String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (code, keysyms) = line.strip_prefix("keycode")?.split_once('=')?;
+            let mut keysyms = keysyms.split_whitespace();
+            let plain = keysyms.next()?.to_string();
+            let shifted = keysyms
+                .next()
+                .filter(|k| *k != "NoSymbol")
+                .map_or_else(|| plain.clone(), str::to_string);
+            Some((code.trim().parse().ok()?, (plain, shifted)))
+        })
+        .collect()
+}
+
+/// Character typed by a keysym, for the keysyms a trigger can contain
+fn keysym_char(name: &str) -> Option<char> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(c);
+    }
+    if let Some(hex) = name.strip_prefix('U').filter(|hex| hex.len() >= 4) {
+        return u32::from_str_radix(hex, 16).ok().and_then(char::from_u32);
+    }
+    Some(match name {
+        "space" => ' ',
+        "exclam" => '!',
+        "quotedbl" => '"',
+        "numbersign" => '#',
+        "dollar" => '$',
+        "percent" => '%',
+        "ampersand" => '&',
+        "apostrophe" => '\'',
+        "parenleft" => '(',
+        "parenright" => ')',
+        "asterisk" => '*',
+        "plus" => '+',
+        "comma" => ',',
+        "minus" => '-',
+        "period" => '.',
+        "slash" => '/',
+        "colon" => ':',
+        "semicolon" => ';',
+        "less" => '<',
+        "equal" => '=',
+        "greater" => '>',
+        "question" => '?',
+        "at" => '@',
+        "bracketleft" => '[',
+        "backslash" => '\\',
+        "bracketright" => ']',
+        "asciicircum" => '^',
+        "underscore" => '_',
+        "grave" => '`',
+        "braceleft" => '{',
+        "bar" => '|',
+        "braceright" => '}',
+        "asciitilde" => '~',
+        _ => return None,
+    })
+}
+
+/// Turns raw X11 key events into typed keys, following Shift and Caps Lock
+#[derive(Debug, Default)]
+struct X11Keyboard {
+    keymap: HashMap<u32, (String, String)>,
+    shift_held: HashSet<u32>,
+    /// Control, Alt, Super and AltGr keys held down
+    modifiers_held: HashSet<u32>,
+    caps_lock: bool,
+}
+
+impl X11Keyboard {
+    fn new(keymap: HashMap<u32, (String, String)>) -> Self {
+        Self {
+            keymap,
+            ..Self::default()
+        }
+    }
+
+    fn key(&mut self, event: RawEvent) -> Option<TypedKey> {
+        let code = match event {
+            RawEvent::ButtonPress => return Some(TypedKey::Reset),
+            RawEvent::KeyRelease(code) => {
+                self.shift_held.remove(&code);
+                self.modifiers_held.remove(&code);
+                return None;
+            }
+            RawEvent::KeyPress(code) => code,
+        };
+
+        let Some((plain, shifted)) = self.keymap.get(&code) else {
+            return Some(TypedKey::Reset);
+        };
+        if plain.starts_with("Shift_") {
+            self.shift_held.insert(code);
+            return None;
+        }
+        if ["Control_", "Alt_", "Meta_", "Super_", "Hyper_"]
+            .iter()
+            .any(|prefix| plain.starts_with(prefix))
+            || plain == "ISO_Level3_Shift"
+            || plain == "Mode_switch"
+        {
+            self.modifiers_held.insert(code);
+            return None;
+        }
+        if plain == "Caps_Lock" {
+            self.caps_lock = !self.caps_lock;
+            return None;
+        }
+        if !self.modifiers_held.is_empty() {
+            return Some(TypedKey::Reset);
+        }
+        if plain == "BackSpace" {
+            return Some(TypedKey::Backspace);
+        }
+
+        let name = if self.shift_held.is_empty() {
+            plain
+        } else {
+            shifted
+        };
+        let Some(c) = keysym_char(name) else {
+            return Some(TypedKey::Reset);
+        };
+        if self.caps_lock && c.is_alphabetic() {
+            let flipped = if c.is_lowercase() {
+                c.to_uppercase().next()
+            } else {
+                c.to_lowercase().next()
+            };
+            return Some(TypedKey::Char(flipped.unwrap_or(c)));
+        }
+        Some(TypedKey::Char(c))
+    }
+}
+
+/// Watches typing on X11 through `xinput test-xi2`
+///
+/// Raw events reach the root window whichever window has focus. They carry
+/// keycodes only, so the keymap comes from `xmodmap`.
+fn watch_x11(events: mpsc::UnboundedSender<WatchEvent>) -> Result<StopWatching, AppError> {
+    let keymap = parse_xmodmap(&focus::run("xmodmap", &["-pke"])?);
+    let mut child = Command::new("xinput")
+        .args(["test-xi2", "--root"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| AppError::External(format!("Failed to run xinput: {}", e)))?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| AppError::External("xinput has no output".to_string()))?;
+
+    std::thread::spawn(move || {
+        let mut parser = XinputParser::default();
+        let mut keyboard = X11Keyboard::new(keymap);
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            let Some(key) = parser.feed(&line).and_then(|event| keyboard.key(event)) else {
+                continue;
+            };
+            if events.send(WatchEvent::Key(key)).is_err() {
+                break;
+            }
+        }
+    });
+
+    Ok(Box::new(move || {
+        let _ = child.kill();
+        let _ = child.wait();
+    }))
+}
+
+/// Listen-only Quartz event tap.
+///
+/// Needs the Input Monitoring permission; without it the tap cannot be
+/// created. Pasting through System Events needs Accessibility as well.
+#[cfg(target_os = "macos")]
+mod quartz {
+    use super::{StopWatching, TypedKey, WatchEvent};
+    use crate::utils::error::AppError;
+    use std::os::raw::c_void;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use tokio::sync::mpsc;
+
+    type TapCallback = extern "C" fn(*mut c_void, u32, *mut c_void, *mut c_void) -> *mut c_void;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventTapCreate(
+            tap: u32,
+            place: u32,
+            options: u32,
+            events_of_interest: u64,
+            callback: TapCallback,
+            user_info: *mut c_void,
+        ) -> *mut c_void;
+        fn CGEventTapEnable(tap: *mut c_void, enable: bool);
+        fn CGEventGetFlags(event: *mut c_void) -> u64;
+        fn CGEventGetIntegerValueField(event: *mut c_void, field: u32) -> i64;
+        fn CGEventKeyboardGetUnicodeString(
+            event: *mut c_void,
+            max_length: usize,
+            actual_length: *mut usize,
+            buffer: *mut u16,
+        );
+    }
+
+    #[link(name = "Carbon", kind = "framework")]
+    extern "C" {
+        fn IsSecureEventInputEnabled() -> u8;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        static kCFRunLoopDefaultMode: *const c_void;
+        fn CFMachPortCreateRunLoopSource(
+            allocator: *const c_void,
+            port: *mut c_void,
+            order: isize,
+        ) -> *mut c_void;
+        fn CFRunLoopGetCurrent() -> *mut c_void;
+        fn CFRunLoopAddSource(run_loop: *mut c_void, source: *mut c_void, mode: *const c_void);
+        fn CFRunLoopRunInMode(mode: *const c_void, seconds: f64, return_after_source: u8) -> i32;
+        fn CFRelease(object: *const c_void);
+    }
+
+    const SESSION_EVENT_TAP: u32 = 1;
+    const HEAD_INSERT_EVENT_TAP: u32 = 0;
+    const LISTEN_ONLY: u32 = 1;
+
+    const LEFT_MOUSE_DOWN: u32 = 1;
+    const RIGHT_MOUSE_DOWN: u32 = 3;
+    const KEY_DOWN: u32 = 10;
+    const TAP_DISABLED_BY_TIMEOUT: u32 = 0xFFFF_FFFE;
+    const TAP_DISABLED_BY_USER_INPUT: u32 = 0xFFFF_FFFF;
+
+    const KEYBOARD_EVENT_KEYCODE: u32 = 9;
+    const DELETE_KEYCODE: i64 = 51;
+    /// Control, Option and Command
+    const SHORTCUT_FLAGS: u64 = 0x0004_0000 | 0x0008_0000 | 0x0010_0000;
+
+    /// How often the watcher thread checks whether it should stop
+    const STOP_POLL_SECONDS: f64 = 0.25;
+
+    struct TapContext {
+        events: mpsc::UnboundedSender<WatchEvent>,
+        tap: *mut c_void,
+    }
+
+    /// # Safety
+    ///
+    /// `event` must be a live keyboard event
+    unsafe fn typed_key(event: *mut c_void) -> TypedKey {
+        if CGEventGetFlags(event) & SHORTCUT_FLAGS != 0 {
+            return TypedKey::Reset;
+        }
+        if CGEventGetIntegerValueField(event, KEYBOARD_EVENT_KEYCODE) == DELETE_KEYCODE {
+            return TypedKey::Backspace;
+        }
+        let mut buffer = [0u16; 4];
+        let mut length = 0;
+        CGEventKeyboardGetUnicodeString(event, buffer.len(), &mut length, buffer.as_mut_ptr());
+        let mut chars = char::decode_utf16(buffer[..length.min(buffer.len())].iter().copied());
+        match (chars.next(), chars.next()) {
+            (Some(Ok(c)), None) if !c.is_control() => TypedKey::Char(c),
+            _ => TypedKey::Reset,
+        }
+    }
+
+    extern "C" fn on_event(
+        _proxy: *mut c_void,
+        event_type: u32,
+        event: *mut c_void,
+        user_info: *mut c_void,
+    ) -> *mut c_void {
+        // SAFETY: `user_info` is the context the watcher thread owns; it is
+        // freed only after the thread's run loop has stopped calling us
+        let context = unsafe { &*(user_info as *const TapContext) };
+        let key = match event_type {
+            TAP_DISABLED_BY_TIMEOUT | TAP_DISABLED_BY_USER_INPUT => {
+                // SAFETY: the tap outlives its run loop source
+                unsafe { CGEventTapEnable(context.tap, true) };
+                return event;
+            }
+            // SAFETY: the tap only delivers live events
+            KEY_DOWN => unsafe { typed_key(event) },
+            _ => TypedKey::Reset,
+        };
+        let _ = context.events.send(WatchEvent::Key(key));
+        event
+    }
+
+    /// Whether secure input is on, as it is while a password field has focus
+    pub(super) fn secure_input_enabled() -> bool {
+        // SAFETY: takes no arguments and only reads session state
+        unsafe { IsSecureEventInputEnabled() != 0 }
+    }
+
+    pub(super) fn watch(
+        events: mpsc::UnboundedSender<WatchEvent>,
+    ) -> Result<StopWatching, AppError> {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let (started, starting) = std::sync::mpsc::channel();
+
+        let stop_flag = stopped.clone();
+        std::thread::spawn(move || {
+            let mask = (1u64 << KEY_DOWN) | (1u64 << LEFT_MOUSE_DOWN) | (1u64 << RIGHT_MOUSE_DOWN);
+            let context = Box::into_raw(Box::new(TapContext {
+                events,
+                tap: std::ptr::null_mut(),
+            }));
+
+            // SAFETY: the context stays allocated until the tap and its run
+            // loop source are released; every object is checked for null
+            // and released once
+            unsafe {
+                let tap = CGEventTapCreate(
+                    SESSION_EVENT_TAP,
+                    HEAD_INSERT_EVENT_TAP,
+                    LISTEN_ONLY,
+                    mask,
+                    on_event,
+                    context.cast(),
+                );
+                if tap.is_null() {
+                    drop(Box::from_raw(context));
+                    let _ = started.send(Err(AppError::Forbidden(
+                        "Allow Snips under Input Monitoring in System Settings".to_string(),
+                    )));
+                    return;
+                }
+                (*context).tap = tap;
+
+                let source = CFMachPortCreateRunLoopSource(std::ptr::null(), tap, 0);
+                if source.is_null() {
+                    CFRelease(tap);
+                    drop(Box::from_raw(context));
+                    let _ = started.send(Err(AppError::External(
+                        "Failed to watch the event tap".to_string(),
+                    )));
+                    return;
+                }
+                CFRunLoopAddSource(CFRunLoopGetCurrent(), source, kCFRunLoopDefaultMode);
+                let _ = started.send(Ok(()));
+
+                while !stop_flag.load(Ordering::Relaxed) {
+                    CFRunLoopRunInMode(kCFRunLoopDefaultMode, STOP_POLL_SECONDS, 0);
+                }
+
+                CGEventTapEnable(tap, false);
+                CFRelease(source);
+                CFRelease(tap);
+                drop(Box::from_raw(context));
+            }
+        });
+
+        starting
+            .recv()
+            .map_err(|_| AppError::Unknown("Keyboard watcher exited".to_string()))??;
+        Ok(Box::new(move || stopped.store(true, Ordering::Relaxed)))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod quartz {
+    use super::{StopWatching, WatchEvent};
+    use crate::utils::error::AppError;
+    use tokio::sync::mpsc;
+
+    pub(super) fn secure_input_enabled() -> bool {
+        false
+    }
+
+    pub(super) fn watch(
+        _events: mpsc::UnboundedSender<WatchEvent>,
+    ) -> Result<StopWatching, AppError> {
+        Err(AppError::Unsupported(
+            "Quartz event taps are only available on macOS".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::run_migrations;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    fn type_text(matcher: &mut TriggerMatcher, text: &str) -> Option<TriggerMatch> {
+        text.chars()
+            .map(|c| matcher.feed(TypedKey::Char(c)))
+            .fold(None, |found, next| next.or(found))
+    }
+
+    #[test]
+    fn test_trigger_matcher() {
+        let mut matcher = TriggerMatcher::new(HashMap::from([
+            (";sig".to_string(), 1),
+            ("@@".to_string(), 2),
+        ]));
+
+        assert_eq!(
+            type_text(&mut matcher, "Best, ;sig"),
+            Some(TriggerMatch {
+                snippet_id: 1,
+                chars: 4
+            })
+        );
+
+        // Corrections count, moving the caret does not
+        type_text(&mut matcher, ";sx");
+        matcher.feed(TypedKey::Backspace);
+        matcher.feed(TypedKey::Backspace);
+        assert_eq!(
+            type_text(&mut matcher, "sig").map(|m| m.snippet_id),
+            Some(1)
+        );
+        type_text(&mut matcher, ";s");
+        matcher.feed(TypedKey::Reset);
+        assert_eq!(type_text(&mut matcher, "ig"), None);
+
+        // Typed characters are forgotten once a trigger matches
+        assert_eq!(type_text(&mut matcher, "@@").map(|m| m.chars), Some(2));
+        assert_eq!(type_text(&mut matcher, "@"), None);
+        assert_eq!(type_text(&mut matcher, "@").map(|m| m.snippet_id), Some(2));
+
+        assert_eq!(type_text(&mut TriggerMatcher::default(), ";sig"), None);
+    }
+
+    #[test]
+    fn test_blocked_app() {
+        let blocklist = vec![
+            "KeePassXC".to_string(),
+            "com.1password.1password".to_string(),
+        ];
+        let window = |handle: &str, app: Option<&str>| PreviousWindow {
+            backend: FocusBackend::X11,
+            handle: handle.to_string(),
+            title: None,
+            pid: None,
+            app: app.map(str::to_string),
+        };
+
+        assert_eq!(
+            blocked_app(&window("62914567", Some("keepassxc")), &blocklist),
+            Some("KeePassXC")
+        );
+        assert_eq!(
+            blocked_app(
+                &window("com.1password.1password", Some("1Password")),
+                &blocklist
+            ),
+            Some("com.1password.1password")
+        );
+        assert_eq!(
+            blocked_app(&window("62914567", Some("firefox")), &blocklist),
+            None
+        );
+        assert_eq!(blocked_app(&window("62914567", None), &blocklist), None);
+    }
+
+    #[test]
+    fn test_validate_trigger() {
+        assert!(validate_trigger(";sig").is_ok());
+        assert!(validate_trigger(";").is_err());
+        assert!(validate_trigger(&"x".repeat(MAX_TRIGGER_CHARS + 1)).is_err());
+        assert!(matches!(
+            validate_trigger(";my sig"),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_xinput_parser() {
+        let output = "\
+EVENT type 13 (RawKeyPress)
+    device: 3 (11)
+    detail: 47
+    valuators:
+EVENT type 13 (RawKeyPress)
+    device: 11 (11)
+    detail: 47
+    valuators:
+EVENT type 14 (RawKeyRelease)
+    device: 11 (11)
+    detail: 47
+EVENT type 17 (RawMotion)
+    device: 12 (12)
+    detail: 0
+EVENT type 15 (RawButtonPress)
+    device: 12 (12)
+    detail: 1";
+        let mut parser = XinputParser::default();
+        let events: Vec<RawEvent> = output.lines().filter_map(|l| parser.feed(l)).collect();
+        assert_eq!(
+            events,
+            vec![
+                RawEvent::KeyPress(47),
+                RawEvent::KeyRelease(47),
+                RawEvent::ButtonPress
+            ]
+        );
+    }
+
+    #[test]
+    fn test_x11_keyboard() {
+        let keymap = parse_xmodmap(
+            "keycode  22 = BackSpace BackSpace BackSpace BackSpace
+keycode  37 = Control_L NoSymbol Control_L
+keycode  38 = a A a A
+keycode  47 = semicolon colon semicolon colon
+keycode  50 = Shift_L NoSymbol Shift_L
+keycode  66 = Caps_Lock NoSymbol Caps_Lock
+keycode  36 = Return NoSymbol Return
+keycode 255 =",
+        );
+        assert_eq!(keymap.len(), 7);
+
+        let mut keyboard = X11Keyboard::new(keymap);
+        let mut press = |code| keyboard.key(RawEvent::KeyPress(code));
+        assert_eq!(press(47), Some(TypedKey::Char(';')));
+        assert_eq!(press(50), None);
+        assert_eq!(press(47), Some(TypedKey::Char(':')));
+        assert_eq!(press(66), None);
+        // Shift and Caps Lock cancel out for letters
+        assert_eq!(press(38), Some(TypedKey::Char('a')));
+        assert_eq!(press(22), Some(TypedKey::Backspace));
+        assert_eq!(press(36), Some(TypedKey::Reset));
+
+        keyboard.key(RawEvent::KeyRelease(50));
+        assert_eq!(
+            keyboard.key(RawEvent::KeyPress(38)),
+            Some(TypedKey::Char('A'))
+        );
+        keyboard.key(RawEvent::KeyPress(37));
+        assert_eq!(keyboard.key(RawEvent::KeyPress(38)), Some(TypedKey::Reset));
+        assert_eq!(keyboard.key(RawEvent::ButtonPress), Some(TypedKey::Reset));
+    }
+
+    #[test]
+    fn test_keysym_char() {
+        assert_eq!(keysym_char("x"), Some('x'));
+        assert_eq!(keysym_char("semicolon"), Some(';'));
+        assert_eq!(keysym_char("U00E9"), Some('é'));
+        assert_eq!(keysym_char("Escape"), None);
+    }
+
+    #[tokio::test]
+    async fn test_set_trigger() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+        sqlx::query(
+            "INSERT INTO snippets (id, name, content, created_at, updated_at, archived, kind)
+             VALUES
+             (1, 'signature', 'Best, Sam', 0, 0, 0, 'static'),
+             (2, 'address', '1 Main St', 0, 0, 0, 'static'),
+             (3, 'old signature', 'Cheers', 0, 0, 1, 'static'),
+             (4, 'branch', 'git branch --show-current', 0, 0, 0, 'dynamic')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(
+            set_trigger(&pool, 1, Some(" ;sig "))
+                .await
+                .unwrap()
+                .as_deref(),
+            Some(";sig")
+        );
+        set_trigger(&pool, 3, Some(";old")).await.unwrap();
+        assert!(matches!(
+            set_trigger(&pool, 2, Some(";sig")).await,
+            Err(AppError::Duplicate(_))
+        ));
+        // ";si" would fire before ";sig" could be finished
+        assert!(matches!(
+            set_trigger(&pool, 2, Some(";si")).await,
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            set_trigger(&pool, 9, Some(";addr")).await,
+            Err(AppError::NotFound(_))
+        ));
+        set_trigger(&pool, 2, Some(";addr")).await.unwrap();
+
+        // Typing a trigger must never run a command unconfirmed
+        assert!(matches!(
+            set_trigger(&pool, 4, Some(";br")).await,
+            Err(AppError::Validation(_))
+        ));
+        sqlx::query("UPDATE snippets SET expansion_trigger = ';br' WHERE id = 4")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Archived and dynamic snippets keep their trigger but do not expand
+        let triggers = list_triggers(&pool).await.unwrap();
+        assert_eq!(
+            triggers
+                .iter()
+                .map(|t| (t.trigger.as_str(), t.snippet_id))
+                .collect::<Vec<_>>(),
+            vec![(";addr", 2), (";sig", 1)]
+        );
+
+        assert_eq!(set_trigger(&pool, 1, Some("  ")).await.unwrap(), None);
+        assert_eq!(load_triggers(&pool).await.unwrap().len(), 1);
+    }
+}
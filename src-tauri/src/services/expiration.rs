@@ -0,0 +1,198 @@
+/// Expiration dates of temporary snippets.
+///
+/// A snippet can carry an `expires_at` timestamp, for things that stop being
+/// useful on a known date such as temporary tokens or meeting links. Expired
+/// snippets are flagged in search results; with `auto_archive` turned on in
+/// the expiration settings, a background task also archives them.
+use crate::services::database::get_pool;
+use crate::services::menubar;
+use crate::services::settings::SettingsService;
+use crate::utils::error::AppError;
+use crate::utils::time::current_timestamp;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// How often the scheduler looks for expired snippets
+const CHECK_INTERVAL_SECS: u64 = 15 * 60;
+
+/// Hours ahead `get_expiring_snippets` looks by default
+pub const DEFAULT_REMINDER_HOURS: u32 = 24;
+
+/// A snippet that has expired or is about to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpiringSnippet {
+    pub snippet_id: i64,
+    pub snippet_name: String,
+    pub expires_at: i64,
+    /// Whether `expires_at` has already passed
+    pub expired: bool,
+}
+
+/// Sets or clears a snippet's expiration date
+///
+/// Like pinning, this does not change `updated_at`.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the snippet does not exist
+pub async fn set_expiration(
+    pool: &SqlitePool,
+    id: i64,
+    expires_at: Option<i64>,
+) -> Result<(), AppError> {
+    let result = sqlx::query("UPDATE snippets SET expires_at = ? WHERE id = ?")
+        .bind(expires_at)
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!(
+            "Snippet with id {} not found",
+            id
+        )));
+    }
+    Ok(())
+}
+
+/// Lists snippets that expire before `until`, including already expired ones
+///
+/// Archived snippets are left out, so a reminder stops once an expired
+/// snippet has been dealt with.
+///
+/// # Returns
+///
+/// The snippets, soonest expiration first
+pub async fn get_expiring_snippets(
+    pool: &SqlitePool,
+    now: i64,
+    until: i64,
+) -> Result<Vec<ExpiringSnippet>, AppError> {
+    let rows = sqlx::query_as::<_, (i64, String, i64)>(
+        r#"
+        SELECT id, name, expires_at
+        FROM snippets
+        WHERE expires_at IS NOT NULL AND expires_at < ? AND archived = 0
+        ORDER BY expires_at, id
+        "#,
+    )
+    .bind(until)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| AppError::Database(format!("Failed to fetch expiring snippets: {}", e)))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(snippet_id, snippet_name, expires_at)| ExpiringSnippet {
+            snippet_id,
+            snippet_name,
+            expires_at,
+            expired: expires_at <= now,
+        })
+        .collect())
+}
+
+/// Archives every snippet that has expired by `now`
+///
+/// # Returns
+///
+/// Number of snippets archived
+pub async fn archive_expired(pool: &SqlitePool, now: i64) -> Result<u64, AppError> {
+    let result = sqlx::query(
+        "UPDATE snippets SET archived = 1
+         WHERE expires_at IS NOT NULL AND expires_at <= ? AND archived = 0",
+    )
+    .bind(now)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Starts the background loop that archives expired snippets
+///
+/// Settings are read on every pass, so turning `auto_archive` on or off
+/// takes effect without a restart.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match get_pool(&app) {
+                Ok(pool) => match SettingsService::new(pool.clone()).get_settings().await {
+                    Ok(settings) if settings.expiration_settings.auto_archive => {
+                        match archive_expired(&pool, current_timestamp()).await {
+                            Ok(0) => {}
+                            Ok(_) => menubar::notify_snippets_changed(&app),
+                            Err(e) => eprintln!("Failed to archive expired snippets: {}", e),
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => eprintln!("Skipping expired snippet check: {}", e),
+                },
+                Err(e) => eprintln!("Skipping expired snippet check: {}", e),
+            }
+
+            tokio::time::sleep(Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::run_migrations;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+        sqlx::raw_sql(
+            "INSERT INTO snippets (id, name, content, created_at, updated_at, expires_at) VALUES
+                (1, 'token', 'x', 0, 0, 100), (2, 'meeting', 'x', 0, 0, 250),
+                (3, 'later', 'x', 0, 0, 5000), (4, 'forever', 'x', 0, 0, NULL)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_get_expiring_snippets() {
+        let pool = setup_test_db().await;
+
+        let expiring = get_expiring_snippets(&pool, 200, 300).await.unwrap();
+        let found: Vec<(&str, bool)> = expiring
+            .iter()
+            .map(|s| (s.snippet_name.as_str(), s.expired))
+            .collect();
+        assert_eq!(found, vec![("token", true), ("meeting", false)]);
+
+        set_expiration(&pool, 1, None).await.unwrap();
+        set_expiration(&pool, 4, Some(150)).await.unwrap();
+        let expiring = get_expiring_snippets(&pool, 200, 300).await.unwrap();
+        let names: Vec<&str> = expiring.iter().map(|s| s.snippet_name.as_str()).collect();
+        assert_eq!(names, vec!["forever", "meeting"]);
+
+        assert!(matches!(
+            set_expiration(&pool, 99, Some(1)).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_archive_expired() {
+        let pool = setup_test_db().await;
+
+        assert_eq!(archive_expired(&pool, 250).await.unwrap(), 2);
+        assert_eq!(archive_expired(&pool, 250).await.unwrap(), 0);
+        // Archived snippets no longer need a reminder
+        let expiring = get_expiring_snippets(&pool, 250, 6000).await.unwrap();
+        assert_eq!(expiring.len(), 1);
+        assert_eq!(expiring[0].snippet_name, "later");
+    }
+}
@@ -0,0 +1,202 @@
+/// Versions of the JSON export format.
+///
+/// Every export records the format version it was written in. Importing reads
+/// the version first: older formats are migrated forward one step at a time
+/// to the current one, and formats newer than this build knows are refused
+/// instead of being half understood.
+use crate::commands::storage_commands::ExportData;
+use crate::utils::error::AppError;
+use serde_json::{Map, Value};
+
+/// Version written by `export_to_json`
+pub const EXPORT_FORMAT_VERSION: &str = "1.1.0";
+
+/// Version given to exports written before the format had a version: a bare
+/// array of snippets, or an object without a `version` field
+const UNVERSIONED: &str = "0.0.0";
+
+/// A step that brings an export up to `to` from the version before it
+struct FormatMigration {
+    to: &'static str,
+    migrate: fn(Value) -> Result<Value, AppError>,
+}
+
+/// Steps in version order; the last one ends at `EXPORT_FORMAT_VERSION`
+const MIGRATIONS: &[FormatMigration] = &[
+    FormatMigration {
+        to: "1.0.0",
+        migrate: wrap_unversioned,
+    },
+    FormatMigration {
+        to: "1.1.0",
+        migrate: add_snippet_state,
+    },
+];
+
+/// Parses `major.minor.patch`; missing parts count as 0
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version.trim().splitn(3, '.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map_or(Some(0), |part| part.parse().ok())?;
+    let patch = parts.next().map_or(Some(0), |part| part.parse().ok())?;
+    Some((major, minor, patch))
+}
+
+/// The range of versions this build imports, for error messages
+pub fn supported_range() -> String {
+    format!("{} (unversioned) to {}", UNVERSIONED, EXPORT_FORMAT_VERSION)
+}
+
+/// 0.0.0 to 1.0.0: wraps a bare array in an export object and fills in the
+/// `version` and `exported_at` fields
+fn wrap_unversioned(value: Value) -> Result<Value, AppError> {
+    let mut export = match value {
+        Value::Array(snippets) => {
+            let mut export = Map::new();
+            export.insert("snippets".to_string(), Value::Array(snippets));
+            export
+        }
+        Value::Object(export) => export,
+        other => {
+            return Err(AppError::InvalidInput(format!(
+                "Not a Snips export: {}",
+                other
+            )))
+        }
+    };
+    export
+        .entry("exported_at")
+        .or_insert_with(|| Value::from(0));
+    Ok(Value::Object(export))
+}
+
+/// 1.0.0 to 1.1.0: snippets gained `kind`, `aliases`, `parameters`,
+/// `archived` and `expires_at`
+///
+/// Nothing to rewrite; snippets without them read as static, unarchived
+/// snippets with no aliases, parameters or expiry.
+fn add_snippet_state(value: Value) -> Result<Value, AppError> {
+    Ok(value)
+}
+
+/// Reads the version an export was written in
+fn read_version(value: &Value) -> Result<String, AppError> {
+    match value {
+        Value::Array(_) => Ok(UNVERSIONED.to_string()),
+        Value::Object(export) => match export.get("version") {
+            None => Ok(UNVERSIONED.to_string()),
+            Some(Value::String(version)) => Ok(version.clone()),
+            Some(other) => Err(AppError::InvalidInput(format!(
+                "Export version must be a string, found {}",
+                other
+            ))),
+        },
+        _ => Err(AppError::InvalidInput(
+            "Not a Snips export: expected an object or an array of snippets".to_string(),
+        )),
+    }
+}
+
+/// Parses an export in any supported format version
+///
+/// # Returns
+///
+/// The export in the current format, with `version` set to
+/// `EXPORT_FORMAT_VERSION`
+///
+/// # Errors
+///
+/// * `AppError::InvalidInput` - The text is not JSON, not an export, or its
+///   version is malformed
+/// * `AppError::Unsupported` - The export was written by a newer version of
+///   Snips
+pub fn parse_export(text: &str) -> Result<ExportData, AppError> {
+    let mut value: Value = serde_json::from_str(text)
+        .map_err(|e| AppError::InvalidInput(format!("Export is not valid JSON: {}", e)))?;
+
+    let version = read_version(&value)?;
+    let parsed = parse_version(&version)
+        .ok_or_else(|| AppError::InvalidInput(format!("Invalid export version: {}", version)))?;
+    let current = parse_version(EXPORT_FORMAT_VERSION).unwrap_or_default();
+    if parsed > current {
+        return Err(AppError::Unsupported(format!(
+            "Export format {} is newer than this version of Snips can import \
+             (supported: {}); update Snips and try again",
+            version,
+            supported_range()
+        )));
+    }
+
+    for step in MIGRATIONS {
+        if parse_version(step.to).is_some_and(|to| to > parsed) {
+            value = (step.migrate)(value)?;
+        }
+    }
+    if let Value::Object(export) = &mut value {
+        export.insert("version".to_string(), Value::from(EXPORT_FORMAT_VERSION));
+    }
+
+    serde_json::from_value(value)
+        .map_err(|e| AppError::InvalidInput(format!("Not a Snips export: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SnippetKind;
+
+    const SNIPPET: &str = r#"{"name": "a", "content": "x", "description": null, "tags": [],
+        "created_at": 0, "updated_at": 0}"#;
+
+    #[test]
+    fn test_parse_current_export() {
+        let text = format!(
+            r#"{{"version": "1.0.0", "exported_at": 5, "snippets": [{}]}}"#,
+            SNIPPET
+        );
+        let export = parse_export(&text).unwrap();
+        assert_eq!(export.exported_at, 5);
+        assert_eq!(export.snippets.len(), 1);
+        assert_eq!(export.snippets[0].kind, SnippetKind::Static);
+        assert!(!export.snippets[0].archived);
+    }
+
+    #[test]
+    fn test_migrates_unversioned_exports() {
+        let export = parse_export(&format!("[{}]", SNIPPET)).unwrap();
+        assert_eq!(export.version, EXPORT_FORMAT_VERSION);
+        assert_eq!(export.snippets[0].name, "a");
+
+        let export = parse_export(&format!(r#"{{"snippets": [{}]}}"#, SNIPPET)).unwrap();
+        assert_eq!(
+            (export.version.as_str(), export.exported_at),
+            (EXPORT_FORMAT_VERSION, 0)
+        );
+    }
+
+    #[test]
+    fn test_rejects_newer_and_malformed_versions() {
+        let newer = r#"{"version": "2.0.0", "exported_at": 0, "snippets": []}"#;
+        match parse_export(newer) {
+            Err(AppError::Unsupported(message)) => assert!(message.contains("1.1.0")),
+            other => panic!("expected Unsupported, got {:?}", other),
+        }
+        let minor = r#"{"version": "1.2", "exported_at": 0, "snippets": []}"#;
+        assert!(matches!(parse_export(minor), Err(AppError::Unsupported(_))));
+
+        let malformed = r#"{"version": "one", "exported_at": 0, "snippets": []}"#;
+        assert!(matches!(
+            parse_export(malformed),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(parse_export("42").is_err());
+        assert!(parse_export("{}").is_err());
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("1.0.0"), Some((1, 0, 0)));
+        assert_eq!(parse_version("1.2"), Some((1, 2, 0)));
+        assert_eq!(parse_version("v1"), None);
+    }
+}
@@ -0,0 +1,249 @@
+/// Wiping all local data and starting over.
+///
+/// Snips keeps its data in the app data directory: snippets, settings,
+/// analytics and API tokens in `snips.db` (plus SQLite's `-wal`, `-shm` and
+/// `-journal` side files), the safety copy left by a restore, the clones of
+/// team feeds and the backups directory. Custom themes, the theme fragments
+/// laid over the built-in palettes, live in the app config directory, logs
+/// (which the diagnostics bundle collects) in the app log directory and the
+/// gist token in the OS keychain. A factory reset closes the backend pool,
+/// deletes those files and credentials and restarts the app; the next launch
+/// recreates the database and runs every migration, exactly as on first
+/// install.
+use crate::services::confirmation::ConfirmationChallenge;
+use crate::services::custom_themes;
+use crate::services::database::get_pool;
+use crate::services::keyring;
+use crate::services::team_feeds;
+use crate::utils::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// Time the frontend gets to receive the report before the app restarts
+const RESTART_DELAY: Duration = Duration::from_millis(750);
+
+/// Files and directories deleted by a factory reset
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FactoryResetReport {
+    /// Paths that existed and were deleted
+    pub removed: Vec<String>,
+    /// Whether the backups directory was deleted too
+    pub backups_removed: bool,
+    /// Keychain accounts whose credentials were deleted, or had none
+    pub credentials_removed: Vec<String>,
+}
+
+/// Result of a `factory_reset` call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum FactoryResetOutcome {
+    /// Nothing was deleted; call again with the challenge token to go ahead
+    ConfirmationRequired { challenge: ConfirmationChallenge },
+    /// The data was deleted and the app is about to restart
+    Reset { report: FactoryResetReport },
+}
+
+/// Paths a factory reset deletes, whether or not they exist
+///
+/// `app_dir` is the app data directory and `config_dir` the app config
+/// directory; they are the same on macOS. `log_dir`, the app log directory,
+/// is deleted as a whole.
+pub fn reset_targets(
+    app_dir: &Path,
+    config_dir: &Path,
+    log_dir: &Path,
+    delete_backups: bool,
+) -> Vec<PathBuf> {
+    let mut targets: Vec<PathBuf> = [
+        "snips.db",
+        "snips.db-wal",
+        "snips.db-shm",
+        "snips.db-journal",
+        "snips_pre_restore.db",
+    ]
+    .iter()
+    .map(|name| app_dir.join(name))
+    .collect();
+    targets.push(app_dir.join(team_feeds::FEEDS_DIR));
+    targets.push(config_dir.join(custom_themes::THEMES_DIR));
+    targets.push(log_dir.to_path_buf());
+    if delete_backups {
+        targets.push(app_dir.join("backups"));
+    }
+    targets
+}
+
+/// Keychain accounts a factory reset clears
+pub const RESET_CREDENTIALS: [&str; 1] = [keyring::GIST_ACCESS_TOKEN];
+
+/// Deletes the targets that exist
+///
+/// # Errors
+///
+/// Returns `AppError::Database` naming the first path that could not be
+/// deleted; paths before it are already gone
+fn remove_targets(targets: &[PathBuf]) -> Result<Vec<String>, AppError> {
+    let mut removed = Vec::new();
+    for target in targets {
+        let result = if target.is_dir() {
+            std::fs::remove_dir_all(target)
+        } else if target.exists() {
+            std::fs::remove_file(target)
+        } else {
+            continue;
+        };
+        result.map_err(|e| {
+            AppError::Database(format!("Failed to delete {}: {}", target.display(), e))
+        })?;
+        removed.push(target.to_string_lossy().to_string());
+    }
+    Ok(removed)
+}
+
+/// Deletes the credentials stored for `accounts`
+///
+/// A keychain that cannot be reached (e.g. no Secret Service is running) is
+/// logged rather than failing the reset, which has already deleted the data.
+async fn remove_credentials(accounts: &[&str]) -> Vec<String> {
+    let mut removed = Vec::new();
+    for account in accounts {
+        match keyring::delete_credential(account).await {
+            Ok(()) => removed.push(account.to_string()),
+            Err(e) => eprintln!("Failed to delete keychain credential {}: {}", account, e),
+        }
+    }
+    removed
+}
+
+/// Closes the database, deletes all local data and schedules a restart
+///
+/// The pool is closed first so no write lands in a file that is about to
+/// disappear; queries issued after this point fail until the restart.
+///
+/// # Arguments
+///
+/// * `app` - Application handle
+/// * `delete_backups` - Also delete the backups directory
+///
+/// # Errors
+///
+/// Returns `AppError` if the app data directory cannot be resolved or a file
+/// cannot be deleted. The app restarts anyway once the pool is closed, so
+/// a partial reset is retried from a clean process.
+pub async fn factory_reset(
+    app: &AppHandle,
+    delete_backups: bool,
+) -> Result<FactoryResetReport, AppError> {
+    let app_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Database(format!("Failed to get app data dir: {}", e)))?;
+    let config_dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| AppError::Database(format!("Failed to get app config dir: {}", e)))?;
+    let log_dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| AppError::Database(format!("Failed to get app log dir: {}", e)))?;
+
+    get_pool(app)?.close().await;
+
+    let result = remove_targets(&reset_targets(
+        &app_dir,
+        &config_dir,
+        &log_dir,
+        delete_backups,
+    ));
+    let credentials_removed = remove_credentials(&RESET_CREDENTIALS).await;
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(RESTART_DELAY).await;
+        handle.restart();
+    });
+
+    Ok(FactoryResetReport {
+        removed: result?,
+        backups_removed: delete_backups,
+        credentials_removed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("snips_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_reset_targets() {
+        let dir = Path::new("/data/snips");
+        let config = Path::new("/config/snips");
+        let logs = Path::new("/logs/snips");
+        let targets = reset_targets(dir, config, logs, false);
+        assert_eq!(targets[0], dir.join("snips.db"));
+        assert!(targets.contains(&dir.join("feeds")));
+        assert!(targets.contains(&config.join("themes")));
+        assert!(targets.contains(&logs.to_path_buf()));
+        assert!(!targets.contains(&dir.join("backups")));
+        assert_eq!(
+            reset_targets(dir, config, logs, true).last(),
+            Some(&dir.join("backups"))
+        );
+    }
+
+    #[test]
+    fn test_reset_targets_cover_all_local_data() {
+        let dir = Path::new("/data/snips");
+        let config = Path::new("/config/snips");
+        let logs = Path::new("/logs/snips");
+        let expected = [
+            dir.join("snips.db"),
+            dir.join("snips.db-wal"),
+            dir.join("snips.db-shm"),
+            dir.join("snips.db-journal"),
+            dir.join("snips_pre_restore.db"),
+            dir.join("feeds"),
+            config.join("themes"),
+            logs.to_path_buf(),
+            dir.join("backups"),
+        ];
+        assert_eq!(reset_targets(dir, config, logs, true), expected);
+        assert_eq!(RESET_CREDENTIALS, [keyring::GIST_ACCESS_TOKEN]);
+    }
+
+    #[test]
+    fn test_remove_targets_keeps_unlisted_files() {
+        let dir = test_dir("factory_reset");
+        std::fs::write(dir.join("snips.db"), b"db").unwrap();
+        std::fs::write(dir.join("snips.db-wal"), b"wal").unwrap();
+        std::fs::create_dir_all(dir.join("backups")).unwrap();
+        std::fs::write(dir.join("backups/snips_backup_1.db"), b"backup").unwrap();
+        std::fs::create_dir_all(dir.join("logs")).unwrap();
+        std::fs::write(dir.join("logs/snips.log"), b"log").unwrap();
+        let logs = dir.join("logs");
+
+        let removed = remove_targets(&reset_targets(&dir, &dir, &logs, false)).unwrap();
+        assert_eq!(removed.len(), 3);
+        assert!(!dir.join("snips.db").exists());
+        assert!(!logs.exists());
+        assert!(dir.join("backups/snips_backup_1.db").exists());
+
+        let removed = remove_targets(&reset_targets(&dir, &dir, &logs, true)).unwrap();
+        assert_eq!(
+            removed,
+            vec![dir.join("backups").to_string_lossy().to_string()]
+        );
+        assert!(!dir.join("backups").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,543 @@
+use crate::utils::error::AppError;
+use crate::utils::time::current_timestamp;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::process::Command;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+/// Mechanism used to query and re-activate the focused window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusBackend {
+    /// `hyprctl` on Hyprland
+    Hyprland,
+    /// `swaymsg` on Sway
+    Sway,
+    /// `xdotool` on X11 (and XWayland sessions without a known compositor)
+    X11,
+    /// AppleScript via `osascript` on macOS
+    MacOs,
+    /// No known mechanism; focus is left to the window manager
+    Unsupported,
+}
+
+impl FocusBackend {
+    /// Detects the backend for the current session from the environment
+    pub fn detect() -> Self {
+        if cfg!(target_os = "macos") {
+            return Self::MacOs;
+        }
+        let set = |name: &str| std::env::var_os(name).is_some_and(|v| !v.is_empty());
+        if set("HYPRLAND_INSTANCE_SIGNATURE") {
+            Self::Hyprland
+        } else if set("SWAYSOCK") {
+            Self::Sway
+        } else if set("DISPLAY") {
+            Self::X11
+        } else {
+            Self::Unsupported
+        }
+    }
+}
+
+/// The window that was active before the overlay was shown
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreviousWindow {
+    pub backend: FocusBackend,
+    /// Backend-specific handle: Hyprland address, Sway container ID, X11
+    /// window ID or macOS bundle identifier
+    pub handle: String,
+    /// Window title or application name, for diagnostics
+    pub title: Option<String>,
+    pub pid: Option<u32>,
+    /// Application the window belongs to: window class or app ID on Linux,
+    /// application name on macOS
+    #[serde(default)]
+    pub app: Option<String>,
+}
+
+/// Frame of the active window in the compositor's layout coordinates
+///
+/// Logical pixels on macOS, Hyprland and Sway; physical pixels on X11.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl WindowBounds {
+    /// Center point of the frame
+    pub fn center(&self) -> (f64, f64) {
+        (
+            f64::from(self.x) + f64::from(self.width) / 2.0,
+            f64::from(self.y) + f64::from(self.height) / 2.0,
+        )
+    }
+}
+
+/// Outcome of the last attempt to give focus back after hiding the overlay
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusRestoreReport {
+    pub backend: FocusBackend,
+    pub window: Option<PreviousWindow>,
+    pub success: bool,
+    pub error: Option<String>,
+    pub attempted_at: i64,
+}
+
+#[derive(Debug, Default)]
+struct FocusTracker {
+    previous: Option<PreviousWindow>,
+    last_restore: Option<FocusRestoreReport>,
+}
+
+/// State tracking the window to return focus to
+#[derive(Debug, Default)]
+pub struct FocusState(Mutex<FocusTracker>);
+
+impl FocusState {
+    /// Records the currently active window before the overlay takes focus
+    ///
+    /// Our own windows are ignored so toggling the overlay from the
+    /// management window does not "restore" focus to Snips itself.
+    pub fn remember_active_window(&self) {
+        let backend = FocusBackend::detect();
+        let active = active_window(backend)
+            .ok()
+            .flatten()
+            .filter(|window| window.pid != Some(std::process::id()));
+        if let Ok(mut tracker) = self.0.lock() {
+            tracker.previous = active;
+        }
+    }
+
+    /// Re-activates the window recorded by `remember_active_window`
+    ///
+    /// The outcome is kept for `last_restore`. Does nothing when no window was
+    /// recorded.
+    pub fn restore_previous_window(&self) {
+        let Some(previous) = self.0.lock().ok().and_then(|mut t| t.previous.take()) else {
+            return;
+        };
+
+        let result = activate_window(&previous);
+        let report = FocusRestoreReport {
+            backend: previous.backend,
+            success: result.is_ok(),
+            error: result.err().map(|e| e.to_string()),
+            window: Some(previous),
+            attempted_at: current_timestamp(),
+        };
+        if let Ok(mut tracker) = self.0.lock() {
+            tracker.last_restore = Some(report);
+        }
+    }
+
+    /// Forgets the recorded window, e.g. when the overlay lost focus to
+    /// another app and restoring would steal focus back
+    pub fn forget_previous_window(&self) {
+        if let Ok(mut tracker) = self.0.lock() {
+            tracker.previous = None;
+        }
+    }
+
+    /// Application of the window recorded by `remember_active_window`
+    pub fn previous_app(&self) -> Option<String> {
+        self.0
+            .lock()
+            .ok()
+            .and_then(|t| t.previous.as_ref().and_then(|w| w.app.clone()))
+    }
+
+    /// The outcome of the most recent restore attempt
+    pub fn last_restore(&self) -> Option<FocusRestoreReport> {
+        self.0.lock().ok().and_then(|t| t.last_restore.clone())
+    }
+}
+
+/// Application the user is working in, recorded with snippet usage
+///
+/// This is the application of the window the overlay was opened over, as
+/// long as it is remembered; `None` when it is not known.
+pub fn active_app(app: &AppHandle) -> Option<String> {
+    app.try_state::<FocusState>()?.previous_app()
+}
+
+/// Queries the active window
+///
+/// # Returns
+///
+/// `None` when the backend is unsupported or no window is focused
+///
+/// # Errors
+///
+/// Returns `AppError::External` if the helper tool is missing or fails
+pub fn active_window(backend: FocusBackend) -> Result<Option<PreviousWindow>, AppError> {
+    match backend {
+        FocusBackend::Hyprland => {
+            let output = run("hyprctl", &["activewindow", "-j"])?;
+            Ok(parse_hyprland_active_window(&output))
+        }
+        FocusBackend::Sway => {
+            let output = run("swaymsg", &["-t", "get_tree", "-r"])?;
+            let tree: Value = serde_json::from_str(&output)?;
+            Ok(find_sway_focused(&tree))
+        }
+        FocusBackend::X11 => {
+            let output = run("xdotool", &["getactivewindow", "getwindowpid"])?;
+            let id = run("xdotool", &["getactivewindow"])?;
+            Ok(parse_xdotool_window(&id, &output))
+        }
+        FocusBackend::MacOs => {
+            let script = "tell application \"System Events\" to set p to first application \
+                process whose frontmost is true\n\
+                return (bundle identifier of p) & \"\\n\" & (name of p) & \"\\n\" & (unix id of p)";
+            let output = run("osascript", &["-e", script])?;
+            let mut lines = output.lines();
+            let handle = lines.next().unwrap_or_default().trim().to_string();
+            let title = lines.next().map(str::to_string);
+            Ok(
+                (!handle.is_empty() && handle != "missing value").then(|| PreviousWindow {
+                    backend,
+                    handle,
+                    app: title.clone(),
+                    title,
+                    pid: lines.next().and_then(|pid| pid.trim().parse().ok()),
+                }),
+            )
+        }
+        FocusBackend::Unsupported => Ok(None),
+    }
+}
+
+/// Queries the frame of the active window
+///
+/// # Returns
+///
+/// `None` when the backend is unsupported or no window is focused
+///
+/// # Errors
+///
+/// Returns `AppError::External` if the helper tool is missing or fails
+pub fn active_window_bounds(backend: FocusBackend) -> Result<Option<WindowBounds>, AppError> {
+    match backend {
+        FocusBackend::Hyprland => {
+            let output = run("hyprctl", &["activewindow", "-j"])?;
+            Ok(parse_hyprland_bounds(&output))
+        }
+        FocusBackend::Sway => {
+            let output = run("swaymsg", &["-t", "get_tree", "-r"])?;
+            let tree: Value = serde_json::from_str(&output)?;
+            Ok(find_sway_focused_bounds(&tree))
+        }
+        FocusBackend::X11 => {
+            let output = run(
+                "xdotool",
+                &["getactivewindow", "getwindowgeometry", "--shell"],
+            )?;
+            Ok(parse_xdotool_geometry(&output))
+        }
+        FocusBackend::MacOs => {
+            let script = "tell application \"System Events\" to tell (first application \
+                process whose frontmost is true) to get {position, size} of front window";
+            let output = run("osascript", &["-e", script])?;
+            Ok(parse_osascript_bounds(&output))
+        }
+        FocusBackend::Unsupported => Ok(None),
+    }
+}
+
+/// Gives focus back to a previously recorded window
+///
+/// # Errors
+///
+/// Returns `AppError::External` if the helper tool fails (e.g. the window was
+/// closed in the meantime) or `AppError::Unsupported` for unknown backends
+pub fn activate_window(window: &PreviousWindow) -> Result<(), AppError> {
+    match window.backend {
+        FocusBackend::Hyprland => {
+            let output = run(
+                "hyprctl",
+                &[
+                    "dispatch",
+                    "focuswindow",
+                    &format!("address:{}", window.handle),
+                ],
+            )?;
+            // hyprctl exits 0 even when the dispatch fails
+            if output.trim() != "ok" {
+                return Err(AppError::External(format!("hyprctl: {}", output.trim())));
+            }
+            Ok(())
+        }
+        FocusBackend::Sway => run(
+            "swaymsg",
+            &[&format!("[con_id={}]", window.handle), "focus"],
+        )
+        .map(|_| ()),
+        FocusBackend::X11 => run("xdotool", &["windowactivate", &window.handle]).map(|_| ()),
+        FocusBackend::MacOs => {
+            let script = format!(
+                "tell application id \"{}\" to activate",
+                window.handle.replace(['"', '\\'], "")
+            );
+            run("osascript", &["-e", &script]).map(|_| ())
+        }
+        FocusBackend::Unsupported => Err(AppError::Unsupported(
+            "No focus mechanism for this session".to_string(),
+        )),
+    }
+}
+
+/// Runs a helper program and returns its standard output
+pub(crate) fn run(program: &str, args: &[&str]) -> Result<String, AppError> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| AppError::External(format!("Failed to run {}: {}", program, e)))?;
+
+    if !output.status.success() {
+        return Err(AppError::External(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn parse_hyprland_active_window(output: &str) -> Option<PreviousWindow> {
+    let window: Value = serde_json::from_str(output).ok()?;
+    let address = window.get("address")?.as_str()?;
+    Some(PreviousWindow {
+        backend: FocusBackend::Hyprland,
+        handle: address.to_string(),
+        title: window
+            .get("title")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        pid: window
+            .get("pid")
+            .and_then(Value::as_u64)
+            .and_then(|pid| u32::try_from(pid).ok()),
+        app: window
+            .get("class")
+            .and_then(Value::as_str)
+            .filter(|class| !class.is_empty())
+            .map(str::to_string),
+    })
+}
+
+/// Depth-first search of a Sway tree for the focused leaf container
+fn find_sway_focused(node: &Value) -> Option<PreviousWindow> {
+    if node.get("focused").and_then(Value::as_bool) == Some(true) {
+        return Some(PreviousWindow {
+            backend: FocusBackend::Sway,
+            handle: node.get("id")?.as_u64()?.to_string(),
+            title: node.get("name").and_then(Value::as_str).map(str::to_string),
+            pid: node
+                .get("pid")
+                .and_then(Value::as_u64)
+                .and_then(|pid| u32::try_from(pid).ok()),
+            // Native Wayland windows have an app ID, XWayland ones a class
+            app: node
+                .get("app_id")
+                .and_then(Value::as_str)
+                .or_else(|| {
+                    node.get("window_properties")
+                        .and_then(|props| props.get("class"))
+                        .and_then(Value::as_str)
+                })
+                .map(str::to_string),
+        });
+    }
+
+    ["nodes", "floating_nodes"]
+        .iter()
+        .filter_map(|key| node.get(key).and_then(Value::as_array))
+        .flatten()
+        .find_map(find_sway_focused)
+}
+
+fn parse_xdotool_window(id: &str, pid: &str) -> Option<PreviousWindow> {
+    let id = id.trim();
+    let pid: Option<u32> = pid.trim().parse().ok();
+    (!id.is_empty() && id.chars().all(|c| c.is_ascii_digit())).then(|| PreviousWindow {
+        backend: FocusBackend::X11,
+        handle: id.to_string(),
+        title: None,
+        pid,
+        app: pid.and_then(process_name),
+    })
+}
+
+/// Name of a running process, from `/proc/<pid>/comm`
+fn process_name(pid: u32) -> Option<String> {
+    let name = std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+    let name = name.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Reads `at` and `size` from `hyprctl activewindow -j`
+fn parse_hyprland_bounds(output: &str) -> Option<WindowBounds> {
+    let window: Value = serde_json::from_str(output).ok()?;
+    let pair = |key: &str| -> Option<(i64, i64)> {
+        let values = window.get(key)?.as_array()?;
+        Some((values.first()?.as_i64()?, values.get(1)?.as_i64()?))
+    };
+    let (x, y) = pair("at")?;
+    let (width, height) = pair("size")?;
+    Some(WindowBounds {
+        x: i32::try_from(x).ok()?,
+        y: i32::try_from(y).ok()?,
+        width: u32::try_from(width).ok()?,
+        height: u32::try_from(height).ok()?,
+    })
+}
+
+/// Depth-first search of a Sway tree for the `rect` of the focused container
+fn find_sway_focused_bounds(node: &Value) -> Option<WindowBounds> {
+    if node.get("focused").and_then(Value::as_bool) == Some(true) {
+        let rect = node.get("rect")?;
+        let field = |key: &str| rect.get(key).and_then(Value::as_i64);
+        return Some(WindowBounds {
+            x: i32::try_from(field("x")?).ok()?,
+            y: i32::try_from(field("y")?).ok()?,
+            width: u32::try_from(field("width")?).ok()?,
+            height: u32::try_from(field("height")?).ok()?,
+        });
+    }
+
+    ["nodes", "floating_nodes"]
+        .iter()
+        .filter_map(|key| node.get(key).and_then(Value::as_array))
+        .flatten()
+        .find_map(find_sway_focused_bounds)
+}
+
+/// Reads `xdotool getwindowgeometry --shell` output (`X=`, `Y=`, `WIDTH=`, `HEIGHT=`)
+fn parse_xdotool_geometry(output: &str) -> Option<WindowBounds> {
+    let field = |key: &str| {
+        output.lines().find_map(|line| {
+            let (name, value) = line.split_once('=')?;
+            (name.trim() == key).then(|| value.trim().to_string())
+        })
+    };
+    Some(WindowBounds {
+        x: field("X")?.parse().ok()?,
+        y: field("Y")?.parse().ok()?,
+        width: field("WIDTH")?.parse().ok()?,
+        height: field("HEIGHT")?.parse().ok()?,
+    })
+}
+
+/// Reads AppleScript's `{position, size}` list, printed as `x, y, width, height`
+fn parse_osascript_bounds(output: &str) -> Option<WindowBounds> {
+    let values: Vec<&str> = output.trim().split(',').map(str::trim).collect();
+    match values.as_slice() {
+        [x, y, width, height] => Some(WindowBounds {
+            x: x.parse().ok()?,
+            y: y.parse().ok()?,
+            width: width.parse().ok()?,
+            height: height.parse().ok()?,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hyprland_active_window() {
+        let output = r#"{"address":"0x55d1c8a0","title":"nvim","pid":4242,"class":"kitty"}"#;
+        let window = parse_hyprland_active_window(output).unwrap();
+        assert_eq!(window.handle, "0x55d1c8a0");
+        assert_eq!(window.title.as_deref(), Some("nvim"));
+        assert_eq!(window.pid, Some(4242));
+        assert_eq!(window.app.as_deref(), Some("kitty"));
+
+        // No focused window
+        assert!(parse_hyprland_active_window("{}").is_none());
+        assert!(parse_hyprland_active_window("Invalid").is_none());
+    }
+
+    #[test]
+    fn test_find_sway_focused() {
+        let tree = serde_json::json!({
+            "id": 1, "focused": false,
+            "nodes": [{
+                "id": 2, "focused": false,
+                "nodes": [
+                    { "id": 10, "focused": false, "name": "a", "nodes": [] },
+                    { "id": 11, "focused": false, "name": "b", "nodes": [] }
+                ],
+                "floating_nodes": [
+                    {
+                        "id": 12, "focused": true, "name": "float", "pid": 99,
+                        "window_properties": { "class": "Gimp" }, "nodes": []
+                    }
+                ]
+            }]
+        });
+        let window = find_sway_focused(&tree).unwrap();
+        assert_eq!(window.handle, "12");
+        assert_eq!(window.title.as_deref(), Some("float"));
+        assert_eq!(window.pid, Some(99));
+        assert_eq!(window.app.as_deref(), Some("Gimp"));
+    }
+
+    #[test]
+    fn test_parse_xdotool_window() {
+        let window = parse_xdotool_window("62914567\n", "1234\n").unwrap();
+        assert_eq!(window.handle, "62914567");
+        assert_eq!(window.pid, Some(1234));
+        assert!(parse_xdotool_window("", "").is_none());
+    }
+
+    #[test]
+    fn test_parse_active_window_bounds() {
+        let expected = WindowBounds {
+            x: 1940,
+            y: 40,
+            width: 800,
+            height: 600,
+        };
+
+        let hyprland = r#"{"address":"0x1","at":[1940,40],"size":[800,600]}"#;
+        assert_eq!(parse_hyprland_bounds(hyprland), Some(expected));
+        assert_eq!(parse_hyprland_bounds("{}"), None);
+
+        let tree = serde_json::json!({
+            "id": 1, "focused": false,
+            "nodes": [{
+                "id": 2, "focused": true,
+                "rect": { "x": 1940, "y": 40, "width": 800, "height": 600 },
+                "nodes": []
+            }]
+        });
+        assert_eq!(find_sway_focused_bounds(&tree), Some(expected));
+
+        let xdotool = "WINDOW=62914567\nX=1940\nY=40\nWIDTH=800\nHEIGHT=600\nSCREEN=0\n";
+        assert_eq!(parse_xdotool_geometry(xdotool), Some(expected));
+        assert_eq!(parse_xdotool_geometry("WINDOW=1\n"), None);
+
+        assert_eq!(
+            parse_osascript_bounds("1940, 40, 800, 600\n"),
+            Some(expected)
+        );
+        assert_eq!(parse_osascript_bounds("missing value"), None);
+        assert_eq!(expected.center(), (2340.0, 340.0));
+    }
+
+    #[test]
+    fn test_restore_without_previous_window_is_noop() {
+        let state = FocusState::default();
+        state.restore_previous_window();
+        assert!(state.last_restore().is_none());
+    }
+}
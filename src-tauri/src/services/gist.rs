@@ -0,0 +1,458 @@
+use crate::commands::storage_commands::SnippetExport;
+use crate::models::snippet::Snippet;
+use crate::services::language::language_id_for_tag;
+use crate::utils::error::AppError;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+const REQUEST_TIMEOUT_SECS: u32 = 30;
+
+/// Headers every GitHub API request sends
+const GITHUB_HEADERS: &[&str] = &[
+    "Accept: application/vnd.github+json",
+    "X-GitHub-Api-Version: 2022-11-28",
+];
+
+/// File extensions used when naming exported gist files by language tag
+const LANGUAGE_EXTENSIONS: &[(&str, &str)] = &[
+    ("c", "c"),
+    ("cpp", "cpp"),
+    ("csharp", "cs"),
+    ("css", "css"),
+    ("go", "go"),
+    ("html", "html"),
+    ("java", "java"),
+    ("javascript", "js"),
+    ("json", "json"),
+    ("kotlin", "kt"),
+    ("lua", "lua"),
+    ("markdown", "md"),
+    ("php", "php"),
+    ("python", "py"),
+    ("ruby", "rb"),
+    ("rust", "rs"),
+    ("shellscript", "sh"),
+    ("sql", "sql"),
+    ("swift", "swift"),
+    ("toml", "toml"),
+    ("typescript", "ts"),
+    ("yaml", "yml"),
+];
+
+/// A gist created from a snippet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GistExportResult {
+    pub gist_id: String,
+    pub url: String,
+}
+
+/// Extracts a gist ID from a raw ID or a gist URL
+///
+/// Accepts `abc123`, `https://gist.github.com/user/abc123` and URLs with a
+/// trailing revision or `#file-...` anchor.
+pub fn parse_gist_id(input: &str) -> Option<String> {
+    let input = input.trim();
+    let path = input.split(['#', '?']).next().unwrap_or_default();
+    let id = match path.split_once("gist.github.com/") {
+        Some((_, rest)) => {
+            let segments: Vec<&str> = rest.split('/').filter(|s| !s.is_empty()).collect();
+            match segments.as_slice() {
+                [id] => *id,
+                [_user, id, ..] => *id,
+                [] => return None,
+            }
+        }
+        None => path,
+    };
+
+    (!id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric())).then(|| id.to_string())
+}
+
+/// Converts a gist API response into one snippet per file
+///
+/// Each file's name becomes the snippet name, its `language` becomes a tag and
+/// the gist description becomes the snippet description. Files marked
+/// `truncated` must have their content filled in beforehand.
+pub fn gist_to_snippets(gist: &Value, now: i64) -> Vec<SnippetExport> {
+    let description = gist
+        .get("description")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(str::to_string);
+    let created_at = parse_github_timestamp(gist.get("created_at")).unwrap_or(now);
+    let updated_at = parse_github_timestamp(gist.get("updated_at")).unwrap_or(now);
+
+    let Some(files) = gist.get("files").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+
+    files
+        .iter()
+        .filter_map(|(file_name, file)| {
+            let content = file.get("content").and_then(Value::as_str)?;
+            if content.trim().is_empty() {
+                return None;
+            }
+            let tags = file
+                .get("language")
+                .and_then(Value::as_str)
+                .map(|language| vec![language.to_lowercase()])
+                .unwrap_or_default();
+
+            Some(SnippetExport {
+                name: file_name.clone(),
+                content: content.to_string(),
+                description: description.clone(),
+                tags,
+                created_at,
+                updated_at,
+                ..SnippetExport::default()
+            })
+        })
+        .collect()
+}
+
+/// Builds the request body for creating a gist from a snippet
+///
+/// The file is named after the snippet, with an extension taken from its
+/// first language tag when the name has none, so GitHub highlights it.
+pub fn snippet_to_gist_payload(snippet: &Snippet, public: bool) -> Value {
+    let tags = snippet.tags.as_deref().unwrap_or_default();
+    let description = snippet
+        .description
+        .clone()
+        .unwrap_or_else(|| snippet.name.clone());
+
+    let mut file_name = snippet.name.trim().replace('/', "-");
+    if !file_name.contains('.') {
+        let extension = tags
+            .iter()
+            .filter_map(|tag| language_id_for_tag(tag))
+            .find_map(|id| {
+                LANGUAGE_EXTENSIONS
+                    .iter()
+                    .find(|(language, _)| *language == id)
+                    .map(|(_, extension)| *extension)
+            })
+            .unwrap_or("txt");
+        file_name = format!("{}.{}", file_name, extension);
+    }
+
+    json!({
+        "description": description,
+        "public": public,
+        "files": { file_name: { "content": snippet.content } },
+    })
+}
+
+/// Fetches a gist, including the full content of truncated files
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` for a missing (or private, without a token)
+/// gist and `AppError::External` if curl or the API fails
+pub async fn fetch_gist(gist_id: &str, token: Option<&str>) -> Result<Value, AppError> {
+    let url = format!("{}/gists/{}", GITHUB_API_URL, gist_id);
+    let mut gist = match api_request("GET", &url, token, None).await? {
+        (404, _) => return Err(AppError::NotFound(format!("Gist {} not found", gist_id))),
+        (200, body) => body,
+        (status, body) => return Err(api_error(status, &body)),
+    };
+
+    if let Some(files) = gist.get_mut("files").and_then(Value::as_object_mut) {
+        for file in files.values_mut() {
+            let truncated = file.get("truncated").and_then(Value::as_bool) == Some(true);
+            let raw_url = file
+                .get("raw_url")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            if let (true, Some(raw_url)) = (truncated, raw_url) {
+                let content = fetch_raw(&raw_url, token).await?;
+                file["content"] = Value::String(content);
+            }
+        }
+    }
+
+    Ok(gist)
+}
+
+/// Creates a gist
+///
+/// # Errors
+///
+/// Returns `AppError::External` if curl fails or GitHub rejects the request
+pub async fn create_gist(payload: &Value, token: &str) -> Result<GistExportResult, AppError> {
+    let url = format!("{}/gists", GITHUB_API_URL);
+    match api_request("POST", &url, Some(token), Some(payload)).await? {
+        (201, body) => {
+            let field = |key: &str| body.get(key).and_then(Value::as_str).map(str::to_string);
+            match (field("id"), field("html_url")) {
+                (Some(gist_id), Some(url)) => Ok(GistExportResult { gist_id, url }),
+                _ => Err(AppError::External(
+                    "GitHub returned an unexpected response".to_string(),
+                )),
+            }
+        }
+        (status, body) => Err(api_error(status, &body)),
+    }
+}
+
+fn api_error(status: u16, body: &Value) -> AppError {
+    let message = body
+        .get("message")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown error");
+    match status {
+        401 => AppError::External(format!("GitHub rejected the access token: {}", message)),
+        _ => AppError::External(format!("GitHub API error {}: {}", status, message)),
+    }
+}
+
+/// Downloads a file, such as a gist's raw file URL
+///
+/// An `http://` URL may be fetched over http; an `https://` one, and any
+/// redirect from it, only over https.
+///
+/// # Errors
+///
+/// Returns `AppError::External` if curl fails or the server does not answer 200
+pub async fn fetch_raw(url: &str, token: Option<&str>) -> Result<String, AppError> {
+    let https_only = !url.starts_with("http://");
+    let config = curl_config("GET", url, &[], token, None, https_only);
+    let (status, body) = run_curl(&config).await?;
+    if status != 200 {
+        return Err(AppError::External(format!(
+            "Failed to download {} ({})",
+            url, status
+        )));
+    }
+    Ok(body)
+}
+
+/// Sends a request over https with curl, returning the status code and body
+///
+/// Neither the request nor a redirect may use another protocol.
+///
+/// # Errors
+///
+/// Returns `AppError::External` if curl fails
+pub async fn send_request(
+    method: &str,
+    url: &str,
+    headers: &[&str],
+    body: Option<&str>,
+) -> Result<(u16, String), AppError> {
+    run_curl(&curl_config(method, url, headers, None, body, true)).await
+}
+
+/// Sends a GitHub API request with curl and parses the JSON response
+async fn api_request(
+    method: &str,
+    url: &str,
+    token: Option<&str>,
+    body: Option<&Value>,
+) -> Result<(u16, Value), AppError> {
+    let mut headers = GITHUB_HEADERS.to_vec();
+    if body.is_some() {
+        headers.push("Content-Type: application/json");
+    }
+    let body = body.map(Value::to_string);
+    let config = curl_config(method, url, &headers, token, body.as_deref(), true);
+    let (status, text) = run_curl(&config).await?;
+    let body = serde_json::from_str(&text).unwrap_or(Value::Null);
+    Ok((status, body))
+}
+
+/// Builds a curl config file
+///
+/// The config is passed on stdin rather than as arguments so the access
+/// token never appears in the process list. With `https_only`, curl refuses
+/// any other protocol for the request and for redirects it follows.
+fn curl_config(
+    method: &str,
+    url: &str,
+    headers: &[&str],
+    token: Option<&str>,
+    body: Option<&str>,
+    https_only: bool,
+) -> String {
+    let mut config = String::new();
+    let mut option = |name: &str, value: &str| {
+        config.push_str(&format!("{} = \"{}\"\n", name, escape_config_value(value)));
+    };
+
+    option("url", url);
+    option("request", method);
+    let protocols = if https_only { "=https" } else { "=http,https" };
+    option("proto", protocols);
+    option("proto-redir", protocols);
+    for header in headers {
+        option("header", header);
+    }
+    option("user-agent", "snips");
+    if let Some(token) = token {
+        option("header", &format!("Authorization: Bearer {}", token));
+    }
+    if let Some(body) = body {
+        option("data-binary", body);
+    }
+    option("max-time", &REQUEST_TIMEOUT_SECS.to_string());
+    option("write-out", "\n%{http_code}");
+    config
+}
+
+fn escape_config_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t")
+}
+
+/// Runs curl with the given config, returning the status code and body
+async fn run_curl(config: &str) -> Result<(u16, String), AppError> {
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--location", "--config", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::External(format!("Failed to run curl: {}", e)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(config.as_bytes())
+            .await
+            .map_err(|e| AppError::External(format!("Failed to write to curl: {}", e)))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| AppError::External(format!("Failed to run curl: {}", e)))?;
+    if !output.status.success() {
+        return Err(AppError::External(format!(
+            "Request failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = stdout.rsplit_once('\n').unwrap_or(("", &stdout));
+    let status = status
+        .trim()
+        .parse()
+        .map_err(|_| AppError::External("Malformed response from curl".to_string()))?;
+    Ok((status, body.to_string()))
+}
+
+/// Parses GitHub's `YYYY-MM-DDTHH:MM:SSZ` timestamps
+fn parse_github_timestamp(value: Option<&Value>) -> Option<i64> {
+    let text = value?.as_str()?;
+    time::OffsetDateTime::parse(text, &time::format_description::well_known::Rfc3339)
+        .ok()
+        .map(|datetime| datetime.unix_timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::snippet::{SnippetId, SnippetKind};
+
+    #[test]
+    fn test_parse_gist_id() {
+        assert_eq!(
+            parse_gist_id("aa5a315d61ae9438b18d").as_deref(),
+            Some("aa5a315d61ae9438b18d")
+        );
+        assert_eq!(
+            parse_gist_id("https://gist.github.com/octocat/aa5a315d61ae9438b18d#file-hello-rb")
+                .as_deref(),
+            Some("aa5a315d61ae9438b18d")
+        );
+        assert_eq!(
+            parse_gist_id("https://gist.github.com/aa5a315d61ae9438b18d").as_deref(),
+            Some("aa5a315d61ae9438b18d")
+        );
+        assert_eq!(parse_gist_id(""), None);
+        assert_eq!(parse_gist_id("../etc"), None);
+    }
+
+    #[test]
+    fn test_gist_to_snippets() {
+        let gist = json!({
+            "description": "Handy scripts",
+            "created_at": "2024-01-02T03:04:05Z",
+            "updated_at": "2024-01-03T03:04:05Z",
+            "files": {
+                "deploy.sh": { "language": "Shell", "content": "#!/bin/sh\nmake deploy" },
+                "notes.txt": { "language": null, "content": "plain" },
+                "empty.md": { "language": "Markdown", "content": "  " }
+            }
+        });
+        let snippets = gist_to_snippets(&gist, 0);
+
+        assert_eq!(snippets.len(), 2);
+        let deploy = snippets.iter().find(|s| s.name == "deploy.sh").unwrap();
+        assert_eq!(deploy.tags, vec!["shell"]);
+        assert_eq!(deploy.description.as_deref(), Some("Handy scripts"));
+        assert_eq!(deploy.created_at, 1_704_164_645);
+        let notes = snippets.iter().find(|s| s.name == "notes.txt").unwrap();
+        assert!(notes.tags.is_empty());
+    }
+
+    #[test]
+    fn test_snippet_to_gist_payload() {
+        let snippet = Snippet {
+            id: SnippetId(1),
+            name: "Parse args".to_string(),
+            content: "fn main() {}".to_string(),
+            description: None,
+            created_at: 0,
+            updated_at: 0,
+            tags: Some(vec!["cli".to_string(), "rust".to_string()]),
+            pinned: false,
+            kind: SnippetKind::Static,
+            language: None,
+            archived: false,
+            expires_at: None,
+        };
+        let payload = snippet_to_gist_payload(&snippet, false);
+        assert_eq!(payload["public"], false);
+        assert_eq!(payload["description"], "Parse args");
+        assert_eq!(payload["files"]["Parse args.rs"]["content"], "fn main() {}");
+    }
+
+    #[test]
+    fn test_curl_config_escapes_values() {
+        let config = curl_config(
+            "POST",
+            "https://x",
+            &[],
+            Some("tok"),
+            Some("{\"a\":\"b\\n\"}"),
+            true,
+        );
+        assert!(config.contains("header = \"Authorization: Bearer tok\"\n"));
+        assert!(config.contains("data-binary = \"{\\\"a\\\":\\\"b\\\\n\\\"}\"\n"));
+    }
+
+    #[test]
+    fn test_curl_config_headers_and_protocols() {
+        let config = curl_config("GET", "https://x", &[], None, None, true);
+        assert!(!config.contains("github"));
+        assert!(!config.contains("Content-Type"));
+        assert!(config.contains("proto = \"=https\"\n"));
+        assert!(config.contains("proto-redir = \"=https\"\n"));
+
+        let config = curl_config("GET", "http://x", GITHUB_HEADERS, None, None, false);
+        assert!(config.contains("header = \"X-GitHub-Api-Version: 2022-11-28\"\n"));
+        assert!(config.contains("proto-redir = \"=http,https\"\n"));
+    }
+}
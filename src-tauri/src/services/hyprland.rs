@@ -0,0 +1,267 @@
+/// Hyprland IPC over the compositor's request socket.
+///
+/// Hyprland ignores `always_on_top` and client-side positioning, and only
+/// hands focus to a window on the active workspace. Overlays therefore get
+/// float and pin window rules at startup, and every window shown is moved
+/// to the active workspace and focused through the compositor once it has
+/// been mapped, instead of hoping `set_focus` lands.
+use crate::utils::error::AppError;
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How long to wait for a newly shown window to be mapped by the compositor
+const MAP_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Interval between client list checks while waiting
+const MAP_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// A window as listed by `j/clients`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyprClient {
+    pub address: String,
+    pub pinned: bool,
+    pub workspace_id: i64,
+}
+
+/// Path of the request socket of the running Hyprland instance
+///
+/// Hyprland 0.40 moved the socket from `/tmp/hypr` to `$XDG_RUNTIME_DIR/hypr`.
+pub fn socket_path() -> Option<PathBuf> {
+    let signature = std::env::var("HYPRLAND_INSTANCE_SIGNATURE")
+        .ok()
+        .filter(|s| !s.is_empty())?;
+    let candidates = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .into_iter()
+        .chain([PathBuf::from("/tmp")]);
+    candidates
+        .map(|dir| dir.join("hypr").join(&signature).join(".socket.sock"))
+        .find(|path| path.exists())
+}
+
+/// Sends one request and returns the reply
+#[cfg(unix)]
+pub fn request(command: &str) -> Result<String, AppError> {
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+
+    let path = socket_path()
+        .ok_or_else(|| AppError::Unsupported("Hyprland socket not found".to_string()))?;
+    let to_error = |e: std::io::Error| AppError::External(format!("Hyprland IPC failed: {}", e));
+    let mut stream = UnixStream::connect(path).map_err(to_error)?;
+    stream
+        .set_read_timeout(Some(Duration::from_secs(1)))
+        .map_err(to_error)?;
+    stream.write_all(command.as_bytes()).map_err(to_error)?;
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply).map_err(to_error)?;
+    Ok(reply)
+}
+
+#[cfg(not(unix))]
+pub fn request(_command: &str) -> Result<String, AppError> {
+    Err(AppError::Unsupported(
+        "Hyprland IPC needs Unix sockets".to_string(),
+    ))
+}
+
+/// Joins commands into one batch request, applied in order
+pub fn batch(commands: &[String]) -> String {
+    format!("[[BATCH]]{}", commands.join(";"))
+}
+
+/// Sends commands as one batch, failing if any of them is rejected
+///
+/// Hyprland answers each command with `ok` or an error message.
+pub fn run_batch(commands: &[String]) -> Result<(), AppError> {
+    if commands.is_empty() {
+        return Ok(());
+    }
+    let reply = request(&batch(commands))?;
+    if batch_succeeded(&reply) {
+        Ok(())
+    } else {
+        Err(AppError::External(format!(
+            "Hyprland rejected a request: {}",
+            reply.trim()
+        )))
+    }
+}
+
+/// Whether every reply in a batch reply is `ok`
+pub fn batch_succeeded(reply: &str) -> bool {
+    reply.replace("ok", "").trim().is_empty()
+}
+
+/// Escapes a window title for use in a window rule regex
+pub fn escape_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Rules that float overlays and pin them to every workspace
+pub fn overlay_rules(titles: &[&str]) -> Vec<String> {
+    titles
+        .iter()
+        .flat_map(|title| {
+            let matcher = format!("title:^({})$", escape_regex(title));
+            ["float", "pin"].map(|rule| format!("keyword windowrulev2 {}, {}", rule, matcher))
+        })
+        .collect()
+}
+
+/// Registers the overlay window rules for this session
+///
+/// Rules only apply to windows mapped after they are added, so this runs
+/// before any window is shown.
+pub fn install_overlay_rules(titles: &[&str]) -> Result<(), AppError> {
+    run_batch(&overlay_rules(titles))
+}
+
+/// Finds a window of this process by title in `j/clients` output
+pub fn find_client(clients: &str, pid: u32, title: &str) -> Option<HyprClient> {
+    let clients: Value = serde_json::from_str(clients).ok()?;
+    clients.as_array()?.iter().find_map(|client| {
+        if client.get("pid")?.as_u64()? != u64::from(pid) || client.get("title")?.as_str()? != title
+        {
+            return None;
+        }
+        Some(HyprClient {
+            address: client.get("address")?.as_str()?.to_string(),
+            pinned: client.get("pinned")?.as_bool()?,
+            workspace_id: client.get("workspace")?.get("id")?.as_i64()?,
+        })
+    })
+}
+
+/// ID of the active workspace in `j/activeworkspace` output
+pub fn parse_workspace_id(workspace: &str) -> Option<i64> {
+    serde_json::from_str::<Value>(workspace)
+        .ok()?
+        .get("id")?
+        .as_i64()
+}
+
+/// Dispatches that bring a client to the active workspace and focus it
+///
+/// Pinned windows are on every workspace already.
+pub fn present_commands(client: &HyprClient, active_workspace: Option<i64>) -> Vec<String> {
+    let target = format!("address:{}", client.address);
+    let mut commands = Vec::new();
+    if let Some(workspace) = active_workspace {
+        if !client.pinned && client.workspace_id != workspace {
+            commands.push(format!(
+                "dispatch movetoworkspacesilent {},{}",
+                workspace, target
+            ));
+        }
+    }
+    commands.push(format!("dispatch focuswindow {}", target));
+    commands
+}
+
+/// Waits until this process has a mapped window with the given title
+fn wait_for_client(title: &str) -> Result<Option<HyprClient>, AppError> {
+    let pid = std::process::id();
+    let started = std::time::Instant::now();
+    loop {
+        if let Some(client) = find_client(&request("j/clients")?, pid, title) {
+            return Ok(Some(client));
+        }
+        if started.elapsed() >= MAP_TIMEOUT {
+            return Ok(None);
+        }
+        std::thread::sleep(MAP_POLL_INTERVAL);
+    }
+}
+
+/// Moves a just-shown window to the active workspace and focuses it
+///
+/// Blocks while the compositor maps the window; call it off the main thread.
+pub fn present_window(title: &str) -> Result<(), AppError> {
+    let Some(client) = wait_for_client(title)? else {
+        return Err(AppError::External(format!(
+            "Window \"{}\" was not mapped by Hyprland",
+            title
+        )));
+    };
+    let active_workspace = parse_workspace_id(&request("j/activeworkspace")?);
+    run_batch(&present_commands(&client, active_workspace))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlay_rules() {
+        assert_eq!(
+            overlay_rules(&["Snips", "Quick Add (beta)"]),
+            vec![
+                "keyword windowrulev2 float, title:^(Snips)$",
+                "keyword windowrulev2 pin, title:^(Snips)$",
+                "keyword windowrulev2 float, title:^(Quick Add \\(beta\\))$",
+                "keyword windowrulev2 pin, title:^(Quick Add \\(beta\\))$",
+            ]
+        );
+        assert_eq!(
+            batch(&["dispatch a".to_string(), "dispatch b".to_string()]),
+            "[[BATCH]]dispatch a;dispatch b"
+        );
+        assert!(batch_succeeded("ok\n\nok\n\n"));
+        assert!(!batch_succeeded("ok\n\nInvalid dispatcher\n\n"));
+    }
+
+    #[test]
+    fn test_find_client() {
+        let clients = r#"[
+            {"address": "0x1", "pid": 10, "title": "Snips", "floating": true,
+             "pinned": false, "workspace": {"id": 1, "name": "1"}},
+            {"address": "0x2", "pid": 20, "title": "Snips", "floating": true,
+             "pinned": true, "workspace": {"id": 3, "name": "3"}},
+            {"address": "0x3", "pid": 20, "title": "Quick Add Snippet", "floating": false,
+             "pinned": false, "workspace": {"id": 2, "name": "2"}}
+        ]"#;
+        let found = find_client(clients, 20, "Snips").unwrap();
+        assert_eq!(found.address, "0x2");
+        assert!(found.pinned);
+        assert_eq!(find_client(clients, 20, "Settings"), None);
+        assert_eq!(find_client("not json", 20, "Snips"), None);
+
+        assert_eq!(parse_workspace_id(r#"{"id": 4, "name": "4"}"#), Some(4));
+        assert_eq!(parse_workspace_id("{}"), None);
+    }
+
+    #[test]
+    fn test_present_commands() {
+        let client = HyprClient {
+            address: "0xabc".to_string(),
+            pinned: false,
+            workspace_id: 1,
+        };
+        assert_eq!(
+            present_commands(&client, Some(2)),
+            vec![
+                "dispatch movetoworkspacesilent 2,address:0xabc",
+                "dispatch focuswindow address:0xabc",
+            ]
+        );
+        assert_eq!(
+            present_commands(&client, Some(1)),
+            vec!["dispatch focuswindow address:0xabc"]
+        );
+
+        let pinned = HyprClient {
+            pinned: true,
+            ..client
+        };
+        assert_eq!(present_commands(&pinned, Some(2)).len(), 1);
+    }
+}
@@ -0,0 +1,285 @@
+use crate::utils::error::AppError;
+use crate::utils::time::current_timestamp;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use tauri::{AppHandle, Emitter};
+
+/// Event emitted as an import writes snippets, with an `ImportProgress`
+pub const IMPORT_PROGRESS_EVENT: &str = "import-progress";
+
+/// Progress of an import that has not finished yet
+///
+/// Jobs are keyed by the SHA-256 of the import file, so a renamed or moved
+/// file still resumes while an edited one starts over.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportJob {
+    pub id: i64,
+    pub file_path: String,
+    pub file_hash: String,
+    /// Import format, e.g. `json`
+    pub format: String,
+    pub total_items: i64,
+    /// Items handled so far (imported or skipped); resumption starts here
+    pub processed_items: i64,
+    pub imported_items: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// How far a running import has got
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportProgress {
+    pub job_id: i64,
+    /// Items handled so far, including any from an earlier run of the job
+    pub processed: usize,
+    pub total: usize,
+}
+
+const JOB_COLUMNS: &str = "id, file_path, file_hash, format, total_items, processed_items,
+     imported_items, created_at, updated_at";
+
+/// Hash identifying an import file's contents
+pub fn content_hash(contents: &[u8]) -> String {
+    hex::encode(Sha256::digest(contents))
+}
+
+/// Finds the unfinished job for a file, if any
+///
+/// # Errors
+///
+/// Returns `AppError` if the database query fails
+pub async fn find_unfinished(
+    pool: &SqlitePool,
+    file_hash: &str,
+    format: &str,
+) -> Result<Option<ImportJob>, AppError> {
+    let row = sqlx::query(&format!(
+        "SELECT {} FROM import_jobs WHERE file_hash = ? AND format = ?",
+        JOB_COLUMNS
+    ))
+    .bind(file_hash)
+    .bind(format)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.as_ref().map(job_from_row))
+}
+
+/// Lists every unfinished job, most recently active first
+///
+/// # Errors
+///
+/// Returns `AppError` if the database query fails
+pub async fn list_unfinished(pool: &SqlitePool) -> Result<Vec<ImportJob>, AppError> {
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM import_jobs ORDER BY updated_at DESC, id DESC",
+        JOB_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.iter().map(job_from_row).collect())
+}
+
+/// Records the start of a new import, replacing any earlier job for the file
+///
+/// # Errors
+///
+/// Returns `AppError` if the database write fails
+pub async fn start(
+    pool: &SqlitePool,
+    file_path: &str,
+    file_hash: &str,
+    format: &str,
+    total_items: i64,
+) -> Result<ImportJob, AppError> {
+    let now = current_timestamp();
+    let result = sqlx::query(
+        "INSERT OR REPLACE INTO import_jobs
+         (file_path, file_hash, format, total_items, processed_items, imported_items,
+          created_at, updated_at)
+         VALUES (?, ?, ?, ?, 0, 0, ?, ?)",
+    )
+    .bind(file_path)
+    .bind(file_hash)
+    .bind(format)
+    .bind(total_items)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await?;
+
+    Ok(ImportJob {
+        id: result.last_insert_rowid(),
+        file_path: file_path.to_string(),
+        file_hash: file_hash.to_string(),
+        format: format.to_string(),
+        total_items,
+        processed_items: 0,
+        imported_items: 0,
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+/// Saves progress for a job
+///
+/// Takes a connection so callers can record progress in the same transaction
+/// as the items it covers; after a crash the job then never points past data
+/// that was rolled back.
+///
+/// # Errors
+///
+/// Returns `AppError` if the database write fails
+pub async fn record_progress(
+    conn: &mut SqliteConnection,
+    id: i64,
+    processed_items: i64,
+    imported_items: i64,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "UPDATE import_jobs SET processed_items = ?, imported_items = ?, updated_at = ?
+         WHERE id = ?",
+    )
+    .bind(processed_items)
+    .bind(imported_items)
+    .bind(current_timestamp())
+    .bind(id)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Removes a job, either because it finished or the user declined to resume it
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if no job has the given ID
+pub async fn remove(pool: &SqlitePool, id: i64) -> Result<(), AppError> {
+    let result = sqlx::query("DELETE FROM import_jobs WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Import job {} not found", id)));
+    }
+    Ok(())
+}
+
+/// Removes a finished job inside the transaction that wrote its items
+///
+/// # Errors
+///
+/// Returns `AppError` if the database write fails
+pub async fn finish(conn: &mut SqliteConnection, id: i64) -> Result<(), AppError> {
+    sqlx::query("DELETE FROM import_jobs WHERE id = ?")
+        .bind(id)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Tells the frontend how far an import has got; a failed emit is only logged
+pub fn emit_progress(app: &AppHandle, job_id: i64, processed: usize, total: usize) {
+    let progress = ImportProgress {
+        job_id,
+        processed,
+        total,
+    };
+    if let Err(e) = app.emit(IMPORT_PROGRESS_EVENT, progress) {
+        eprintln!("Failed to emit import progress: {}", e);
+    }
+}
+
+fn job_from_row(row: &SqliteRow) -> ImportJob {
+    ImportJob {
+        id: row.get("id"),
+        file_path: row.get("file_path"),
+        file_hash: row.get("file_hash"),
+        format: row.get("format"),
+        total_items: row.get("total_items"),
+        processed_items: row.get("processed_items"),
+        imported_items: row.get("imported_items"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        sqlx::raw_sql(include_str!("../migrations/008_create_import_jobs.sql"))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_job_lifecycle() {
+        let pool = setup_test_db().await;
+        assert!(find_unfinished(&pool, "abc", "json")
+            .await
+            .unwrap()
+            .is_none());
+
+        let job = start(&pool, "/tmp/big.json", "abc", "json", 5000)
+            .await
+            .unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        record_progress(&mut conn, job.id, 1200, 1100)
+            .await
+            .unwrap();
+        drop(conn);
+
+        let found = find_unfinished(&pool, "abc", "json")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, job.id);
+        assert_eq!(found.processed_items, 1200);
+        assert_eq!(found.imported_items, 1100);
+        assert!(find_unfinished(&pool, "abc", "markdown")
+            .await
+            .unwrap()
+            .is_none());
+
+        assert_eq!(list_unfinished(&pool).await.unwrap().len(), 1);
+        remove(&pool, job.id).await.unwrap();
+        assert!(list_unfinished(&pool).await.unwrap().is_empty());
+        assert!(matches!(
+            remove(&pool, job.id).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_restart_replaces_job() {
+        let pool = setup_test_db().await;
+        let first = start(&pool, "/a.json", "abc", "json", 10).await.unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        record_progress(&mut conn, first.id, 5, 5).await.unwrap();
+        drop(conn);
+
+        let second = start(&pool, "/b.json", "abc", "json", 10).await.unwrap();
+        let jobs = list_unfinished(&pool).await.unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].id, second.id);
+        assert_eq!(jobs[0].processed_items, 0);
+        assert_eq!(jobs[0].file_path, "/b.json");
+    }
+}
@@ -0,0 +1,367 @@
+use crate::models::snippet::{CreateSnippetInput, SnippetKind};
+use crate::utils::error::AppError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use zip::ZipArchive;
+
+/// Largest file accepted from inside a snippet bundle
+const MAX_ENTRY_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Largest total decompressed size accepted for a snippet bundle
+const MAX_TOTAL_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Most files accepted in a snippet bundle
+const MAX_ENTRIES: usize = 10_000;
+
+/// Snippet manager a library is imported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportSource {
+    /// Alfred `.alfredsnippets` bundle (zip of one JSON file per snippet)
+    Alfred,
+    /// Raycast snippet export (JSON array)
+    Raycast,
+}
+
+impl ImportSource {
+    /// Guesses the source from the file extension
+    pub fn detect(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "alfredsnippets" | "zip" => Some(Self::Alfred),
+            "json" => Some(Self::Raycast),
+            _ => None,
+        }
+    }
+}
+
+/// Outcome of importing a library from another snippet manager
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub source: ImportSource,
+    pub imported: usize,
+    /// Names that were skipped because a snippet with that name already exists
+    pub skipped: Vec<String>,
+}
+
+/// Reads a snippet library exported from another app
+///
+/// # Arguments
+///
+/// * `path` - The exported file
+/// * `source` - Format of the file; detected from the extension when `None`
+///
+/// # Returns
+///
+/// The detected source and the snippets it contains. Alfred snippets are
+/// tagged with the collection (file) name.
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidInput` if the format cannot be determined or the
+/// file is malformed
+pub fn read_library(
+    path: &Path,
+    source: Option<ImportSource>,
+) -> Result<(ImportSource, Vec<CreateSnippetInput>), AppError> {
+    let source = source
+        .or_else(|| ImportSource::detect(path))
+        .ok_or_else(|| {
+            AppError::InvalidInput(format!(
+                "Cannot determine snippet format of {}",
+                path.display()
+            ))
+        })?;
+
+    let bytes = std::fs::read(path)
+        .map_err(|e| AppError::InvalidInput(format!("Failed to read {}: {}", path.display(), e)))?;
+
+    let snippets = match source {
+        ImportSource::Alfred => {
+            let collection = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string());
+            parse_alfred_bundle(&bytes, collection.as_deref())?
+        }
+        ImportSource::Raycast => {
+            let text = String::from_utf8(bytes).map_err(|_| {
+                AppError::InvalidInput("Raycast export is not valid UTF-8".to_string())
+            })?;
+            parse_raycast_export(&text)?
+        }
+    };
+
+    Ok((source, snippets))
+}
+
+/// Parses an Alfred `.alfredsnippets` bundle
+///
+/// Each `*.json` entry holds `{"alfredsnippet": {"name", "snippet", "keyword"}}`.
+/// Alfred placeholders such as `{clipboard}` are kept as written.
+///
+/// # Arguments
+///
+/// * `bytes` - Contents of the bundle
+/// * `collection` - Collection name, added as a tag to every snippet
+pub fn parse_alfred_bundle(
+    bytes: &[u8],
+    collection: Option<&str>,
+) -> Result<Vec<CreateSnippetInput>, AppError> {
+    let mut entries = read_zip_entries(bytes)?;
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let collection_tag = collection.map(str::trim).filter(|c| !c.is_empty());
+    let mut snippets = Vec::new();
+    for (name, data) in entries {
+        if !name.ends_with(".json") {
+            continue;
+        }
+        let document: Value = serde_json::from_slice(&data)
+            .map_err(|e| AppError::InvalidInput(format!("Invalid snippet {}: {}", name, e)))?;
+        let Some(entry) = document.get("alfredsnippet") else {
+            continue;
+        };
+
+        let content = string_field(entry, "snippet");
+        let keyword = string_field(entry, "keyword");
+        let title = string_field(entry, "name");
+        let Some(input) = to_input(title.or(keyword.clone()), content, keyword, collection_tag)
+        else {
+            continue;
+        };
+        snippets.push(input);
+    }
+
+    Ok(snippets)
+}
+
+/// Parses a Raycast snippet export
+///
+/// The export is a JSON array of `{"name", "text", "keyword"}` objects.
+/// Raycast placeholders such as `{cursor}` are kept as written.
+pub fn parse_raycast_export(text: &str) -> Result<Vec<CreateSnippetInput>, AppError> {
+    let document: Value = serde_json::from_str(text.trim_start_matches('\u{feff}'))
+        .map_err(|e| AppError::InvalidInput(format!("Invalid Raycast export: {}", e)))?;
+    let Value::Array(items) = document else {
+        return Err(AppError::InvalidInput(
+            "Invalid Raycast export: expected an array of snippets".to_string(),
+        ));
+    };
+
+    Ok(items
+        .iter()
+        .filter_map(|item| {
+            let keyword = string_field(item, "keyword");
+            to_input(
+                string_field(item, "name"),
+                string_field(item, "text"),
+                keyword,
+                None,
+            )
+        })
+        .collect())
+}
+
+fn string_field(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Builds a snippet, recording the trigger keyword in the description since
+/// Snips has no keyword field
+fn to_input(
+    name: Option<String>,
+    content: Option<String>,
+    keyword: Option<String>,
+    collection: Option<&str>,
+) -> Option<CreateSnippetInput> {
+    Some(CreateSnippetInput {
+        name: name?,
+        content: content?,
+        description: keyword.map(|keyword| format!("Keyword: {}", keyword)),
+        tags: collection.map(str::to_string).into_iter().collect(),
+        kind: SnippetKind::Static,
+    })
+}
+
+/// Extracts all files from a zip archive
+///
+/// Sizes are checked against what is actually decompressed rather than what
+/// the archive declares, so a crafted archive cannot exhaust memory.
+pub(crate) fn read_zip_entries(bytes: &[u8]) -> Result<Vec<(String, Vec<u8>)>, AppError> {
+    let invalid =
+        |reason: String| AppError::InvalidInput(format!("Invalid zip archive: {}", reason));
+
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(|e| invalid(e.to_string()))?;
+    if archive.len() > MAX_ENTRIES {
+        return Err(invalid(format!("more than {} entries", MAX_ENTRIES)));
+    }
+
+    let mut entries = Vec::new();
+    let mut total_size = 0;
+    for index in 0..archive.len() {
+        let file = archive
+            .by_index(index)
+            .map_err(|e| invalid(e.to_string()))?;
+        if file.is_dir() {
+            continue;
+        }
+        let name = file.name().to_string();
+
+        // Read one byte past the limit to detect oversized entries
+        let limit = MAX_ENTRY_SIZE.min(MAX_TOTAL_SIZE - total_size);
+        let mut data = Vec::new();
+        file.take(limit + 1)
+            .read_to_end(&mut data)
+            .map_err(|e| invalid(format!("{}: {}", name, e)))?;
+        let size = data.len() as u64;
+        if size > MAX_ENTRY_SIZE {
+            return Err(invalid(format!("{} is too large", name)));
+        }
+        if size > limit {
+            return Err(invalid(format!(
+                "contents are larger than {} MiB",
+                MAX_TOTAL_SIZE / (1024 * 1024)
+            )));
+        }
+        total_size += size;
+        entries.push((name, data));
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::{CompressionMethod, ZipWriter};
+
+    /// Builds a zip archive; entries are deflated when `deflate` is set
+    fn build_zip<C: AsRef<[u8]>>(files: &[(&str, C)], deflate: bool) -> Vec<u8> {
+        let method = if deflate {
+            CompressionMethod::Deflated
+        } else {
+            CompressionMethod::Stored
+        };
+        let options = SimpleFileOptions::default().compression_method(method);
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(content.as_ref()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_parse_alfred_bundle() {
+        for deflate in [false, true] {
+            let bundle = build_zip(
+                &[
+                    ("info.plist", "<plist></plist>"),
+                    (
+                        "Signature [A1].json",
+                        r#"{"alfredsnippet":{"snippet":"Best,\nMe","uid":"A1","name":"Signature","keyword":"!sig"}}"#,
+                    ),
+                    (
+                        "Date [B2].json",
+                        r#"{"alfredsnippet":{"snippet":"{date}","uid":"B2","name":"","keyword":"!d"}}"#,
+                    ),
+                    (
+                        "Empty [C3].json",
+                        r#"{"alfredsnippet":{"snippet":"","uid":"C3","name":"Empty"}}"#,
+                    ),
+                ],
+                deflate,
+            );
+
+            let snippets = parse_alfred_bundle(&bundle, Some("Work")).unwrap();
+            assert_eq!(snippets.len(), 2);
+            assert_eq!(snippets[0].name, "!d");
+            assert_eq!(snippets[0].content, "{date}");
+            assert_eq!(snippets[1].name, "Signature");
+            assert_eq!(snippets[1].content, "Best,\nMe");
+            assert_eq!(snippets[1].description.as_deref(), Some("Keyword: !sig"));
+            assert_eq!(snippets[1].tags, vec!["Work"]);
+        }
+    }
+
+    #[test]
+    fn test_parse_raycast_export() {
+        let text = r#"[
+            {"name": "Email", "text": "me@example.com", "keyword": "@@"},
+            {"name": "Greeting", "text": "Hi {cursor}"},
+            {"name": "", "text": "nameless"}
+        ]"#;
+        let snippets = parse_raycast_export(text).unwrap();
+        assert_eq!(snippets.len(), 2);
+        assert_eq!(snippets[0].name, "Email");
+        assert_eq!(snippets[0].description.as_deref(), Some("Keyword: @@"));
+        assert_eq!(snippets[1].content, "Hi {cursor}");
+        assert!(snippets[1].description.is_none());
+        assert!(snippets[1].tags.is_empty());
+
+        assert!(parse_raycast_export("{}").is_err());
+    }
+
+    #[test]
+    fn test_invalid_zip_is_rejected() {
+        assert!(matches!(
+            parse_alfred_bundle(b"not a zip", None),
+            Err(AppError::InvalidInput(_))
+        ));
+        let mut truncated = build_zip(&[("a.json", "{}")], false);
+        truncated.truncate(truncated.len() - 10);
+        assert!(parse_alfred_bundle(&truncated, None).is_err());
+    }
+
+    #[test]
+    fn test_oversized_zip_is_rejected() {
+        let zeros = vec![0u8; 15 * 1024 * 1024];
+        let names: Vec<String> = (0..5).map(|i| format!("{}.json", i)).collect();
+        let files: Vec<(&str, &[u8])> = names
+            .iter()
+            .map(|name| (name.as_str(), zeros.as_slice()))
+            .collect();
+        let bundle = build_zip(&files, true);
+        assert!(read_zip_entries(&build_zip(&files[..4], true)).is_ok());
+        let error = read_zip_entries(&bundle).unwrap_err().to_string();
+        assert!(error.contains("larger than 64 MiB"));
+
+        let huge = vec![0u8; MAX_ENTRY_SIZE as usize + 1];
+        let error = read_zip_entries(&build_zip(&[("big.json", huge)], true))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("big.json is too large"));
+    }
+
+    #[test]
+    fn test_zip_entry_count_is_limited() {
+        let names: Vec<String> = (0..=MAX_ENTRIES).map(|i| format!("{}.json", i)).collect();
+        let files: Vec<(&str, &str)> = names.iter().map(|name| (name.as_str(), "")).collect();
+        let error = read_zip_entries(&build_zip(&files, false))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("more than 10000 entries"));
+    }
+
+    #[test]
+    fn test_detect_source() {
+        assert_eq!(
+            ImportSource::detect(Path::new("Work.alfredsnippets")),
+            Some(ImportSource::Alfred)
+        );
+        assert_eq!(
+            ImportSource::detect(Path::new("snippets.json")),
+            Some(ImportSource::Raycast)
+        );
+        assert_eq!(ImportSource::detect(Path::new("notes.txt")), None);
+    }
+}
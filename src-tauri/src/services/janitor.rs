@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Kinds of temporary files that a crash can leave in the app data directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactKind {
+    /// `snips_pre_restore.db` safety copy written by `restore_database`
+    PreRestoreBackup,
+    /// `.write_test` files used to probe directory permissions
+    WriteTest,
+    /// Half-written export files (`*.partial`)
+    PartialExport,
+    /// Scratch files left by an interrupted backup verification (`.verify-*.tmp`)
+    VerificationScratch,
+    /// Lock files (`*.lock`) whose owner is gone
+    StaleLock,
+}
+
+impl ArtifactKind {
+    /// Minimum age before an artifact of this kind is considered abandoned
+    ///
+    /// The pre-restore copy is kept for a week so a bad restore can still be undone.
+    pub fn max_age(self) -> Duration {
+        match self {
+            Self::PreRestoreBackup => Duration::from_secs(7 * 24 * 3600),
+            Self::WriteTest | Self::PartialExport | Self::VerificationScratch => {
+                Duration::from_secs(3600)
+            }
+            Self::StaleLock => Duration::from_secs(24 * 3600),
+        }
+    }
+
+    /// Classifies a file by name; `None` means the janitor never touches it
+    ///
+    /// SQLite `-journal`/`-wal` files are deliberately not matched: they hold
+    /// data needed to recover the database after a crash.
+    pub fn classify(file_name: &str) -> Option<Self> {
+        if file_name == "snips_pre_restore.db" {
+            Some(Self::PreRestoreBackup)
+        } else if file_name.ends_with(".write_test") {
+            Some(Self::WriteTest)
+        } else if file_name.ends_with(".partial") {
+            Some(Self::PartialExport)
+        } else if file_name.starts_with(".verify-") && file_name.ends_with(".tmp") {
+            Some(Self::VerificationScratch)
+        } else if file_name.ends_with(".lock") {
+            Some(Self::StaleLock)
+        } else {
+            None
+        }
+    }
+}
+
+/// A temporary file removed by the janitor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemovedArtifact {
+    pub path: String,
+    pub kind: ArtifactKind,
+    pub size_bytes: u64,
+}
+
+/// Summary of a janitor run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JanitorReport {
+    pub removed: Vec<RemovedArtifact>,
+    /// Artifacts that were stale but could not be deleted, with the reason
+    pub failed: Vec<String>,
+    pub reclaimed_bytes: u64,
+}
+
+/// Removes abandoned temporary files from the app data directory
+///
+/// Scans `app_dir` and its `backups` subdirectory (non-recursively) for known
+/// temporary artifacts older than their kind's `max_age`, and deletes them.
+///
+/// # Arguments
+///
+/// * `app_dir` - The application data directory
+/// * `now` - Reference time used to compute file ages
+///
+/// # Returns
+///
+/// A `JanitorReport` listing what was removed. Unreadable directories are
+/// skipped and failed deletions are recorded in `failed`.
+pub fn clean_app_dir(app_dir: &Path, now: SystemTime) -> JanitorReport {
+    let mut report = JanitorReport::default();
+
+    for dir in [app_dir.to_path_buf(), app_dir.join("backups")] {
+        for (path, kind, size_bytes) in find_stale_artifacts(&dir, now) {
+            match std::fs::remove_file(&path) {
+                Ok(()) => {
+                    report.reclaimed_bytes += size_bytes;
+                    report.removed.push(RemovedArtifact {
+                        path: path.to_string_lossy().to_string(),
+                        kind,
+                        size_bytes,
+                    });
+                }
+                Err(e) => report.failed.push(format!("{}: {}", path.display(), e)),
+            }
+        }
+    }
+
+    report
+}
+
+fn find_stale_artifacts(dir: &Path, now: SystemTime) -> Vec<(PathBuf, ArtifactKind, u64)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let kind = ArtifactKind::classify(entry.file_name().to_str()?)?;
+            let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+            let age = now.duration_since(metadata.modified().ok()?).ok()?;
+            (age >= kind.max_age()).then(|| (entry.path(), kind, metadata.len()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("snips_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("backups")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(
+            ArtifactKind::classify("snips_pre_restore.db"),
+            Some(ArtifactKind::PreRestoreBackup)
+        );
+        assert_eq!(
+            ArtifactKind::classify("dir.write_test"),
+            Some(ArtifactKind::WriteTest)
+        );
+        assert_eq!(
+            ArtifactKind::classify("export.json.partial"),
+            Some(ArtifactKind::PartialExport)
+        );
+        assert_eq!(
+            ArtifactKind::classify(".verify-snips_backup_1.db.gz.tmp"),
+            Some(ArtifactKind::VerificationScratch)
+        );
+        assert_eq!(
+            ArtifactKind::classify("snips.lock"),
+            Some(ArtifactKind::StaleLock)
+        );
+        assert_eq!(ArtifactKind::classify("snips.db"), None);
+        assert_eq!(ArtifactKind::classify("snips.db-journal"), None);
+        assert_eq!(ArtifactKind::classify("snips_backup_1.db.gz"), None);
+    }
+
+    #[test]
+    fn test_clean_app_dir_removes_only_stale_artifacts() {
+        let dir = test_dir("janitor");
+        std::fs::write(dir.join("snips.db"), b"db").unwrap();
+        std::fs::write(dir.join("probe.write_test"), b"x").unwrap();
+        std::fs::write(dir.join("snips_pre_restore.db"), b"restore").unwrap();
+        std::fs::write(dir.join("backups").join(".verify-a.db.tmp"), b"scratch").unwrap();
+
+        // Two hours later: scratch files are stale, the pre-restore copy is not
+        let now = SystemTime::now() + Duration::from_secs(2 * 3600);
+        let report = clean_app_dir(&dir, now);
+
+        let mut kinds: Vec<ArtifactKind> = report.removed.iter().map(|r| r.kind).collect();
+        kinds.sort_by_key(|kind| format!("{:?}", kind));
+        assert_eq!(
+            kinds,
+            vec![ArtifactKind::VerificationScratch, ArtifactKind::WriteTest]
+        );
+        assert_eq!(report.reclaimed_bytes, 8);
+        assert!(report.failed.is_empty());
+        assert!(dir.join("snips.db").exists());
+        assert!(dir.join("snips_pre_restore.db").exists());
+
+        // A week later the pre-restore copy goes too
+        let later = SystemTime::now() + Duration::from_secs(8 * 24 * 3600);
+        let report = clean_app_dir(&dir, later);
+        assert_eq!(report.removed.len(), 1);
+        assert_eq!(report.removed[0].kind, ArtifactKind::PreRestoreBackup);
+        assert!(dir.join("snips.db").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_clean_missing_dir_is_empty() {
+        let report = clean_app_dir(Path::new("/nonexistent/snips"), SystemTime::now());
+        assert!(report.removed.is_empty());
+        assert!(report.failed.is_empty());
+    }
+}
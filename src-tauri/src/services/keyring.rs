@@ -0,0 +1,204 @@
+/// Credentials kept in the OS keychain instead of the settings table.
+///
+/// Secret Service holds them on Linux, the login Keychain on macOS and the
+/// Credential Manager on Windows. Each credential is stored under the app
+/// identifier as service and an account name such as `gist-access-token`.
+use crate::commands::settings_commands::SettingsServiceState;
+use crate::services::database::get_pool;
+use crate::services::settings::SettingsService;
+use crate::utils::error::AppError;
+use ::keyring::{Entry, Error as KeyringError};
+use std::future::Future;
+use tauri::{AppHandle, Manager};
+
+/// Service name the credentials are stored under
+const SERVICE: &str = "io.utensils.snips";
+
+/// Account holding the GitHub personal access token used for gists
+pub const GIST_ACCESS_TOKEN: &str = "gist-access-token";
+
+fn entry(account: &str) -> Result<Entry, AppError> {
+    Entry::new(SERVICE, account).map_err(keyring_error)
+}
+
+fn keyring_error(e: KeyringError) -> AppError {
+    AppError::External(format!("OS keychain error: {}", e))
+}
+
+/// Runs a keychain call off the async runtime; Secret Service may block while
+/// it asks the user to unlock the keyring
+async fn blocking<T, F>(f: F) -> Result<T, AppError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, AppError> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| AppError::Unknown(format!("Keychain task failed: {}", e)))?
+}
+
+/// Stores a secret, replacing any previous one for the account
+///
+/// # Errors
+///
+/// Returns `AppError::External` if the keychain is unavailable, e.g. when no
+/// Secret Service is running
+pub async fn store_credential(account: &str, secret: &str) -> Result<(), AppError> {
+    let account = account.to_string();
+    let secret = secret.to_string();
+    blocking(move || {
+        entry(&account)?
+            .set_password(&secret)
+            .map_err(keyring_error)
+    })
+    .await
+}
+
+/// Gets the secret for an account, or `None` if none is stored
+pub async fn get_credential(account: &str) -> Result<Option<String>, AppError> {
+    let account = account.to_string();
+    blocking(move || match entry(&account)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(KeyringError::NoEntry) => Ok(None),
+        Err(e) => Err(keyring_error(e)),
+    })
+    .await
+}
+
+/// Deletes the secret for an account; deleting a missing one is not an error
+pub async fn delete_credential(account: &str) -> Result<(), AppError> {
+    let account = account.to_string();
+    blocking(move || match entry(&account)?.delete_credential() {
+        Ok(()) | Err(KeyringError::NoEntry) => Ok(()),
+        Err(e) => Err(keyring_error(e)),
+    })
+    .await
+}
+
+/// Stores or deletes the gist access token; an empty token deletes it
+pub async fn set_gist_access_token(token: Option<&str>) -> Result<(), AppError> {
+    match token.map(str::trim).filter(|token| !token.is_empty()) {
+        Some(token) => store_credential(GIST_ACCESS_TOKEN, token).await,
+        None => delete_credential(GIST_ACCESS_TOKEN).await,
+    }
+}
+
+/// Moves a gist token saved in settings by an earlier version to the keychain
+///
+/// The token stays in settings if the keychain cannot take it, so gists keep
+/// working on systems without one.
+pub async fn migrate_settings_credentials(app: &AppHandle) -> Result<(), AppError> {
+    let service = SettingsService::new(get_pool(app)?);
+    let moved = move_settings_credentials(&service, |token| async move {
+        store_credential(GIST_ACCESS_TOKEN, &token).await
+    })
+    .await?;
+
+    // Drop the cached settings that still hold the token
+    if moved {
+        if let Some(state) = app.try_state::<SettingsServiceState>() {
+            *state.0.lock().await = None;
+        }
+    }
+    Ok(())
+}
+
+/// Hands the gist token in settings to `store`, then clears it from settings
+///
+/// # Returns
+///
+/// Whether settings held a token
+async fn move_settings_credentials<F, Fut>(
+    service: &SettingsService,
+    store: F,
+) -> Result<bool, AppError>
+where
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = Result<(), AppError>>,
+{
+    let mut settings = service.get_settings().await?;
+    let Some(token) = settings.gist_settings.access_token.take() else {
+        return Ok(false);
+    };
+
+    let token = token.trim();
+    if !token.is_empty() {
+        store(token.to_string()).await?;
+    }
+    service.update_settings(settings).await?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::settings::AppSettings;
+    use crate::services::database::run_migrations;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use sqlx::SqlitePool;
+    use std::sync::Mutex;
+
+    async fn service_with_token(token: Option<&str>) -> (SqlitePool, SettingsService) {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+        let service = SettingsService::new(pool.clone());
+        let mut settings = AppSettings::default();
+        settings.gist_settings.access_token = token.map(str::to_string);
+        service.update_settings(settings).await.unwrap();
+        (pool, service)
+    }
+
+    #[tokio::test]
+    async fn test_move_settings_credentials() {
+        let (pool, service) = service_with_token(Some(" ghp_secret ")).await;
+        let stored = Mutex::new(None);
+        let moved = move_settings_credentials(&service, |token| {
+            *stored.lock().unwrap() = Some(token);
+            async { Ok::<_, AppError>(()) }
+        })
+        .await
+        .unwrap();
+        assert!(moved);
+        assert_eq!(stored.lock().unwrap().as_deref(), Some("ghp_secret"));
+
+        // Cleared from the stored JSON, not only from the cached settings
+        let json = SettingsService::new(pool)
+            .get_setting("app_settings")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!json.contains("ghp_secret"));
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(json["gist_settings"]["access_token"].is_null());
+
+        // Nothing left to move
+        let moved = move_settings_credentials(&service, |_| async {
+            Err::<(), _>(AppError::Unknown("no token should be stored".to_string()))
+        })
+        .await
+        .unwrap();
+        assert!(!moved);
+    }
+
+    #[tokio::test]
+    async fn test_move_settings_credentials_keeps_token_without_keychain() {
+        let (pool, service) = service_with_token(Some("ghp_secret")).await;
+        let result = move_settings_credentials(&service, |_| async {
+            Err::<(), _>(AppError::External(
+                "OS keychain error: no Secret Service".to_string(),
+            ))
+        })
+        .await;
+        assert!(matches!(result, Err(AppError::External(_))));
+
+        let settings = SettingsService::new(pool).get_settings().await.unwrap();
+        assert_eq!(
+            settings.gist_settings.access_token.as_deref(),
+            Some("ghp_secret")
+        );
+    }
+}
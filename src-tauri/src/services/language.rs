@@ -0,0 +1,401 @@
+use crate::models::SnippetKind;
+use crate::utils::error::AppError;
+use sqlx::{Row, SqlitePool};
+
+/// File extensions recognised in snippet names, mapped to language identifiers
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("sh", "shellscript"),
+    ("bash", "shellscript"),
+    ("zsh", "shellscript"),
+    ("py", "python"),
+    ("rs", "rust"),
+    ("js", "javascript"),
+    ("mjs", "javascript"),
+    ("jsx", "javascriptreact"),
+    ("ts", "typescript"),
+    ("tsx", "typescriptreact"),
+    ("go", "go"),
+    ("rb", "ruby"),
+    ("sql", "sql"),
+    ("json", "json"),
+    ("yaml", "yaml"),
+    ("yml", "yaml"),
+    ("toml", "toml"),
+    ("md", "markdown"),
+    ("html", "html"),
+    ("css", "css"),
+    ("java", "java"),
+    ("kt", "kotlin"),
+    ("swift", "swift"),
+    ("c", "c"),
+    ("h", "c"),
+    ("cpp", "cpp"),
+    ("lua", "lua"),
+    ("php", "php"),
+    ("ps1", "powershell"),
+    ("nix", "nix"),
+];
+
+/// Interpreters named in a shebang line, mapped to language identifiers
+const INTERPRETERS: &[(&str, &str)] = &[
+    ("sh", "shellscript"),
+    ("bash", "shellscript"),
+    ("zsh", "shellscript"),
+    ("fish", "shellscript"),
+    ("python", "python"),
+    ("node", "javascript"),
+    ("deno", "typescript"),
+    ("ts-node", "typescript"),
+    ("ruby", "ruby"),
+    ("perl", "perl"),
+    ("php", "php"),
+    ("lua", "lua"),
+];
+
+/// Content markers per language; each marker found adds one point
+const KEYWORDS: &[(&str, &[&str])] = &[
+    (
+        "rust",
+        &[
+            "fn ",
+            "let mut ",
+            "impl ",
+            "pub fn ",
+            "use std::",
+            "println!",
+            "-> Result<",
+        ],
+    ),
+    (
+        "python",
+        &[
+            "def ",
+            "import ",
+            "elif ",
+            "self.",
+            "__init__",
+            "print(",
+            "from __future__",
+        ],
+    ),
+    (
+        "typescript",
+        &[
+            ": string",
+            ": number",
+            "interface ",
+            "export type ",
+            "as const",
+            ": boolean",
+        ],
+    ),
+    (
+        "javascript",
+        &[
+            "const ",
+            "function ",
+            "=> ",
+            "console.log",
+            "require(",
+            "document.",
+        ],
+    ),
+    ("go", &["package ", "func ", ":= ", "fmt.", "import ("]),
+    (
+        "sql",
+        &[
+            "SELECT ",
+            " FROM ",
+            "WHERE ",
+            "INSERT INTO",
+            "CREATE TABLE",
+            "GROUP BY",
+            "JOIN ",
+        ],
+    ),
+    (
+        "shellscript",
+        &[
+            "echo ", "sudo ", "export ", "$(", "| grep", "fi\n", "; then", "apt ", "brew ",
+        ],
+    ),
+    (
+        "html",
+        &["<!DOCTYPE", "<html", "<div", "</div>", "<span", "<a href="],
+    ),
+    (
+        "css",
+        &[
+            "px;",
+            "color:",
+            "margin:",
+            "padding:",
+            "display:",
+            "font-size:",
+        ],
+    ),
+    ("markdown", &["```", "\n## ", "](http", "- [ ] ", "**"]),
+];
+
+/// VS Code language identifiers a tag can name
+///
+/// Tags matching one of these (or an alias below) set the preview language
+/// and become the snippet's scope on VS Code export.
+const LANGUAGE_IDS: &str = "abap bat bibtex c clojure coffeescript cpp csharp css cuda-cpp dart \
+    diff dockerfile elixir erlang fsharp go graphql groovy handlebars haml haskell html ini java \
+    javascript javascriptreact json jsonc julia kotlin latex less lua makefile markdown nix \
+    objective-c objective-cpp perl php plaintext powershell pug python r razor ruby rust sass \
+    scala scss shellscript slim sql stylus swift terraform tex toml typescript typescriptreact vb \
+    vue xml xsl yaml zig";
+
+/// Common tag spellings mapped to their VS Code language identifier
+const LANGUAGE_ALIASES: &[(&str, &str)] = &[
+    ("bash", "shellscript"),
+    ("sh", "shellscript"),
+    ("shell", "shellscript"),
+    ("zsh", "shellscript"),
+    ("js", "javascript"),
+    ("jsx", "javascriptreact"),
+    ("ts", "typescript"),
+    ("tsx", "typescriptreact"),
+    ("py", "python"),
+    ("rb", "ruby"),
+    ("rs", "rust"),
+    ("golang", "go"),
+    ("yml", "yaml"),
+    ("c++", "cpp"),
+    ("c#", "csharp"),
+    ("md", "markdown"),
+];
+
+/// Minimum keyword score before content is considered code at all
+///
+/// Most snippets are prose prompts, so one stray marker is not enough.
+const MIN_KEYWORD_SCORE: usize = 2;
+
+/// Guesses the language of a snippet
+///
+/// Checks, in order: dynamic snippets (always shell), an extension in the
+/// name (`deploy.sh`), a shebang line, JSON that parses, then keyword
+/// markers in the content.
+///
+/// # Returns
+///
+/// A VS Code language identifier (e.g. `rust`, `shellscript`), or `None`
+/// when the content looks like plain text
+pub fn detect_language(name: &str, content: &str, kind: SnippetKind) -> Option<&'static str> {
+    if kind == SnippetKind::Dynamic {
+        return Some("shellscript");
+    }
+
+    if let Some((_, extension)) = name.trim().rsplit_once('.') {
+        let extension = extension.to_ascii_lowercase();
+        if let Some((_, id)) = EXTENSIONS.iter().find(|(ext, _)| *ext == extension) {
+            return Some(id);
+        }
+    }
+
+    let content = content.trim_start();
+    if let Some(id) = content.lines().next().and_then(language_from_shebang) {
+        return Some(id);
+    }
+
+    if (content.starts_with('{') || content.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(content).is_ok()
+    {
+        return Some("json");
+    }
+
+    let mut best: Option<(&'static str, usize)> = None;
+    for (id, markers) in KEYWORDS {
+        let score = markers.iter().filter(|m| content.contains(*m)).count();
+        if score >= MIN_KEYWORD_SCORE && best.is_none_or(|(_, top)| score > top) {
+            best = Some((id, score));
+        }
+    }
+    best.map(|(id, _)| id)
+}
+
+/// Extracts the language from a `#!` line, following `/usr/bin/env`
+fn language_from_shebang(line: &str) -> Option<&'static str> {
+    let command = line.strip_prefix("#!")?.trim();
+    let mut parts = command.split_whitespace();
+    let mut program = parts.next()?.rsplit('/').next()?;
+    if program == "env" {
+        program = parts.find(|part| !part.starts_with('-'))?;
+    }
+    // python3, python3.12, ...
+    let program = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+
+    INTERPRETERS
+        .iter()
+        .find(|(interpreter, _)| *interpreter == program)
+        .map(|(_, id)| *id)
+}
+
+/// Maps a tag to a VS Code language identifier, if it names a language
+pub fn language_id_for_tag(tag: &str) -> Option<&'static str> {
+    let tag = tag.trim().to_lowercase();
+    LANGUAGE_IDS
+        .split_whitespace()
+        .find(|id| *id == tag)
+        .or_else(|| {
+            LANGUAGE_ALIASES
+                .iter()
+                .find(|(alias, _)| *alias == tag)
+                .map(|(_, id)| *id)
+        })
+}
+
+/// Language named by the first language tag, e.g. `py` or `bash`
+pub fn language_from_tags(tags: &[String]) -> Option<&'static str> {
+    tags.iter().find_map(|tag| language_id_for_tag(tag))
+}
+
+/// Normalizes a `lang:` filter value to a language identifier
+///
+/// Accepts the same aliases as language tags (`bash`, `py`, `ts`, ...).
+pub fn normalize_language(value: &str) -> String {
+    let value = value.trim().to_lowercase();
+    language_id_for_tag(&value)
+        .map(str::to_string)
+        .unwrap_or(value)
+}
+
+/// Detects the language of snippets that have none stored
+///
+/// Runs at startup so snippets created before language detection existed can
+/// be found with `lang:`. Snippets that still look like plain text stay
+/// `NULL`.
+///
+/// # Returns
+///
+/// Number of snippets that got a language
+pub async fn backfill_languages(pool: &SqlitePool) -> Result<u64, AppError> {
+    let rows = sqlx::query("SELECT id, name, content, kind FROM snippets WHERE language IS NULL")
+        .fetch_all(pool)
+        .await?;
+
+    let mut updated = 0;
+    for row in rows {
+        let name: String = row.get(1);
+        let content: String = row.get(2);
+        let kind = SnippetKind::from_db(row.get(3));
+        if let Some(language) = detect_language(&name, &content, kind) {
+            sqlx::query("UPDATE snippets SET language = ? WHERE id = ?")
+                .bind(language)
+                .bind(row.get::<i64, _>(0))
+                .execute(pool)
+                .await?;
+            updated += 1;
+        }
+    }
+
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(name: &str, content: &str) -> Option<&'static str> {
+        detect_language(name, content, SnippetKind::Static)
+    }
+
+    #[test]
+    fn test_detect_from_name_extension() {
+        assert_eq!(detect("deploy.sh", "make deploy"), Some("shellscript"));
+        assert_eq!(detect("Query.SQL", "anything"), Some("sql"));
+        assert_eq!(detect("v1.2 notes", "plain text"), None);
+    }
+
+    #[test]
+    fn test_detect_from_shebang() {
+        assert_eq!(
+            detect("script", "#!/usr/bin/env python3\nprint('hi')"),
+            Some("python")
+        );
+        assert_eq!(detect("script", "#!/bin/bash\nls"), Some("shellscript"));
+        assert_eq!(
+            detect("script", "#!/usr/bin/env -S deno run\nx"),
+            Some("typescript")
+        );
+        assert_eq!(detect("script", "#!/opt/unknown\nx"), None);
+    }
+
+    #[test]
+    fn test_detect_from_content() {
+        assert_eq!(detect("cfg", r#"{"a": [1, 2]}"#), Some("json"));
+        assert_eq!(
+            detect("hook", "pub fn main() {\n    let mut x = 1;\n}"),
+            Some("rust")
+        );
+        assert_eq!(
+            detect("report", "SELECT name FROM users WHERE id = 1"),
+            Some("sql")
+        );
+        assert_eq!(
+            detect("prompt", "You are a helpful assistant. Summarize the text."),
+            None
+        );
+    }
+
+    #[test]
+    fn test_dynamic_snippets_are_shell() {
+        assert_eq!(
+            detect_language("branch", "git branch", SnippetKind::Dynamic),
+            Some("shellscript")
+        );
+    }
+
+    #[test]
+    fn test_language_id_for_tag() {
+        assert_eq!(language_id_for_tag("Rust"), Some("rust"));
+        assert_eq!(language_id_for_tag("zsh"), Some("shellscript"));
+        assert_eq!(language_id_for_tag("work"), None);
+        assert_eq!(
+            language_from_tags(&["work".to_string(), "py".to_string()]),
+            Some("python")
+        );
+    }
+
+    #[test]
+    fn test_normalize_language() {
+        assert_eq!(normalize_language("Bash"), "shellscript");
+        assert_eq!(normalize_language("py"), "python");
+        assert_eq!(normalize_language("rust"), "rust");
+        assert_eq!(normalize_language("klingon"), "klingon");
+    }
+
+    #[tokio::test]
+    async fn test_backfill_languages() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "CREATE TABLE snippets (id INTEGER PRIMARY KEY, name TEXT, content TEXT,
+                kind TEXT NOT NULL DEFAULT 'static', language TEXT)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO snippets (id, name, content, language) VALUES
+                (1, 'a.py', 'x', NULL), (2, 'prose', 'hello there', NULL),
+                (3, 'b.rs', 'y', 'go')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(backfill_languages(&pool).await.unwrap(), 1);
+
+        let languages: Vec<Option<String>> =
+            sqlx::query_scalar("SELECT language FROM snippets ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            languages,
+            vec![Some("python".to_string()), None, Some("go".to_string())]
+        );
+    }
+}
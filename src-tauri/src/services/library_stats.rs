@@ -0,0 +1,404 @@
+use crate::services::database::get_pool;
+use crate::utils::error::AppError;
+use crate::utils::time::current_timestamp;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use std::collections::{BTreeMap, HashMap};
+use tauri::{AppHandle, Manager};
+use tokio::sync::RwLock;
+
+/// Maximum number of tag pairs reported in `tag_cooccurrence`
+const MAX_TAG_PAIRS: usize = 50;
+
+/// Number of snippets written in a language
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageCount {
+    /// VS Code language identifier, or `None` for prose and unrecognised content
+    pub language: Option<String>,
+    pub count: usize,
+}
+
+/// Number of snippets carrying both tags
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagPair {
+    pub first: String,
+    pub second: String,
+    pub count: usize,
+}
+
+/// Snippets added in one calendar month (UTC)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GrowthPoint {
+    /// Month in `YYYY-MM` form
+    pub month: String,
+    pub added: usize,
+    /// Library size at the end of the month
+    pub cumulative: usize,
+}
+
+/// Aggregate content statistics for the whole library
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryStatistics {
+    pub total_snippets: usize,
+    pub total_words: u64,
+    pub total_characters: u64,
+    /// Most common language first
+    pub languages: Vec<LanguageCount>,
+    /// Most frequent pairs first, capped at 50
+    pub tag_cooccurrence: Vec<TagPair>,
+    /// Oldest month first
+    pub growth: Vec<GrowthPoint>,
+    pub computed_at: i64,
+}
+
+/// What one snippet contributes to the aggregates
+#[derive(Debug, Clone)]
+struct SnippetContribution {
+    words: u64,
+    characters: u64,
+    language: Option<String>,
+    tags: Vec<String>,
+    created_at: i64,
+}
+
+/// Per-snippet statistics cache
+///
+/// Holds each snippet's contribution so a mutation only re-reads the snippet
+/// that changed; the aggregates are then folded from memory without touching
+/// the database.
+#[derive(Debug, Default)]
+pub struct LibraryStatsCache {
+    snippets: HashMap<i64, SnippetContribution>,
+    loaded: bool,
+}
+
+impl LibraryStatsCache {
+    /// Adds or replaces a snippet's contribution
+    ///
+    /// `language` is the language stored with the snippet when it was saved.
+    pub fn upsert(
+        &mut self,
+        id: i64,
+        content: &str,
+        language: Option<String>,
+        mut tags: Vec<String>,
+        created_at: i64,
+    ) {
+        tags.sort();
+        tags.dedup();
+        self.snippets.insert(
+            id,
+            SnippetContribution {
+                words: content.split_whitespace().count() as u64,
+                characters: content.chars().count() as u64,
+                language,
+                tags,
+                created_at,
+            },
+        );
+    }
+
+    /// Removes a deleted snippet's contribution
+    pub fn remove(&mut self, id: i64) {
+        self.snippets.remove(&id);
+    }
+
+    /// Folds the cached contributions into library statistics
+    pub fn statistics(&self, now: i64) -> LibraryStatistics {
+        let mut languages: HashMap<Option<&str>, usize> = HashMap::new();
+        let mut pairs: HashMap<(&str, &str), usize> = HashMap::new();
+        let mut months: BTreeMap<String, usize> = BTreeMap::new();
+        let mut total_words = 0;
+        let mut total_characters = 0;
+
+        for snippet in self.snippets.values() {
+            total_words += snippet.words;
+            total_characters += snippet.characters;
+            *languages.entry(snippet.language.as_deref()).or_default() += 1;
+            *months.entry(month_of(snippet.created_at)).or_default() += 1;
+
+            // Tags are sorted, so each pair is counted once in a stable order
+            for (i, first) in snippet.tags.iter().enumerate() {
+                for second in &snippet.tags[i + 1..] {
+                    *pairs.entry((first, second)).or_default() += 1;
+                }
+            }
+        }
+
+        let mut languages: Vec<LanguageCount> = languages
+            .into_iter()
+            .map(|(language, count)| LanguageCount {
+                language: language.map(str::to_string),
+                count,
+            })
+            .collect();
+        languages.sort_by(|a, b| b.count.cmp(&a.count).then(a.language.cmp(&b.language)));
+
+        let mut tag_cooccurrence: Vec<TagPair> = pairs
+            .into_iter()
+            .map(|((first, second), count)| TagPair {
+                first: first.to_string(),
+                second: second.to_string(),
+                count,
+            })
+            .collect();
+        tag_cooccurrence.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.first.cmp(&b.first))
+                .then_with(|| a.second.cmp(&b.second))
+        });
+        tag_cooccurrence.truncate(MAX_TAG_PAIRS);
+
+        let mut cumulative = 0;
+        let growth = months
+            .into_iter()
+            .map(|(month, added)| {
+                cumulative += added;
+                GrowthPoint {
+                    month,
+                    added,
+                    cumulative,
+                }
+            })
+            .collect();
+
+        LibraryStatistics {
+            total_snippets: self.snippets.len(),
+            total_words,
+            total_characters,
+            languages,
+            tag_cooccurrence,
+            growth,
+            computed_at: now,
+        }
+    }
+}
+
+/// State wrapper for the statistics cache
+#[derive(Default)]
+pub struct LibraryStatsState(pub RwLock<LibraryStatsCache>);
+
+/// Returns library statistics, loading the cache on first use
+///
+/// # Errors
+///
+/// Returns `AppError` if the cache has to be loaded and the query fails
+pub async fn get_library_statistics(app: &AppHandle) -> Result<LibraryStatistics, AppError> {
+    let state = app
+        .try_state::<LibraryStatsState>()
+        .ok_or_else(|| AppError::Unknown("Library statistics are not initialized".to_string()))?;
+
+    {
+        let cache = state.0.read().await;
+        if cache.loaded {
+            return Ok(cache.statistics(current_timestamp()));
+        }
+    }
+
+    let pool = get_pool(app)?;
+    let mut cache = state.0.write().await;
+    if !cache.loaded {
+        *cache = load_cache(&pool).await?;
+    }
+    Ok(cache.statistics(current_timestamp()))
+}
+
+/// Re-reads one snippet into the cache after it was created, updated or deleted
+///
+/// Does nothing until the cache has been loaded. If the snippet cannot be
+/// read the cache is invalidated instead, so statistics are never stale.
+pub async fn refresh_snippet(app: &AppHandle, id: i64) {
+    let Some(state) = app.try_state::<LibraryStatsState>() else {
+        return;
+    };
+    let mut cache = state.0.write().await;
+    if !cache.loaded {
+        return;
+    }
+
+    let refreshed = match get_pool(app) {
+        Ok(pool) => load_snippet(&pool, id).await,
+        Err(e) => Err(e),
+    };
+    match refreshed {
+        Ok(Some(row)) => cache.upsert(id, &row.content, row.language, row.tags, row.created_at),
+        Ok(None) => cache.remove(id),
+        Err(_) => *cache = LibraryStatsCache::default(),
+    }
+}
+
+/// Drops the cache after bulk changes such as imports and restores
+pub async fn invalidate(app: &AppHandle) {
+    if let Some(state) = app.try_state::<LibraryStatsState>() {
+        *state.0.write().await = LibraryStatsCache::default();
+    }
+}
+
+async fn load_cache(pool: &SqlitePool) -> Result<LibraryStatsCache, AppError> {
+    let rows = sqlx::query(
+        "SELECT s.id, s.content, s.language, s.created_at, GROUP_CONCAT(t.name, char(31)) AS tags
+         FROM snippets s
+         LEFT JOIN snippet_tags st ON st.snippet_id = s.id
+         LEFT JOIN tags t ON t.id = st.tag_id
+         GROUP BY s.id",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut cache = LibraryStatsCache {
+        loaded: true,
+        ..Default::default()
+    };
+    for row in rows {
+        let content: String = row.get("content");
+        cache.upsert(
+            row.get("id"),
+            &content,
+            row.get("language"),
+            split_tags(row.get("tags")),
+            row.get("created_at"),
+        );
+    }
+    Ok(cache)
+}
+
+/// One snippet as read for the cache
+struct SnippetRow {
+    content: String,
+    language: Option<String>,
+    tags: Vec<String>,
+    created_at: i64,
+}
+
+async fn load_snippet(pool: &SqlitePool, id: i64) -> Result<Option<SnippetRow>, AppError> {
+    let row = sqlx::query(
+        "SELECT s.content, s.language, s.created_at, GROUP_CONCAT(t.name, char(31)) AS tags
+         FROM snippets s
+         LEFT JOIN snippet_tags st ON st.snippet_id = s.id
+         LEFT JOIN tags t ON t.id = st.tag_id
+         WHERE s.id = ?
+         GROUP BY s.id",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(|row| SnippetRow {
+        content: row.get("content"),
+        language: row.get("language"),
+        tags: split_tags(row.get("tags")),
+        created_at: row.get("created_at"),
+    }))
+}
+
+/// Splits tags joined with the unit separator, which cannot appear in tag names
+fn split_tags(joined: Option<String>) -> Vec<String> {
+    joined
+        .map(|joined| joined.split('\u{1f}').map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn month_of(timestamp: i64) -> String {
+    match time::OffsetDateTime::from_unix_timestamp(timestamp) {
+        Ok(datetime) => format!("{:04}-{:02}", datetime.year(), u8::from(datetime.month())),
+        Err(_) => "unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_statistics_aggregate_contributions() {
+        let mut cache = LibraryStatsCache::default();
+        // 2024-01-15 and 2024-03-01
+        cache.upsert(
+            1,
+            "fn main() -> () { let x = 1; }",
+            Some("rust".to_string()),
+            tags(&["rust", "cli"]),
+            1_705_312_800,
+        );
+        cache.upsert(
+            2,
+            "#!/bin/bash\necho hi",
+            Some("shellscript".to_string()),
+            tags(&["cli", "ops"]),
+            1_705_312_800,
+        );
+        cache.upsert(
+            3,
+            "Thanks for reaching out",
+            None,
+            tags(&["cli", "ops"]),
+            1_709_251_200,
+        );
+
+        let stats = cache.statistics(42);
+        assert_eq!(stats.total_snippets, 3);
+        assert_eq!(stats.total_words, 10 + 3 + 4);
+        assert_eq!(stats.computed_at, 42);
+
+        assert_eq!(stats.tag_cooccurrence[0].first, "cli");
+        assert_eq!(stats.tag_cooccurrence[0].second, "ops");
+        assert_eq!(stats.tag_cooccurrence[0].count, 2);
+        assert_eq!(stats.tag_cooccurrence.len(), 2);
+
+        assert_eq!(
+            stats.growth,
+            vec![
+                GrowthPoint {
+                    month: "2024-01".to_string(),
+                    added: 2,
+                    cumulative: 2
+                },
+                GrowthPoint {
+                    month: "2024-03".to_string(),
+                    added: 1,
+                    cumulative: 3
+                },
+            ]
+        );
+
+        let rust = stats
+            .languages
+            .iter()
+            .find(|l| l.language.as_deref() == Some("rust"))
+            .unwrap();
+        assert_eq!(rust.count, 1);
+        assert!(stats.languages.iter().any(|l| l.language.is_none()));
+    }
+
+    #[test]
+    fn test_incremental_updates() {
+        let mut cache = LibraryStatsCache::default();
+        cache.upsert(1, "one two", None, tags(&["a", "b", "a"]), 0);
+        assert_eq!(cache.statistics(0).total_words, 2);
+        assert_eq!(cache.statistics(0).tag_cooccurrence.len(), 1);
+
+        cache.upsert(1, "one two three", None, Vec::new(), 0);
+        let stats = cache.statistics(0);
+        assert_eq!(stats.total_words, 3);
+        assert!(stats.tag_cooccurrence.is_empty());
+
+        cache.remove(1);
+        let stats = cache.statistics(0);
+        assert_eq!(stats.total_snippets, 0);
+        assert!(stats.growth.is_empty());
+    }
+
+    #[test]
+    fn test_split_tags() {
+        assert_eq!(
+            split_tags(Some("a\u{1f}b c".to_string())),
+            tags(&["a", "b c"])
+        );
+        assert!(split_tags(None).is_empty());
+    }
+}
@@ -0,0 +1,432 @@
+/// Clipboard access on Linux.
+///
+/// On Wayland this goes through `wl-copy`/`wl-paste` from wl-clipboard, which
+/// speak the `zwlr_data_control` (and `ext_data_control`) protocol on
+/// wlroots-based compositors such as Sway and Hyprland, so PRIMARY can be read
+/// without Snips having keyboard focus. On X11 it goes through `xclip`.
+///
+/// On both, the selection owner is the data source: if the process that
+/// copied exits, the copied text is gone. `wl-copy` and `xclip` fork a small
+/// process that keeps offering the text until something else takes the
+/// selection, so copies survive the window closing and Snips exiting.
+use crate::utils::error::AppError;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Display server protocol used for the clipboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinuxClipboardBackend {
+    /// `wl-copy`/`wl-paste` (data-control)
+    Wayland,
+    /// `xclip`
+    X11,
+}
+
+/// Format of the data written to a selection
+///
+/// Each tool process offers a single format, so writing HTML replaces any
+/// plain text flavor rather than adding to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentType {
+    /// UTF-8 text
+    PlainText,
+    /// An HTML fragment, for pasting with formatting
+    Html,
+}
+
+impl ContentType {
+    /// Name the backend's tool uses for this format
+    pub fn target(self, backend: LinuxClipboardBackend) -> &'static str {
+        match (self, backend) {
+            (Self::PlainText, LinuxClipboardBackend::Wayland) => "text/plain;charset=utf-8",
+            (Self::PlainText, LinuxClipboardBackend::X11) => "UTF8_STRING",
+            (Self::Html, _) => "text/html",
+        }
+    }
+}
+
+/// Which selection to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    /// The regular clipboard (Ctrl+C / Ctrl+V)
+    Clipboard,
+    /// The primary selection (highlight / middle-click)
+    Primary,
+}
+
+impl LinuxClipboardBackend {
+    /// Detects the backend for the current session from the environment
+    ///
+    /// Wayland wins over `DISPLAY`, which XWayland also sets.
+    pub fn detect() -> Option<Self> {
+        let set = |name: &str| std::env::var_os(name).is_some_and(|v| !v.is_empty());
+        if set("WAYLAND_DISPLAY") {
+            Some(Self::Wayland)
+        } else if set("DISPLAY") {
+            Some(Self::X11)
+        } else {
+            None
+        }
+    }
+
+    /// The external program used to write the clipboard
+    pub fn write_program(self) -> &'static str {
+        match self {
+            Self::Wayland => "wl-copy",
+            Self::X11 => "xclip",
+        }
+    }
+
+    /// The distribution package that provides the clipboard tools
+    pub fn package(self) -> &'static str {
+        match self {
+            Self::Wayland => "wl-clipboard",
+            Self::X11 => "xclip",
+        }
+    }
+
+    fn read_command(self, selection: Selection) -> (&'static str, Vec<&'static str>) {
+        match self {
+            Self::Wayland => self.read_target_command(selection, "text"),
+            Self::X11 => self.read_target_command(selection, "UTF8_STRING"),
+        }
+    }
+
+    fn read_target_command(self, selection: Selection, target: &str) -> (&'static str, Vec<&str>) {
+        match self {
+            Self::Wayland => {
+                let mut args = vec!["--no-newline", "--type", target];
+                if selection == Selection::Primary {
+                    args.push("--primary");
+                }
+                ("wl-paste", args)
+            }
+            Self::X11 => (
+                "xclip",
+                vec![
+                    "-selection",
+                    x11_selection_name(selection),
+                    "-out",
+                    "-target",
+                    target,
+                ],
+            ),
+        }
+    }
+
+    fn list_types_command(self, selection: Selection) -> (&'static str, Vec<&'static str>) {
+        match self {
+            Self::Wayland => {
+                let mut args = vec!["--list-types"];
+                if selection == Selection::Primary {
+                    args.push("--primary");
+                }
+                ("wl-paste", args)
+            }
+            Self::X11 => self.read_target_command(selection, "TARGETS"),
+        }
+    }
+
+    fn write_command(self, selection: Selection, target: &str) -> (&'static str, Vec<&str>) {
+        match self {
+            Self::Wayland => {
+                let mut args = vec!["--type", target];
+                if selection == Selection::Primary {
+                    args.push("--primary");
+                }
+                ("wl-copy", args)
+            }
+            Self::X11 => (
+                "xclip",
+                vec![
+                    "-selection",
+                    x11_selection_name(selection),
+                    "-in",
+                    "-target",
+                    target,
+                ],
+            ),
+        }
+    }
+}
+
+fn x11_selection_name(selection: Selection) -> &'static str {
+    match selection {
+        Selection::Clipboard => "clipboard",
+        Selection::Primary => "primary",
+    }
+}
+
+/// Detects the backend, failing when the session has no display server
+pub fn current_backend() -> Result<LinuxClipboardBackend, AppError> {
+    LinuxClipboardBackend::detect().ok_or_else(|| {
+        AppError::Unsupported(
+            "Clipboard operations on Linux require a Wayland or X11 session".to_string(),
+        )
+    })
+}
+
+/// Reads text from a selection
+///
+/// An empty selection is returned as an empty string rather than an error.
+///
+/// # Errors
+///
+/// * `AppError::Unsupported` - The clipboard tool is not installed
+/// * `AppError::External` - The clipboard tool failed
+pub fn read(backend: LinuxClipboardBackend, selection: Selection) -> Result<String, AppError> {
+    let (program, args) = backend.read_command(selection);
+    let output = run_read(backend, program, &args)?;
+    Ok(String::from_utf8_lossy(&output).to_string())
+}
+
+/// Reads the data a selection offers under a target (MIME type or X11 atom)
+///
+/// An empty selection is returned as empty data rather than an error.
+///
+/// # Errors
+///
+/// * `AppError::Unsupported` - The clipboard tool is not installed
+/// * `AppError::External` - The clipboard tool failed, or the selection does
+///   not offer the target
+pub fn read_bytes(
+    backend: LinuxClipboardBackend,
+    selection: Selection,
+    target: &str,
+) -> Result<Vec<u8>, AppError> {
+    let (program, args) = backend.read_target_command(selection, target);
+    run_read(backend, program, &args)
+}
+
+/// Lists the targets a selection offers, empty when nothing is copied
+///
+/// # Errors
+///
+/// * `AppError::Unsupported` - The clipboard tool is not installed
+/// * `AppError::External` - The clipboard tool failed
+pub fn list_types(
+    backend: LinuxClipboardBackend,
+    selection: Selection,
+) -> Result<Vec<String>, AppError> {
+    let (program, args) = backend.list_types_command(selection);
+    let output = run_read(backend, program, &args)?;
+    Ok(String::from_utf8_lossy(&output)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Error for a clipboard tool that could not be started
+///
+/// A missing tool gets a message naming the package to install, as it is the
+/// one failure the user can fix.
+fn spawn_error(backend: LinuxClipboardBackend, program: &str, e: std::io::Error) -> AppError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        AppError::Unsupported(format!(
+            "{} was not found. Install {} to use the clipboard",
+            program,
+            backend.package()
+        ))
+    } else {
+        AppError::External(format!("Failed to run {}: {}", program, e))
+    }
+}
+
+fn run_read(
+    backend: LinuxClipboardBackend,
+    program: &str,
+    args: &[&str],
+) -> Result<Vec<u8>, AppError> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| spawn_error(backend, program, e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if is_empty_selection_error(&stderr) {
+            return Ok(Vec::new());
+        }
+        return Err(AppError::External(format!(
+            "{} failed: {}",
+            program,
+            stderr.trim()
+        )));
+    }
+
+    Ok(output.stdout)
+}
+
+/// Writes text to a selection
+///
+/// Returns once the selection is owned; the text stays available after
+/// Snips exits.
+///
+/// # Errors
+///
+/// * `AppError::Unsupported` - The clipboard tool is not installed
+/// * `AppError::External` - The clipboard tool failed
+pub fn write(
+    backend: LinuxClipboardBackend,
+    selection: Selection,
+    text: &str,
+) -> Result<(), AppError> {
+    write_as(backend, selection, ContentType::PlainText, text)
+}
+
+/// Writes data of the given format to a selection
+///
+/// # Errors
+///
+/// * `AppError::Unsupported` - The clipboard tool is not installed
+/// * `AppError::External` - The clipboard tool failed
+pub fn write_as(
+    backend: LinuxClipboardBackend,
+    selection: Selection,
+    content_type: ContentType,
+    text: &str,
+) -> Result<(), AppError> {
+    write_bytes(
+        backend,
+        selection,
+        content_type.target(backend),
+        text.as_bytes(),
+    )
+}
+
+/// Writes data to a selection under a target (MIME type or X11 atom)
+///
+/// # Errors
+///
+/// * `AppError::Unsupported` - The clipboard tool is not installed
+/// * `AppError::External` - The clipboard tool failed
+pub fn write_bytes(
+    backend: LinuxClipboardBackend,
+    selection: Selection,
+    target: &str,
+    data: &[u8],
+) -> Result<(), AppError> {
+    let (program, args) = backend.write_command(selection, target);
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        // The forked selection owner inherits these; don't keep our pipes open
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| spawn_error(backend, program, e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(data)
+            .map_err(|e| AppError::External(format!("Failed to write to clipboard: {}", e)))?;
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| AppError::External(format!("Failed to write to clipboard: {}", e)))?;
+    if !status.success() {
+        return Err(AppError::External(format!("{} failed", program)));
+    }
+
+    Ok(())
+}
+
+/// Returns whether an executable with this name is on `PATH`
+pub fn is_installed(program: &str) -> bool {
+    let Some(path) = std::env::var_os("PATH") else {
+        return false;
+    };
+    std::env::split_paths(&path).any(|dir| is_executable(&dir.join(program)))
+}
+
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+/// Recognises the messages the clipboard tools print when a selection is empty
+fn is_empty_selection_error(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+    stderr.contains("nothing is copied")
+        || stderr.contains("no selection")
+        || stderr.contains("no suitable type of content")
+        || stderr.contains("target utf8_string not available")
+        || stderr.contains("there is no owner")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wayland_commands() {
+        let backend = LinuxClipboardBackend::Wayland;
+        assert_eq!(
+            backend.read_command(Selection::Primary),
+            (
+                "wl-paste",
+                vec!["--no-newline", "--type", "text", "--primary"]
+            )
+        );
+        let (program, args) =
+            backend.write_command(Selection::Clipboard, ContentType::PlainText.target(backend));
+        assert_eq!(program, "wl-copy");
+        assert!(!args.contains(&"--primary"));
+        let (_, args) = backend.write_command(Selection::Clipboard, "image/png");
+        assert_eq!(args, ["--type", "image/png"]);
+        assert_eq!(
+            backend.list_types_command(Selection::Clipboard),
+            ("wl-paste", vec!["--list-types"])
+        );
+    }
+
+    #[test]
+    fn test_x11_commands() {
+        let backend = LinuxClipboardBackend::X11;
+        let (program, args) = backend.read_command(Selection::Clipboard);
+        assert_eq!(program, "xclip");
+        assert_eq!(&args[..3], ["-selection", "clipboard", "-out"]);
+        let (_, args) = backend.write_command(Selection::Primary, "UTF8_STRING");
+        assert_eq!(&args[..3], ["-selection", "primary", "-in"]);
+        assert_eq!(ContentType::Html.target(backend), "text/html");
+        let (_, args) = backend.list_types_command(Selection::Clipboard);
+        assert_eq!(args.last(), Some(&"TARGETS"));
+    }
+
+    #[test]
+    fn test_is_empty_selection_error() {
+        assert!(is_empty_selection_error("Nothing is copied\n"));
+        assert!(is_empty_selection_error("No selection\n"));
+        assert!(is_empty_selection_error(
+            "Error: target UTF8_STRING not available\n"
+        ));
+        assert!(!is_empty_selection_error(
+            "Failed to connect to a Wayland server"
+        ));
+    }
+
+    #[test]
+    fn test_missing_tool_names_package() {
+        let missing = std::io::Error::from(std::io::ErrorKind::NotFound);
+        let error = spawn_error(LinuxClipboardBackend::Wayland, "wl-copy", missing);
+        assert!(matches!(&error, AppError::Unsupported(message)
+            if message.contains("wl-copy") && message.contains("wl-clipboard")));
+
+        let denied = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        let error = spawn_error(LinuxClipboardBackend::X11, "xclip", denied);
+        assert!(matches!(error, AppError::External(_)));
+    }
+
+    #[test]
+    fn test_is_installed() {
+        assert!(is_installed("sh"));
+        assert!(!is_installed("definitely-not-a-snips-tool"));
+    }
+}
@@ -0,0 +1,353 @@
+use crate::commands::storage_commands::SnippetExport;
+use crate::models::Snippet;
+use serde_json::Value;
+use std::collections::HashSet;
+
+const FRONT_MATTER_DELIMITER: &str = "---";
+
+/// Renders a snippet as a markdown file with YAML front matter
+///
+/// String values are written as double-quoted scalars (JSON escaping is a
+/// subset of YAML's), so names containing colons or quotes round-trip safely
+/// through Obsidian and other front-matter aware tools.
+pub fn snippet_to_markdown(snippet: &SnippetExport) -> String {
+    let mut out = String::new();
+    out.push_str(FRONT_MATTER_DELIMITER);
+    out.push('\n');
+    out.push_str(&format!("name: {}\n", quote(&snippet.name)));
+    if let Some(description) = snippet.description.as_deref().filter(|d| !d.is_empty()) {
+        out.push_str(&format!("description: {}\n", quote(description)));
+    }
+    let tags: Vec<String> = snippet.tags.iter().map(|tag| quote(tag)).collect();
+    out.push_str(&format!("tags: [{}]\n", tags.join(", ")));
+    out.push_str(&format!("created_at: {}\n", snippet.created_at));
+    out.push_str(&format!("updated_at: {}\n", snippet.updated_at));
+    out.push_str(FRONT_MATTER_DELIMITER);
+    out.push('\n');
+    out.push_str(&snippet.content);
+    if !snippet.content.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+/// Parses a markdown file into a snippet
+///
+/// Front matter is optional. Supported keys are `name` (or `title`),
+/// `description`, `tags` (flow `[a, b]`, block `- a` or comma-separated) and
+/// `created_at`/`updated_at` Unix timestamps. Unknown keys are ignored.
+///
+/// # Arguments
+///
+/// * `text` - Contents of the markdown file
+/// * `fallback_name` - Name used when the front matter has none (usually the file stem)
+/// * `now` - Timestamp used when the front matter has no timestamps
+///
+/// # Returns
+///
+/// The parsed snippet, or `None` if the body is empty
+pub fn markdown_to_snippet(text: &str, fallback_name: &str, now: i64) -> Option<SnippetExport> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let (front_matter, body) = split_front_matter(text);
+
+    let mut snippet = SnippetExport {
+        name: fallback_name.to_string(),
+        content: body.trim_end_matches(['\n', '\r']).to_string(),
+        description: None,
+        tags: Vec::new(),
+        created_at: now,
+        updated_at: now,
+        ..SnippetExport::default()
+    };
+
+    let lines: Vec<&str> = front_matter.lines().collect();
+    let mut i = 0;
+    while i < lines.len() {
+        let Some((key, raw_value)) = lines[i].split_once(':') else {
+            i += 1;
+            continue;
+        };
+        let key = key.trim();
+        let raw_value = raw_value.trim();
+        i += 1;
+
+        match key {
+            "name" | "title" => {
+                let name = parse_scalar(raw_value);
+                if !name.trim().is_empty() {
+                    snippet.name = name;
+                }
+            }
+            "description" => {
+                let description = parse_scalar(raw_value);
+                snippet.description = (!description.is_empty()).then_some(description);
+            }
+            "tags" => {
+                if raw_value.is_empty() {
+                    // Block sequence on the following lines
+                    while i < lines.len() {
+                        let Some(item) = lines[i].trim_start().strip_prefix("- ") else {
+                            break;
+                        };
+                        snippet.tags.push(parse_scalar(item.trim()));
+                        i += 1;
+                    }
+                } else {
+                    snippet.tags = parse_list(raw_value);
+                }
+            }
+            "created_at" => {
+                if let Ok(timestamp) = raw_value.parse() {
+                    snippet.created_at = timestamp;
+                }
+            }
+            "updated_at" => {
+                if let Ok(timestamp) = raw_value.parse() {
+                    snippet.updated_at = timestamp;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    snippet.tags.retain(|tag| !tag.is_empty());
+    (!snippet.content.trim().is_empty()).then_some(snippet)
+}
+
+/// Builds a unique, filesystem-safe `.md` file name for a snippet
+///
+/// # Arguments
+///
+/// * `name` - Snippet name
+/// * `used` - File names already taken in this export; the result is added to it
+pub fn markdown_file_name(name: &str, used: &mut HashSet<String>) -> String {
+    let mut slug = String::new();
+    for c in name.trim().chars() {
+        if c.is_alphanumeric() || c == '-' || c == '_' {
+            slug.extend(c.to_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches('-');
+    let slug = if slug.is_empty() { "snippet" } else { slug };
+
+    let mut candidate = format!("{}.md", slug);
+    let mut counter = 2;
+    while used.contains(&candidate) {
+        candidate = format!("{}-{}.md", slug, counter);
+        counter += 1;
+    }
+    used.insert(candidate.clone());
+    candidate
+}
+
+/// Renders several snippets as one markdown document
+///
+/// Each snippet becomes a `##` section with its description, its content in
+/// a fenced code block labelled with the detected language, and a tag
+/// footer. Dynamic snippets show their command; nothing is executed.
+pub fn snippets_to_markdown_document(snippets: &[Snippet]) -> String {
+    let sections: Vec<String> = snippets
+        .iter()
+        .map(|snippet| {
+            let mut section = format!("## {}\n\n", snippet.name.trim());
+            if let Some(description) = snippet.description.as_deref().map(str::trim) {
+                if !description.is_empty() {
+                    section.push_str(description);
+                    section.push_str("\n\n");
+                }
+            }
+
+            let fence = fence_for(&snippet.content);
+            section.push_str(&fence);
+            section.push_str(snippet.language.as_deref().unwrap_or_default());
+            section.push('\n');
+            section.push_str(snippet.content.trim_end_matches(['\n', '\r']));
+            section.push('\n');
+            section.push_str(&fence);
+            section.push('\n');
+
+            let tags: Vec<String> = snippet
+                .tags
+                .iter()
+                .flatten()
+                .map(|tag| format!("`{}`", tag))
+                .collect();
+            if !tags.is_empty() {
+                section.push_str(&format!("\nTags: {}\n", tags.join(", ")));
+            }
+            section
+        })
+        .collect();
+
+    sections.join("\n")
+}
+
+/// Returns a backtick fence longer than any backtick run in the content
+fn fence_for(content: &str) -> String {
+    let longest = content
+        .split(|c: char| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or_default();
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn quote(value: &str) -> String {
+    Value::String(value.to_string()).to_string()
+}
+
+/// Splits `---` delimited front matter from the body
+fn split_front_matter(text: &str) -> (&str, &str) {
+    let Some(rest) = text
+        .strip_prefix("---\n")
+        .or_else(|| text.strip_prefix("---\r\n"))
+    else {
+        return ("", text);
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == FRONT_MATTER_DELIMITER {
+            return (&rest[..offset], &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+
+    // Unterminated front matter: treat the whole file as content
+    ("", text)
+}
+
+fn parse_scalar(raw: &str) -> String {
+    if raw.starts_with('"') {
+        if let Ok(Value::String(value)) = serde_json::from_str(raw) {
+            return value;
+        }
+    }
+    if raw.len() >= 2 && raw.starts_with('\'') && raw.ends_with('\'') {
+        return raw[1..raw.len() - 1].replace("''", "'");
+    }
+    raw.to_string()
+}
+
+fn parse_list(raw: &str) -> Vec<String> {
+    if let Ok(Value::Array(items)) = serde_json::from_str::<Value>(raw) {
+        return items
+            .into_iter()
+            .filter_map(|item| match item {
+                Value::String(value) => Some(value),
+                Value::Null => None,
+                other => Some(other.to_string()),
+            })
+            .collect();
+    }
+
+    raw.trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(|item| parse_scalar(item.trim()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SnippetId, SnippetKind};
+
+    fn snippet() -> SnippetExport {
+        SnippetExport {
+            name: "Deploy: \"prod\"".to_string(),
+            content: "# Steps\n\n1. build\n---\n2. ship".to_string(),
+            description: Some("How to deploy".to_string()),
+            tags: vec!["ops".to_string(), "shell, bash".to_string()],
+            created_at: 100,
+            updated_at: 200,
+            ..SnippetExport::default()
+        }
+    }
+
+    #[test]
+    fn test_markdown_round_trip() {
+        let original = snippet();
+        let markdown = snippet_to_markdown(&original);
+        assert!(markdown.starts_with("---\nname: \"Deploy: \\\"prod\\\"\"\n"));
+
+        let parsed = markdown_to_snippet(&markdown, "fallback", 0).unwrap();
+        assert_eq!(parsed.name, original.name);
+        assert_eq!(parsed.content, original.content);
+        assert_eq!(parsed.description, original.description);
+        assert_eq!(parsed.tags, original.tags);
+        assert_eq!(parsed.created_at, 100);
+        assert_eq!(parsed.updated_at, 200);
+    }
+
+    #[test]
+    fn test_obsidian_style_front_matter() {
+        let text =
+            "---\ntitle: Notes\ntags:\n  - work\n  - 'daily'\naliases: [n]\n---\nBody text\n";
+        let parsed = markdown_to_snippet(text, "file", 42).unwrap();
+        assert_eq!(parsed.name, "Notes");
+        assert_eq!(parsed.tags, vec!["work", "daily"]);
+        assert_eq!(parsed.content, "Body text");
+        assert_eq!(parsed.created_at, 42);
+    }
+
+    #[test]
+    fn test_plain_markdown_uses_fallback_name() {
+        let parsed = markdown_to_snippet("just content", "my-note", 7).unwrap();
+        assert_eq!(parsed.name, "my-note");
+        assert_eq!(parsed.content, "just content");
+        assert!(parsed.tags.is_empty());
+
+        assert!(markdown_to_snippet("---\nname: empty\n---\n\n", "x", 0).is_none());
+    }
+
+    #[test]
+    fn test_snippets_to_markdown_document() {
+        let make = |name: &str, content: &str, language: Option<&str>, tags: &[&str]| Snippet {
+            id: SnippetId(1),
+            name: name.to_string(),
+            content: content.to_string(),
+            description: None,
+            created_at: 0,
+            updated_at: 0,
+            tags: Some(tags.iter().map(|tag| tag.to_string()).collect()),
+            pinned: false,
+            kind: SnippetKind::Static,
+            language: language.map(str::to_string),
+            archived: false,
+            expires_at: None,
+        };
+        let mut first = make(
+            "Restart",
+            "systemctl restart app\n",
+            Some("shellscript"),
+            &["ops"],
+        );
+        first.description = Some("After config changes".to_string());
+        let second = make("Notes", "Use ```inline``` fences", None, &[]);
+
+        let document = snippets_to_markdown_document(&[first, second]);
+        assert_eq!(
+            document,
+            "## Restart\n\nAfter config changes\n\n```shellscript\nsystemctl restart app\n```\n\
+             \nTags: `ops`\n\n## Notes\n\n````\nUse ```inline``` fences\n````\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_file_name_is_unique_and_safe() {
+        let mut used = HashSet::new();
+        assert_eq!(
+            markdown_file_name("Git: Undo Commit", &mut used),
+            "git-undo-commit.md"
+        );
+        assert_eq!(
+            markdown_file_name("git undo commit", &mut used),
+            "git-undo-commit-2.md"
+        );
+        assert_eq!(markdown_file_name("../../etc", &mut used), "etc.md");
+        assert_eq!(markdown_file_name("???", &mut used), "snippet.md");
+    }
+}
@@ -1,20 +1,229 @@
-use tauri::AppHandle;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::{AppHandle, Emitter, Listener, Wry};
 
+use crate::services::database::get_pool;
+use crate::services::tray::{self, TRAY_ID};
 use crate::utils::error::AppError;
 
+/// Event emitted after snippets are created, edited, deleted, imported or used
+pub const SNIPPETS_CHANGED_EVENT: &str = "snippets-changed";
+
+/// Snippets listed in the tray's "Recent" submenu
+pub const RECENT_MENU_LIMIT: i64 = 10;
+
+/// Longest snippet name shown in the tray menu, in characters
+const MAX_MENU_LABEL_CHARS: usize = 40;
+
+/// Prefix of the menu item IDs in the "Recent" submenu, followed by the snippet ID
+const RECENT_ITEM_PREFIX: &str = "recent:";
+
+/// A recently used snippet as listed in the tray menu
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentSnippet {
+    pub id: i64,
+    pub name: String,
+}
+
 /// Updates the tray icon badge count (for selected snippets)
 pub fn update_badge_count(app: &AppHandle, count: u32) -> Result<(), AppError> {
-    if let Some(tray) = app.tray_by_id("main-tray") {
-        // On macOS, we can set a badge on the tray icon
-        // Note: This functionality may be limited depending on the platform
-        let tooltip = if count > 0 {
-            format!("Snips - {} selected", count)
-        } else {
-            "Snips - Snippet Manager".to_string()
-        };
-
-        tray.set_tooltip(Some(tooltip))
-            .map_err(|e| AppError::TauriError(e.to_string()))?;
+    tray::set_selected_count(app, count)
+}
+
+/// Menu item ID for a snippet in the "Recent" submenu
+pub fn recent_item_id(snippet_id: i64) -> String {
+    format!("{}{}", RECENT_ITEM_PREFIX, snippet_id)
+}
+
+/// Snippet ID of a "Recent" submenu item, or `None` for any other item
+pub fn parse_recent_item_id(item_id: &str) -> Option<i64> {
+    item_id.strip_prefix(RECENT_ITEM_PREFIX)?.parse().ok()
+}
+
+/// Shortens a snippet name to fit in the tray menu
+fn menu_label(name: &str) -> String {
+    let name = name.trim();
+    if name.chars().count() <= MAX_MENU_LABEL_CHARS {
+        return name.to_string();
+    }
+    let shortened: String = name.chars().take(MAX_MENU_LABEL_CHARS - 1).collect();
+    format!("{}…", shortened.trim_end())
+}
+
+/// Gets the most recently used snippets, each listed once
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `limit` - Maximum number of snippets
+///
+/// # Returns
+///
+/// Snippets ordered by their latest use, most recent first
+///
+/// # Errors
+///
+/// Returns `AppError` if database operations fail
+pub async fn recent_snippets(
+    pool: &SqlitePool,
+    limit: i64,
+) -> Result<Vec<RecentSnippet>, AppError> {
+    let rows = sqlx::query(
+        "SELECT s.id, s.name
+         FROM analytics a
+         INNER JOIN snippets s ON s.id = a.snippet_id
+         GROUP BY s.id
+         ORDER BY MAX(a.used_at) DESC, s.id DESC
+         LIMIT ?",
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| RecentSnippet {
+            id: row.get(0),
+            name: row.get(1),
+        })
+        .collect())
+}
+
+/// Builds the tray menu with a "Recent" submenu listing `recent`
+pub fn build_tray_menu(app: &AppHandle, recent: &[RecentSnippet]) -> tauri::Result<Menu<Wry>> {
+    let search_item = MenuItem::with_id(
+        app,
+        "search",
+        "Search Snippets",
+        true,
+        Some("CmdOrCtrl+Shift+S"),
+    )?;
+    let quick_add_item = MenuItem::with_id(
+        app,
+        "quick-add",
+        "Quick Add",
+        true,
+        Some("CmdOrCtrl+Shift+A"),
+    )?;
+
+    let recent_menu = Submenu::with_id(app, "recent", "Recent", true)?;
+    if recent.is_empty() {
+        recent_menu.append(&MenuItem::with_id(
+            app,
+            "recent-empty",
+            "No recent snippets",
+            false,
+            None::<&str>,
+        )?)?;
+    }
+    for snippet in recent {
+        recent_menu.append(&MenuItem::with_id(
+            app,
+            recent_item_id(snippet.id),
+            menu_label(&snippet.name),
+            true,
+            None::<&str>,
+        )?)?;
+    }
+
+    let settings_item = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
+    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, Some("CmdOrCtrl+Q"))?;
+
+    Menu::with_items(
+        app,
+        &[
+            &search_item,
+            &quick_add_item,
+            &recent_menu,
+            &PredefinedMenuItem::separator(app)?,
+            &settings_item,
+            &quit_item,
+        ],
+    )
+}
+
+/// Rebuilds the tray menu with the current recent snippets
+///
+/// # Errors
+///
+/// Returns `AppError` if the recent snippets cannot be read or the menu
+/// cannot be built
+pub async fn refresh_tray_menu(app: &AppHandle) -> Result<(), AppError> {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return Ok(());
+    };
+    let pool = get_pool(app)?;
+    let recent = recent_snippets(&pool, RECENT_MENU_LIMIT).await?;
+
+    let menu = build_tray_menu(app, &recent).map_err(|e| AppError::TauriError(e.to_string()))?;
+    tray.set_menu(Some(menu))
+        .map_err(|e| AppError::TauriError(e.to_string()))
+}
+
+/// Tells listeners, including the tray menu, that snippets changed
+pub fn notify_snippets_changed(app: &AppHandle) {
+    if let Err(e) = app.emit(SNIPPETS_CHANGED_EVENT, ()) {
+        eprintln!("Failed to emit {}: {}", SNIPPETS_CHANGED_EVENT, e);
+    }
+}
+
+/// Rebuilds the tray menu now and whenever snippets change
+pub fn start(app: &AppHandle) {
+    let refresh = |app: AppHandle| {
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = refresh_tray_menu(&app).await {
+                eprintln!("Failed to refresh tray menu: {}", e);
+            }
+        });
+    };
+
+    refresh(app.clone());
+    let handle = app.clone();
+    app.listen_any(SNIPPETS_CHANGED_EVENT, move |_| refresh(handle.clone()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[test]
+    fn test_recent_item_id_round_trip() {
+        assert_eq!(parse_recent_item_id(&recent_item_id(42)), Some(42));
+        assert_eq!(parse_recent_item_id("settings"), None);
+        assert_eq!(parse_recent_item_id("recent:abc"), None);
+        assert_eq!(parse_recent_item_id("recent-empty"), None);
+    }
+
+    #[test]
+    fn test_menu_label() {
+        assert_eq!(menu_label(" Deploy "), "Deploy");
+        let long = "x".repeat(60);
+        let label = menu_label(&long);
+        assert_eq!(label.chars().count(), MAX_MENU_LABEL_CHARS);
+        assert!(label.ends_with('…'));
+    }
+
+    #[tokio::test]
+    async fn test_recent_snippets() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        for statement in [
+            "CREATE TABLE snippets (id INTEGER PRIMARY KEY, name TEXT)",
+            "CREATE TABLE analytics (id INTEGER PRIMARY KEY, snippet_id INTEGER, used_at INTEGER)",
+            "INSERT INTO snippets VALUES (1, 'one'), (2, 'two'), (3, 'three')",
+            "INSERT INTO analytics (snippet_id, used_at) VALUES (1, 10), (2, 20), (1, 30)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        let recent = recent_snippets(&pool, 10).await.unwrap();
+        let names: Vec<&str> = recent.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["one", "two"]);
+        assert_eq!(recent_snippets(&pool, 1).await.unwrap().len(), 1);
     }
-    Ok(())
 }
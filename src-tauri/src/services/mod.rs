@@ -1,9 +1,58 @@
 pub mod analytics;
+pub mod api_server;
+pub mod api_tokens;
+pub mod appearance;
+pub mod archive;
+pub mod attachments;
+pub mod backup_files;
 pub mod backup_scheduler;
+pub mod confirmation;
+pub mod cursor;
+pub mod custom_themes;
 pub mod database;
+pub mod db_watcher;
+pub mod diagnostics_bundle;
+pub mod dynamic_snippets;
+pub mod expander;
+pub mod expiration;
+pub mod export_format;
+pub mod factory_reset;
+pub mod focus;
+pub mod gist;
+pub mod hyprland;
+pub mod import_jobs;
+pub mod importers;
+pub mod janitor;
+pub mod keyring;
+pub mod language;
+pub mod library_stats;
+#[cfg(target_os = "linux")]
+pub mod linux_clipboard;
+pub mod markdown_format;
 pub mod menubar;
+pub mod name_index;
+pub mod notifications;
+pub mod overlay_placement;
+pub mod portal_shortcuts;
+pub mod preview;
+pub mod pywal;
+pub mod quick_windows;
+pub mod resource_monitor;
 pub mod search;
 pub mod settings;
+pub mod share;
 pub mod shortcuts;
+pub mod snippet_aliases;
+pub mod snippet_order;
+pub mod snippet_parameters;
+pub mod snippet_slots;
+pub mod source_refresh;
+pub mod startup;
+pub mod tag_suggestions;
 pub mod tags;
+pub mod team_feeds;
+pub mod token_estimate;
+pub mod tray;
+pub mod vscode_snippets;
 pub mod window;
+pub mod window_layout;
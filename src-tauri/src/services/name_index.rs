@@ -0,0 +1,322 @@
+use crate::services::database::get_pool;
+use crate::utils::error::AppError;
+use sqlx::{Row, SqlitePool};
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, Manager};
+use tokio::sync::{RwLock, RwLockReadGuard};
+
+/// Largest edit distance tolerated, reached by terms of this many characters
+const MAX_TYPO_DISTANCE: usize = 2;
+
+/// Terms shorter than this must match exactly; "git" one edit away from
+/// "gif" or "get" is noise, not a typo
+const MIN_TYPO_TERM_LENGTH: usize = 4;
+
+/// Terms at least this long tolerate `MAX_TYPO_DISTANCE` edits, shorter ones one
+const LONG_TERM_LENGTH: usize = 6;
+
+/// Levenshtein distance between two strings, counted in characters
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// Edit distance tolerated for a search term of this length
+fn tolerance_for(term: &str) -> usize {
+    match term.chars().count() {
+        n if n < MIN_TYPO_TERM_LENGTH => 0,
+        n if n < LONG_TERM_LENGTH => 1,
+        _ => MAX_TYPO_DISTANCE,
+    }
+}
+
+/// Lowercased words of a snippet name
+fn name_words(name: &str) -> Vec<String> {
+    let mut words: Vec<String> = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    words.sort();
+    words.dedup();
+    words
+}
+
+#[derive(Debug)]
+struct BkNode {
+    word: String,
+    snippets: HashSet<i64>,
+    /// Child node index by edit distance to this node's word
+    children: HashMap<usize, usize>,
+}
+
+/// BK-tree of snippet name words for typo-tolerant lookup
+///
+/// Each distinct word is a node holding the snippets whose names contain it.
+/// A lookup with tolerance `d` only descends into children whose distance
+/// to the node is within `d` of the query's distance, so it visits a small
+/// part of the tree. Words are never removed from the tree; a word whose
+/// last snippet was renamed or deleted just holds no snippets.
+#[derive(Debug, Default)]
+pub struct NameIndex {
+    nodes: Vec<BkNode>,
+    positions: HashMap<String, usize>,
+    words_by_snippet: HashMap<i64, Vec<String>>,
+    loaded: bool,
+}
+
+impl NameIndex {
+    /// Adds a snippet's name, replacing any name indexed for it before
+    pub fn upsert(&mut self, id: i64, name: &str) {
+        self.remove(id);
+        let words = name_words(name);
+        for word in &words {
+            let position = self.insert_word(word);
+            self.nodes[position].snippets.insert(id);
+        }
+        self.words_by_snippet.insert(id, words);
+    }
+
+    /// Removes a deleted snippet
+    pub fn remove(&mut self, id: i64) {
+        for word in self.words_by_snippet.remove(&id).unwrap_or_default() {
+            if let Some(&position) = self.positions.get(&word) {
+                self.nodes[position].snippets.remove(&id);
+            }
+        }
+    }
+
+    fn insert_word(&mut self, word: &str) -> usize {
+        if let Some(&position) = self.positions.get(word) {
+            return position;
+        }
+
+        let new_position = self.nodes.len();
+        self.nodes.push(BkNode {
+            word: word.to_string(),
+            snippets: HashSet::new(),
+            children: HashMap::new(),
+        });
+        self.positions.insert(word.to_string(), new_position);
+
+        if new_position > 0 {
+            let mut current = 0;
+            loop {
+                let distance = edit_distance(word, &self.nodes[current].word);
+                match self.nodes[current].children.get(&distance) {
+                    Some(&child) => current = child,
+                    None => {
+                        self.nodes[current].children.insert(distance, new_position);
+                        break;
+                    }
+                }
+            }
+        }
+        new_position
+    }
+
+    /// Finds snippets with a name word within the term's typo tolerance
+    ///
+    /// Short terms (under four characters) tolerate no edits, terms up to
+    /// five characters one edit, longer terms two.
+    ///
+    /// # Returns
+    ///
+    /// Snippet IDs with their smallest edit distance, closest first
+    pub fn find(&self, term: &str) -> Vec<(i64, usize)> {
+        let term = term.to_lowercase();
+        let tolerance = tolerance_for(&term);
+        let mut best: HashMap<i64, usize> = HashMap::new();
+
+        let mut pending = if self.nodes.is_empty() {
+            Vec::new()
+        } else {
+            vec![0]
+        };
+        while let Some(position) = pending.pop() {
+            let node = &self.nodes[position];
+            let distance = edit_distance(&term, &node.word);
+            if distance <= tolerance {
+                for id in &node.snippets {
+                    let entry = best.entry(*id).or_insert(distance);
+                    *entry = (*entry).min(distance);
+                }
+            }
+            for (child_distance, child) in &node.children {
+                if child_distance.abs_diff(distance) <= tolerance {
+                    pending.push(*child);
+                }
+            }
+        }
+
+        let mut matches: Vec<(i64, usize)> = best.into_iter().collect();
+        matches.sort_by_key(|(id, distance)| (*distance, *id));
+        matches
+    }
+
+    /// Finds snippets matching any of the terms, closest first
+    pub fn find_any(&self, terms: &[&str]) -> Vec<i64> {
+        let mut best: HashMap<i64, usize> = HashMap::new();
+        for term in terms {
+            for (id, distance) in self.find(term) {
+                let entry = best.entry(id).or_insert(distance);
+                *entry = (*entry).min(distance);
+            }
+        }
+
+        let mut matches: Vec<(i64, usize)> = best.into_iter().collect();
+        matches.sort_by_key(|(id, distance)| (*distance, *id));
+        matches.into_iter().map(|(id, _)| id).collect()
+    }
+}
+
+/// State wrapper for the name index
+#[derive(Default)]
+pub struct NameIndexState(pub RwLock<NameIndex>);
+
+/// Returns the name index, loading it on first use
+///
+/// # Errors
+///
+/// Returns `AppError` if the index has to be loaded and the query fails
+pub async fn read(app: &AppHandle) -> Result<RwLockReadGuard<'_, NameIndex>, AppError> {
+    let state = app
+        .try_state::<NameIndexState>()
+        .ok_or_else(|| AppError::Unknown("Name index is not initialized".to_string()))?
+        .inner();
+
+    {
+        let index = state.0.read().await;
+        if index.loaded {
+            return Ok(index);
+        }
+    }
+
+    let pool = get_pool(app)?;
+    {
+        let mut index = state.0.write().await;
+        if !index.loaded {
+            *index = load_index(&pool).await?;
+        }
+    }
+    Ok(state.0.read().await)
+}
+
+/// Re-reads one snippet's name after it was created, renamed or deleted
+///
+/// Does nothing until the index has been loaded. If the snippet cannot be
+/// read the index is invalidated instead, so lookups are never stale.
+pub async fn refresh_snippet(app: &AppHandle, id: i64) {
+    let Some(state) = app.try_state::<NameIndexState>() else {
+        return;
+    };
+    let mut index = state.0.write().await;
+    if !index.loaded {
+        return;
+    }
+
+    let name = match get_pool(app) {
+        Ok(pool) => sqlx::query_scalar::<_, String>("SELECT name FROM snippets WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(AppError::from),
+        Err(e) => Err(e),
+    };
+    match name {
+        Ok(Some(name)) => index.upsert(id, &name),
+        Ok(None) => index.remove(id),
+        Err(_) => *index = NameIndex::default(),
+    }
+}
+
+/// Drops the index after bulk changes such as imports and restores
+pub async fn invalidate(app: &AppHandle) {
+    if let Some(state) = app.try_state::<NameIndexState>() {
+        *state.0.write().await = NameIndex::default();
+    }
+}
+
+async fn load_index(pool: &SqlitePool) -> Result<NameIndex, AppError> {
+    let rows = sqlx::query("SELECT id, name FROM snippets")
+        .fetch_all(pool)
+        .await?;
+
+    let mut index = NameIndex {
+        loaded: true,
+        ..Default::default()
+    };
+    for row in rows {
+        let name: String = row.get(1);
+        index.upsert(row.get(0), &name);
+    }
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> NameIndex {
+        let mut index = NameIndex::default();
+        index.upsert(1, "kubectl get pods");
+        index.upsert(2, "Kubectl: describe node");
+        index.upsert(3, "git undo commit");
+        index.upsert(4, "docker compose up");
+        index
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("kubectl", "kubectl"), 0);
+        assert_eq!(edit_distance("kubcetl", "kubectl"), 2);
+        assert_eq!(edit_distance("dockr", "docker"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("café", "cafe"), 1);
+    }
+
+    #[test]
+    fn test_find_tolerates_typos() {
+        let index = index();
+        let ids: Vec<i64> = index
+            .find("kubcetl")
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(ids, vec![1, 2]);
+        assert_eq!(index.find("dockr"), vec![(4, 1)]);
+        assert_eq!(index.find("comit"), vec![(3, 1)]);
+        // Short terms must match exactly
+        assert!(index.find("gti").is_empty());
+        assert_eq!(index.find("git"), vec![(3, 0)]);
+    }
+
+    #[test]
+    fn test_find_any_orders_by_distance() {
+        let index = index();
+        assert_eq!(index.find_any(&["describe", "kubcetl"]), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_upsert_and_remove() {
+        let mut index = index();
+        index.upsert(4, "podman compose up");
+        assert!(index.find("docker").is_empty());
+        assert_eq!(index.find("podmn"), vec![(4, 1)]);
+
+        index.remove(1);
+        assert_eq!(index.find("kubectl"), vec![(2, 0)]);
+    }
+}
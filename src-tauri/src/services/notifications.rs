@@ -0,0 +1,109 @@
+/// Native desktop notifications.
+///
+/// Background work that has no window to report to (scheduled backups,
+/// shortcuts) tells the user through the system's notification center.
+/// Every kind of notification can be switched off in the notification
+/// settings; failing to show one is logged and never fails the work that
+/// triggered it.
+use crate::models::settings::NotificationSettings;
+use crate::services::database::get_pool;
+use crate::services::settings::SettingsService;
+use crate::utils::error::AppError;
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+/// Events that can show a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    BackupCompleted,
+    BackupFailed,
+    SnippetCopied,
+    ShortcutRegistrationFailed,
+    SourceUpdated,
+}
+
+impl NotificationKind {
+    /// Title shown above the message
+    pub fn title(self) -> &'static str {
+        match self {
+            NotificationKind::BackupCompleted => "Backup completed",
+            NotificationKind::BackupFailed => "Backup failed",
+            NotificationKind::SnippetCopied => "Snippet copied",
+            NotificationKind::ShortcutRegistrationFailed => "Shortcuts unavailable",
+            NotificationKind::SourceUpdated => "Snippet sources updated",
+        }
+    }
+
+    /// Whether the settings allow this notification
+    pub fn is_enabled(self, settings: &NotificationSettings) -> bool {
+        settings.enabled
+            && match self {
+                NotificationKind::BackupCompleted => settings.backup_completed,
+                NotificationKind::BackupFailed => settings.backup_failed,
+                NotificationKind::SnippetCopied => settings.snippet_copied,
+                NotificationKind::ShortcutRegistrationFailed => settings.shortcut_failures,
+                NotificationKind::SourceUpdated => settings.source_updated,
+            }
+    }
+}
+
+/// Shows a notification if the settings allow it
+///
+/// # Returns
+///
+/// Whether the notification was shown
+///
+/// # Errors
+///
+/// Returns an error if the settings cannot be read or the notification
+/// center refuses the notification
+pub async fn send(app: &AppHandle, kind: NotificationKind, body: &str) -> Result<bool, AppError> {
+    let pool = get_pool(app)?;
+    let settings = SettingsService::new(pool).get_settings().await?;
+    if !kind.is_enabled(&settings.notification_settings) {
+        return Ok(false);
+    }
+
+    app.notification()
+        .builder()
+        .title(kind.title())
+        .body(body)
+        .show()
+        .map_err(|e| AppError::External(format!("Failed to show notification: {}", e)))?;
+    Ok(true)
+}
+
+/// Shows a notification in the background, logging any failure
+///
+/// For callers that must not wait on, or fail because of, a notification.
+pub fn notify(app: &AppHandle, kind: NotificationKind, body: impl Into<String>) {
+    let app = app.clone();
+    let body = body.into();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = send(&app, kind, &body).await {
+            eprintln!("Failed to notify \"{}\": {}", kind.title(), e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_enabled() {
+        let mut settings = NotificationSettings::default();
+        assert!(NotificationKind::BackupFailed.is_enabled(&settings));
+        assert!(!NotificationKind::BackupCompleted.is_enabled(&settings));
+        assert!(!NotificationKind::SnippetCopied.is_enabled(&settings));
+        assert!(NotificationKind::SourceUpdated.is_enabled(&settings));
+
+        settings.snippet_copied = true;
+        assert!(NotificationKind::SnippetCopied.is_enabled(&settings));
+
+        // The main switch silences everything
+        settings.enabled = false;
+        assert!(!NotificationKind::SnippetCopied.is_enabled(&settings));
+        assert!(!NotificationKind::BackupFailed.is_enabled(&settings));
+    }
+}
@@ -0,0 +1,338 @@
+/// Placement of the search and Quick Add overlays.
+///
+/// `center()` centers a window on whichever monitor it was last on, which
+/// on a multi-monitor desk is rarely the one the user is looking at. The
+/// overlays are centered on the monitor chosen by the `overlay_monitor`
+/// setting instead, or, with `search_placement` set to near-cursor, the
+/// search overlay opens next to the mouse cursor. Overlays are shown from
+/// synchronous shortcut handlers, so the settings are cached in memory and
+/// kept current from the settings-changed event.
+use crate::models::{AppSettings, OverlayMonitor, SearchPlacement};
+use crate::services::cursor;
+use crate::services::database::get_pool;
+use crate::services::focus::{self, FocusBackend};
+use crate::services::settings::{SettingsService, SETTINGS_CHANGED_EVENT};
+use crate::services::window::{
+    center_window, compositor_controls_positioning, should_ignore_positioning_error,
+};
+use crate::services::window_layout::MonitorArea;
+use crate::utils::error::AppError;
+use std::sync::Mutex;
+use tauri::{AppHandle, Listener, Manager, PhysicalPosition, WebviewWindow};
+
+/// Gap between the cursor and a window opened next to it, in logical pixels
+const CURSOR_GAP: f64 = 12.0;
+
+/// The placement settings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct OverlayPreferences {
+    monitor: OverlayMonitor,
+    search: SearchPlacement,
+}
+
+impl From<&AppSettings> for OverlayPreferences {
+    fn from(settings: &AppSettings) -> Self {
+        Self {
+            monitor: settings.overlay_monitor,
+            search: settings.search_placement,
+        }
+    }
+}
+
+/// Cached placement settings
+#[derive(Default)]
+pub struct OverlayPlacementState(Mutex<OverlayPreferences>);
+
+/// A point on the desktop, such as the cursor or the focused window's center
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScreenPoint {
+    Physical(f64, f64),
+    Logical(f64, f64),
+}
+
+/// The monitor containing a point, and the point in physical pixels
+pub fn locate(point: ScreenPoint, monitors: &[MonitorArea]) -> Option<((f64, f64), MonitorArea)> {
+    monitors.iter().find_map(|monitor| match point {
+        ScreenPoint::Physical(x, y) => monitor.contains(x, y).then_some(((x, y), *monitor)),
+        ScreenPoint::Logical(x, y) => monitor
+            .contains_logical(x, y)
+            .then(|| (monitor.to_physical(x, y), *monitor)),
+    })
+}
+
+/// Monitor to center an overlay on
+///
+/// The monitor containing `target` wins; without one (no target, or the
+/// target is between monitors) the primary monitor is used, then any.
+pub fn pick_monitor(
+    target: Option<ScreenPoint>,
+    monitors: &[MonitorArea],
+    primary: Option<MonitorArea>,
+) -> Option<MonitorArea> {
+    target
+        .and_then(|point| locate(point, monitors))
+        .map(|(_, monitor)| monitor)
+        .or(primary)
+        .or_else(|| monitors.first().copied())
+}
+
+/// Clamps a coordinate to `i32`
+fn to_i32(value: i64) -> i32 {
+    value.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32
+}
+
+/// Top-left corner that centers a window of the given outer size on a monitor
+pub fn centered_position(monitor: &MonitorArea, width: u32, height: u32) -> (i32, i32) {
+    let offset = |monitor_len: u32, window_len: u32| {
+        ((i64::from(monitor_len) - i64::from(window_len)) / 2).max(0)
+    };
+    let x = i64::from(monitor.x) + offset(monitor.width, width);
+    let y = i64::from(monitor.y) + offset(monitor.height, height);
+    (to_i32(x), to_i32(y))
+}
+
+/// Top-left corner that opens a window just below and right of the cursor
+///
+/// The window is pushed back inside the cursor's monitor where it would
+/// spill over an edge.
+pub fn near_cursor_position(
+    cursor: (f64, f64),
+    monitor: &MonitorArea,
+    width: u32,
+    height: u32,
+) -> (i32, i32) {
+    let (gap, _) = monitor.to_physical(CURSOR_GAP, 0.0);
+    let fit = |pointer: f64, start: i32, monitor_len: u32, window_len: u32| {
+        let start = i64::from(start);
+        let last = start + (i64::from(monitor_len) - i64::from(window_len)).max(0);
+        ((pointer + gap).round() as i64).clamp(start, last)
+    };
+    (
+        to_i32(fit(cursor.0, monitor.x, monitor.width, width)),
+        to_i32(fit(cursor.1, monitor.y, monitor.height, height)),
+    )
+}
+
+/// The cached placement settings
+fn preferences(app: &AppHandle) -> OverlayPreferences {
+    app.try_state::<OverlayPlacementState>()
+        .and_then(|state| state.0.lock().ok().map(|preferences| *preferences))
+        .unwrap_or_default()
+}
+
+fn set_preferences(app: &AppHandle, preferences: OverlayPreferences) {
+    if let Some(state) = app.try_state::<OverlayPlacementState>() {
+        if let Ok(mut current) = state.0.lock() {
+            *current = preferences;
+        }
+    }
+}
+
+/// Current cursor position, asking Tauri where the platform lookup has none
+fn cursor_point(window: &WebviewWindow) -> Option<ScreenPoint> {
+    cursor::cursor_position(FocusBackend::detect()).or_else(|| {
+        window
+            .cursor_position()
+            .ok()
+            .map(|position| ScreenPoint::Physical(position.x, position.y))
+    })
+}
+
+/// Where the user is looking, according to the setting
+fn target_point(window: &WebviewWindow, choice: OverlayMonitor) -> Option<ScreenPoint> {
+    match choice {
+        OverlayMonitor::Primary => None,
+        OverlayMonitor::Cursor => cursor_point(window),
+        OverlayMonitor::ActiveWindow => {
+            let backend = FocusBackend::detect();
+            let bounds = match focus::active_window_bounds(backend) {
+                Ok(bounds) => bounds?,
+                Err(e) => {
+                    eprintln!("Failed to get active window bounds: {}", e);
+                    return None;
+                }
+            };
+            let (x, y) = bounds.center();
+            Some(match backend {
+                FocusBackend::X11 => ScreenPoint::Physical(x, y),
+                _ => ScreenPoint::Logical(x, y),
+            })
+        }
+    }
+}
+
+fn monitor_areas(window: &WebviewWindow) -> Vec<MonitorArea> {
+    window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(MonitorArea::from_monitor)
+        .collect()
+}
+
+/// Moves a window, ignoring compositors that do not allow it
+fn move_window(window: &WebviewWindow, (x, y): (i32, i32)) -> Result<(), AppError> {
+    match window.set_position(PhysicalPosition::new(x, y)) {
+        Err(e) if !should_ignore_positioning_error(&e) => Err(AppError::TauriError(e.to_string())),
+        _ => Ok(()),
+    }
+}
+
+/// Centers an overlay on the monitor chosen by the `overlay_monitor` setting
+///
+/// Must run before the overlay is shown: the active window is looked up
+/// while it still has focus. Where the compositor places windows itself
+/// this only asks it to center.
+pub fn place_overlay(window: &WebviewWindow) -> Result<(), AppError> {
+    if compositor_controls_positioning() {
+        return center_window(window);
+    }
+
+    let choice = preferences(window.app_handle()).monitor;
+    let monitors = monitor_areas(window);
+    let primary = window
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .map(|monitor| MonitorArea::from_monitor(&monitor));
+
+    let Some(monitor) = pick_monitor(target_point(window, choice), &monitors, primary) else {
+        return center_window(window);
+    };
+    let size = window
+        .outer_size()
+        .map_err(|e| AppError::TauriError(e.to_string()))?;
+    move_window(window, centered_position(&monitor, size.width, size.height))
+}
+
+/// Opens a window next to the mouse cursor
+///
+/// Falls back to `place_overlay` when the cursor position is unknown,
+/// which is the case on most Wayland compositors.
+pub fn place_near_cursor(window: &WebviewWindow) -> Result<(), AppError> {
+    if compositor_controls_positioning() {
+        return place_overlay(window);
+    }
+
+    let monitors = monitor_areas(window);
+    let Some((cursor, monitor)) = cursor_point(window).and_then(|point| locate(point, &monitors))
+    else {
+        return place_overlay(window);
+    };
+    let size = window
+        .outer_size()
+        .map_err(|e| AppError::TauriError(e.to_string()))?;
+    move_window(
+        window,
+        near_cursor_position(cursor, &monitor, size.width, size.height),
+    )
+}
+
+/// Places the search overlay according to the `search_placement` setting
+pub fn place_search_overlay(window: &WebviewWindow) -> Result<(), AppError> {
+    match preferences(window.app_handle()).search {
+        SearchPlacement::Center => place_overlay(window),
+        SearchPlacement::NearCursor => place_near_cursor(window),
+    }
+}
+
+/// Loads the settings and keeps them current as settings change
+pub async fn start(app: &AppHandle) -> Result<(), AppError> {
+    let settings = SettingsService::new(get_pool(app)?).get_settings().await?;
+    set_preferences(app, OverlayPreferences::from(&settings));
+
+    let handle = app.clone();
+    app.listen_any(
+        SETTINGS_CHANGED_EVENT,
+        move |event| match serde_json::from_str::<AppSettings>(event.payload()) {
+            Ok(settings) => set_preferences(&handle, OverlayPreferences::from(&settings)),
+            Err(e) => eprintln!("Failed to read changed settings: {}", e),
+        },
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEFT: MonitorArea = MonitorArea {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1080,
+        scale_factor: 1.0,
+    };
+    const RIGHT: MonitorArea = MonitorArea {
+        x: 1920,
+        y: 0,
+        width: 2560,
+        height: 1440,
+        scale_factor: 2.0,
+    };
+
+    #[test]
+    fn test_pick_monitor() {
+        let monitors = [LEFT, RIGHT];
+
+        let cursor = Some(ScreenPoint::Physical(2500.0, 300.0));
+        assert_eq!(pick_monitor(cursor, &monitors, Some(LEFT)), Some(RIGHT));
+
+        // Logical points are scaled by each monitor's factor
+        let window = Some(ScreenPoint::Logical(2000.0, 200.0));
+        assert_eq!(pick_monitor(window, &monitors, Some(LEFT)), Some(RIGHT));
+
+        // No target or a target off every monitor falls back to the primary
+        assert_eq!(pick_monitor(None, &monitors, Some(RIGHT)), Some(RIGHT));
+        let lost = Some(ScreenPoint::Physical(-50.0, 0.0));
+        assert_eq!(pick_monitor(lost, &monitors, Some(RIGHT)), Some(RIGHT));
+
+        // Then to any monitor
+        assert_eq!(pick_monitor(None, &monitors, None), Some(LEFT));
+        assert_eq!(pick_monitor(None, &[], None), None);
+    }
+
+    #[test]
+    fn test_locate() {
+        let monitors = [LEFT, RIGHT];
+        assert_eq!(
+            locate(ScreenPoint::Logical(1000.0, 100.0), &monitors),
+            Some(((1000.0, 100.0), LEFT))
+        );
+        assert_eq!(
+            locate(ScreenPoint::Logical(2000.0, 100.0), &monitors),
+            Some(((4000.0, 200.0), RIGHT))
+        );
+        assert_eq!(locate(ScreenPoint::Physical(0.0, 2000.0), &monitors), None);
+    }
+
+    #[test]
+    fn test_near_cursor_position() {
+        assert_eq!(
+            near_cursor_position((100.0, 100.0), &LEFT, 600, 400),
+            (112, 112)
+        );
+        // The gap is scaled like the monitor
+        assert_eq!(
+            near_cursor_position((2000.0, 100.0), &RIGHT, 600, 400),
+            (2024, 124)
+        );
+        // Kept on the monitor near its bottom-right corner
+        assert_eq!(
+            near_cursor_position((1900.0, 1000.0), &LEFT, 600, 400),
+            (1320, 680)
+        );
+        assert_eq!(
+            near_cursor_position((1900.0, 1000.0), &LEFT, 4000, 400),
+            (0, 680)
+        );
+    }
+
+    #[test]
+    fn test_centered_position() {
+        assert_eq!(centered_position(&LEFT, 600, 400), (660, 340));
+        assert_eq!(centered_position(&RIGHT, 600, 400), (2900, 520));
+        // A window larger than the monitor starts at its corner
+        assert_eq!(centered_position(&LEFT, 4000, 400), (0, 340));
+    }
+}
@@ -0,0 +1,452 @@
+/// Global shortcuts through the XDG desktop portal.
+///
+/// Wayland compositors do not let clients grab keys, so the global shortcut
+/// plugin only sees key presses while an XWayland window has focus. GNOME
+/// and KDE instead offer the `org.freedesktop.portal.GlobalShortcuts`
+/// interface: the app describes its shortcuts with a preferred trigger, the
+/// desktop lets the user confirm or change the keys, and activations arrive
+/// as D-Bus signals. The whole set is bound again in a fresh session
+/// whenever shortcuts change, since a session can only be bound once.
+use crate::models::GlobalShortcuts;
+use crate::services::shortcuts;
+use crate::utils::error::AppError;
+use tauri::AppHandle;
+
+/// What a portal shortcut does when activated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutAction {
+    Search,
+    QuickAdd,
+    QuickPaste,
+    Slot(u8),
+}
+
+impl ShortcutAction {
+    /// ID the shortcut is bound under
+    pub fn id(self) -> String {
+        match self {
+            Self::Search => "search".to_string(),
+            Self::QuickAdd => "quick-add".to_string(),
+            Self::QuickPaste => "quick-paste".to_string(),
+            Self::Slot(slot) => format!("slot-{}", slot),
+        }
+    }
+
+    /// Action for a bound ID
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "search" => Some(Self::Search),
+            "quick-add" => Some(Self::QuickAdd),
+            "quick-paste" => Some(Self::QuickPaste),
+            _ => id.strip_prefix("slot-")?.parse().ok().map(Self::Slot),
+        }
+    }
+
+    /// Description the desktop shows in its shortcut settings
+    pub fn description(self) -> String {
+        match self {
+            Self::Search => "Search snippets".to_string(),
+            Self::QuickAdd => "Quick Add snippet".to_string(),
+            Self::QuickPaste => "Copy the last used snippet".to_string(),
+            Self::Slot(slot) => format!("Copy the snippet in slot {}", slot),
+        }
+    }
+}
+
+/// A shortcut as handed to the portal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortalShortcut {
+    pub action: ShortcutAction,
+    /// Keys in the XDG shortcuts format; the user may pick others
+    pub preferred_trigger: Option<String>,
+}
+
+/// Converts a shortcut string such as `CmdOrCtrl+Shift+S` to the XDG
+/// shortcuts format (`CTRL+SHIFT+s`)
+pub fn portal_trigger(shortcut: &str) -> Option<String> {
+    let parts: Vec<&str> = shortcut.split('+').map(str::trim).collect();
+    let (key, modifiers) = parts.split_last()?;
+
+    let mut trigger = Vec::new();
+    for modifier in modifiers {
+        let name = match modifier.to_ascii_lowercase().as_str() {
+            "cmdorctrl" | "commandorcontrol" | "ctrl" | "control" => "CTRL",
+            "shift" => "SHIFT",
+            "alt" | "option" => "ALT",
+            "cmd" | "command" | "super" | "meta" => "LOGO",
+            _ => return None,
+        };
+        if !trigger.iter().any(|existing| existing == name) {
+            trigger.push(name.to_string());
+        }
+    }
+
+    let key = key
+        .strip_prefix("Key")
+        .or_else(|| key.strip_prefix("Digit"))
+        .filter(|rest| rest.len() == 1)
+        .unwrap_or(key);
+    let key = match key.to_ascii_lowercase().as_str() {
+        "" => return None,
+        "space" => "space".to_string(),
+        "enter" | "return" => "Return".to_string(),
+        "esc" | "escape" => "Escape".to_string(),
+        "tab" => "Tab".to_string(),
+        _ if key.chars().count() == 1 => key.to_lowercase(),
+        _ => key.to_string(),
+    };
+    trigger.push(key);
+    Some(trigger.join("+"))
+}
+
+/// The shortcuts to bind for the current settings and slot assignments
+pub fn shortcut_list(settings: &GlobalShortcuts, slots: &[u8]) -> Vec<PortalShortcut> {
+    let mut list = vec![
+        PortalShortcut {
+            action: ShortcutAction::Search,
+            preferred_trigger: portal_trigger(&settings.search_select),
+        },
+        PortalShortcut {
+            action: ShortcutAction::QuickAdd,
+            preferred_trigger: portal_trigger(&settings.quick_add),
+        },
+    ];
+    if !settings.quick_paste.trim().is_empty() {
+        list.push(PortalShortcut {
+            action: ShortcutAction::QuickPaste,
+            preferred_trigger: portal_trigger(&settings.quick_paste),
+        });
+    }
+    list.extend(slots.iter().map(|&slot| PortalShortcut {
+        action: ShortcutAction::Slot(slot),
+        preferred_trigger: portal_trigger(&shortcuts::slot_shortcut(slot)),
+    }));
+    list
+}
+
+/// Whether this is a Wayland session, where the portal is used
+pub fn is_wayland_session() -> bool {
+    let set = |name: &str| std::env::var_os(name).is_some_and(|v| !v.is_empty());
+    cfg!(target_os = "linux")
+        && (set("WAYLAND_DISPLAY")
+            || std::env::var("XDG_SESSION_TYPE").is_ok_and(|kind| kind == "wayland"))
+}
+
+#[cfg(target_os = "linux")]
+mod portal {
+    use super::{PortalShortcut, ShortcutAction};
+    use crate::services::{shortcuts, window};
+    use crate::utils::error::AppError;
+    use futures_util::StreamExt;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use tauri::async_runtime::JoinHandle;
+    use tauri::AppHandle;
+    use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+    use zbus::{Connection, Proxy};
+
+    const DESTINATION: &str = "org.freedesktop.portal.Desktop";
+    const PATH: &str = "/org/freedesktop/portal/desktop";
+    const INTERFACE: &str = "org.freedesktop.portal.GlobalShortcuts";
+
+    /// Makes request and session handle tokens unique within the process
+    static NEXT_TOKEN: AtomicU32 = AtomicU32::new(0);
+
+    /// A bound set of shortcuts; dropping it without `close` leaks the session
+    pub struct PortalSession {
+        connection: Connection,
+        session: OwnedObjectPath,
+        listener: JoinHandle<()>,
+    }
+
+    impl PortalSession {
+        /// Releases the shortcuts and stops listening for them
+        pub async fn close(self) -> Result<(), AppError> {
+            self.listener.abort();
+            let session = Proxy::new(
+                &self.connection,
+                DESTINATION,
+                self.session.clone().into_inner(),
+                "org.freedesktop.portal.Session",
+            )
+            .await
+            .map_err(to_error)?;
+            session.call_method("Close", &()).await.map_err(to_error)?;
+            Ok(())
+        }
+    }
+
+    /// Runs an activated shortcut on the main thread, like the plugin's handlers
+    fn run_action(app: &AppHandle, action: ShortcutAction) {
+        let handle = app.clone();
+        let result = app.run_on_main_thread(move || {
+            let result = match action {
+                ShortcutAction::Search => window::toggle_search_window(&handle),
+                ShortcutAction::QuickAdd => window::show_quick_add_window(&handle),
+                ShortcutAction::QuickPaste => {
+                    shortcuts::copy_last_used_snippet(&handle);
+                    Ok(())
+                }
+                ShortcutAction::Slot(slot) => {
+                    shortcuts::copy_slot_snippet(&handle, slot);
+                    Ok(())
+                }
+            };
+            if let Err(e) = result {
+                eprintln!("Failed to run portal shortcut {}: {}", action.id(), e);
+            }
+        });
+        if let Err(e) = result {
+            eprintln!("Failed to dispatch portal shortcut {}: {}", action.id(), e);
+        }
+    }
+
+    fn to_error(e: zbus::Error) -> AppError {
+        AppError::External(format!("Global shortcuts portal: {}", e))
+    }
+
+    fn new_token() -> String {
+        format!(
+            "snips_{}_{}",
+            std::process::id(),
+            NEXT_TOKEN.fetch_add(1, Ordering::Relaxed)
+        )
+    }
+
+    /// The caller's unique bus name as used in request and session paths
+    fn sender(connection: &Connection) -> Result<String, AppError> {
+        let name = connection
+            .unique_name()
+            .ok_or_else(|| AppError::External("No unique D-Bus name".to_string()))?;
+        Ok(name.as_str().trim_start_matches(':').replace('.', "_"))
+    }
+
+    /// Calls a portal method and waits for its `Response`
+    ///
+    /// The response arrives as a signal on a request object whose path
+    /// follows from the handle token, so it is subscribed to before the
+    /// call to avoid missing a quick reply.
+    async fn call_request<B>(
+        connection: &Connection,
+        portal: &Proxy<'static>,
+        method: &str,
+        token: &str,
+        body: &B,
+    ) -> Result<HashMap<String, OwnedValue>, AppError>
+    where
+        B: serde::Serialize + zbus::zvariant::DynamicType,
+    {
+        let path = format!("{}/request/{}/{}", PATH, sender(connection)?, token);
+        let request = Proxy::new(
+            connection,
+            DESTINATION,
+            path,
+            "org.freedesktop.portal.Request",
+        )
+        .await
+        .map_err(to_error)?;
+        let mut responses = request.receive_signal("Response").await.map_err(to_error)?;
+        portal.call_method(method, body).await.map_err(to_error)?;
+
+        let message = responses
+            .next()
+            .await
+            .ok_or_else(|| AppError::External(format!("No response to {}", method)))?;
+        let (code, results): (u32, HashMap<String, OwnedValue>) =
+            message.body().deserialize().map_err(to_error)?;
+        match code {
+            0 => Ok(results),
+            1 => Err(AppError::External(format!("{} was cancelled", method))),
+            _ => Err(AppError::External(format!("{} failed", method))),
+        }
+    }
+
+    /// Creates a session, binds the shortcuts and listens for activations
+    pub async fn bind(
+        app: &AppHandle,
+        shortcuts: &[PortalShortcut],
+    ) -> Result<PortalSession, AppError> {
+        let connection = Connection::session().await.map_err(to_error)?;
+        let portal = Proxy::new(&connection, DESTINATION, PATH, INTERFACE)
+            .await
+            .map_err(to_error)?;
+
+        let handle_token = new_token();
+        let session_token = new_token();
+        let options: HashMap<&str, Value> = HashMap::from([
+            ("handle_token", Value::from(handle_token.as_str())),
+            ("session_handle_token", Value::from(session_token.as_str())),
+        ]);
+        call_request(
+            &connection,
+            &portal,
+            "CreateSession",
+            &handle_token,
+            &(options,),
+        )
+        .await?;
+        let session = OwnedObjectPath::try_from(format!(
+            "{}/session/{}/{}",
+            PATH,
+            sender(&connection)?,
+            session_token
+        ))
+        .map_err(|e| AppError::External(e.to_string()))?;
+
+        let list: Vec<(String, HashMap<&str, Value>)> = shortcuts
+            .iter()
+            .map(|shortcut| {
+                let mut properties =
+                    HashMap::from([("description", Value::from(shortcut.action.description()))]);
+                if let Some(trigger) = &shortcut.preferred_trigger {
+                    properties.insert("preferred_trigger", Value::from(trigger.as_str()));
+                }
+                (shortcut.action.id(), properties)
+            })
+            .collect();
+        let mut activations = portal.receive_signal("Activated").await.map_err(to_error)?;
+        let handle_token = new_token();
+        let options: HashMap<&str, Value> =
+            HashMap::from([("handle_token", Value::from(handle_token.as_str()))]);
+        call_request(
+            &connection,
+            &portal,
+            "BindShortcuts",
+            &handle_token,
+            &(session.clone().into_inner(), list, "", options),
+        )
+        .await?;
+
+        let app = app.clone();
+        let bound = session.clone();
+        let listener = tauri::async_runtime::spawn(async move {
+            while let Some(message) = activations.next().await {
+                let Ok((session, id, _timestamp, _options)) =
+                    message
+                        .body()
+                        .deserialize::<(OwnedObjectPath, String, u64, HashMap<String, OwnedValue>)>(
+                        )
+                else {
+                    continue;
+                };
+                if session != bound {
+                    continue;
+                }
+                match ShortcutAction::from_id(&id) {
+                    Some(action) => run_action(&app, action),
+                    None => eprintln!("Ignoring unknown portal shortcut {}", id),
+                }
+            }
+        });
+
+        Ok(PortalSession {
+            connection,
+            session,
+            listener,
+        })
+    }
+}
+
+/// The current portal session, replaced whenever shortcuts change
+#[derive(Default)]
+pub struct PortalShortcutState {
+    #[cfg(target_os = "linux")]
+    session: tokio::sync::Mutex<Option<portal::PortalSession>>,
+}
+
+/// Binds the shortcuts through the portal, replacing any earlier binding
+///
+/// # Errors
+///
+/// Returns `AppError` if the portal is missing or refuses the shortcuts
+#[cfg(target_os = "linux")]
+pub async fn rebind(
+    app: &AppHandle,
+    state: &PortalShortcutState,
+    settings: &GlobalShortcuts,
+    slots: &[u8],
+) -> Result<(), AppError> {
+    let mut current = state.session.lock().await;
+    if let Some(session) = current.take() {
+        if let Err(e) = session.close().await {
+            eprintln!("Failed to close portal shortcut session: {}", e);
+        }
+    }
+    *current = Some(portal::bind(app, &shortcut_list(settings, slots)).await?);
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub async fn rebind(
+    _app: &AppHandle,
+    _state: &PortalShortcutState,
+    _settings: &GlobalShortcuts,
+    _slots: &[u8],
+) -> Result<(), AppError> {
+    Err(AppError::Unsupported(
+        "The global shortcuts portal is only available on Linux".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portal_trigger() {
+        assert_eq!(
+            portal_trigger("CmdOrCtrl+Shift+S").as_deref(),
+            Some("CTRL+SHIFT+s")
+        );
+        assert_eq!(
+            portal_trigger("CommandOrControl+Shift+L").as_deref(),
+            Some("CTRL+SHIFT+l")
+        );
+        assert_eq!(
+            portal_trigger("Super+Alt+Space").as_deref(),
+            Some("LOGO+ALT+space")
+        );
+        assert_eq!(portal_trigger("Ctrl+KeyK").as_deref(), Some("CTRL+k"));
+        assert_eq!(
+            portal_trigger("Ctrl+Shift+Digit1").as_deref(),
+            Some("CTRL+SHIFT+1")
+        );
+        assert_eq!(portal_trigger("Ctrl+F5").as_deref(), Some("CTRL+F5"));
+        assert_eq!(portal_trigger("Hyper+S"), None);
+        assert_eq!(portal_trigger("Ctrl+"), None);
+    }
+
+    #[test]
+    fn test_shortcut_action_ids() {
+        for action in [
+            ShortcutAction::Search,
+            ShortcutAction::QuickAdd,
+            ShortcutAction::QuickPaste,
+            ShortcutAction::Slot(3),
+        ] {
+            assert_eq!(ShortcutAction::from_id(&action.id()), Some(action));
+        }
+        assert_eq!(ShortcutAction::from_id("slot-x"), None);
+        assert_eq!(ShortcutAction::from_id("settings"), None);
+    }
+
+    #[test]
+    fn test_shortcut_list() {
+        let settings = GlobalShortcuts {
+            quick_add: "CmdOrCtrl+Shift+A".to_string(),
+            search_select: "CmdOrCtrl+Shift+S".to_string(),
+            quick_paste: String::new(),
+        };
+        let list = shortcut_list(&settings, &[2]);
+        let actions: Vec<ShortcutAction> = list.iter().map(|s| s.action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                ShortcutAction::Search,
+                ShortcutAction::QuickAdd,
+                ShortcutAction::Slot(2)
+            ]
+        );
+        assert_eq!(list[2].preferred_trigger.as_deref(), Some("CTRL+SHIFT+2"));
+    }
+}
@@ -0,0 +1,381 @@
+use crate::services::language::{language_from_tags, language_id_for_tag};
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Options, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use syntect::html::{ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+/// Requested rendering mode for a snippet preview
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PreviewFormat {
+    /// Pick markdown, code or plain text based on the snippet content and tags
+    #[default]
+    Auto,
+    Markdown,
+    Code,
+    Plain,
+}
+
+/// HTML preview of a snippet, safe to inject into any window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RenderedPreview {
+    /// The format that was actually used (never `Auto`)
+    pub format: PreviewFormat,
+    /// Language used for syntax highlighting, if any
+    pub language: Option<String>,
+    pub html: String,
+}
+
+/// Prefix of the token classes emitted by the highlighter, e.g.
+/// `tok-keyword` or `tok-string`; styled by the frontend
+const TOKEN_CLASS_PREFIX: &str = "tok-";
+
+/// Language identifiers without a syntax of the same name in syntect's
+/// default set, mapped to the closest one
+const SYNTAX_ALIASES: &[(&str, &str)] = &[
+    ("shellscript", "sh"),
+    ("javascriptreact", "js"),
+    ("typescript", "js"),
+    ("typescriptreact", "js"),
+];
+
+/// Renders snippet content as a sanitized HTML preview
+///
+/// Raw HTML embedded in a snippet is always displayed literally and never
+/// executed, and the markup is run through a sanitizer before it is returned.
+///
+/// # Arguments
+///
+/// * `content` - The snippet content to render
+/// * `format` - Requested format; `Auto` chooses one from the content and tags
+/// * `tags` - Snippet tags; a language tag picks the highlighting language
+/// * `language` - The snippet's stored language, used when no tag names one
+///
+/// # Returns
+///
+/// The rendered preview along with the format and language actually used
+pub fn render_preview(
+    content: &str,
+    format: PreviewFormat,
+    tags: &[String],
+    language: Option<&str>,
+) -> RenderedPreview {
+    let language = language_from_tags(tags).or(language);
+
+    let format = match format {
+        PreviewFormat::Auto => match language {
+            Some("markdown") => PreviewFormat::Markdown,
+            Some(_) => PreviewFormat::Code,
+            None if content.trim_start().starts_with("#!") => PreviewFormat::Code,
+            None if looks_like_markdown(content) => PreviewFormat::Markdown,
+            None => PreviewFormat::Plain,
+        },
+        other => other,
+    };
+
+    match format {
+        PreviewFormat::Markdown => RenderedPreview {
+            format,
+            language: None,
+            html: render_markdown(content),
+        },
+        PreviewFormat::Code => RenderedPreview {
+            format,
+            language: language.map(str::to_string),
+            html: sanitize(&render_code_block(content, language)),
+        },
+        _ => RenderedPreview {
+            format: PreviewFormat::Plain,
+            language: None,
+            html: format!(
+                "<pre class=\"snips-plain\">{}</pre>\n",
+                escape_text(content)
+            ),
+        },
+    }
+}
+
+/// Cheap check for markdown structure, used by `PreviewFormat::Auto`
+fn looks_like_markdown(content: &str) -> bool {
+    content.contains("](")
+        || content.contains("**")
+        || content.lines().any(|line| {
+            let line = line.trim_start();
+            line.starts_with("> ")
+                || line.starts_with("```")
+                || line.starts_with("~~~")
+                || is_heading(line)
+        })
+}
+
+fn is_heading(line: &str) -> bool {
+    let level = line.len() - line.trim_start_matches('#').len();
+    (1..=6).contains(&level) && line[level..].starts_with(' ')
+}
+
+/// Escapes text for use as an HTML text node
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    html::push_html(&mut escaped, std::iter::once(Event::Text(text.into())));
+    escaped
+}
+
+/// Strips anything but formatting markup and safe links from rendered HTML
+///
+/// Links are only kept for `http`, `https` and `mailto` targets.
+fn sanitize(html: &str) -> String {
+    ammonia::Builder::default()
+        .url_schemes(HashSet::from(["http", "https", "mailto"]))
+        .link_rel(Some("noopener noreferrer"))
+        .add_tag_attributes("pre", &["class"])
+        .add_tag_attributes("code", &["class"])
+        .add_tag_attributes("span", &["class"])
+        .clean(html)
+        .to_string()
+}
+
+// ============================================================================
+// Markdown
+// ============================================================================
+
+/// Renders a markdown document to sanitized HTML
+///
+/// Raw HTML in the document is rendered as text and fenced code blocks are
+/// highlighted like code snippets.
+pub fn render_markdown(source: &str) -> String {
+    let mut events = Vec::new();
+    let mut code_block: Option<(Option<String>, String)> = None;
+
+    for event in Parser::new_ext(
+        source,
+        Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES,
+    ) {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        info.split_whitespace().next().map(str::to_string)
+                    }
+                    CodeBlockKind::Indented => None,
+                };
+                code_block = Some((language, String::new()));
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((language, code)) = code_block.take() {
+                    let html = render_code_block(&code, language.as_deref());
+                    events.push(Event::Html(CowStr::from(html)));
+                }
+            }
+            Event::Text(text) => match code_block.as_mut() {
+                Some((_, code)) => code.push_str(&text),
+                None => events.push(Event::Text(text)),
+            },
+            Event::Html(raw) | Event::InlineHtml(raw) => events.push(Event::Text(raw)),
+            other => events.push(other),
+        }
+    }
+
+    let mut html = String::new();
+    html::push_html(&mut html, events.into_iter());
+    sanitize(&html)
+}
+
+// ============================================================================
+// Code highlighting
+// ============================================================================
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn find_syntax(language: &str) -> Option<&'static SyntaxReference> {
+    let language = language_id_for_tag(language).unwrap_or(language);
+    let token = SYNTAX_ALIASES
+        .iter()
+        .find(|(id, _)| *id == language)
+        .map_or(language, |(_, token)| *token);
+    syntax_set().find_syntax_by_token(token)
+}
+
+/// Renders a code block, highlighting it when the language is known
+///
+/// Unknown languages are still rendered (escaped) and keep a sanitized
+/// `language-*` class so the frontend can style them.
+pub fn render_code_block(code: &str, language: Option<&str>) -> String {
+    let class = language
+        .map(|name| {
+            let name = name.trim().to_lowercase();
+            let name = language_id_for_tag(&name).map_or(name, str::to_string);
+            name.chars()
+                .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '+')
+                .collect::<String>()
+        })
+        .filter(|class| !class.is_empty());
+
+    let body = language
+        .and_then(find_syntax)
+        .and_then(|syntax| highlight(code, syntax))
+        .unwrap_or_else(|| escape_text(code));
+
+    match class {
+        Some(class) => format!(
+            "<pre class=\"snips-code\"><code class=\"language-{}\">{}</code></pre>\n",
+            class, body
+        ),
+        None => format!("<pre class=\"snips-code\"><code>{}</code></pre>\n", body),
+    }
+}
+
+/// Highlights code as `<span>`s with `tok-` prefixed scope classes
+///
+/// Returns `None` if the syntax fails to parse the code.
+fn highlight(code: &str, syntax: &SyntaxReference) -> Option<String> {
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(
+        syntax,
+        syntax_set(),
+        ClassStyle::SpacedPrefixed {
+            prefix: TOKEN_CLASS_PREFIX,
+        },
+    );
+    for line in LinesWithEndings::from(code) {
+        generator
+            .parse_html_for_line_which_includes_newline(line)
+            .ok()?;
+    }
+    Some(generator.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(
+            escape_text("<b>Tom & Jerry</b>"),
+            "&lt;b&gt;Tom &amp; Jerry&lt;/b&gt;"
+        );
+    }
+
+    #[test]
+    fn test_markdown_blocks() {
+        let html = render_markdown("# Title\n\nFirst *line*\nsecond\n\n- one\n- **two**\n\n---");
+        assert_eq!(
+            html,
+            "<h1>Title</h1>\n<p>First <em>line</em>\nsecond</p>\n<ul>\n<li>one</li>\n<li><strong>two</strong></li>\n</ul>\n<hr>\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_ordered_list_and_quote() {
+        let html = render_markdown("3. three\n4. four\n\n> quoted `code`");
+        assert!(html.contains("<ol start=\"3\">\n<li>three</li>\n<li>four</li>\n</ol>"));
+        assert!(html.contains("<blockquote>"));
+        assert!(html.contains("<p>quoted <code>code</code></p>"));
+    }
+
+    #[test]
+    fn test_markdown_escapes_raw_html() {
+        let html = render_markdown("<script>alert('x')</script>\n\n<img src=x onerror=alert(1)>");
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("<img"));
+        assert!(html.contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn test_markdown_links_are_sanitized() {
+        let safe = render_markdown("[docs](https://example.com \"Docs\")");
+        assert!(safe.contains("href=\"https://example.com\""));
+        assert!(safe.contains("rel=\"noopener noreferrer\""));
+
+        let unsafe_link = render_markdown("[click](javascript:alert(1))");
+        assert!(!unsafe_link.contains("javascript:"));
+        assert!(unsafe_link.contains("click"));
+
+        let autolink = render_markdown("<https://example.com/a?b=1&c=2>");
+        assert!(autolink.contains("href=\"https://example.com/a?b=1&amp;c=2\""));
+    }
+
+    #[test]
+    fn test_markdown_fenced_code_is_highlighted() {
+        let html = render_markdown("```rust\nlet x = \"<b>\";\n```");
+        assert!(html.starts_with("<pre class=\"snips-code\"><code class=\"language-rust\">"));
+        assert!(html.contains("tok-string"));
+        assert!(html.contains("&lt;b&gt;"));
+        assert!(!html.contains("<b>"));
+    }
+
+    #[test]
+    fn test_intraword_underscores_are_literal() {
+        assert_eq!(
+            render_markdown("snake_case_name"),
+            "<p>snake_case_name</p>\n"
+        );
+    }
+
+    #[test]
+    fn test_unknown_language_class_is_sanitized() {
+        let html = render_code_block("x", Some("evil\"><script>"));
+        assert_eq!(
+            html,
+            "<pre class=\"snips-code\"><code class=\"language-evilscript\">x</code></pre>\n"
+        );
+    }
+
+    #[test]
+    fn test_highlight_comments_and_numbers() {
+        let html = render_code_block("SELECT 1 -- one", Some("sql"));
+        assert!(html.contains("tok-keyword"));
+        assert!(html.contains("tok-numeric"));
+        assert!(html.contains("tok-comment"));
+    }
+
+    #[test]
+    fn test_auto_format_detection() {
+        let markdown = render_preview("## Notes\n\nSee **this**", PreviewFormat::Auto, &[], None);
+        assert_eq!(markdown.format, PreviewFormat::Markdown);
+
+        let code = render_preview(
+            "#!/bin/bash\necho hi",
+            PreviewFormat::Auto,
+            &[],
+            Some("shellscript"),
+        );
+        assert_eq!(code.format, PreviewFormat::Code);
+        assert_eq!(code.language.as_deref(), Some("shellscript"));
+        assert!(code.html.contains("tok-"));
+
+        let tagged = render_preview(
+            "x = 1",
+            PreviewFormat::Auto,
+            &["py".to_string()],
+            Some("ruby"),
+        );
+        assert_eq!(tagged.format, PreviewFormat::Code);
+        assert_eq!(tagged.language.as_deref(), Some("python"));
+
+        let stored_markdown = render_preview("notes", PreviewFormat::Auto, &[], Some("markdown"));
+        assert_eq!(stored_markdown.format, PreviewFormat::Markdown);
+
+        let plain = render_preview("Best regards, <me>", PreviewFormat::Auto, &[], None);
+        assert_eq!(plain.format, PreviewFormat::Plain);
+        assert_eq!(
+            plain.html,
+            "<pre class=\"snips-plain\">Best regards, &lt;me&gt;</pre>\n"
+        );
+    }
+
+    #[test]
+    fn test_format_serialization() {
+        assert_eq!(
+            serde_json::to_string(&PreviewFormat::Markdown).unwrap(),
+            "\"markdown\""
+        );
+        let format: PreviewFormat = serde_json::from_str("\"auto\"").unwrap();
+        assert_eq!(format, PreviewFormat::Auto);
+    }
+}
@@ -0,0 +1,124 @@
+/// Turns a pywal or wallust color scheme into a custom theme.
+///
+/// Both tools write the colors picked from the wallpaper to
+/// `~/.cache/wal/colors.json`, which lets Snips match the rest of a themed
+/// desktop.
+use crate::services::custom_themes::{CustomTheme, Hsl};
+use crate::utils::error::AppError;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+/// Name given to an imported scheme when none is chosen
+pub const DEFAULT_THEME_NAME: &str = "Pywal";
+
+/// The parts of `colors.json` a theme is built from
+#[derive(Debug, Deserialize)]
+struct PywalScheme {
+    special: PywalSpecial,
+    colors: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PywalSpecial {
+    background: String,
+    foreground: String,
+}
+
+/// Where pywal and wallust write their scheme: `$XDG_CACHE_HOME/wal`, or
+/// `~/.cache/wal` when that is not set
+pub fn default_colors_path() -> Option<PathBuf> {
+    let cache = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(cache.join("wal").join("colors.json"))
+}
+
+/// Builds a theme from the contents of a `colors.json` file
+///
+/// The special background and foreground become the base colors, `color4`
+/// (`color1` if missing) the primary color, and the surfaces in between are
+/// shades of the background leaning toward the foreground.
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidInput` if the JSON is not a pywal scheme or a
+/// color is not a hex color
+pub fn theme_from_pywal(name: &str, json: &str) -> Result<CustomTheme, AppError> {
+    let scheme: PywalScheme = serde_json::from_str(json)
+        .map_err(|e| AppError::InvalidInput(format!("Not a pywal color scheme: {}", e)))?;
+
+    let color = |label: &str, hex: &str| {
+        Hsl::from_hex(hex).ok_or_else(|| {
+            AppError::InvalidInput(format!("Invalid {} color in pywal scheme: {}", label, hex))
+        })
+    };
+    let background = color("background", &scheme.special.background)?;
+    let foreground = color("foreground", &scheme.special.foreground)?;
+    let primary = match scheme
+        .colors
+        .get("color4")
+        .or_else(|| scheme.colors.get("color1"))
+    {
+        Some(hex) => color("primary", hex)?,
+        None => foreground,
+    };
+    let surface = background.toward(foreground, 0.1);
+    let edge = background.toward(foreground, 0.2);
+
+    let variables: BTreeMap<String, String> = [
+        ("background", background),
+        ("foreground", foreground),
+        ("primary", primary),
+        ("primary-foreground", background),
+        ("secondary", surface),
+        ("secondary-foreground", foreground),
+        ("accent", surface),
+        ("accent-foreground", foreground),
+        ("muted", surface),
+        ("muted-foreground", background.toward(foreground, 0.6)),
+        ("border", edge),
+        ("input", edge),
+        ("ring", primary),
+    ]
+    .into_iter()
+    .map(|(variable, hsl)| (variable.to_string(), hsl.to_css()))
+    .collect();
+
+    Ok(CustomTheme {
+        name: name.trim().to_string(),
+        dark: background.is_dark(),
+        variables,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::custom_themes::validate_theme;
+
+    const NORD_SCHEME: &str = r##"{
+        "wallpaper": "/home/user/wallpapers/lake.png",
+        "alpha": "100",
+        "special": { "background": "#2e3440", "foreground": "#eceff4", "cursor": "#eceff4" },
+        "colors": { "color0": "#3b4252", "color1": "#bf616a", "color4": "#81a1c1" }
+    }"##;
+
+    #[test]
+    fn test_theme_from_pywal() {
+        let theme = theme_from_pywal("Lake", NORD_SCHEME).unwrap();
+        assert!(validate_theme(&theme).is_ok());
+        assert!(theme.dark);
+        assert_eq!(theme.variables["background"], "220 16.4% 21.6%");
+        assert_eq!(theme.variables["primary"], "210 34% 63.1%");
+        assert_eq!(theme.variables["primary-foreground"], "220 16.4% 21.6%");
+    }
+
+    #[test]
+    fn test_theme_from_pywal_rejects_bad_schemes() {
+        assert!(theme_from_pywal("Bad", "{}").is_err());
+        let bad_color = NORD_SCHEME.replace("#2e3440", "navy");
+        assert!(theme_from_pywal("Bad", &bad_color).is_err());
+    }
+}
@@ -0,0 +1,143 @@
+/// Auto-hide policy for the search and Quick Add overlays.
+///
+/// Like launchers, the overlays hide when they lose focus or when Escape is
+/// pressed, each as allowed by `quick_window_preferences`. Focus loss is
+/// seen through window events; Escape reaches the backend from the
+/// overlay's key handler. The preferences are cached in memory for the
+/// event handlers and kept current from the settings-changed event.
+use crate::models::{AppSettings, QuickWindowPreferences};
+use crate::services::database::get_pool;
+use crate::services::settings::{SettingsService, SETTINGS_CHANGED_EVENT};
+use crate::services::window::{self, QUICK_ADD_WINDOW_LABEL, SEARCH_WINDOW_LABEL};
+use crate::utils::error::AppError;
+use std::sync::Mutex;
+use tauri::{AppHandle, Listener, Manager, WebviewWindow, WindowEvent};
+
+/// Cached `quick_window_preferences` setting
+#[derive(Default)]
+pub struct QuickWindowState(Mutex<QuickWindowPreferences>);
+
+/// What asks an overlay to hide
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HideTrigger {
+    /// Another window took focus
+    Blur,
+    /// Escape was pressed in the overlay
+    Escape,
+}
+
+/// Whether a window should hide in response to a trigger
+pub fn should_hide(
+    preferences: &QuickWindowPreferences,
+    label: &str,
+    trigger: HideTrigger,
+) -> bool {
+    let is_overlay = label == SEARCH_WINDOW_LABEL || label == QUICK_ADD_WINDOW_LABEL;
+    is_overlay
+        && match trigger {
+            HideTrigger::Blur => preferences.hide_on_blur,
+            HideTrigger::Escape => preferences.hide_on_escape,
+        }
+}
+
+fn preferences(app: &AppHandle) -> QuickWindowPreferences {
+    app.try_state::<QuickWindowState>()
+        .and_then(|state| state.0.lock().ok().map(|preferences| *preferences))
+        .unwrap_or_default()
+}
+
+fn set_preferences(app: &AppHandle, preferences: QuickWindowPreferences) {
+    if let Some(state) = app.try_state::<QuickWindowState>() {
+        if let Ok(mut current) = state.0.lock() {
+            *current = preferences;
+        }
+    }
+}
+
+/// Hides an overlay the way its own close action does
+fn hide_overlay(window: &WebviewWindow) -> Result<(), AppError> {
+    if window.label() == SEARCH_WINDOW_LABEL {
+        window::hide_search_window(window.app_handle())
+    } else {
+        window::hide_window(window)
+    }
+}
+
+/// Hides an overlay whenever it loses focus, if the preferences allow
+pub fn attach(window: &WebviewWindow) {
+    // Looked up by label; holding the window in its own handler would keep it alive
+    let app = window.app_handle().clone();
+    let label = window.label().to_string();
+    window.on_window_event(move |event| {
+        if !matches!(event, WindowEvent::Focused(false))
+            || !should_hide(&preferences(&app), &label, HideTrigger::Blur)
+        {
+            return;
+        }
+        let Some(window) = app.get_webview_window(&label) else {
+            return;
+        };
+        // Hiding takes focus away too
+        if !window.is_visible().unwrap_or(false) {
+            return;
+        }
+        if let Err(e) = hide_overlay(&window) {
+            eprintln!("Failed to hide window {} on focus loss: {}", label, e);
+        }
+    });
+}
+
+/// Hides an overlay in response to Escape, if the preferences allow
+///
+/// Returns whether the window was hidden, so the overlay can otherwise
+/// treat Escape as its own (clearing the query, for example).
+pub fn handle_escape(window: &WebviewWindow) -> Result<bool, AppError> {
+    if !should_hide(
+        &preferences(window.app_handle()),
+        window.label(),
+        HideTrigger::Escape,
+    ) {
+        return Ok(false);
+    }
+    hide_overlay(window)?;
+    Ok(true)
+}
+
+/// Loads the preferences and keeps them current as settings change
+pub async fn start(app: &AppHandle) -> Result<(), AppError> {
+    let settings = SettingsService::new(get_pool(app)?).get_settings().await?;
+    set_preferences(app, settings.quick_window_preferences);
+
+    let handle = app.clone();
+    app.listen_any(
+        SETTINGS_CHANGED_EVENT,
+        move |event| match serde_json::from_str::<AppSettings>(event.payload()) {
+            Ok(settings) => set_preferences(&handle, settings.quick_window_preferences),
+            Err(e) => eprintln!("Failed to read changed settings: {}", e),
+        },
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_hide() {
+        use HideTrigger::{Blur, Escape};
+
+        let defaults = QuickWindowPreferences::default();
+        assert!(should_hide(&defaults, SEARCH_WINDOW_LABEL, Blur));
+        assert!(should_hide(&defaults, QUICK_ADD_WINDOW_LABEL, Escape));
+        // Regular windows are never hidden by the policy
+        assert!(!should_hide(&defaults, "settings", Blur));
+
+        let sticky = QuickWindowPreferences {
+            hide_on_blur: false,
+            hide_on_escape: true,
+        };
+        assert!(!should_hide(&sticky, SEARCH_WINDOW_LABEL, Blur));
+        assert!(should_hide(&sticky, SEARCH_WINDOW_LABEL, Escape));
+    }
+}
@@ -0,0 +1,336 @@
+/// Periodic sampling of the app's own resource usage.
+///
+/// Snips runs for days in the tray, so slow leaks matter: a webview that is
+/// not torn down on every hide/show cycle on Wayland, a file descriptor per
+/// clipboard call, a task per shortcut press. The monitor samples RSS, open
+/// file descriptors, threads, tokio tasks and open windows every minute,
+/// keeps the last hour, and warns once per resource when it has been growing
+/// steadily over that window.
+use crate::utils::time::current_timestamp;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::RwLock;
+
+/// Time between samples
+const SAMPLE_INTERVAL_SECS: u64 = 60;
+
+/// Samples kept, one hour at the default interval
+const MAX_SAMPLES: usize = 60;
+
+/// Fewest samples before growth is judged; startup allocations look like growth
+const MIN_SAMPLES_FOR_TREND: usize = 15;
+
+/// Share of sample-to-sample steps that must not decrease for growth to count as steady
+const STEADY_GROWTH_RATIO: f64 = 0.8;
+
+/// Event emitted when a resource looks like it is leaking
+pub const LEAK_SUSPECTED_EVENT: &str = "resource-leak-suspected";
+
+/// One measurement of the app's resource usage
+///
+/// Values the platform cannot report are `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceSample {
+    pub taken_at: i64,
+    /// Resident set size in bytes
+    pub rss_bytes: Option<u64>,
+    pub open_fds: Option<u64>,
+    pub threads: Option<u64>,
+    /// Tasks alive on the async runtime
+    pub tokio_tasks: Option<u64>,
+    /// Open webview windows
+    pub windows: u64,
+}
+
+/// A resource that kept growing across the sampled window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeakWarning {
+    /// `rss_bytes`, `open_fds`, `threads`, `tokio_tasks` or `windows`
+    pub resource: String,
+    pub first_value: u64,
+    pub last_value: u64,
+    /// Seconds between the first and last sample
+    pub over_secs: i64,
+}
+
+/// Current usage, recent history and suspected leaks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    pub current: ResourceSample,
+    /// Oldest first
+    pub samples: Vec<ResourceSample>,
+    pub warnings: Vec<LeakWarning>,
+}
+
+/// A sampled resource and the growth that counts as a leak
+struct Tracked {
+    name: &'static str,
+    value: fn(&ResourceSample) -> Option<u64>,
+    /// Minimum absolute growth across the window
+    min_growth: u64,
+}
+
+const TRACKED: &[Tracked] = &[
+    Tracked {
+        name: "rss_bytes",
+        value: |s| s.rss_bytes,
+        min_growth: 64 * 1024 * 1024,
+    },
+    Tracked {
+        name: "open_fds",
+        value: |s| s.open_fds,
+        min_growth: 64,
+    },
+    Tracked {
+        name: "threads",
+        value: |s| s.threads,
+        min_growth: 16,
+    },
+    Tracked {
+        name: "tokio_tasks",
+        value: |s| s.tokio_tasks,
+        min_growth: 256,
+    },
+    Tracked {
+        name: "windows",
+        value: |s| Some(s.windows),
+        min_growth: 4,
+    },
+];
+
+/// Finds resources that grew steadily across the samples
+///
+/// A resource is flagged when there are enough samples, at least 80% of the
+/// steps between consecutive samples did not decrease it, and it grew by
+/// more than its threshold overall. A one-off jump (opening the management
+/// window, a large import) is not steady and is not flagged.
+pub fn detect_leaks(samples: &[ResourceSample]) -> Vec<LeakWarning> {
+    if samples.len() < MIN_SAMPLES_FOR_TREND {
+        return Vec::new();
+    }
+
+    let mut warnings = Vec::new();
+    for tracked in TRACKED {
+        let values: Vec<(i64, u64)> = samples
+            .iter()
+            .filter_map(|s| (tracked.value)(s).map(|v| (s.taken_at, v)))
+            .collect();
+        if values.len() < MIN_SAMPLES_FOR_TREND {
+            continue;
+        }
+
+        let steps = values.len() - 1;
+        let non_decreasing = values.windows(2).filter(|w| w[1].1 >= w[0].1).count();
+        let (first_at, first_value) = values[0];
+        let (last_at, last_value) = values[values.len() - 1];
+        let steady = non_decreasing as f64 >= steps as f64 * STEADY_GROWTH_RATIO;
+
+        if steady && last_value > first_value.saturating_add(tracked.min_growth) {
+            warnings.push(LeakWarning {
+                resource: tracked.name.to_string(),
+                first_value,
+                last_value,
+                over_secs: last_at - first_at,
+            });
+        }
+    }
+    warnings
+}
+
+/// Takes a sample of the current process
+pub fn sample(app: &AppHandle) -> ResourceSample {
+    let (rss_bytes, threads) = process_memory_and_threads();
+    ResourceSample {
+        taken_at: current_timestamp(),
+        rss_bytes,
+        open_fds: open_fd_count(),
+        threads,
+        tokio_tasks: tokio::runtime::Handle::try_current()
+            .ok()
+            .map(|handle| handle.metrics().num_alive_tasks() as u64),
+        windows: app.webview_windows().len() as u64,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn process_memory_and_threads() -> (Option<u64>, Option<u64>) {
+    let Ok(status) = std::fs::read_to_string("/proc/self/status") else {
+        return (None, None);
+    };
+    parse_proc_status(&status)
+}
+
+#[cfg(target_os = "macos")]
+fn process_memory_and_threads() -> (Option<u64>, Option<u64>) {
+    // `ps` reports RSS in kilobytes; thread counts need Mach calls, so none
+    let rss = std::process::Command::new("ps")
+        .args(["-o", "rss=", "-p", &std::process::id().to_string()])
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .and_then(|text| text.trim().parse::<u64>().ok())
+        .map(|kb| kb * 1024);
+    (rss, None)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn process_memory_and_threads() -> (Option<u64>, Option<u64>) {
+    (None, None)
+}
+
+/// Reads `VmRSS` and `Threads` from `/proc/self/status`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_status(status: &str) -> (Option<u64>, Option<u64>) {
+    let field = |name: &str| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|value| value.parse::<u64>().ok())
+    };
+    (field("VmRSS:").map(|kb| kb * 1024), field("Threads:"))
+}
+
+fn open_fd_count() -> Option<u64> {
+    let dir = if cfg!(target_os = "linux") {
+        "/proc/self/fd"
+    } else if cfg!(target_os = "macos") {
+        "/dev/fd"
+    } else {
+        return None;
+    };
+    // The directory handle itself is one of the listed descriptors
+    std::fs::read_dir(dir)
+        .ok()
+        .map(|entries| (entries.count() as u64).saturating_sub(1))
+}
+
+/// Sample history kept between calls
+#[derive(Default)]
+pub struct ResourceMonitorState {
+    samples: RwLock<VecDeque<ResourceSample>>,
+}
+
+/// Returns the current sample with the history and any suspected leaks
+pub async fn get_resource_usage(app: &AppHandle) -> ResourceUsage {
+    let current = sample(app);
+    let samples: Vec<ResourceSample> = match app.try_state::<ResourceMonitorState>() {
+        Some(state) => state.samples.read().await.iter().cloned().collect(),
+        None => Vec::new(),
+    };
+    let warnings = detect_leaks(&samples);
+    ResourceUsage {
+        current,
+        samples,
+        warnings,
+    }
+}
+
+/// Starts the background sampling loop
+///
+/// Each suspected leak is logged and emitted as `resource-leak-suspected`
+/// once; it is reported again only after it stopped looking like a leak.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut reported: HashSet<String> = HashSet::new();
+        loop {
+            tokio::time::sleep(Duration::from_secs(SAMPLE_INTERVAL_SECS)).await;
+            let Some(state) = app.try_state::<ResourceMonitorState>() else {
+                continue;
+            };
+
+            let warnings = {
+                let mut samples = state.samples.write().await;
+                samples.push_back(sample(&app));
+                while samples.len() > MAX_SAMPLES {
+                    samples.pop_front();
+                }
+                detect_leaks(samples.make_contiguous())
+            };
+
+            reported.retain(|resource| warnings.iter().any(|w| w.resource == *resource));
+            for warning in warnings {
+                if !reported.insert(warning.resource.clone()) {
+                    continue;
+                }
+                eprintln!(
+                    "Warning: {} grew from {} to {} over {}s; possible leak",
+                    warning.resource, warning.first_value, warning.last_value, warning.over_secs
+                );
+                if let Err(e) = app.emit(LEAK_SUSPECTED_EVENT, &warning) {
+                    eprintln!("Failed to emit leak warning: {}", e);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(rss: impl Fn(usize) -> u64, windows: impl Fn(usize) -> u64) -> Vec<ResourceSample> {
+        (0..20)
+            .map(|i| ResourceSample {
+                taken_at: i as i64 * 60,
+                rss_bytes: Some(rss(i)),
+                open_fds: Some(40),
+                threads: None,
+                tokio_tasks: Some(12),
+                windows: windows(i),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_leaks_flags_steady_growth() {
+        let mb = 1024 * 1024;
+        // Grows 8 MB a minute, with one dip from a collection
+        let leaking = samples(
+            |i| {
+                if i == 10 {
+                    150 * mb
+                } else {
+                    (100 + 8 * i as u64) * mb
+                }
+            },
+            |i| 2 + i as u64 / 3,
+        );
+        let warnings = detect_leaks(&leaking);
+        let resources: Vec<&str> = warnings.iter().map(|w| w.resource.as_str()).collect();
+        assert_eq!(resources, vec!["rss_bytes", "windows"]);
+        assert_eq!(warnings[0].over_secs, 19 * 60);
+    }
+
+    #[test]
+    fn test_detect_leaks_ignores_stable_and_one_off_jumps() {
+        let mb = 1024 * 1024;
+        let stable = samples(|i| (100 + (i % 3) as u64) * mb, |_| 2);
+        assert!(detect_leaks(&stable).is_empty());
+
+        // A single large jump followed by a sawtooth is not steady growth
+        let jump = samples(
+            |i| {
+                if i < 5 {
+                    100 * mb
+                } else {
+                    (300 - 10 * (i % 2) as u64) * mb
+                }
+            },
+            |_| 2,
+        );
+        assert!(detect_leaks(&jump).is_empty());
+
+        // Too few samples to judge
+        assert!(detect_leaks(&samples(|i| i as u64 * 100 * mb, |_| 2)[..5]).is_empty());
+    }
+
+    #[test]
+    fn test_parse_proc_status() {
+        let status = "Name:\tsnips\nVmRSS:\t  204800 kB\nThreads:\t27\n";
+        assert_eq!(parse_proc_status(status), (Some(204800 * 1024), Some(27)));
+        assert_eq!(parse_proc_status("Name:\tsnips\n"), (None, None));
+    }
+}
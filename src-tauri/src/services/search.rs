@@ -1,7 +1,15 @@
-use crate::models::{SearchResult, Snippet, SnippetId};
-use crate::services::{database::get_pool, settings::SettingsService, tags};
+use crate::models::settings::SearchSettings;
+use crate::models::{SearchPage, SearchResult, Snippet, SnippetId, SnippetKind};
+use crate::services::focus;
+use crate::services::name_index::{self, NameIndex};
+use crate::services::tags::normalize_tag_path;
+use crate::services::{database::get_pool, language, settings::SettingsService};
 use crate::utils::error::AppError;
-use sqlx::Row;
+use crate::utils::time::current_timestamp;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqliteConnection};
+use std::collections::{HashMap, HashSet};
 use tauri::AppHandle;
 
 /// Default limit for search results
@@ -10,6 +18,12 @@ const DEFAULT_SEARCH_LIMIT: i64 = 50;
 /// Maximum allowed limit to prevent performance issues
 const MAX_SEARCH_LIMIT: i64 = 1000;
 
+/// Characters of content in a preview taken from the start of a snippet
+pub const PREVIEW_CHARS: usize = 200;
+
+/// Tokens in a preview excerpted around the matched terms
+const EXCERPT_TOKENS: i64 = 24;
+
 /// Recency thresholds for scoring (in days)
 const RECENCY_RECENT_DAYS: f64 = 7.0;
 const RECENCY_MEDIUM_DAYS: f64 = 30.0;
@@ -26,140 +40,281 @@ const RECENCY_OLD_BONUS: f64 = 0.5;
 ///
 /// * `app` - Tauri application handle
 /// * `query` - Search query string (supports "tag:" prefix for filtering by tag)
-/// * `limit` - Optional maximum number of results (defaults to 50, max 1000)
+/// * `limit` - Optional maximum number of results per page (defaults to 50, max 1000)
+/// * `offset` - Optional number of matches to skip (defaults to 0)
+/// * `cursor` - Optional `next_cursor` of the previous page; takes precedence over `offset`
 ///
 /// # Returns
 ///
-/// Vector of `SearchResult` sorted by relevance score and usage frequency.
+/// A `SearchPage` of results sorted by relevance score and usage frequency,
+/// with the total number of matches and the cursor of the next page.
 ///
 /// # Errors
 ///
-/// Returns `AppError` if the query fails or database is unavailable.
+/// Returns `AppError` if the query fails, the cursor is malformed or the
+/// database is unavailable.
 ///
 /// # Tag Filtering
 ///
-/// Query can include a tag filter using the format "tagname:search terms"
+/// `tag:` terms anywhere in the query filter by tag:
+/// - "tag:python tag:async" - snippets with both tags
+/// - "tag:python,rust" - snippets with either tag
+/// - "-tag:archive" - snippets without the tag
+///
+/// The older "tagname:search terms" prefix still filters by a single tag:
 /// - "python:" - shows all snippets with the "python" tag
 /// - "python:async" - shows snippets with "python" tag containing "async"
+///
+/// # Language Filtering
+///
+/// A leading "lang:<language>" restricts results to snippets detected as that
+/// language, e.g. "lang:rust async" or "lang:bash". Language aliases accepted
+/// for tags work here too.
+///
+/// # Archived Snippets
+///
+/// Archived snippets are left out unless the query contains "is:archived",
+/// which finds archived snippets only, e.g. "is:archived deploy".
+///
+/// # Search Operators
+///
+/// - "\"error handling\"" - the exact phrase
+/// - "name:deploy" or "content:\"kubectl get\"" - terms in one field only
+///
+/// Phrases and field-scoped terms must all match; other words match as
+/// prefixes, and at least one of them must.
+///
+/// # Typo Tolerance
+///
+/// When fuzzy search is enabled and a plain search matches fewer snippets
+/// than the limit, snippets whose name contains a word within a small edit
+/// distance of a search term ("kubcetl" for "kubectl") are appended after the
+/// exact matches, closest first.
+///
+/// # Pagination
+///
+/// Every match is ranked, but only the requested page is loaded. A cursor
+/// resumes right after the last snippet of the previous page, so snippets
+/// added or removed before it do not shift the next page.
 pub async fn search_snippets(
     app: &AppHandle,
     query: &str,
     limit: Option<i64>,
-) -> Result<Vec<SearchResult>, AppError> {
+    offset: Option<i64>,
+    cursor: Option<&str>,
+) -> Result<SearchPage, AppError> {
     let pool = get_pool(app)?;
 
     // Load search settings to get configurable weights
     let settings_service = SettingsService::new(pool.clone());
     let settings = settings_service.get_settings().await?;
-    let search_settings = &settings.search_settings;
 
+    let names = if settings.search_settings.enable_fuzzy_search {
+        Some(name_index::read(app).await?)
+    } else {
+        None
+    };
+
+    let mut conn = pool.acquire().await?;
+    let ranking = Ranking::load(&mut conn, settings.search_settings, app).await?;
+    run_search(
+        &mut conn,
+        &ranking,
+        names.as_deref(),
+        query,
+        limit,
+        offset,
+        cursor,
+    )
+    .await
+}
+
+/// Settings and context that matches are ranked with
+#[derive(Debug, Clone, Default)]
+struct Ranking {
+    settings: SearchSettings,
+    /// Uses of each snippet with the focused application; empty when the
+    /// application is not known
+    app_usage: HashMap<i64, i64>,
+}
+
+impl Ranking {
+    /// Loads the usage counts for the application the user is working in
+    async fn load(
+        conn: &mut SqliteConnection,
+        settings: SearchSettings,
+        app: &AppHandle,
+    ) -> Result<Self, AppError> {
+        let app_usage = match focus::active_app(app) {
+            Some(active_app) if settings.weight_app_context > 0.0 => {
+                fetch_app_usage(conn, &active_app).await?
+            }
+            _ => HashMap::new(),
+        };
+        Ok(Self {
+            settings,
+            app_usage,
+        })
+    }
+
+    /// Uses of a snippet with the focused application
+    fn app_usage_count(&self, snippet_id: i64) -> i64 {
+        self.app_usage.get(&snippet_id).copied().unwrap_or(0)
+    }
+}
+
+/// Counts how often each snippet was used with an application
+async fn fetch_app_usage(
+    conn: &mut SqliteConnection,
+    active_app: &str,
+) -> Result<HashMap<i64, i64>, AppError> {
+    let rows = sqlx::query_as::<_, (i64, i64)>(
+        "SELECT snippet_id, COUNT(*) FROM analytics WHERE active_app = ? GROUP BY snippet_id",
+    )
+    .bind(active_app)
+    .fetch_all(&mut *conn)
+    .await?;
+    Ok(rows.into_iter().collect())
+}
+
+/// Ranking inputs of a match, known before its snippet is loaded
+#[derive(Debug, Clone, PartialEq)]
+struct RankedMatch {
+    id: i64,
+    usage_count: i64,
+    last_used: Option<i64>,
+    fts_rank: f64,
+    pinned: bool,
+    relevance_score: f64,
+}
+
+/// Every match of a query, best first
+struct Matches {
+    ranked: Vec<RankedMatch>,
+    /// FTS5 query built from the text terms; empty when only filters were given
+    fts_query: String,
+}
+
+/// Executes a single search on an existing connection
+///
+/// Shared by `search_snippets` and `batch_search` so a batch can run every
+/// query on one connection with settings loaded once. `names` is the name
+/// index used for typo-tolerant matches, or `None` when fuzzy search is off.
+async fn run_search(
+    conn: &mut SqliteConnection,
+    ranking: &Ranking,
+    names: Option<&NameIndex>,
+    query: &str,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    cursor: Option<&str>,
+) -> Result<SearchPage, AppError> {
     // Validate and apply limit
     let limit = limit
         .unwrap_or(DEFAULT_SEARCH_LIMIT)
         .clamp(1, MAX_SEARCH_LIMIT);
 
+    let Some(Matches { ranked, fts_query }) =
+        find_matches(conn, ranking, names, query, limit).await?
+    else {
+        return Ok(SearchPage::default());
+    };
+
+    let start = page_start(&ranked, offset, cursor)?.min(ranked.len());
+    let end = (start + limit as usize).min(ranked.len());
+    let mut results = load_page(conn, &ranked[start..end]).await?;
+    if !fts_query.is_empty() {
+        let ids: Vec<i64> = results.iter().map(|r| r.snippet.id.0).collect();
+        let mut excerpts = fetch_excerpts(conn, &fts_query, &ids).await?;
+        for result in &mut results {
+            if let Some(excerpt) = excerpts.remove(&result.snippet.id.0) {
+                result.preview = excerpt;
+            }
+        }
+    }
+
+    Ok(SearchPage {
+        results,
+        total_matches: ranked.len() as i64,
+        offset: start as i64,
+        next_cursor: (end < ranked.len()).then(|| format!("{}:{}", end, ranked[end - 1].id)),
+    })
+}
+
+/// Finds and ranks every match of a query
+///
+/// A plain search that finds fewer than `limit` snippets is filled up with
+/// typo-tolerant name matches from `names`. Returns `None` for a query with
+/// neither search terms nor filters.
+async fn find_matches(
+    conn: &mut SqliteConnection,
+    ranking: &Ranking,
+    names: Option<&NameIndex>,
+    query: &str,
+    limit: i64,
+) -> Result<Option<Matches>, AppError> {
     // Sanitize query input
     let query = query.trim();
     if query.is_empty() {
-        return Ok(Vec::new());
+        return Ok(None);
     }
 
-    // Parse query to extract tag filter
-    let (tag_filter, search_query) = parse_tag_filter(query);
+    // Parse query to extract archive, language and tag filters
+    let (archived, query) = parse_archived_term(query);
+    let (language_filter, query) = parse_language_filter(&query);
+    let language_filter = language_filter.map(language::normalize_language);
+    let (tag_filter, search_query) = parse_tag_terms(query);
+    let (tag_sql, tag_binds) = tag_filter.to_sql();
 
     // Build FTS5 query - use simple match for now
-    let fts_query = build_fts5_query(search_query);
-
-    // Execute search query with relevance scoring
-    // FTS5 provides bm25() ranking function for relevance
-    // We also join with analytics to get usage statistics
-    let results = if let Some(tag) = tag_filter {
-        // Tag-filtered search: join with snippet_tags and tags tables
-        if fts_query.is_empty() {
-            // No search query, just show all snippets with this tag
-            sqlx::query(
-                r#"
-                SELECT
-                    s.id,
-                    s.name,
-                    s.content,
-                    s.description,
-                    s.created_at,
-                    s.updated_at,
-                    COALESCE(usage.count, 0) as usage_count,
-                    usage.last_used,
-                    0.0 as fts_rank
-                FROM snippets s
-                INNER JOIN snippet_tags st ON s.id = st.snippet_id
-                INNER JOIN tags t ON st.tag_id = t.id
-                LEFT JOIN (
-                    SELECT
-                        snippet_id,
-                        COUNT(*) as count,
-                        MAX(used_at) as last_used
-                    FROM analytics
-                    GROUP BY snippet_id
-                ) usage ON s.id = usage.snippet_id
-                WHERE LOWER(t.name) = LOWER(?)
-                ORDER BY s.updated_at DESC
-                LIMIT ?
-                "#,
-            )
-            .bind(tag)
-            .bind(limit)
-            .fetch_all(&pool)
-            .await?
-        } else {
-            // Tag-filtered FTS search
-            sqlx::query(
-                r#"
+    let fts_query = build_fts5_query(&search_query, ranking.settings.search_in_tags);
+
+    // Rank every match on its usage statistics and FTS5 bm25() rank; the
+    // snippets themselves are only loaded for the requested page
+    let rows = if fts_query.is_empty() {
+        // Filters alone, e.g. "tag:python", "lang:rust" or "is:archived"
+        if tag_filter.is_empty() && language_filter.is_none() && !archived {
+            return Ok(None);
+        }
+        let sql = format!(
+            r#"
+            SELECT
+                s.id,
+                COALESCE(usage.count, 0) as usage_count,
+                usage.last_used,
+                0.0 as fts_rank,
+                s.pinned
+            FROM snippets s
+            LEFT JOIN (
                 SELECT
-                    s.id,
-                    s.name,
-                    s.content,
-                    s.description,
-                    s.created_at,
-                    s.updated_at,
-                    COALESCE(usage.count, 0) as usage_count,
-                    usage.last_used,
-                    snippets_fts.rank as fts_rank
-                FROM snippets_fts
-                INNER JOIN snippets s ON snippets_fts.rowid = s.id
-                INNER JOIN snippet_tags st ON s.id = st.snippet_id
-                INNER JOIN tags t ON st.tag_id = t.id
-                LEFT JOIN (
-                    SELECT
-                        snippet_id,
-                        COUNT(*) as count,
-                        MAX(used_at) as last_used
-                    FROM analytics
-                    GROUP BY snippet_id
-                ) usage ON s.id = usage.snippet_id
-                WHERE snippets_fts MATCH ? AND LOWER(t.name) = LOWER(?)
-                ORDER BY snippets_fts.rank
-                LIMIT ?
-                "#,
-            )
-            .bind(&fts_query)
-            .bind(tag)
-            .bind(limit)
-            .fetch_all(&pool)
-            .await?
+                    snippet_id,
+                    COUNT(*) as count,
+                    MAX(used_at) as last_used
+                FROM analytics
+                GROUP BY snippet_id
+            ) usage ON s.id = usage.snippet_id
+            WHERE s.archived = ? AND (? IS NULL OR s.language = ?){}
+            ORDER BY s.updated_at DESC
+            "#,
+            tag_sql
+        );
+        let mut query = sqlx::query(&sql)
+            .bind(archived)
+            .bind(&language_filter)
+            .bind(&language_filter);
+        for tag in &tag_binds {
+            query = query.bind(*tag);
         }
+        query.fetch_all(&mut *conn).await?
     } else {
-        // Regular FTS search without tag filter
-        sqlx::query(
+        let sql = format!(
             r#"
             SELECT
                 s.id,
-                s.name,
-                s.content,
-                s.description,
-                s.created_at,
-                s.updated_at,
                 COALESCE(usage.count, 0) as usage_count,
                 usage.last_used,
-                snippets_fts.rank as fts_rank
+                {} as fts_rank,
+                s.pinned
             FROM snippets_fts
             INNER JOIN snippets s ON snippets_fts.rowid = s.id
             LEFT JOIN (
@@ -170,65 +325,1083 @@ pub async fn search_snippets(
                 FROM analytics
                 GROUP BY snippet_id
             ) usage ON s.id = usage.snippet_id
-            WHERE snippets_fts MATCH ?
-            ORDER BY snippets_fts.rank
-            LIMIT ?
+            WHERE snippets_fts MATCH ? AND s.archived = ?
+                AND (? IS NULL OR s.language = ?){}
+            ORDER BY fts_rank
             "#,
-        )
-        .bind(&fts_query)
-        .bind(limit)
-        .fetch_all(&pool)
+            FTS_RANK, tag_sql
+        );
+        let mut query = sqlx::query(&sql)
+            .bind(&fts_query)
+            .bind(archived)
+            .bind(&language_filter)
+            .bind(&language_filter);
+        for tag in &tag_binds {
+            query = query.bind(*tag);
+        }
+        query.fetch_all(&mut *conn).await?
+    };
+
+    let mut ranked: Vec<RankedMatch> = rows.iter().map(|row| rank_row(row, ranking)).collect();
+    // Re-sort by relevance score (combines FTS rank with usage stats)
+    ranked.sort_by(|a, b| {
+        b.relevance_score
+            .partial_cmp(&a.relevance_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    // Fill up a short plain search with snippets whose names are near misses
+    if let Some(names) = names {
+        if tag_filter.is_empty() && !fts_query.is_empty() && (ranked.len() as i64) < limit {
+            let words: Vec<String> = split_query_terms(&search_query)
+                .into_iter()
+                .filter(|term| !term.quoted)
+                .map(|term| term.text)
+                .collect();
+            let terms: Vec<&str> = words.iter().map(String::as_str).collect();
+            let found: HashSet<i64> = ranked.iter().map(|m| m.id).collect();
+            let candidates: Vec<i64> = names
+                .find_any(&terms)
+                .into_iter()
+                .filter(|id| !found.contains(id))
+                .take(limit as usize - ranked.len())
+                .collect();
+            let typo_matches =
+                fetch_typo_matches(conn, &candidates, archived, &language_filter, ranking).await?;
+            ranked.extend(typo_matches);
+        }
+    }
+
+    Ok(Some(Matches { ranked, fts_query }))
+}
+
+/// Beginning of a snippet's content, used as its preview
+pub fn content_preview(content: &str) -> String {
+    let content = content.trim();
+    match content.char_indices().nth(PREVIEW_CHARS) {
+        Some((end, _)) => format!("{}…", content[..end].trim_end()),
+        None => content.to_string(),
+    }
+}
+
+/// Drops the full content of results, leaving their previews
+///
+/// For callers that only list results; a result's content can be large,
+/// and the snippet is copied by ID anyway.
+pub fn omit_content(results: &mut [SearchResult]) {
+    for result in results {
+        result.snippet.content = String::new();
+    }
+}
+
+/// Excerpts of content around the matched terms, by snippet ID
+///
+/// Snippets that matched on their name alone get no excerpt and keep the
+/// beginning of their content as the preview.
+async fn fetch_excerpts(
+    conn: &mut SqliteConnection,
+    fts_query: &str,
+    ids: &[i64],
+) -> Result<HashMap<i64, String>, AppError> {
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    // Matched terms are marked so excerpts without any can be told apart
+    let placeholders = vec!["?"; ids.len()].join(", ");
+    let sql = format!(
+        "SELECT rowid, snippet(snippets_fts, 1, char(2), char(3), '…', ?)
+         FROM snippets_fts
+         WHERE snippets_fts MATCH ? AND rowid IN ({})",
+        placeholders
+    );
+    let mut query = sqlx::query(&sql).bind(EXCERPT_TOKENS).bind(fts_query);
+    for id in ids {
+        query = query.bind(id);
+    }
+
+    Ok(query
+        .fetch_all(&mut *conn)
         .await?
+        .into_iter()
+        .filter_map(|row| {
+            let excerpt: String = row.get(1);
+            excerpt.contains('\u{2}').then(|| {
+                (
+                    row.get(0),
+                    excerpt.replace(['\u{2}', '\u{3}'], "").trim().to_string(),
+                )
+            })
+        })
+        .collect())
+}
+
+/// Index of the first match on the requested page
+///
+/// A cursor has the form `position:snippet_id` and names the last snippet of
+/// the previous page. The page starts right after that snippet wherever it
+/// ranks now, or at the stored position if it no longer matches.
+fn page_start(
+    ranked: &[RankedMatch],
+    offset: Option<i64>,
+    cursor: Option<&str>,
+) -> Result<usize, AppError> {
+    let Some(cursor) = cursor else {
+        return Ok(offset.unwrap_or(0).max(0) as usize);
     };
 
-    // Convert to SearchResult with computed relevance scores
-    let mut search_results = Vec::new();
-    for row in results {
+    let (position, last_id) = cursor
+        .split_once(':')
+        .and_then(|(position, id)| Some((position.parse::<usize>().ok()?, id.parse::<i64>().ok()?)))
+        .ok_or_else(|| AppError::InvalidInput(format!("Invalid search cursor: {}", cursor)))?;
+    Ok(ranked
+        .iter()
+        .position(|m| m.id == last_id)
+        .map_or(position, |index| index + 1))
+}
+
+/// Scores a ranking row
+///
+/// Rows must select `id, usage_count, last_used, fts_rank, pinned` in that order.
+fn rank_row(row: &SqliteRow, ranking: &Ranking) -> RankedMatch {
+    let id: i64 = row.get(0);
+    let usage_count: i64 = row.get(1);
+    let last_used: Option<i64> = row.get(2);
+    let fts_rank: f64 = row.get(3);
+    let pinned: bool = row.get(4);
+    RankedMatch {
+        id,
+        usage_count,
+        last_used,
+        fts_rank,
+        pinned,
+        relevance_score: calculate_relevance_score(
+            fts_rank,
+            usage_count,
+            last_used,
+            ranking.app_usage_count(id),
+            &ranking.settings,
+            pinned,
+        ),
+    }
+}
+
+/// Ranks snippets found by typo-tolerant name matching, keeping the order of `ids`
+async fn fetch_typo_matches(
+    conn: &mut SqliteConnection,
+    ids: &[i64],
+    archived: bool,
+    language_filter: &Option<String>,
+    ranking: &Ranking,
+) -> Result<Vec<RankedMatch>, AppError> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let placeholders = vec!["?"; ids.len()].join(", ");
+    let sql = format!(
+        r#"
+        SELECT
+            s.id,
+            COALESCE(usage.count, 0) as usage_count,
+            usage.last_used,
+            0.0 as fts_rank,
+            s.pinned
+        FROM snippets s
+        LEFT JOIN (
+            SELECT
+                snippet_id,
+                COUNT(*) as count,
+                MAX(used_at) as last_used
+            FROM analytics
+            GROUP BY snippet_id
+        ) usage ON s.id = usage.snippet_id
+        WHERE s.id IN ({}) AND s.archived = ? AND (? IS NULL OR s.language = ?)
+        "#,
+        placeholders
+    );
+
+    let mut query = sqlx::query(&sql);
+    for id in ids {
+        query = query.bind(id);
+    }
+    let rows = query
+        .bind(archived)
+        .bind(language_filter)
+        .bind(language_filter)
+        .fetch_all(&mut *conn)
+        .await?;
+
+    let mut matches: Vec<RankedMatch> = rows.iter().map(|row| rank_row(row, ranking)).collect();
+    let order: HashMap<i64, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    matches.sort_by_key(|m| order.get(&m.id).copied());
+    Ok(matches)
+}
+
+/// Whether a snippet's expiration date has passed at `now`
+fn is_expired(snippet: &Snippet, now: i64) -> bool {
+    snippet
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= now)
+}
+
+/// Loads the snippets of one page of matches, in the matches' order
+async fn load_page(
+    conn: &mut SqliteConnection,
+    page: &[RankedMatch],
+) -> Result<Vec<SearchResult>, AppError> {
+    if page.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let ids: Vec<i64> = page.iter().map(|m| m.id).collect();
+    let placeholders = vec!["?"; ids.len()].join(", ");
+    let sql = format!(
+        "SELECT id, name, content, description, created_at, updated_at, pinned, kind, language,
+                archived, expires_at
+         FROM snippets WHERE id IN ({})",
+        placeholders
+    );
+    let mut query = sqlx::query(&sql);
+    for id in &ids {
+        query = query.bind(id);
+    }
+    let rows = query.fetch_all(&mut *conn).await?;
+    let mut tags_by_snippet = fetch_tags_for_snippets(conn, &ids).await?;
+
+    let mut snippets: HashMap<i64, Snippet> = rows
+        .iter()
+        .map(|row| {
+            let id: i64 = row.get(0);
+            let snippet = Snippet {
+                id: SnippetId(id),
+                name: row.get(1),
+                content: row.get(2),
+                description: row.get(3),
+                created_at: row.get(4),
+                updated_at: row.get(5),
+                tags: Some(tags_by_snippet.remove(&id).unwrap_or_default()),
+                pinned: row.get(6),
+                kind: SnippetKind::from_db(row.get(7)),
+                language: row.get(8),
+                archived: row.get(9),
+                expires_at: row.get(10),
+            };
+            (id, snippet)
+        })
+        .collect();
+
+    // A snippet deleted since it was ranked is skipped
+    let now = current_timestamp();
+    Ok(page
+        .iter()
+        .filter_map(|m| {
+            snippets.remove(&m.id).map(|snippet| SearchResult {
+                preview: content_preview(&snippet.content),
+                expired: is_expired(&snippet, now),
+                snippet,
+                usage_count: m.usage_count,
+                last_used: m.last_used,
+                relevance_score: m.relevance_score,
+            })
+        })
+        .collect())
+}
+
+/// Converts result rows into `SearchResult`s, sorted by relevance score
+///
+/// Rows must select `id, name, content, description, created_at, updated_at,
+/// usage_count, last_used, fts_rank, pinned, kind, language, archived, expires_at` in that order.
+/// Tags for all rows are loaded with a single query.
+async fn rows_to_results(
+    conn: &mut SqliteConnection,
+    rows: Vec<SqliteRow>,
+    ranking: &Ranking,
+) -> Result<Vec<SearchResult>, AppError> {
+    let mut search_results = rows_to_ordered_results(conn, rows, ranking).await?;
+
+    // Re-sort by relevance score (combines FTS rank with usage stats)
+    search_results.sort_by(|a, b| {
+        b.relevance_score
+            .partial_cmp(&a.relevance_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    Ok(search_results)
+}
+
+/// Converts result rows into `SearchResult`s, keeping the order of the rows
+///
+/// Rows are selected as for `rows_to_results`.
+async fn rows_to_ordered_results(
+    conn: &mut SqliteConnection,
+    rows: Vec<SqliteRow>,
+    ranking: &Ranking,
+) -> Result<Vec<SearchResult>, AppError> {
+    let ids: Vec<i64> = rows.iter().map(|row| row.get(0)).collect();
+    let mut tags_by_snippet = fetch_tags_for_snippets(conn, &ids).await?;
+
+    let now = current_timestamp();
+    let mut search_results = Vec::with_capacity(rows.len());
+    for row in rows {
         let snippet_id: i64 = row.get(0);
         let usage_count: i64 = row.get(6);
         let last_used: Option<i64> = row.get(7);
         let fts_rank: f64 = row.get(8);
+        let pinned: bool = row.get(9);
+
+        let snippet = Snippet {
+            id: SnippetId(snippet_id),
+            name: row.get(1),
+            content: row.get(2),
+            description: row.get(3),
+            created_at: row.get(4),
+            updated_at: row.get(5),
+            tags: Some(tags_by_snippet.remove(&snippet_id).unwrap_or_default()),
+            pinned,
+            kind: SnippetKind::from_db(row.get(10)),
+            language: row.get(11),
+            archived: row.get(12),
+            expires_at: row.get(13),
+        };
+
+        // Calculate relevance score combining FTS rank and usage statistics
+        // Use configurable weights from settings
+        let relevance_score = calculate_relevance_score(
+            fts_rank,
+            usage_count,
+            last_used,
+            ranking.app_usage_count(snippet_id),
+            &ranking.settings,
+            pinned,
+        );
+
+        search_results.push(SearchResult {
+            preview: content_preview(&snippet.content),
+            expired: is_expired(&snippet, now),
+            snippet,
+            usage_count,
+            last_used,
+            relevance_score,
+        });
+    }
+
+    Ok(search_results)
+}
+
+/// Loads the tags of several snippets in one query
+///
+/// # Returns
+///
+/// Map of snippet ID to its tag names, sorted alphabetically. Snippets without
+/// tags are absent from the map.
+async fn fetch_tags_for_snippets(
+    conn: &mut SqliteConnection,
+    snippet_ids: &[i64],
+) -> Result<HashMap<i64, Vec<String>>, AppError> {
+    let mut tags_by_snippet: HashMap<i64, Vec<String>> = HashMap::new();
+    if snippet_ids.is_empty() {
+        return Ok(tags_by_snippet);
+    }
+
+    let placeholders = vec!["?"; snippet_ids.len()].join(", ");
+    let sql = format!(
+        "SELECT st.snippet_id, t.name FROM tags t
+         INNER JOIN snippet_tags st ON t.id = st.tag_id
+         WHERE st.snippet_id IN ({})
+         ORDER BY t.name",
+        placeholders
+    );
+
+    let mut query = sqlx::query(&sql);
+    for id in snippet_ids {
+        query = query.bind(id);
+    }
+
+    for row in query.fetch_all(&mut *conn).await? {
+        tags_by_snippet
+            .entry(row.get(0))
+            .or_default()
+            .push(row.get(1));
+    }
+
+    Ok(tags_by_snippet)
+}
+
+/// One query in a `batch_search` call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BatchSearchRequest {
+    /// Full-text search, same semantics as `search_snippets`
+    Search { query: String, limit: Option<i64> },
+    /// Suggested snippets for an empty query, ranked by usage and recency
+    Suggestions { limit: Option<i64> },
+    /// Most recently used snippets, same as `get_recent_snippets`
+    Recent { limit: Option<i64> },
+    /// Most often used snippets, same as `get_frequent_snippets`
+    Frequent { limit: Option<i64> },
+    /// Number of snippets per tag
+    TagCounts,
+}
+
+/// Snippet count for a tag
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagCount {
+    pub name: String,
+    pub color: String,
+    pub count: i64,
+}
+
+/// Result of one query in a `batch_search` call, in request order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BatchSearchResponse {
+    Search {
+        results: Vec<SearchResult>,
+    },
+    Suggestions {
+        results: Vec<SearchResult>,
+    },
+    Recent {
+        results: Vec<SearchResult>,
+    },
+    Frequent {
+        results: Vec<SearchResult>,
+    },
+    TagCounts {
+        tags: Vec<TagCount>,
+    },
+    /// The query failed; other queries in the batch are unaffected
+    Error {
+        message: String,
+    },
+}
+
+/// One weighted part of a relevance score
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScoreComponent {
+    /// Score before weighting
+    pub score: f64,
+    pub weight: f64,
+    /// `score * weight`, the amount added to the relevance score
+    pub weighted: f64,
+}
+
+impl ScoreComponent {
+    fn new(score: f64, weight: f64) -> Self {
+        Self {
+            score,
+            weight,
+            weighted: score * weight,
+        }
+    }
+}
+
+/// The parts a relevance score is the sum of
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    /// Text relevance, the FTS5 bm25() rank negated
+    pub text: ScoreComponent,
+    /// Usage frequency, logarithmic in the usage count
+    pub usage: ScoreComponent,
+    /// Recency bonus for the last use
+    pub recency: ScoreComponent,
+    /// Usage with the focused application
+    pub app_context: ScoreComponent,
+    /// Bonus for pinning; 0.0 for unpinned snippets
+    pub pinned_bonus: f64,
+    pub relevance_score: f64,
+}
+
+/// How a snippet's rank for a query came about
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RankingExplanation {
+    pub snippet_id: i64,
+    /// Position among all matches, starting at 1
+    pub position: i64,
+    pub total_matches: i64,
+    /// FTS5 bm25() rank; 0.0 for filter-only and typo-tolerant matches
+    pub fts_rank: f64,
+    pub usage_count: i64,
+    pub last_used: Option<i64>,
+    /// Uses with the focused application
+    pub app_usage_count: i64,
+    pub pinned: bool,
+    pub scores: ScoreBreakdown,
+}
+
+/// Explains where a snippet ranks for a query and why
+///
+/// The query is ranked as `search_snippets` ranks it for the largest page
+/// size, with the current settings and focused application.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if the snippet does not match the query
+pub async fn explain_search_ranking(
+    app: &AppHandle,
+    query: &str,
+    snippet_id: i64,
+) -> Result<RankingExplanation, AppError> {
+    let pool = get_pool(app)?;
+    let settings = SettingsService::new(pool.clone()).get_settings().await?;
+    let names = if settings.search_settings.enable_fuzzy_search {
+        Some(name_index::read(app).await?)
+    } else {
+        None
+    };
+
+    let mut conn = pool.acquire().await?;
+    let ranking = Ranking::load(&mut conn, settings.search_settings, app).await?;
+    explain_ranking(&mut conn, &ranking, names.as_deref(), query, snippet_id).await
+}
+
+async fn explain_ranking(
+    conn: &mut SqliteConnection,
+    ranking: &Ranking,
+    names: Option<&NameIndex>,
+    query: &str,
+    snippet_id: i64,
+) -> Result<RankingExplanation, AppError> {
+    let not_found = || {
+        AppError::NotFound(format!(
+            "Snippet with id {} does not match \"{}\"",
+            snippet_id,
+            query.trim()
+        ))
+    };
+    let matches = find_matches(conn, ranking, names, query, MAX_SEARCH_LIMIT)
+        .await?
+        .ok_or_else(not_found)?;
+    let (index, found) = matches
+        .ranked
+        .iter()
+        .enumerate()
+        .find(|(_, m)| m.id == snippet_id)
+        .ok_or_else(not_found)?;
+
+    let app_usage_count = ranking.app_usage_count(snippet_id);
+    Ok(RankingExplanation {
+        snippet_id,
+        position: index as i64 + 1,
+        total_matches: matches.ranked.len() as i64,
+        fts_rank: found.fts_rank,
+        usage_count: found.usage_count,
+        last_used: found.last_used,
+        app_usage_count,
+        pinned: found.pinned,
+        scores: score_breakdown(
+            found.fts_rank,
+            found.usage_count,
+            found.last_used,
+            app_usage_count,
+            &ranking.settings,
+            found.pinned,
+        ),
+    })
+}
+
+/// Runs several search queries in one call
+///
+/// Settings are loaded once and every query runs on the same connection
+/// inside one read transaction, so results are consistent with each other.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `requests` - Queries to run
+///
+/// # Returns
+///
+/// One response per request, in the same order. A failing query yields a
+/// `BatchSearchResponse::Error` entry instead of failing the whole batch.
+///
+/// # Errors
+///
+/// Returns `AppError` if settings cannot be loaded or no connection is available
+pub async fn batch_search(
+    app: &AppHandle,
+    requests: Vec<BatchSearchRequest>,
+) -> Result<Vec<BatchSearchResponse>, AppError> {
+    let pool = get_pool(app)?;
+    let settings = SettingsService::new(pool.clone()).get_settings().await?;
+    let names = if settings.search_settings.enable_fuzzy_search {
+        Some(name_index::read(app).await?)
+    } else {
+        None
+    };
+
+    let mut tx = pool.begin().await?;
+    let ranking = Ranking::load(&mut tx, settings.search_settings, app).await?;
+    let mut responses = Vec::with_capacity(requests.len());
+    for request in requests {
+        let response = match request {
+            BatchSearchRequest::Search { query, limit } => run_search(
+                &mut tx,
+                &ranking,
+                names.as_deref(),
+                &query,
+                limit,
+                None,
+                None,
+            )
+            .await
+            .map(|page| BatchSearchResponse::Search {
+                results: page.results,
+            }),
+            BatchSearchRequest::Suggestions { limit } => run_suggestions(&mut tx, &ranking, limit)
+                .await
+                .map(|results| BatchSearchResponse::Suggestions { results }),
+            BatchSearchRequest::Recent { limit } => {
+                run_used(&mut tx, &ranking, UsageOrder::Recent, limit)
+                    .await
+                    .map(|results| BatchSearchResponse::Recent { results })
+            }
+            BatchSearchRequest::Frequent { limit } => {
+                run_used(&mut tx, &ranking, UsageOrder::Frequent, limit)
+                    .await
+                    .map(|results| BatchSearchResponse::Frequent { results })
+            }
+            BatchSearchRequest::TagCounts => fetch_tag_counts(&mut tx)
+                .await
+                .map(|tags| BatchSearchResponse::TagCounts { tags }),
+        };
+
+        responses.push(response.unwrap_or_else(|e| BatchSearchResponse::Error {
+            message: e.to_string(),
+        }));
+    }
+    // Read-only transaction; nothing to commit
+    tx.rollback().await?;
+
+    Ok(responses)
+}
+
+/// Returns snippets to suggest before the user has typed anything
+async fn run_suggestions(
+    conn: &mut SqliteConnection,
+    ranking: &Ranking,
+    limit: Option<i64>,
+) -> Result<Vec<SearchResult>, AppError> {
+    let limit = limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            s.id,
+            s.name,
+            s.content,
+            s.description,
+            s.created_at,
+            s.updated_at,
+            COALESCE(usage.count, 0) as usage_count,
+            usage.last_used,
+            0.0 as fts_rank,
+            s.pinned,
+            s.kind,
+            s.language,
+            s.archived,
+            s.expires_at
+        FROM snippets s
+        LEFT JOIN (
+            SELECT
+                snippet_id,
+                COUNT(*) as count,
+                MAX(used_at) as last_used
+            FROM analytics
+            GROUP BY snippet_id
+        ) usage ON s.id = usage.snippet_id
+        WHERE s.archived = 0
+        ORDER BY usage.last_used IS NULL, usage.count DESC, s.updated_at DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    rows_to_results(conn, rows, ranking).await
+}
+
+/// Returns pinned snippets for the search overlay's empty state
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `limit` - Optional maximum number of results (defaults to 50, max 1000)
+///
+/// # Returns
+///
+/// Pinned snippets sorted by relevance score, i.e. by usage and recency
+///
+/// # Errors
+///
+/// Returns `AppError` if the query fails or database is unavailable.
+pub async fn get_pinned_snippets(
+    app: &AppHandle,
+    limit: Option<i64>,
+) -> Result<Vec<SearchResult>, AppError> {
+    let pool = get_pool(app)?;
+    let settings = SettingsService::new(pool.clone()).get_settings().await?;
+
+    let mut conn = pool.acquire().await?;
+    let ranking = Ranking::load(&mut conn, settings.search_settings, app).await?;
+    run_pinned(&mut conn, &ranking, limit).await
+}
+
+async fn run_pinned(
+    conn: &mut SqliteConnection,
+    ranking: &Ranking,
+    limit: Option<i64>,
+) -> Result<Vec<SearchResult>, AppError> {
+    let limit = limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            s.id,
+            s.name,
+            s.content,
+            s.description,
+            s.created_at,
+            s.updated_at,
+            COALESCE(usage.count, 0) as usage_count,
+            usage.last_used,
+            0.0 as fts_rank,
+            s.pinned,
+            s.kind,
+            s.language,
+            s.archived,
+            s.expires_at
+        FROM snippets s
+        LEFT JOIN (
+            SELECT
+                snippet_id,
+                COUNT(*) as count,
+                MAX(used_at) as last_used
+            FROM analytics
+            GROUP BY snippet_id
+        ) usage ON s.id = usage.snippet_id
+        WHERE s.pinned = 1 AND s.archived = 0
+        ORDER BY s.updated_at DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    rows_to_results(conn, rows, ranking).await
+}
+
+/// Order of a list of used snippets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UsageOrder {
+    /// Most recently used first
+    Recent,
+    /// Most often used first
+    Frequent,
+}
+
+impl UsageOrder {
+    fn order_by(self) -> &'static str {
+        match self {
+            UsageOrder::Recent => "usage.last_used DESC, s.id",
+            UsageOrder::Frequent => "usage.count DESC, usage.last_used DESC, s.id",
+        }
+    }
+}
+
+/// Returns the most recently used snippets, for the search overlay's empty state
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `limit` - Optional maximum number of results (defaults to 50, max 1000)
+///
+/// # Returns
+///
+/// Used, unarchived snippets, most recently used first
+///
+/// # Errors
+///
+/// Returns `AppError` if the query fails or database is unavailable.
+pub async fn get_recent_snippets(
+    app: &AppHandle,
+    limit: Option<i64>,
+) -> Result<Vec<SearchResult>, AppError> {
+    get_used_snippets(app, UsageOrder::Recent, limit).await
+}
+
+/// Returns the most often used snippets, for the search overlay's empty state
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `limit` - Optional maximum number of results (defaults to 50, max 1000)
+///
+/// # Returns
+///
+/// Used, unarchived snippets, most often used first; ties go to the more
+/// recently used one
+///
+/// # Errors
+///
+/// Returns `AppError` if the query fails or database is unavailable.
+pub async fn get_frequent_snippets(
+    app: &AppHandle,
+    limit: Option<i64>,
+) -> Result<Vec<SearchResult>, AppError> {
+    get_used_snippets(app, UsageOrder::Frequent, limit).await
+}
+
+async fn get_used_snippets(
+    app: &AppHandle,
+    order: UsageOrder,
+    limit: Option<i64>,
+) -> Result<Vec<SearchResult>, AppError> {
+    let pool = get_pool(app)?;
+    let settings = SettingsService::new(pool.clone()).get_settings().await?;
+
+    let mut conn = pool.acquire().await?;
+    let ranking = Ranking::load(&mut conn, settings.search_settings, app).await?;
+    run_used(&mut conn, &ranking, order, limit).await
+}
+
+/// Lists used snippets in `order`
+///
+/// Usage is aggregated from the `(snippet_id, used_at)` index alone and only
+/// used snippets are joined, so the list stays cheap on large libraries.
+async fn run_used(
+    conn: &mut SqliteConnection,
+    ranking: &Ranking,
+    order: UsageOrder,
+    limit: Option<i64>,
+) -> Result<Vec<SearchResult>, AppError> {
+    let limit = limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    let sql = format!(
+        r#"
+        SELECT
+            s.id,
+            s.name,
+            s.content,
+            s.description,
+            s.created_at,
+            s.updated_at,
+            usage.count as usage_count,
+            usage.last_used,
+            0.0 as fts_rank,
+            s.pinned,
+            s.kind,
+            s.language,
+            s.archived,
+            s.expires_at
+        FROM (
+            SELECT
+                snippet_id,
+                COUNT(*) as count,
+                MAX(used_at) as last_used
+            FROM analytics
+            GROUP BY snippet_id
+        ) usage
+        INNER JOIN snippets s ON s.id = usage.snippet_id
+        WHERE s.archived = 0
+        ORDER BY {}
+        LIMIT ?
+        "#,
+        order.order_by()
+    );
+    let rows = sqlx::query(&sql).bind(limit).fetch_all(&mut *conn).await?;
+
+    rows_to_ordered_results(conn, rows, ranking).await
+}
+
+/// Counts snippets per tag, most used tags first
+async fn fetch_tag_counts(conn: &mut SqliteConnection) -> Result<Vec<TagCount>, AppError> {
+    let rows = sqlx::query(
+        r#"
+        SELECT t.name, t.color, COUNT(st.snippet_id) as count
+        FROM tags t
+        LEFT JOIN snippet_tags st ON t.id = st.tag_id
+        GROUP BY t.id
+        ORDER BY count DESC, t.name
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| TagCount {
+            name: row.get(0),
+            color: row.get(1),
+            count: row.get(2),
+        })
+        .collect())
+}
+
+/// Parse a leading "lang:" filter from the query string
+///
+/// Returns tuple of (language, remaining_query). The language is returned as
+/// typed; callers normalize aliases.
+///
+/// # Examples
+///
+/// ```ignore
+/// assert_eq!(parse_language_filter("lang:rust async"), (Some("rust"), "async"));
+/// assert_eq!(parse_language_filter("python:async"), (None, "python:async"));
+/// ```
+fn parse_language_filter(query: &str) -> (Option<&str>, &str) {
+    let Some(prefix) = query.get(..5) else {
+        return (None, query);
+    };
+    if !prefix.eq_ignore_ascii_case("lang:") {
+        return (None, query);
+    }
+
+    let rest = query[5..].trim_start();
+    let (language, remaining) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    if language.is_empty() {
+        return (None, query);
+    }
+    (Some(language), remaining.trim())
+}
+
+/// Extracts `is:archived` terms from a query
+///
+/// Returns whether the query asks for archived snippets, which are otherwise
+/// left out, and the rest of the query.
+///
+/// # Examples
+///
+/// ```ignore
+/// assert_eq!(parse_archived_term("deploy is:archived"), (true, "deploy".to_string()));
+/// assert_eq!(parse_archived_term("deploy"), (false, "deploy".to_string()));
+/// ```
+fn parse_archived_term(query: &str) -> (bool, String) {
+    let mut archived = false;
+    let remaining: Vec<&str> = query
+        .split_whitespace()
+        .filter(|term| {
+            let is_archived = term.eq_ignore_ascii_case("is:archived");
+            archived |= is_archived;
+            !is_archived
+        })
+        .collect();
+    (archived, remaining.join(" "))
+}
+
+/// Tag conditions of a search query
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TagFilter {
+    /// Groups that must all match; a group matches a snippet with any of its tags
+    required: Vec<Vec<String>>,
+    /// Tags a snippet must not have
+    excluded: Vec<String>,
+}
+
+impl TagFilter {
+    fn is_empty(&self) -> bool {
+        self.required.is_empty() && self.excluded.is_empty()
+    }
+
+    /// SQL conditions on snippet `s` and their bind values, in order
+    ///
+    /// Each condition starts with ` AND ` so it can be appended to a WHERE
+    /// clause. Tag names are compared case-insensitively, and a tag path
+    /// also matches every tag nested below it.
+    fn to_sql(&self) -> (String, Vec<&str>) {
+        let mut sql = String::new();
+        let mut binds = Vec::new();
+        for group in &self.required {
+            sql.push_str(&format!(" AND EXISTS ({})", tag_subquery(group.len())));
+            binds.extend(group.iter().flat_map(|tag| tag_binds(tag)));
+        }
+        if !self.excluded.is_empty() {
+            sql.push_str(&format!(
+                " AND NOT EXISTS ({})",
+                tag_subquery(self.excluded.len())
+            ));
+            binds.extend(self.excluded.iter().flat_map(|tag| tag_binds(tag)));
+        }
+        (sql, binds)
+    }
+}
+
+/// Condition matching tag `t` against one bound path or anything below it
+const TAG_PATH_CONDITION: &str =
+    "(LOWER(t.name) = LOWER(?) OR LOWER(SUBSTR(t.name, 1, LENGTH(?) + 1)) = LOWER(?) || '/')";
 
-        // Get tags for this snippet
-        let tags = tags::get_snippet_tags(app, snippet_id).await?;
+/// Bind values for one `TAG_PATH_CONDITION`
+fn tag_binds(tag: &str) -> [&str; 3] {
+    [tag; 3]
+}
 
-        let snippet = Snippet {
-            id: SnippetId(snippet_id),
-            name: row.get(1),
-            content: row.get(2),
-            description: row.get(3),
-            created_at: row.get(4),
-            updated_at: row.get(5),
-            tags: Some(tags),
-        };
+/// Subquery finding a tag of snippet `s` under any of `count` bound paths
+fn tag_subquery(count: usize) -> String {
+    format!(
+        "SELECT 1 FROM snippet_tags st INNER JOIN tags t ON st.tag_id = t.id \
+         WHERE st.snippet_id = s.id AND ({})",
+        vec![TAG_PATH_CONDITION; count].join(" OR ")
+    )
+}
 
-        // Calculate relevance score combining FTS rank and usage statistics
-        // Use configurable weights from settings
-        let relevance_score = calculate_relevance_score(
-            fts_rank,
-            usage_count,
-            last_used,
-            search_settings.weight_text_relevance,
-            search_settings.weight_usage_frequency,
-            search_settings.weight_recency,
-        );
+/// Extracts `tag:` and `-tag:` terms and the "tagname:" prefix from a query
+///
+/// Returns the tag conditions and the remaining search text. Each `tag:`
+/// term is a group of comma-separated alternatives; all groups must match.
+///
+/// # Examples
+///
+/// ```ignore
+/// let (filter, text) = parse_tag_terms("tag:python,rust -tag:archive async");
+/// assert_eq!(filter.required, vec![vec!["python", "rust"]]);
+/// assert_eq!(filter.excluded, vec!["archive"]);
+/// assert_eq!(text, "async");
+/// ```
+fn parse_tag_terms(query: &str) -> (TagFilter, String) {
+    let mut filter = TagFilter::default();
+    let mut remaining = Vec::new();
 
-        search_results.push(SearchResult {
-            snippet,
-            usage_count,
-            last_used,
-            relevance_score,
-        });
-    }
+    for term in query.split_whitespace() {
+        let (negated, rest) = match term.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, term),
+        };
+        let tags: Vec<String> = match rest.get(..4) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("tag:") => rest[4..]
+                .split(',')
+                .map(normalize_tag_path)
+                .filter(|tag| !tag.is_empty())
+                .collect(),
+            _ => Vec::new(),
+        };
 
-    // Re-sort by relevance score (combines FTS rank with usage stats)
-    search_results.sort_by(|a, b| {
-        b.relevance_score
-            .partial_cmp(&a.relevance_score)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
+        if tags.is_empty() {
+            remaining.push(term);
+        } else if negated {
+            filter.excluded.extend(tags);
+        } else {
+            filter.required.push(tags);
+        }
+    }
 
-    Ok(search_results)
+    let remaining = remaining.join(" ");
+    // "name:" and "content:" scope a search term rather than name a tag
+    match parse_tag_filter(&remaining) {
+        (Some(tag), text) if !is_search_field(tag) => {
+            filter.required.push(vec![normalize_tag_path(tag)]);
+            (filter, text.to_string())
+        }
+        _ => (filter, remaining),
+    }
 }
 
 /// Parse tag filter from query string
@@ -260,32 +1433,121 @@ fn parse_tag_filter(query: &str) -> (Option<&str>, &str) {
     (None, query)
 }
 
+/// Columns of the search index a term can be scoped to with `field:`
+const SEARCH_FIELDS: &[&str] = &["name", "content"];
+
+/// FTS5 column filter for unscoped terms while tag names are not searched
+const UNTAGGED_COLUMNS: &str = "{name content aliases}";
+
+/// bm25() rank of a match with the name, content, tags and aliases columns
+/// weighted in that order, so an alias match outranks the other columns
+const FTS_RANK: &str = "bm25(snippets_fts, 1.0, 1.0, 1.0, 10.0)";
+
+fn is_search_field(name: &str) -> bool {
+    SEARCH_FIELDS
+        .iter()
+        .any(|field| field.eq_ignore_ascii_case(name))
+}
+
+/// One term of a search query
+#[derive(Debug, PartialEq, Eq)]
+struct QueryTerm {
+    /// Index column the term is scoped to, lowercased
+    field: Option<String>,
+    text: String,
+    /// Written in double quotes; matched as an exact phrase
+    quoted: bool,
+}
+
+/// Splits a query into terms, keeping quoted phrases together
+///
+/// A term may be scoped with a `name:` or `content:` prefix. An unclosed
+/// quote runs to the end of the query. Terms without a letter or digit are
+/// dropped, since FTS5 has nothing to match them against.
+fn split_query_terms(query: &str) -> Vec<QueryTerm> {
+    let mut terms = Vec::new();
+    let mut rest = query.trim_start();
+
+    while !rest.is_empty() {
+        let (field, after_field) = match rest.split_once(':') {
+            Some((field, after)) if is_search_field(field) => (Some(field.to_lowercase()), after),
+            _ => (None, rest),
+        };
+
+        let (text, quoted, remaining) = match after_field.strip_prefix('"') {
+            Some(phrase) => match phrase.find('"') {
+                Some(end) => (&phrase[..end], true, &phrase[end + 1..]),
+                None => (phrase, true, ""),
+            },
+            None => {
+                let end = after_field
+                    .find(char::is_whitespace)
+                    .unwrap_or(after_field.len());
+                (&after_field[..end], false, &after_field[end..])
+            }
+        };
+
+        if text.chars().any(char::is_alphanumeric) {
+            terms.push(QueryTerm {
+                field,
+                text: text.trim().to_string(),
+                quoted,
+            });
+        }
+        rest = remaining.trim_start();
+    }
+    terms
+}
+
+/// Quotes text as an FTS5 string, so operators and punctuation in it are literal
+fn fts5_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
 /// Build FTS5 query from user input
 ///
 /// This function prepares the user's search query for FTS5.
 /// Features:
 /// - Prefix matching: "taur" matches "tauri"
 /// - Multi-token OR search: "react hooks" matches snippets containing either term
-/// - Special character escaping for safety
-fn build_fts5_query(query: &str) -> String {
-    // Escape special FTS5 characters to prevent syntax errors
-    // Remove: " (phrases), * (wildcards we'll add ourselves), ( ) (grouping)
-    let escaped = query.replace(['"', '*', '(', ')'], "");
+/// - Exact phrases: "\"error handling\"" matches the words next to each other
+/// - Field scoping: "name:deploy" or "content:\"kubectl get\"" searches one column
+/// - Every term is quoted, so punctuation never causes FTS5 syntax errors
+///
+/// Phrases and scoped terms are required; plain words then need at least
+/// one match among them. Unscoped terms also match tag names when
+/// `search_in_tags` is set.
+fn build_fts5_query(query: &str, search_in_tags: bool) -> String {
+    let unscoped = |expression: String| {
+        if search_in_tags {
+            expression
+        } else {
+            format!("{} : {}", UNTAGGED_COLUMNS, expression)
+        }
+    };
+    let mut required = Vec::new();
+    let mut any = Vec::new();
 
-    // Split into tokens
-    let tokens: Vec<&str> = escaped.split_whitespace().collect();
+    for term in split_query_terms(query) {
+        // Bare words match as prefixes, phrases exactly
+        let mut expression = fts5_string(&term.text);
+        if !term.quoted {
+            expression.push('*');
+        }
 
-    if tokens.is_empty() {
-        return String::new();
+        match term.field {
+            Some(field) => required.push(format!("{} : {}", field, expression)),
+            None if term.quoted => required.push(unscoped(expression)),
+            None => any.push(expression),
+        }
     }
 
-    // Add prefix matching wildcard to each token for partial matching
-    // This enables "taur" to match "tauri"
-    let prefix_tokens: Vec<String> = tokens.iter().map(|t| format!("{}*", t)).collect();
-
-    // Join tokens with OR operator for broader matching
-    // This allows matching any of the search terms
-    prefix_tokens.join(" OR ")
+    match any.len() {
+        0 => {}
+        1 => required.extend(any.pop().map(unscoped)),
+        _ => required.push(unscoped(format!("({})", any.join(" OR ")))),
+    }
+    required.join(" AND ")
 }
 
 /// Calculate relevance score combining FTS rank with usage statistics
@@ -294,15 +1556,17 @@ fn build_fts5_query(query: &str) -> String {
 /// 1. FTS5 BM25 rank (text relevance)
 /// 2. Usage frequency (how often the snippet is used)
 /// 3. Recency (when it was last used)
+/// 4. App context (how often it is used with the focused application)
+/// 5. Pinning (a flat bonus for pinned snippets)
 ///
 /// # Arguments
 ///
 /// * `fts_rank` - FTS5 BM25 rank (negative number, closer to 0 is better)
 /// * `usage_count` - Number of times snippet has been used
 /// * `last_used` - Timestamp of last usage (None if never used)
-/// * `weight_text` - Weight multiplier for text relevance (default: 10.0)
-/// * `weight_usage` - Weight multiplier for usage frequency (default: 2.0)
-/// * `weight_recency` - Weight multiplier for recency (default: 1.0)
+/// * `app_usage_count` - Number of times it was used with the focused application
+/// * `search_settings` - Weights of each part and the pinned bonus
+/// * `pinned` - Whether the snippet is pinned
 ///
 /// # Returns
 ///
@@ -311,20 +1575,37 @@ fn calculate_relevance_score(
     fts_rank: f64,
     usage_count: i64,
     last_used: Option<i64>,
-    weight_text: f64,
-    weight_usage: f64,
-    weight_recency: f64,
+    app_usage_count: i64,
+    search_settings: &SearchSettings,
+    pinned: bool,
 ) -> f64 {
+    score_breakdown(
+        fts_rank,
+        usage_count,
+        last_used,
+        app_usage_count,
+        search_settings,
+        pinned,
+    )
+    .relevance_score
+}
+
+/// Each part of the relevance score, with the same arguments as
+/// `calculate_relevance_score`
+fn score_breakdown(
+    fts_rank: f64,
+    usage_count: i64,
+    last_used: Option<i64>,
+    app_usage_count: i64,
+    search_settings: &SearchSettings,
+    pinned: bool,
+) -> ScoreBreakdown {
     // FTS5 rank is negative, normalize to positive (closer to 0 = better match)
     // Convert to positive score where higher is better
     let text_score = -fts_rank;
 
     // Usage frequency score (logarithmic to prevent domination by highly-used snippets)
-    let usage_score = if usage_count > 0 {
-        (usage_count as f64).ln() + 1.0
-    } else {
-        0.0
-    };
+    let usage_score = frequency_score(usage_count);
 
     // Recency score (bonus for recently used snippets)
     let recency_score = match last_used {
@@ -347,15 +1628,63 @@ fn calculate_relevance_score(
         None => 0.0,
     };
 
+    // Usage with the focused application, scaled like overall usage
+    let app_score = frequency_score(app_usage_count);
+
     // Weighted combination of scores using configurable weights
     // This allows users to tune ranking behavior based on their preferences
-    (text_score * weight_text) + (usage_score * weight_usage) + (recency_score * weight_recency)
+    let text = ScoreComponent::new(text_score, search_settings.weight_text_relevance);
+    let usage = ScoreComponent::new(usage_score, search_settings.weight_usage_frequency);
+    let recency = ScoreComponent::new(recency_score, search_settings.weight_recency);
+    let app_context = ScoreComponent::new(app_score, search_settings.weight_app_context);
+    let pinned_bonus = if pinned {
+        search_settings.pinned_bonus
+    } else {
+        0.0
+    };
+    ScoreBreakdown {
+        text,
+        usage,
+        recency,
+        app_context,
+        pinned_bonus,
+        relevance_score: text.weighted
+            + usage.weighted
+            + recency.weighted
+            + app_context.weighted
+            + pinned_bonus,
+    }
+}
+
+/// Logarithmic score of a usage count; 0.0 for unused snippets
+fn frequency_score(count: i64) -> f64 {
+    if count > 0 {
+        (count as f64).ln() + 1.0
+    } else {
+        0.0
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_language_filter() {
+        assert_eq!(
+            parse_language_filter("lang:rust async"),
+            (Some("rust"), "async")
+        );
+        assert_eq!(parse_language_filter("LANG: bash"), (Some("bash"), ""));
+        assert_eq!(
+            parse_language_filter("lang:py python:async"),
+            (Some("py"), "python:async")
+        );
+        assert_eq!(parse_language_filter("lang:"), (None, "lang:"));
+        assert_eq!(parse_language_filter("language"), (None, "language"));
+        assert_eq!(parse_language_filter("läng"), (None, "läng"));
+    }
+
     #[test]
     fn test_parse_tag_filter() {
         // Test tag filter with no search query
@@ -396,99 +1725,754 @@ mod tests {
         assert_eq!(parse_tag_filter("Python:"), (Some("Python"), ""));
     }
 
+    #[test]
+    fn test_parse_tag_terms() {
+        let (filter, text) = parse_tag_terms("tag:python tag:async await");
+        assert_eq!(filter.required, vec![vec!["python"], vec!["async"]]);
+        assert!(filter.excluded.is_empty());
+        assert_eq!(text, "await");
+
+        let (filter, text) = parse_tag_terms("deploy TAG:python,rust -tag:archive,old");
+        assert_eq!(filter.required, vec![vec!["python", "rust"]]);
+        assert_eq!(filter.excluded, vec!["archive", "old"]);
+        assert_eq!(text, "deploy");
+
+        // The prefix form combines with tag terms
+        let (filter, text) = parse_tag_terms("python: -tag:archive async");
+        assert_eq!(filter.required, vec![vec!["python"]]);
+        assert_eq!(filter.excluded, vec!["archive"]);
+        assert_eq!(text, "async");
+
+        // Tag paths are normalized
+        let (filter, _) = parse_tag_terms("tag:lang//rust/ lang/go/:");
+        assert_eq!(filter.required, vec![vec!["lang/rust"], vec!["lang/go"]]);
+
+        // Empty tag lists are left as text
+        let (filter, text) = parse_tag_terms("react -tag: hooks");
+        assert!(filter.is_empty());
+        assert_eq!(text, "react -tag: hooks");
+    }
+
+    #[test]
+    fn test_tag_filter_sql() {
+        let (filter, _) = parse_tag_terms("tag:a,b tag:c -tag:d");
+        let (sql, binds) = filter.to_sql();
+        assert_eq!(sql.matches(" AND EXISTS").count(), 2);
+        assert_eq!(sql.matches(" AND NOT EXISTS").count(), 1);
+        assert_eq!(sql.matches('?').count(), binds.len());
+        assert_eq!(binds, ["a", "b", "c", "d"].map(|tag| [tag; 3]).concat());
+
+        assert_eq!(TagFilter::default().to_sql(), (String::new(), Vec::new()));
+    }
+
     #[test]
     fn test_build_fts5_query() {
         // Test simple query with prefix matching
-        assert_eq!(build_fts5_query("react"), "react*");
+        assert_eq!(build_fts5_query("react", true), "\"react\"*");
 
         // Test multiple words with prefix matching on each
-        assert_eq!(build_fts5_query("react hooks"), "react* OR hooks*");
+        assert_eq!(
+            build_fts5_query("react hooks", true),
+            "(\"react\"* OR \"hooks\"*)"
+        );
 
-        // Test with special characters (should be escaped, then * added)
-        assert_eq!(build_fts5_query("test*query"), "testquery*");
+        // Punctuation is quoted instead of breaking the FTS5 syntax
+        assert_eq!(
+            build_fts5_query("http://example.com", true),
+            "\"http://example.com\"*"
+        );
+        assert_eq!(build_fts5_query("say\"hi", true), "\"say\"\"hi\"*");
+        assert_eq!(build_fts5_query("- *", true), "");
 
         // Test empty query
-        assert_eq!(build_fts5_query(""), "");
+        assert_eq!(build_fts5_query("", true), "");
 
         // Test whitespace only
-        assert_eq!(build_fts5_query("   "), "");
+        assert_eq!(build_fts5_query("   ", true), "");
 
         // Test partial word matching
-        assert_eq!(build_fts5_query("taur"), "taur*");
+        assert_eq!(build_fts5_query("taur", true), "\"taur\"*");
+    }
+
+    #[test]
+    fn test_build_fts5_query_phrases_and_fields() {
+        assert_eq!(
+            build_fts5_query("\"error handling\"", true),
+            "\"error handling\""
+        );
+        assert_eq!(build_fts5_query("name:deploy", true), "name : \"deploy\"*");
+        assert_eq!(
+            build_fts5_query("Content:\"kubectl get\"", true),
+            "content : \"kubectl get\""
+        );
+
+        // Phrases and scoped terms are required, bare words optional among themselves
+        assert_eq!(
+            build_fts5_query("\"error handling\" rust go", true),
+            "\"error handling\" AND (\"rust\"* OR \"go\"*)"
+        );
+        assert_eq!(
+            build_fts5_query("name:deploy staging", true),
+            "name : \"deploy\"* AND \"staging\"*"
+        );
+
+        // An unclosed quote runs to the end; unknown fields are plain words
+        assert_eq!(build_fts5_query("\"exit code", true), "\"exit code\"");
+        assert_eq!(build_fts5_query("desc:foo", true), "\"desc:foo\"*");
+    }
+
+    #[test]
+    fn test_build_fts5_query_without_tags() {
+        assert_eq!(
+            build_fts5_query("react hooks", false),
+            "{name content aliases} : (\"react\"* OR \"hooks\"*)"
+        );
+        assert_eq!(
+            build_fts5_query("\"error handling\" name:deploy rust", false),
+            "{name content aliases} : \"error handling\" AND name : \"deploy\"* \
+             AND {name content aliases} : \"rust\"*"
+        );
+    }
+
+    #[test]
+    fn test_search_fields_are_not_tags() {
+        let (filter, text) = parse_tag_terms("name:deploy tag:ops");
+        assert_eq!(filter.required, vec![vec!["ops"]]);
+        assert_eq!(text, "name:deploy");
+
+        let (filter, _) = parse_tag_terms("docker: compose");
+        assert_eq!(filter.required, vec![vec!["docker"]]);
     }
 
     #[test]
     fn test_calculate_relevance_score() {
         // Default weights for testing
-        let weight_text = 10.0;
-        let weight_usage = 2.0;
-        let weight_recency = 1.0;
+        let settings = SearchSettings {
+            weight_text_relevance: 10.0,
+            weight_usage_frequency: 2.0,
+            weight_recency: 1.0,
+            ..Default::default()
+        };
 
         // Test text relevance only (unused snippet)
-        let score =
-            calculate_relevance_score(-1.0, 0, None, weight_text, weight_usage, weight_recency);
+        let score = calculate_relevance_score(-1.0, 0, None, 0, &settings, false);
         assert_eq!(score, 10.0); // text_score * 10
 
         // Test with usage count
-        let score =
-            calculate_relevance_score(-1.0, 10, None, weight_text, weight_usage, weight_recency);
+        let score = calculate_relevance_score(-1.0, 10, None, 0, &settings, false);
         assert!(score > 10.0); // Should be higher due to usage
 
         // Test with recent usage (within 7 days)
         let now = crate::utils::time::current_timestamp();
         let recent = now - (3 * 24 * 3600); // 3 days ago
-        let score = calculate_relevance_score(
-            -1.0,
-            0,
-            Some(recent),
-            weight_text,
-            weight_usage,
-            weight_recency,
-        );
+        let score = calculate_relevance_score(-1.0, 0, Some(recent), 0, &settings, false);
         assert_eq!(score, 12.0); // 10 (text) + 0 (no usage) + 2 (recent)
 
         // Test with older usage (within 30 days)
         let older = now - (20 * 24 * 3600); // 20 days ago
-        let score = calculate_relevance_score(
-            -1.0,
-            0,
-            Some(older),
-            weight_text,
-            weight_usage,
-            weight_recency,
-        );
+        let score = calculate_relevance_score(-1.0, 0, Some(older), 0, &settings, false);
         assert_eq!(score, 11.0); // 10 (text) + 0 (no usage) + 1 (medium recency)
     }
 
     #[test]
     fn test_calculate_relevance_score_custom_weights() {
         // Test with custom weights that prioritize usage over text relevance
-        let weight_text = 1.0;
-        let weight_usage = 10.0;
-        let weight_recency = 0.5;
+        let settings = SearchSettings {
+            weight_text_relevance: 1.0,
+            weight_usage_frequency: 10.0,
+            weight_recency: 0.5,
+            ..Default::default()
+        };
 
         // Snippet with high usage should score higher
-        let score_high_usage =
-            calculate_relevance_score(-1.0, 100, None, weight_text, weight_usage, weight_recency);
-        let score_low_usage =
-            calculate_relevance_score(-1.0, 1, None, weight_text, weight_usage, weight_recency);
+        let score_high_usage = calculate_relevance_score(-1.0, 100, None, 0, &settings, false);
+        let score_low_usage = calculate_relevance_score(-1.0, 1, None, 0, &settings, false);
         assert!(score_high_usage > score_low_usage);
 
         // Test that weights actually affect the score
         let now = crate::utils::time::current_timestamp();
         let recent = now - (3 * 24 * 3600);
-        let score_with_recency = calculate_relevance_score(
-            -1.0,
-            0,
-            Some(recent),
-            weight_text,
-            weight_usage,
-            weight_recency,
-        );
+        let score_with_recency =
+            calculate_relevance_score(-1.0, 0, Some(recent), 0, &settings, false);
         // Should be text (1.0) + recency bonus (2.0 * 0.5) = 2.0
         assert_eq!(score_with_recency, 2.0);
     }
 
+    #[test]
+    fn test_calculate_relevance_score_pinned_bonus() {
+        let settings = SearchSettings {
+            pinned_bonus: 20.0,
+            ..Default::default()
+        };
+        let unpinned = calculate_relevance_score(-1.0, 5, None, 0, &settings, false);
+        let pinned = calculate_relevance_score(-1.0, 5, None, 0, &settings, true);
+        assert_eq!(pinned - unpinned, 20.0);
+    }
+
+    #[test]
+    fn test_calculate_relevance_score_app_context() {
+        let settings = SearchSettings {
+            weight_app_context: 3.0,
+            ..Default::default()
+        };
+        let elsewhere = calculate_relevance_score(-1.0, 5, None, 0, &settings, false);
+        let here = calculate_relevance_score(-1.0, 5, None, 1, &settings, false);
+        assert_eq!(here - elsewhere, 3.0);
+
+        // A zero weight turns the boost off
+        let settings = SearchSettings {
+            weight_app_context: 0.0,
+            ..settings
+        };
+        let here = calculate_relevance_score(-1.0, 5, None, 4, &settings, false);
+        assert_eq!(
+            here,
+            calculate_relevance_score(-1.0, 5, None, 0, &settings, false)
+        );
+    }
+
+    async fn setup_test_db() -> sqlx::SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        for statement in [
+            "CREATE TABLE snippets (id INTEGER PRIMARY KEY, name TEXT, content TEXT,
+                description TEXT, created_at INTEGER, updated_at INTEGER,
+                pinned INTEGER NOT NULL DEFAULT 0, kind TEXT NOT NULL DEFAULT 'static',
+                language TEXT, archived INTEGER NOT NULL DEFAULT 0, expires_at INTEGER)",
+            "CREATE TABLE tags (id INTEGER PRIMARY KEY, name TEXT, color TEXT)",
+            "CREATE TABLE snippet_tags (snippet_id INTEGER, tag_id INTEGER)",
+            "CREATE TABLE analytics (id INTEGER PRIMARY KEY, snippet_id INTEGER, used_at INTEGER)",
+            "INSERT INTO snippets (id, name, content, description, created_at, updated_at)
+                VALUES (1, 'a', 'A', NULL, 1, 1), (2, 'b', 'B', NULL, 2, 2),
+                (3, 'c', 'C', NULL, 3, 3)",
+            "INSERT INTO tags VALUES (1, 'rust', '#fff'), (2, 'sql', '#000'), (3, 'unused', '#111')",
+            "INSERT INTO snippet_tags VALUES (1, 2), (1, 1), (2, 1)",
+            "INSERT INTO analytics (snippet_id, used_at) VALUES (2, 100), (2, 200), (1, 50)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_fetch_tags_for_snippets() {
+        let pool = setup_test_db().await;
+        let mut conn = pool.acquire().await.unwrap();
+
+        let tags = fetch_tags_for_snippets(&mut conn, &[1, 2, 3])
+            .await
+            .unwrap();
+        assert_eq!(tags[&1], vec!["rust", "sql"]);
+        assert_eq!(tags[&2], vec!["rust"]);
+        assert!(!tags.contains_key(&3));
+
+        let empty = fetch_tags_for_snippets(&mut conn, &[]).await.unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_suggestions_prefer_used_snippets() {
+        let pool = setup_test_db().await;
+        let mut conn = pool.acquire().await.unwrap();
+
+        let results = run_suggestions(&mut conn, &Ranking::default(), Some(10))
+            .await
+            .unwrap();
+        let names: Vec<&str> = results.iter().map(|r| r.snippet.name.as_str()).collect();
+        assert_eq!(names, vec!["b", "a", "c"]);
+        assert_eq!(results[0].usage_count, 2);
+        assert_eq!(
+            results[0].snippet.tags.as_deref(),
+            Some(&["rust".to_string()][..])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pinned_snippets() {
+        let pool = setup_test_db().await;
+        sqlx::query("UPDATE snippets SET pinned = 1 WHERE id IN (1, 3)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+
+        let pinned = run_pinned(&mut conn, &Ranking::default(), None)
+            .await
+            .unwrap();
+        let names: Vec<&str> = pinned.iter().map(|r| r.snippet.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "c"]);
+        assert!(pinned.iter().all(|r| r.snippet.pinned));
+
+        // The pin bonus lifts an unused pinned snippet above a used one
+        let suggestions = run_suggestions(&mut conn, &Ranking::default(), None)
+            .await
+            .unwrap();
+        let names: Vec<&str> = suggestions
+            .iter()
+            .map(|r| r.snippet.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["a", "c", "b"]);
+    }
+
+    fn ranked(ids: &[i64]) -> Vec<RankedMatch> {
+        ids.iter()
+            .map(|id| RankedMatch {
+                id: *id,
+                usage_count: 0,
+                last_used: None,
+                fts_rank: 0.0,
+                pinned: false,
+                relevance_score: 0.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_page_start() {
+        let matches = ranked(&[5, 3, 8, 1]);
+        assert_eq!(page_start(&matches, None, None).unwrap(), 0);
+        assert_eq!(page_start(&matches, Some(2), None).unwrap(), 2);
+        assert_eq!(page_start(&matches, Some(-4), None).unwrap(), 0);
+
+        // The cursor follows its snippet when matches shift ahead of it
+        assert_eq!(page_start(&matches, Some(0), Some("2:3")).unwrap(), 2);
+        assert_eq!(
+            page_start(&ranked(&[9, 5, 3, 8]), None, Some("2:3")).unwrap(),
+            3
+        );
+        // and falls back to its position when the snippet is gone
+        assert_eq!(
+            page_start(&ranked(&[5, 8, 1]), None, Some("2:3")).unwrap(),
+            2
+        );
+
+        assert!(page_start(&matches, None, Some("garbage")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_recent_and_frequent_snippets() {
+        let pool = setup_test_db().await;
+        sqlx::query("INSERT INTO analytics (snippet_id, used_at) VALUES (1, 300), (3, 400)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        let ranking = Ranking::default();
+
+        let recent = run_used(&mut conn, &ranking, UsageOrder::Recent, None)
+            .await
+            .unwrap();
+        let names: Vec<&str> = recent.iter().map(|r| r.snippet.name.as_str()).collect();
+        assert_eq!(names, vec!["c", "a", "b"]);
+        assert_eq!(recent[0].last_used, Some(400));
+
+        // Equal counts go to the more recently used snippet
+        let frequent = run_used(&mut conn, &ranking, UsageOrder::Frequent, Some(2))
+            .await
+            .unwrap();
+        let names: Vec<&str> = frequent.iter().map(|r| r.snippet.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "b"]);
+        assert_eq!(frequent[0].usage_count, 2);
+        assert_eq!(
+            frequent[1].snippet.tags.as_deref(),
+            Some(&["rust".to_string()][..])
+        );
+
+        // Archived and unused snippets are left out
+        sqlx::query("UPDATE snippets SET archived = 1 WHERE id = 3")
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO snippets (id, name, content) VALUES (4, 'd', 'D')")
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        let recent = run_used(&mut conn, &ranking, UsageOrder::Recent, None)
+            .await
+            .unwrap();
+        let names: Vec<&str> = recent.iter().map(|r| r.snippet.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn test_search_pages() {
+        let pool = setup_test_db().await;
+        for statement in [
+            "CREATE VIRTUAL TABLE snippets_fts USING fts5(name, content)",
+            "INSERT INTO snippets (id, name, content, created_at, updated_at)
+                VALUES (10, 'deploy api', 'x', 0, 0), (11, 'deploy web', 'x', 0, 0),
+                (12, 'deploy docs', 'x', 0, 0), (13, 'unrelated', 'x', 0, 0)",
+            "INSERT INTO snippets_fts (rowid, name, content) SELECT id, name, content FROM snippets",
+            "INSERT INTO analytics (snippet_id, used_at) VALUES (12, 10)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        let mut conn = pool.acquire().await.unwrap();
+        let settings = Ranking::default();
+
+        let first = run_search(&mut conn, &settings, None, "deploy", Some(2), None, None)
+            .await
+            .unwrap();
+        assert_eq!(first.total_matches, 3);
+        assert_eq!(first.offset, 0);
+        assert_eq!(first.results.len(), 2);
+        // The used snippet ranks first
+        assert_eq!(first.results[0].snippet.id.0, 12);
+        assert_eq!(first.results[0].usage_count, 1);
+        // Name-only matches preview the start of the content
+        assert_eq!(first.results[0].preview, "x");
+
+        let cursor = first.next_cursor.expect("a second page");
+        let second = run_search(
+            &mut conn,
+            &settings,
+            None,
+            "deploy",
+            Some(2),
+            None,
+            Some(&cursor),
+        )
+        .await
+        .unwrap();
+        assert_eq!(second.offset, 2);
+        assert_eq!(second.results.len(), 1);
+        assert_eq!(second.next_cursor, None);
+
+        let mut seen: Vec<i64> = first
+            .results
+            .iter()
+            .chain(&second.results)
+            .map(|r| r.snippet.id.0)
+            .collect();
+        seen.sort();
+        assert_eq!(seen, vec![10, 11, 12]);
+
+        let past_end = run_search(&mut conn, &settings, None, "deploy", Some(2), Some(9), None)
+            .await
+            .unwrap();
+        assert!(past_end.results.is_empty());
+        assert_eq!(past_end.total_matches, 3);
+    }
+
+    #[tokio::test]
+    async fn test_explain_ranking() {
+        let pool = setup_test_db().await;
+        for statement in [
+            "CREATE VIRTUAL TABLE snippets_fts USING fts5(name, content)",
+            "INSERT INTO snippets (id, name, content, created_at, updated_at)
+                VALUES (10, 'deploy api', 'x', 0, 0), (11, 'deploy web', 'x', 0, 0)",
+            "INSERT INTO snippets_fts (rowid, name, content) SELECT id, name, content FROM snippets",
+            "INSERT INTO analytics (snippet_id, used_at) VALUES (11, 10), (11, 20)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        let mut conn = pool.acquire().await.unwrap();
+        let ranking = Ranking {
+            app_usage: HashMap::from([(11, 1)]),
+            ..Default::default()
+        };
+
+        let explanation = explain_ranking(&mut conn, &ranking, None, "deploy", 11)
+            .await
+            .unwrap();
+        assert_eq!(explanation.position, 1);
+        assert_eq!(explanation.total_matches, 2);
+        assert_eq!(explanation.usage_count, 2);
+        assert_eq!(explanation.last_used, Some(20));
+        assert_eq!(explanation.app_usage_count, 1);
+        assert!(explanation.fts_rank < 0.0);
+
+        let scores = &explanation.scores;
+        assert_eq!(scores.text.score, -explanation.fts_rank);
+        assert_eq!(scores.app_context.weighted, 3.0);
+        assert_eq!(scores.pinned_bonus, 0.0);
+        assert_eq!(
+            scores.relevance_score,
+            scores.text.weighted
+                + scores.usage.weighted
+                + scores.recency.weighted
+                + scores.app_context.weighted
+        );
+
+        let second = explain_ranking(&mut conn, &ranking, None, "deploy", 10)
+            .await
+            .unwrap();
+        assert_eq!(second.position, 2);
+        assert!(second.scores.relevance_score < scores.relevance_score);
+
+        assert!(matches!(
+            explain_ranking(&mut conn, &ranking, None, "deploy", 1).await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            explain_ranking(&mut conn, &ranking, None, "  ", 10).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_search_in_tags_setting() {
+        let pool = setup_test_db().await;
+        for statement in [
+            "CREATE VIRTUAL TABLE snippets_fts USING fts5(name, content, tags, aliases)",
+            "INSERT INTO snippets (id, name, content) VALUES (20, 'compose up', 'x')",
+            "INSERT INTO snippets_fts (rowid, name, content, tags)
+                VALUES (20, 'compose up', 'x', 'docker infra/k8s')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        let mut conn = pool.acquire().await.unwrap();
+
+        let page = run_search(
+            &mut conn,
+            &Ranking::default(),
+            None,
+            "k8s",
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(page.total_matches, 1);
+        assert_eq!(page.results[0].snippet.id.0, 20);
+
+        let ranking = Ranking {
+            settings: SearchSettings {
+                search_in_tags: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let page = run_search(&mut conn, &ranking, None, "docker", None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(page.total_matches, 0);
+        let page = run_search(&mut conn, &ranking, None, "compose", None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(page.total_matches, 1);
+    }
+
+    #[tokio::test]
+    async fn test_alias_matches_rank_first() {
+        let pool = setup_test_db().await;
+        for statement in [
+            "CREATE VIRTUAL TABLE snippets_fts USING fts5(name, content, tags, aliases)",
+            "INSERT INTO snippets (id, name, content, created_at, updated_at)
+                VALUES (20, 'k8s cheatsheet', 'pods', 0, 0),
+                (21, 'kubernetes rollout', 'kubectl rollout restart', 0, 0)",
+            "INSERT INTO snippets_fts (rowid, name, content) SELECT id, name, content FROM snippets",
+            r#"UPDATE snippets_fts SET aliases = '["k8s","kro"]' WHERE rowid = 21"#,
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        let mut conn = pool.acquire().await.unwrap();
+
+        let page = run_search(
+            &mut conn,
+            &Ranking::default(),
+            None,
+            "k8s",
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let ids: Vec<i64> = page.results.iter().map(|r| r.snippet.id.0).collect();
+        assert_eq!(ids, vec![21, 20]);
+    }
+
+    #[tokio::test]
+    async fn test_search_previews() {
+        let pool = setup_test_db().await;
+        let long_content = format!("{} needle {}", "hay ".repeat(60), "hay ".repeat(60));
+        for statement in [
+            "CREATE VIRTUAL TABLE snippets_fts USING fts5(name, content)",
+            "INSERT INTO snippets_fts (rowid, name, content) SELECT id, name, content FROM snippets",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        sqlx::query("INSERT INTO snippets (id, name, content) VALUES (20, 'long', ?)")
+            .bind(&long_content)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO snippets_fts (rowid, name, content) VALUES (20, 'long', ?)")
+            .bind(&long_content)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        let settings = Ranking::default();
+
+        let page = run_search(&mut conn, &settings, None, "needle", None, None, None)
+            .await
+            .unwrap();
+        let preview = &page.results[0].preview;
+        assert!(preview.contains("needle"), "{}", preview);
+        assert!(preview.starts_with('…') && preview.ends_with('…'));
+
+        let mut results = page.results;
+        omit_content(&mut results);
+        assert!(results[0].snippet.content.is_empty());
+        assert!(!results[0].preview.is_empty());
+    }
+
+    #[test]
+    fn test_content_preview() {
+        assert_eq!(content_preview("  short\n"), "short");
+        let long = "é".repeat(PREVIEW_CHARS + 10);
+        let preview = content_preview(&long);
+        assert_eq!(preview.chars().count(), PREVIEW_CHARS + 1);
+        assert!(preview.ends_with('…'));
+    }
+
+    #[tokio::test]
+    async fn test_search_by_tags() {
+        let pool = setup_test_db().await;
+        let mut conn = pool.acquire().await.unwrap();
+        let settings = Ranking::default();
+        for statement in [
+            "INSERT INTO tags VALUES (4, 'lang/Rust/async', '#fff'), (5, 'language', '#fff')",
+            "INSERT INTO snippet_tags VALUES (3, 4), (2, 5)",
+        ] {
+            sqlx::query(statement).execute(&mut *conn).await.unwrap();
+        }
+
+        for (query, expected) in [
+            ("tag:rust", vec!["a", "b"]),
+            ("tag:RUST tag:sql", vec!["a"]),
+            ("tag:sql,unused", vec!["a"]),
+            ("tag:rust -tag:sql", vec!["b"]),
+            ("-tag:rust", vec!["c"]),
+            ("rust: -tag:sql", vec!["b"]),
+            ("tag:lang", vec!["c"]),
+            ("tag:lang/rust/ -tag:sql", vec!["c"]),
+            ("tag:lang/ru", vec![]),
+            ("-tag:lang/rust", vec!["a", "b"]),
+        ] {
+            let page = run_search(&mut conn, &settings, None, query, None, None, None)
+                .await
+                .unwrap();
+            let mut names: Vec<&str> = page
+                .results
+                .iter()
+                .map(|r| r.snippet.name.as_str())
+                .collect();
+            names.sort();
+            assert_eq!(names, expected, "query {:?}", query);
+        }
+    }
+
+    #[test]
+    fn test_parse_archived_term() {
+        assert_eq!(
+            parse_archived_term("deploy IS:ARCHIVED api"),
+            (true, "deploy api".to_string())
+        );
+        assert_eq!(parse_archived_term("is:archived"), (true, String::new()));
+        assert_eq!(
+            parse_archived_term("this:archived"),
+            (false, "this:archived".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_archived() {
+        let pool = setup_test_db().await;
+        for statement in [
+            "CREATE VIRTUAL TABLE snippets_fts USING fts5(name, content)",
+            "INSERT INTO snippets (id, name, content, created_at, updated_at, pinned, archived)
+                VALUES (10, 'deploy api', 'x', 0, 0, 1, 0), (11, 'deploy web', 'x', 0, 0, 1, 1)",
+            "INSERT INTO snippets_fts (rowid, name, content) SELECT id, name, content FROM snippets",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        let mut conn = pool.acquire().await.unwrap();
+        let settings = Ranking::default();
+
+        for (query, expected) in [
+            ("deploy", vec![10]),
+            ("is:archived deploy", vec![11]),
+            ("is:archived", vec![11]),
+            ("is:archived tag:rust", vec![]),
+        ] {
+            let page = run_search(&mut conn, &settings, None, query, None, None, None)
+                .await
+                .unwrap();
+            let ids: Vec<i64> = page.results.iter().map(|r| r.snippet.id.0).collect();
+            assert_eq!(ids, expected, "query {:?}", query);
+        }
+
+        let pinned = run_pinned(&mut conn, &settings, None).await.unwrap();
+        assert_eq!(pinned.len(), 1);
+        assert!(!pinned[0].snippet.archived);
+    }
+
+    #[tokio::test]
+    async fn test_search_flags_expired() {
+        let pool = setup_test_db().await;
+        sqlx::query("UPDATE snippets SET expires_at = CASE id WHEN 1 THEN 10 WHEN 2 THEN ? END")
+            .bind(current_timestamp() + 3600)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+
+        let page = run_search(
+            &mut conn,
+            &Ranking::default(),
+            None,
+            "tag:rust",
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let mut flags: Vec<(i64, bool)> = page
+            .results
+            .iter()
+            .map(|r| (r.snippet.id.0, r.expired))
+            .collect();
+        flags.sort();
+        assert_eq!(flags, vec![(1, true), (2, false)]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_tag_counts() {
+        let pool = setup_test_db().await;
+        let mut conn = pool.acquire().await.unwrap();
+
+        let counts = fetch_tag_counts(&mut conn).await.unwrap();
+        let summary: Vec<(&str, i64)> = counts.iter().map(|t| (t.name.as_str(), t.count)).collect();
+        assert_eq!(summary, vec![("rust", 2), ("sql", 1), ("unused", 0)]);
+    }
+
+    #[test]
+    fn test_batch_request_deserialization() {
+        let requests: Vec<BatchSearchRequest> = serde_json::from_str(
+            r#"[{"kind":"search","query":"git","limit":5},{"kind":"suggestions"},{"kind":"tag_counts"}]"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            &requests[0],
+            BatchSearchRequest::Search { query, limit: Some(5) } if query == "git"
+        ));
+        assert!(matches!(
+            requests[1],
+            BatchSearchRequest::Suggestions { limit: None }
+        ));
+        assert!(matches!(requests[2], BatchSearchRequest::TagCounts));
+    }
+
     #[test]
     fn test_search_limits() {
         // Test default limit
@@ -1,14 +1,20 @@
 use crate::models::settings::AppSettings;
 use crate::utils::error::AppError;
 use crate::utils::time::current_timestamp;
+use serde_json::{Map, Value};
 use sqlx::SqlitePool;
+use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Event emitted with the full `AppSettings` after settings are saved
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
 /// Settings service for managing application settings
 pub struct SettingsService {
     pool: SqlitePool,
     cache: Arc<RwLock<Option<AppSettings>>>,
+    invalid_sections: Arc<RwLock<Vec<String>>>,
 }
 
 impl SettingsService {
@@ -17,6 +23,7 @@ impl SettingsService {
         Self {
             pool,
             cache: Arc::new(RwLock::new(None)),
+            invalid_sections: Arc::new(RwLock::new(Vec::new())),
         }
     }
 
@@ -31,7 +38,8 @@ impl SettingsService {
         }
 
         // Load from database
-        let settings = self.load_from_database().await?;
+        let (settings, invalid_sections) = self.load_from_database().await?;
+        *self.invalid_sections.write().await = invalid_sections;
 
         // Update cache
         {
@@ -43,12 +51,21 @@ impl SettingsService {
     }
 
     /// Update settings in database and cache
+    ///
+    /// Keys in the stored JSON that this version does not know about (for
+    /// example, written by a newer version) are preserved, so a downgrade and
+    /// upgrade cycle does not lose configuration.
     pub async fn update_settings(&self, settings: AppSettings) -> Result<(), AppError> {
         // Validate settings
         self.validate_settings(&settings)?;
 
-        // Serialize to JSON
-        let settings_json = serde_json::to_string(&settings)?;
+        // Merge over the stored JSON so unknown keys survive the save
+        let mut stored = match self.get_setting("app_settings").await? {
+            Some(json) => serde_json::from_str(&json).unwrap_or(Value::Object(Map::new())),
+            None => Value::Object(Map::new()),
+        };
+        merge_preserving_unknown(&mut stored, serde_json::to_value(&settings)?);
+        let settings_json = serde_json::to_string(&stored)?;
 
         let timestamp = current_timestamp();
 
@@ -117,6 +134,13 @@ impl SettingsService {
         Ok(row.map(|(value,)| value))
     }
 
+    /// Top-level settings sections that were reset to their defaults when the
+    /// settings were last loaded, because the stored value did not match the
+    /// expected schema
+    pub async fn invalid_sections(&self) -> Vec<String> {
+        self.invalid_sections.read().await.clone()
+    }
+
     /// Clear the settings cache
     pub async fn clear_cache(&self) {
         let mut cache = self.cache.write().await;
@@ -124,7 +148,11 @@ impl SettingsService {
     }
 
     /// Load settings from database or return defaults
-    async fn load_from_database(&self) -> Result<AppSettings, AppError> {
+    ///
+    /// # Returns
+    ///
+    /// The settings and the sections that fell back to their defaults
+    async fn load_from_database(&self) -> Result<(AppSettings, Vec<String>), AppError> {
         let row: Option<(String,)> =
             sqlx::query_as("SELECT value FROM settings WHERE key = 'app_settings'")
                 .fetch_optional(&self.pool)
@@ -132,12 +160,15 @@ impl SettingsService {
                 .map_err(|e| AppError::Database(format!("Failed to load settings: {}", e)))?;
 
         match row {
-            Some((json,)) => serde_json::from_str(&json).map_err(AppError::Serialization),
+            Some((json,)) => {
+                let value: Value = serde_json::from_str(&json)?;
+                deserialize_lenient(&value)
+            }
             None => {
                 // Return default settings and save them
                 let defaults = AppSettings::default();
                 self.save_defaults(&defaults).await?;
-                Ok(defaults)
+                Ok((defaults, Vec::new()))
             }
         }
     }
@@ -197,6 +228,18 @@ impl SettingsService {
             ));
         }
 
+        if settings.search_settings.pinned_bonus < 0.0 {
+            return Err(AppError::Validation(
+                "pinned_bonus must be non-negative".to_string(),
+            ));
+        }
+
+        if settings.search_settings.weight_app_context < 0.0 {
+            return Err(AppError::Validation(
+                "weight_app_context must be non-negative".to_string(),
+            ));
+        }
+
         // Ensure at least one weight is non-zero (otherwise all results would have score 0)
         if settings.search_settings.weight_text_relevance == 0.0
             && settings.search_settings.weight_usage_frequency == 0.0
@@ -223,6 +266,25 @@ impl SettingsService {
             }
         }
 
+        if settings.api_server.port < 1024 {
+            return Err(AppError::Validation(
+                "api_server port must be 1024 or higher".to_string(),
+            ));
+        }
+
+        if settings.source_refresh_settings.interval_hours == 0 {
+            return Err(AppError::Validation(
+                "source refresh interval_hours must be greater than 0".to_string(),
+            ));
+        }
+
+        let endpoint = settings.share_settings.endpoint.trim();
+        if !endpoint.starts_with("https://") && !endpoint.starts_with("http://") {
+            return Err(AppError::Validation(
+                "share endpoint must be an http or https URL".to_string(),
+            ));
+        }
+
         // Validate shortcuts are not empty
         if settings.global_shortcuts.quick_add.is_empty() {
             return Err(AppError::Validation(
@@ -240,6 +302,114 @@ impl SettingsService {
     }
 }
 
+/// File format of exported settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsFileFormat {
+    Json,
+    Toml,
+}
+
+impl SettingsFileFormat {
+    /// TOML for `.toml` files, JSON for anything else
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => Self::Toml,
+            _ => Self::Json,
+        }
+    }
+}
+
+/// Serializes settings for a settings file, leaving out credentials
+pub fn serialize_settings(
+    settings: &AppSettings,
+    format: SettingsFileFormat,
+) -> Result<String, AppError> {
+    let settings = settings.without_credentials();
+    match format {
+        SettingsFileFormat::Json => Ok(serde_json::to_string_pretty(&settings)?),
+        SettingsFileFormat::Toml => toml::to_string_pretty(&settings)
+            .map_err(|e| AppError::Unknown(format!("Failed to serialize settings: {}", e))),
+    }
+}
+
+/// Parses a settings file
+///
+/// Unlike settings loaded from the database, a section with an invalid
+/// value is an error rather than falling back to its default, so a typo in
+/// a hand-edited file is reported instead of silently ignored. Missing
+/// sections and fields get their defaults.
+pub fn parse_settings(text: &str, format: SettingsFileFormat) -> Result<AppSettings, AppError> {
+    let invalid =
+        |e: &dyn std::fmt::Display| AppError::InvalidInput(format!("Invalid settings file: {}", e));
+    let value: Value = match format {
+        SettingsFileFormat::Json => serde_json::from_str(text).map_err(|e| invalid(&e))?,
+        SettingsFileFormat::Toml => toml::from_str(text).map_err(|e| invalid(&e))?,
+    };
+    serde_json::from_value(value).map_err(|e| invalid(&e))
+}
+
+/// Recursively merges `update` into `stored`
+///
+/// Objects are merged key by key; any other value in `update` replaces the
+/// stored one. Keys that exist only in `stored` are left untouched.
+fn merge_preserving_unknown(stored: &mut Value, update: Value) {
+    match (stored, update) {
+        (Value::Object(stored), Value::Object(update)) => {
+            for (key, value) in update {
+                match stored.get_mut(&key) {
+                    Some(existing) => merge_preserving_unknown(existing, value),
+                    None => {
+                        stored.insert(key, value);
+                    }
+                }
+            }
+        }
+        (stored, update) => *stored = update,
+    }
+}
+
+/// Deserializes stored settings, validating each known section separately
+///
+/// Sections are merged over the defaults, so fields missing from the stored
+/// JSON get their default value. A section whose stored value does not match
+/// the expected schema falls back to its default instead of discarding every
+/// setting. Unknown keys are
+/// ignored here (and preserved on save by `merge_preserving_unknown`).
+///
+/// # Returns
+///
+/// The settings and the top-level keys that were rejected
+fn deserialize_lenient(value: &Value) -> Result<(AppSettings, Vec<String>), AppError> {
+    if let Ok(settings) = serde_json::from_value::<AppSettings>(value.clone()) {
+        return Ok((settings, Vec::new()));
+    }
+
+    let mut accepted = serde_json::to_value(AppSettings::default())?;
+    let mut rejected = Vec::new();
+
+    if let (Value::Object(stored), Value::Object(fields)) = (value, &mut accepted) {
+        for (key, section) in stored {
+            // Merge over the defaults so fields missing from a section keep their defaults
+            let mut candidate = fields.clone();
+            match candidate.get_mut(key) {
+                Some(existing) => merge_preserving_unknown(existing, section.clone()),
+                None => {
+                    candidate.insert(key.clone(), section.clone());
+                }
+            }
+
+            if serde_json::from_value::<AppSettings>(Value::Object(candidate.clone())).is_ok() {
+                *fields = candidate;
+            } else {
+                rejected.push(key.clone());
+            }
+        }
+    }
+
+    let settings = serde_json::from_value(accepted).unwrap_or_default();
+    Ok((settings, rejected))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -328,6 +498,18 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_validate_settings_api_server_port() {
+        let pool = setup_test_db().await;
+        let service = SettingsService::new(pool);
+
+        let mut settings = AppSettings::default();
+        settings.api_server.port = 80;
+
+        let result = service.update_settings(settings).await;
+        assert!(result.is_err());
+    }
+
     #[tokio::test]
     async fn test_update_individual_setting() {
         let pool = setup_test_db().await;
@@ -342,6 +524,116 @@ mod tests {
         assert_eq!(value, Some("custom_value".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_invalid_sections_are_reported() {
+        let pool = setup_test_db().await;
+        let service = SettingsService::new(pool);
+        service
+            .update_setting(
+                "app_settings",
+                r#"{"theme": "dark", "storage_type": 42}"#.to_string(),
+            )
+            .await
+            .unwrap();
+
+        let settings = service.get_settings().await.unwrap();
+        assert_eq!(settings.storage_type, StorageType::Local);
+        assert_eq!(service.invalid_sections().await, vec!["storage_type"]);
+    }
+
+    #[tokio::test]
+    async fn test_update_preserves_unknown_keys() {
+        let pool = setup_test_db().await;
+        let stored = serde_json::json!({
+            "theme": "dark",
+            "future_feature": { "enabled": true },
+            "search_settings": {
+                "max_results": 20,
+                "enable_fuzzy_search": true,
+                "search_in_tags": true,
+                "future_ranking": "semantic"
+            }
+        });
+        sqlx::query("INSERT INTO settings (key, value, updated_at) VALUES ('app_settings', ?, 0)")
+            .bind(stored.to_string())
+            .execute(&pool)
+            .await
+            .unwrap();
+        let service = SettingsService::new(pool);
+
+        let mut settings = service.get_settings().await.unwrap();
+        assert_eq!(settings.search_settings.max_results, 20);
+        settings.search_settings.max_results = 30;
+        service.update_settings(settings).await.unwrap();
+
+        let json = service.get_setting("app_settings").await.unwrap().unwrap();
+        let saved: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(saved["future_feature"]["enabled"], Value::Bool(true));
+        assert_eq!(saved["search_settings"]["future_ranking"], "semantic");
+        assert_eq!(saved["search_settings"]["max_results"], 30);
+        assert_eq!(saved["theme"], "dark");
+    }
+
+    #[test]
+    fn test_deserialize_lenient_rejects_invalid_section() {
+        let stored = serde_json::json!({
+            "theme": "dark",
+            "storage_type": 42,
+            "unknown": [1, 2, 3]
+        });
+
+        let (settings, rejected) = deserialize_lenient(&stored).unwrap();
+        assert_eq!(rejected, vec!["storage_type".to_string()]);
+        assert_eq!(settings.storage_type, StorageType::Local);
+        assert_eq!(settings.theme, crate::models::settings::Theme::Dark);
+    }
+
+    #[test]
+    fn test_merge_replaces_non_objects() {
+        let mut stored = serde_json::json!({ "a": { "b": 1, "keep": true }, "c": [1] });
+        merge_preserving_unknown(
+            &mut stored,
+            serde_json::json!({ "a": { "b": 2 }, "c": null }),
+        );
+        assert_eq!(
+            stored,
+            serde_json::json!({ "a": { "b": 2, "keep": true }, "c": null })
+        );
+    }
+
+    #[test]
+    fn test_settings_file_round_trip() {
+        let mut settings = AppSettings::default();
+        settings.search_settings.max_results = 120;
+        settings.gist_settings.access_token = Some("ghp_secret".to_string());
+
+        for format in [SettingsFileFormat::Json, SettingsFileFormat::Toml] {
+            let text = serialize_settings(&settings, format).unwrap();
+            assert!(!text.contains("ghp_secret"));
+
+            let parsed = parse_settings(&text, format).unwrap();
+            assert_eq!(parsed, settings.without_credentials());
+        }
+    }
+
+    #[test]
+    fn test_parse_settings_rejects_invalid_values() {
+        let result = parse_settings(
+            r#"{"theme": "purple"}"#,
+            SettingsFileFormat::from_path(Path::new("settings.json")),
+        );
+        assert!(matches!(result, Err(AppError::InvalidInput(_))));
+
+        // Missing sections get their defaults
+        let parsed = parse_settings(
+            "theme = \"dark\"\n",
+            SettingsFileFormat::from_path(Path::new("snips.TOML")),
+        )
+        .unwrap();
+        assert_eq!(parsed.theme, crate::models::settings::Theme::Dark);
+        assert_eq!(parsed.search_settings.max_results, 50);
+    }
+
     #[tokio::test]
     async fn test_clear_cache() {
         let pool = setup_test_db().await;
@@ -0,0 +1,223 @@
+/// End-to-end encrypted snippet sharing through a paste service.
+///
+/// A shared snippet is encrypted with a fresh AES-256-GCM key before it
+/// leaves the machine. Only the ciphertext is uploaded; the key goes in the
+/// link's fragment, which browsers and HTTP clients never send to the server.
+/// Importing a share deletes the paste, so each link works once.
+use crate::commands::storage_commands::SnippetExport;
+use crate::services::gist;
+use crate::utils::error::AppError;
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+
+/// First line of every uploaded share, naming the format
+const SHARE_HEADER: &str = "snips-share-v1";
+const KEY_BYTES: usize = 32;
+const NONCE_BYTES: usize = 12;
+
+/// Longest paste URL accepted from the endpoint
+const MAX_URL_LEN: usize = 2048;
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], AppError> {
+    let mut bytes = [0u8; N];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| AppError::Unknown(format!("Failed to generate share key: {}", e)))?;
+    Ok(bytes)
+}
+
+fn cipher(key: &[u8; KEY_BYTES]) -> Result<Aes256Gcm, AppError> {
+    Aes256Gcm::new_from_slice(key)
+        .map_err(|e| AppError::Unknown(format!("Invalid share key: {}", e)))
+}
+
+/// Encrypts a snippet under `key`
+///
+/// # Returns
+///
+/// The text to upload: the format header, then the hex-encoded nonce and
+/// ciphertext
+pub fn encrypt_share(snippet: &SnippetExport, key: &[u8; KEY_BYTES]) -> Result<String, AppError> {
+    let nonce = random_bytes::<NONCE_BYTES>()?;
+    let plaintext = serde_json::to_vec(snippet)?;
+    let ciphertext = cipher(key)?
+        .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+        .map_err(|_| AppError::Unknown("Failed to encrypt snippet".to_string()))?;
+    Ok(format!(
+        "{}\n{}{}",
+        SHARE_HEADER,
+        hex::encode(nonce),
+        hex::encode(ciphertext)
+    ))
+}
+
+/// Decrypts a share downloaded from a paste
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidInput` if the text is not a share or the key
+/// does not open it, e.g. because the link was cut short or tampered with
+pub fn decrypt_share(text: &str, key: &[u8; KEY_BYTES]) -> Result<SnippetExport, AppError> {
+    let invalid = || AppError::InvalidInput("Not a Snips share, or the key is wrong".to_string());
+    let encoded = text
+        .trim()
+        .strip_prefix(SHARE_HEADER)
+        .ok_or_else(invalid)?
+        .trim();
+    let bytes = hex::decode(encoded).map_err(|_| invalid())?;
+    if bytes.len() <= NONCE_BYTES {
+        return Err(invalid());
+    }
+    let (nonce, ciphertext) = bytes.split_at(NONCE_BYTES);
+    let plaintext = cipher(key)?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| invalid())?;
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+/// The link to hand out: the paste URL with the key as its fragment
+pub fn share_url(paste_url: &str, key: &[u8; KEY_BYTES]) -> String {
+    format!("{}#{}", paste_url.trim(), hex::encode(key))
+}
+
+/// Splits a share link into the paste URL and the key
+///
+/// # Errors
+///
+/// Returns `AppError::InvalidInput` if the link is not https or has no key in
+/// its fragment
+pub fn parse_share_url(url: &str) -> Result<(String, [u8; KEY_BYTES]), AppError> {
+    let invalid = || AppError::InvalidInput(format!("Not a Snips share link: {}", url));
+    let (paste_url, fragment) = url.trim().split_once('#').ok_or_else(invalid)?;
+    if !paste_url.starts_with("https://") {
+        return Err(invalid());
+    }
+    let key: [u8; KEY_BYTES] = hex::decode(fragment)
+        .ok()
+        .and_then(|key| key.try_into().ok())
+        .ok_or_else(invalid)?;
+    Ok((paste_url.to_string(), key))
+}
+
+/// Encrypts a snippet, uploads it to `endpoint` and returns the share link
+///
+/// # Errors
+///
+/// * `AppError::InvalidInput` - The endpoint is not an https URL
+/// * `AppError::External` - The upload fails or the endpoint does not answer
+///   with an https URL
+pub async fn share(endpoint: &str, snippet: &SnippetExport) -> Result<String, AppError> {
+    let endpoint = endpoint.trim();
+    if !endpoint.starts_with("https://") {
+        return Err(AppError::InvalidInput(format!(
+            "Share endpoint must be an https URL: {}",
+            endpoint
+        )));
+    }
+    let key = random_bytes::<KEY_BYTES>()?;
+    let body = encrypt_share(snippet, &key)?;
+
+    let headers = ["Content-Type: text/plain; charset=utf-8"];
+    let (status, response) = gist::send_request("POST", endpoint, &headers, Some(&body)).await?;
+    let paste_url = response.trim();
+    let is_url = paste_url.starts_with("https://");
+    if !(200..300).contains(&status) || !is_url || paste_url.len() > MAX_URL_LEN {
+        return Err(AppError::External(format!(
+            "Share endpoint {} rejected the upload ({})",
+            endpoint, status
+        )));
+    }
+    Ok(share_url(paste_url, &key))
+}
+
+/// Downloads and decrypts a share, then deletes the paste
+///
+/// The snippet is returned even if the paste cannot be deleted, as not every
+/// paste service allows it.
+pub async fn receive(url: &str) -> Result<SnippetExport, AppError> {
+    let (paste_url, key) = parse_share_url(url)?;
+    let (status, body) = gist::send_request("GET", &paste_url, &[], None).await?;
+    match status {
+        200 => {}
+        404 | 410 => {
+            return Err(AppError::NotFound(
+                "This share has already been opened or has expired".to_string(),
+            ))
+        }
+        _ => {
+            return Err(AppError::External(format!(
+                "Failed to download share ({})",
+                status
+            )))
+        }
+    }
+    let snippet = decrypt_share(&body, &key)?;
+
+    match gist::send_request("DELETE", &paste_url, &[], None).await {
+        Ok((status, _)) if (200..300).contains(&status) => {}
+        Ok((status, _)) => eprintln!("Paste service kept the share ({})", status),
+        Err(e) => eprintln!("Failed to delete the share: {}", e),
+    }
+    Ok(snippet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet() -> SnippetExport {
+        SnippetExport {
+            name: "deploy".to_string(),
+            content: "kubectl rollout restart deploy/api".to_string(),
+            description: Some("Restart the API".to_string()),
+            tags: vec!["k8s".to_string()],
+            created_at: 1_700_000_000,
+            updated_at: 1_700_000_100,
+            ..SnippetExport::default()
+        }
+    }
+
+    #[test]
+    fn test_share_round_trip() {
+        let key = [7u8; KEY_BYTES];
+        let text = encrypt_share(&snippet(), &key).unwrap();
+        assert!(text.starts_with(SHARE_HEADER));
+        assert!(!text.contains("kubectl"));
+
+        let received = decrypt_share(&text, &key).unwrap();
+        assert_eq!(received.content, snippet().content);
+        assert_eq!(received.tags, ["k8s"]);
+    }
+
+    #[test]
+    fn test_decrypt_share_rejects_wrong_key_and_tampering() {
+        let key = [7u8; KEY_BYTES];
+        let text = encrypt_share(&snippet(), &key).unwrap();
+        assert!(decrypt_share(&text, &[8u8; KEY_BYTES]).is_err());
+
+        let last = text.chars().last().unwrap();
+        let flipped = if last == '0' { '1' } else { '0' };
+        let tampered = format!("{}{}", &text[..text.len() - 1], flipped);
+        assert!(decrypt_share(&tampered, &key).is_err());
+        assert!(decrypt_share("hello", &key).is_err());
+    }
+
+    #[test]
+    fn test_share_url_round_trip() {
+        let key = [0xabu8; KEY_BYTES];
+        let url = share_url("https://paste.rs/Xyz\n", &key);
+        assert_eq!(
+            url,
+            format!("https://paste.rs/Xyz#{}", "ab".repeat(KEY_BYTES))
+        );
+        assert_eq!(
+            parse_share_url(&url).unwrap(),
+            ("https://paste.rs/Xyz".to_string(), key)
+        );
+
+        assert!(parse_share_url("https://paste.rs/Xyz").is_err());
+        assert!(parse_share_url("https://paste.rs/Xyz#abcd").is_err());
+        assert!(parse_share_url("file:///etc/passwd#00").is_err());
+        let plain = format!("http://paste.rs/Xyz#{}", "ab".repeat(KEY_BYTES));
+        assert!(parse_share_url(&plain).is_err());
+    }
+}
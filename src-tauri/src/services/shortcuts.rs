@@ -5,7 +5,10 @@
 use tauri::AppHandle;
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
+use crate::commands::analytics_commands;
 use crate::models::settings::GlobalShortcuts;
+use crate::services::notifications::{self, NotificationKind};
+use crate::services::snippet_slots::MAX_SLOT;
 use crate::services::window;
 
 /// The default keyboard shortcut for opening the search overlay.
@@ -16,6 +19,14 @@ pub const SHORTCUT_SEARCH: &str = "CmdOrCtrl+Shift+S";
 /// macOS: Cmd+Shift+A, Windows/Linux: Ctrl+Shift+A
 pub const SHORTCUT_QUICK_ADD: &str = "CmdOrCtrl+Shift+A";
 
+/// The default keyboard shortcut for copying the most recently used snippet.
+/// macOS: Cmd+Shift+L, Windows/Linux: Ctrl+Shift+L
+pub const SHORTCUT_QUICK_PASTE: &str = "CmdOrCtrl+Shift+L";
+
+/// Modifiers for the numbered slot shortcuts; the slot's digit is appended.
+/// macOS: Cmd+Shift+1..9, Windows/Linux: Ctrl+Shift+1..9
+pub const SHORTCUT_SLOT_MODIFIERS: &str = "CmdOrCtrl+Shift";
+
 /// Represents an error that occurred while working with global shortcuts.
 #[derive(Debug, thiserror::Error)]
 pub enum ShortcutError {
@@ -46,6 +57,7 @@ impl From<tauri::Error> for ShortcutError {
 /// This function registers:
 /// - Search shortcut (Cmd/Ctrl+Shift+S) - Opens the search overlay
 /// - Quick add shortcut (Cmd/Ctrl+Shift+A) - Opens the quick add dialog
+/// - Quick paste shortcut (Cmd/Ctrl+Shift+L) - Copies the last used snippet
 ///
 /// # Arguments
 ///
@@ -85,6 +97,11 @@ pub fn register_all_shortcuts(app: &AppHandle) -> Result<(), ShortcutError> {
         }
     }
 
+    // Register quick paste shortcut
+    if let Err(e) = register_quick_paste_shortcut_with_key(app, SHORTCUT_QUICK_PASTE) {
+        eprintln!("Warning: Failed to register quick paste shortcut: {}", e);
+    }
+
     Ok(())
 }
 
@@ -118,6 +135,11 @@ pub fn register_shortcuts_from_settings(
     // Register quick add shortcut with custom key combination
     register_quick_add_shortcut_with_key(app, &shortcuts.quick_add)?;
 
+    // Quick paste is optional; an empty key combination disables it
+    if !shortcuts.quick_paste.trim().is_empty() {
+        register_quick_paste_shortcut_with_key(app, &shortcuts.quick_paste)?;
+    }
+
     Ok(())
 }
 
@@ -195,6 +217,102 @@ pub fn register_quick_add_shortcut_with_key(
     Ok(())
 }
 
+/// Registers the quick paste shortcut with a custom key combination.
+///
+/// Pressing it copies the most recently used snippet to the clipboard
+/// without opening any window.
+///
+/// # Arguments
+///
+/// * `app` - The Tauri application handle
+/// * `shortcut_str` - The shortcut string (e.g., "Cmd+Shift+L")
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the shortcut was registered successfully.
+///
+/// # Errors
+///
+/// Returns `ShortcutError` if registration fails.
+pub fn register_quick_paste_shortcut_with_key(
+    app: &AppHandle,
+    shortcut_str: &str,
+) -> Result<(), ShortcutError> {
+    register_custom_shortcut(app, shortcut_str, copy_last_used_snippet)
+}
+
+/// Copies the most recently used snippet in the background
+pub fn copy_last_used_snippet(app: &AppHandle) {
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match analytics_commands::copy_last_used_snippet(app_handle.clone()).await {
+            Ok(Some(_)) => notifications::notify(
+                &app_handle,
+                NotificationKind::SnippetCopied,
+                "The last used snippet is on the clipboard",
+            ),
+            Ok(None) => eprintln!("Quick paste: no snippet has been used yet"),
+            Err(e) => eprintln!("Failed to copy last used snippet from shortcut: {}", e),
+        }
+    });
+}
+
+/// Returns the shortcut string for a numbered slot (e.g. "CmdOrCtrl+Shift+1").
+pub fn slot_shortcut(slot: u8) -> String {
+    format!("{}+{}", SHORTCUT_SLOT_MODIFIERS, slot)
+}
+
+/// Registers shortcuts for the assigned numbered slots.
+///
+/// Slot shortcuts are registered only while a snippet is assigned, so
+/// unused digits stay free for other applications. Any previously registered
+/// slot shortcut is released first; call this again whenever assignments
+/// change.
+///
+/// # Arguments
+///
+/// * `app` - The Tauri application handle
+/// * `slots` - Slot numbers that currently have a snippet
+///
+/// # Errors
+///
+/// Returns `ShortcutError` if a slot shortcut cannot be registered.
+pub fn register_slot_shortcuts(app: &AppHandle, slots: &[u8]) -> Result<(), ShortcutError> {
+    for slot in 1..=MAX_SLOT {
+        let shortcut_str = slot_shortcut(slot);
+        let shortcut = shortcut_str
+            .parse::<Shortcut>()
+            .map_err(|e| ShortcutError::InvalidFormat(format!("{}: {}", shortcut_str, e)))?;
+        if app.global_shortcut().is_registered(shortcut) {
+            unregister_shortcut(app, &shortcut_str)?;
+        }
+    }
+
+    for &slot in slots {
+        register_custom_shortcut(app, &slot_shortcut(slot), move |app| {
+            copy_slot_snippet(app, slot)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Copies the snippet assigned to a slot in the background
+pub fn copy_slot_snippet(app: &AppHandle, slot: u8) {
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match analytics_commands::copy_slot_snippet(app_handle.clone(), slot).await {
+            Ok(Some(_)) => notifications::notify(
+                &app_handle,
+                NotificationKind::SnippetCopied,
+                format!("The snippet in slot {} is on the clipboard", slot),
+            ),
+            Ok(None) => eprintln!("Slot {}: no snippet assigned", slot),
+            Err(e) => eprintln!("Failed to copy slot {} snippet from shortcut: {}", slot, e),
+        }
+    });
+}
+
 /// Registers the search overlay shortcut (Cmd/Ctrl+Shift+S).
 ///
 /// # Arguments
@@ -271,6 +389,144 @@ pub fn register_quick_add_shortcut(app: &AppHandle) -> Result<(), ShortcutError>
     Ok(())
 }
 
+/// A shortcut broken into canonical parts for display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortcutParts {
+    /// Canonical modifier names ("CommandOrControl", "Super", "Control",
+    /// "Alt", "Shift") in that order
+    pub modifiers: Vec<&'static str>,
+    /// The non-modifier key, e.g. "S", "1" or "F5"
+    pub key: String,
+    /// Platform display string, e.g. "⇧⌘S" on macOS or "Ctrl+Shift+S" elsewhere
+    pub display: String,
+}
+
+/// Canonical modifier names, in the order they are listed.
+const MODIFIER_ORDER: [&str; 5] = ["CommandOrControl", "Super", "Control", "Alt", "Shift"];
+
+fn canonical_modifier(token: &str) -> Option<&'static str> {
+    match token.to_ascii_lowercase().as_str() {
+        "cmdorctrl" | "cmdorcontrol" | "commandorctrl" | "commandorcontrol" => {
+            Some("CommandOrControl")
+        }
+        "cmd" | "command" | "super" | "meta" => Some("Super"),
+        "ctrl" | "control" => Some("Control"),
+        "alt" | "option" => Some("Alt"),
+        "shift" => Some("Shift"),
+        _ => None,
+    }
+}
+
+/// Splits a shortcut string into modifiers, key and a display string.
+///
+/// # Arguments
+///
+/// * `shortcut_str` - The shortcut string (e.g., "CmdOrCtrl+Shift+S")
+/// * `macos` - Whether to use macOS symbols (⌃⌥⇧⌘) for the display string
+///
+/// # Returns
+///
+/// `None` if the shortcut does not parse.
+pub fn describe_shortcut(shortcut_str: &str, macos: bool) -> Option<ShortcutParts> {
+    shortcut_str.parse::<Shortcut>().ok()?;
+
+    let mut tokens: Vec<&str> = shortcut_str.split('+').map(str::trim).collect();
+    let key = tokens.pop()?;
+    let mut modifiers: Vec<&'static str> = tokens
+        .into_iter()
+        .map(canonical_modifier)
+        .collect::<Option<_>>()?;
+    modifiers.sort_by_key(|m| MODIFIER_ORDER.iter().position(|o| o == m));
+    modifiers.dedup();
+
+    let key = key
+        .strip_prefix("Key")
+        .or_else(|| key.strip_prefix("Digit"))
+        .filter(|rest| rest.len() == 1)
+        .unwrap_or(key);
+    let key = if key.chars().count() == 1 {
+        key.to_uppercase()
+    } else {
+        key.to_string()
+    };
+
+    let display = if macos {
+        // Apple's order: Control, Option, Shift, Command
+        let mut symbols = String::new();
+        for (name, symbol) in [("Control", "⌃"), ("Alt", "⌥"), ("Shift", "⇧")] {
+            if modifiers.contains(&name) {
+                symbols.push_str(symbol);
+            }
+        }
+        if modifiers.contains(&"CommandOrControl") || modifiers.contains(&"Super") {
+            symbols.push('⌘');
+        }
+        format!("{}{}", symbols, key)
+    } else {
+        let mut names: Vec<&str> = modifiers
+            .iter()
+            .map(|m| match *m {
+                "CommandOrControl" | "Control" => "Ctrl",
+                other => other,
+            })
+            .collect();
+        names.dedup();
+        names.push(&key);
+        names.join("+")
+    };
+
+    Some(ShortcutParts {
+        modifiers,
+        key,
+        display,
+    })
+}
+
+/// Whether a shortcut can be registered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShortcutAvailability {
+    /// Nothing holds the shortcut
+    Available,
+    /// Snips itself already registered it
+    RegisteredBySnips,
+    /// The trial registration failed, usually because another application
+    /// holds the shortcut (the reason is the platform's error)
+    Taken(String),
+}
+
+/// Checks whether a shortcut is free by trying to register it.
+///
+/// A successful trial registration is released immediately, so nothing
+/// changes. Platforms without global shortcut support report every
+/// shortcut as taken.
+///
+/// # Errors
+///
+/// Returns `ShortcutError::InvalidFormat` if the shortcut does not parse.
+pub fn probe_availability(
+    app: &AppHandle,
+    shortcut_str: &str,
+) -> Result<ShortcutAvailability, ShortcutError> {
+    let shortcut = shortcut_str
+        .parse::<Shortcut>()
+        .map_err(|e| ShortcutError::InvalidFormat(format!("{}: {}", shortcut_str, e)))?;
+
+    let global_shortcut = app.global_shortcut();
+    if global_shortcut.is_registered(shortcut) {
+        return Ok(ShortcutAvailability::RegisteredBySnips);
+    }
+
+    match global_shortcut.register(shortcut) {
+        Ok(()) => {
+            global_shortcut.unregister(shortcut).map_err(|e| {
+                ShortcutError::UnregistrationFailed(shortcut_str.to_string(), e.to_string())
+            })?;
+            Ok(ShortcutAvailability::Available)
+        }
+        Err(e) => Ok(ShortcutAvailability::Taken(e.to_string())),
+    }
+}
+
 /// Registers a custom shortcut with a callback.
 ///
 /// This function provides a flexible way to register custom shortcuts
@@ -378,6 +634,36 @@ mod tests {
     fn test_shortcut_constants() {
         assert_eq!(SHORTCUT_SEARCH, "CmdOrCtrl+Shift+S");
         assert_eq!(SHORTCUT_QUICK_ADD, "CmdOrCtrl+Shift+A");
+        assert_eq!(SHORTCUT_QUICK_PASTE, "CmdOrCtrl+Shift+L");
+    }
+
+    #[test]
+    fn test_slot_shortcuts_parse() {
+        assert_eq!(slot_shortcut(1), "CmdOrCtrl+Shift+1");
+        for slot in 1..=MAX_SLOT {
+            assert!(slot_shortcut(slot).parse::<Shortcut>().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_describe_shortcut() {
+        let parts = describe_shortcut("CmdOrCtrl+Shift+S", false).unwrap();
+        assert_eq!(parts.modifiers, vec!["CommandOrControl", "Shift"]);
+        assert_eq!(parts.key, "S");
+        assert_eq!(parts.display, "Ctrl+Shift+S");
+
+        let parts = describe_shortcut("Shift+Alt+Control+KeyK", true).unwrap();
+        assert_eq!(parts.modifiers, vec!["Control", "Alt", "Shift"]);
+        assert_eq!(parts.display, "⌃⌥⇧K");
+
+        let parts = describe_shortcut("Shift+CommandOrControl+Digit1", true).unwrap();
+        assert_eq!(parts.display, "⇧⌘1");
+
+        assert_eq!(
+            describe_shortcut("Alt+F5", false).unwrap().display,
+            "Alt+F5"
+        );
+        assert!(describe_shortcut("NotAShortcut", false).is_none());
     }
 
     #[test]
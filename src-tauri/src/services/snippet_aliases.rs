@@ -0,0 +1,221 @@
+/// Search aliases of snippets.
+///
+/// An alias is a short keyword such as `k8s` that should bring up a snippet
+/// even though its name and content do not contain it. Aliases are stored as
+/// a JSON array in the `aliases` column and indexed by search, which weighs
+/// alias matches above matches in names and content.
+use crate::utils::error::AppError;
+use sqlx::SqlitePool;
+
+/// Most aliases a snippet can have
+pub const MAX_ALIASES: usize = 20;
+
+/// Longest alias, in characters
+pub const MAX_ALIAS_CHARS: usize = 50;
+
+/// Trims aliases and drops blank ones and duplicates
+///
+/// Duplicates are found ignoring case; the first spelling is kept.
+///
+/// # Errors
+///
+/// Returns `AppError::Validation` if an alias is too long or there are too many
+pub fn normalize_aliases(aliases: &[String]) -> Result<Vec<String>, AppError> {
+    let mut normalized: Vec<String> = Vec::new();
+    for alias in aliases {
+        let alias = alias.trim();
+        if alias.is_empty() {
+            continue;
+        }
+        if alias.chars().count() > MAX_ALIAS_CHARS {
+            return Err(AppError::Validation(format!(
+                "Alias \"{}\" is longer than {} characters",
+                alias, MAX_ALIAS_CHARS
+            )));
+        }
+        if !normalized.iter().any(|a| a.eq_ignore_ascii_case(alias)) {
+            normalized.push(alias.to_string());
+        }
+    }
+
+    if normalized.len() > MAX_ALIASES {
+        return Err(AppError::Validation(format!(
+            "A snippet can have at most {} aliases",
+            MAX_ALIASES
+        )));
+    }
+    Ok(normalized)
+}
+
+/// Loads the aliases of a snippet
+///
+/// # Errors
+///
+/// * `AppError::NotFound` - The snippet does not exist
+pub async fn get_aliases(pool: &SqlitePool, snippet_id: i64) -> Result<Vec<String>, AppError> {
+    let stored: Option<Option<String>> =
+        sqlx::query_scalar("SELECT aliases FROM snippets WHERE id = ?")
+            .bind(snippet_id)
+            .fetch_optional(pool)
+            .await?;
+    let stored = stored
+        .ok_or_else(|| AppError::NotFound(format!("Snippet with id {} not found", snippet_id)))?;
+
+    match stored {
+        Some(json) => Ok(serde_json::from_str(&json)?),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Replaces the aliases of a snippet
+///
+/// Like pinning, this does not change `updated_at`. An empty list removes
+/// every alias.
+///
+/// # Returns
+///
+/// The aliases as stored, after `normalize_aliases`
+///
+/// # Errors
+///
+/// * `AppError::Validation` - An alias is too long or there are too many
+/// * `AppError::NotFound` - The snippet does not exist
+pub async fn set_aliases(
+    pool: &SqlitePool,
+    snippet_id: i64,
+    aliases: &[String],
+) -> Result<Vec<String>, AppError> {
+    let aliases = normalize_aliases(aliases)?;
+    let json = if aliases.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&aliases)?)
+    };
+
+    let result = sqlx::query("UPDATE snippets SET aliases = ? WHERE id = ?")
+        .bind(json)
+        .bind(snippet_id)
+        .execute(pool)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!(
+            "Snippet with id {} not found",
+            snippet_id
+        )));
+    }
+    Ok(aliases)
+}
+
+/// Adds one alias to a snippet; adding one it already has changes nothing
+///
+/// # Returns
+///
+/// All aliases of the snippet
+pub async fn add_alias(
+    pool: &SqlitePool,
+    snippet_id: i64,
+    alias: &str,
+) -> Result<Vec<String>, AppError> {
+    let mut aliases = get_aliases(pool, snippet_id).await?;
+    if alias.trim().is_empty() {
+        return Err(AppError::Validation("Alias cannot be empty".to_string()));
+    }
+    aliases.push(alias.to_string());
+    set_aliases(pool, snippet_id, &aliases).await
+}
+
+/// Removes one alias from a snippet, ignoring case
+///
+/// # Returns
+///
+/// The remaining aliases of the snippet
+pub async fn remove_alias(
+    pool: &SqlitePool,
+    snippet_id: i64,
+    alias: &str,
+) -> Result<Vec<String>, AppError> {
+    let mut aliases = get_aliases(pool, snippet_id).await?;
+    aliases.retain(|a| !a.eq_ignore_ascii_case(alias.trim()));
+    set_aliases(pool, snippet_id, &aliases).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::run_migrations;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_normalize_aliases() {
+        assert_eq!(
+            normalize_aliases(&strings(&[" k8s ", "", "kube", "K8S"])).unwrap(),
+            strings(&["k8s", "kube"])
+        );
+        assert!(matches!(
+            normalize_aliases(&strings(&[&"x".repeat(MAX_ALIAS_CHARS + 1)])),
+            Err(AppError::Validation(_))
+        ));
+        let many: Vec<String> = (0..=MAX_ALIASES).map(|i| format!("a{}", i)).collect();
+        assert!(matches!(
+            normalize_aliases(&many),
+            Err(AppError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_manage_aliases() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+        sqlx::query(
+            "INSERT INTO snippets (id, name, content, created_at, updated_at)
+             VALUES (1, 'kubernetes rollout', 'kubectl rollout restart', 0, 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert!(get_aliases(&pool, 1).await.unwrap().is_empty());
+        add_alias(&pool, 1, "k8s").await.unwrap();
+        let aliases = add_alias(&pool, 1, "kro").await.unwrap();
+        assert_eq!(aliases, strings(&["k8s", "kro"]));
+        assert_eq!(add_alias(&pool, 1, "KRO").await.unwrap(), aliases);
+
+        // The search index picks up aliases
+        let found: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM snippets_fts WHERE snippets_fts MATCH 'aliases : k8s'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(found, 1);
+
+        assert_eq!(
+            remove_alias(&pool, 1, "K8s").await.unwrap(),
+            strings(&["kro"])
+        );
+        assert!(set_aliases(&pool, 1, &[]).await.unwrap().is_empty());
+        let stored: Option<String> =
+            sqlx::query_scalar("SELECT aliases FROM snippets WHERE id = 1")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(stored, None);
+
+        assert!(matches!(
+            get_aliases(&pool, 9).await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            add_alias(&pool, 1, "  ").await,
+            Err(AppError::Validation(_))
+        ));
+    }
+}
@@ -0,0 +1,398 @@
+use crate::models::settings::SortPreference;
+use crate::models::{Snippet, SnippetId, SnippetKind};
+use crate::utils::error::AppError;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Row, SqlitePool};
+use std::collections::{HashMap, HashSet};
+
+/// `ORDER BY` clause for the snippet list, over the `snippets` table
+///
+/// Every order ends with `created_at DESC, id DESC` so ties (and snippets
+/// imported without a position) stay stable.
+pub fn order_by_clause(preference: SortPreference) -> &'static str {
+    match preference {
+        SortPreference::Name => "name COLLATE NOCASE ASC, created_at DESC, id DESC",
+        SortPreference::Created => "created_at DESC, id DESC",
+        SortPreference::Updated => "updated_at DESC, created_at DESC, id DESC",
+        SortPreference::Usage => {
+            "(SELECT COUNT(*) FROM analytics a WHERE a.snippet_id = snippets.id) DESC,
+             created_at DESC, id DESC"
+        }
+        SortPreference::Manual => "position ASC, created_at DESC, id DESC",
+    }
+}
+
+/// Loads a page of the snippet list with each snippet's tags
+///
+/// Snippets and tags come back in one query: the page is selected first and
+/// then joined with its tags, so a large library costs one round trip
+/// instead of one tag query per snippet.
+///
+/// # Arguments
+///
+/// * `pool` - Database pool
+/// * `preference` - Order of the list
+/// * `include_archived` - Whether archived snippets are listed too
+/// * `limit` - Maximum number of snippets, or `None` for all of them
+/// * `offset` - Number of snippets to skip
+///
+/// # Returns
+///
+/// The snippets in list order, with tags sorted alphabetically
+pub async fn list_snippets(
+    pool: &SqlitePool,
+    preference: SortPreference,
+    include_archived: bool,
+    limit: Option<i64>,
+    offset: i64,
+) -> Result<Vec<Snippet>, AppError> {
+    let rows = sqlx::query(&format!(
+        "WITH page AS (
+            SELECT id, name, content, description, created_at, updated_at, pinned, kind,
+                   language, archived, expires_at, ROW_NUMBER() OVER (ORDER BY {}) AS list_position
+            FROM snippets
+            WHERE ? OR archived = 0
+            ORDER BY list_position
+            LIMIT ? OFFSET ?
+        )
+        SELECT page.id, page.name, page.content, page.description, page.created_at,
+               page.updated_at, page.pinned, page.kind, page.language, page.archived,
+               page.expires_at, json_group_array(t.name)
+        FROM page
+        LEFT JOIN snippet_tags st ON st.snippet_id = page.id
+        LEFT JOIN tags t ON t.id = st.tag_id
+        GROUP BY page.id
+        ORDER BY page.list_position",
+        order_by_clause(preference)
+    ))
+    .bind(include_archived)
+    // SQLite treats a negative limit as no limit
+    .bind(limit.unwrap_or(-1))
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    rows.iter().map(snippet_from_row).collect()
+}
+
+/// Loads snippets by ID with their tags in one query
+///
+/// Used wherever whole snippets are needed by ID, so fetching several costs
+/// one round trip rather than a snippet and a tag query for each.
+///
+/// # Arguments
+///
+/// * `pool` - Database pool
+/// * `ids` - Snippets to load; may repeat
+///
+/// # Returns
+///
+/// The snippets in the order of `ids`, repeated where an ID is. IDs that do
+/// not exist are skipped.
+pub async fn load_snippets(pool: &SqlitePool, ids: &[i64]) -> Result<Vec<Snippet>, AppError> {
+    let unique: Vec<i64> = {
+        let mut seen = HashSet::new();
+        ids.iter().copied().filter(|id| seen.insert(*id)).collect()
+    };
+    if unique.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let sql = format!(
+        "SELECT s.id, s.name, s.content, s.description, s.created_at, s.updated_at, s.pinned,
+                s.kind, s.language, s.archived, s.expires_at, json_group_array(t.name)
+         FROM snippets s
+         LEFT JOIN snippet_tags st ON st.snippet_id = s.id
+         LEFT JOIN tags t ON t.id = st.tag_id
+         WHERE s.id IN ({})
+         GROUP BY s.id",
+        vec!["?"; unique.len()].join(", ")
+    );
+    let mut query = sqlx::query(&sql);
+    for id in &unique {
+        query = query.bind(id);
+    }
+
+    let mut by_id = HashMap::with_capacity(unique.len());
+    for row in query.fetch_all(pool).await? {
+        let snippet = snippet_from_row(&row)?;
+        by_id.insert(snippet.id.0, snippet);
+    }
+
+    Ok(ids.iter().filter_map(|id| by_id.get(id).cloned()).collect())
+}
+
+/// Builds a snippet from a row of the columns selected by `list_snippets`
+fn snippet_from_row(row: &SqliteRow) -> Result<Snippet, AppError> {
+    // A snippet without tags yields `[null]` from the outer join
+    let tags: Vec<Option<String>> = serde_json::from_str(row.get(11))?;
+    let mut tags: Vec<String> = tags.into_iter().flatten().collect();
+    tags.sort();
+
+    Ok(Snippet {
+        id: SnippetId(row.get(0)),
+        name: row.get(1),
+        content: row.get(2),
+        description: row.get(3),
+        created_at: row.get(4),
+        updated_at: row.get(5),
+        tags: Some(tags),
+        pinned: row.get(6),
+        kind: SnippetKind::from_db(row.get(7)),
+        language: row.get(8),
+        archived: row.get(9),
+        expires_at: row.get(10),
+    })
+}
+
+/// Stores a manual order
+///
+/// Listed snippets take the first positions in the given order; any snippet
+/// not listed keeps its relative order after them, so the frontend may send
+/// just the visible (e.g. filtered) subset.
+///
+/// # Errors
+///
+/// Returns `AppError::NotFound` if an ID does not exist, or
+/// `AppError::InvalidInput` if an ID is listed twice
+pub async fn reorder(pool: &SqlitePool, ids: &[i64]) -> Result<(), AppError> {
+    let mut seen = HashSet::new();
+    if let Some(duplicate) = ids.iter().find(|id| !seen.insert(**id)) {
+        return Err(AppError::InvalidInput(format!(
+            "Snippet {} is listed more than once",
+            duplicate
+        )));
+    }
+
+    let mut tx = pool.begin().await?;
+    let current: Vec<i64> = sqlx::query_scalar(&format!(
+        "SELECT id FROM snippets ORDER BY {}",
+        order_by_clause(SortPreference::Manual)
+    ))
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let existing: HashSet<i64> = current.iter().copied().collect();
+    if let Some(missing) = ids.iter().find(|id| !existing.contains(id)) {
+        return Err(AppError::NotFound(format!(
+            "Snippet with id {} not found",
+            missing
+        )));
+    }
+
+    let rest = current.into_iter().filter(|id| !seen.contains(id));
+    for (position, id) in ids.iter().copied().chain(rest).enumerate() {
+        sqlx::query("UPDATE snippets SET position = ? WHERE id = ?")
+            .bind(position as i64)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        for statement in [
+            "CREATE TABLE snippets (id INTEGER PRIMARY KEY, name TEXT, created_at INTEGER,
+                updated_at INTEGER, position INTEGER NOT NULL DEFAULT 0)",
+            "CREATE TABLE analytics (id INTEGER PRIMARY KEY, snippet_id INTEGER, used_at INTEGER)",
+            "INSERT INTO snippets VALUES (1, 'beta', 10, 40, 2), (2, 'Alpha', 20, 30, 1),
+                (3, 'gamma', 30, 20, 0)",
+            "INSERT INTO analytics (snippet_id, used_at) VALUES (1, 1), (1, 2), (2, 3)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        pool
+    }
+
+    /// Adds the remaining snippet columns and some tags
+    async fn setup_tagged_db() -> SqlitePool {
+        let pool = setup_test_db().await;
+        for statement in [
+            "ALTER TABLE snippets ADD COLUMN content TEXT NOT NULL DEFAULT 'body'",
+            "ALTER TABLE snippets ADD COLUMN description TEXT",
+            "ALTER TABLE snippets ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0",
+            "ALTER TABLE snippets ADD COLUMN kind TEXT NOT NULL DEFAULT 'static'",
+            "ALTER TABLE snippets ADD COLUMN language TEXT",
+            "ALTER TABLE snippets ADD COLUMN archived INTEGER NOT NULL DEFAULT 0",
+            "ALTER TABLE snippets ADD COLUMN expires_at INTEGER",
+            "CREATE TABLE tags (id INTEGER PRIMARY KEY, name TEXT)",
+            "CREATE TABLE snippet_tags (snippet_id INTEGER, tag_id INTEGER)",
+            "INSERT INTO tags VALUES (1, 'sql'), (2, 'rust')",
+            "INSERT INTO snippet_tags VALUES (1, 1), (1, 2), (3, 2)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_list_snippets_pages_with_tags() {
+        let pool = setup_tagged_db().await;
+        let all = list_snippets(&pool, SortPreference::Name, false, None, 0)
+            .await
+            .unwrap();
+        let names: Vec<&str> = all.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Alpha", "beta", "gamma"]);
+        assert_eq!(all[0].tags, Some(vec![]));
+        assert_eq!(
+            all[1].tags,
+            Some(vec!["rust".to_string(), "sql".to_string()])
+        );
+
+        let page = list_snippets(&pool, SortPreference::Usage, false, Some(1), 1)
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].name, "Alpha");
+
+        let past_end = list_snippets(&pool, SortPreference::Created, false, Some(10), 5)
+            .await
+            .unwrap();
+        assert!(past_end.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_snippets_leaves_out_archived() {
+        let pool = setup_tagged_db().await;
+        sqlx::query("UPDATE snippets SET archived = 1 WHERE id = 1")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let listed = list_snippets(&pool, SortPreference::Name, false, None, 0)
+            .await
+            .unwrap();
+        let names: Vec<&str> = listed.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Alpha", "gamma"]);
+
+        let all = list_snippets(&pool, SortPreference::Name, true, None, 0)
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(all[1].archived);
+    }
+
+    #[tokio::test]
+    async fn test_load_snippets() {
+        let pool = setup_tagged_db().await;
+        let snippets = load_snippets(&pool, &[3, 9, 1, 3]).await.unwrap();
+        let ids: Vec<i64> = snippets.iter().map(|s| s.id.0).collect();
+        assert_eq!(ids, [3, 1, 3]);
+        assert_eq!(snippets[0].tags, Some(vec!["rust".to_string()]));
+        assert_eq!(
+            snippets[1].tags,
+            Some(vec!["rust".to_string(), "sql".to_string()])
+        );
+
+        assert!(load_snippets(&pool, &[]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_load_single_snippet() {
+        let pool = setup_tagged_db().await;
+        sqlx::query(
+            "UPDATE snippets SET content = 'git branch', description = 'Branches',
+                pinned = 1, kind = 'dynamic', language = 'shellscript' WHERE id = 2",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let snippet = load_snippets(&pool, &[2]).await.unwrap().pop().unwrap();
+        assert_eq!(snippet.id.0, 2);
+        assert_eq!(snippet.name, "Alpha");
+        assert_eq!(snippet.content, "git branch");
+        assert_eq!(snippet.description.as_deref(), Some("Branches"));
+        assert_eq!((snippet.created_at, snippet.updated_at), (20, 30));
+        assert!(snippet.pinned);
+        assert_eq!(snippet.kind, SnippetKind::Dynamic);
+        assert_eq!(snippet.language.as_deref(), Some("shellscript"));
+        assert_eq!(snippet.tags, Some(vec![]));
+
+        // get_snippet turns this into NotFound
+        assert!(load_snippets(&pool, &[9]).await.unwrap().is_empty());
+    }
+
+    async fn ordered_names(pool: &SqlitePool, preference: SortPreference) -> Vec<String> {
+        sqlx::query_scalar(&format!(
+            "SELECT name FROM snippets ORDER BY {}",
+            order_by_clause(preference)
+        ))
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sort_preferences() {
+        let pool = setup_test_db().await;
+        assert_eq!(
+            ordered_names(&pool, SortPreference::Name).await,
+            ["Alpha", "beta", "gamma"]
+        );
+        assert_eq!(
+            ordered_names(&pool, SortPreference::Created).await,
+            ["gamma", "Alpha", "beta"]
+        );
+        assert_eq!(
+            ordered_names(&pool, SortPreference::Updated).await,
+            ["beta", "Alpha", "gamma"]
+        );
+        assert_eq!(
+            ordered_names(&pool, SortPreference::Usage).await,
+            ["beta", "Alpha", "gamma"]
+        );
+        assert_eq!(
+            ordered_names(&pool, SortPreference::Manual).await,
+            ["gamma", "Alpha", "beta"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reorder_partial_list() {
+        let pool = setup_test_db().await;
+        reorder(&pool, &[1]).await.unwrap();
+        assert_eq!(
+            ordered_names(&pool, SortPreference::Manual).await,
+            ["beta", "gamma", "Alpha"]
+        );
+
+        reorder(&pool, &[2, 3, 1]).await.unwrap();
+        assert_eq!(
+            ordered_names(&pool, SortPreference::Manual).await,
+            ["Alpha", "gamma", "beta"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reorder_rejects_bad_ids() {
+        let pool = setup_test_db().await;
+        assert!(matches!(
+            reorder(&pool, &[1, 1]).await,
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            reorder(&pool, &[1, 99]).await,
+            Err(AppError::NotFound(_))
+        ));
+        // Nothing changed
+        assert_eq!(
+            ordered_names(&pool, SortPreference::Manual).await,
+            ["gamma", "Alpha", "beta"]
+        );
+    }
+}
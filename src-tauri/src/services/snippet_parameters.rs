@@ -0,0 +1,405 @@
+use crate::utils::error::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use time::{Date, Month};
+
+/// Value type of a declared parameter, which decides the form control
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ParameterType {
+    /// Free text input
+    Text,
+    /// Numeric input with optional bounds
+    Number {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<f64>,
+    },
+    /// Date picker; values are `YYYY-MM-DD`
+    Date,
+    /// Dropdown limited to the listed values
+    Choice { choices: Vec<String> },
+    /// Checkbox; values are `true` or `false`
+    Boolean,
+}
+
+/// A parameter declared by a snippet, filled in through `{{name}}` placeholders
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnippetParameter {
+    pub name: String,
+    /// Label shown in the form; the name is used when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(flatten)]
+    pub kind: ParameterType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[serde(default = "default_required")]
+    pub required: bool,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+/// Checks parameter declarations before they are stored
+///
+/// Names must be unique identifiers (letters, digits, `_`, `-`), choice
+/// parameters need at least one choice, and defaults must be valid values.
+pub fn validate_parameters(parameters: &[SnippetParameter]) -> Result<(), AppError> {
+    let mut names = HashSet::new();
+    for parameter in parameters {
+        let name = parameter.name.as_str();
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(AppError::Validation(format!(
+                "Invalid parameter name '{}'",
+                name
+            )));
+        }
+        if !names.insert(name) {
+            return Err(AppError::Duplicate(format!(
+                "Parameter '{}' is declared more than once",
+                name
+            )));
+        }
+        match &parameter.kind {
+            ParameterType::Choice { choices } if choices.is_empty() => {
+                return Err(AppError::Validation(format!(
+                    "Parameter '{}' has no choices",
+                    name
+                )));
+            }
+            ParameterType::Number {
+                min: Some(min),
+                max: Some(max),
+            } if min > max => {
+                return Err(AppError::Validation(format!(
+                    "Parameter '{}' has a minimum above its maximum",
+                    name
+                )));
+            }
+            _ => {}
+        }
+        if let Some(default) = &parameter.default {
+            validate_value(parameter, default)?;
+        }
+    }
+    Ok(())
+}
+
+/// Checks one submitted value against its parameter's type
+fn validate_value(parameter: &SnippetParameter, value: &str) -> Result<(), AppError> {
+    let invalid = |reason: &str| {
+        Err(AppError::Validation(format!(
+            "Parameter '{}' {}",
+            parameter.name, reason
+        )))
+    };
+
+    match &parameter.kind {
+        ParameterType::Text => Ok(()),
+        ParameterType::Number { min, max } => {
+            let Ok(number) = value.trim().parse::<f64>() else {
+                return invalid("must be a number");
+            };
+            if !number.is_finite() {
+                return invalid("must be a number");
+            }
+            if min.is_some_and(|min| number < min) || max.is_some_and(|max| number > max) {
+                return invalid("is out of range");
+            }
+            Ok(())
+        }
+        ParameterType::Date => {
+            if parse_date(value.trim()).is_none() {
+                return invalid("must be a date (YYYY-MM-DD)");
+            }
+            Ok(())
+        }
+        ParameterType::Choice { choices } => {
+            if !choices.iter().any(|choice| choice == value) {
+                return invalid("must be one of the listed choices");
+            }
+            Ok(())
+        }
+        ParameterType::Boolean => {
+            if value != "true" && value != "false" {
+                return invalid("must be true or false");
+            }
+            Ok(())
+        }
+    }
+}
+
+fn parse_date(value: &str) -> Option<Date> {
+    let mut parts = value.splitn(3, '-');
+    let year: i32 = parts.next()?.parse().ok()?;
+    let month: u8 = parts.next()?.parse().ok()?;
+    let day: u8 = parts.next()?.parse().ok()?;
+    Date::from_calendar_date(year, Month::try_from(month).ok()?, day).ok()
+}
+
+/// Required parameters of a copied snippet that were left unfilled
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnfilledParameters {
+    pub snippet_id: i64,
+    pub snippet_name: String,
+    /// Parameter names, in declaration order
+    pub parameters: Vec<String>,
+}
+
+/// Validates submitted values and substitutes them into `{{name}}` placeholders
+///
+/// Missing values fall back to the parameter's default; a required parameter
+/// with neither is an error. Placeholders that match no declared parameter
+/// are left as written.
+///
+/// # Arguments
+///
+/// * `content` - Snippet content containing placeholders
+/// * `parameters` - Declared parameters
+/// * `values` - Values submitted from the form, by parameter name
+pub fn render_with_parameters(
+    content: &str,
+    parameters: &[SnippetParameter],
+    values: &HashMap<String, String>,
+) -> Result<String, AppError> {
+    let (text, unfilled) = fill_parameters(content, parameters, values)?;
+    match unfilled.first() {
+        Some(name) => Err(AppError::Validation(format!(
+            "Parameter '{}' is required",
+            name
+        ))),
+        None => Ok(text),
+    }
+}
+
+/// Like `render_with_parameters`, but leaves the placeholders of required
+/// parameters without a value as written instead of failing
+///
+/// # Returns
+///
+/// The text and the names of the required parameters left unfilled
+///
+/// # Errors
+///
+/// Returns an error naming the parameter when a value is invalid
+pub fn fill_parameters(
+    content: &str,
+    parameters: &[SnippetParameter],
+    values: &HashMap<String, String>,
+) -> Result<(String, Vec<String>), AppError> {
+    let mut resolved = HashMap::new();
+    let mut unfilled = Vec::new();
+    for parameter in parameters {
+        let value = values
+            .get(&parameter.name)
+            .filter(|value| !value.is_empty())
+            .or(parameter.default.as_ref());
+        match value {
+            Some(value) => {
+                validate_value(parameter, value)?;
+                resolved.insert(parameter.name.as_str(), value.as_str());
+            }
+            None if parameter.required => unfilled.push(parameter.name.clone()),
+            None => {
+                resolved.insert(parameter.name.as_str(), "");
+            }
+        }
+    }
+
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        match resolved.get(after[..end].trim()) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok((out, unfilled))
+}
+
+/// Loads the declared parameters of a snippet
+///
+/// # Errors
+///
+/// * `AppError::NotFound` - The snippet does not exist
+pub async fn get_parameters(
+    pool: &SqlitePool,
+    snippet_id: i64,
+) -> Result<Vec<SnippetParameter>, AppError> {
+    let stored: Option<Option<String>> =
+        sqlx::query_scalar("SELECT parameters FROM snippets WHERE id = ?")
+            .bind(snippet_id)
+            .fetch_optional(pool)
+            .await?;
+    let stored = stored
+        .ok_or_else(|| AppError::NotFound(format!("Snippet with id {} not found", snippet_id)))?;
+
+    match stored {
+        Some(json) => Ok(serde_json::from_str(&json)?),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Validates and stores the declared parameters of a snippet
+///
+/// An empty list removes the form.
+///
+/// # Errors
+///
+/// * `AppError::Validation` / `AppError::Duplicate` - Invalid declarations
+/// * `AppError::NotFound` - The snippet does not exist
+pub async fn set_parameters(
+    pool: &SqlitePool,
+    snippet_id: i64,
+    parameters: &[SnippetParameter],
+) -> Result<(), AppError> {
+    validate_parameters(parameters)?;
+    let json = if parameters.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(parameters)?)
+    };
+
+    let result = sqlx::query("UPDATE snippets SET parameters = ? WHERE id = ?")
+        .bind(json)
+        .bind(snippet_id)
+        .execute(pool)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!(
+            "Snippet with id {} not found",
+            snippet_id
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters() -> Vec<SnippetParameter> {
+        serde_json::from_str(
+            r#"[
+                {"name": "env", "type": "choice", "choices": ["staging", "prod"]},
+                {"name": "replicas", "type": "number", "min": 1, "max": 10, "default": "2"},
+                {"name": "date", "type": "date", "required": false}
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parameter_json_shape() {
+        let parsed = parameters();
+        assert_eq!(
+            parsed[0].kind,
+            ParameterType::Choice {
+                choices: vec!["staging".to_string(), "prod".to_string()]
+            }
+        );
+        assert!(parsed[0].required);
+        assert!(!parsed[2].required);
+        assert!(validate_parameters(&parsed).is_ok());
+    }
+
+    #[test]
+    fn test_validate_parameters_rejects_bad_declarations() {
+        let mut duplicate = parameters();
+        duplicate[1].name = "env".to_string();
+        assert!(matches!(
+            validate_parameters(&duplicate),
+            Err(AppError::Duplicate(_))
+        ));
+
+        let mut bad_default = parameters();
+        bad_default[1].default = Some("20".to_string());
+        assert!(validate_parameters(&bad_default).is_err());
+
+        let mut bad_name = parameters();
+        bad_name[0].name = "two words".to_string();
+        assert!(validate_parameters(&bad_name).is_err());
+    }
+
+    #[test]
+    fn test_render_with_parameters() {
+        let content = "deploy {{env}} x{{ replicas }} on {{date}} {{unknown}}";
+        let values = HashMap::from([("env".to_string(), "prod".to_string())]);
+        assert_eq!(
+            render_with_parameters(content, &parameters(), &values).unwrap(),
+            "deploy prod x2 on  {{unknown}}"
+        );
+    }
+
+    #[test]
+    fn test_render_validates_values() {
+        let content = "{{env}} {{date}}";
+        let render = |pairs: &[(&str, &str)]| {
+            let values = pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            render_with_parameters(content, &parameters(), &values)
+        };
+
+        assert!(render(&[]).is_err());
+        assert!(render(&[("env", "dev")]).is_err());
+        assert!(render(&[("env", "prod"), ("date", "2024-02-30")]).is_err());
+        assert_eq!(
+            render(&[("env", "prod"), ("date", "2024-02-29")]).unwrap(),
+            "prod 2024-02-29"
+        );
+    }
+
+    #[test]
+    fn test_fill_parameters_leaves_required_placeholders() {
+        let content = "deploy {{env}} x{{replicas}} on {{date}}";
+        let (text, unfilled) = fill_parameters(content, &parameters(), &HashMap::new()).unwrap();
+        assert_eq!(text, "deploy {{env}} x2 on ");
+        assert_eq!(unfilled, vec!["env"]);
+
+        let values = HashMap::from([("replicas".to_string(), "99".to_string())]);
+        assert!(fill_parameters(content, &parameters(), &values).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_and_set_parameters() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE snippets (id INTEGER PRIMARY KEY, parameters TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO snippets (id) VALUES (1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(get_parameters(&pool, 1).await.unwrap().is_empty());
+        set_parameters(&pool, 1, &parameters()).await.unwrap();
+        assert_eq!(get_parameters(&pool, 1).await.unwrap(), parameters());
+
+        set_parameters(&pool, 1, &[]).await.unwrap();
+        assert!(get_parameters(&pool, 1).await.unwrap().is_empty());
+
+        assert!(matches!(
+            get_parameters(&pool, 9).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+}
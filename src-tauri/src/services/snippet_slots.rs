@@ -0,0 +1,187 @@
+use crate::utils::error::AppError;
+use crate::utils::time::current_timestamp;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+
+/// Highest slot number; slots map to the digit keys 1 through 9
+pub const MAX_SLOT: u8 = 9;
+
+/// A snippet assigned to a numbered slot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotAssignment {
+    pub slot: u8,
+    pub snippet_id: i64,
+    pub snippet_name: String,
+}
+
+fn check_slot(slot: u8) -> Result<(), AppError> {
+    if !(1..=MAX_SLOT).contains(&slot) {
+        return Err(AppError::InvalidInput(format!(
+            "Slot must be between 1 and {}, got {}",
+            MAX_SLOT, slot
+        )));
+    }
+    Ok(())
+}
+
+/// Assigns a snippet to a slot, or clears the slot
+///
+/// A snippet occupies at most one slot, so assigning it moves it out of its
+/// previous slot. Whatever was in the target slot is replaced.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `slot` - Slot number, 1 through 9
+/// * `snippet_id` - Snippet to assign, or `None` to clear the slot
+///
+/// # Errors
+///
+/// * `AppError::InvalidInput` - The slot is out of range
+/// * `AppError::NotFound` - The snippet does not exist
+pub async fn assign_slot(
+    pool: &SqlitePool,
+    slot: u8,
+    snippet_id: Option<i64>,
+) -> Result<(), AppError> {
+    check_slot(slot)?;
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM snippet_slots WHERE slot = ? OR snippet_id = ?")
+        .bind(slot)
+        .bind(snippet_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if let Some(snippet_id) = snippet_id {
+        let exists = sqlx::query("SELECT 1 FROM snippets WHERE id = ?")
+            .bind(snippet_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if exists.is_none() {
+            return Err(AppError::NotFound(format!(
+                "Snippet with id {} not found",
+                snippet_id
+            )));
+        }
+
+        sqlx::query("INSERT INTO snippet_slots (slot, snippet_id, assigned_at) VALUES (?, ?, ?)")
+            .bind(slot)
+            .bind(snippet_id)
+            .bind(current_timestamp())
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Lists assigned slots in slot order
+pub async fn get_assignments(pool: &SqlitePool) -> Result<Vec<SlotAssignment>, AppError> {
+    let rows = sqlx::query(
+        "SELECT sl.slot, sl.snippet_id, s.name FROM snippet_slots sl
+         INNER JOIN snippets s ON s.id = sl.snippet_id
+         ORDER BY sl.slot",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| SlotAssignment {
+            slot: row.get(0),
+            snippet_id: row.get(1),
+            snippet_name: row.get(2),
+        })
+        .collect())
+}
+
+/// Returns the snippet assigned to a slot, if any
+pub async fn snippet_for_slot(pool: &SqlitePool, slot: u8) -> Result<Option<i64>, AppError> {
+    check_slot(slot)?;
+    let snippet_id = sqlx::query_scalar(
+        "SELECT sl.snippet_id FROM snippet_slots sl
+         INNER JOIN snippets s ON s.id = sl.snippet_id
+         WHERE sl.slot = ?",
+    )
+    .bind(slot)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(snippet_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE snippets (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(include_str!("../migrations/014_create_snippet_slots.sql"))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO snippets (id, name) VALUES (1, 'one'), (2, 'two')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_assign_and_move_slots() {
+        let pool = setup_test_db().await;
+
+        assign_slot(&pool, 1, Some(1)).await.unwrap();
+        assign_slot(&pool, 2, Some(2)).await.unwrap();
+        assert_eq!(snippet_for_slot(&pool, 1).await.unwrap(), Some(1));
+
+        // Moving snippet 1 to slot 3 frees slot 1
+        assign_slot(&pool, 3, Some(1)).await.unwrap();
+        let slots: Vec<(u8, i64)> = get_assignments(&pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|a| (a.slot, a.snippet_id))
+            .collect();
+        assert_eq!(slots, vec![(2, 2), (3, 1)]);
+
+        // Replacing the occupant of a slot
+        assign_slot(&pool, 2, Some(1)).await.unwrap();
+        assert_eq!(snippet_for_slot(&pool, 2).await.unwrap(), Some(1));
+        assert_eq!(snippet_for_slot(&pool, 3).await.unwrap(), None);
+
+        assign_slot(&pool, 2, None).await.unwrap();
+        assert!(get_assignments(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_assign_slot_rejects_bad_input() {
+        let pool = setup_test_db().await;
+
+        assert!(matches!(
+            assign_slot(&pool, 0, Some(1)).await,
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            assign_slot(&pool, 10, Some(1)).await,
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            assign_slot(&pool, 1, Some(99)).await,
+            Err(AppError::NotFound(_))
+        ));
+        // The failed assignment left nothing behind
+        assert!(get_assignments(&pool).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,544 @@
+/// Snippets that mirror a remote file.
+///
+/// A snippet with a `source_url` (a raw file URL, a GitHub file page or a
+/// gist) is re-fetched on a schedule when refresh is enabled in settings.
+/// Changed content replaces the snippet's content; the previous content is
+/// kept in `snippet_versions` and a `snippet-source-updated` event carries a
+/// summary of the change to the UI. Scheduled refreshes that changed
+/// anything also show a desktop notification.
+use crate::models::SnippetKind;
+use crate::services::database::get_pool;
+use crate::services::notifications::{self, NotificationKind};
+use crate::services::settings::SettingsService;
+use crate::services::{gist, language, library_stats, name_index};
+use crate::utils::error::AppError;
+use crate::utils::time::current_timestamp;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// How often the scheduler looks for snippets whose source is due
+const CHECK_INTERVAL_SECS: u64 = 10 * 60;
+
+/// Event emitted when a refresh changed a snippet
+pub const SOURCE_UPDATED_EVENT: &str = "snippet-source-updated";
+
+/// `snippet_versions.reason` for content replaced by a source refresh
+const VERSION_REASON_SOURCE_REFRESH: &str = "source_refresh";
+
+/// A snippet whose content changed when its source was fetched
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceChange {
+    pub snippet_id: i64,
+    pub snippet_name: String,
+    pub source_url: String,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub refreshed_at: i64,
+}
+
+/// The remote file a snippet mirrors
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnippetSource {
+    pub url: String,
+    /// When the source was last fetched; `None` until the first refresh
+    pub checked_at: Option<i64>,
+}
+
+/// An earlier content of a snippet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnippetVersion {
+    pub id: i64,
+    pub snippet_id: i64,
+    pub content: String,
+    /// Why the content was replaced, e.g. `source_refresh`
+    pub reason: String,
+    pub created_at: i64,
+}
+
+/// Checks a source URL and returns it trimmed
+///
+/// Only `http` and `https` are accepted; curl would otherwise happily read
+/// `file://` URLs.
+pub fn validate_source_url(url: &str) -> Result<String, AppError> {
+    let url = url.trim();
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"));
+    match rest {
+        Some(rest) if !rest.is_empty() && !rest.starts_with('/') => Ok(url.to_string()),
+        _ => Err(AppError::InvalidInput(format!(
+            "Source must be an http(s) URL: {}",
+            url
+        ))),
+    }
+}
+
+/// Rewrites page URLs to the URL of the raw file
+///
+/// * `https://github.com/o/r/blob/main/f` becomes
+///   `https://raw.githubusercontent.com/o/r/main/f`
+/// * `https://gist.github.com/u/id` becomes `https://gist.github.com/u/id/raw`,
+///   the gist's first file
+///
+/// Other URLs are fetched as they are.
+pub fn raw_source_url(url: &str) -> String {
+    let url = url.split('#').next().unwrap_or(url);
+
+    if let Some(path) = url.strip_prefix("https://github.com/") {
+        let parts: Vec<&str> = path.splitn(4, '/').collect();
+        if let [owner, repo, "blob", file] = parts[..] {
+            return format!(
+                "https://raw.githubusercontent.com/{}/{}/{}",
+                owner, repo, file
+            );
+        }
+    }
+
+    if let Some(path) = url.strip_prefix("https://gist.github.com/") {
+        let path = path.trim_end_matches('/');
+        if !path.contains("/raw") {
+            return format!("https://gist.github.com/{}/raw", path);
+        }
+    }
+
+    url.to_string()
+}
+
+/// Counts lines added and removed between two versions, ignoring order
+pub fn summarize_diff(old: &str, new: &str) -> (usize, usize) {
+    let mut old_lines: HashMap<&str, usize> = HashMap::new();
+    for line in old.lines() {
+        *old_lines.entry(line).or_default() += 1;
+    }
+
+    let mut added = 0;
+    for line in new.lines() {
+        match old_lines.get_mut(line) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => added += 1,
+        }
+    }
+    let removed = old_lines.values().sum();
+    (added, removed)
+}
+
+/// Sets or clears the source a snippet mirrors
+///
+/// # Errors
+///
+/// * `AppError::InvalidInput` - The URL is not http(s)
+/// * `AppError::NotFound` - The snippet does not exist
+pub async fn set_source(
+    pool: &SqlitePool,
+    snippet_id: i64,
+    source_url: Option<&str>,
+) -> Result<(), AppError> {
+    let source_url = source_url
+        .filter(|url| !url.trim().is_empty())
+        .map(validate_source_url)
+        .transpose()?;
+
+    let result =
+        sqlx::query("UPDATE snippets SET source_url = ?, source_checked_at = NULL WHERE id = ?")
+            .bind(source_url)
+            .bind(snippet_id)
+            .execute(pool)
+            .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!(
+            "Snippet with id {} not found",
+            snippet_id
+        )));
+    }
+    Ok(())
+}
+
+/// Returns the source a snippet mirrors, if any
+///
+/// # Errors
+///
+/// * `AppError::NotFound` - The snippet does not exist
+pub async fn get_source(
+    pool: &SqlitePool,
+    snippet_id: i64,
+) -> Result<Option<SnippetSource>, AppError> {
+    let row = sqlx::query("SELECT source_url, source_checked_at FROM snippets WHERE id = ?")
+        .bind(snippet_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Snippet with id {} not found", snippet_id)))?;
+
+    let url: Option<String> = row.get(0);
+    Ok(url.map(|url| SnippetSource {
+        url,
+        checked_at: row.get(1),
+    }))
+}
+
+/// Stores fetched source content for a snippet
+///
+/// Unchanged content (ignoring surrounding whitespace) only records the
+/// check. Changed content replaces the snippet's content, after the previous
+/// content is saved as a version.
+///
+/// # Returns
+///
+/// The change, or `None` if the content was unchanged
+pub async fn apply_fetched_content(
+    pool: &SqlitePool,
+    snippet_id: i64,
+    fetched: &str,
+    now: i64,
+) -> Result<Option<SourceChange>, AppError> {
+    let mut tx = pool.begin().await?;
+    let row = sqlx::query("SELECT name, content, kind, source_url FROM snippets WHERE id = ?")
+        .bind(snippet_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Snippet with id {} not found", snippet_id)))?;
+    let name: String = row.get(0);
+    let content: String = row.get(1);
+    let kind = SnippetKind::from_db(row.get(2));
+    let source_url: Option<String> = row.get(3);
+
+    let fetched = fetched.trim();
+    if fetched.is_empty() || fetched == content {
+        sqlx::query("UPDATE snippets SET source_checked_at = ? WHERE id = ?")
+            .bind(now)
+            .bind(snippet_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        return Ok(None);
+    }
+
+    sqlx::query(
+        "INSERT INTO snippet_versions (snippet_id, content, reason, created_at) VALUES (?, ?, ?, ?)",
+    )
+    .bind(snippet_id)
+    .bind(&content)
+    .bind(VERSION_REASON_SOURCE_REFRESH)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "UPDATE snippets
+         SET content = ?, language = ?, updated_at = ?, source_checked_at = ?
+         WHERE id = ?",
+    )
+    .bind(fetched)
+    .bind(language::detect_language(&name, fetched, kind))
+    .bind(now)
+    .bind(now)
+    .bind(snippet_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    let (lines_added, lines_removed) = summarize_diff(&content, fetched);
+    Ok(Some(SourceChange {
+        snippet_id,
+        snippet_name: name,
+        source_url: source_url.unwrap_or_default(),
+        lines_added,
+        lines_removed,
+        refreshed_at: now,
+    }))
+}
+
+/// Lists the earlier contents of a snippet, newest first
+pub async fn get_versions(
+    pool: &SqlitePool,
+    snippet_id: i64,
+) -> Result<Vec<SnippetVersion>, AppError> {
+    let rows = sqlx::query(
+        "SELECT id, snippet_id, content, reason, created_at FROM snippet_versions
+         WHERE snippet_id = ?
+         ORDER BY created_at DESC, id DESC",
+    )
+    .bind(snippet_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| SnippetVersion {
+            id: row.get(0),
+            snippet_id: row.get(1),
+            content: row.get(2),
+            reason: row.get(3),
+            created_at: row.get(4),
+        })
+        .collect())
+}
+
+/// Fetches one snippet's source and stores the result
+///
+/// Emits `snippet-source-updated` when the content changed.
+///
+/// # Errors
+///
+/// * `AppError::InvalidInput` - The snippet has no source
+/// * `AppError::External` - The download failed
+pub async fn refresh_snippet(
+    app: &AppHandle,
+    snippet_id: i64,
+) -> Result<Option<SourceChange>, AppError> {
+    let pool = get_pool(app)?;
+    let source_url: Option<String> =
+        sqlx::query_scalar("SELECT source_url FROM snippets WHERE id = ?")
+            .bind(snippet_id)
+            .fetch_optional(&pool)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Snippet with id {} not found", snippet_id))
+            })?;
+    let source_url = source_url.ok_or_else(|| {
+        AppError::InvalidInput(format!("Snippet {} has no source URL", snippet_id))
+    })?;
+
+    // No token: it must not be sent to whatever host the URL names
+    let fetched = gist::fetch_raw(&raw_source_url(&source_url), None).await?;
+    let change = apply_fetched_content(&pool, snippet_id, &fetched, current_timestamp()).await?;
+
+    if let Some(change) = &change {
+        library_stats::refresh_snippet(app, snippet_id).await;
+        name_index::refresh_snippet(app, snippet_id).await;
+        if let Err(e) = app.emit(SOURCE_UPDATED_EVENT, change) {
+            eprintln!("Failed to emit source update event: {}", e);
+        }
+    }
+    Ok(change)
+}
+
+/// Refreshes every snippet whose source was last fetched before `checked_before`
+///
+/// A failing source is logged and skipped; it is retried on the next check.
+pub async fn refresh_due(
+    app: &AppHandle,
+    checked_before: i64,
+) -> Result<Vec<SourceChange>, AppError> {
+    let pool = get_pool(app)?;
+    let due: Vec<i64> = sqlx::query_scalar(
+        "SELECT id FROM snippets
+         WHERE source_url IS NOT NULL
+           AND (source_checked_at IS NULL OR source_checked_at < ?)
+         ORDER BY id",
+    )
+    .bind(checked_before)
+    .fetch_all(&pool)
+    .await?;
+
+    let mut changes = Vec::new();
+    for snippet_id in due {
+        match refresh_snippet(app, snippet_id).await {
+            Ok(Some(change)) => changes.push(change),
+            Ok(None) => {}
+            Err(e) => eprintln!("Failed to refresh source of snippet {}: {}", snippet_id, e),
+        }
+    }
+    Ok(changes)
+}
+
+/// Body of the notification for a scheduled refresh
+pub fn change_summary(changes: &[SourceChange]) -> Option<String> {
+    match changes {
+        [] => None,
+        [change] => Some(format!(
+            "{} updated from its source (+{} -{} lines)",
+            change.snippet_name, change.lines_added, change.lines_removed
+        )),
+        _ => Some(format!(
+            "Sources updated ({} changed): {}",
+            changes.len(),
+            changes
+                .iter()
+                .map(|change| change.snippet_name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Starts the background loop that refreshes due sources
+///
+/// Settings are re-read on every check, so enabling refresh or changing the
+/// interval takes effect without a restart.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match get_pool(&app) {
+                Ok(pool) => match SettingsService::new(pool).get_settings().await {
+                    Ok(settings) if settings.source_refresh_settings.enabled => {
+                        let interval =
+                            i64::from(settings.source_refresh_settings.interval_hours) * 3600;
+                        match refresh_due(&app, current_timestamp() - interval).await {
+                            Ok(changes) => {
+                                if let Some(summary) = change_summary(&changes) {
+                                    notifications::notify(
+                                        &app,
+                                        NotificationKind::SourceUpdated,
+                                        summary,
+                                    );
+                                }
+                            }
+                            Err(e) => eprintln!("Failed to refresh snippet sources: {}", e),
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => eprintln!("Skipping source refresh: {}", e),
+                },
+                Err(e) => eprintln!("Skipping source refresh: {}", e),
+            }
+
+            tokio::time::sleep(Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_source_url() {
+        assert_eq!(
+            validate_source_url(" https://example.com/a.sh ").unwrap(),
+            "https://example.com/a.sh"
+        );
+        assert!(validate_source_url("http://example.com/a").is_ok());
+        assert!(validate_source_url("file:///etc/passwd").is_err());
+        assert!(validate_source_url("https://").is_err());
+        assert!(validate_source_url("example.com").is_err());
+    }
+
+    #[test]
+    fn test_raw_source_url() {
+        assert_eq!(
+            raw_source_url("https://github.com/o/r/blob/main/dir/f.toml"),
+            "https://raw.githubusercontent.com/o/r/main/dir/f.toml"
+        );
+        assert_eq!(
+            raw_source_url("https://gist.github.com/u/abc123#file-x"),
+            "https://gist.github.com/u/abc123/raw"
+        );
+        assert_eq!(
+            raw_source_url("https://gist.github.com/u/abc123/raw/f.sh"),
+            "https://gist.github.com/u/abc123/raw/f.sh"
+        );
+        assert_eq!(
+            raw_source_url("https://example.com/f.sh"),
+            "https://example.com/f.sh"
+        );
+    }
+
+    #[test]
+    fn test_summarize_diff() {
+        assert_eq!(summarize_diff("a\nb\nc", "a\nb\nc"), (0, 0));
+        assert_eq!(summarize_diff("a\nb\nc", "a\nx\nc\nd"), (2, 1));
+        assert_eq!(summarize_diff("a\na", "a"), (0, 1));
+    }
+
+    #[test]
+    fn test_change_summary() {
+        let change = |name: &str| SourceChange {
+            snippet_id: 1,
+            snippet_name: name.to_string(),
+            source_url: "https://example.com/f.sh".to_string(),
+            lines_added: 2,
+            lines_removed: 1,
+            refreshed_at: 0,
+        };
+        assert_eq!(change_summary(&[]), None);
+        assert_eq!(
+            change_summary(&[change("deploy")]).as_deref(),
+            Some("deploy updated from its source (+2 -1 lines)")
+        );
+        assert_eq!(
+            change_summary(&[change("deploy"), change("lint")]).as_deref(),
+            Some("Sources updated (2 changed): deploy, lint")
+        );
+    }
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "CREATE TABLE snippets (
+                id INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                content TEXT NOT NULL,
+                kind TEXT NOT NULL DEFAULT 'static',
+                language TEXT,
+                updated_at INTEGER NOT NULL DEFAULT 0
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::raw_sql(include_str!("../migrations/015_add_snippet_sources.sql"))
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO snippets (id, name, content) VALUES (1, 'ssh config', 'Host a')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_apply_fetched_content_records_version() {
+        let pool = setup_test_db().await;
+        set_source(&pool, 1, Some("https://example.com/config"))
+            .await
+            .unwrap();
+        assert_eq!(
+            get_source(&pool, 1).await.unwrap().unwrap().checked_at,
+            None
+        );
+
+        // Unchanged content only records the check
+        assert!(apply_fetched_content(&pool, 1, "Host a\n", 100)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(get_versions(&pool, 1).await.unwrap().is_empty());
+
+        let change = apply_fetched_content(&pool, 1, "Host a\nHost b\n", 200)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(change.source_url, "https://example.com/config");
+        assert_eq!((change.lines_added, change.lines_removed), (1, 0));
+
+        let content: String = sqlx::query_scalar("SELECT content FROM snippets WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(content, "Host a\nHost b");
+
+        let versions = get_versions(&pool, 1).await.unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].content, "Host a");
+        assert_eq!(versions[0].reason, "source_refresh");
+    }
+
+    #[tokio::test]
+    async fn test_set_source_rejects_bad_input() {
+        let pool = setup_test_db().await;
+        assert!(matches!(
+            set_source(&pool, 1, Some("file:///etc/hosts")).await,
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            set_source(&pool, 9, Some("https://example.com")).await,
+            Err(AppError::NotFound(_))
+        ));
+        set_source(&pool, 1, None).await.unwrap();
+        assert_eq!(get_source(&pool, 1).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::services::janitor::JanitorReport;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::sync::RwLock;
+
+/// How long to wait for the first window to load before starting the deferred
+/// phases anyway, e.g. when every window starts hidden and never loads
+pub const DEFERRED_FALLBACK: Duration = Duration::from_secs(5);
+
+/// Integrity report collected while the application starts
+///
+/// Surfaced to the UI through `get_startup_report` so maintenance performed at
+/// launch is visible instead of happening silently.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StartupReport {
+    /// Unix timestamp of when the report was generated
+    pub generated_at: i64,
+    /// Temporary files cleaned up by the startup janitor
+    pub janitor: JanitorReport,
+    /// Timings of the startup phases, in the order they finished
+    #[serde(default)]
+    pub phases: Vec<StartupPhase>,
+    /// Milliseconds from launch until `setup()` returned
+    #[serde(default)]
+    pub setup_ms: u64,
+    /// Milliseconds from launch until the deferred phases started, once they have
+    #[serde(default)]
+    pub deferred_at_ms: Option<u64>,
+}
+
+/// How long one startup phase took
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StartupPhase {
+    /// Short name of the phase, e.g. `database`
+    pub name: String,
+    /// Milliseconds from launch until the phase started
+    pub started_ms: u64,
+    /// Milliseconds the phase took
+    pub duration_ms: u64,
+    /// Whether the phase waited until after the first window loaded
+    pub deferred: bool,
+}
+
+/// State wrapper for the startup report
+pub struct StartupReportState(pub RwLock<StartupReport>);
+
+/// Records phase timings relative to launch
+#[derive(Debug)]
+pub struct StartupTimer {
+    launched: Instant,
+    deferred: bool,
+    phases: Vec<StartupPhase>,
+}
+
+impl StartupTimer {
+    /// A timer for the phases run by `setup()`
+    pub fn new(launched: Instant) -> Self {
+        Self {
+            launched,
+            deferred: false,
+            phases: Vec::new(),
+        }
+    }
+
+    /// A timer for the phases run after the first window loaded
+    pub fn deferred(launched: Instant) -> Self {
+        Self {
+            deferred: true,
+            ..Self::new(launched)
+        }
+    }
+
+    /// Records a phase that began at `began` and just ended
+    pub fn record(&mut self, name: &str, began: Instant) {
+        self.phases.push(StartupPhase {
+            name: name.to_string(),
+            started_ms: millis(began.saturating_duration_since(self.launched)),
+            duration_ms: millis(began.elapsed()),
+            deferred: self.deferred,
+        });
+    }
+
+    /// Milliseconds since launch
+    pub fn elapsed_ms(&self) -> u64 {
+        millis(self.launched.elapsed())
+    }
+
+    /// The recorded phases, in the order they finished
+    pub fn into_phases(self) -> Vec<StartupPhase> {
+        self.phases
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Tracks whether the deferred startup phases have run
+///
+/// The phases start on the first page load, with `DEFERRED_FALLBACK` as a
+/// backstop; whichever comes first claims them.
+#[derive(Debug)]
+pub struct DeferredStartup {
+    launched: Instant,
+    claimed: AtomicBool,
+}
+
+impl DeferredStartup {
+    pub fn new(launched: Instant) -> Self {
+        Self {
+            launched,
+            claimed: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns a timer for the deferred phases the first time it is called,
+    /// and `None` after that
+    pub fn claim(&self) -> Option<StartupTimer> {
+        if self.claimed.swap(true, Ordering::SeqCst) {
+            return None;
+        }
+        Some(StartupTimer::deferred(self.launched))
+    }
+}
+
+/// Adds the timings of the deferred phases to the startup report
+pub async fn record_deferred(app: &AppHandle, timer: StartupTimer, started_at_ms: u64) {
+    if let Some(state) = app.try_state::<StartupReportState>() {
+        let mut report = state.0.write().await;
+        report.deferred_at_ms = Some(started_at_ms);
+        report.phases.extend(timer.into_phases());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_startup_timer() {
+        let launched = Instant::now() - Duration::from_millis(50);
+        let mut timer = StartupTimer::new(launched);
+        let began = Instant::now();
+        timer.record("database", began);
+        assert!(timer.elapsed_ms() >= 50);
+
+        let phases = timer.into_phases();
+        assert_eq!(phases.len(), 1);
+        assert_eq!(phases[0].name, "database");
+        assert!(phases[0].started_ms >= 50);
+        assert!(!phases[0].deferred);
+    }
+
+    #[test]
+    fn test_deferred_startup_is_claimed_once() {
+        let startup = DeferredStartup::new(Instant::now());
+        let mut timer = startup.claim().expect("first claim");
+        timer.record("backup_scheduler", Instant::now());
+        assert!(timer.into_phases()[0].deferred);
+        assert!(startup.claim().is_none());
+    }
+}
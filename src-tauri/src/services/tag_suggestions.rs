@@ -0,0 +1,311 @@
+/// Tag suggestions for a snippet that is being written.
+///
+/// Three signals are combined: the language detected from the name and
+/// content, existing tags whose name appears as a word in the text, and the
+/// tags of the library snippets whose words overlap most with it. Existing
+/// tags are preferred over new ones so suggestions do not fragment the tag
+/// corpus (`rust` rather than a fresh `lang/rust` when both would fit).
+use crate::models::SnippetKind;
+use crate::services::language::{self, language_id_for_tag};
+use crate::services::tags::{normalize_tag_path, TAG_PATH_SEPARATOR};
+use crate::utils::error::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use std::collections::{HashMap, HashSet};
+
+/// Suggestions returned when the caller does not ask for a count
+pub const DEFAULT_SUGGESTION_LIMIT: usize = 5;
+
+/// Score for the tag naming the detected language
+const LANGUAGE_WEIGHT: f64 = 3.0;
+
+/// Score for an existing tag found as a word in the text
+const KEYWORD_WEIGHT: f64 = 2.0;
+
+/// Score for a tag carried by an identical snippet; scaled by similarity
+const SIMILARITY_WEIGHT: f64 = 2.0;
+
+/// Library snippets whose tags are considered
+const MAX_NEIGHBOURS: usize = 5;
+
+/// Word overlap below which a library snippet is not considered similar
+const MIN_SIMILARITY: f64 = 0.1;
+
+/// Why a tag was suggested
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionReason {
+    /// Names the language detected in the content
+    Language,
+    /// Appears as a word in the name or content
+    Keyword,
+    /// Carried by snippets with similar content
+    SimilarSnippets,
+}
+
+/// A suggested tag, best first in the returned list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagSuggestion {
+    pub tag: String,
+    pub score: f64,
+    pub reasons: Vec<SuggestionReason>,
+    /// Whether the tag already exists; new tags are created on save
+    pub existing: bool,
+}
+
+/// A tagged library snippet used for similarity
+#[derive(Debug, Clone)]
+pub struct CorpusSnippet {
+    pub words: HashSet<String>,
+    pub tags: Vec<String>,
+}
+
+/// Lowercased words of at least three letters or digits
+///
+/// Short words are mostly noise (`a`, `if`, `of`) and would make every
+/// snippet look similar to every other.
+pub fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 3)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Share of distinct words two snippets have in common (Jaccard index)
+fn similarity(first: &HashSet<String>, second: &HashSet<String>) -> f64 {
+    let shared = first.intersection(second).count();
+    if shared == 0 {
+        return 0.0;
+    }
+    shared as f64 / (first.len() + second.len() - shared) as f64
+}
+
+/// Last level of a tag path, lowercased
+fn tag_leaf(tag: &str) -> String {
+    tag.rsplit(TAG_PATH_SEPARATOR)
+        .next()
+        .unwrap_or(tag)
+        .to_lowercase()
+}
+
+/// Ranks tag suggestions for a snippet
+///
+/// # Arguments
+///
+/// * `name` - Snippet name
+/// * `content` - Snippet content
+/// * `kind` - Static or dynamic
+/// * `existing_tags` - Every tag in the library
+/// * `corpus` - Tagged library snippets
+/// * `current_tags` - Tags already on the snippet; never suggested
+/// * `limit` - Maximum number of suggestions
+///
+/// # Returns
+///
+/// Suggestions ordered by score, ties broken by tag name
+pub fn rank_suggestions(
+    name: &str,
+    content: &str,
+    kind: SnippetKind,
+    existing_tags: &[String],
+    corpus: &[CorpusSnippet],
+    current_tags: &[String],
+    limit: usize,
+) -> Vec<TagSuggestion> {
+    let mut scores: HashMap<String, (f64, Vec<SuggestionReason>)> = HashMap::new();
+    let mut add = |tag: &str, score: f64, reason: SuggestionReason| {
+        let entry = scores.entry(tag.to_string()).or_default();
+        entry.0 += score;
+        if !entry.1.contains(&reason) {
+            entry.1.push(reason);
+        }
+    };
+
+    if let Some(language) = language::detect_language(name, content, kind) {
+        let tag = existing_tags
+            .iter()
+            .find(|tag| language_id_for_tag(&tag_leaf(tag)) == Some(language))
+            .map(String::as_str)
+            .unwrap_or(language);
+        add(tag, LANGUAGE_WEIGHT, SuggestionReason::Language);
+    }
+
+    let text_words = words(&format!("{} {}", name, content));
+    for tag in existing_tags {
+        let leaf_words = words(&tag_leaf(tag));
+        if !leaf_words.is_empty() && leaf_words.is_subset(&text_words) {
+            add(tag, KEYWORD_WEIGHT, SuggestionReason::Keyword);
+        }
+    }
+
+    let mut neighbours: Vec<(f64, &CorpusSnippet)> = corpus
+        .iter()
+        .map(|snippet| (similarity(&text_words, &snippet.words), snippet))
+        .filter(|(score, _)| *score >= MIN_SIMILARITY)
+        .collect();
+    neighbours.sort_by(|a, b| b.0.total_cmp(&a.0));
+    for (score, snippet) in neighbours.into_iter().take(MAX_NEIGHBOURS) {
+        for tag in &snippet.tags {
+            add(
+                tag,
+                SIMILARITY_WEIGHT * score,
+                SuggestionReason::SimilarSnippets,
+            );
+        }
+    }
+
+    let current: HashSet<String> = current_tags
+        .iter()
+        .map(|tag| normalize_tag_path(tag).to_lowercase())
+        .collect();
+    let known: HashSet<&str> = existing_tags.iter().map(String::as_str).collect();
+
+    let mut suggestions: Vec<TagSuggestion> = scores
+        .into_iter()
+        .filter(|(tag, _)| !current.contains(&tag.to_lowercase()))
+        .map(|(tag, (score, reasons))| TagSuggestion {
+            existing: known.contains(tag.as_str()),
+            tag,
+            score,
+            reasons,
+        })
+        .collect();
+    suggestions.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.tag.cmp(&b.tag)));
+    suggestions.truncate(limit);
+    suggestions
+}
+
+/// Suggests tags for a snippet from the library's tags and tagged snippets
+///
+/// # Errors
+///
+/// Returns `AppError` if the library cannot be read
+pub async fn suggest_tags_for_content(
+    pool: &SqlitePool,
+    name: &str,
+    content: &str,
+    kind: SnippetKind,
+    current_tags: &[String],
+    limit: usize,
+) -> Result<Vec<TagSuggestion>, AppError> {
+    let existing_tags: Vec<String> = sqlx::query_scalar("SELECT name FROM tags ORDER BY name")
+        .fetch_all(pool)
+        .await?;
+
+    let rows = sqlx::query(
+        "SELECT s.name, s.content, json_group_array(t.name)
+         FROM snippets s
+         INNER JOIN snippet_tags st ON st.snippet_id = s.id
+         INNER JOIN tags t ON t.id = st.tag_id
+         GROUP BY s.id",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let corpus = rows
+        .iter()
+        .map(|row| {
+            let name: &str = row.get(0);
+            let content: &str = row.get(1);
+            Ok(CorpusSnippet {
+                words: words(&format!("{} {}", name, content)),
+                tags: serde_json::from_str(row.get(2))?,
+            })
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+    Ok(rank_suggestions(
+        name,
+        content,
+        kind,
+        &existing_tags,
+        &corpus,
+        current_tags,
+        limit,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus_snippet(text: &str, tags: &[&str]) -> CorpusSnippet {
+        CorpusSnippet {
+            words: words(text),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        }
+    }
+
+    fn tags(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_words() {
+        let found = words("git commit -m 'Fix it' && git push");
+        let mut found: Vec<&str> = found.iter().map(String::as_str).collect();
+        found.sort();
+        assert_eq!(found, vec!["commit", "fix", "git", "push"]);
+    }
+
+    #[test]
+    fn test_rank_suggestions_combines_signals() {
+        let existing = tags(&["docker", "git", "lang/shellscript", "prompts"]);
+        let corpus = vec![
+            corpus_snippet("git push origin main", &["git", "deploy"]),
+            corpus_snippet("write a haiku about the sea", &["prompts"]),
+        ];
+
+        let suggestions = rank_suggestions(
+            "push.sh",
+            "git push origin feature",
+            SnippetKind::Static,
+            &existing,
+            &corpus,
+            &[],
+            DEFAULT_SUGGESTION_LIMIT,
+        );
+        let ranked: Vec<&str> = suggestions.iter().map(|s| s.tag.as_str()).collect();
+        assert_eq!(ranked, vec!["git", "lang/shellscript", "deploy"]);
+
+        assert_eq!(
+            suggestions[0].reasons,
+            vec![SuggestionReason::Keyword, SuggestionReason::SimilarSnippets]
+        );
+        assert_eq!(suggestions[1].reasons, vec![SuggestionReason::Language]);
+        assert!(suggestions[1].existing);
+        // Tags of similar snippets are suggested even though no tag row has them yet
+        assert!(!suggestions[2].existing);
+    }
+
+    #[test]
+    fn test_rank_suggestions_skips_current_tags() {
+        let existing = tags(&["git"]);
+        let suggestions = rank_suggestions(
+            "status",
+            "git status --short",
+            SnippetKind::Static,
+            &existing,
+            &[],
+            &tags(&["GIT"]),
+            DEFAULT_SUGGESTION_LIMIT,
+        );
+        assert!(suggestions.is_empty());
+    }
+
+    #[test]
+    fn test_rank_suggestions_new_language_tag() {
+        let suggestions = rank_suggestions(
+            "main.rs",
+            "fn main() {}",
+            SnippetKind::Static,
+            &[],
+            &[],
+            &[],
+            1,
+        );
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].tag, "rust");
+        assert!(!suggestions[0].existing);
+    }
+}
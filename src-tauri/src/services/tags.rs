@@ -1,14 +1,30 @@
-use crate::models::tag::Tag;
+use crate::models::tag::{Tag, TagReassignment, TagStats, TagTreeNode};
 use crate::services::database::get_pool;
 use crate::utils::error::AppError;
-use sqlx::Row;
+use sqlx::{Row, Sqlite, SqlitePool, Transaction};
+use std::collections::{BTreeMap, HashSet};
 use tauri::AppHandle;
 
+/// Separator between the levels of a hierarchical tag
+pub const TAG_PATH_SEPARATOR: char = '/';
+
+/// Normalizes a tag name as a path
+///
+/// Segments are trimmed and empty segments dropped, so `" lang//rust/ "`
+/// becomes `lang/rust`. A flat tag is a path with one segment.
+pub fn normalize_tag_path(tag: &str) -> String {
+    tag.split(TAG_PATH_SEPARATOR)
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
 /// Gets or creates a tag by name, returns tag_id
 ///
 /// # Arguments
 ///
-/// * `app` - Tauri application handle
+/// * `pool` - Database connection pool
 /// * `tag_name` - The name of the tag to get or create
 /// * `color` - Optional color for the tag (defaults to #EDEDED if not provided or if tag exists)
 ///
@@ -20,16 +36,14 @@ use tauri::AppHandle;
 ///
 /// Returns `AppError` if database operations fail
 pub async fn get_or_create_tag(
-    app: &AppHandle,
+    pool: &SqlitePool,
     tag_name: &str,
     color: Option<&str>,
 ) -> Result<i64, AppError> {
-    let pool = get_pool(app)?;
-
     // Try to get existing tag
     let result = sqlx::query("SELECT id FROM tags WHERE name = ?")
         .bind(tag_name)
-        .fetch_optional(&pool)
+        .fetch_optional(pool)
         .await?;
 
     if let Some(row) = result {
@@ -41,7 +55,7 @@ pub async fn get_or_create_tag(
     let result = sqlx::query("INSERT INTO tags (name, color) VALUES (?, ?)")
         .bind(tag_name)
         .bind(tag_color)
-        .execute(&pool)
+        .execute(pool)
         .await?;
 
     Ok(result.last_insert_rowid())
@@ -51,7 +65,7 @@ pub async fn get_or_create_tag(
 ///
 /// # Arguments
 ///
-/// * `app` - Tauri application handle
+/// * `pool` - Database connection pool
 /// * `snippet_id` - The ID of the snippet to associate tags with
 /// * `tags` - Slice of tag names to associate
 ///
@@ -63,25 +77,23 @@ pub async fn get_or_create_tag(
 ///
 /// Returns `AppError` if database operations fail
 pub async fn associate_tags(
-    app: &AppHandle,
+    pool: &SqlitePool,
     snippet_id: i64,
     tags: &[String],
 ) -> Result<(), AppError> {
-    let pool = get_pool(app)?;
-
     for tag_name in tags {
-        let tag_name = tag_name.trim();
+        let tag_name = normalize_tag_path(tag_name);
         if tag_name.is_empty() {
             continue;
         }
 
-        let tag_id = get_or_create_tag(app, tag_name, None).await?;
+        let tag_id = get_or_create_tag(pool, &tag_name, None).await?;
 
         // Create snippet-tag association (ignore duplicates)
         sqlx::query("INSERT OR IGNORE INTO snippet_tags (snippet_id, tag_id) VALUES (?, ?)")
             .bind(snippet_id)
             .bind(tag_id)
-            .execute(&pool)
+            .execute(pool)
             .await?;
     }
 
@@ -208,8 +220,462 @@ pub async fn update_tag_color(
     Ok(())
 }
 
+/// Looks up a tag by exact name inside a transaction
+async fn find_tag(tx: &mut Transaction<'_, Sqlite>, name: &str) -> Result<Option<Tag>, AppError> {
+    let row = sqlx::query("SELECT id, name, color FROM tags WHERE name = ?")
+        .bind(name)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+    Ok(row.map(|row| Tag {
+        id: row.get::<i64, _>(0).into(),
+        name: row.get(1),
+        color: row.get(2),
+    }))
+}
+
+/// Looks up a tag that must exist
+async fn require_tag(tx: &mut Transaction<'_, Sqlite>, name: &str) -> Result<Tag, AppError> {
+    find_tag(tx, name)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Tag '{}' not found", name)))
+}
+
+/// Renames a tag, keeping its color and snippets
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `old_name` - Current name of the tag
+/// * `new_name` - New name, normalized as a tag path
+///
+/// # Returns
+///
+/// The renamed tag
+///
+/// # Errors
+///
+/// * `AppError::InvalidInput` - The new name is empty
+/// * `AppError::NotFound` - No tag is named `old_name`
+/// * `AppError::Duplicate` - Another tag already has the new name; merge
+///   the two instead
+pub async fn rename_tag(
+    pool: &SqlitePool,
+    old_name: &str,
+    new_name: &str,
+) -> Result<Tag, AppError> {
+    let new_name = normalize_tag_path(new_name);
+    let new_name = new_name.as_str();
+    if new_name.is_empty() {
+        return Err(AppError::InvalidInput(
+            "Tag name cannot be empty".to_string(),
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+    let mut tag = require_tag(&mut tx, old_name).await?;
+    if tag.name == new_name {
+        return Ok(tag);
+    }
+    if find_tag(&mut tx, new_name).await?.is_some() {
+        return Err(AppError::Duplicate(format!(
+            "Tag '{}' already exists; merge the tags instead",
+            new_name
+        )));
+    }
+
+    sqlx::query("UPDATE tags SET name = ? WHERE id = ?")
+        .bind(new_name)
+        .bind(tag.id.0)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    tag.name = new_name.to_string();
+    Ok(tag)
+}
+
+/// Moves every snippet from one tag to another and deletes the first
+///
+/// Snippets that already carry both tags keep a single association. The
+/// target keeps its own name and color.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `source` - Tag to merge away
+/// * `target` - Tag that takes over the snippets
+///
+/// # Errors
+///
+/// * `AppError::InvalidInput` - Source and target are the same tag
+/// * `AppError::NotFound` - Either tag does not exist
+pub async fn merge_tags(
+    pool: &SqlitePool,
+    source: &str,
+    target: &str,
+) -> Result<TagReassignment, AppError> {
+    if source == target {
+        return Err(AppError::InvalidInput(
+            "Cannot merge a tag into itself".to_string(),
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+    let source = require_tag(&mut tx, source).await?;
+    let target = require_tag(&mut tx, target).await?;
+
+    sqlx::query(
+        "INSERT OR IGNORE INTO snippet_tags (snippet_id, tag_id)
+         SELECT snippet_id, ? FROM snippet_tags WHERE tag_id = ?",
+    )
+    .bind(target.id.0)
+    .bind(source.id.0)
+    .execute(&mut *tx)
+    .await?;
+    let snippets_affected = remove_tag(&mut tx, &source).await?;
+    tx.commit().await?;
+
+    Ok(TagReassignment {
+        removed: source.name,
+        reassigned_to: Some(target.name),
+        snippets_affected,
+    })
+}
+
+/// Deletes a tag, optionally handing its snippets to another tag first
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `name` - Tag to delete
+/// * `reassign_to` - Tag that takes over the snippets; without one the
+///   snippets simply lose the tag
+///
+/// # Errors
+///
+/// * `AppError::InvalidInput` - The tag would be reassigned to itself
+/// * `AppError::NotFound` - Either tag does not exist
+pub async fn delete_tag(
+    pool: &SqlitePool,
+    name: &str,
+    reassign_to: Option<&str>,
+) -> Result<TagReassignment, AppError> {
+    if let Some(target) = reassign_to {
+        return merge_tags(pool, name, target).await;
+    }
+
+    let mut tx = pool.begin().await?;
+    let tag = require_tag(&mut tx, name).await?;
+    let snippets_affected = remove_tag(&mut tx, &tag).await?;
+    tx.commit().await?;
+
+    Ok(TagReassignment {
+        removed: tag.name,
+        reassigned_to: None,
+        snippets_affected,
+    })
+}
+
+/// Deletes a tag and its associations, returning how many snippets had it
+async fn remove_tag(tx: &mut Transaction<'_, Sqlite>, tag: &Tag) -> Result<u64, AppError> {
+    let associations = sqlx::query("DELETE FROM snippet_tags WHERE tag_id = ?")
+        .bind(tag.id.0)
+        .execute(&mut **tx)
+        .await?;
+    sqlx::query("DELETE FROM tags WHERE id = ?")
+        .bind(tag.id.0)
+        .execute(&mut **tx)
+        .await?;
+    Ok(associations.rows_affected())
+}
+
+/// Gets every tag with its snippet count and last use
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+///
+/// # Returns
+///
+/// One entry per tag, sorted by name, including tags no snippet carries
+///
+/// # Errors
+///
+/// Returns `AppError` if database operations fail
+pub async fn get_tag_stats(pool: &SqlitePool) -> Result<Vec<TagStats>, AppError> {
+    let rows = sqlx::query(
+        "SELECT t.id, t.name, t.color, COUNT(st.snippet_id),
+                (SELECT MAX(a.used_at) FROM analytics a
+                 INNER JOIN snippet_tags used ON used.snippet_id = a.snippet_id
+                 WHERE used.tag_id = t.id)
+         FROM tags t
+         LEFT JOIN snippet_tags st ON st.tag_id = t.id
+         GROUP BY t.id
+         ORDER BY t.name",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| TagStats {
+            id: row.get::<i64, _>(0).into(),
+            name: row.get(1),
+            color: row.get(2),
+            snippet_count: row.get(3),
+            last_used: row.get(4),
+        })
+        .collect())
+}
+
+/// Deletes tags that no snippet carries
+///
+/// Tags are left behind when their last snippet is deleted or retagged.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+///
+/// # Returns
+///
+/// Names of the deleted tags, sorted
+///
+/// # Errors
+///
+/// Returns `AppError` if database operations fail
+pub async fn cleanup_orphan_tags(pool: &SqlitePool) -> Result<Vec<String>, AppError> {
+    const ORPHANS: &str = "NOT EXISTS (SELECT 1 FROM snippet_tags st WHERE st.tag_id = tags.id)";
+
+    let mut tx = pool.begin().await?;
+    let removed: Vec<String> = sqlx::query_scalar(&format!(
+        "SELECT name FROM tags WHERE {} ORDER BY name",
+        ORPHANS
+    ))
+    .fetch_all(&mut *tx)
+    .await?;
+    sqlx::query(&format!("DELETE FROM tags WHERE {}", ORPHANS))
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(removed)
+}
+
+/// Tag hierarchy level being assembled
+#[derive(Default)]
+struct TreeLevel {
+    color: Option<String>,
+    snippets: HashSet<i64>,
+    children: BTreeMap<String, TreeLevel>,
+}
+
+impl TreeLevel {
+    /// Converts the level into a node, returning the snippets below it too
+    fn finish(self, name: String, path: String) -> (TagTreeNode, HashSet<i64>) {
+        let snippet_count = self.snippets.len() as i64;
+        let mut all_snippets = self.snippets;
+        let mut children = Vec::new();
+        for (child_name, child) in self.children {
+            let child_path = format!("{}{}{}", path, TAG_PATH_SEPARATOR, child_name);
+            let (node, snippets) = child.finish(child_name, child_path);
+            all_snippets.extend(snippets);
+            children.push(node);
+        }
+
+        let node = TagTreeNode {
+            name,
+            path,
+            color: self.color,
+            snippet_count,
+            total_count: all_snippets.len() as i64,
+            children,
+        };
+        (node, all_snippets)
+    }
+}
+
+/// Builds the tag hierarchy from tags and the snippets carrying them
+///
+/// # Arguments
+///
+/// * `tags` - Tag name, color and the IDs of the snippets carrying it
+///
+/// # Returns
+///
+/// The top-level nodes, sorted by name
+pub fn build_tag_tree(tags: Vec<(String, String, Vec<i64>)>) -> Vec<TagTreeNode> {
+    let mut root = TreeLevel::default();
+    for (name, color, snippets) in tags {
+        let path = normalize_tag_path(&name);
+        if path.is_empty() {
+            continue;
+        }
+        let level = path
+            .split(TAG_PATH_SEPARATOR)
+            .fold(&mut root, |level, segment| {
+                level.children.entry(segment.to_string()).or_default()
+            });
+        // Tags that normalize to the same path share a node
+        level.color.get_or_insert(color);
+        level.snippets.extend(snippets);
+    }
+
+    root.children
+        .into_iter()
+        .map(|(name, level)| level.finish(name.clone(), name).0)
+        .collect()
+}
+
+/// Gets every tag arranged by path
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+///
+/// # Returns
+///
+/// The top-level nodes of the tag hierarchy, sorted by name
+///
+/// # Errors
+///
+/// Returns `AppError` if database operations fail
+pub async fn get_tag_tree(pool: &SqlitePool) -> Result<Vec<TagTreeNode>, AppError> {
+    let rows = sqlx::query(
+        "SELECT t.name, t.color, st.snippet_id
+         FROM tags t
+         LEFT JOIN snippet_tags st ON st.tag_id = t.id
+         ORDER BY t.name",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut tags: Vec<(String, String, Vec<i64>)> = Vec::new();
+    for row in rows {
+        let name: String = row.get(0);
+        let snippet_id: Option<i64> = row.get(2);
+        match tags.last_mut() {
+            Some((last, _, snippets)) if *last == name => snippets.extend(snippet_id),
+            _ => tags.push((name, row.get(1), snippet_id.into_iter().collect())),
+        }
+    }
+
+    Ok(build_tag_tree(tags))
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        for statement in [
+            "CREATE TABLE tags (id INTEGER PRIMARY KEY, name TEXT NOT NULL UNIQUE,
+                color TEXT NOT NULL DEFAULT '#EDEDED')",
+            "CREATE TABLE snippet_tags (snippet_id INTEGER NOT NULL, tag_id INTEGER NOT NULL,
+                PRIMARY KEY (snippet_id, tag_id))",
+            "INSERT INTO tags (id, name, color) VALUES (1, 'js', '#F7DF1E'),
+                (2, 'javascript', '#EDEDED'), (3, 'rust', '#DEA584')",
+            "INSERT INTO snippet_tags VALUES (1, 1), (2, 1), (2, 2), (3, 3)",
+            "CREATE TABLE analytics (id INTEGER PRIMARY KEY, snippet_id INTEGER, used_at INTEGER)",
+            "INSERT INTO analytics (snippet_id, used_at) VALUES (1, 100), (2, 300), (3, 200)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        pool
+    }
+
+    async fn tag_ids_of(pool: &SqlitePool, snippet_id: i64) -> Vec<i64> {
+        sqlx::query_scalar("SELECT tag_id FROM snippet_tags WHERE snippet_id = ? ORDER BY tag_id")
+            .bind(snippet_id)
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rename_tag() {
+        let pool = setup_test_db().await;
+
+        let tag = rename_tag(&pool, "rust", "  Rust ").await.unwrap();
+        assert_eq!(tag.name, "Rust");
+        assert_eq!(tag.color, "#DEA584");
+        assert_eq!(tag_ids_of(&pool, 3).await, vec![3]);
+
+        assert!(matches!(
+            rename_tag(&pool, "js", "javascript").await,
+            Err(AppError::Duplicate(_))
+        ));
+        assert!(matches!(
+            rename_tag(&pool, "go", "golang").await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            rename_tag(&pool, "js", " ").await,
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_merge_tags() {
+        let pool = setup_test_db().await;
+
+        let report = merge_tags(&pool, "js", "javascript").await.unwrap();
+        assert_eq!(
+            report,
+            TagReassignment {
+                removed: "js".to_string(),
+                reassigned_to: Some("javascript".to_string()),
+                snippets_affected: 2,
+            }
+        );
+        // Snippet 2 had both tags and keeps a single association
+        assert_eq!(tag_ids_of(&pool, 1).await, vec![2]);
+        assert_eq!(tag_ids_of(&pool, 2).await, vec![2]);
+
+        let remaining: Vec<String> = sqlx::query_scalar("SELECT name FROM tags ORDER BY name")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, vec!["javascript", "rust"]);
+
+        assert!(matches!(
+            merge_tags(&pool, "rust", "rust").await,
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            merge_tags(&pool, "rust", "go").await,
+            Err(AppError::NotFound(_))
+        ));
+        // A failed merge leaves the source untouched
+        assert_eq!(tag_ids_of(&pool, 3).await, vec![3]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_tag() {
+        let pool = setup_test_db().await;
+
+        let report = delete_tag(&pool, "rust", Some("javascript")).await.unwrap();
+        assert_eq!(report.reassigned_to.as_deref(), Some("javascript"));
+        assert_eq!(tag_ids_of(&pool, 3).await, vec![2]);
+
+        let report = delete_tag(&pool, "js", None).await.unwrap();
+        assert_eq!(report.reassigned_to, None);
+        assert_eq!(report.snippets_affected, 2);
+        assert!(tag_ids_of(&pool, 1).await.is_empty());
+        assert_eq!(tag_ids_of(&pool, 2).await, vec![2]);
+
+        assert!(matches!(
+            delete_tag(&pool, "js", None).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
 
     #[test]
     fn test_tag_name_trimming() {
@@ -217,6 +683,42 @@ mod tests {
         assert_eq!(tag.trim(), "rust");
     }
 
+    #[test]
+    fn test_normalize_tag_path() {
+        assert_eq!(normalize_tag_path(" lang//rust/ async "), "lang/rust/async");
+        assert_eq!(normalize_tag_path("rust"), "rust");
+        assert_eq!(normalize_tag_path(" / "), "");
+    }
+
+    #[test]
+    fn test_build_tag_tree() {
+        let tag = |name: &str, snippets: &[i64]| {
+            (name.to_string(), "#fff".to_string(), snippets.to_vec())
+        };
+        let tree = build_tag_tree(vec![
+            tag("lang/rust", &[1]),
+            tag("lang/rust/async", &[1, 2]),
+            tag("lang/go", &[3]),
+            tag("sql", &[]),
+        ]);
+
+        assert_eq!(tree.len(), 2);
+        let lang = &tree[0];
+        assert_eq!((lang.path.as_str(), lang.color.as_deref()), ("lang", None));
+        assert_eq!((lang.snippet_count, lang.total_count), (0, 3));
+
+        let children: Vec<&str> = lang.children.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(children, vec!["lang/go", "lang/rust"]);
+        let rust = &lang.children[1];
+        assert_eq!((rust.snippet_count, rust.total_count), (1, 2));
+        assert_eq!(rust.children[0].name, "async");
+        assert_eq!(rust.children[0].path, "lang/rust/async");
+
+        assert_eq!(tree[1].path, "sql");
+        assert_eq!(tree[1].color.as_deref(), Some("#fff"));
+        assert!(tree[1].children.is_empty());
+    }
+
     #[test]
     fn test_empty_tag_detection() {
         let empty_tags = vec!["".to_string(), "   ".to_string()];
@@ -224,4 +726,48 @@ mod tests {
             assert!(tag.trim().is_empty());
         }
     }
+
+    #[tokio::test]
+    async fn test_get_tag_stats() {
+        let pool = setup_test_db().await;
+        sqlx::query("INSERT INTO tags (id, name) VALUES (4, 'unused')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let stats = get_tag_stats(&pool).await.unwrap();
+        let summary: Vec<(&str, i64, Option<i64>)> = stats
+            .iter()
+            .map(|s| (s.name.as_str(), s.snippet_count, s.last_used))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("javascript", 1, Some(300)),
+                ("js", 2, Some(300)),
+                ("rust", 1, Some(200)),
+                ("unused", 0, None),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_orphan_tags() {
+        let pool = setup_test_db().await;
+        sqlx::query("DELETE FROM snippet_tags WHERE snippet_id = 2 OR snippet_id = 3")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            cleanup_orphan_tags(&pool).await.unwrap(),
+            vec!["javascript", "rust"]
+        );
+        let remaining: Vec<String> = sqlx::query_scalar("SELECT name FROM tags")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, vec!["js"]);
+        assert!(cleanup_orphan_tags(&pool).await.unwrap().is_empty());
+    }
 }
@@ -0,0 +1,945 @@
+/// Read-only snippet feeds a team publishes.
+///
+/// A feed is a URL serving a Snips JSON export, or a Git repository holding
+/// either a `snips.json` export or plain files. Its snippets are synced into
+/// the library on a schedule, named `<feed>/<name>` and tagged
+/// `team/<feed>`. They are read-only: editing one saves a local fork that
+/// remembers where it came from, and the next sync leaves the fork alone.
+use crate::commands::storage_commands::SnippetExport;
+use crate::services::database::get_pool;
+use crate::services::source_refresh::validate_source_url;
+use crate::services::tags::normalize_tag_path;
+use crate::services::{
+    export_format, gist, language, library_stats, menubar, name_index, snippet_aliases,
+    snippet_parameters,
+};
+use crate::utils::error::AppError;
+use crate::utils::time::current_timestamp;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::process::Command;
+
+/// Subdirectory of the app data directory Git feeds are cloned into
+pub const FEEDS_DIR: &str = "feeds";
+
+/// How often the scheduler looks for feeds that are due
+const CHECK_INTERVAL_SECS: u64 = 10 * 60;
+
+/// Parent of every feed's tag
+const FEED_TAG_PREFIX: &str = "team";
+
+/// Export file read from the root of a Git feed
+const GIT_FEED_EXPORT: &str = "snips.json";
+
+/// Files larger than this are left out of a Git feed without `snips.json`
+const MAX_FILE_BYTES: u64 = 256 * 1024;
+
+/// Most snippets taken from one feed
+const MAX_FEED_SNIPPETS: usize = 2000;
+
+/// Where a feed is published
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedKind {
+    /// A URL serving a Snips JSON export
+    Json,
+    /// A Git repository, cloned into the app data dir
+    Git,
+}
+
+impl FeedKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FeedKind::Json => "json",
+            FeedKind::Git => "git",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "git" => FeedKind::Git,
+            _ => FeedKind::Json,
+        }
+    }
+
+    /// Guesses the kind from a URL: `.git`, `ssh://` and `git@` URLs are
+    /// repositories, anything else a JSON export
+    pub fn detect(url: &str) -> Self {
+        let url = url.trim();
+        if url.ends_with(".git") || url.starts_with("ssh://") || url.starts_with("git@") {
+            FeedKind::Git
+        } else {
+            FeedKind::Json
+        }
+    }
+}
+
+/// A subscribed feed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnippetFeed {
+    pub id: i64,
+    pub name: String,
+    pub url: String,
+    pub kind: FeedKind,
+    pub interval_hours: u32,
+    /// When the feed last synced successfully; `None` until the first sync
+    pub synced_at: Option<i64>,
+    /// Why the last sync failed, cleared by the next successful one
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    /// Snippets currently synced from the feed
+    pub snippet_count: i64,
+}
+
+/// What a sync changed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedSyncReport {
+    pub feed_id: i64,
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    /// Feed snippets left out because a local snippet already has the name
+    pub skipped: Vec<String>,
+}
+
+/// Where a snippet came from
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnippetProvenance {
+    /// The feed a read-only snippet is synced from
+    pub feed_id: Option<i64>,
+    pub feed_name: Option<String>,
+    /// The snippet's name in the feed
+    pub feed_key: Option<String>,
+    /// The feed snippet a local fork was copied from, while it still exists
+    pub forked_from: Option<i64>,
+}
+
+impl SnippetProvenance {
+    /// Whether the snippet is synced from a feed and so cannot be edited
+    pub fn is_read_only(&self) -> bool {
+        self.feed_id.is_some()
+    }
+}
+
+/// Name a feed snippet is stored under
+pub fn feed_snippet_name(feed_name: &str, key: &str) -> String {
+    format!("{}/{}", feed_name, key)
+}
+
+/// Tag every snippet of a feed carries
+pub fn feed_tag(feed_name: &str) -> String {
+    normalize_tag_path(&format!("{}/{}", FEED_TAG_PREFIX, feed_name))
+}
+
+/// Checks a feed URL and returns it trimmed
+///
+/// JSON feeds must be http(s). Git feeds must use `https://`, `ssh://` or
+/// `git@host:path`, so git never fetches unencrypted or runs one of its other
+/// transports; a URL starting with `-` is refused so it cannot be read as a
+/// git option.
+pub fn validate_feed_url(url: &str, kind: FeedKind) -> Result<String, AppError> {
+    let url = url.trim();
+    match kind {
+        FeedKind::Json => validate_source_url(url),
+        FeedKind::Git => {
+            let allowed = ["https://", "ssh://", "git@"]
+                .iter()
+                .any(|scheme| url.starts_with(scheme));
+            if !allowed || url.contains(char::is_whitespace) {
+                return Err(AppError::InvalidInput(format!(
+                    "Git feed must be an https, ssh or git@ URL: {}",
+                    url
+                )));
+            }
+            Ok(url.to_string())
+        }
+    }
+}
+
+/// Reads the snippets of a JSON feed: a Snips export in any supported format
+/// version, including a bare array of exported snippets
+pub fn parse_json_feed(text: &str) -> Result<Vec<SnippetExport>, AppError> {
+    Ok(export_format::parse_export(text)?.snippets)
+}
+
+/// Reads the snippets of a Git checkout
+///
+/// A `snips.json` export at the root wins. Otherwise every text file outside
+/// hidden directories becomes a snippet named after its path. Symlinks are
+/// skipped, since a feed could otherwise point one at any file of the user's.
+pub fn read_git_checkout(dir: &Path, now: i64) -> Result<Vec<SnippetExport>, AppError> {
+    let export = dir.join(GIT_FEED_EXPORT);
+    if std::fs::symlink_metadata(&export).is_ok_and(|metadata| metadata.is_file()) {
+        let text = std::fs::read_to_string(&export).map_err(|e| {
+            AppError::External(format!("Failed to read {}: {}", export.display(), e))
+        })?;
+        return parse_json_feed(&text);
+    }
+
+    let mut snippets = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let entries = std::fs::read_dir(&current).map_err(|e| {
+            AppError::External(format!("Failed to read {}: {}", current.display(), e))
+        })?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            // Not followed: `DirEntry::metadata` describes the link itself
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.file_type().is_symlink() {
+                continue;
+            }
+            if metadata.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            if !metadata.is_file() || metadata.len() > MAX_FILE_BYTES {
+                continue;
+            }
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue; // Binary file
+            };
+            let Ok(relative) = path.strip_prefix(dir) else {
+                continue;
+            };
+            let name = relative
+                .components()
+                .map(|part| part.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            snippets.push(SnippetExport {
+                name,
+                content,
+                description: None,
+                tags: Vec::new(),
+                created_at: now,
+                updated_at: now,
+                ..SnippetExport::default()
+            });
+        }
+    }
+    snippets.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(snippets)
+}
+
+/// Clones a Git feed into `dir`, or updates the existing clone to the remote
+/// default branch
+async fn checkout_git_feed(url: &str, dir: &Path) -> Result<(), AppError> {
+    let mut command = Command::new("git");
+    if dir.join(".git").is_dir() {
+        command
+            .arg("-C")
+            .arg(dir)
+            .args(["fetch", "--depth", "1", "origin", "HEAD"]);
+        run_git(command).await?;
+
+        let mut command = Command::new("git");
+        command
+            .arg("-C")
+            .arg(dir)
+            .args(["reset", "--hard", "--quiet", "FETCH_HEAD"]);
+        run_git(command).await
+    } else {
+        command
+            .args(["clone", "--depth", "1", "--quiet", "--", url])
+            .arg(dir);
+        run_git(command).await
+    }
+}
+
+async fn run_git(mut command: Command) -> Result<(), AppError> {
+    // Never wait on a credentials prompt nobody can see
+    let output = command
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .await
+        .map_err(|e| AppError::External(format!("Failed to run git: {}", e)))?;
+    if !output.status.success() {
+        return Err(AppError::External(format!(
+            "git failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Directory a Git feed is cloned into
+fn checkout_dir(app: &AppHandle, feed_id: i64) -> Result<PathBuf, AppError> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::TauriError(format!("Failed to get app data dir: {}", e)))?
+        .join(FEEDS_DIR);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| AppError::External(format!("Failed to create {}: {}", dir.display(), e)))?;
+    Ok(dir.join(feed_id.to_string()))
+}
+
+/// Subscribes to a feed; it is synced on the next scheduler check
+///
+/// # Errors
+///
+/// * `AppError::InvalidInput` - The name is empty or the URL is not allowed
+/// * `AppError::Duplicate` - A feed with the name or URL already exists
+pub async fn subscribe(
+    pool: &SqlitePool,
+    name: &str,
+    url: &str,
+    kind: Option<FeedKind>,
+    interval_hours: Option<u32>,
+) -> Result<SnippetFeed, AppError> {
+    let name = normalize_tag_path(name);
+    if name.is_empty() {
+        return Err(AppError::InvalidInput(
+            "Feed name cannot be empty".to_string(),
+        ));
+    }
+    let kind = kind.unwrap_or_else(|| FeedKind::detect(url));
+    let url = validate_feed_url(url, kind)?;
+    let interval_hours = interval_hours.unwrap_or(24).max(1);
+
+    let result = sqlx::query(
+        "INSERT INTO snippet_feeds (name, url, kind, interval_hours, created_at)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(&name)
+    .bind(&url)
+    .bind(kind.as_str())
+    .bind(interval_hours)
+    .bind(current_timestamp())
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint failed") {
+            AppError::Duplicate(format!(
+                "Already subscribed to a feed named '{}' or at {}",
+                name, url
+            ))
+        } else {
+            AppError::from(e)
+        }
+    })?;
+
+    get_feed(pool, result.last_insert_rowid()).await
+}
+
+/// Unsubscribes from a feed, removing its snippets
+///
+/// Local forks of its snippets are kept.
+pub async fn unsubscribe(pool: &SqlitePool, feed_id: i64) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM snippets WHERE feed_id = ?")
+        .bind(feed_id)
+        .execute(&mut *tx)
+        .await?;
+    let result = sqlx::query("DELETE FROM snippet_feeds WHERE id = ?")
+        .bind(feed_id)
+        .execute(&mut *tx)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("Feed {} not found", feed_id)));
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+const FEED_COLUMNS: &str = "f.id, f.name, f.url, f.kind, f.interval_hours, f.synced_at,
+    f.last_error, f.created_at,
+    (SELECT COUNT(*) FROM snippets s WHERE s.feed_id = f.id) AS snippet_count";
+
+fn feed_from_row(row: &sqlx::sqlite::SqliteRow) -> SnippetFeed {
+    SnippetFeed {
+        id: row.get("id"),
+        name: row.get("name"),
+        url: row.get("url"),
+        kind: FeedKind::from_db(row.get("kind")),
+        interval_hours: row
+            .get::<i64, _>("interval_hours")
+            .clamp(1, i64::from(u32::MAX)) as u32,
+        synced_at: row.get("synced_at"),
+        last_error: row.get("last_error"),
+        created_at: row.get("created_at"),
+        snippet_count: row.get("snippet_count"),
+    }
+}
+
+/// Lists the subscribed feeds by name
+pub async fn list_feeds(pool: &SqlitePool) -> Result<Vec<SnippetFeed>, AppError> {
+    let rows = sqlx::query(&format!(
+        "SELECT {} FROM snippet_feeds f ORDER BY f.name",
+        FEED_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.iter().map(feed_from_row).collect())
+}
+
+/// Gets one feed
+///
+/// # Errors
+///
+/// * `AppError::NotFound` - The feed does not exist
+pub async fn get_feed(pool: &SqlitePool, feed_id: i64) -> Result<SnippetFeed, AppError> {
+    let row = sqlx::query(&format!(
+        "SELECT {} FROM snippet_feeds f WHERE f.id = ?",
+        FEED_COLUMNS
+    ))
+    .bind(feed_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Feed {} not found", feed_id)))?;
+    Ok(feed_from_row(&row))
+}
+
+/// Where a snippet came from
+///
+/// # Errors
+///
+/// * `AppError::NotFound` - The snippet does not exist
+pub async fn get_provenance(
+    pool: &SqlitePool,
+    snippet_id: i64,
+) -> Result<SnippetProvenance, AppError> {
+    let row = sqlx::query(
+        "SELECT s.feed_id, f.name AS feed_name, s.feed_key, s.forked_from
+         FROM snippets s
+         LEFT JOIN snippet_feeds f ON f.id = s.feed_id
+         WHERE s.id = ?",
+    )
+    .bind(snippet_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("Snippet with id {} not found", snippet_id)))?;
+
+    Ok(SnippetProvenance {
+        feed_id: row.get("feed_id"),
+        feed_name: row.get("feed_name"),
+        feed_key: row.get("feed_key"),
+        forked_from: row.get("forked_from"),
+    })
+}
+
+/// Replaces a snippet's tags inside a feed sync
+async fn set_tags(
+    conn: &mut SqliteConnection,
+    snippet_id: i64,
+    tags: &[String],
+) -> Result<(), AppError> {
+    sqlx::query("DELETE FROM snippet_tags WHERE snippet_id = ?")
+        .bind(snippet_id)
+        .execute(&mut *conn)
+        .await?;
+    for tag in tags {
+        let tag = normalize_tag_path(tag);
+        if tag.is_empty() {
+            continue;
+        }
+        sqlx::query("INSERT OR IGNORE INTO tags (name) VALUES (?)")
+            .bind(&tag)
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query(
+            "INSERT OR IGNORE INTO snippet_tags (snippet_id, tag_id)
+             SELECT ?, id FROM tags WHERE name = ?",
+        )
+        .bind(snippet_id)
+        .bind(&tag)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Columns a sync writes, as stored: content, description, kind, aliases,
+/// parameters and expiry
+type FeedColumns = (
+    String,
+    Option<String>,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<i64>,
+);
+
+/// A JSON array column value, `NULL` when there is nothing in it
+fn json_list<T: Serialize>(items: &[T]) -> Result<Option<String>, AppError> {
+    if items.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(serde_json::to_string(items)?))
+    }
+}
+
+/// Makes a feed's snippets match `snippets`
+///
+/// Snippets new to the feed are added, changed ones updated in place and
+/// ones no longer in the feed removed, all in one transaction. Snippets with
+/// an empty name or content, invalid aliases or parameters, or a name already
+/// seen, are ignored. A snippet the feed marks archived arrives archived;
+/// after that, archiving it is up to the user.
+///
+/// # Returns
+///
+/// What changed
+pub async fn apply_feed(
+    pool: &SqlitePool,
+    feed_id: i64,
+    snippets: Vec<SnippetExport>,
+    now: i64,
+) -> Result<FeedSyncReport, AppError> {
+    let mut tx = pool.begin().await?;
+    let feed_name: String = sqlx::query_scalar("SELECT name FROM snippet_feeds WHERE id = ?")
+        .bind(feed_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Feed {} not found", feed_id)))?;
+    let team_tag = feed_tag(&feed_name);
+
+    let rows = sqlx::query(
+        "SELECT id, feed_key, content, description, kind, aliases, parameters, expires_at
+         FROM snippets WHERE feed_id = ?",
+    )
+    .bind(feed_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    let existing: HashMap<String, (i64, FeedColumns)> = rows
+        .iter()
+        .map(|row| {
+            let columns = (
+                row.get("content"),
+                row.get("description"),
+                row.get("kind"),
+                row.get("aliases"),
+                row.get("parameters"),
+                row.get("expires_at"),
+            );
+            (row.get("feed_key"), (row.get("id"), columns))
+        })
+        .collect();
+
+    let mut report = FeedSyncReport {
+        feed_id,
+        ..FeedSyncReport::default()
+    };
+    let mut seen = HashSet::new();
+    for snippet in snippets.into_iter().take(MAX_FEED_SNIPPETS) {
+        let key = snippet.name.trim().to_string();
+        if key.is_empty() || snippet.content.trim().is_empty() || !seen.insert(key.clone()) {
+            continue;
+        }
+        let Ok(aliases) = snippet_aliases::normalize_aliases(&snippet.aliases) else {
+            continue;
+        };
+        if snippet_parameters::validate_parameters(&snippet.parameters).is_err() {
+            continue;
+        }
+        let name = feed_snippet_name(&feed_name, &key);
+        let language = language::detect_language(&key, &snippet.content, snippet.kind);
+        let columns: FeedColumns = (
+            snippet.content,
+            snippet.description.filter(|d| !d.trim().is_empty()),
+            snippet.kind.as_str().to_string(),
+            json_list(&aliases)?,
+            json_list(&snippet.parameters)?,
+            snippet.expires_at,
+        );
+        let (content, description, kind, aliases, parameters, expires_at) = &columns;
+
+        let snippet_id = match existing.get(&key) {
+            Some((id, stored)) => {
+                if *stored != columns {
+                    sqlx::query(
+                        "UPDATE snippets
+                         SET name = ?, content = ?, description = ?, language = ?, kind = ?,
+                             aliases = ?, parameters = ?, expires_at = ?, updated_at = ?
+                         WHERE id = ?",
+                    )
+                    .bind(&name)
+                    .bind(content)
+                    .bind(description)
+                    .bind(language)
+                    .bind(kind)
+                    .bind(aliases)
+                    .bind(parameters)
+                    .bind(expires_at)
+                    .bind(now)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+                    report.updated += 1;
+                }
+                *id
+            }
+            None => {
+                let taken: bool =
+                    sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM snippets WHERE name = ?)")
+                        .bind(&name)
+                        .fetch_one(&mut *tx)
+                        .await?;
+                if taken {
+                    report.skipped.push(name);
+                    continue;
+                }
+                let result = sqlx::query(
+                    "INSERT INTO snippets
+                        (name, content, description, created_at, updated_at, language, kind,
+                         aliases, parameters, archived, expires_at, feed_id, feed_key)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                )
+                .bind(&name)
+                .bind(content)
+                .bind(description)
+                .bind(snippet.created_at)
+                .bind(now)
+                .bind(language)
+                .bind(kind)
+                .bind(aliases)
+                .bind(parameters)
+                .bind(snippet.archived)
+                .bind(expires_at)
+                .bind(feed_id)
+                .bind(&key)
+                .execute(&mut *tx)
+                .await?;
+                report.added += 1;
+                result.last_insert_rowid()
+            }
+        };
+
+        let mut tags = vec![team_tag.clone()];
+        tags.extend(snippet.tags);
+        set_tags(&mut tx, snippet_id, &tags).await?;
+    }
+
+    for (key, (id, _)) in &existing {
+        if !seen.contains(key) {
+            sqlx::query("DELETE FROM snippets WHERE id = ?")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            report.removed += 1;
+        }
+    }
+
+    sqlx::query("UPDATE snippet_feeds SET synced_at = ?, last_error = NULL WHERE id = ?")
+        .bind(now)
+        .bind(feed_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(report)
+}
+
+/// Downloads a feed and syncs its snippets
+///
+/// A failure is recorded on the feed as `last_error`, so the UI can show why
+/// a feed is stale.
+pub async fn sync_feed(app: &AppHandle, feed_id: i64) -> Result<FeedSyncReport, AppError> {
+    let pool = get_pool(app)?;
+    let feed = get_feed(&pool, feed_id).await?;
+
+    let result = async {
+        let now = current_timestamp();
+        let snippets = match feed.kind {
+            FeedKind::Json => parse_json_feed(&gist::fetch_raw(&feed.url, None).await?)?,
+            FeedKind::Git => {
+                let dir = checkout_dir(app, feed.id)?;
+                checkout_git_feed(&feed.url, &dir).await?;
+                read_git_checkout(&dir, now)?
+            }
+        };
+        apply_feed(&pool, feed.id, snippets, now).await
+    }
+    .await;
+
+    match &result {
+        Ok(report) if report.added + report.updated + report.removed > 0 => {
+            library_stats::invalidate(app).await;
+            name_index::invalidate(app).await;
+            menubar::notify_snippets_changed(app);
+        }
+        Ok(_) => {}
+        Err(e) => {
+            sqlx::query("UPDATE snippet_feeds SET last_error = ? WHERE id = ?")
+                .bind(e.to_string())
+                .bind(feed.id)
+                .execute(&pool)
+                .await?;
+        }
+    }
+    result
+}
+
+/// Removes a feed's snippets and its Git clone
+pub async fn remove_feed(app: &AppHandle, feed_id: i64) -> Result<(), AppError> {
+    let pool = get_pool(app)?;
+    let feed = get_feed(&pool, feed_id).await?;
+    unsubscribe(&pool, feed_id).await?;
+
+    if feed.kind == FeedKind::Git {
+        let dir = checkout_dir(app, feed_id)?;
+        if dir.exists() {
+            if let Err(e) = std::fs::remove_dir_all(&dir) {
+                eprintln!("Failed to remove clone of feed {}: {}", feed.name, e);
+            }
+        }
+    }
+    library_stats::invalidate(app).await;
+    name_index::invalidate(app).await;
+    menubar::notify_snippets_changed(app);
+    Ok(())
+}
+
+/// Syncs every feed whose interval has passed since it last synced
+///
+/// A failing feed is logged and retried on the next check.
+pub async fn sync_due(app: &AppHandle, now: i64) -> Result<Vec<FeedSyncReport>, AppError> {
+    let pool = get_pool(app)?;
+    let due: Vec<i64> = sqlx::query_scalar(
+        "SELECT id FROM snippet_feeds
+         WHERE synced_at IS NULL OR synced_at < ? - interval_hours * 3600
+         ORDER BY id",
+    )
+    .bind(now)
+    .fetch_all(&pool)
+    .await?;
+
+    let mut reports = Vec::new();
+    for feed_id in due {
+        match sync_feed(app, feed_id).await {
+            Ok(report) => reports.push(report),
+            Err(e) => eprintln!("Failed to sync feed {}: {}", feed_id, e),
+        }
+    }
+    Ok(reports)
+}
+
+/// Starts the background loop that syncs due feeds
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = sync_due(&app, current_timestamp()).await {
+                eprintln!("Failed to sync snippet feeds: {}", e);
+            }
+            tokio::time::sleep(Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::database::run_migrations;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+        pool
+    }
+
+    fn export(name: &str, content: &str) -> SnippetExport {
+        SnippetExport {
+            name: name.to_string(),
+            content: content.to_string(),
+            description: None,
+            tags: vec!["shell".to_string()],
+            created_at: 100,
+            updated_at: 100,
+            ..SnippetExport::default()
+        }
+    }
+
+    async fn feed_snippets(pool: &SqlitePool) -> Vec<(String, String)> {
+        sqlx::query_as("SELECT name, content FROM snippets WHERE feed_id IS NOT NULL ORDER BY name")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_feed_kind_detect() {
+        assert_eq!(
+            FeedKind::detect("https://example.com/team.json"),
+            FeedKind::Json
+        );
+        assert_eq!(
+            FeedKind::detect("https://github.com/o/snips.git"),
+            FeedKind::Git
+        );
+        assert_eq!(FeedKind::detect("git@github.com:o/snips"), FeedKind::Git);
+    }
+
+    #[test]
+    fn test_validate_feed_url() {
+        assert!(validate_feed_url("git@github.com:o/r.git", FeedKind::Git).is_ok());
+        assert!(validate_feed_url("--upload-pack=touch /tmp/x", FeedKind::Git).is_err());
+        assert!(validate_feed_url("file:///etc", FeedKind::Git).is_err());
+        assert!(validate_feed_url("http://example.com/r.git", FeedKind::Git).is_err());
+        assert!(validate_feed_url("ext::sh -c touch% /tmp/x", FeedKind::Git).is_err());
+        assert!(validate_feed_url("git@github.com:o/r.git", FeedKind::Json).is_err());
+    }
+
+    #[test]
+    fn test_parse_json_feed() {
+        let export = r#"{"version": "1.0.0", "exported_at": 0, "snippets": [
+            {"name": "a", "content": "x", "description": null, "tags": [],
+             "created_at": 0, "updated_at": 0}]}"#;
+        assert_eq!(parse_json_feed(export).unwrap().len(), 1);
+        let bare = r#"[{"name": "a", "content": "x", "description": null, "tags": [],
+            "created_at": 0, "updated_at": 0}]"#;
+        assert_eq!(parse_json_feed(bare).unwrap().len(), 1);
+        assert!(parse_json_feed("{}").is_err());
+    }
+
+    #[test]
+    fn test_read_git_checkout() {
+        let dir = std::env::temp_dir().join(format!("snips_feed_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("k8s")).unwrap();
+        std::fs::create_dir_all(dir.join(".git")).unwrap();
+        std::fs::write(
+            dir.join("k8s").join("restart.sh"),
+            "kubectl rollout restart",
+        )
+        .unwrap();
+        std::fs::write(dir.join(".git").join("HEAD"), "ref: refs/heads/main").unwrap();
+        std::fs::write(dir.join("binary.bin"), [0xff, 0xfe, 0x00]).unwrap();
+
+        let snippets = read_git_checkout(&dir, 5).unwrap();
+        let names: Vec<&str> = snippets.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["k8s/restart.sh"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_git_checkout_skips_symlinks() {
+        let dir = std::env::temp_dir().join(format!("snips_feed_links_{}", std::process::id()));
+        let outside = dir.with_extension("secret");
+        std::fs::create_dir_all(dir.join("notes")).unwrap();
+        std::fs::write(&outside, "ghp_secret").unwrap();
+        std::fs::write(dir.join("notes").join("todo.md"), "- [ ] ship").unwrap();
+        std::os::unix::fs::symlink(&outside, dir.join("key.txt")).unwrap();
+        std::os::unix::fs::symlink(std::env::temp_dir(), dir.join("tmp")).unwrap();
+
+        let snippets = read_git_checkout(&dir, 5).unwrap();
+        let names: Vec<&str> = snippets.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["notes/todo.md"]);
+
+        // A linked export is not read either
+        std::os::unix::fs::symlink(&outside, dir.join(GIT_FEED_EXPORT)).unwrap();
+        let snippets = read_git_checkout(&dir, 5).unwrap();
+        assert!(snippets.iter().all(|s| s.content != "ghp_secret"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        std::fs::remove_file(&outside).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_apply_feed_adds_updates_and_removes() {
+        let pool = setup_test_db().await;
+        let feed = subscribe(
+            &pool,
+            "Platform",
+            "https://example.com/team.json",
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(feed.kind, FeedKind::Json);
+
+        let report = apply_feed(
+            &pool,
+            feed.id,
+            vec![
+                export("deploy", "v1"),
+                export("logs", "tail"),
+                export("deploy", "dup"),
+            ],
+            200,
+        )
+        .await
+        .unwrap();
+        assert_eq!((report.added, report.updated, report.removed), (2, 0, 0));
+
+        let report = apply_feed(&pool, feed.id, vec![export("deploy", "v2")], 300)
+            .await
+            .unwrap();
+        assert_eq!((report.added, report.updated, report.removed), (0, 1, 1));
+        assert_eq!(
+            feed_snippets(&pool).await,
+            [("Platform/deploy".to_string(), "v2".to_string())]
+        );
+
+        let tags: Vec<String> = sqlx::query_scalar(
+            "SELECT t.name FROM tags t JOIN snippet_tags st ON st.tag_id = t.id ORDER BY t.name",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(tags, ["shell", "team/Platform"]);
+
+        let feed = get_feed(&pool, feed.id).await.unwrap();
+        assert_eq!((feed.synced_at, feed.snippet_count), (Some(300), 1));
+    }
+
+    #[tokio::test]
+    async fn test_apply_feed_skips_local_names_and_unsubscribe_keeps_forks() {
+        let pool = setup_test_db().await;
+        let feed = subscribe(&pool, "ops", "https://example.com/ops.json", None, None)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO snippets (name, content, created_at, updated_at) VALUES ('ops/a', 'mine', 0, 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let report = apply_feed(
+            &pool,
+            feed.id,
+            vec![export("a", "theirs"), export("b", "x")],
+            1,
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.skipped, ["ops/a"]);
+
+        let feed_id: i64 = sqlx::query_scalar("SELECT id FROM snippets WHERE name = 'ops/b'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(get_provenance(&pool, feed_id).await.unwrap().is_read_only());
+        sqlx::query(
+            "INSERT INTO snippets (name, content, created_at, updated_at, forked_from)
+             VALUES ('b', 'edited', 0, 0, ?)",
+        )
+        .bind(feed_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        unsubscribe(&pool, feed.id).await.unwrap();
+        let names: Vec<String> = sqlx::query_scalar("SELECT name FROM snippets ORDER BY name")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(names, ["b", "ops/a"]);
+        assert!(list_feeds(&pool).await.unwrap().is_empty());
+    }
+}
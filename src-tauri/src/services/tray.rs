@@ -0,0 +1,401 @@
+/// Tray icon, title and tooltip.
+///
+/// Everything the tray shows besides its menu is derived from one
+/// `TrayStatus` so concurrent updates cannot leave the icon and tooltip
+/// disagreeing. Today's usage count is kept current from the analytics
+/// table; the selection count comes from the search window and backup
+/// failures from the backup scheduler. Windows and most Linux trays show
+/// neither title nor tooltip at a glance, so the count and a failed backup
+/// are also drawn onto the icon.
+use crate::services::database::get_pool;
+use crate::services::menubar::SNIPPETS_CHANGED_EVENT;
+use crate::utils::error::AppError;
+use crate::utils::time::current_timestamp;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::image::Image;
+use tauri::{AppHandle, Listener, Manager};
+
+/// ID the tray icon is registered under
+pub const TRAY_ID: &str = "main-tray";
+
+/// Tooltip when there is nothing to report
+const DEFAULT_TOOLTIP: &str = "Snips - Snippet Manager";
+
+/// Whether today's usage count is drawn onto the icon; macOS shows it as
+/// the menu bar title instead
+const USAGE_BADGE: bool = !cfg!(target_os = "macos");
+
+/// Badge behind today's usage count
+const BADGE_COLOR: [u8; 4] = [0x25, 0x63, 0xEB, 0xFF];
+
+/// Digits of today's usage count
+const BADGE_TEXT_COLOR: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
+
+/// Dot shown after the last automatic backup failed
+const BACKUP_FAILED_COLOR: [u8; 4] = [0xDC, 0x26, 0x26, 0xFF];
+
+/// 3x5 pixel glyphs for the usage badge, one row per entry, high bit left
+const GLYPHS: &[(char, [u8; 5])] = &[
+    ('0', [0b111, 0b101, 0b101, 0b101, 0b111]),
+    ('1', [0b010, 0b110, 0b010, 0b010, 0b111]),
+    ('2', [0b111, 0b001, 0b111, 0b100, 0b111]),
+    ('3', [0b111, 0b001, 0b111, 0b001, 0b111]),
+    ('4', [0b101, 0b101, 0b111, 0b001, 0b001]),
+    ('5', [0b111, 0b100, 0b111, 0b001, 0b111]),
+    ('6', [0b111, 0b100, 0b111, 0b101, 0b111]),
+    ('7', [0b111, 0b001, 0b010, 0b010, 0b010]),
+    ('8', [0b111, 0b101, 0b111, 0b101, 0b111]),
+    ('9', [0b111, 0b101, 0b111, 0b001, 0b111]),
+    ('+', [0b000, 0b010, 0b111, 0b010, 0b000]),
+];
+
+/// What the tray icon reports
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrayStatus {
+    /// Snippet uses since midnight UTC, the day boundary of the usage trends
+    pub uses_today: i64,
+    /// Snippets selected in the search window
+    pub selected: u32,
+    /// Whether the last automatic backup failed
+    pub backup_failed: bool,
+}
+
+/// Current tray status
+#[derive(Default)]
+pub struct TrayState(Mutex<TrayStatus>);
+
+/// Tooltip describing a status
+pub fn tooltip(status: &TrayStatus) -> String {
+    let mut parts = Vec::new();
+    if status.backup_failed {
+        parts.push("last backup failed".to_string());
+    }
+    if status.selected > 0 {
+        parts.push(format!("{} selected", status.selected));
+    }
+    if status.uses_today > 0 {
+        parts.push(format!("{} uses today", status.uses_today));
+    }
+
+    if parts.is_empty() {
+        DEFAULT_TOOLTIP.to_string()
+    } else {
+        format!("Snips - {}", parts.join(", "))
+    }
+}
+
+/// Text shown next to the icon, where the platform supports it
+///
+/// macOS draws it in the menu bar and Linux appindicators beside the icon;
+/// elsewhere the count only appears in the tooltip.
+pub fn title(status: &TrayStatus) -> Option<String> {
+    (status.uses_today > 0).then(|| status.uses_today.to_string())
+}
+
+/// Text of the usage badge: the count up to 99, then `99+`
+pub fn badge_label(status: &TrayStatus) -> Option<String> {
+    match status.uses_today {
+        0 => None,
+        1..=99 => Some(status.uses_today.to_string()),
+        _ => Some("99+".to_string()),
+    }
+}
+
+/// Fills the pixels of RGBA data for which `inside(x, y)` holds
+fn fill(
+    rgba: &mut [u8],
+    width: u32,
+    height: u32,
+    color: [u8; 4],
+    inside: impl Fn(f32, f32) -> bool,
+) {
+    for y in 0..height {
+        for x in 0..width {
+            if inside(x as f32 + 0.5, y as f32 + 0.5) {
+                let offset = ((y * width + x) * 4) as usize;
+                rgba[offset..offset + 4].copy_from_slice(&color);
+            }
+        }
+    }
+}
+
+/// Draws a filled circle into RGBA pixels
+fn draw_circle(
+    rgba: &mut [u8],
+    width: u32,
+    height: u32,
+    center: (f32, f32),
+    radius: f32,
+    color: [u8; 4],
+) {
+    fill(rgba, width, height, color, |x, y| {
+        let (dx, dy) = (x - center.0, y - center.1);
+        dx * dx + dy * dy <= radius * radius
+    });
+}
+
+/// Draws `text` in `GLYPHS`, centered on `center` and at most `max_size`
+/// wide or tall
+fn draw_text(
+    rgba: &mut [u8],
+    width: u32,
+    height: u32,
+    text: &str,
+    center: (f32, f32),
+    max_size: f32,
+    color: [u8; 4],
+) {
+    let glyphs: Vec<[u8; 5]> = text
+        .chars()
+        .filter_map(|c| GLYPHS.iter().find(|(glyph, _)| *glyph == c))
+        .map(|(_, rows)| *rows)
+        .collect();
+    if glyphs.is_empty() {
+        return;
+    }
+    // One blank column between glyphs
+    let columns = glyphs.len() * 4 - 1;
+    let scale = (max_size / columns as f32)
+        .min(max_size / 5.0)
+        .floor()
+        .max(1.0);
+    let left = center.0 - columns as f32 * scale / 2.0;
+    let top = center.1 - 5.0 * scale / 2.0;
+
+    fill(rgba, width, height, color, |x, y| {
+        let column = ((x - left) / scale).floor();
+        let row = ((y - top) / scale).floor();
+        if column < 0.0 || row < 0.0 || row >= 5.0 || column >= columns as f32 {
+            return false;
+        }
+        let (column, row) = (column as usize, row as usize);
+        let bit = column % 4;
+        bit < 3 && glyphs[column / 4][row] & (0b100 >> bit) != 0
+    });
+}
+
+/// Renders the tray icon for a status from the base icon's pixels
+///
+/// With `usage_badge`, today's usage count is drawn in a badge in the
+/// bottom-right corner. A failed backup adds a red dot in the top-right one.
+pub fn render_icon(
+    base: &[u8],
+    width: u32,
+    height: u32,
+    status: &TrayStatus,
+    usage_badge: bool,
+) -> Vec<u8> {
+    let mut rgba = base.to_vec();
+    let size = width.min(height) as f32;
+
+    if let Some(label) = badge_label(status).filter(|_| usage_badge) {
+        let radius = size * 0.3;
+        let center = (width as f32 - radius, height as f32 - radius);
+        draw_circle(&mut rgba, width, height, center, radius, BADGE_COLOR);
+        draw_text(
+            &mut rgba,
+            width,
+            height,
+            &label,
+            center,
+            radius * 1.3,
+            BADGE_TEXT_COLOR,
+        );
+    }
+    if status.backup_failed {
+        let radius = size * 0.2;
+        let center = (width as f32 - radius, radius);
+        draw_circle(
+            &mut rgba,
+            width,
+            height,
+            center,
+            radius,
+            BACKUP_FAILED_COLOR,
+        );
+    }
+    rgba
+}
+
+/// Pushes the current status to the tray icon
+fn apply(app: &AppHandle) -> Result<(), AppError> {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return Ok(());
+    };
+    let Some(state) = app.try_state::<TrayState>() else {
+        return Ok(());
+    };
+    let status = state
+        .0
+        .lock()
+        .map_err(|e| AppError::Unknown(e.to_string()))?
+        .clone();
+
+    let to_error = |e: tauri::Error| AppError::TauriError(e.to_string());
+    let base =
+        Image::from_bytes(include_bytes!("../../icons/icon-menubar.png")).map_err(to_error)?;
+    let rgba = render_icon(
+        base.rgba(),
+        base.width(),
+        base.height(),
+        &status,
+        USAGE_BADGE,
+    );
+    tray.set_icon(Some(Image::new_owned(rgba, base.width(), base.height())))
+        .map_err(to_error)?;
+    tray.set_title(title(&status)).map_err(to_error)?;
+    tray.set_tooltip(Some(tooltip(&status))).map_err(to_error)?;
+    Ok(())
+}
+
+/// Changes the status and updates the tray icon
+fn update(app: &AppHandle, change: impl FnOnce(&mut TrayStatus)) -> Result<(), AppError> {
+    if let Some(state) = app.try_state::<TrayState>() {
+        let mut status = state
+            .0
+            .lock()
+            .map_err(|e| AppError::Unknown(e.to_string()))?;
+        change(&mut *status);
+    }
+    apply(app)
+}
+
+/// Shows how many snippets are selected in the search window
+pub fn set_selected_count(app: &AppHandle, count: u32) -> Result<(), AppError> {
+    update(app, |status| status.selected = count)
+}
+
+/// Marks whether the last automatic backup failed
+pub fn set_backup_failed(app: &AppHandle, failed: bool) -> Result<(), AppError> {
+    update(app, |status| status.backup_failed = failed)
+}
+
+/// Start of the UTC day containing `timestamp`
+fn day_start(timestamp: i64) -> i64 {
+    timestamp - timestamp.rem_euclid(24 * 3600)
+}
+
+/// Counts snippet uses at or after `since`
+///
+/// # Errors
+///
+/// Returns `AppError` if the analytics table cannot be read
+pub async fn count_uses_since(pool: &SqlitePool, since: i64) -> Result<i64, AppError> {
+    Ok(
+        sqlx::query_scalar("SELECT COUNT(*) FROM analytics WHERE used_at >= ?")
+            .bind(since)
+            .fetch_one(pool)
+            .await?,
+    )
+}
+
+/// Recounts today's uses and updates the tray icon
+pub async fn refresh_usage(app: &AppHandle) -> Result<(), AppError> {
+    let pool = get_pool(app)?;
+    let uses_today = count_uses_since(&pool, day_start(current_timestamp())).await?;
+    update(app, |status| status.uses_today = uses_today)
+}
+
+/// Keeps today's usage count current
+///
+/// Recounts now, whenever snippets change, and at each UTC midnight so
+/// the count starts over without waiting for the next use.
+pub fn start(app: &AppHandle) {
+    let refresh = |app: AppHandle| {
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = refresh_usage(&app).await {
+                eprintln!("Failed to refresh tray usage count: {}", e);
+            }
+        });
+    };
+
+    refresh(app.clone());
+    let handle = app.clone();
+    app.listen_any(SNIPPETS_CHANGED_EVENT, move |_| refresh(handle.clone()));
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let now = current_timestamp();
+            let next_day = day_start(now) + 24 * 3600;
+            tokio::time::sleep(Duration::from_secs((next_day - now) as u64)).await;
+            if let Err(e) = refresh_usage(&handle).await {
+                eprintln!("Failed to reset tray usage count: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tooltip_and_title() {
+        let idle = TrayStatus::default();
+        assert_eq!(tooltip(&idle), DEFAULT_TOOLTIP);
+        assert_eq!(title(&idle), None);
+
+        let busy = TrayStatus {
+            uses_today: 12,
+            selected: 2,
+            backup_failed: true,
+        };
+        assert_eq!(
+            tooltip(&busy),
+            "Snips - last backup failed, 2 selected, 12 uses today"
+        );
+        assert_eq!(title(&busy).as_deref(), Some("12"));
+    }
+
+    #[test]
+    fn test_badge_label() {
+        let label = |uses_today| {
+            badge_label(&TrayStatus {
+                uses_today,
+                ..TrayStatus::default()
+            })
+        };
+        assert_eq!(label(0), None);
+        assert_eq!(label(42).as_deref(), Some("42"));
+        assert_eq!(label(150).as_deref(), Some("99+"));
+    }
+
+    #[test]
+    fn test_render_icon() {
+        let base = [10u8, 20, 30, 255].repeat(20 * 20);
+        let pixel = |rgba: &[u8], x: usize, y: usize| rgba[(y * 20 + x) * 4..][..4].to_vec();
+        assert_eq!(
+            render_icon(&base, 20, 20, &TrayStatus::default(), true),
+            base
+        );
+
+        let used = TrayStatus {
+            uses_today: 7,
+            ..TrayStatus::default()
+        };
+        assert_eq!(render_icon(&base, 20, 20, &used, false), base);
+        let badged = render_icon(&base, 20, 20, &used, true);
+        // The badge covers the bottom-right corner, with the digit in white
+        assert!([BADGE_COLOR.to_vec(), BADGE_TEXT_COLOR.to_vec()].contains(&pixel(&badged, 14, 14)));
+        assert!(badged.chunks_exact(4).any(|p| p == BADGE_TEXT_COLOR));
+        assert_eq!(pixel(&badged, 0, 0), pixel(&base, 0, 0));
+
+        let failed = TrayStatus {
+            backup_failed: true,
+            ..TrayStatus::default()
+        };
+        let dotted = render_icon(&base, 20, 20, &failed, true);
+        assert_eq!(pixel(&dotted, 17, 2), BACKUP_FAILED_COLOR);
+        assert_eq!(pixel(&dotted, 2, 17), pixel(&base, 2, 17));
+    }
+
+    #[test]
+    fn test_day_start() {
+        assert_eq!(day_start(86_400 * 3 + 5), 86_400 * 3);
+        assert_eq!(day_start(86_400 * 3), 86_400 * 3);
+    }
+}
@@ -0,0 +1,322 @@
+use crate::commands::storage_commands::SnippetExport;
+use crate::services::language::language_id_for_tag;
+use serde_json::{Map, Value};
+
+/// Serializes snippets as a VS Code `.code-snippets` document
+///
+/// Each snippet becomes an entry keyed by its name with a `prefix` derived
+/// from the name, the content split into a `body` line array and the
+/// description. Language tags become the `scope`. `$` and `\` in the content
+/// are escaped so VS Code inserts the text literally instead of treating it
+/// as tab stops.
+pub fn snippets_to_vscode(snippets: &[SnippetExport]) -> Map<String, Value> {
+    let mut document = Map::new();
+
+    for snippet in snippets {
+        let mut entry = Map::new();
+
+        let mut scopes: Vec<&str> = Vec::new();
+        for id in snippet
+            .tags
+            .iter()
+            .filter_map(|tag| language_id_for_tag(tag))
+        {
+            if !scopes.contains(&id) {
+                scopes.push(id);
+            }
+        }
+        if !scopes.is_empty() {
+            entry.insert("scope".to_string(), Value::from(scopes.join(",")));
+        }
+
+        entry.insert("prefix".to_string(), Value::from(prefix_for(&snippet.name)));
+        let body: Vec<Value> = escape_body(&snippet.content)
+            .split('\n')
+            .map(Value::from)
+            .collect();
+        entry.insert("body".to_string(), Value::Array(body));
+        if let Some(description) = snippet.description.as_deref().filter(|d| !d.is_empty()) {
+            entry.insert("description".to_string(), Value::from(description));
+        }
+
+        document.insert(snippet.name.clone(), Value::Object(entry));
+    }
+
+    document
+}
+
+/// Parses a VS Code snippets file into snippets
+///
+/// Accepts the JSON-with-comments dialect VS Code uses (line and block
+/// comments, trailing commas). Entries without a body are skipped.
+///
+/// # Arguments
+///
+/// * `text` - Contents of a `.code-snippets` or language snippets file
+/// * `file_language` - Language of a per-language file (e.g. `rust` for
+///   `rust.json`), used as a tag when an entry has no `scope`
+/// * `now` - Timestamp assigned to the imported snippets
+///
+/// # Errors
+///
+/// Returns an error message if the file is not a JSON object
+pub fn vscode_to_snippets(
+    text: &str,
+    file_language: Option<&str>,
+    now: i64,
+) -> Result<Vec<SnippetExport>, String> {
+    let document: Value = serde_json::from_str(&strip_jsonc(text))
+        .map_err(|e| format!("Invalid VS Code snippets file: {}", e))?;
+    let Value::Object(entries) = document else {
+        return Err("Invalid VS Code snippets file: expected an object".to_string());
+    };
+
+    let mut snippets = Vec::new();
+    for (key, entry) in entries {
+        let Value::Object(entry) = entry else {
+            continue;
+        };
+
+        let content = match entry.get("body") {
+            Some(Value::String(body)) => unescape_body(body),
+            Some(Value::Array(lines)) => {
+                let lines: Vec<&str> = lines.iter().filter_map(Value::as_str).collect();
+                unescape_body(&lines.join("\n"))
+            }
+            _ => continue,
+        };
+        if content.trim().is_empty() {
+            continue;
+        }
+
+        let name = if key.trim().is_empty() {
+            match entry.get("prefix") {
+                Some(Value::String(prefix)) => prefix.clone(),
+                Some(Value::Array(prefixes)) => prefixes
+                    .first()
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                _ => continue,
+            }
+        } else {
+            key
+        };
+
+        let description = match entry.get("description") {
+            Some(Value::String(description)) if !description.is_empty() => {
+                Some(description.clone())
+            }
+            Some(Value::Array(lines)) => {
+                let lines: Vec<&str> = lines.iter().filter_map(Value::as_str).collect();
+                (!lines.is_empty()).then(|| lines.join("\n"))
+            }
+            _ => None,
+        };
+
+        let mut tags: Vec<String> = Vec::new();
+        match entry.get("scope").and_then(Value::as_str) {
+            Some(scope) => {
+                for tag in scope.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                    if !tags.iter().any(|existing| existing == tag) {
+                        tags.push(tag.to_string());
+                    }
+                }
+            }
+            None => tags.extend(file_language.map(str::to_string)),
+        }
+
+        snippets.push(SnippetExport {
+            name: name.trim().to_string(),
+            content,
+            description,
+            tags,
+            created_at: now,
+            updated_at: now,
+            ..SnippetExport::default()
+        });
+    }
+
+    Ok(snippets)
+}
+
+/// Builds a short kebab-case trigger prefix from a snippet name
+fn prefix_for(name: &str) -> String {
+    let mut prefix = String::new();
+    for c in name.trim().chars() {
+        if c.is_alphanumeric() {
+            prefix.extend(c.to_lowercase());
+        } else if !prefix.is_empty() && !prefix.ends_with('-') {
+            prefix.push('-');
+        }
+    }
+    let prefix = prefix.trim_end_matches('-');
+    if prefix.is_empty() {
+        "snippet".to_string()
+    } else {
+        prefix.to_string()
+    }
+}
+
+fn escape_body(content: &str) -> String {
+    content.replace('\\', "\\\\").replace('$', "\\$")
+}
+
+/// Removes the escapes VS Code applies to `$`, `}` and `\`; placeholders such
+/// as `${1:name}` are kept as written
+fn unescape_body(body: &str) -> String {
+    let mut out = String::with_capacity(body.len());
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(&next) = chars.peek() {
+                if matches!(next, '$' | '}' | '\\') {
+                    out.push(next);
+                    chars.next();
+                    continue;
+                }
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Strips `//` and `/* */` comments and trailing commas outside strings
+fn strip_jsonc(text: &str) -> String {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    out.push(escaped);
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = '\0';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            (']' | '}', _) => {
+                let trimmed = out.trim_end().len();
+                if out[..trimmed].ends_with(',') {
+                    out.truncate(trimmed - 1);
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(name: &str, content: &str, tags: &[&str]) -> SnippetExport {
+        SnippetExport {
+            name: name.to_string(),
+            content: content.to_string(),
+            description: Some("A snippet".to_string()),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            created_at: 1,
+            updated_at: 2,
+            ..SnippetExport::default()
+        }
+    }
+
+    #[test]
+    fn test_export_maps_language_tags_to_scope() {
+        let snippets = vec![snippet(
+            "Print Env",
+            "echo $HOME\nprintf '\\n'",
+            &["bash", "sh", "ops"],
+        )];
+        let document = snippets_to_vscode(&snippets);
+        let entry = &document["Print Env"];
+
+        assert_eq!(entry["scope"], "shellscript");
+        assert_eq!(entry["prefix"], "print-env");
+        assert_eq!(entry["body"][0], "echo \\$HOME");
+        assert_eq!(entry["body"][1], "printf '\\\\n'");
+        assert_eq!(entry["description"], "A snippet");
+    }
+
+    #[test]
+    fn test_round_trip_preserves_content() {
+        let original = snippet("Regex", "match \\d+ and ${var}\n}", &["rust"]);
+        let document = Value::Object(snippets_to_vscode(std::slice::from_ref(&original)));
+        let parsed = vscode_to_snippets(&document.to_string(), None, 5).unwrap();
+
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].name, "Regex");
+        assert_eq!(parsed[0].content, original.content);
+        assert_eq!(parsed[0].tags, vec!["rust"]);
+        assert_eq!(parsed[0].created_at, 5);
+    }
+
+    #[test]
+    fn test_import_jsonc_with_placeholders() {
+        let text = r#"{
+            // Line comment
+            "Print to console": {
+                "scope": "javascript, typescript",
+                "prefix": ["log", "cl"],
+                "body": ["console.log('${1:msg}');", "$2"],
+                "description": "Log output", /* trailing */
+            },
+            "": { "prefix": "todo", "body": "// TODO: \\$x" },
+            "Broken": { "prefix": "x" },
+        }"#;
+        let parsed = vscode_to_snippets(text, Some("markdown"), 0).unwrap();
+        assert_eq!(parsed.len(), 2);
+
+        let console = parsed
+            .iter()
+            .find(|s| s.name == "Print to console")
+            .unwrap();
+        assert_eq!(console.content, "console.log('${1:msg}');\n$2");
+        assert_eq!(console.tags, vec!["javascript", "typescript"]);
+        assert_eq!(console.description.as_deref(), Some("Log output"));
+
+        let todo = parsed.iter().find(|s| s.name == "todo").unwrap();
+        assert_eq!(todo.content, "// TODO: $x");
+        assert_eq!(todo.tags, vec!["markdown"]);
+    }
+
+    #[test]
+    fn test_import_rejects_non_object() {
+        assert!(vscode_to_snippets("[]", None, 0).is_err());
+        assert!(vscode_to_snippets("not json", None, 0).is_err());
+    }
+}
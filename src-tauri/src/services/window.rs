@@ -1,6 +1,12 @@
 use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize, WebviewWindow};
 
+use crate::models::{QuickAddFallback, Theme};
+use crate::services::database::get_pool;
+use crate::services::focus::{FocusBackend, FocusRestoreReport, FocusState};
+use crate::services::settings::SettingsService;
+use crate::services::{hyprland, overlay_placement, quick_windows, window_layout};
 use crate::utils::error::AppError;
+use serde::Serialize;
 
 /// Window labels used in the application
 pub const SEARCH_WINDOW_LABEL: &str = "search";
@@ -8,6 +14,45 @@ pub const MANAGEMENT_WINDOW_LABEL: &str = "management";
 pub const QUICK_ADD_WINDOW_LABEL: &str = "quick-add";
 pub const SETTINGS_WINDOW_LABEL: &str = "settings";
 
+/// Overlay window titles; the search window's is set in tauri.conf.json
+pub const SEARCH_WINDOW_TITLE: &str = "Snips";
+pub const QUICK_ADD_WINDOW_TITLE: &str = "Quick Add Snippet";
+
+/// Logical sizes windows open with until the user resizes them
+const MANAGEMENT_WINDOW_SIZE: (f64, f64) = (1000.0, 700.0);
+const QUICK_ADD_WINDOW_SIZE: (f64, f64) = (650.0, 700.0);
+const SETTINGS_WINDOW_SIZE: (f64, f64) = (1000.0, 700.0);
+
+/// Default size of a window whose geometry is remembered
+///
+/// The search overlay is placed afresh every time it opens, so it has none.
+fn default_window_size(label: &str) -> Option<(f64, f64)> {
+    match label {
+        MANAGEMENT_WINDOW_LABEL => Some(MANAGEMENT_WINDOW_SIZE),
+        QUICK_ADD_WINDOW_LABEL => Some(QUICK_ADD_WINDOW_SIZE),
+        SETTINGS_WINDOW_LABEL => Some(SETTINGS_WINDOW_SIZE),
+        _ => None,
+    }
+}
+
+/// Whether the compositor decides where windows go
+///
+/// Wayland does not let clients place their windows or learn where they
+/// are. GTK running through XWayland (`GDK_BACKEND=x11`) still can.
+pub fn compositor_controls_positioning() -> bool {
+    let set = |name: &str| std::env::var_os(name).is_some_and(|v| !v.is_empty());
+    cfg!(target_os = "linux")
+        && set("WAYLAND_DISPLAY")
+        && std::env::var("GDK_BACKEND").map_or(true, |backend| !backend.contains("x11"))
+}
+
+/// Whether a failure to read or set a window position is a compositor limitation
+///
+/// Such failures are expected; the window stays where the compositor put it.
+pub fn should_ignore_positioning_error(error: &tauri::Error) -> bool {
+    compositor_controls_positioning() || error.to_string().to_lowercase().contains("not supported")
+}
+
 /// Gets the search window handle
 pub fn get_search_window(app: &AppHandle) -> Result<WebviewWindow, AppError> {
     app.get_webview_window(SEARCH_WINDOW_LABEL)
@@ -27,7 +72,7 @@ pub fn get_or_create_management_window(app: &AppHandle) -> Result<WebviewWindow,
         tauri::WebviewUrl::App("index.html".into()),
     )
     .title("Snips - Management")
-    .inner_size(1000.0, 700.0)
+    .inner_size(MANAGEMENT_WINDOW_SIZE.0, MANAGEMENT_WINDOW_SIZE.1)
     .center()
     .resizable(true)
     .visible(false)
@@ -35,6 +80,7 @@ pub fn get_or_create_management_window(app: &AppHandle) -> Result<WebviewWindow,
     .decorations(true)
     .build()
     .map_err(|e| AppError::TauriError(e.to_string()))?;
+    window_layout::track(&window)?;
 
     Ok(window)
 }
@@ -51,8 +97,8 @@ pub fn get_or_create_quick_add_window(app: &AppHandle) -> Result<WebviewWindow,
         QUICK_ADD_WINDOW_LABEL,
         tauri::WebviewUrl::App("index.html".into()),
     )
-    .title("Quick Add Snippet")
-    .inner_size(650.0, 700.0)
+    .title(QUICK_ADD_WINDOW_TITLE)
+    .inner_size(QUICK_ADD_WINDOW_SIZE.0, QUICK_ADD_WINDOW_SIZE.1)
     .center()
     .resizable(false)
     .visible(false)
@@ -61,6 +107,8 @@ pub fn get_or_create_quick_add_window(app: &AppHandle) -> Result<WebviewWindow,
     .decorations(true)
     .build()
     .map_err(|e| AppError::TauriError(e.to_string()))?;
+    window_layout::track(&window)?;
+    quick_windows::attach(&window);
 
     Ok(window)
 }
@@ -78,7 +126,7 @@ pub fn get_or_create_settings_window(app: &AppHandle) -> Result<WebviewWindow, A
         tauri::WebviewUrl::App("index.html".into()),
     )
     .title("Snips - Settings")
-    .inner_size(1000.0, 700.0)
+    .inner_size(SETTINGS_WINDOW_SIZE.0, SETTINGS_WINDOW_SIZE.1)
     .center()
     .resizable(true)
     .visible(false)
@@ -86,6 +134,7 @@ pub fn get_or_create_settings_window(app: &AppHandle) -> Result<WebviewWindow, A
     .decorations(true)
     .build()
     .map_err(|e| AppError::TauriError(e.to_string()))?;
+    window_layout::track(&window)?;
 
     Ok(window)
 }
@@ -98,11 +147,49 @@ pub fn show_window(window: &WebviewWindow) -> Result<(), AppError> {
     window
         .set_focus()
         .map_err(|e| AppError::TauriError(e.to_string()))?;
+    if FocusBackend::detect() == FocusBackend::Hyprland {
+        present_on_hyprland(window);
+    }
     Ok(())
 }
 
-/// Hides a window
+/// Asks Hyprland to bring a shown window to the active workspace and focus it
+///
+/// Hyprland ignores `set_focus` for windows on other workspaces.
+fn present_on_hyprland(window: &WebviewWindow) {
+    let title = match window.title() {
+        Ok(title) => title,
+        Err(e) => {
+            eprintln!("Failed to read title of window {}: {}", window.label(), e);
+            return;
+        }
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(e) = hyprland::present_window(&title) {
+            eprintln!("Failed to present window on Hyprland: {}", e);
+        }
+    });
+}
+
+/// Installs window manager rules the overlays rely on
+///
+/// Hyprland ignores `always_on_top`, so the overlays are floated and pinned
+/// to every workspace with window rules instead.
+pub fn install_compositor_rules() {
+    if FocusBackend::detect() != FocusBackend::Hyprland {
+        return;
+    }
+    if let Err(e) = hyprland::install_overlay_rules(&[SEARCH_WINDOW_TITLE, QUICK_ADD_WINDOW_TITLE])
+    {
+        eprintln!("Failed to install Hyprland window rules: {}", e);
+    }
+}
+
+/// Hides a window, remembering its geometry if it has a default size
 pub fn hide_window(window: &WebviewWindow) -> Result<(), AppError> {
+    if default_window_size(window.label()).is_some() {
+        window_layout::remember(window);
+    }
     window
         .hide()
         .map_err(|e| AppError::TauriError(e.to_string()))?;
@@ -119,11 +206,7 @@ pub fn center_window(window: &WebviewWindow) -> Result<(), AppError> {
 
 /// Positions a window near the cursor position
 pub fn position_near_cursor(window: &WebviewWindow) -> Result<(), AppError> {
-    // Get cursor position - this is a placeholder implementation
-    // On macOS, we'll need to use platform-specific APIs to get cursor position
-    // For now, we'll just center the window
-    center_window(window)?;
-    Ok(())
+    overlay_placement::place_near_cursor(window)
 }
 
 /// Positions a window at a specific screen position
@@ -144,10 +227,13 @@ pub fn resize_window(window: &WebviewWindow, width: u32, height: u32) -> Result<
     Ok(())
 }
 
-/// Shows and centers the search window
+/// Shows the search window where the user is working
 pub fn show_search_window(app: &AppHandle) -> Result<(), AppError> {
     let window = get_search_window(app)?;
-    center_window(&window)?;
+    if !window.is_visible().unwrap_or(false) {
+        remember_focused_window(app);
+    }
+    overlay_placement::place_search_overlay(&window)?;
     show_window(&window)?;
     Ok(())
 }
@@ -155,7 +241,9 @@ pub fn show_search_window(app: &AppHandle) -> Result<(), AppError> {
 /// Hides the search window
 pub fn hide_search_window(app: &AppHandle) -> Result<(), AppError> {
     let window = get_search_window(app)?;
+    let had_focus = window.is_focused().unwrap_or(false);
     hide_window(&window)?;
+    restore_focused_window(app, had_focus);
     Ok(())
 }
 
@@ -163,14 +251,66 @@ pub fn hide_search_window(app: &AppHandle) -> Result<(), AppError> {
 pub fn toggle_search_window(app: &AppHandle) -> Result<(), AppError> {
     let window = get_search_window(app)?;
     if window.is_visible().unwrap_or(false) {
+        let had_focus = window.is_focused().unwrap_or(false);
         hide_window(&window)?;
+        restore_focused_window(app, had_focus);
     } else {
-        center_window(&window)?;
+        remember_focused_window(app);
+        overlay_placement::place_search_overlay(&window)?;
         show_window(&window)?;
     }
     Ok(())
 }
 
+/// Records the window to hand focus back to once the search window hides
+fn remember_focused_window(app: &AppHandle) {
+    if let Some(state) = app.try_state::<FocusState>() {
+        state.remember_active_window();
+    }
+}
+
+/// Re-activates the window that was focused before the search window
+///
+/// Skipped when the search window had already lost focus (e.g. the user
+/// clicked into another app), since restoring would then steal focus back.
+fn restore_focused_window(app: &AppHandle, had_focus: bool) {
+    if let Some(state) = app.try_state::<FocusState>() {
+        if had_focus {
+            state.restore_previous_window();
+        } else {
+            state.forget_previous_window();
+        }
+    }
+}
+
+/// Snapshot of window state for troubleshooting focus problems
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowDiagnostics {
+    pub search_window_visible: bool,
+    pub search_window_focused: bool,
+    /// Mechanism used to return focus after the search window hides
+    pub focus_backend: FocusBackend,
+    /// Outcome of the most recent focus restore, if one was attempted
+    pub last_focus_restore: Option<FocusRestoreReport>,
+}
+
+/// Collects window diagnostics
+pub fn window_diagnostics(app: &AppHandle) -> WindowDiagnostics {
+    let search = app.get_webview_window(SEARCH_WINDOW_LABEL);
+    WindowDiagnostics {
+        search_window_visible: search
+            .as_ref()
+            .is_some_and(|w| w.is_visible().unwrap_or(false)),
+        search_window_focused: search
+            .as_ref()
+            .is_some_and(|w| w.is_focused().unwrap_or(false)),
+        focus_backend: FocusBackend::detect(),
+        last_focus_restore: app
+            .try_state::<FocusState>()
+            .and_then(|state| state.last_restore()),
+    }
+}
+
 /// Shows the management window
 pub fn show_management_window(app: &AppHandle) -> Result<(), AppError> {
     let window = get_or_create_management_window(app)?;
@@ -185,48 +325,187 @@ pub fn show_settings_window(app: &AppHandle) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Forgets remembered window geometry and puts open windows back at their
+/// default size, centered
+pub async fn reset_window_layout(app: &AppHandle) -> Result<(), AppError> {
+    window_layout::reset(app).await?;
+
+    for (label, window) in app.webview_windows() {
+        let Some((width, height)) = default_window_size(&label) else {
+            continue;
+        };
+        window
+            .set_size(tauri::LogicalSize::new(width, height))
+            .map_err(|e| AppError::TauriError(e.to_string()))?;
+        if let Err(e) = window.center() {
+            if !should_ignore_positioning_error(&e) {
+                return Err(AppError::TauriError(e.to_string()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Applies the theme to the native window chrome (title bar, scroll bars)
+///
+/// `System` hands the choice back to the OS.
+pub fn apply_window_theme(app: &AppHandle, theme: Theme) {
+    let native = match theme {
+        Theme::Light => Some(tauri::Theme::Light),
+        Theme::Dark => Some(tauri::Theme::Dark),
+        Theme::System => None,
+    };
+    for (label, window) in app.webview_windows() {
+        if let Err(e) = window.set_theme(native) {
+            eprintln!("Failed to set theme of window {}: {}", label, e);
+        }
+    }
+}
+
+/// Where the text Quick Add opens with came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuickAddSource {
+    Selection,
+    Clipboard,
+    /// Nothing was captured; the user types the content
+    Empty,
+}
+
+/// Payload of the `selected-text-captured` event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuickAddContent {
+    pub text: String,
+    pub source: QuickAddSource,
+}
+
+/// Decides what Quick Add opens with once the selection has been captured
+///
+/// A failed capture (nothing selected, or an empty primary selection on
+/// Wayland) is only reported when the fallback asks for it; otherwise the
+/// form opens with the clipboard or with nothing.
+///
+/// # Arguments
+///
+/// * `captured` - Result of capturing the selected text
+/// * `fallback` - The `quick_add_fallback` setting
+/// * `read_clipboard` - Reads the regular clipboard; only called for `Clipboard`
+pub fn resolve_quick_add_content(
+    captured: Result<String, AppError>,
+    fallback: QuickAddFallback,
+    read_clipboard: impl FnOnce() -> Result<String, AppError>,
+) -> Result<QuickAddContent, AppError> {
+    let error = match captured {
+        Ok(text) => {
+            return Ok(QuickAddContent {
+                text,
+                source: QuickAddSource::Selection,
+            })
+        }
+        Err(e) => e,
+    };
+
+    let empty = QuickAddContent {
+        text: String::new(),
+        source: QuickAddSource::Empty,
+    };
+    match fallback {
+        QuickAddFallback::Error => Err(error),
+        QuickAddFallback::EmptyForm => Ok(empty),
+        QuickAddFallback::Clipboard => match read_clipboard() {
+            Ok(text) if !text.trim().is_empty() => Ok(QuickAddContent {
+                text,
+                source: QuickAddSource::Clipboard,
+            }),
+            Ok(_) => Ok(empty),
+            Err(e) => {
+                eprintln!("Failed to read clipboard for Quick Add: {}", e);
+                Ok(empty)
+            }
+        },
+    }
+}
+
+/// Reads the `quick_add_fallback` setting, falling back to the default
+async fn quick_add_fallback(app: &AppHandle) -> QuickAddFallback {
+    let settings = match get_pool(app) {
+        Ok(pool) => SettingsService::new(pool).get_settings().await,
+        Err(e) => Err(e),
+    };
+    match settings {
+        Ok(settings) => settings.quick_add_fallback,
+        Err(e) => {
+            eprintln!("Failed to load Quick Add fallback setting: {}", e);
+            QuickAddFallback::default()
+        }
+    }
+}
+
 /// Shows the quick add window with pre-captured selected text
+///
+/// When nothing is selected the window opens according to the
+/// `quick_add_fallback` setting.
 pub fn show_quick_add_window(app: &AppHandle) -> Result<(), AppError> {
     // IMPORTANT: Capture selected text BEFORE showing window to avoid losing focus
     let selected_text = capture_selected_text_sync();
 
     let window = get_or_create_quick_add_window(app)?;
 
-    center_window(&window)?;
+    // A remembered position was restored when the window was created
+    let remembered = window_layout::geometry(app, QUICK_ADD_WINDOW_LABEL)
+        .is_some_and(|geometry| geometry.position.is_some());
+    if !remembered {
+        overlay_placement::place_overlay(&window)?;
+    }
     show_window(&window)?;
 
     // Emit event AFTER showing window to ensure frontend listener is ready
     // Use a delay to allow the webview to initialize and frontend to mount
-    if let Ok(text) = selected_text {
-        let app_clone = app.clone();
-        std::thread::spawn(move || {
-            // Longer delay to ensure webview is fully initialized
-            std::thread::sleep(std::time::Duration::from_millis(200));
-            // Use emit_to to target the specific window
-            if let Err(e) =
-                app_clone.emit_to(QUICK_ADD_WINDOW_LABEL, "selected-text-captured", text)
-            {
-                eprintln!("Failed to emit selected-text-captured event: {}", e);
+    let app_clone = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let fallback = quick_add_fallback(&app_clone).await;
+        let content = resolve_quick_add_content(selected_text, fallback, read_clipboard_sync);
+
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        // Use emit_to to target the specific window
+        let emitted = match content {
+            Ok(content) => {
+                app_clone.emit_to(QUICK_ADD_WINDOW_LABEL, "selected-text-captured", content)
             }
-        });
-    } else if let Err(e) = selected_text {
-        // If text capture failed, emit an error event
-        let app_clone = app.clone();
-        let error_msg = e.to_string();
-        std::thread::spawn(move || {
-            std::thread::sleep(std::time::Duration::from_millis(200));
-            // Use emit_to to target the specific window
-            if let Err(e) =
-                app_clone.emit_to(QUICK_ADD_WINDOW_LABEL, "selected-text-error", error_msg)
-            {
-                eprintln!("Failed to emit selected-text-error event: {}", e);
+            Err(e) => {
+                app_clone.emit_to(QUICK_ADD_WINDOW_LABEL, "selected-text-error", e.to_string())
             }
-        });
-    }
+        };
+        if let Err(e) = emitted {
+            eprintln!("Failed to emit Quick Add content: {}", e);
+        }
+    });
 
     Ok(())
 }
 
+/// Reads the regular clipboard, used when nothing is selected
+fn read_clipboard_sync() -> Result<String, AppError> {
+    #[cfg(target_os = "macos")]
+    {
+        get_clipboard_sync()
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use crate::services::linux_clipboard::{self, Selection};
+
+        linux_clipboard::read(linux_clipboard::current_backend()?, Selection::Clipboard)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        Err(AppError::Unsupported(
+            "Clipboard reading only supported on macOS and Linux".to_string(),
+        ))
+    }
+}
+
 /// Synchronously captures selected text using clipboard method
 /// This must be called BEFORE the window takes focus
 fn capture_selected_text_sync() -> Result<String, AppError> {
@@ -274,7 +553,22 @@ fn capture_selected_text_sync() -> Result<String, AppError> {
         Ok(selected)
     }
 
-    #[cfg(not(target_os = "macos"))]
+    // Linux exposes the highlighted text as the primary selection (through
+    // data-control on Wayland), so no copy needs to be simulated
+    #[cfg(target_os = "linux")]
+    {
+        use crate::services::linux_clipboard::{self, Selection};
+
+        let backend = linux_clipboard::current_backend()?;
+        let selected = linux_clipboard::read(backend, Selection::Primary)?;
+        if selected.trim().is_empty() {
+            return Err(AppError::NotFound("No text selected".to_string()));
+        }
+
+        Ok(selected)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
     {
         Err(AppError::Unsupported(
             "Text capture only supported on macOS".to_string(),
@@ -327,4 +621,56 @@ mod tests {
         assert_eq!(QUICK_ADD_WINDOW_LABEL, "quick-add");
         assert_eq!(SETTINGS_WINDOW_LABEL, "settings");
     }
+
+    #[test]
+    fn test_default_window_size() {
+        assert_eq!(
+            default_window_size(QUICK_ADD_WINDOW_LABEL),
+            Some(QUICK_ADD_WINDOW_SIZE)
+        );
+        assert!(default_window_size(MANAGEMENT_WINDOW_LABEL).is_some());
+        assert!(default_window_size(SETTINGS_WINDOW_LABEL).is_some());
+        // The search overlay is positioned every time it opens
+        assert_eq!(default_window_size(SEARCH_WINDOW_LABEL), None);
+    }
+
+    #[test]
+    fn test_resolve_quick_add_content() {
+        let no_selection = || Err(AppError::NotFound("No text selected".to_string()));
+        let clipboard = || Ok("copied".to_string());
+
+        let selected =
+            resolve_quick_add_content(Ok("picked".to_string()), QuickAddFallback::Error, clipboard)
+                .unwrap();
+        assert_eq!(selected.source, QuickAddSource::Selection);
+        assert_eq!(selected.text, "picked");
+
+        assert!(
+            resolve_quick_add_content(no_selection(), QuickAddFallback::Error, clipboard).is_err()
+        );
+
+        let empty =
+            resolve_quick_add_content(no_selection(), QuickAddFallback::EmptyForm, clipboard)
+                .unwrap();
+        assert_eq!(empty.source, QuickAddSource::Empty);
+        assert!(empty.text.is_empty());
+
+        let prefilled =
+            resolve_quick_add_content(no_selection(), QuickAddFallback::Clipboard, clipboard)
+                .unwrap();
+        assert_eq!(prefilled.source, QuickAddSource::Clipboard);
+        assert_eq!(prefilled.text, "copied");
+
+        // An empty or unreadable clipboard still opens the form
+        let blank = resolve_quick_add_content(no_selection(), QuickAddFallback::Clipboard, || {
+            Ok("  ".to_string())
+        })
+        .unwrap();
+        assert_eq!(blank.source, QuickAddSource::Empty);
+        let failed = resolve_quick_add_content(no_selection(), QuickAddFallback::Clipboard, || {
+            Err(AppError::External("wl-paste missing".to_string()))
+        })
+        .unwrap();
+        assert_eq!(failed.source, QuickAddSource::Empty);
+    }
 }
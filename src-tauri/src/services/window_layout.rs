@@ -0,0 +1,372 @@
+/// Remembered window sizes and positions.
+///
+/// Each window's geometry is captured when it is hidden or closed and
+/// restored the next time it is created. The layout lives in memory for the
+/// synchronous window code and is persisted under its own key in the
+/// settings table, so it neither travels with exported settings nor churns
+/// the settings cache. Compositors that do not let clients read or set
+/// window positions (Wayland) only get their sizes remembered.
+use crate::services::database::get_pool;
+use crate::services::settings::SettingsService;
+use crate::services::window::{compositor_controls_positioning, should_ignore_positioning_error};
+use crate::utils::error::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{
+    AppHandle, Manager, Monitor, PhysicalPosition, PhysicalSize, WebviewWindow, WindowEvent,
+};
+
+/// Settings table key holding the layout
+pub const WINDOW_LAYOUT_KEY: &str = "window_layout";
+
+/// Width of the window strip that must be on a monitor for a position to be restored
+const MIN_VISIBLE_WIDTH: i64 = 100;
+
+/// Height of that strip; roughly a title bar
+const MIN_VISIBLE_HEIGHT: i64 = 30;
+
+/// Size and position of a window, in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    /// Outer top-left corner; `None` where the compositor does not report it
+    pub position: Option<(i32, i32)>,
+    /// Inner (content) size
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Area of a monitor, in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonitorArea {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+}
+
+impl MonitorArea {
+    pub fn from_monitor(monitor: &Monitor) -> Self {
+        Self {
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+            scale_factor: monitor.scale_factor(),
+        }
+    }
+
+    /// Whether a point in physical pixels is on this monitor
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        let left = f64::from(self.x);
+        let top = f64::from(self.y);
+        x >= left
+            && x < left + f64::from(self.width)
+            && y >= top
+            && y < top + f64::from(self.height)
+    }
+
+    /// A point in logical pixels converted with this monitor's scale factor
+    pub fn to_physical(&self, x: f64, y: f64) -> (f64, f64) {
+        let scale = if self.scale_factor > 0.0 {
+            self.scale_factor
+        } else {
+            1.0
+        };
+        (x * scale, y * scale)
+    }
+
+    /// Whether a point in logical pixels is on this monitor
+    pub fn contains_logical(&self, x: f64, y: f64) -> bool {
+        let (x, y) = self.to_physical(x, y);
+        self.contains(x, y)
+    }
+}
+
+/// Remembered geometry by window label
+#[derive(Default)]
+pub struct WindowLayoutState(Mutex<HashMap<String, WindowGeometry>>);
+
+/// Whether the top strip of a window at `position` would be on a monitor
+///
+/// Guards against restoring a window onto a monitor that has since been
+/// unplugged or rearranged, where it could not be reached.
+pub fn is_on_screen(position: (i32, i32), width: u32, monitors: &[MonitorArea]) -> bool {
+    let (x, y) = (i64::from(position.0), i64::from(position.1));
+    let strip_width = i64::from(width).min(MIN_VISIBLE_WIDTH);
+    monitors.iter().any(|monitor| {
+        let left = i64::from(monitor.x);
+        let top = i64::from(monitor.y);
+        let right = left + i64::from(monitor.width);
+        let bottom = top + i64::from(monitor.height);
+        x >= left && x + strip_width <= right && y >= top && y + MIN_VISIBLE_HEIGHT <= bottom
+    })
+}
+
+/// Reads the persisted layout
+///
+/// # Errors
+///
+/// Returns `AppError` if the settings table cannot be read
+pub async fn load_layout(pool: &SqlitePool) -> Result<HashMap<String, WindowGeometry>, AppError> {
+    let stored = SettingsService::new(pool.clone())
+        .get_setting(WINDOW_LAYOUT_KEY)
+        .await?;
+    Ok(stored
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default())
+}
+
+/// Persists the layout
+///
+/// # Errors
+///
+/// Returns `AppError` if the layout cannot be written
+pub async fn save_layout(
+    pool: &SqlitePool,
+    layout: &HashMap<String, WindowGeometry>,
+) -> Result<(), AppError> {
+    SettingsService::new(pool.clone())
+        .update_setting(WINDOW_LAYOUT_KEY, serde_json::to_string(layout)?)
+        .await
+}
+
+/// Loads the persisted layout into memory; called once at startup
+pub async fn init(app: &AppHandle) -> Result<(), AppError> {
+    let layout = load_layout(&get_pool(app)?).await?;
+    if let Some(state) = app.try_state::<WindowLayoutState>() {
+        *state
+            .0
+            .lock()
+            .map_err(|e| AppError::Unknown(e.to_string()))? = layout;
+    }
+    Ok(())
+}
+
+/// Remembered geometry of a window
+pub fn geometry(app: &AppHandle, label: &str) -> Option<WindowGeometry> {
+    let state = app.try_state::<WindowLayoutState>()?;
+    let layout = state.0.lock().ok()?;
+    layout.get(label).copied()
+}
+
+/// Writes the in-memory layout to the database in the background
+fn persist(app: &AppHandle) {
+    let Some(state) = app.try_state::<WindowLayoutState>() else {
+        return;
+    };
+    let Ok(layout) = state.0.lock().map(|layout| layout.clone()) else {
+        return;
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = match get_pool(&app) {
+            Ok(pool) => save_layout(&pool, &layout).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            eprintln!("Failed to save window layout: {}", e);
+        }
+    });
+}
+
+/// Captures a window's current geometry and persists the layout
+pub fn remember(window: &WebviewWindow) {
+    let size = match window.inner_size() {
+        Ok(size) => size,
+        Err(e) => {
+            eprintln!("Failed to read size of window {}: {}", window.label(), e);
+            return;
+        }
+    };
+    let position = match window.outer_position() {
+        // Positions reported under such compositors are meaningless
+        Ok(_) if compositor_controls_positioning() => None,
+        Ok(position) => Some((position.x, position.y)),
+        Err(e) if should_ignore_positioning_error(&e) => None,
+        Err(e) => {
+            eprintln!(
+                "Failed to read position of window {}: {}",
+                window.label(),
+                e
+            );
+            None
+        }
+    };
+
+    let app = window.app_handle();
+    let Some(state) = app.try_state::<WindowLayoutState>() else {
+        return;
+    };
+    if let Ok(mut layout) = state.0.lock() {
+        let previous = layout.get(window.label()).copied();
+        let geometry = WindowGeometry {
+            // Keep the last known position when the compositor stops reporting one
+            position: position.or(previous.and_then(|geometry| geometry.position)),
+            width: size.width,
+            height: size.height,
+        };
+        if previous == Some(geometry) {
+            return;
+        }
+        layout.insert(window.label().to_string(), geometry);
+    }
+    persist(app);
+}
+
+/// Applies the remembered geometry to a window, if there is one
+///
+/// A position that is no longer on any monitor is skipped, leaving the
+/// window where it was built (centered).
+pub fn restore(window: &WebviewWindow) -> Result<(), AppError> {
+    let Some(geometry) = geometry(window.app_handle(), window.label()) else {
+        return Ok(());
+    };
+
+    window
+        .set_size(PhysicalSize::new(geometry.width, geometry.height))
+        .map_err(|e| AppError::TauriError(e.to_string()))?;
+
+    let Some(position) = geometry
+        .position
+        .filter(|_| !compositor_controls_positioning())
+    else {
+        return Ok(());
+    };
+    let monitors: Vec<MonitorArea> = window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(MonitorArea::from_monitor)
+        .collect();
+    if !is_on_screen(position, geometry.width, &monitors) {
+        return Ok(());
+    }
+
+    match window.set_position(PhysicalPosition::new(position.0, position.1)) {
+        Err(e) if !should_ignore_positioning_error(&e) => Err(AppError::TauriError(e.to_string())),
+        _ => Ok(()),
+    }
+}
+
+/// Restores a new window's geometry and remembers it whenever it closes
+///
+/// Hiding is covered by `window::hide_window`.
+pub fn track(window: &WebviewWindow) -> Result<(), AppError> {
+    restore(window)?;
+    // Looked up by label; holding the window in its own handler would keep it alive
+    let app = window.app_handle().clone();
+    let label = window.label().to_string();
+    window.on_window_event(move |event| {
+        if let WindowEvent::CloseRequested { .. } = event {
+            if let Some(window) = app.get_webview_window(&label) {
+                remember(&window);
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Forgets every remembered geometry
+///
+/// # Errors
+///
+/// Returns `AppError` if the cleared layout cannot be saved
+pub async fn reset(app: &AppHandle) -> Result<(), AppError> {
+    if let Some(state) = app.try_state::<WindowLayoutState>() {
+        state
+            .0
+            .lock()
+            .map_err(|e| AppError::Unknown(e.to_string()))?
+            .clear();
+    }
+    save_layout(&get_pool(app)?, &HashMap::new()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    const MONITORS: [MonitorArea; 2] = [
+        MonitorArea {
+            x: 0,
+            y: 0,
+            width: 1920,
+            height: 1080,
+            scale_factor: 1.0,
+        },
+        MonitorArea {
+            x: 1920,
+            y: 0,
+            width: 1280,
+            height: 1024,
+            scale_factor: 2.0,
+        },
+    ];
+
+    #[test]
+    fn test_is_on_screen() {
+        assert!(is_on_screen((100, 100), 800, &MONITORS));
+        // On the second monitor
+        assert!(is_on_screen((2000, 500), 800, &MONITORS));
+        // Monitor to the left was unplugged
+        assert!(!is_on_screen((-1500, 100), 800, &MONITORS));
+        // Title bar below the bottom edge
+        assert!(!is_on_screen((100, 1070), 800, &MONITORS));
+        assert!(!is_on_screen((100, 100), 800, &[]));
+    }
+
+    #[test]
+    fn test_monitor_contains() {
+        assert!(MONITORS[0].contains(1919.0, 0.0));
+        assert!(!MONITORS[0].contains(1920.0, 0.0));
+        assert!(MONITORS[1].contains(1920.0, 1023.0));
+        // Logical points are scaled by the monitor's factor
+        assert!(MONITORS[1].contains_logical(1000.0, 100.0));
+        assert!(!MONITORS[1].contains_logical(1700.0, 100.0));
+    }
+
+    #[tokio::test]
+    async fn test_layout_round_trip() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert!(load_layout(&pool).await.unwrap().is_empty());
+
+        let mut layout = HashMap::new();
+        layout.insert(
+            "settings".to_string(),
+            WindowGeometry {
+                position: Some((40, -20)),
+                width: 1000,
+                height: 700,
+            },
+        );
+        layout.insert(
+            "management".to_string(),
+            WindowGeometry {
+                position: None,
+                width: 1200,
+                height: 800,
+            },
+        );
+        save_layout(&pool, &layout).await.unwrap();
+        assert_eq!(load_layout(&pool).await.unwrap(), layout);
+    }
+}
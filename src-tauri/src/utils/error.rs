@@ -33,6 +33,12 @@ pub enum AppError {
     #[error("External process error: {0}")]
     External(String),
 
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     #[error("Unsupported operation: {0}")]
     Unsupported(String),
 
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod database_tests {
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_database_migrations_exist() {
         // This test verifies that the migrations can be loaded
         // Actual database testing will be done in integration tests
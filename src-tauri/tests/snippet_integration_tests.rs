@@ -1,5 +1,7 @@
-use snips_lib::models::{CreateSnippetInput, SnippetId, UpdateSnippetInput};
-use snips_lib::{create_snippet, delete_snippet, get_all_snippets, get_snippet, update_snippet};
+use snips_lib::models::{CreateSnippetInput, SnippetId, SnippetKind, UpdateSnippetInput};
+use snips_lib::{
+    archive_snippet, create_snippet, delete_snippet, get_all_snippets, get_snippet, update_snippet,
+};
 use tauri::AppHandle;
 
 /// Helper to create a test app instance
@@ -19,6 +21,7 @@ async fn test_create_snippet_success() {
         content: "This is test content".to_string(),
         description: Some("Test description".to_string()),
         tags: vec!["rust".to_string(), "testing".to_string()],
+        kind: SnippetKind::Static,
     };
 
     let result = create_snippet(app, input).await;
@@ -42,6 +45,7 @@ async fn test_create_snippet_with_empty_name() {
         content: "content".to_string(),
         description: None,
         tags: vec![],
+        kind: SnippetKind::Static,
     };
 
     let result = create_snippet(app, input).await;
@@ -59,6 +63,7 @@ async fn test_create_snippet_with_empty_content() {
         content: "".to_string(),
         description: None,
         tags: vec![],
+        kind: SnippetKind::Static,
     };
 
     let result = create_snippet(app, input).await;
@@ -76,6 +81,7 @@ async fn test_create_duplicate_snippet() {
         content: "content1".to_string(),
         description: None,
         tags: vec![],
+        kind: SnippetKind::Static,
     };
 
     let input2 = CreateSnippetInput {
@@ -83,6 +89,7 @@ async fn test_create_duplicate_snippet() {
         content: "content2".to_string(),
         description: None,
         tags: vec![],
+        kind: SnippetKind::Static,
     };
 
     let result1 = create_snippet(app.clone(), input1).await;
@@ -104,6 +111,7 @@ async fn test_get_snippet_success() {
         content: "content".to_string(),
         description: None,
         tags: vec!["tag1".to_string()],
+        kind: SnippetKind::Static,
     };
 
     let created = create_snippet(app.clone(), input).await.unwrap();
@@ -139,18 +147,48 @@ async fn test_get_all_snippets() {
             content: format!("Content {}", i),
             description: None,
             tags: vec![],
+            kind: SnippetKind::Static,
         };
         create_snippet(app.clone(), input).await.unwrap();
     }
 
     // Get all snippets
-    let result = get_all_snippets(app).await;
+    let result = get_all_snippets(app, None, None, None, None).await;
     assert!(result.is_ok());
 
     let snippets = result.unwrap();
     assert!(snippets.len() >= 3);
 }
 
+#[tokio::test]
+#[ignore = "Integration tests require proper Tauri app setup"]
+async fn test_get_all_snippets_hides_archived() {
+    let app = setup_test_app().await;
+
+    let input = CreateSnippetInput {
+        name: "Archived Snippet".to_string(),
+        content: "old content".to_string(),
+        description: None,
+        tags: vec![],
+        kind: SnippetKind::Static,
+    };
+    let created = create_snippet(app.clone(), input).await.unwrap();
+    let archived = archive_snippet(app.clone(), created.id).await.unwrap();
+    assert!(archived.archived);
+
+    // Hidden by default
+    let listed = get_all_snippets(app.clone(), None, None, None, None)
+        .await
+        .unwrap();
+    assert!(listed.iter().all(|s| s.id != created.id));
+
+    // Shown when asked for
+    let listed = get_all_snippets(app, None, None, None, Some(true))
+        .await
+        .unwrap();
+    assert!(listed.iter().any(|s| s.id == created.id && s.archived));
+}
+
 #[tokio::test]
 #[ignore = "Integration tests require proper Tauri app setup"]
 async fn test_update_snippet_success() {
@@ -162,6 +200,7 @@ async fn test_update_snippet_success() {
         content: "original content".to_string(),
         description: None,
         tags: vec!["tag1".to_string()],
+        kind: SnippetKind::Static,
     };
     let created = create_snippet(app.clone(), input).await.unwrap();
 
@@ -171,6 +210,7 @@ async fn test_update_snippet_success() {
         content: "updated content".to_string(),
         description: Some("new description".to_string()),
         tags: vec!["tag2".to_string(), "tag3".to_string()],
+        kind: SnippetKind::Static,
     };
 
     let result = update_snippet(app, created.id, update_input).await;
@@ -193,6 +233,7 @@ async fn test_update_snippet_not_found() {
         content: "content".to_string(),
         description: None,
         tags: vec![],
+        kind: SnippetKind::Static,
     };
 
     let result = update_snippet(app, SnippetId(99999), update_input).await;
@@ -211,6 +252,7 @@ async fn test_update_snippet_with_empty_name() {
         content: "content".to_string(),
         description: None,
         tags: vec![],
+        kind: SnippetKind::Static,
     };
     let created = create_snippet(app.clone(), input).await.unwrap();
 
@@ -220,6 +262,7 @@ async fn test_update_snippet_with_empty_name() {
         content: "content".to_string(),
         description: None,
         tags: vec![],
+        kind: SnippetKind::Static,
     };
 
     let result = update_snippet(app, created.id, update_input).await;
@@ -238,6 +281,7 @@ async fn test_delete_snippet_success() {
         content: "content".to_string(),
         description: None,
         tags: vec![],
+        kind: SnippetKind::Static,
     };
     let created = create_snippet(app.clone(), input).await.unwrap();
 
@@ -275,6 +319,7 @@ async fn test_snippet_tags_association() {
             "tauri".to_string(),
             "testing".to_string(),
         ],
+        kind: SnippetKind::Static,
     };
     let created = create_snippet(app.clone(), input).await.unwrap();
 
@@ -299,6 +344,7 @@ async fn test_update_snippet_tags() {
         content: "content".to_string(),
         description: None,
         tags: vec!["tag1".to_string(), "tag2".to_string()],
+        kind: SnippetKind::Static,
     };
     let created = create_snippet(app.clone(), input).await.unwrap();
 
@@ -308,6 +354,7 @@ async fn test_update_snippet_tags() {
         content: "content".to_string(),
         description: None,
         tags: vec!["tag3".to_string(), "tag4".to_string()],
+        kind: SnippetKind::Static,
     };
     let updated = update_snippet(app, created.id, update_input).await.unwrap();
 
@@ -331,6 +378,7 @@ async fn test_delete_snippet_cascades_to_tags() {
         content: "content".to_string(),
         description: None,
         tags: vec!["tag1".to_string()],
+        kind: SnippetKind::Static,
     };
     let created = create_snippet(app.clone(), input).await.unwrap();
 
@@ -4,6 +4,7 @@ import { type ReactElement, useEffect, useState } from 'react';
 import { QuickAddDialog } from '@/components/QuickAddDialog';
 import { SearchOverlay } from '@/components/SearchOverlay';
 import { SettingsWindow } from '@/components/SettingsWindow';
+import { useCustomTheme } from '@/hooks/useCustomTheme';
 import { useTheme } from '@/hooks/useTheme';
 
 /**
@@ -15,6 +16,7 @@ function App(): ReactElement {
 
   // Initialize theme (system preference detection)
   useTheme();
+  useCustomTheme();
 
   useEffect(() => {
     const getWindowLabel = async (): Promise<void> => {
@@ -5,7 +5,15 @@ import { type FormEvent, type ReactElement, useCallback, useEffect, useState } f
 import { Button } from '@/components/ui/Button';
 import { Input } from '@/components/ui/Input';
 import { Textarea } from '@/components/ui/Textarea';
-import { createSnippet, getAllSnippets } from '@/lib/api';
+import {
+  appendToSnippet,
+  createSnippetWithResolution,
+  getAllSnippets,
+  handleOverlayEscape,
+  searchSnippets,
+} from '@/lib/api';
+import type { NameConflictMode, SearchResult, Snippet } from '@/types/snippet';
+import type { QuickAddContent, QuickAddSource } from '@/types/window';
 
 interface QuickAddDialogProps {
   onSuccess?: () => void;
@@ -15,9 +23,16 @@ interface QuickAddDialogProps {
 /**
  * QuickAddDialog component for quickly adding snippets from selected text.
  * Captures selected text when opened, allows user to name and tag the snippet.
+ * When nothing was selected the content is prefilled from the clipboard or
+ * left empty for the user to type, depending on the Quick Add fallback setting.
+ * If the name is taken, the user chooses between a free name and appending to
+ * the existing snippet. In append mode the text is added to the end of a
+ * snippet chosen by search instead, for building up running notes.
  */
 export function QuickAddDialog({ onSuccess, onError }: QuickAddDialogProps): ReactElement {
   const [selectedText, setSelectedText] = useState<string>('');
+  const [contentSource, setContentSource] = useState<QuickAddSource>('selection');
+  const [contentError, setContentError] = useState<string>('');
   const [name, setName] = useState<string>('');
   const [description, setDescription] = useState<string>('');
   const [tags, setTags] = useState<string>('');
@@ -25,6 +40,14 @@ export function QuickAddDialog({ onSuccess, onError }: QuickAddDialogProps): Rea
   const [isSaving, setIsSaving] = useState<boolean>(false);
   const [error, setError] = useState<string>('');
   const [nameError, setNameError] = useState<string>('');
+  const [conflict, setConflict] = useState<{ existing: Snippet; suggestedName: string } | null>(
+    null
+  );
+  const [captureMode, setCaptureMode] = useState<'create' | 'append'>('create');
+  const [targetQuery, setTargetQuery] = useState<string>('');
+  const [targetResults, setTargetResults] = useState<SearchResult[]>([]);
+  const [target, setTarget] = useState<Snippet | null>(null);
+  const [targetError, setTargetError] = useState<string>('');
   const [existingTags, setExistingTags] = useState<string[]>([]);
   const [showTagSuggestions, setShowTagSuggestions] = useState<boolean>(false);
 
@@ -48,10 +71,15 @@ export function QuickAddDialog({ onSuccess, onError }: QuickAddDialogProps): Rea
 
         // Use window-specific listen API for events sent via emit_to
         // Window-specific events are NOT triggered to global listeners
-        unlistenText = await window.listen<string>('selected-text-captured', (event) => {
-          console.warn('Received selected-text-captured event:', event.payload.substring(0, 50));
+        unlistenText = await window.listen<QuickAddContent>('selected-text-captured', (event) => {
+          console.warn(
+            'Received selected-text-captured event:',
+            event.payload.source,
+            event.payload.text.substring(0, 50)
+          );
           if (mounted) {
-            setSelectedText(event.payload);
+            setSelectedText(event.payload.text);
+            setContentSource(event.payload.source);
             setIsLoading(false);
           }
         });
@@ -135,9 +163,36 @@ export function QuickAddDialog({ onSuccess, onError }: QuickAddDialogProps): Rea
     setShowTagSuggestions(false);
   };
 
+  // Search for the snippet to append to
+  useEffect(() => {
+    if (captureMode !== 'append') return;
+    let cancelled = false;
+
+    searchSnippets(targetQuery, 8, undefined, undefined, false)
+      .then((page) => {
+        if (!cancelled) setTargetResults(page.results);
+      })
+      .catch((err) => console.error('Failed to search snippets:', err));
+
+    return () => {
+      cancelled = true;
+    };
+  }, [captureMode, targetQuery]);
+
   // Validate form
   const validateForm = (): boolean => {
     setNameError('');
+    setContentError('');
+
+    if (!selectedText.trim()) {
+      setContentError('Snippet content is required');
+      return false;
+    }
+
+    if (captureMode === 'append') {
+      setTargetError(target ? '' : 'Choose a snippet to append to');
+      return target !== null;
+    }
 
     if (!name.trim()) {
       setNameError('Snippet name is required');
@@ -152,16 +207,11 @@ export function QuickAddDialog({ onSuccess, onError }: QuickAddDialogProps): Rea
     return true;
   };
 
-  // Handle form submission
-  const handleSubmit = async (e: FormEvent<HTMLFormElement>): Promise<void> => {
-    e.preventDefault();
-
-    if (!validateForm()) {
-      return;
-    }
-
+  // Save the snippet; a taken name is reported back unless `onConflict` resolves it
+  const saveSnippet = async (onConflict: NameConflictMode): Promise<void> => {
     setIsSaving(true);
     setError('');
+    setConflict(null);
 
     try {
       // Parse tags from comma-separated string
@@ -170,12 +220,21 @@ export function QuickAddDialog({ onSuccess, onError }: QuickAddDialogProps): Rea
         .map((t) => t.trim())
         .filter((t) => t.length > 0);
 
-      await createSnippet({
-        name: name.trim(),
-        content: selectedText,
-        description: description.trim() || null,
-        tags: tagList,
-      });
+      const outcome = await createSnippetWithResolution(
+        {
+          name: name.trim(),
+          content: selectedText,
+          description: description.trim() || null,
+          tags: tagList,
+        },
+        onConflict
+      );
+
+      if (outcome.status === 'name_taken') {
+        setConflict({ existing: outcome.existing, suggestedName: outcome.suggested_name });
+        setIsSaving(false);
+        return;
+      }
 
       onSuccess?.();
 
@@ -190,6 +249,40 @@ export function QuickAddDialog({ onSuccess, onError }: QuickAddDialogProps): Rea
     }
   };
 
+  // Append the text to an existing snippet
+  const appendText = async (snippet: Snippet): Promise<void> => {
+    setIsSaving(true);
+    setError('');
+
+    try {
+      await appendToSnippet(snippet.id, selectedText);
+      onSuccess?.();
+
+      const window = getCurrentWindow();
+      await window.close();
+    } catch (err) {
+      const errorMessage = err instanceof Error ? err.message : 'Failed to append to snippet';
+      setError(errorMessage);
+      setIsSaving(false);
+      onError?.(errorMessage);
+    }
+  };
+
+  // Handle form submission
+  const handleSubmit = async (e: FormEvent<HTMLFormElement>): Promise<void> => {
+    e.preventDefault();
+
+    if (!validateForm()) {
+      return;
+    }
+
+    if (captureMode === 'append' && target) {
+      await appendText(target);
+    } else {
+      await saveSnippet('ask');
+    }
+  };
+
   // Handle cancel/close
   const handleCancel = async (): Promise<void> => {
     const window = getCurrentWindow();
@@ -200,7 +293,7 @@ export function QuickAddDialog({ onSuccess, onError }: QuickAddDialogProps): Rea
   useEffect(() => {
     const handleKeyDown = (e: KeyboardEvent): void => {
       if (e.key === 'Escape') {
-        handleCancel();
+        handleOverlayEscape().catch((err) => console.error('Failed to handle Escape:', err));
       }
     };
 
@@ -221,7 +314,7 @@ export function QuickAddDialog({ onSuccess, onError }: QuickAddDialogProps): Rea
     );
   }
 
-  if (error && !selectedText) {
+  if (error && !selectedText && contentSource === 'selection') {
     return (
       <div className="flex items-center justify-center min-h-screen p-6 bg-gray-50 dark:bg-gray-900">
         <div className="max-w-md w-full bg-white dark:bg-gray-800 rounded-lg shadow-lg p-6">
@@ -238,82 +331,188 @@ export function QuickAddDialog({ onSuccess, onError }: QuickAddDialogProps): Rea
   return (
     <div className="min-h-screen p-6 bg-gray-50 dark:bg-gray-900 animate-fade-in">
       <div className="max-w-2xl mx-auto bg-white dark:bg-gray-800 rounded-lg shadow-lg p-6 animate-fade-in-scale">
-        <h1 className="text-2xl font-bold text-gray-900 dark:text-gray-100 mb-6">
+        <h1 className="text-2xl font-bold text-gray-900 dark:text-gray-100 mb-4">
           Quick Add Snippet
         </h1>
 
+        {/* Capture mode: create a new snippet or append to an existing one */}
+        <div className="flex gap-2 mb-6" role="group" aria-label="Capture mode">
+          <Button
+            type="button"
+            size="sm"
+            variant={captureMode === 'create' ? 'primary' : 'secondary'}
+            onClick={() => setCaptureMode('create')}
+            aria-pressed={captureMode === 'create'}
+          >
+            New snippet
+          </Button>
+          <Button
+            type="button"
+            size="sm"
+            variant={captureMode === 'append' ? 'primary' : 'secondary'}
+            onClick={() => setCaptureMode('append')}
+            aria-pressed={captureMode === 'append'}
+          >
+            Append to existing
+          </Button>
+        </div>
+
         <form onSubmit={handleSubmit} className="space-y-4">
-          {/* Selected text preview (read-only) */}
-          <div>
-            <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
-              Selected Text
-            </label>
-            <div className="px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-gray-50 dark:bg-gray-700 text-sm text-gray-700 dark:text-gray-300 max-h-32 overflow-y-auto">
-              {selectedText}
+          {/* Selected text preview (read-only); editable when nothing was selected */}
+          {contentSource === 'selection' ? (
+            <div>
+              <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+                Selected Text
+              </label>
+              <div className="px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-gray-50 dark:bg-gray-700 text-sm text-gray-700 dark:text-gray-300 max-h-32 overflow-y-auto">
+                {selectedText}
+              </div>
             </div>
-          </div>
-
-          {/* Name input (required) */}
-          <Input
-            label="Name"
-            value={name}
-            onChange={(e) => setName(e.target.value)}
-            placeholder="e.g., Python async function template"
-            required
-            fullWidth
-            error={nameError}
-            autoFocus
-            maxLength={255}
-          />
-
-          {/* Description input (optional) */}
-          <Textarea
-            label="Description"
-            value={description}
-            onChange={(e) => setDescription(e.target.value)}
-            placeholder="Optional description of this snippet"
-            fullWidth
-            rows={2}
-          />
-
-          {/* Tags input (optional) with autocomplete */}
-          <div className="relative">
-            <Input
-              label="Tags"
-              value={tags}
-              onChange={(e) => {
-                setTags(e.target.value);
-                setShowTagSuggestions(true);
-              }}
-              onFocus={() => setShowTagSuggestions(true)}
-              onBlur={() => {
-                // Delay to allow clicking suggestions
-                setTimeout(() => setShowTagSuggestions(false), 200);
-              }}
-              placeholder="e.g., python, async, template (comma-separated)"
+          ) : (
+            <Textarea
+              label="Content"
+              value={selectedText}
+              onChange={(e) => setSelectedText(e.target.value)}
+              placeholder="Nothing was selected; type or paste the snippet content"
+              required
               fullWidth
-              helperText="Comma-separated list of tags"
+              rows={4}
+              error={contentError}
+              helperText={
+                contentSource === 'clipboard' ? 'Prefilled from the clipboard' : undefined
+              }
             />
+          )}
 
-            {/* Tag suggestions dropdown */}
-            {showTagSuggestions && tagSuggestions.length > 0 && (
-              <div className="absolute z-10 w-full mt-1 bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 rounded-lg shadow-lg max-h-40 overflow-y-auto">
-                {tagSuggestions.map((suggestion) => (
-                  <button
-                    key={suggestion}
-                    type="button"
-                    className="w-full px-3 py-2 text-left text-sm text-gray-900 dark:text-gray-100 hover:bg-gray-100 dark:hover:bg-gray-600 focus:bg-gray-100 dark:focus:bg-gray-600 focus:outline-none"
-                    onMouseDown={(e) => {
-                      e.preventDefault();
-                      handleTagSuggestionClick(suggestion);
-                    }}
-                  >
-                    {suggestion}
-                  </button>
+          {captureMode === 'append' ? (
+            <div>
+              <Input
+                label="Append to"
+                value={targetQuery}
+                onChange={(e) => setTargetQuery(e.target.value)}
+                placeholder="Search snippets"
+                fullWidth
+                error={targetError}
+                autoFocus
+              />
+              <ul className="mt-1 border border-gray-300 dark:border-gray-600 rounded-lg max-h-48 overflow-y-auto">
+                {targetResults.map((result) => (
+                  <li key={result.id}>
+                    <button
+                      type="button"
+                      className={`w-full px-3 py-2 text-left text-sm text-gray-900 dark:text-gray-100 hover:bg-gray-100 dark:hover:bg-gray-600 focus:outline-none ${
+                        target?.id === result.id ? 'bg-blue-50 dark:bg-blue-900/30' : ''
+                      }`}
+                      onClick={() => {
+                        setTarget(result);
+                        setTargetError('');
+                      }}
+                      aria-pressed={target?.id === result.id}
+                    >
+                      {result.name}
+                    </button>
+                  </li>
                 ))}
+                {targetResults.length === 0 && (
+                  <li className="px-3 py-2 text-sm text-gray-500 dark:text-gray-400">
+                    No matching snippets
+                  </li>
+                )}
+              </ul>
+            </div>
+          ) : (
+            <>
+              {/* Name input (required) */}
+              <Input
+                label="Name"
+                value={name}
+                onChange={(e) => setName(e.target.value)}
+                placeholder="e.g., Python async function template"
+                required
+                fullWidth
+                error={nameError}
+                autoFocus
+                maxLength={255}
+              />
+
+              {/* Description input (optional) */}
+              <Textarea
+                label="Description"
+                value={description}
+                onChange={(e) => setDescription(e.target.value)}
+                placeholder="Optional description of this snippet"
+                fullWidth
+                rows={2}
+              />
+
+              {/* Tags input (optional) with autocomplete */}
+              <div className="relative">
+                <Input
+                  label="Tags"
+                  value={tags}
+                  onChange={(e) => {
+                    setTags(e.target.value);
+                    setShowTagSuggestions(true);
+                  }}
+                  onFocus={() => setShowTagSuggestions(true)}
+                  onBlur={() => {
+                    // Delay to allow clicking suggestions
+                    setTimeout(() => setShowTagSuggestions(false), 200);
+                  }}
+                  placeholder="e.g., python, async, template (comma-separated)"
+                  fullWidth
+                  helperText="Comma-separated list of tags"
+                />
+
+                {/* Tag suggestions dropdown */}
+                {showTagSuggestions && tagSuggestions.length > 0 && (
+                  <div className="absolute z-10 w-full mt-1 bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 rounded-lg shadow-lg max-h-40 overflow-y-auto">
+                    {tagSuggestions.map((suggestion) => (
+                      <button
+                        key={suggestion}
+                        type="button"
+                        className="w-full px-3 py-2 text-left text-sm text-gray-900 dark:text-gray-100 hover:bg-gray-100 dark:hover:bg-gray-600 focus:bg-gray-100 dark:focus:bg-gray-600 focus:outline-none"
+                        onMouseDown={(e) => {
+                          e.preventDefault();
+                          handleTagSuggestionClick(suggestion);
+                        }}
+                      >
+                        {suggestion}
+                      </button>
+                    ))}
+                  </div>
+                )}
               </div>
-            )}
-          </div>
+            </>
+          )}
+
+          {/* Name conflict: save under a free name or append to the existing snippet */}
+          {conflict && (
+            <div
+              className="px-4 py-3 bg-yellow-50 dark:bg-yellow-900/20 border border-yellow-200 dark:border-yellow-800 rounded-lg text-sm text-yellow-800 dark:text-yellow-300 space-y-3"
+              role="alert"
+            >
+              <p>A snippet named &quot;{conflict.existing.name}&quot; already exists.</p>
+              <div className="flex gap-3">
+                <Button
+                  type="button"
+                  variant="secondary"
+                  onClick={() => saveSnippet('rename')}
+                  disabled={isSaving}
+                >
+                  Save as &quot;{conflict.suggestedName}&quot;
+                </Button>
+                <Button
+                  type="button"
+                  variant="secondary"
+                  onClick={() => saveSnippet('append')}
+                  disabled={isSaving}
+                >
+                  Append to existing
+                </Button>
+              </div>
+            </div>
+          )}
 
           {/* Error message */}
           {error && selectedText && (
@@ -328,7 +527,7 @@ export function QuickAddDialog({ onSuccess, onError }: QuickAddDialogProps): Rea
           {/* Action buttons */}
           <div className="flex gap-3 pt-2">
             <Button type="submit" variant="primary" fullWidth disabled={isSaving}>
-              {isSaving ? 'Saving...' : 'Save Snippet'}
+              {isSaving ? 'Saving...' : captureMode === 'append' ? 'Append' : 'Save Snippet'}
             </Button>
             <Button
               type="button"
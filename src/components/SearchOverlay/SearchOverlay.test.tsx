@@ -31,6 +31,14 @@ vi.mock('@/hooks/useTags', () => ({
   }),
 }));
 
+/** Wraps results in the single page `search_snippets` returns */
+const searchPage = (results: unknown[]) => ({
+  results,
+  total_matches: results.length,
+  offset: 0,
+  next_cursor: null,
+});
+
 describe('SearchOverlay', () => {
   beforeEach(() => {
     vi.clearAllMocks();
@@ -61,13 +69,16 @@ describe('SearchOverlay', () => {
         created_at: Date.now(),
         updated_at: Date.now(),
         tags: ['test'],
+        pinned: false,
+        kind: 'static',
         usage_count: 5,
         last_used: null,
         relevance_score: 1.0,
+        preview: 'Test content',
       },
     ];
 
-    vi.mocked(invoke).mockResolvedValue(mockResults);
+    vi.mocked(invoke).mockResolvedValue(searchPage(mockResults));
 
     render(<SearchOverlay />);
     const searchInput = screen.getByPlaceholderText('Search snippets...');
@@ -78,6 +89,7 @@ describe('SearchOverlay', () => {
       expect(invoke).toHaveBeenCalledWith('search_snippets', {
         query: 'test',
         limit: 50,
+        includeContent: false,
       });
     });
   });
@@ -92,13 +104,16 @@ describe('SearchOverlay', () => {
         created_at: Date.now(),
         updated_at: Date.now(),
         tags: ['test'],
+        pinned: false,
+        kind: 'static',
         usage_count: 5,
         last_used: null,
         relevance_score: 1.0,
+        preview: 'Test content',
       },
     ];
 
-    vi.mocked(invoke).mockResolvedValue(mockResults);
+    vi.mocked(invoke).mockResolvedValue(searchPage(mockResults));
 
     render(<SearchOverlay />);
     const searchInput = screen.getByPlaceholderText('Search snippets...');
@@ -112,7 +127,7 @@ describe('SearchOverlay', () => {
   });
 
   it('shows empty state when no results found', async () => {
-    vi.mocked(invoke).mockResolvedValue([]);
+    vi.mocked(invoke).mockResolvedValue(searchPage([]));
 
     render(<SearchOverlay />);
     const searchInput = screen.getByPlaceholderText('Search snippets...');
@@ -134,13 +149,16 @@ describe('SearchOverlay', () => {
         created_at: Date.now(),
         updated_at: Date.now(),
         tags: [],
+        pinned: false,
+        kind: 'static',
         usage_count: 0,
         last_used: null,
         relevance_score: 1.0,
+        preview: 'Test content',
       },
     ];
 
-    vi.mocked(invoke).mockResolvedValue(mockResults);
+    vi.mocked(invoke).mockResolvedValue(searchPage(mockResults));
 
     render(<SearchOverlay />);
     const searchInput = screen.getByPlaceholderText('Search snippets...');
@@ -169,13 +187,16 @@ describe('SearchOverlay', () => {
         created_at: Date.now(),
         updated_at: Date.now(),
         tags: [],
+        pinned: false,
+        kind: 'static',
         usage_count: 0,
         last_used: null,
         relevance_score: 1.0,
+        preview: 'Test content',
       },
     ];
 
-    vi.mocked(invoke).mockResolvedValue(mockResults);
+    vi.mocked(invoke).mockResolvedValue(searchPage(mockResults));
 
     render(<SearchOverlay />);
     const searchInput = screen.getByPlaceholderText('Search snippets...');
@@ -216,13 +237,16 @@ describe('SearchOverlay', () => {
         created_at: Date.now(),
         updated_at: Date.now(),
         tags: ['react', 'typescript'],
+        pinned: false,
+        kind: 'static',
         usage_count: 0,
         last_used: null,
         relevance_score: 1.0,
+        preview: 'Content',
       },
     ];
 
-    vi.mocked(invoke).mockResolvedValue(mockResults);
+    vi.mocked(invoke).mockResolvedValue(searchPage(mockResults));
 
     render(<SearchOverlay />);
     const searchInput = screen.getByPlaceholderText('Search snippets...');
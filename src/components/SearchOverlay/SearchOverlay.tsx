@@ -13,14 +13,16 @@ import { useKeyboardNavigation } from '@/hooks/useKeyboardNavigation';
 import { useSelectionBadge } from '@/hooks/useSelectionBadge';
 import { useTags } from '@/hooks/useTags';
 import { useSnippetStore } from '@/stores/snippetStore';
-import type { SearchResult } from '@/types';
+import type { DynamicCommand, SearchPage, SearchResult, UnfilledParameters } from '@/types';
 
 const ITEM_HEIGHT = 80;
 const MAX_VISIBLE_ITEMS = 8;
 
 /**
  * Parse tag filter from query string
- * Returns tuple of [tagName, remainingQuery]
+ * Returns tuple of [tagName, remainingQuery]. Queries using `tag:` terms
+ * (`tag:a tag:b`, `tag:a,b`, `-tag:a`) have no single tag filter, and
+ * `name:` / `content:` scope a search term rather than name a tag.
  */
 function parseTagFilter(query: string): [string | null, string] {
   const colonPos = query.indexOf(':');
@@ -29,6 +31,9 @@ function parseTagFilter(query: string): [string | null, string] {
   }
 
   const potentialTag = query.slice(0, colonPos);
+  if (/(^|\s)-?tag:/i.test(query) || /^(name|content)$/i.test(potentialTag)) {
+    return [null, query];
+  }
   // Tag names should be non-empty and not contain spaces
   if (potentialTag && !potentialTag.includes(' ')) {
     const remaining = query.slice(colonPos + 1).trim();
@@ -123,7 +128,7 @@ function SearchResultItem({
           <p
             className={`text-sm line-clamp-2 ${isSelected ? 'text-blue-800 dark:text-blue-200' : 'text-gray-600 dark:text-gray-300'}`}
           >
-            {result.content}
+            {result.preview}
           </p>
         </div>
       </div>
@@ -181,11 +186,12 @@ export function SearchOverlay(): ReactElement {
     const performSearch = async (): Promise<void> => {
       setIsSearching(true);
       try {
-        const results = await invoke<SearchResult[]>('search_snippets', {
+        const page = await invoke<SearchPage>('search_snippets', {
           query: debouncedQuery,
           limit: 50,
+          includeContent: false,
         });
-        setSearchResults(results);
+        setSearchResults(page.results);
       } catch (error) {
         console.error('Search failed:', error);
         setSearchResults([]);
@@ -206,6 +212,15 @@ export function SearchOverlay(): ReactElement {
     }
   }, []);
 
+  // Escape hides the window unless the auto-hide preferences say otherwise
+  const handleEscape = useCallback(async () => {
+    try {
+      await invoke<boolean>('handle_overlay_escape');
+    } catch (error) {
+      console.error('Failed to handle Escape:', error);
+    }
+  }, []);
+
   // Handle settings open
   const handleOpenSettings = useCallback(async () => {
     try {
@@ -227,14 +242,45 @@ export function SearchOverlay(): ReactElement {
       // Get selected snippet IDs in order (preserving selection across searches)
       // Convert Set to Array to maintain order
       const snippetIds = Array.from(selectedSnippets);
+      const [tagFilter] = parseTagFilter(searchQuery);
+      const context = { source: 'search_overlay', tagFilter };
 
       // Copy to clipboard and record analytics using the combined command
       // The backend will fetch the snippet content and concatenate it
-      await invoke('copy_snippets_with_analytics', { snippetIds });
+      let unfilled: UnfilledParameters[] | undefined;
+      try {
+        unfilled = await invoke<UnfilledParameters[]>('copy_snippets_with_analytics', {
+          snippetIds,
+          ...context,
+        });
+      } catch (error) {
+        // Dynamic snippets run shell commands, which the backend refuses to
+        // do until the user has seen and confirmed them
+        if (!String(error).startsWith('Forbidden')) {
+          throw error;
+        }
+        const commands = await invoke<DynamicCommand[]>('get_dynamic_snippet_commands', {
+          snippetIds,
+        });
+        const list = commands.map((c) => `${c.name}: ${c.command}`).join('\n');
+        if (!window.confirm(`Run these commands and copy their output?\n\n${list}`)) {
+          return;
+        }
+        unfilled = await invoke<UnfilledParameters[]>('copy_snippets_with_analytics', {
+          snippetIds,
+          confirmDynamic: true,
+          ...context,
+        });
+      }
 
       // Show success feedback
       const count = selectedSnippets.size;
-      showToast(`Copied ${count} snippet${count === 1 ? '' : 's'} to clipboard`, 'success', 2000);
+      if (unfilled && unfilled.length > 0) {
+        const names = unfilled.map((u) => u.snippet_name).join(', ');
+        showToast(`Copied with unfilled placeholders in ${names}`, 'warning', 3000);
+      } else {
+        showToast(`Copied ${count} snippet${count === 1 ? '' : 's'} to clipboard`, 'success', 2000);
+      }
 
       // Clear selection and close window after a brief delay
       setTimeout(async () => {
@@ -245,7 +291,7 @@ export function SearchOverlay(): ReactElement {
       console.error('Failed to copy to clipboard:', error);
       showToast('Failed to copy to clipboard', 'error');
     }
-  }, [selectedSnippets, clearSelected, handleClose, showToast]);
+  }, [selectedSnippets, searchQuery, clearSelected, handleClose, showToast]);
 
   // Keyboard navigation
   const { focusedIndex } = useKeyboardNavigation({
@@ -268,7 +314,7 @@ export function SearchOverlay(): ReactElement {
         toggleSelected(result.id);
       }
     },
-    onEscape: handleClose,
+    onEscape: handleEscape,
     enabled: searchResults.length > 0,
   });
 
@@ -6,6 +6,7 @@ import { Spinner } from '@/components/ui/Spinner';
 import {
   clearAllAnalytics,
   clearAnalyticsBefore,
+  exportAnalyticsToCsv,
   exportAnalyticsToJson,
   getGlobalAnalytics,
   getSettings,
@@ -74,6 +75,9 @@ export function AnalyticsTab(): ReactElement {
       await updateSettings(updatedSettings);
       setSettings(updatedSettings);
       showSuccess(enabled ? 'Analytics enabled' : 'Analytics disabled');
+      if (!enabled && updatedSettings.privacy_settings.purge_on_disable) {
+        await loadData();
+      }
     } catch (err) {
       setError(err instanceof Error ? err.message : 'Failed to update settings');
     } finally {
@@ -81,18 +85,41 @@ export function AnalyticsTab(): ReactElement {
     }
   };
 
-  const handleExportAnalytics = async (): Promise<void> => {
+  const handleTogglePurge = async (purgeOnDisable: boolean): Promise<void> => {
+    if (!settings) return;
+
     try {
       setIsProcessing(true);
       setError(null);
-      const jsonData = await exportAnalyticsToJson();
+      const updatedSettings: AppSettings = {
+        ...settings,
+        privacy_settings: {
+          ...settings.privacy_settings,
+          purge_on_disable: purgeOnDisable,
+        },
+      };
+      await updateSettings(updatedSettings);
+      setSettings(updatedSettings);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to update settings');
+    } finally {
+      setIsProcessing(false);
+    }
+  };
+
+  const handleExportAnalytics = async (format: 'json' | 'csv'): Promise<void> => {
+    try {
+      setIsProcessing(true);
+      setError(null);
+      const data = format === 'csv' ? await exportAnalyticsToCsv() : await exportAnalyticsToJson();
 
       // Create a blob and download
-      const blob = new Blob([jsonData], { type: 'application/json' });
+      const type = format === 'csv' ? 'text/csv' : 'application/json';
+      const blob = new Blob([data], { type });
       const url = URL.createObjectURL(blob);
       const a = document.createElement('a');
       a.href = url;
-      a.download = `snips-analytics-${Date.now()}.json`;
+      a.download = `snips-analytics-${Date.now()}.${format}`;
       document.body.appendChild(a);
       a.click();
       document.body.removeChild(a);
@@ -272,6 +299,24 @@ export function AnalyticsTab(): ReactElement {
                 </p>
               </div>
             </label>
+
+            <label className="flex items-start space-x-3">
+              <input
+                type="checkbox"
+                className="mt-1 w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                checked={settings.privacy_settings.purge_on_disable}
+                onChange={(e) => handleTogglePurge(e.target.checked)}
+                disabled={isProcessing}
+              />
+              <div className="flex-1">
+                <span className="text-sm font-medium text-gray-700 dark:text-gray-300">
+                  Delete usage data when tracking is turned off
+                </span>
+                <p className="text-xs text-gray-500 dark:text-gray-500 mt-1">
+                  Removes all recorded usage as soon as analytics tracking is disabled.
+                </p>
+              </div>
+            </label>
           </div>
         </div>
       </Card>
@@ -407,12 +452,25 @@ export function AnalyticsTab(): ReactElement {
                   Export Analytics
                 </p>
                 <p className="text-xs text-gray-500 dark:text-gray-500 mt-1">
-                  Download all analytics data as JSON
+                  Download all analytics data as JSON, or as CSV for spreadsheets
                 </p>
               </div>
-              <Button onClick={handleExportAnalytics} disabled={isProcessing} variant="secondary">
-                Export
-              </Button>
+              <div className="flex gap-2">
+                <Button
+                  onClick={() => handleExportAnalytics('json')}
+                  disabled={isProcessing}
+                  variant="secondary"
+                >
+                  JSON
+                </Button>
+                <Button
+                  onClick={() => handleExportAnalytics('csv')}
+                  disabled={isProcessing}
+                  variant="secondary"
+                >
+                  CSV
+                </Button>
+              </div>
             </div>
 
             <div className="flex items-center justify-between p-3 bg-gray-50 dark:bg-gray-800 rounded-lg">
@@ -3,8 +3,16 @@ import { type ReactElement, useEffect, useState } from 'react';
 import { Button } from '@/components/ui/Button';
 import { Card } from '@/components/ui/Card';
 import { Spinner } from '@/components/ui/Spinner';
-import { getSettings, updateSettings } from '@/lib/api';
-import type { AppSettings, Theme } from '@/types/settings';
+import { getSettings, probeClipboardSupport, updateSettings } from '@/lib/api';
+import type { ClipboardSupport } from '@/types/clipboard';
+import type {
+  AppSettings,
+  OverlayMonitor,
+  QuickAddFallback,
+  QuickWindowPreferences,
+  SearchPlacement,
+  Theme,
+} from '@/types/settings';
 
 /**
  * General Settings Tab
@@ -16,10 +24,14 @@ export function GeneralTab(): ReactElement {
   const [isSaving, setIsSaving] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [saveSuccess, setSaveSuccess] = useState(false);
+  const [clipboardSupport, setClipboardSupport] = useState<ClipboardSupport | null>(null);
 
   // Load settings on mount
   useEffect(() => {
     loadSettings();
+    probeClipboardSupport()
+      .then(setClipboardSupport)
+      .catch(() => setClipboardSupport(null));
   }, []);
 
   const loadSettings = async (): Promise<void> => {
@@ -35,7 +47,7 @@ export function GeneralTab(): ReactElement {
     }
   };
 
-  const handleThemeChange = async (theme: Theme): Promise<void> => {
+  const saveSettings = async (changes: Partial<AppSettings>): Promise<void> => {
     if (!settings) return;
 
     try {
@@ -45,7 +57,7 @@ export function GeneralTab(): ReactElement {
 
       const updatedSettings: AppSettings = {
         ...settings,
-        theme,
+        ...changes,
       };
 
       await updateSettings(updatedSettings);
@@ -61,6 +73,36 @@ export function GeneralTab(): ReactElement {
     }
   };
 
+  const handleThemeChange = async (theme: Theme): Promise<void> => {
+    await saveSettings({ theme });
+  };
+
+  const handleQuickAddFallbackChange = async (fallback: QuickAddFallback): Promise<void> => {
+    await saveSettings({ quick_add_fallback: fallback });
+  };
+
+  const handleOverlayMonitorChange = async (monitor: OverlayMonitor): Promise<void> => {
+    await saveSettings({ overlay_monitor: monitor });
+  };
+
+  const handleSearchPlacementChange = async (placement: SearchPlacement): Promise<void> => {
+    await saveSettings({ search_placement: placement });
+  };
+
+  const handleQuickWindowChange = async (
+    changes: Partial<QuickWindowPreferences>
+  ): Promise<void> => {
+    const current = settings?.quick_window_preferences ?? {
+      hide_on_blur: true,
+      hide_on_escape: true,
+    };
+    await saveSettings({ quick_window_preferences: { ...current, ...changes } });
+  };
+
+  const handleCopyToPrimaryChange = async (copyToPrimary: boolean): Promise<void> => {
+    await saveSettings({ linux_clipboard: { copy_to_primary: copyToPrimary } });
+  };
+
   if (isLoading) {
     return (
       <div className="flex items-center justify-center py-12">
@@ -155,6 +197,137 @@ export function GeneralTab(): ReactElement {
         </div>
       </Card>
 
+      {/* Quick Add */}
+      <Card className="p-6">
+        <div className="space-y-4">
+          <div>
+            <h3 className="text-lg font-medium text-gray-900 dark:text-gray-100 mb-1">
+              Quick Add
+            </h3>
+            <p className="text-sm text-gray-600 dark:text-gray-400">
+              Choose what Quick Add opens with when no text is selected
+            </p>
+          </div>
+
+          <label className="block">
+            <span className="text-sm font-medium text-gray-700 dark:text-gray-300 mb-2 block">
+              When nothing is selected
+            </span>
+            <select
+              value={settings.quick_add_fallback ?? 'empty_form'}
+              onChange={(e) => handleQuickAddFallbackChange(e.target.value as QuickAddFallback)}
+              disabled={isSaving}
+              className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-sm text-gray-900 dark:text-gray-100"
+            >
+              <option value="empty_form">Open an empty form</option>
+              <option value="clipboard">Prefill from the clipboard</option>
+              <option value="error">Show an error</option>
+            </select>
+          </label>
+        </div>
+      </Card>
+
+      {/* Overlay Placement */}
+      <Card className="p-6">
+        <div className="space-y-4">
+          <div>
+            <h3 className="text-lg font-medium text-gray-900 dark:text-gray-100 mb-1">
+              Overlay Placement
+            </h3>
+            <p className="text-sm text-gray-600 dark:text-gray-400">
+              Choose where the search and Quick Add windows open and when they hide
+            </p>
+          </div>
+
+          <label className="block">
+            <span className="text-sm font-medium text-gray-700 dark:text-gray-300 mb-2 block">
+              Open on
+            </span>
+            <select
+              value={settings.overlay_monitor ?? 'cursor'}
+              onChange={(e) => handleOverlayMonitorChange(e.target.value as OverlayMonitor)}
+              disabled={isSaving}
+              className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-sm text-gray-900 dark:text-gray-100"
+            >
+              <option value="cursor">The monitor with the mouse cursor</option>
+              <option value="active_window">The monitor with the focused window</option>
+              <option value="primary">The primary monitor</option>
+            </select>
+          </label>
+
+          <label className="block">
+            <span className="text-sm font-medium text-gray-700 dark:text-gray-300 mb-2 block">
+              Search window position
+            </span>
+            <select
+              value={settings.search_placement ?? 'center'}
+              onChange={(e) => handleSearchPlacementChange(e.target.value as SearchPlacement)}
+              disabled={isSaving}
+              className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-sm text-gray-900 dark:text-gray-100"
+            >
+              <option value="center">Centered on the monitor</option>
+              <option value="near_cursor">Next to the mouse cursor</option>
+            </select>
+          </label>
+
+          <div className="space-y-3">
+            <label className="flex items-center space-x-3">
+              <input
+                type="checkbox"
+                className="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                checked={settings.quick_window_preferences?.hide_on_blur ?? true}
+                onChange={(e) => handleQuickWindowChange({ hide_on_blur: e.target.checked })}
+                disabled={isSaving}
+              />
+              <span className="text-sm text-gray-700 dark:text-gray-300">
+                Hide when another window is focused
+              </span>
+            </label>
+            <label className="flex items-center space-x-3">
+              <input
+                type="checkbox"
+                className="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                checked={settings.quick_window_preferences?.hide_on_escape ?? true}
+                onChange={(e) => handleQuickWindowChange({ hide_on_escape: e.target.checked })}
+                disabled={isSaving}
+              />
+              <span className="text-sm text-gray-700 dark:text-gray-300">Hide on Escape</span>
+            </label>
+          </div>
+        </div>
+      </Card>
+
+      {/* Clipboard */}
+      <Card className="p-6">
+        <div className="space-y-4">
+          <div>
+            <h3 className="text-lg font-medium text-gray-900 dark:text-gray-100 mb-1">
+              Clipboard
+            </h3>
+            <p className="text-sm text-gray-600 dark:text-gray-400">
+              Configure how copied snippets reach the clipboard
+            </p>
+          </div>
+
+          {clipboardSupport && !clipboardSupport.tool_available && clipboardSupport.message && (
+            <p className="text-sm text-red-600 dark:text-red-400">{clipboardSupport.message}</p>
+          )}
+
+          <label className="flex items-center space-x-3">
+            <input
+              type="checkbox"
+              className="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+              checked={settings.linux_clipboard?.copy_to_primary ?? false}
+              onChange={(e) => handleCopyToPrimaryChange(e.target.checked)}
+              disabled={isSaving}
+            />
+            <span className="text-sm text-gray-700 dark:text-gray-300">
+              Also set the primary selection for middle-click paste (Linux)
+            </span>
+          </label>
+        </div>
+      </Card>
+
       {/* Startup Behavior */}
       <Card className="p-6">
         <div className="space-y-4">
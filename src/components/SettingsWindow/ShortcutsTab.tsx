@@ -4,7 +4,7 @@ import { type ReactElement, useState, useEffect, useCallback } from 'react';
 import { Stack } from '@/components/layout/Stack';
 import { Button } from '@/components/ui/Button';
 import { Card } from '@/components/ui/Card';
-import type { AppSettings, GlobalShortcuts } from '@/types/settings';
+import type { AppSettings, GlobalShortcuts, ShortcutCheck } from '@/types/settings';
 
 import { ShortcutRecorder } from './ShortcutRecorder';
 
@@ -31,6 +31,11 @@ const SHORTCUT_ACTIONS: ShortcutAction[] = [
     label: 'Quick Add Snippet',
     description: 'Quickly capture selected text as a new snippet',
   },
+  {
+    id: 'quick_paste',
+    label: 'Copy Last Used Snippet',
+    description: 'Copy the most recently used snippet without opening a window',
+  },
 ];
 
 /**
@@ -39,6 +44,7 @@ const SHORTCUT_ACTIONS: ShortcutAction[] = [
 const DEFAULT_SHORTCUTS: GlobalShortcuts = {
   search_select: 'CommandOrControl+Shift+S',
   quick_add: 'CommandOrControl+Shift+A',
+  quick_paste: 'CommandOrControl+Shift+L',
 };
 
 /**
@@ -73,14 +79,18 @@ export function ShortcutsTab(): ReactElement {
   };
 
   /**
-   * Validate a shortcut string
+   * Validate a shortcut string and check whether another application holds it
    */
-  const validateShortcut = async (shortcut: string): Promise<boolean> => {
+  const validateShortcut = async (shortcut: string): Promise<string | null> => {
     try {
-      return await invoke<boolean>('is_shortcut_valid', { shortcut });
+      const check = await invoke<ShortcutCheck>('check_shortcut', { shortcut });
+      if (!check.valid) {
+        return 'Invalid shortcut format';
+      }
+      return check.available ? null : (check.conflict ?? 'Shortcut is already in use');
     } catch (err) {
       console.error('Error validating shortcut:', err);
-      return false;
+      return 'Invalid shortcut format';
     }
   };
 
@@ -115,10 +125,10 @@ export function ShortcutsTab(): ReactElement {
     actionId: keyof GlobalShortcuts,
     newShortcut: string
   ): Promise<void> => {
-    // Validate the shortcut format
-    const isValid = await validateShortcut(newShortcut);
-    if (!isValid) {
-      setError('Invalid shortcut format');
+    // Validate the shortcut format and check for other applications holding it
+    const problem = await validateShortcut(newShortcut);
+    if (problem) {
+      setError(problem);
       return;
     }
 
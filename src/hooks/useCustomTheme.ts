@@ -0,0 +1,64 @@
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import { useEffect } from 'react';
+
+import { getActiveCustomTheme } from '@/lib/api';
+import type { CustomTheme } from '@/types/settings';
+
+/**
+ * Hook to lay the active custom theme over the built-in palette
+ *
+ * Sets the theme's CSS variables on the document root and follows
+ * `custom-theme-changed`, so applying a theme in one window restyles all of them
+ */
+export function useCustomTheme(): void {
+  useEffect(() => {
+    const root = document.documentElement;
+    let applied: CustomTheme | null = null;
+    let wasDark = root.classList.contains('dark');
+
+    const apply = (theme: CustomTheme | null): void => {
+      if (applied) {
+        for (const variable of Object.keys(applied.variables)) {
+          root.style.removeProperty(`--${variable}`);
+        }
+        root.classList.toggle('dark', wasDark);
+      }
+
+      if (theme) {
+        wasDark = root.classList.contains('dark');
+        for (const [variable, value] of Object.entries(theme.variables)) {
+          root.style.setProperty(`--${variable}`, value);
+        }
+        root.classList.toggle('dark', theme.dark);
+      }
+      applied = theme;
+    };
+
+    let unlisten: UnlistenFn | undefined;
+    let cancelled = false;
+
+    const setup = async (): Promise<void> => {
+      try {
+        const stop = await listen<CustomTheme | null>('custom-theme-changed', (event) => {
+          apply(event.payload);
+        });
+        if (cancelled) {
+          stop();
+          return;
+        }
+        unlisten = stop;
+        apply(await getActiveCustomTheme());
+      } catch (error) {
+        console.error('Failed to load custom theme:', error);
+      }
+    };
+
+    void setup();
+
+    return () => {
+      cancelled = true;
+      unlisten?.();
+      apply(null);
+    };
+  }, []);
+}
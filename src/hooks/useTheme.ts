@@ -1,5 +1,8 @@
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 import { useEffect, useState } from 'react';
 
+import type { ResolvedTheme } from '@/types/settings';
+
 export type Theme = 'light' | 'dark' | 'system';
 
 /**
@@ -30,12 +33,12 @@ export function useTheme(): { theme: Theme; setTheme: (theme: Theme) => void; is
 
   // Apply theme to document
   useEffect(() => {
-    const updateTheme = (): void => {
+    const updateTheme = (systemIsDark?: boolean): void => {
       const root = document.documentElement;
       let shouldBeDark = false;
 
       if (theme === 'system') {
-        shouldBeDark = window.matchMedia('(prefers-color-scheme: dark)').matches;
+        shouldBeDark = systemIsDark ?? window.matchMedia('(prefers-color-scheme: dark)').matches;
       } else {
         shouldBeDark = theme === 'dark';
       }
@@ -51,15 +54,38 @@ export function useTheme(): { theme: Theme; setTheme: (theme: Theme) => void; is
 
     updateTheme();
 
-    // Listen for system preference changes
+    // Listen for system preference changes; the backend reports them on
+    // macOS and Windows, where the webview's media query can lag behind
     if (theme === 'system') {
       const mediaQuery = window.matchMedia('(prefers-color-scheme: dark)');
       const handleChange = (): void => {
         updateTheme();
       };
 
+      let unlisten: UnlistenFn | undefined;
+      let cancelled = false;
+      void listen<ResolvedTheme>('appearance-updated', (event) => {
+        if (event.payload.theme === 'system') {
+          updateTheme(event.payload.appearance === 'dark');
+        }
+      })
+        .then((stop) => {
+          if (cancelled) {
+            stop();
+          } else {
+            unlisten = stop;
+          }
+        })
+        .catch((error: unknown) => {
+          console.error('Failed to listen for appearance changes:', error);
+        });
+
       mediaQuery.addEventListener('change', handleChange);
-      return () => mediaQuery.removeEventListener('change', handleChange);
+      return () => {
+        cancelled = true;
+        unlisten?.();
+        mediaQuery.removeEventListener('change', handleChange);
+      };
     }
 
     return undefined;
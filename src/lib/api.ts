@@ -3,13 +3,88 @@ import { invoke } from '@tauri-apps/api/core';
 import type {
   Snippet,
   CreateSnippetInput,
+  NameConflictMode,
+  CreateSnippetOutcome,
   UpdateSnippetInput,
+  SearchPage,
   SearchResult,
   SnippetAnalytics,
   GlobalAnalytics,
+  UsageSource,
+  TagUsage,
+  SourceUsage,
+  TrendBucket,
+  AnalyticsCsvColumn,
+  AnalyticsConsentState,
+  UsageTrends,
+  ProductivitySummary,
+  LibraryStatistics,
+  PreviewFormat,
+  RenderedPreview,
+  BatchSearchRequest,
+  BatchSearchResponse,
+  RankingExplanation,
+  DynamicCommand,
+  ExecutionEnvironment,
+  SnippetKind,
+  ComposedTokenEstimate,
+  SnippetParameter,
+  UnfilledParameters,
+  SlotAssignment,
+  ExpansionTrigger,
+  SnippetSource,
+  SourceChange,
+  SnippetVersion,
+  SnippetAttachment,
+  FeedKind,
+  SnippetFeed,
+  FeedSyncReport,
+  SnippetProvenance,
+  StaleSnippet,
+  ExpiringSnippet,
 } from '@/types';
-import type { AppSettings, StorageType } from '@/types/settings';
-import type { BackupConfig, BackupInfo, DatabaseStats } from '@/types/storage';
+import type {
+  ApiScope,
+  ApiToken,
+  ApiTokenAuditEntry,
+  CreatedApiToken,
+} from '@/types/apiToken';
+import type { ClipboardSupport } from '@/types/clipboard';
+import type {
+  ApiServerStatus,
+  AppSettings,
+  ExpanderStatus,
+  CustomTheme,
+  ResolvedTheme,
+  ShortcutCheck,
+  SortPreference,
+  StorageType,
+} from '@/types/settings';
+import type {
+  BackupConfig,
+  BackupInfo,
+  BackupPreview,
+  BackupVerification,
+  DatabaseDiagnostics,
+  DatabaseStats,
+  DiagnosticsBundleReport,
+  ExportFilter,
+  FactoryResetOutcome,
+  GistExportResult,
+  ImportConflict,
+  ImportJob,
+  ImportMode,
+  ImportReport,
+  ImportSource,
+  ImportSummary,
+  OptimizeReport,
+  PruneReport,
+  RepairReport,
+  RestoreReport,
+  SearchIndexConsistency,
+  StartupReport,
+} from '@/types/storage';
+import type { ResourceUsage, WindowDiagnostics } from '@/types/window';
 
 /**
  * API client wrapper for Tauri commands
@@ -29,6 +104,38 @@ export async function createSnippet(input: CreateSnippetInput): Promise<Snippet>
   return await invoke<Snippet>('create_snippet', { input });
 }
 
+/**
+ * Creates a new snippet, resolving a taken name instead of failing
+ * @param input - Snippet creation data
+ * @param onConflict - Optional; create under a free name (`rename`), append to the existing
+ *   snippet (`append`), or write nothing and report the conflict (`ask`, the default)
+ * @returns What was done; `name_taken` carries the existing snippet and a free name
+ */
+export async function createSnippetWithResolution(
+  input: CreateSnippetInput,
+  onConflict?: NameConflictMode
+): Promise<CreateSnippetOutcome> {
+  return await invoke<CreateSnippetOutcome>('create_snippet_with_resolution', {
+    input,
+    onConflict,
+  });
+}
+
+/**
+ * Adds text to the end of an existing snippet
+ * @param id - Snippet ID
+ * @param text - Text to append; trimmed
+ * @param separator - Optional text put before it (default: a blank line)
+ * @returns The updated snippet
+ */
+export async function appendToSnippet(
+  id: number,
+  text: string,
+  separator?: string
+): Promise<Snippet> {
+  return await invoke<Snippet>('append_to_snippet', { id, text, separator: separator ?? null });
+}
+
 /**
  * Retrieves a single snippet by ID
  * @param id - Snippet ID
@@ -39,11 +146,25 @@ export async function getSnippet(id: number): Promise<Snippet> {
 }
 
 /**
- * Retrieves all snippets
- * @returns Array of all snippets
+ * Retrieves snippets with their tags, optionally one page at a time
+ * @param limit - Optional maximum number of snippets; all of them when omitted
+ * @param offset - Optional number of snippets to skip
+ * @param sortBy - Optional order; defaults to the `sort_preference` setting
+ * @param includeArchived - Optional; also list archived snippets (default: false)
+ * @returns Array of snippets in list order
  */
-export async function getAllSnippets(): Promise<Snippet[]> {
-  return await invoke<Snippet[]>('get_all_snippets');
+export async function getAllSnippets(
+  limit?: number,
+  offset?: number,
+  sortBy?: SortPreference,
+  includeArchived?: boolean
+): Promise<Snippet[]> {
+  return await invoke<Snippet[]>('get_all_snippets', {
+    limit: limit ?? null,
+    offset: offset ?? null,
+    sortBy: sortBy ?? null,
+    includeArchived: includeArchived ?? null,
+  });
 }
 
 /**
@@ -64,6 +185,101 @@ export async function deleteSnippet(id: number): Promise<void> {
   await invoke<void>('delete_snippet', { id });
 }
 
+/**
+ * Sets the manual snippet order (used when `sort_preference` is `manual`)
+ * @param ids - Snippet IDs in the desired order; unlisted snippets follow them
+ */
+export async function reorderSnippets(ids: number[]): Promise<void> {
+  await invoke<void>('reorder_snippets', { ids });
+}
+
+/**
+ * Pins or unpins a snippet
+ * @param id - Snippet ID
+ * @returns The snippet with its new pinned state
+ */
+export async function togglePinSnippet(id: number): Promise<Snippet> {
+  return await invoke<Snippet>('toggle_pin_snippet', { id });
+}
+
+/**
+ * Lists snippets nobody has used for a while, as candidates for archiving
+ * @param thresholdDays - Optional number of days without a usage (default: 90)
+ * @returns Unused snippets, longest unused first, without pinned or archived ones
+ */
+export async function getStaleSnippets(thresholdDays?: number): Promise<StaleSnippet[]> {
+  return await invoke<StaleSnippet[]>('get_stale_snippets', {
+    thresholdDays: thresholdDays ?? null,
+  });
+}
+
+/**
+ * Archives snippets, leaving them out of search
+ * @param ids - Snippet IDs
+ * @returns Number of snippets archived
+ */
+export async function archiveSnippets(ids: number[]): Promise<number> {
+  return await invoke<number>('archive_snippets', { ids });
+}
+
+/**
+ * Archives a snippet, leaving it out of search and the snippet list
+ * Archived snippets are found with an `is:archived` search
+ * @param id - Snippet ID
+ * @returns The archived snippet
+ */
+export async function archiveSnippet(id: number): Promise<Snippet> {
+  return await invoke<Snippet>('archive_snippet', { id });
+}
+
+/**
+ * Restores an archived snippet to search and the snippet list
+ * @param id - Snippet ID
+ * @returns The restored snippet
+ */
+export async function unarchiveSnippet(id: number): Promise<Snippet> {
+  return await invoke<Snippet>('unarchive_snippet', { id });
+}
+
+/**
+ * Sets or clears a snippet's expiration date
+ * @param id - Snippet ID
+ * @param expiresAt - Unix timestamp, or null to never expire
+ * @returns The snippet with its new expiration date
+ */
+export async function setSnippetExpiration(
+  id: number,
+  expiresAt: number | null
+): Promise<Snippet> {
+  return await invoke<Snippet>('set_snippet_expiration', { id, expiresAt });
+}
+
+/**
+ * Lists snippets that have expired or expire soon, for a reminder notification
+ * @param withinHours - Optional number of hours to look ahead (default: 24)
+ * @returns Expired and soon expiring snippets that are not archived, soonest first
+ */
+export async function getExpiringSnippets(withinHours?: number): Promise<ExpiringSnippet[]> {
+  return await invoke<ExpiringSnippet[]>('get_expiring_snippets', {
+    withinHours: withinHours ?? null,
+  });
+}
+
+/**
+ * Guesses a snippet's language without saving it
+ * @param name - Snippet name (extensions like `.sh` are used as hints)
+ * @param content - Snippet content
+ * @param kind - Snippet kind; dynamic snippets are always shell
+ * @returns Language identifier, or null for plain text
+ */
+export async function detectSnippetLanguage(
+  name: string,
+  content: string,
+  kind?: SnippetKind
+): Promise<string | null> {
+  return await invoke<string | null>('detect_snippet_language', { name, content, kind });
+}
+
 // ============================================================================
 // Search Commands
 // ============================================================================
@@ -71,13 +287,122 @@ export async function deleteSnippet(id: number): Promise<void> {
 /**
  * Searches snippets using full-text search
  * @param query - Search query string
- * @param limit - Optional maximum number of results
- * @returns Array of search results with relevance scoring
+ * @param limit - Optional maximum number of results per page
+ * @param offset - Optional number of matches to skip
+ * @param cursor - Optional `next_cursor` of the previous page; overrides offset
+ * @param includeContent - Set to false to get previews without each result's full content
+ * @returns One page of search results with relevance scoring and the total match count
  */
-export async function searchSnippets(query: string, limit?: number): Promise<SearchResult[]> {
-  return await invoke<SearchResult[]>('search_snippets', {
+export async function searchSnippets(
+  query: string,
+  limit?: number,
+  offset?: number,
+  cursor?: string,
+  includeContent?: boolean
+): Promise<SearchPage> {
+  return await invoke<SearchPage>('search_snippets', {
     query,
     limit: limit ?? null,
+    offset: offset ?? null,
+    cursor: cursor ?? null,
+    includeContent: includeContent ?? null,
+  });
+}
+
+/**
+ * Runs several search queries in a single invoke
+ * @param requests - Queries to run (max 20)
+ * @param includeContent - Set to false to get previews without each result's full content
+ * @returns One response per request, in request order
+ */
+export async function batchSearch(
+  requests: BatchSearchRequest[],
+  includeContent?: boolean
+): Promise<BatchSearchResponse[]> {
+  return await invoke<BatchSearchResponse[]>('batch_search', {
+    requests,
+    includeContent: includeContent ?? null,
+  });
+}
+
+/**
+ * Gets pinned snippets for the search overlay's empty state
+ * @param limit - Optional maximum number of results
+ * @param includeContent - Set to false to get previews without each result's full content
+ * @returns Pinned snippets sorted by relevance
+ */
+export async function getPinnedSnippets(
+  limit?: number,
+  includeContent?: boolean
+): Promise<SearchResult[]> {
+  return await invoke<SearchResult[]>('get_pinned_snippets', {
+    limit: limit ?? null,
+    includeContent: includeContent ?? null,
+  });
+}
+
+/**
+ * Gets the most recently used snippets for the search overlay's empty state
+ * @param limit - Optional maximum number of results
+ * @param includeContent - Set to false to get previews without each result's full content
+ * @returns Used snippets, most recently used first
+ */
+export async function getRecentSnippets(
+  limit?: number,
+  includeContent?: boolean
+): Promise<SearchResult[]> {
+  return await invoke<SearchResult[]>('get_recent_snippets', {
+    limit: limit ?? null,
+    includeContent: includeContent ?? null,
+  });
+}
+
+/**
+ * Gets the most often used snippets for the search overlay's empty state
+ * @param limit - Optional maximum number of results
+ * @param includeContent - Set to false to get previews without each result's full content
+ * @returns Used snippets, most often used first
+ */
+export async function getFrequentSnippets(
+  limit?: number,
+  includeContent?: boolean
+): Promise<SearchResult[]> {
+  return await invoke<SearchResult[]>('get_frequent_snippets', {
+    limit: limit ?? null,
+    includeContent: includeContent ?? null,
+  });
+}
+
+/**
+ * Explains how a snippet's relevance score for a query is made up
+ * @param query - The search query
+ * @param snippetId - A snippet matching the query
+ * @returns The snippet's position and each part of its score
+ */
+export async function explainSearchRanking(
+  query: string,
+  snippetId: number
+): Promise<RankingExplanation> {
+  return await invoke<RankingExplanation>('explain_search_ranking', { query, snippetId });
+}
+
+// ============================================================================
+// Preview Commands
+// ============================================================================
+
+/**
+ * Renders a snippet as sanitized HTML using the shared backend renderer
+ * @param id - Snippet ID
+ * @param format - Optional rendering mode (defaults to auto-detection)
+ * @returns Rendered HTML with the format and language that were used
+ */
+export async function renderSnippetPreview(
+  id: number,
+  format?: PreviewFormat
+): Promise<RenderedPreview> {
+  return await invoke<RenderedPreview>('render_snippet_preview', {
+    id,
+    format: format ?? null,
   });
 }
 
@@ -88,9 +413,19 @@ export async function searchSnippets(query: string, limit?: number): Promise<Sea
 /**
  * Records usage of a snippet for analytics
  * @param snippetId - ID of the snippet that was used
+ * @param source - Optional entry point the snippet was used from
+ * @param tagFilter - Optional tag filter active in the search at the time
  */
-export async function recordSnippetUsage(snippetId: number): Promise<void> {
-  await invoke<void>('record_snippet_usage', { snippetId });
+export async function recordSnippetUsage(
+  snippetId: number,
+  source?: UsageSource,
+  tagFilter?: string | null
+): Promise<void> {
+  await invoke<void>('record_snippet_usage', {
+    snippetId,
+    source: source ?? null,
+    tagFilter: tagFilter ?? null,
+  });
 }
 
 /**
@@ -118,14 +453,322 @@ export async function getGlobalAnalytics(
   });
 }
 
+/**
+ * Retrieves usage grouped by the tags of the used snippets
+ * @param since - Optional Unix timestamp; only usages from then on are counted
+ * @returns Tags with usage and tag-filter counts, most used first
+ */
+export async function getAnalyticsByTag(since?: number): Promise<TagUsage[]> {
+  return await invoke<TagUsage[]>('get_analytics_by_tag', { since: since ?? null });
+}
+
+/**
+ * Retrieves usage grouped by entry point (search overlay, shortcuts, API)
+ * @param since - Optional Unix timestamp; only usages from then on are counted
+ * @returns Entry points, most used first
+ */
+export async function getAnalyticsBySource(since?: number): Promise<SourceUsage[]> {
+  return await invoke<SourceUsage[]>('get_analytics_by_source', { since: since ?? null });
+}
+
+/**
+ * Retrieves usage counts over time, globally and for the most used snippets
+ * @param bucket - Bucket width: day, week or month
+ * @param start - Optional range start as a Unix timestamp (default: 30 days before end)
+ * @param end - Optional range end as a Unix timestamp (default: now)
+ * @param snippetLimit - Optional maximum number of per-snippet series (default: 10)
+ * @returns Series with one point per bucket, including empty buckets
+ */
+export async function getUsageTrends(
+  bucket: TrendBucket,
+  start?: number,
+  end?: number,
+  snippetLimit?: number
+): Promise<UsageTrends> {
+  return await invoke<UsageTrends>('get_usage_trends', {
+    bucket,
+    start: start ?? null,
+    end: end ?? null,
+    snippetLimit: snippetLimit ?? null,
+  });
+}
+
+/**
+ * Retrieves usage streaks, estimated keystrokes saved and the busiest hours
+ * @param utcOffsetMinutes - Optional time zone offset in minutes east of UTC
+ *   (default: the local time zone)
+ * @returns Daily and weekly streaks, keystrokes saved and usage per hour
+ */
+export async function getProductivitySummary(
+  utcOffsetMinutes?: number
+): Promise<ProductivitySummary> {
+  return await invoke<ProductivitySummary>('get_productivity_summary', {
+    utcOffsetMinutes: utcOffsetMinutes ?? -new Date().getTimezoneOffset(),
+  });
+}
+
 /**
  * Copies snippets to clipboard and records usage analytics
  * This combines the copy operation with automatic usage tracking
- * The backend fetches snippet content and concatenates it before copying
- * @param snippetIds - Array of snippet IDs being copied (in desired order)
+ * The backend fetches snippet content, fills each snippet's placeholders and concatenates it
+ * @param snippetIds - Array of snippet IDs being copied, in the order they are joined
+ * @param confirmDynamic - Required when any snippet is dynamic, after the user
+ *   confirmed the commands from `getDynamicSnippetCommands`
+ * @param source - Optional entry point, recorded with the usage
+ * @param tagFilter - Optional tag filter active in the search, recorded with the usage
+ * @param separator - Optional text put between snippets (default: a blank line)
+ * @param values - Optional placeholder values by snippet ID, then parameter name
+ * @returns Snippets whose required parameters were left unfilled and copied as written
  */
-export async function copySnippetsWithAnalytics(snippetIds: number[]): Promise<void> {
-  await invoke<void>('copy_snippets_with_analytics', { snippetIds });
+export async function copySnippetsWithAnalytics(
+  snippetIds: number[],
+  confirmDynamic?: boolean,
+  source?: UsageSource,
+  tagFilter?: string | null,
+  separator?: string,
+  values?: Record<number, Record<string, string>>
+): Promise<UnfilledParameters[]> {
+  return await invoke<UnfilledParameters[]>('copy_snippets_with_analytics', {
+    snippetIds,
+    confirmDynamic: confirmDynamic ?? null,
+    source: source ?? null,
+    tagFilter: tagFilter ?? null,
+    separator: separator ?? null,
+    values: values ?? null,
+  });
+}
+
+/**
+ * Copies snippets to the clipboard as one text, joined by a separator, and records usage
+ * Unlike `copySnippetsWithAnalytics`, a missing snippet fails the copy
+ * @param snippetIds - Snippet IDs in the order they are joined; may repeat
+ * @param separator - Optional text put between snippets (default: a blank line)
+ * @param confirmDynamic - Required when any snippet is dynamic
+ * @param source - Optional entry point, recorded with the usage
+ * @returns The text that was copied
+ */
+export async function composeSnippets(
+  snippetIds: number[],
+  separator?: string,
+  confirmDynamic?: boolean,
+  source?: UsageSource
+): Promise<string> {
+  return await invoke<string>('compose_snippets', {
+    snippetIds,
+    separator: separator ?? null,
+    confirmDynamic: confirmDynamic ?? null,
+    source: source ?? null,
+  });
+}
+
+/**
+ * Copies the most recently used snippet to the clipboard and records usage
+ * @returns ID of the copied snippet, or null if no snippet has been used yet
+ */
+export async function copyLastUsedSnippet(): Promise<number | null> {
+  return await invoke<number | null>('copy_last_used_snippet');
+}
+
+/**
+ * Copies the snippet assigned to a numbered slot and records usage
+ * @param slot - Slot number, 1 through 9
+ * @returns ID of the copied snippet, or null if the slot is empty
+ */
+export async function copySlotSnippet(slot: number): Promise<number | null> {
+  return await invoke<number | null>('copy_slot_snippet', { slot });
+}
+
+/**
+ * Assigns a snippet to a numbered slot (Cmd/Ctrl+Shift+1..9), or clears the slot
+ * A snippet occupies at most one slot; assigning it elsewhere moves it
+ * @param slot - Slot number, 1 through 9
+ * @param snippetId - Snippet to assign, or null to clear the slot
+ * @returns Slot assignments after the change
+ */
+export async function assignSnippetSlot(
+  slot: number,
+  snippetId: number | null
+): Promise<SlotAssignment[]> {
+  return await invoke<SlotAssignment[]>('assign_snippet_slot', { slot, snippetId });
+}
+
+/**
+ * Checks a shortcut's format and whether another application already holds it
+ * Performs a trial registration that is released immediately
+ * @param shortcut - Shortcut string, e.g. "CommandOrControl+Shift+K"
+ * @returns Parsed parts, a platform display string and conflict details
+ */
+export async function checkShortcut(shortcut: string): Promise<ShortcutCheck> {
+  return await invoke<ShortcutCheck>('check_shortcut', { shortcut });
+}
+
+/**
+ * Gets the snippets assigned to numbered slots, in slot order
+ */
+export async function getSlotAssignments(): Promise<SlotAssignment[]> {
+  return await invoke<SlotAssignment[]>('get_slot_assignments');
+}
+
+/**
+ * Copies snippets to the clipboard as one markdown document and records usage
+ * Each snippet becomes a section with its content in a fenced code block and a tag footer
+ * @param snippetIds - Snippets to include, in document order
+ * @param source - Optional entry point, recorded with the usage
+ * @param tagFilter - Optional tag filter active in the search, recorded with the usage
+ * @returns The markdown that was copied
+ */
+export async function copySnippetsAsMarkdown(
+  snippetIds: number[],
+  source?: UsageSource,
+  tagFilter?: string | null
+): Promise<string> {
+  return await invoke<string>('copy_snippets_as_markdown', {
+    snippetIds,
+    source: source ?? null,
+    tagFilter: tagFilter ?? null,
+  });
+}
+
+/**
+ * Gets the parameters a snippet declares for its `{{name}}` placeholders
+ * @param id - Snippet ID
+ * @returns Parameters in form order; empty when the snippet has none
+ */
+export async function getSnippetParameters(id: number): Promise<SnippetParameter[]> {
+  return await invoke<SnippetParameter[]>('get_snippet_parameters', { id });
+}
+
+/**
+ * Declares the parameters of a snippet
+ * @param id - Snippet ID
+ * @param parameters - Parameter definitions; an empty list removes the form
+ */
+export async function setSnippetParameters(
+  id: number,
+  parameters: SnippetParameter[]
+): Promise<void> {
+  await invoke('set_snippet_parameters', { id, parameters });
+}
+
+/**
+ * Fills a snippet's placeholders with form values, validated against the declared types
+ * @param id - Snippet ID
+ * @param values - Values by parameter name; empty values use the parameter's default
+ * @returns The rendered content
+ */
+export async function renderSnippetWithParameters(
+  id: number,
+  values: Record<string, string>
+): Promise<string> {
+  return await invoke<string>('render_snippet_with_parameters', { id, values });
+}
+
+/**
+ * Gets the search aliases of a snippet
+ * @param id - Snippet ID
+ * @returns Aliases such as "k8s" that bring the snippet up in search
+ */
+export async function getSnippetAliases(id: number): Promise<string[]> {
+  return await invoke<string[]>('get_snippet_aliases', { id });
+}
+
+/**
+ * Replaces the search aliases of a snippet
+ * @param id - Snippet ID
+ * @param aliases - Aliases; blank and duplicate ones are dropped, an empty list removes all
+ * @returns The aliases as stored
+ */
+export async function setSnippetAliases(id: number, aliases: string[]): Promise<string[]> {
+  return await invoke<string[]>('set_snippet_aliases', { id, aliases });
+}
+
+/**
+ * Adds a search alias to a snippet
+ * @param id - Snippet ID
+ * @param alias - Alias to add
+ * @returns All aliases of the snippet
+ */
+export async function addSnippetAlias(id: number, alias: string): Promise<string[]> {
+  return await invoke<string[]>('add_snippet_alias', { id, alias });
+}
+
+/**
+ * Removes a search alias from a snippet, ignoring case
+ * @param id - Snippet ID
+ * @param alias - Alias to remove
+ * @returns The remaining aliases of the snippet
+ */
+export async function removeSnippetAlias(id: number, alias: string): Promise<string[]> {
+  return await invoke<string[]>('remove_snippet_alias', { id, alias });
+}
+
+/**
+ * Sets or removes the trigger that expands into a snippet while text expansion is on
+ * @param id - Snippet ID
+ * @param trigger - Text such as ";sig", or null to remove the trigger
+ * @returns The trigger as stored
+ */
+export async function setSnippetExpansionTrigger(
+  id: number,
+  trigger: string | null
+): Promise<string | null> {
+  return await invoke<string | null>('set_snippet_expansion_trigger', { id, trigger });
+}
+
+/**
+ * Lists the snippets that have a trigger, by trigger; archived snippets are left out
+ */
+export async function getExpansionTriggers(): Promise<ExpansionTrigger[]> {
+  return await invoke<ExpansionTrigger[]>('get_expansion_triggers');
+}
+
+/**
+ * Turns text expansion on or off; saved with the other settings
+ * @returns Whether expansion is running, or why it could not start
+ */
+export async function setTextExpansionEnabled(enabled: boolean): Promise<ExpanderStatus> {
+  return await invoke<ExpanderStatus>('set_text_expansion_enabled', { enabled });
+}
+
+/**
+ * Gets whether text expansion is running
+ * @returns Running state, or why expansion could not start (e.g. a Wayland session)
+ */
+export async function getTextExpansionStatus(): Promise<ExpanderStatus> {
+  return await invoke<ExpanderStatus>('get_text_expansion_status');
+}
+
+/**
+ * Estimates the LLM token count of snippets, alone and copied together
+ * @param snippetIds - Snippets in copy order
+ * @returns Per-snippet counts and the total for the composed text (within about 15%)
+ */
+export async function estimateSnippetTokens(snippetIds: number[]): Promise<ComposedTokenEstimate> {
+  return await invoke<ComposedTokenEstimate>('estimate_snippet_tokens', { snippetIds });
+}
+
+/**
+ * Estimates the LLM token count of unsaved text
+ * @param text - Text to measure
+ */
+export async function estimateTextTokens(text: string): Promise<number> {
+  return await invoke<number>('estimate_text_tokens', { text });
+}
+
+/**
+ * Gets the shell commands that copying these snippets would run
+ * @param snippetIds - Snippets about to be copied
+ * @returns Dynamic snippets among them, in order
+ */
+export async function getDynamicSnippetCommands(snippetIds: number[]): Promise<DynamicCommand[]> {
+  return await invoke<DynamicCommand[]>('get_dynamic_snippet_commands', { snippetIds });
+}
+
+/**
+ * Gets the sandbox dynamic snippet commands run in
+ */
+export async function getDynamicSnippetEnvironment(): Promise<ExecutionEnvironment> {
+  return await invoke<ExecutionEnvironment>('get_dynamic_snippet_environment');
 }
 
 /**
@@ -144,6 +787,14 @@ export async function clearAnalyticsBefore(beforeTimestamp: number): Promise<num
   return await invoke<number>('clear_analytics_before', { beforeTimestamp });
 }
 
+/**
+ * Gets whether usage is being recorded under the privacy settings, and how
+ * many usage events are stored
+ */
+export async function getAnalyticsConsentState(): Promise<AnalyticsConsentState> {
+  return await invoke<AnalyticsConsentState>('get_analytics_consent_state');
+}
+
 /**
  * Export analytics data to JSON format
  * @returns JSON string containing all analytics data
@@ -152,6 +803,33 @@ export async function exportAnalyticsToJson(): Promise<string> {
   return await invoke<string>('export_analytics_to_json');
 }
 
+/**
+ * Export analytics data to RFC 4180 CSV for spreadsheets
+ * @param columns - Optional columns in order; defaults to snippet name, timestamp, ISO date and tags
+ * @param start - Optional Unix timestamp in seconds; only usages from then on are exported
+ * @param end - Optional Unix timestamp in seconds; only usages before then are exported
+ * @returns CSV text with a header row, most recent usage first
+ */
+export async function exportAnalyticsToCsv(
+  columns?: AnalyticsCsvColumn[],
+  start?: number,
+  end?: number
+): Promise<string> {
+  return await invoke<string>('export_analytics_to_csv', {
+    columns: columns ?? null,
+    start: start ?? null,
+    end: end ?? null,
+  });
+}
+
+/**
+ * Gets aggregate content statistics for the library insights page
+ * @returns Word/character totals, languages, tag co-occurrence and growth
+ */
+export async function getLibraryStatistics(): Promise<LibraryStatistics> {
+  return await invoke<LibraryStatistics>('get_library_statistics');
+}
+
 // ============================================================================
 // Settings Commands
 // ============================================================================
@@ -172,6 +850,33 @@ export async function updateSettings(settings: AppSettings): Promise<void> {
   await invoke<void>('update_settings', { settings });
 }
 
+/**
+ * Exports settings to a file, leaving out credentials
+ * @param path - Destination file; `.toml` writes TOML, anything else JSON
+ */
+export async function exportSettings(path: string): Promise<void> {
+  await invoke<void>('export_settings', { path });
+}
+
+/**
+ * Imports settings from a file written by `exportSettings`, keeping the current
+ * credentials and re-registering global shortcuts
+ * @param path - TOML or JSON settings file
+ * @returns The settings now in effect
+ */
+export async function importSettings(path: string): Promise<AppSettings> {
+  return await invoke<AppSettings>('import_settings', { path });
+}
+
+/**
+ * Gets the settings sections that were reset to their defaults on load because
+ * the stored value was invalid
+ * @returns Top-level setting keys, e.g. `search_settings`
+ */
+export async function getInvalidSettingsSections(): Promise<string[]> {
+  return await invoke<string[]>('get_invalid_settings_sections');
+}
+
 /**
  * Gets the current storage type
  * @returns Current storage type
@@ -188,6 +893,121 @@ export async function setStorageType(storageType: StorageType): Promise<void> {
   await invoke<void>('set_storage_type', { storageType });
 }
 
+/**
+ * Gets whether the local HTTP API server is running
+ * @returns Listening port, or the error that kept the server from starting
+ */
+export async function getApiServerStatus(): Promise<ApiServerStatus> {
+  return await invoke<ApiServerStatus>('get_api_server_status');
+}
+
+/**
+ * Lists the custom themes in the config dir's `themes` folder
+ * @returns Valid themes sorted by name; broken files are skipped
+ */
+export async function listCustomThemes(): Promise<CustomTheme[]> {
+  return await invoke<CustomTheme[]>('list_custom_themes');
+}
+
+/**
+ * Saves a custom theme, replacing any theme with the same name
+ * @param theme - Theme to save
+ * @returns The theme as saved
+ */
+export async function saveCustomTheme(theme: CustomTheme): Promise<CustomTheme> {
+  return await invoke<CustomTheme>('save_custom_theme', { theme });
+}
+
+/**
+ * Imports a pywal or wallust color scheme as a custom theme
+ * @param path - Scheme to import (default: `~/.cache/wal/colors.json`)
+ * @param name - Theme name (default: `Pywal`, replacing the last import)
+ * @returns The theme as saved
+ */
+export async function importPywalTheme(path?: string, name?: string): Promise<CustomTheme> {
+  return await invoke<CustomTheme>('import_pywal_theme', {
+    path: path ?? null,
+    name: name ?? null,
+  });
+}
+
+/**
+ * Deletes a custom theme, switching back to the built-in palette if it was active
+ * @param name - Theme name
+ */
+export async function deleteCustomTheme(name: string): Promise<void> {
+  await invoke<void>('delete_custom_theme', { name });
+}
+
+/**
+ * Makes a custom theme the active one in every window
+ * @param name - Theme name, or null for the built-in light and dark palettes
+ * @returns The theme now active
+ */
+export async function applyCustomTheme(name: string | null): Promise<CustomTheme | null> {
+  return await invoke<CustomTheme | null>('apply_custom_theme', { name });
+}
+
+/**
+ * Gets the active custom theme
+ * @returns The theme, or null when none is set or it is no longer available
+ */
+export async function getActiveCustomTheme(): Promise<CustomTheme | null> {
+  return await invoke<CustomTheme | null>('get_active_custom_theme');
+}
+
+/**
+ * Gets whether the UI should be light or dark, resolving the `system` theme
+ * against the OS appearance
+ * @returns The theme setting, the appearance and its built-in palette
+ */
+export async function getResolvedTheme(): Promise<ResolvedTheme> {
+  return await invoke<ResolvedTheme>('get_resolved_theme');
+}
+
+// ============================================================================
+// API Token Commands
+// ============================================================================
+
+/**
+ * Mints a scoped token for the local REST API
+ * @param name - Label shown in the token list
+ * @param scopes - Scopes to grant
+ * @returns Token metadata and the plaintext secret (only returned once)
+ */
+export async function createApiToken(name: string, scopes: ApiScope[]): Promise<CreatedApiToken> {
+  return await invoke<CreatedApiToken>('create_api_token', { name, scopes });
+}
+
+/**
+ * Lists all API tokens, including revoked ones
+ * @returns Token metadata, newest first
+ */
+export async function listApiTokens(): Promise<ApiToken[]> {
+  return await invoke<ApiToken[]>('list_api_tokens');
+}
+
+/**
+ * Revokes an API token
+ * @param id - ID of the token to revoke
+ */
+export async function revokeApiToken(id: number): Promise<void> {
+  await invoke<void>('revoke_api_token', { id });
+}
+
+/**
+ * Gets the audit trail of REST API requests
+ * @param tokenId - Only return entries for this token (optional)
+ * @param limit - Maximum number of entries (default: 100)
+ * @returns Audit entries, newest first
+ */
+export async function getApiTokenAudit(
+  tokenId?: number,
+  limit?: number
+): Promise<ApiTokenAuditEntry[]> {
+  return await invoke<ApiTokenAuditEntry[]>('get_api_token_audit', { tokenId, limit });
+}
+
 // ============================================================================
 // Storage Commands
 // ============================================================================
@@ -203,9 +1023,54 @@ export async function backupDatabase(): Promise<BackupInfo> {
 /**
  * Restores database from a backup file
  * @param backupPath - Path to the backup file
+ * @param dryRun - Only compare the backup with the current database (default: false)
+ * @returns The snippets the restore adds, removes and changes
+ */
+export async function restoreDatabase(
+  backupPath: string,
+  dryRun?: boolean
+): Promise<RestoreReport> {
+  return await invoke<RestoreReport>('restore_database', { backupPath, dryRun });
+}
+
+/**
+ * Deletes all local data and restarts the app as a fresh install
+ *
+ * Call once without a token to get a confirmation challenge, then again with
+ * its token (and the same `deleteBackups`) within a minute to perform the reset.
+ * @param deleteBackups - Also delete the backups directory
+ * @param confirmationToken - Token from the challenge returned by the first call
  */
-export async function restoreDatabase(backupPath: string): Promise<void> {
-  await invoke<void>('restore_database', { backupPath });
+export async function factoryReset(
+  deleteBackups: boolean,
+  confirmationToken?: string
+): Promise<FactoryResetOutcome> {
+  return await invoke<FactoryResetOutcome>('factory_reset', {
+    deleteBackups,
+    confirmationToken: confirmationToken ?? null,
+  });
+}
+
+/**
+ * Lists the contents of a backup without restoring it
+ * @param backupPath - Path to the backup file
+ * @returns Snippet counts and summaries from the backup
+ */
+export async function previewBackup(backupPath: string): Promise<BackupPreview> {
+  return await invoke<BackupPreview>('preview_backup', { backupPath });
+}
+
+/**
+ * Merges selected snippets from a backup into the current database
+ * @param backupPath - Path to the backup file
+ * @param snippetIds - Backup snippet IDs (from previewBackup) to restore
+ * @returns Number of snippets restored
+ */
+export async function restoreSnippetsFromBackup(
+  backupPath: string,
+  snippetIds: number[]
+): Promise<number> {
+  return await invoke<number>('restore_snippets_from_backup', { backupPath, snippetIds });
 }
 
 /**
@@ -216,21 +1081,159 @@ export async function getDatabaseStats(): Promise<DatabaseStats> {
   return await invoke<DatabaseStats>('get_database_stats');
 }
 
+/**
+ * Gets the effective SQLite pragmas (journal mode, synchronous, busy timeout)
+ * connection pool usage, and the schema version and missing schema objects
+ */
+export async function getDatabaseDiagnostics(): Promise<DatabaseDiagnostics> {
+  return await invoke<DatabaseDiagnostics>('get_database_diagnostics');
+}
+
+/**
+ * Exports a zip of window, database, resource, startup and clipboard diagnostics,
+ * the settings with secrets redacted, and recent logs, to attach to bug reports
+ * @param exportPath - Destination of the zip file
+ * @returns The files written and any sections that could not be collected
+ */
+export async function exportDiagnosticsBundle(
+  exportPath: string
+): Promise<DiagnosticsBundleReport> {
+  return await invoke<DiagnosticsBundleReport>('export_diagnostics_bundle', { exportPath });
+}
+
+/**
+ * Compacts the database (VACUUM) and refreshes query planner statistics
+ * @returns Database size before and after, and the space reclaimed
+ */
+export async function optimizeDatabase(): Promise<OptimizeReport> {
+  return await invoke<OptimizeReport>('optimize_database');
+}
+
+/**
+ * Applies pending migrations, recreates missing tables, indexes and triggers,
+ * and rebuilds the search index
+ * @returns The migrations applied, objects recreated and rows in the search index
+ */
+export async function repairDatabase(): Promise<RepairReport> {
+  return await invoke<RepairReport>('repair_database');
+}
+
+/**
+ * Compares the full-text search index with the snippets table
+ * @returns Row counts and the missing, orphaned and stale index rows
+ */
+export async function checkSearchIndexConsistency(): Promise<SearchIndexConsistency> {
+  return await invoke<SearchIndexConsistency>('check_search_index_consistency');
+}
+
+/**
+ * Drops and repopulates the full-text search index from the snippets table
+ * @returns Consistency of the rebuilt index
+ */
+export async function rebuildSearchIndex(): Promise<SearchIndexConsistency> {
+  return await invoke<SearchIndexConsistency>('rebuild_search_index');
+}
+
 /**
  * Exports database to JSON format
  * @param exportPath - Path where the export file should be saved
+ * @param filter - Which snippets to export; all of them when omitted
+ * @returns Number of snippets exported
+ */
+export async function exportToJson(exportPath: string, filter?: ExportFilter): Promise<number> {
+  return await invoke<number>('export_to_json', { exportPath, filter });
+}
+
+/**
+ * Imports snippets from JSON file in one transaction, emitting `import-progress`
+ * events; nothing is written if any snippet fails
+ * @param importPath - Path to the import file
+ * @param resume - Continue an interrupted import of this file (default: true)
+ * @param mode - What to do with snippets whose name is taken (default: 'overwrite')
+ * @param dryRun - Only report what the import would do (default: false)
+ * @returns The snippets created, updated, skipped and in conflict
  */
-export async function exportToJson(exportPath: string): Promise<void> {
-  await invoke<void>('export_to_json', { exportPath });
+export async function importFromJson(
+  importPath: string,
+  resume?: boolean,
+  mode?: ImportMode,
+  dryRun?: boolean
+): Promise<ImportReport> {
+  return await invoke<ImportReport>('import_from_json', { importPath, resume, mode, dryRun });
 }
 
 /**
- * Imports snippets from JSON file
+ * Checks whether an earlier import of a file was interrupted
  * @param importPath - Path to the import file
+ * @returns The unfinished job, or null if there is nothing to resume
+ */
+export async function getResumableImport(importPath: string): Promise<ImportJob | null> {
+  return await invoke<ImportJob | null>('get_resumable_import', { importPath });
+}
+
+/**
+ * Lists imports that were interrupted before finishing
+ * @returns Unfinished import jobs, most recent first
+ */
+export async function listUnfinishedImports(): Promise<ImportJob[]> {
+  return await invoke<ImportJob[]>('list_unfinished_imports');
+}
+
+/**
+ * Forgets an unfinished import; snippets it already imported are kept
+ * @param id - Import job ID
+ */
+export async function discardImportJob(id: number): Promise<void> {
+  await invoke<void>('discard_import_job', { id });
+}
+
+/**
+ * Exports snippets as markdown files (one per snippet, with front matter)
+ * @param exportDir - Directory to write the files into
+ * @returns Number of files written
+ */
+export async function exportToMarkdown(exportDir: string): Promise<number> {
+  return await invoke<number>('export_to_markdown', { exportDir });
+}
+
+/**
+ * Imports snippets from a directory of markdown files
+ * @param importDir - Directory containing `.md` files
  * @returns Number of snippets imported
  */
-export async function importFromJson(importPath: string): Promise<number> {
-  return await invoke<number>('import_from_json', { importPath });
+export async function importFromMarkdown(importDir: string): Promise<number> {
+  return await invoke<number>('import_from_markdown', { importDir });
+}
+
+/**
+ * Exports all snippets to a VS Code `.code-snippets` file
+ * @param exportPath - Path of the file to write
+ * @returns Number of snippets exported
+ */
+export async function exportToVscodeSnippets(exportPath: string): Promise<number> {
+  return await invoke<number>('export_to_vscode_snippets', { exportPath });
+}
+
+/**
+ * Imports snippets from a VS Code snippets file or directory
+ * @param importPath - `.code-snippets`/`.json` file or a directory of them
+ * @returns Number of snippets imported
+ */
+export async function importFromVscodeSnippets(importPath: string): Promise<number> {
+  return await invoke<number>('import_from_vscode_snippets', { importPath });
+}
+
+/**
+ * Imports a snippet library exported from Alfred or Raycast
+ * @param importPath - `.alfredsnippets` bundle or Raycast JSON export
+ * @param source - Source app; detected from the file extension when omitted
+ * @returns Number of snippets created and the names skipped as duplicates
+ */
+export async function importFromSnippetApp(
+  importPath: string,
+  source?: ImportSource
+): Promise<ImportSummary> {
+  return await invoke<ImportSummary>('import_from_snippet_app', { importPath, source });
 }
 
 /**
@@ -257,6 +1260,229 @@ export async function updateBackupConfig(config: BackupConfig): Promise<void> {
   await invoke<void>('update_backup_config', { config });
 }
 
+/**
+ * Prunes old backups immediately using the current retention policy
+ * @returns Number of deleted backups and reclaimed space
+ */
+export async function pruneBackupsNow(): Promise<PruneReport> {
+  return await invoke<PruneReport>('prune_backups_now');
+}
+
+/**
+ * Verifies a backup's checksum and SQLite integrity without restoring it
+ * @param backupPath - Path to the backup file
+ * @returns Verification result; `valid` is false for corrupt or tampered backups
+ */
+export async function verifyBackup(backupPath: string): Promise<BackupVerification> {
+  return await invoke<BackupVerification>('verify_backup', { backupPath });
+}
+
+/**
+ * Retrieves the integrity report collected when the app started
+ * @returns Startup report including temp files removed by the janitor
+ */
+export async function getStartupReport(): Promise<StartupReport> {
+  return await invoke<StartupReport>('get_startup_report');
+}
+
+// ============================================================================
+// Gist Commands
+// ============================================================================
+
+/**
+ * Imports every file of a GitHub gist as a snippet
+ * @param gist - Gist ID or URL
+ * @returns Number of snippets imported
+ */
+export async function importFromGist(gist: string): Promise<number> {
+  return await invoke<number>('import_from_gist', { gist });
+}
+
+/**
+ * Shares a snippet through an encrypted one-time link; only ciphertext leaves the machine
+ * @param id - Snippet ID
+ * @returns Link with the decryption key in its fragment
+ */
+export async function shareSnippet(id: number): Promise<string> {
+  return await invoke<string>('share_snippet', { id });
+}
+
+/**
+ * Imports a snippet from a share link, deleting the share
+ * @param url - Link from `shareSnippet`
+ * @param mode - What to do if the name is taken (default: `rename`)
+ * @returns What was imported; `conflicted` lists a taken name and how it was resolved
+ */
+export async function importSharedSnippet(
+  url: string,
+  mode?: ImportMode
+): Promise<ImportReport> {
+  return await invoke<ImportReport>('import_shared_snippet', { url, mode });
+}
+
+/**
+ * Subscribes to a team's read-only snippet feed and syncs it
+ * @param name - Name of the team collection
+ * @param url - URL of a Snips JSON export or of a Git repository
+ * @param kind - Guessed from the URL when omitted
+ * @param intervalHours - Hours between syncs (default: 24)
+ * @returns The feed; a failed first sync is in its `last_error`
+ */
+export async function subscribeFeed(
+  name: string,
+  url: string,
+  kind?: FeedKind,
+  intervalHours?: number
+): Promise<SnippetFeed> {
+  return await invoke<SnippetFeed>('subscribe_feed', { name, url, kind, intervalHours });
+}
+
+/**
+ * Unsubscribes from a feed and removes its snippets; local forks are kept
+ * @param id - Feed ID
+ */
+export async function unsubscribeFeed(id: number): Promise<void> {
+  await invoke<void>('unsubscribe_feed', { id });
+}
+
+/**
+ * Lists the subscribed team feeds with their sync state
+ */
+export async function listFeeds(): Promise<SnippetFeed[]> {
+  return await invoke<SnippetFeed[]>('list_feeds');
+}
+
+/**
+ * Syncs a feed now, regardless of its schedule
+ * @param id - Feed ID
+ */
+export async function syncFeedNow(id: number): Promise<FeedSyncReport> {
+  return await invoke<FeedSyncReport>('sync_feed_now', { id });
+}
+
+/**
+ * Gets the feed a snippet is synced from, or the feed snippet it forks
+ * @param id - Snippet ID
+ */
+export async function getSnippetProvenance(id: number): Promise<SnippetProvenance> {
+  return await invoke<SnippetProvenance>('get_snippet_provenance', { id });
+}
+
+/**
+ * Stores the GitHub access token used for gists in the OS keychain
+ * @param token - Token with the `gist` scope; null or empty removes it
+ */
+export async function setGistAccessToken(token: string | null): Promise<void> {
+  await invoke<void>('set_gist_access_token', { token });
+}
+
+/**
+ * Checks whether a GitHub access token is stored, without revealing it
+ * @returns Whether gists can be published
+ */
+export async function hasGistAccessToken(): Promise<boolean> {
+  return await invoke<boolean>('has_gist_access_token');
+}
+
+/**
+ * Publishes a snippet as a new GitHub gist (requires a stored access token)
+ * @param id - Snippet ID
+ * @param isPublic - Create a public gist instead of a secret one
+ * @returns The new gist's ID and URL
+ */
+export async function exportSnippetToGist(
+  id: number,
+  isPublic?: boolean
+): Promise<GistExportResult> {
+  return await invoke<GistExportResult>('export_snippet_to_gist', { id, public: isPublic });
+}
+
+// ============================================================================
+// Source Commands
+// ============================================================================
+
+/**
+ * Gets the remote file a snippet mirrors
+ * @param id - Snippet ID
+ * @returns The source, or null if the snippet is not a mirror
+ */
+export async function getSnippetSource(id: number): Promise<SnippetSource | null> {
+  return await invoke<SnippetSource | null>('get_snippet_source', { id });
+}
+
+/**
+ * Makes a snippet mirror a remote file (raw URL, GitHub file page or gist)
+ * @param id - Snippet ID
+ * @param url - http(s) URL of the source, or null to stop mirroring
+ */
+export async function setSnippetSource(id: number, url: string | null): Promise<void> {
+  await invoke<void>('set_snippet_source', { id, url });
+}
+
+/**
+ * Fetches a snippet's source now, regardless of the refresh schedule
+ * @param id - Snippet ID
+ * @returns A summary of the change, or null if the content was unchanged
+ */
+export async function refreshSnippetSource(id: number): Promise<SourceChange | null> {
+  return await invoke<SourceChange | null>('refresh_snippet_source', { id });
+}
+
+/**
+ * Gets the earlier contents of a snippet, newest first
+ * @param id - Snippet ID
+ */
+export async function getSnippetVersions(id: number): Promise<SnippetVersion[]> {
+  return await invoke<SnippetVersion[]>('get_snippet_versions', { id });
+}
+
+// ============================================================================
+// Attachment Commands
+// ============================================================================
+
+/**
+ * Creates a snippet holding the image on the clipboard
+ * Copying the snippet puts the image back on the clipboard
+ * @param name - Unique snippet name
+ * @param description - Optional description
+ * @param tags - Optional tags
+ */
+export async function createImageSnippet(
+  name: string,
+  description?: string,
+  tags?: string[]
+): Promise<Snippet> {
+  return await invoke<Snippet>('create_image_snippet', { name, description, tags });
+}
+
+/**
+ * Attaches an image file to a snippet by reference; the file is read on every copy
+ * @param snippetId - Snippet ID
+ * @param path - Absolute path of the image file
+ */
+export async function attachImageFile(
+  snippetId: number,
+  path: string
+): Promise<SnippetAttachment> {
+  return await invoke<SnippetAttachment>('attach_image_file', { snippetId, path });
+}
+
+/**
+ * Gets a snippet's attachments, without their data
+ * @param snippetId - Snippet ID
+ */
+export async function getSnippetAttachments(snippetId: number): Promise<SnippetAttachment[]> {
+  return await invoke<SnippetAttachment[]>('get_snippet_attachments', { snippetId });
+}
+
+/**
+ * Removes an attachment; files attached by reference are not deleted
+ * @param id - Attachment ID
+ */
+export async function deleteSnippetAttachment(id: number): Promise<void> {
+  await invoke<void>('delete_snippet_attachment', { id });
+}
+
 // ============================================================================
 // Clipboard Commands
 // ============================================================================
@@ -268,3 +1494,58 @@ export async function updateBackupConfig(config: BackupConfig): Promise<void> {
 export async function copyToClipboard(text: string): Promise<void> {
   await invoke<void>('copy_to_clipboard', { text });
 }
+
+/**
+ * Copies formatted text to the system clipboard
+ * Apps that understand formatting paste the HTML (or RTF on macOS), others the plain text
+ * On Linux only the HTML is offered
+ * @param text - Plain text version
+ * @param html - HTML version
+ */
+export async function copyToClipboardRich(text: string, html: string): Promise<void> {
+  await invoke<void>('copy_to_clipboard_rich', { text, html });
+}
+
+/**
+ * Reports which clipboard mechanism is in use and whether copies survive Snips exiting
+ * On Linux this requires wl-clipboard (Wayland) or xclip (X11) to be installed
+ */
+export async function probeClipboardSupport(): Promise<ClipboardSupport> {
+  return await invoke<ClipboardSupport>('probe_clipboard_support');
+}
+
+// ============================================================================
+// Window Commands
+// ============================================================================
+
+/**
+ * Gets window diagnostics, including whether focus was returned to the
+ * previously active app after the search overlay last closed
+ */
+export async function getWindowDiagnostics(): Promise<WindowDiagnostics> {
+  return await invoke<WindowDiagnostics>('get_window_diagnostics');
+}
+
+/**
+ * Gets the app's memory, file descriptor, thread, task and window counts,
+ * the last hour of samples, and any resources that look like they are leaking
+ */
+export async function getResourceUsage(): Promise<ResourceUsage> {
+  return await invoke<ResourceUsage>('get_resource_usage');
+}
+
+/**
+ * Forgets remembered window sizes and positions; open windows return to their
+ * default size, centered
+ */
+export async function resetWindowLayout(): Promise<void> {
+  await invoke<void>('reset_window_layout');
+}
+
+/**
+ * Hides the calling overlay on Escape if the auto-hide preferences allow;
+ * resolves to whether it was hidden
+ */
+export async function handleOverlayEscape(): Promise<boolean> {
+  return await invoke<boolean>('handle_overlay_escape');
+}
@@ -4,7 +4,14 @@
 
 import { invoke } from '@tauri-apps/api/core';
 
-import type { Tag } from '@/types/tag';
+import type { SnippetKind } from '@/types/snippet';
+import type {
+  Tag,
+  TagReassignment,
+  TagStats,
+  TagSuggestion,
+  TagTreeNode,
+} from '@/types/tag';
 
 /**
  * Get all tags with their colors
@@ -25,3 +32,88 @@ export async function getTags(): Promise<Tag[]> {
 export async function updateTagColor(tagName: string, color: string): Promise<void> {
   return invoke<void>('update_tag_color_cmd', { tagName, color });
 }
+
+/**
+ * Rename a tag, keeping its color and snippets
+ *
+ * @param oldName - Current name of the tag
+ * @param newName - New name; must not belong to another tag
+ * @returns Promise resolving to the renamed tag
+ */
+export async function renameTag(oldName: string, newName: string): Promise<Tag> {
+  return invoke<Tag>('rename_tag', { oldName, newName });
+}
+
+/**
+ * Merge one tag into another, deleting the source tag
+ *
+ * @param source - Tag to merge away
+ * @param target - Tag that takes over the snippets
+ * @returns Promise resolving to what was merged
+ */
+export async function mergeTags(source: string, target: string): Promise<TagReassignment> {
+  return invoke<TagReassignment>('merge_tags', { source, target });
+}
+
+/**
+ * Delete a tag, optionally moving its snippets to another tag
+ *
+ * @param name - Tag to delete
+ * @param reassignTo - Optional tag that takes over the snippets
+ * @returns Promise resolving to what was deleted
+ */
+export async function deleteTag(name: string, reassignTo?: string): Promise<TagReassignment> {
+  return invoke<TagReassignment>('delete_tag', { name, reassignTo: reassignTo ?? null });
+}
+
+/**
+ * Get every tag with its snippet count and last-used timestamp
+ *
+ * @returns Promise resolving to usage statistics per tag, sorted by name
+ */
+export async function getTagStats(): Promise<TagStats[]> {
+  return invoke<TagStats[]>('get_tag_stats');
+}
+
+/**
+ * Delete tags that are not associated with any snippet
+ *
+ * @returns Promise resolving to the names of the deleted tags
+ */
+export async function cleanupOrphanTags(): Promise<string[]> {
+  return invoke<string[]>('cleanup_orphan_tags');
+}
+
+/**
+ * Get all tags arranged as a hierarchy of `/`-separated paths
+ *
+ * @returns Promise resolving to the top-level nodes of the tag tree
+ */
+export async function getTagTree(): Promise<TagTreeNode[]> {
+  return invoke<TagTreeNode[]>('get_tag_tree');
+}
+
+/**
+ * Suggest tags for a snippet from its name and content
+ *
+ * @param content - Snippet content
+ * @param options - Optional name, kind, tags already on the snippet and result limit
+ * @returns Promise resolving to ranked suggestions, best first
+ */
+export async function suggestTagsForContent(
+  content: string,
+  options: {
+    name?: string;
+    kind?: SnippetKind;
+    currentTags?: string[];
+    limit?: number;
+  } = {}
+): Promise<TagSuggestion[]> {
+  return invoke<TagSuggestion[]>('suggest_tags_for_content', {
+    content,
+    name: options.name ?? null,
+    kind: options.kind ?? null,
+    currentTags: options.currentTags ?? null,
+    limit: options.limit ?? null,
+  });
+}
@@ -45,3 +45,164 @@ export interface GlobalAnalytics {
   most_used_snippets: MostUsedSnippet[];
   recent_activity: RecentActivity[];
 }
+
+/**
+ * Entry point a snippet was used from
+ */
+export type UsageSource =
+  | 'search_overlay'
+  | 'management'
+  | 'quick_paste'
+  | 'slot_shortcut'
+  | 'api'
+  | 'tray'
+  | 'expansion';
+
+/**
+ * Usage of the snippets carrying a tag
+ */
+export interface TagUsage {
+  tag_name: string;
+  /** Usages of snippets with this tag */
+  usage_count: number;
+  /** Usages made while the search was filtered to this tag */
+  filtered_count: number;
+  last_used: number | null;
+}
+
+/**
+ * Usage through one entry point
+ */
+export interface SourceUsage {
+  /** Null for usages recorded before entry points were tracked */
+  source: UsageSource | null;
+  usage_count: number;
+  last_used: number | null;
+}
+
+/**
+ * Whether usage is being recorded and how much has been stored
+ */
+export interface AnalyticsConsentState {
+  enable_analytics: boolean;
+  track_usage: boolean;
+  /** Usages are recorded right now, i.e. both switches are on */
+  recording: boolean;
+  purge_on_disable: boolean;
+  stored_events: number;
+  /** Unix timestamp of the oldest stored event */
+  oldest_event: number | null;
+}
+
+/**
+ * Column of an analytics CSV export
+ */
+export type AnalyticsCsvColumn = 'snippet_name' | 'timestamp' | 'iso_date' | 'tags';
+
+/**
+ * Width of the time buckets in usage trends (UTC; weeks start on Monday)
+ */
+export type TrendBucket = 'day' | 'week' | 'month';
+
+/**
+ * Number of usages in one time bucket
+ */
+export interface TrendPoint {
+  /** Unix timestamp of the start of the bucket */
+  bucket_start: number;
+  count: number;
+}
+
+/**
+ * Usage over time of one snippet
+ */
+export interface SnippetTrend {
+  snippet_id: number;
+  snippet_name: string;
+  /** Usages in the whole range */
+  total: number;
+  /** One point per bucket, including empty buckets */
+  points: TrendPoint[];
+}
+
+/**
+ * Time series of usage counts, globally and per snippet
+ */
+export interface UsageTrends {
+  bucket: TrendBucket;
+  /** Start of the range (inclusive) */
+  start: number;
+  /** End of the range (exclusive) */
+  end: number;
+  /** One point per bucket, including empty buckets */
+  global: TrendPoint[];
+  /** Most used snippets in the range, most used first */
+  snippets: SnippetTrend[];
+}
+
+/**
+ * Usages in one hour of the day
+ */
+export interface HourUsage {
+  /** Hour of the day, 0 to 23, in the requested time zone */
+  hour: number;
+  count: number;
+}
+
+/**
+ * Usage streaks and estimated time saved, for the productivity dashboard
+ * (days and weeks in the requested time zone; weeks start on Monday)
+ */
+export interface ProductivitySummary {
+  total_usages: number;
+  /** Days with at least one usage */
+  active_days: number;
+  current_daily_streak: number;
+  longest_daily_streak: number;
+  current_weekly_streak: number;
+  longest_weekly_streak: number;
+  /** Content length of each used snippet times its uses */
+  keystrokes_saved: number;
+  /** Hours with at least one usage, busiest first */
+  busiest_hours: HourUsage[];
+}
+
+/**
+ * Number of snippets written in a language (null for prose/unrecognised)
+ */
+export interface LanguageCount {
+  language: string | null;
+  count: number;
+}
+
+/**
+ * Number of snippets carrying both tags
+ */
+export interface TagPair {
+  first: string;
+  second: string;
+  count: number;
+}
+
+/**
+ * Snippets added in one calendar month (UTC)
+ */
+export interface GrowthPoint {
+  /** Month in YYYY-MM form */
+  month: string;
+  added: number;
+  cumulative: number;
+}
+
+/**
+ * Aggregate content statistics for the library insights page
+ */
+export interface LibraryStatistics {
+  total_snippets: number;
+  total_words: number;
+  total_characters: number;
+  languages: LanguageCount[];
+  tag_cooccurrence: TagPair[];
+  growth: GrowthPoint[];
+  computed_at: number;
+}
@@ -0,0 +1,41 @@
+/**
+ * Permission granted to a REST API token.
+ * `write` implies `read`, and `read` implies `search`.
+ */
+export type ApiScope = 'search' | 'read' | 'write';
+
+/**
+ * Stored API token metadata (never includes the secret)
+ */
+export interface ApiToken {
+  id: number;
+  name: string;
+  token_prefix: string;
+  scopes: ApiScope[];
+  created_at: number;
+  last_used_at: number | null;
+  revoked_at: number | null;
+}
+
+/**
+ * A newly minted token; the secret is only returned once
+ */
+export interface CreatedApiToken {
+  token: ApiToken;
+  secret: string;
+}
+
+/**
+ * One REST API request recorded in the token audit trail
+ */
+export interface ApiTokenAuditEntry {
+  id: number;
+  token_id: number | null;
+  token_name: string | null;
+  method: string;
+  route: string;
+  required_scope: ApiScope | null;
+  allowed: boolean;
+  reason: string | null;
+  created_at: number;
+}
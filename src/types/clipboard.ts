@@ -0,0 +1,21 @@
+/**
+ * Mechanism used to read and write the system clipboard
+ */
+export type ClipboardBackend = 'mac_os' | 'wayland' | 'x11' | 'unsupported';
+
+/**
+ * What the clipboard can do in this session
+ */
+export interface ClipboardSupport {
+  backend: ClipboardBackend;
+  /** External program that writes the clipboard */
+  tool: string | null;
+  /** Whether the tool was found on PATH */
+  tool_available: boolean;
+  /** Copied text stays available after Snips exits */
+  persists_after_exit: boolean;
+  /** Highlighted text can be captured without simulating a copy */
+  primary_selection: boolean;
+  /** What to do when something is missing */
+  message: string | null;
+}
@@ -1,17 +1,132 @@
 // Snippet types
-export type { Snippet, CreateSnippetInput, UpdateSnippetInput, SearchResult } from './snippet';
+export type {
+  Snippet,
+  SnippetKind,
+  DynamicCommand,
+  ExecutionEnvironment,
+  CreateSnippetInput,
+  NameConflictMode,
+  CreateSnippetOutcome,
+  UpdateSnippetInput,
+  SearchResult,
+  SearchPage,
+  PreviewFormat,
+  RenderedPreview,
+  BatchSearchRequest,
+  BatchSearchResponse,
+  TagCount,
+  ScoreComponent,
+  ScoreBreakdown,
+  RankingExplanation,
+  SnippetTokenEstimate,
+  ComposedTokenEstimate,
+  SnippetParameterType,
+  SnippetParameter,
+  UnfilledParameters,
+  SlotAssignment,
+  ExpansionTrigger,
+  SnippetSource,
+  SourceChange,
+  SnippetVersion,
+  SnippetAttachment,
+  FeedKind,
+  SnippetFeed,
+  FeedSyncReport,
+  SnippetProvenance,
+  StaleSnippet,
+  ExpiringSnippet,
+} from './snippet';
 
 // Tag types
-export type { Tag, SnippetTag } from './tag';
+export type {
+  Tag,
+  SnippetTag,
+  TagReassignment,
+  TagStats,
+  TagTreeNode,
+  SuggestionReason,
+  TagSuggestion,
+} from './tag';
 
 // Analytics types
 export type {
   AnalyticsRecord,
+  AnalyticsCsvColumn,
+  AnalyticsConsentState,
   SnippetAnalytics,
   MostUsedSnippet,
   RecentActivity,
   GlobalAnalytics,
+  UsageSource,
+  TagUsage,
+  SourceUsage,
+  TrendBucket,
+  TrendPoint,
+  SnippetTrend,
+  UsageTrends,
+  HourUsage,
+  ProductivitySummary,
+  LanguageCount,
+  TagPair,
+  GrowthPoint,
+  LibraryStatistics,
 } from './analytics';
 
+// API token types
+export type { ApiScope, ApiToken, ApiTokenAuditEntry, CreatedApiToken } from './apiToken';
+
+// Clipboard types
+export type { ClipboardBackend, ClipboardSupport } from './clipboard';
+
 // Storage types
-export type { BackupConfig, BackupInfo, DatabaseStats, ExportData, SnippetExport } from './storage';
+export type {
+  ArtifactKind,
+  BackupCompression,
+  BackupConfig,
+  BackupInfo,
+  BackupPreview,
+  BackupSnippetSummary,
+  BackupVerification,
+  ConfirmationChallenge,
+  DatabaseDiagnostics,
+  DatabaseStats,
+  DestructiveAction,
+  DiagnosticsBundleReport,
+  ExportData,
+  ExportFilter,
+  FactoryResetOutcome,
+  FactoryResetReport,
+  GistExportResult,
+  ImportConflict,
+  ImportJob,
+  ImportMode,
+  ImportProgress,
+  ImportReport,
+  ImportSource,
+  ImportSummary,
+  JanitorReport,
+  OptimizeReport,
+  PruneReport,
+  RemovedArtifact,
+  RepairReport,
+  RestoreReport,
+  ScheduledExport,
+  SchemaObject,
+  SearchIndexConsistency,
+  SnippetExport,
+  StartupPhase,
+  StartupReport,
+} from './storage';
+
+// Window types
+export type {
+  FocusBackend,
+  FocusRestoreReport,
+  LeakWarning,
+  PreviousWindow,
+  QuickAddContent,
+  QuickAddSource,
+  ResourceSample,
+  ResourceUsage,
+  WindowDiagnostics,
+} from './window';
@@ -8,6 +8,11 @@ export type StorageType = 'local' | 'git' | 'cloud';
  */
 export type Theme = 'light' | 'dark' | 'system';
 
+/**
+ * Light or dark, after the `system` theme has been resolved
+ */
+export type Appearance = 'light' | 'dark';
+
 /**
  * Conflict resolution strategy for sync operations
  */
@@ -19,6 +24,26 @@ export type ConflictResolutionStrategy = 'last_write_wins' | 'keep_both' | 'ask_
 export interface GlobalShortcuts {
   quick_add: string;
   search_select: string;
+  /** Copies the most recently used snippet without opening a window; empty disables it */
+  quick_paste: string;
+}
+
+/**
+ * Result of checking a shortcut before assigning it (check_shortcut)
+ */
+export interface ShortcutCheck {
+  shortcut: string;
+  valid: boolean;
+  /** Canonical modifiers: CommandOrControl, Super, Control, Alt, Shift */
+  modifiers: string[];
+  key: string | null;
+  /** Platform display string, e.g. "⇧⌘S" or "Ctrl+Shift+S" */
+  display: string | null;
+  /** Free, or already held by Snips itself */
+  available: boolean;
+  registeredBySnips: boolean;
+  /** Why the shortcut is unavailable */
+  conflict: string | null;
 }
 
 /**
@@ -34,6 +59,10 @@ export interface SearchSettings {
   weight_usage_frequency: number;
   /** Weight for recency in search ranking (default: 1.0) */
   weight_recency: number;
+  /** Flat relevance bonus for pinned snippets (default: 20.0) */
+  pinned_bonus: number;
+  /** Weight for usage with the focused application (default: 3.0) */
+  weight_app_context: number;
 }
 
 /**
@@ -42,6 +71,8 @@ export interface SearchSettings {
 export interface PrivacySettings {
   enable_analytics: boolean;
   track_usage: boolean;
+  /** Delete recorded usage when tracking is turned off */
+  purge_on_disable: boolean;
 }
 
 /**
@@ -53,6 +84,135 @@ export interface CloudSyncSettings {
   conflict_resolution: ConflictResolutionStrategy;
 }
 
+/**
+ * GitHub Gist integration settings
+ */
+export interface GistSettings {
+  /** Personal access token with the `gist` scope */
+  access_token?: string | null;
+}
+
+/**
+ * Order of the snippet list; `manual` uses the order set with `reorderSnippets`
+ */
+export type SortPreference = 'name' | 'created' | 'updated' | 'usage' | 'manual';
+
+/**
+ * Paste endpoint encrypted snippet shares are uploaded to
+ */
+export interface ShareSettings {
+  /** Takes the ciphertext as a POST body and answers with the paste URL */
+  endpoint: string;
+}
+
+/**
+ * Local HTTP API server settings (loopback only, token auth)
+ */
+export interface ApiServerSettings {
+  enabled: boolean;
+  port: number;
+}
+
+/**
+ * Whether the local HTTP API server is running
+ */
+export interface ApiServerStatus {
+  running: boolean;
+  port: number | null;
+  /** Why the server could not start (e.g. the port is in use) */
+  error: string | null;
+}
+
+/**
+ * Whether text expansion is running
+ */
+export interface ExpanderStatus {
+  running: boolean;
+  /** Why expansion could not start (e.g. a Wayland session or a missing permission) */
+  error: string | null;
+}
+
+/**
+ * Scheduled refresh of snippets that mirror a remote file
+ */
+export interface SourceRefreshSettings {
+  enabled: boolean;
+  /** Hours between fetches of each snippet's source */
+  interval_hours: number;
+}
+
+/**
+ * Which events show a desktop notification
+ * `enabled` silences every notification at once
+ */
+export interface NotificationSettings {
+  enabled: boolean;
+  /** A scheduled backup finished */
+  backup_completed: boolean;
+  /** A scheduled backup failed */
+  backup_failed: boolean;
+  /** A snippet was copied by a shortcut, with no window to show it */
+  snippet_copied: boolean;
+  /** Global shortcuts could not be bound through the Wayland portal */
+  shortcut_failures: boolean;
+  /** A scheduled refresh changed snippets that mirror a source URL */
+  source_updated: boolean;
+}
+
+/**
+ * What happens to snippets whose expiration date has passed
+ */
+export interface ExpirationSettings {
+  /** Archive expired snippets automatically instead of only flagging them */
+  auto_archive: boolean;
+}
+
+/**
+ * Typing a snippet's trigger in any application replaces it with the snippet
+ */
+export interface ExpansionSettings {
+  /** Watch typing for triggers; off by default */
+  enabled: boolean;
+  /**
+   * Applications expansions are never pasted into, by name or bundle identifier
+   * (case-insensitive); the snippet is only copied there
+   */
+  paste_blocklist: string[];
+}
+
+/**
+ * What Quick Add opens with when no text is selected
+ */
+export type QuickAddFallback = 'error' | 'empty_form' | 'clipboard';
+
+/**
+ * Monitor the search and Quick Add overlays are centered on
+ */
+export type OverlayMonitor = 'primary' | 'cursor' | 'active_window';
+
+/**
+ * Where the search overlay opens
+ */
+export type SearchPlacement = 'center' | 'near_cursor';
+
+/**
+ * When the search and Quick Add overlays hide themselves
+ */
+export interface QuickWindowPreferences {
+  /** Hide when another window takes focus */
+  hide_on_blur: boolean;
+  /** Hide when Escape is pressed */
+  hide_on_escape: boolean;
+}
+
+/**
+ * Clipboard behavior that only applies on Linux
+ */
+export interface LinuxClipboardSettings {
+  /** Also put copied snippets in the PRIMARY selection, for middle-click paste */
+  copy_to_primary: boolean;
+}
+
 /**
  * Application settings
  */
@@ -63,6 +223,42 @@ export interface AppSettings {
   search_settings: SearchSettings;
   privacy_settings: PrivacySettings;
   cloud_sync_settings?: CloudSyncSettings;
+  gist_settings?: GistSettings;
+  sort_preference?: SortPreference;
+  api_server?: ApiServerSettings;
+  source_refresh_settings?: SourceRefreshSettings;
+  expiration_settings?: ExpirationSettings;
+  quick_add_fallback?: QuickAddFallback;
+  overlay_monitor?: OverlayMonitor;
+  search_placement?: SearchPlacement;
+  quick_window_preferences?: QuickWindowPreferences;
+  linux_clipboard?: LinuxClipboardSettings;
+  /** Name of the custom theme laid over the light or dark palette */
+  custom_theme?: string | null;
+  share_settings?: ShareSettings;
+  notification_settings?: NotificationSettings;
+  expansion_settings?: ExpansionSettings;
+}
+
+/**
+ * User-defined palette stored in the config dir's `themes` folder
+ */
+export interface CustomTheme {
+  name: string;
+  /** Whether the palette is dark, so dark-only styles and window chrome follow it */
+  dark: boolean;
+  /** CSS variable values without the leading `--`, e.g. `primary: '193 43% 67%'` */
+  variables: Record<string, string>;
+}
+
+/**
+ * Theme setting with the appearance it resolves to, sent as `appearance-updated`
+ */
+export interface ResolvedTheme {
+  theme: Theme;
+  appearance: Appearance;
+  /** Built-in palette for the appearance */
+  palette: CustomTheme;
 }
 
 /**
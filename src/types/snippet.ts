@@ -1,3 +1,9 @@
+/**
+ * How a snippet's content is turned into copied text:
+ * `static` copies it as-is, `dynamic` runs it as a shell command and copies the output
+ */
+export type SnippetKind = 'static' | 'dynamic';
+
 /**
  * Represents a text snippet stored in the database
  */
@@ -9,6 +15,15 @@ export interface Snippet {
   created_at: number;
   updated_at: number;
   tags?: string[];
+  /** Pinned snippets rank higher and show in the search overlay's empty state */
+  pinned: boolean;
+  kind: SnippetKind;
+  /** Language detected from the name and content (e.g. `rust`, `shellscript`); null for plain text */
+  language?: string | null;
+  /** Archived snippets are left out of search and the snippet list */
+  archived?: boolean;
+  /** Unix timestamp after which the snippet is flagged as expired */
+  expires_at?: number | null;
 }
 
 /**
@@ -19,8 +34,24 @@ export interface CreateSnippetInput {
   content: string;
   description?: string | null;
   tags?: string[];
+  kind?: SnippetKind;
 }
 
+/**
+ * How `create_snippet_with_resolution` treats a name that is already taken
+ */
+export type NameConflictMode = 'ask' | 'rename' | 'append';
+
+/**
+ * Result of `create_snippet_with_resolution`
+ */
+export type CreateSnippetOutcome =
+  | { status: 'created'; snippet: Snippet }
+  | { status: 'renamed'; snippet: Snippet }
+  | { status: 'appended'; snippet: Snippet }
+  /** Nothing was written; `suggested_name` is a free name to create it under */
+  | { status: 'name_taken'; existing: Snippet; suggested_name: string };
+
 /**
  * Input data for updating an existing snippet
  */
@@ -29,8 +60,23 @@ export interface UpdateSnippetInput {
   content: string;
   description?: string | null;
   tags?: string[];
+  kind?: SnippetKind;
 }
 
+/**
+ * A dynamic snippet's command, shown for confirmation before it runs
+ */
+export interface DynamicCommand {
+  snippet_id: number;
+  name: string;
+  command: string;
+}
+
+/**
+ * Sandbox dynamic snippet commands run in
+ */
+export type ExecutionEnvironment = 'native' | 'flatpak' | 'snap' | 'mac_sandbox';
+
 /**
  * Search result with relevance scoring and usage statistics
  */
@@ -38,4 +84,295 @@ export interface SearchResult extends Snippet {
   usage_count: number;
   last_used: number | null;
   relevance_score: number;
+  /** Excerpt of the content around the matched terms, or its beginning */
+  preview: string;
+  /** Whether the snippet's expires_at has passed */
+  expired?: boolean;
+}
+
+/**
+ * One page of search results
+ */
+export interface SearchPage {
+  results: SearchResult[];
+  /** Matches across all pages */
+  total_matches: number;
+  /** Position of the first result among all matches */
+  offset: number;
+  /** Pass back as `cursor` to get the next page; null on the last page */
+  next_cursor: string | null;
+}
+
+/**
+ * Rendering mode for snippet previews
+ */
+export type PreviewFormat = 'auto' | 'markdown' | 'code' | 'plain';
+
+/**
+ * Sanitized HTML preview rendered by the backend
+ */
+export interface RenderedPreview {
+  format: Exclude<PreviewFormat, 'auto'>;
+  language: string | null;
+  html: string;
+}
+
+/**
+ * One query in a batch_search call
+ */
+export type BatchSearchRequest =
+  | { kind: 'search'; query: string; limit?: number | null }
+  | { kind: 'suggestions'; limit?: number | null }
+  | { kind: 'recent'; limit?: number | null }
+  | { kind: 'frequent'; limit?: number | null }
+  | { kind: 'tag_counts' };
+
+/**
+ * Snippet count for a tag
+ */
+export interface TagCount {
+  name: string;
+  color: string;
+  count: number;
+}
+
+/**
+ * Result of one query in a batch_search call, in request order
+ */
+export type BatchSearchResponse =
+  | { kind: 'search'; results: SearchResult[] }
+  | { kind: 'suggestions'; results: SearchResult[] }
+  | { kind: 'recent'; results: SearchResult[] }
+  | { kind: 'frequent'; results: SearchResult[] }
+  | { kind: 'tag_counts'; tags: TagCount[] }
+  | { kind: 'error'; message: string };
+
+/**
+ * One weighted part of a relevance score
+ */
+export interface ScoreComponent {
+  /** Score before weighting */
+  score: number;
+  weight: number;
+  /** score * weight, the amount added to the relevance score */
+  weighted: number;
+}
+
+/**
+ * The parts a relevance score is the sum of
+ */
+export interface ScoreBreakdown {
+  text: ScoreComponent;
+  usage: ScoreComponent;
+  recency: ScoreComponent;
+  app_context: ScoreComponent;
+  /** 0 for unpinned snippets */
+  pinned_bonus: number;
+  relevance_score: number;
+}
+
+/**
+ * How a snippet's rank for a query came about
+ */
+export interface RankingExplanation {
+  snippet_id: number;
+  /** Position among all matches, starting at 1 */
+  position: number;
+  total_matches: number;
+  /** FTS5 bm25() rank; 0 for filter-only and typo-tolerant matches */
+  fts_rank: number;
+  usage_count: number;
+  last_used: number | null;
+  app_usage_count: number;
+  pinned: boolean;
+  scores: ScoreBreakdown;
+}
+
+/**
+ * Approximate LLM token count for one snippet
+ */
+export interface SnippetTokenEstimate {
+  snippet_id: number;
+  tokens: number;
+  /** Dynamic snippets are counted by their command text, not their output */
+  dynamic: boolean;
+}
+
+/**
+ * Approximate LLM token count (cl100k_base-like) for snippets copied together
+ */
+export interface ComposedTokenEstimate {
+  total_tokens: number;
+  total_characters: number;
+  snippets: SnippetTokenEstimate[];
+}
+
+/**
+ * Value type of a declared snippet parameter, which decides the form control
+ */
+export type SnippetParameterType =
+  | { type: 'text' }
+  | { type: 'number'; min?: number; max?: number }
+  | { type: 'date' }
+  | { type: 'choice'; choices: string[] }
+  | { type: 'boolean' };
+
+/**
+ * A parameter a snippet declares for its `{{name}}` placeholders
+ */
+export type SnippetParameter = SnippetParameterType & {
+  name: string;
+  /** Form label; falls back to the name */
+  label?: string;
+  /** Used when the form leaves the value empty */
+  default?: string;
+  /** Defaults to true */
+  required?: boolean;
+};
+
+/**
+ * Required parameters of a copied snippet that were left unfilled
+ */
+export interface UnfilledParameters {
+  snippet_id: number;
+  snippet_name: string;
+  /** Parameter names, in declaration order */
+  parameters: string[];
+}
+
+/**
+ * The remote file a snippet mirrors
+ */
+export interface SnippetSource {
+  url: string;
+  /** When the source was last fetched; null until the first refresh */
+  checked_at: number | null;
+}
+
+/**
+ * A snippet whose content changed when its source was fetched
+ * Also the payload of the `snippet-source-updated` event
+ */
+export interface SourceChange {
+  snippet_id: number;
+  snippet_name: string;
+  source_url: string;
+  lines_added: number;
+  lines_removed: number;
+  refreshed_at: number;
+}
+
+/**
+ * An earlier content of a snippet
+ */
+export interface SnippetVersion {
+  id: number;
+  snippet_id: number;
+  content: string;
+  /** Why the content was replaced, e.g. `source_refresh` */
+  reason: string;
+  created_at: number;
+}
+
+/**
+ * Where a team feed is published
+ */
+export type FeedKind = 'json' | 'git';
+
+/**
+ * A subscribed team snippet feed
+ */
+export interface SnippetFeed {
+  id: number;
+  name: string;
+  url: string;
+  kind: FeedKind;
+  interval_hours: number;
+  /** When the feed last synced successfully; null until the first sync */
+  synced_at: number | null;
+  /** Why the last sync failed, cleared by the next successful one */
+  last_error: string | null;
+  created_at: number;
+  snippet_count: number;
+}
+
+/**
+ * What syncing a team feed changed
+ */
+export interface FeedSyncReport {
+  feed_id: number;
+  added: number;
+  updated: number;
+  removed: number;
+  /** Feed snippets left out because a local snippet already has the name */
+  skipped: string[];
+}
+
+/**
+ * Where a snippet came from
+ * A snippet with a `feed_id` is read-only; saving an edit returns a fork
+ */
+export interface SnippetProvenance {
+  feed_id: number | null;
+  feed_name: string | null;
+  /** The snippet's name in the feed */
+  feed_key: string | null;
+  /** The feed snippet a local fork was copied from */
+  forked_from: number | null;
+}
+
+/**
+ * A snippet nobody has used for a while, a candidate for archiving
+ */
+export interface StaleSnippet {
+  snippet_id: number;
+  snippet_name: string;
+  created_at: number;
+  usage_count: number;
+  /** null if the snippet was never used */
+  last_used: number | null;
+}
+
+/**
+ * A snippet that has expired or is about to, for a reminder notification
+ */
+export interface ExpiringSnippet {
+  snippet_id: number;
+  snippet_name: string;
+  expires_at: number;
+  /** Whether expires_at has already passed */
+  expired: boolean;
+}
+
+/**
+ * An image or other binary data attached to a snippet, without the data
+ * A snippet with an image attachment copies the image instead of its text
+ */
+export interface SnippetAttachment {
+  id: number;
+  snippet_id: number;
+  mime_type: string;
+  byte_size: number;
+  /** File the data is read from; null when it is stored in the database */
+  file_path: string | null;
+  created_at: number;
+}
+
+/**
+ * A snippet assigned to a numbered slot, pasted with Cmd/Ctrl+Shift+slot
+ */
+export interface SlotAssignment {
+  /** 1 through 9 */
+  slot: number;
+  snippet_id: number;
+  snippet_name: string;
+}
+
+/**
+ * A snippet's text expansion trigger, such as ";sig"
+ */
+export interface ExpansionTrigger {
+  snippet_id: number;
+  snippet_name: string;
+  trigger: string;
 }
@@ -1,3 +1,5 @@
+import type { SnippetKind, SnippetParameter } from './snippet';
+
 /**
  * Database statistics
  */
@@ -7,6 +9,90 @@ export interface DatabaseStats {
   total_analytics_records: number;
   database_size_bytes: number;
   last_backup: number | null;
+  /** Sizes before and after the last optimization, if it ever ran */
+  last_optimization: OptimizeReport | null;
+}
+
+/**
+ * Outcome of compacting (VACUUM) and re-analyzing the database
+ */
+export interface OptimizeReport {
+  optimized_at: number;
+  size_before_bytes: number;
+  size_after_bytes: number;
+  reclaimed_bytes: number;
+  duration_ms: number;
+}
+
+/**
+ * A table, index or trigger defined by the schema migrations
+ */
+export interface SchemaObject {
+  kind: 'table' | 'index' | 'trigger';
+  name: string;
+}
+
+/**
+ * What `repair_database` changed
+ */
+export interface RepairReport {
+  /** Migrations that had not been applied yet */
+  applied_migrations: number[];
+  /** Missing objects that were recreated, tables first */
+  recreated: SchemaObject[];
+  /** Rows in the rebuilt search index */
+  search_index_rows: number;
+}
+
+/**
+ * How the full-text search index compares with the snippets table
+ */
+export interface SearchIndexConsistency {
+  snippet_rows: number;
+  index_rows: number;
+  /** Snippets the index has no row for; searches never find them */
+  missing_rows: number;
+  /** Index rows whose snippet no longer exists */
+  orphaned_rows: number;
+  /** Index rows whose name or content differs from the snippet's */
+  stale_rows: number;
+  consistent: boolean;
+}
+
+/**
+ * Effective SQLite settings, connection pool state and schema health
+ */
+export interface DatabaseDiagnostics {
+  journal_mode: string;
+  synchronous: 'off' | 'normal' | 'full' | 'extra' | 'unknown';
+  busy_timeout_ms: number;
+  foreign_keys: boolean;
+  wal_autocheckpoint: number;
+  page_size: number;
+  page_count: number;
+  /** Unused pages that VACUUM would reclaim */
+  freelist_count: number;
+  pool_size: number;
+  pool_idle: number;
+  pool_max_connections: number;
+  /** Last migration applied to the database */
+  schema_version: number;
+  /** Last migration this build knows about */
+  latest_schema_version: number;
+  /** Schema objects the database lacks; `repair_database` recreates them */
+  missing_objects: SchemaObject[];
+}
+
+/**
+ * Outcome of exporting a diagnostics bundle for a bug report
+ */
+export interface DiagnosticsBundleReport {
+  path: string;
+  /** Files in the zip, starting with `manifest.json` */
+  files: string[];
+  /** Sections that could not be collected, with the reason */
+  errors: string[];
+  size_bytes: number;
 }
 
 /**
@@ -16,6 +102,48 @@ export interface BackupInfo {
   path: string;
   created_at: number;
   size_bytes: number;
+  /** Whether the backup file is gzip-compressed */
+  compressed: boolean;
+}
+
+/**
+ * Summary of a snippet stored in a backup
+ */
+export interface BackupSnippetSummary {
+  /** Snippet ID inside the backup (used to select snippets for restore) */
+  id: number;
+  name: string;
+  description: string | null;
+  tags: string[];
+  updated_at: number;
+  /** Whether a snippet with the same name exists in the current database */
+  exists_in_current: boolean;
+}
+
+/**
+ * Contents of a backup, shown before it is restored
+ */
+export interface BackupPreview {
+  path: string;
+  snippet_count: number;
+  tag_count: number;
+  snippets: BackupSnippetSummary[];
+}
+
+/**
+ * Which snippets an export includes; every condition given must match
+ */
+export interface ExportFilter {
+  /** Snippets with any of these tags; `k8s` also matches `k8s/prod` */
+  tags?: string[];
+  /** Snippets synced from the team feed with this name */
+  collection?: string;
+  /** Snippets matching a search, with the same syntax as the search bar */
+  query?: string;
+  /** Unix timestamp; snippets last updated at or after it */
+  updated_since?: number;
+  /** Unix timestamp; snippets last updated at or before it */
+  updated_until?: number;
 }
 
 /**
@@ -37,6 +165,12 @@ export interface SnippetExport {
   tags: string[];
   created_at: number;
   updated_at: number;
+  /** Missing in exports older than format 1.1.0 */
+  kind?: SnippetKind;
+  aliases?: string[];
+  parameters?: SnippetParameter[];
+  archived?: boolean;
+  expires_at?: number | null;
 }
 
 /**
@@ -46,4 +180,239 @@ export interface BackupConfig {
   enabled: boolean;
   interval_hours: number;
   max_backups: number;
+  /** Maximum age of a backup in days before it is pruned (0 = unlimited) */
+  max_age_days: number;
+  /** Compression applied to new backups */
+  compression: BackupCompression;
+  /** Optimize the database after each automatic backup */
+  optimize_after_backup: boolean;
+  /** Delete tags no snippet carries after each automatic backup */
+  cleanup_orphan_tags: boolean;
+  /** Plaintext export written on each automatic backup; none when null */
+  scheduled_export?: ScheduledExport | null;
+}
+
+/**
+ * Compression applied to backup files
+ */
+export type BackupCompression = 'none' | 'gzip';
+
+/**
+ * A plaintext mirror of the library written alongside scheduled backups
+ * `json` writes `snips.json`, `markdown` one file per snippet
+ */
+export interface ScheduledExport {
+  /** Absolute path, e.g. a synced folder or a git checkout */
+  directory: string;
+  format: 'json' | 'markdown';
+}
+
+/**
+ * Result of verifying a backup before restore
+ */
+export interface BackupVerification {
+  path: string;
+  compressed: boolean;
+  /** SHA-256 of the backup file as stored on disk */
+  checksum: string;
+  /** Whether the stored checksum matches (null if the backup has no checksum file) */
+  checksum_matches: boolean | null;
+  integrity_ok: boolean;
+  /** Output of PRAGMA integrity_check, or the reason the backup could not be opened */
+  integrity_message: string;
+  valid: boolean;
+}
+
+/**
+ * Result of applying the backup retention policy
+ */
+export interface PruneReport {
+  deleted_count: number;
+  reclaimed_bytes: number;
+  remaining_count: number;
+}
+
+/**
+ * Kind of temporary file removed by the startup janitor
+ */
+export type ArtifactKind =
+  | 'pre_restore_backup'
+  | 'write_test'
+  | 'partial_export'
+  | 'verification_scratch'
+  | 'stale_lock';
+
+/**
+ * Temporary file removed by the startup janitor
+ */
+export interface RemovedArtifact {
+  path: string;
+  kind: ArtifactKind;
+  size_bytes: number;
+}
+
+/**
+ * Summary of the startup janitor run
+ */
+export interface JanitorReport {
+  removed: RemovedArtifact[];
+  /** Stale artifacts that could not be deleted, with the reason */
+  failed: string[];
+  reclaimed_bytes: number;
+}
+
+/**
+ * How long one startup phase took
+ */
+export interface StartupPhase {
+  name: string;
+  /** Milliseconds from launch until the phase started */
+  started_ms: number;
+  duration_ms: number;
+  /** Whether the phase waited until after the first window loaded */
+  deferred: boolean;
+}
+
+/**
+ * Integrity report and phase timings collected when the app started
+ */
+export interface StartupReport {
+  generated_at: number;
+  janitor: JanitorReport;
+  phases: StartupPhase[];
+  /** Milliseconds from launch until setup finished */
+  setup_ms: number;
+  /** Milliseconds from launch until the deferred phases started, null until then */
+  deferred_at_ms: number | null;
+}
+
+/**
+ * Snippet manager a library can be imported from
+ */
+export type ImportSource = 'alfred' | 'raycast';
+
+/**
+ * Outcome of importing a library from another snippet manager
+ */
+export interface ImportSummary {
+  source: ImportSource;
+  imported: number;
+  /** Names skipped because a snippet with that name already exists */
+  skipped: string[];
 }
+
+/**
+ * Progress of an import that was interrupted before finishing
+ */
+export interface ImportJob {
+  id: number;
+  file_path: string;
+  file_hash: string;
+  format: string;
+  total_items: number;
+  /** Items handled so far; a resumed import continues from here */
+  processed_items: number;
+  imported_items: number;
+  created_at: number;
+  updated_at: number;
+}
+
+/**
+ * What restoring a backup changes, by snippet name
+ * The backup replaces the whole database, so `removed` snippets are lost
+ */
+export interface RestoreReport {
+  /** Whether this only shows what the restore would do */
+  dry_run: boolean;
+  added: string[];
+  removed: string[];
+  /** Snippets in both whose content or description differ */
+  changed: string[];
+  unchanged: number;
+  /** A backup newer than `latest_schema_version` cannot be restored */
+  backup_schema_version: number;
+  latest_schema_version: number;
+}
+
+/**
+ * Payload of the `import-progress` event emitted while an import runs
+ */
+export interface ImportProgress {
+  job_id: number;
+  /** Items handled so far, including any from an earlier run of the job */
+  processed: number;
+  total: number;
+}
+
+/**
+ * How an import treats a snippet whose name is already taken
+ */
+export type ImportMode = 'skip' | 'overwrite' | 'rename' | 'merge_newer';
+
+/**
+ * An imported snippet whose name was already taken
+ */
+export interface ImportConflict {
+  name: string;
+  resolution: 'skipped' | 'overwritten' | 'renamed';
+  /** The free name the snippet was imported under when renamed */
+  renamed_to: string | null;
+}
+
+/**
+ * What an import wrote, by snippet name
+ */
+export interface ImportReport {
+  /** New snippets, including renamed ones under their new name */
+  created: string[];
+  updated: string[];
+  /** Snippets left out for an empty name or content, or because of a conflict */
+  skipped: string[];
+  conflicted: ImportConflict[];
+  /** Whether this only shows what the import would do */
+  dry_run: boolean;
+}
+
+/**
+ * A gist created from a snippet
+ */
+export interface GistExportResult {
+  gist_id: string;
+  url: string;
+}
+
+/**
+ * Destructive action a confirmation token is bound to
+ */
+export type DestructiveAction =
+  | { action: 'delete_snippet'; id: number }
+  | { action: 'clear_analytics' }
+  | { action: 'restore_database' }
+  | { action: 'factory_reset'; delete_backups: boolean };
+
+/**
+ * Single-use token that must be sent back to go ahead with a destructive action
+ */
+export interface ConfirmationChallenge {
+  token: string;
+  action: DestructiveAction;
+  description: string;
+  expires_at: number;
+}
+
+/**
+ * Files and directories deleted by a factory reset
+ */
+export interface FactoryResetReport {
+  removed: string[];
+  backups_removed: boolean;
+  /** Keychain accounts whose credentials were deleted, or had none */
+  credentials_removed: string[];
+}
+
+/**
+ * Result of a factory reset call: a challenge to confirm, or the completed reset
+ */
+export type FactoryResetOutcome =
+  | { status: 'confirmation_required'; challenge: ConfirmationChallenge }
+  | { status: 'reset'; report: FactoryResetReport };
@@ -7,6 +7,24 @@ export interface Tag {
   color: string;
 }
 
+/**
+ * A level of the tag hierarchy; `/` in a tag name nests a level
+ */
+export interface TagTreeNode {
+  /** Last path segment, e.g. `async` */
+  name: string;
+  /** Full path, e.g. `lang/rust/async` */
+  path: string;
+  /** Color of the tag at this path; null when no tag has exactly this path */
+  color: string | null;
+  /** Snippets carrying exactly this tag */
+  snippet_count: number;
+  /** Distinct snippets carrying this tag or any tag below it */
+  total_count: number;
+  /** Nested levels, sorted by name */
+  children: TagTreeNode[];
+}
+
 /**
  * Association between a snippet and a tag
  */
@@ -14,3 +32,44 @@ export interface SnippetTag {
   snippet_id: number;
   tag_id: number;
 }
+
+/**
+ * How much a tag is used
+ */
+export interface TagStats {
+  id: number;
+  name: string;
+  color: string;
+  /** Snippets carrying the tag; 0 for an orphan */
+  snippet_count: number;
+  /** Most recent use of any snippet carrying the tag */
+  last_used: number | null;
+}
+
+/**
+ * Outcome of merging a tag into another or deleting it
+ */
+export interface TagReassignment {
+  /** Name of the tag that no longer exists */
+  removed: string;
+  /** Tag that took over its snippets, if any */
+  reassigned_to: string | null;
+  /** Snippets that carried the removed tag */
+  snippets_affected: number;
+}
+
+/**
+ * Why a tag was suggested
+ */
+export type SuggestionReason = 'language' | 'keyword' | 'similar_snippets';
+
+/**
+ * A tag suggested for a snippet's content
+ */
+export interface TagSuggestion {
+  tag: string;
+  score: number;
+  reasons: SuggestionReason[];
+  /** Whether the tag already exists; new tags are created on save */
+  existing: boolean;
+}
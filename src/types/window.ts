@@ -0,0 +1,80 @@
+/**
+ * Mechanism used to return focus to the previously active window
+ */
+export type FocusBackend = 'hyprland' | 'sway' | 'x11' | 'mac_os' | 'unsupported';
+
+/**
+ * Window that was active before the search overlay was shown
+ */
+export interface PreviousWindow {
+  backend: FocusBackend;
+  handle: string;
+  title: string | null;
+  pid: number | null;
+}
+
+/**
+ * Outcome of the last attempt to re-activate the previous window
+ */
+export interface FocusRestoreReport {
+  backend: FocusBackend;
+  window: PreviousWindow | null;
+  success: boolean;
+  error: string | null;
+  attempted_at: number;
+}
+
+/**
+ * Where the text Quick Add opens with came from
+ */
+export type QuickAddSource = 'selection' | 'clipboard' | 'empty';
+
+/**
+ * Payload of the `selected-text-captured` event sent to the Quick Add window
+ */
+export interface QuickAddContent {
+  text: string;
+  source: QuickAddSource;
+}
+
+/**
+ * Window state snapshot for troubleshooting focus problems
+ */
+export interface WindowDiagnostics {
+  search_window_visible: boolean;
+  search_window_focused: boolean;
+  focus_backend: FocusBackend;
+  last_focus_restore: FocusRestoreReport | null;
+}
+
+/**
+ * One measurement of the app's own resource usage; null where the
+ * platform cannot report a value
+ */
+export interface ResourceSample {
+  taken_at: number;
+  rss_bytes: number | null;
+  open_fds: number | null;
+  threads: number | null;
+  tokio_tasks: number | null;
+  windows: number;
+}
+
+/**
+ * A resource that kept growing across the sampled history
+ */
+export interface LeakWarning {
+  resource: 'rss_bytes' | 'open_fds' | 'threads' | 'tokio_tasks' | 'windows';
+  first_value: number;
+  last_value: number;
+  over_secs: number;
+}
+
+/**
+ * Current resource usage with recent history and suspected leaks
+ */
+export interface ResourceUsage {
+  current: ResourceSample;
+  samples: ResourceSample[];
+  warnings: LeakWarning[];
+}